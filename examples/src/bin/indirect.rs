@@ -247,6 +247,9 @@ fn main() {
         compare_mask: None,
         write_mask: None,
         reference: None,
+        depth_bias: None,
+        depth_bounds: None,
+        blend_constants: None,
     };
     let mut framebuffers =
         window_size_dependent_setup(&images, render_pass.clone(), &mut dynamic_state);
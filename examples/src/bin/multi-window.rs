@@ -223,6 +223,9 @@ fn main() {
         compare_mask: None,
         write_mask: None,
         reference: None,
+        depth_bias: None,
+        depth_bounds: None,
+        blend_constants: None,
     };
 
     window_surfaces.insert(
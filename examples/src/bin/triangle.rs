@@ -334,6 +334,9 @@ fn main() {
         compare_mask: None,
         write_mask: None,
         reference: None,
+        depth_bias: None,
+        depth_bounds: None,
+        blend_constants: None,
     };
 
     // The render pass we created above only describes the layout of our framebuffers. Before we
@@ -105,6 +105,7 @@ pub const ERROR_VALIDATION_FAILED_EXT: u32 = -1000011001i32 as u32;
 pub const ERROR_INVALID_SHADER_NV: u32 = -1000012000i32 as u32;
 pub const ERROR_OUT_OF_POOL_MEMORY_KHR: u32 = -1000069000i32 as u32;
 pub const ERROR_FULL_SCREEN_EXCLUSIVE_MODE_LOST_EXT: u32 = -1000255000i32 as u32;
+pub const ERROR_INVALID_EXTERNAL_HANDLE_KHR: u32 = -1000072003i32 as u32;
 
 pub type StructureType = u32;
 pub const STRUCTURE_TYPE_APPLICATION_INFO: u32 = 0;
@@ -195,7 +196,9 @@ pub const STRUCTURE_TYPE_IMAGE_MEMORY_REQUIREMENTS_INFO_2_KHR: u32 = 1000146001;
 pub const STRUCTURE_TYPE_IMAGE_SPARSE_MEMORY_REQUIREMENTS_INFO_2_KHR: u32 = 1000146002;
 pub const STRUCTURE_TYPE_MEMORY_REQUIREMENTS_2_KHR: u32 = 1000146003;
 pub const STRUCTURE_TYPE_SPARSE_IMAGE_MEMORY_REQUIREMENTS_2_KHR: u32 = 1000146004;
+pub const STRUCTURE_TYPE_IMAGE_FORMAT_LIST_CREATE_INFO_KHR: u32 = 1000147000;
 pub const STRUCTURE_TYPE_SURFACE_FULL_SCREEN_EXCLUSIVE_INFO_EXT: u32 = 1000255000;
+pub const STRUCTURE_TYPE_SURFACE_FULL_SCREEN_EXCLUSIVE_WIN32_INFO_EXT: u32 = 1000255001;
 pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_BUFFER_ADDRESS_FEATURES_EXT: u32 = 1000244000;
 pub const STRUCTURE_TYPE_BUFFER_DEVICE_ADDRESS_INFO: u32 = 1000244001;
 pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_VARIABLE_POINTERS_FEATURES: u32 = 1000120000;
@@ -203,6 +206,55 @@ pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_SHADER_ATOMIC_INT64_FEATURES: u32 = 100
 pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_8BIT_STORAGE_FEATURES: u32 = 1000177000;
 pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_16BIT_STORAGE_FEATURES: u32 = 1000083000;
 pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_SHADER_FLOAT16_INT8_FEATURES: u32 = 1000082000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_PROPERTIES_EXT: u32 = 1000190000;
+pub const STRUCTURE_TYPE_PIPELINE_VERTEX_INPUT_DIVISOR_STATE_CREATE_INFO_EXT: u32 = 1000190001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_FEATURES_EXT: u32 = 1000190002;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_CONSERVATIVE_RASTERIZATION_PROPERTIES_EXT: u32 = 1000101000;
+pub const STRUCTURE_TYPE_PIPELINE_RASTERIZATION_CONSERVATIVE_STATE_CREATE_INFO_EXT: u32 = 1000101001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_LINE_RASTERIZATION_FEATURES_EXT: u32 = 1000259000;
+pub const STRUCTURE_TYPE_PIPELINE_RASTERIZATION_LINE_STATE_CREATE_INFO_EXT: u32 = 1000259001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_LINE_RASTERIZATION_PROPERTIES_EXT: u32 = 1000259002;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_PIPELINE_EXECUTABLE_PROPERTIES_FEATURES_KHR: u32 = 1000269000;
+pub const STRUCTURE_TYPE_PIPELINE_INFO_KHR: u32 = 1000269001;
+pub const STRUCTURE_TYPE_PIPELINE_EXECUTABLE_PROPERTIES_KHR: u32 = 1000269002;
+pub const STRUCTURE_TYPE_PIPELINE_EXECUTABLE_INFO_KHR: u32 = 1000269003;
+pub const STRUCTURE_TYPE_PIPELINE_EXECUTABLE_STATISTIC_KHR: u32 = 1000269004;
+pub const STRUCTURE_TYPE_PIPELINE_EXECUTABLE_INTERNAL_REPRESENTATION_KHR: u32 = 1000269005;
+pub const STRUCTURE_TYPE_RAY_TRACING_PIPELINE_CREATE_INFO_NV: u32 = 1000165000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_RAY_TRACING_PROPERTIES_NV: u32 = 1000165009;
+pub const STRUCTURE_TYPE_RAY_TRACING_SHADER_GROUP_CREATE_INFO_NV: u32 = 1000165011;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_MESH_SHADER_FEATURES_EXT: u32 = 1000328000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_MESH_SHADER_PROPERTIES_EXT: u32 = 1000328001;
+pub const STRUCTURE_TYPE_MEMORY_BARRIER_2_KHR: u32 = 1000314000;
+pub const STRUCTURE_TYPE_BUFFER_MEMORY_BARRIER_2_KHR: u32 = 1000314001;
+pub const STRUCTURE_TYPE_IMAGE_MEMORY_BARRIER_2_KHR: u32 = 1000314002;
+pub const STRUCTURE_TYPE_DEPENDENCY_INFO_KHR: u32 = 1000314003;
+pub const STRUCTURE_TYPE_SUBMIT_INFO_2_KHR: u32 = 1000314004;
+pub const STRUCTURE_TYPE_SEMAPHORE_SUBMIT_INFO_KHR: u32 = 1000314005;
+pub const STRUCTURE_TYPE_COMMAND_BUFFER_SUBMIT_INFO_KHR: u32 = 1000314006;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_SYNCHRONIZATION_2_FEATURES_KHR: u32 = 1000314007;
+pub const STRUCTURE_TYPE_EXTERNAL_MEMORY_BUFFER_CREATE_INFO_KHR: u32 = 1000072000;
+pub const STRUCTURE_TYPE_EXTERNAL_MEMORY_IMAGE_CREATE_INFO_KHR: u32 = 1000072001;
+pub const STRUCTURE_TYPE_EXPORT_MEMORY_ALLOCATE_INFO_KHR: u32 = 1000072002;
+pub const STRUCTURE_TYPE_IMPORT_MEMORY_WIN32_HANDLE_INFO_KHR: u32 = 1000073000;
+pub const STRUCTURE_TYPE_EXPORT_MEMORY_WIN32_HANDLE_INFO_KHR: u32 = 1000073001;
+pub const STRUCTURE_TYPE_MEMORY_WIN32_HANDLE_PROPERTIES_KHR: u32 = 1000073002;
+pub const STRUCTURE_TYPE_MEMORY_GET_WIN32_HANDLE_INFO_KHR: u32 = 1000073003;
+pub const STRUCTURE_TYPE_IMPORT_MEMORY_FD_INFO_KHR: u32 = 1000074000;
+pub const STRUCTURE_TYPE_EXPORT_SEMAPHORE_CREATE_INFO_KHR: u32 = 1000077000;
+pub const STRUCTURE_TYPE_IMPORT_SEMAPHORE_WIN32_HANDLE_INFO_KHR: u32 = 1000078000;
+pub const STRUCTURE_TYPE_EXPORT_SEMAPHORE_WIN32_HANDLE_INFO_KHR: u32 = 1000078001;
+pub const STRUCTURE_TYPE_SEMAPHORE_GET_WIN32_HANDLE_INFO_KHR: u32 = 1000078003;
+pub const STRUCTURE_TYPE_IMPORT_SEMAPHORE_FD_INFO_KHR: u32 = 1000079000;
+pub const STRUCTURE_TYPE_SEMAPHORE_GET_FD_INFO_KHR: u32 = 1000079001;
+pub const STRUCTURE_TYPE_EXPORT_FENCE_CREATE_INFO_KHR: u32 = 1000113000;
+pub const STRUCTURE_TYPE_IMPORT_FENCE_WIN32_HANDLE_INFO_KHR: u32 = 1000114000;
+pub const STRUCTURE_TYPE_EXPORT_FENCE_WIN32_HANDLE_INFO_KHR: u32 = 1000114001;
+pub const STRUCTURE_TYPE_FENCE_GET_WIN32_HANDLE_INFO_KHR: u32 = 1000114002;
+pub const STRUCTURE_TYPE_IMPORT_FENCE_FD_INFO_KHR: u32 = 1000115000;
+pub const STRUCTURE_TYPE_FENCE_GET_FD_INFO_KHR: u32 = 1000115001;
+pub const STRUCTURE_TYPE_MEMORY_FD_PROPERTIES_KHR: u32 = 1000074001;
+pub const STRUCTURE_TYPE_MEMORY_GET_FD_INFO_KHR: u32 = 1000074002;
 
 pub type SystemAllocationScope = u32;
 pub const SYSTEM_ALLOCATION_SCOPE_COMMAND: u32 = 0;
@@ -437,6 +489,8 @@ pub const IMAGE_LAYOUT_TRANSFER_SRC_OPTIMAL: u32 = 6;
 pub const IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL: u32 = 7;
 pub const IMAGE_LAYOUT_PREINITIALIZED: u32 = 8;
 pub const IMAGE_LAYOUT_PRESENT_SRC_KHR: u32 = 1000001002;
+pub const IMAGE_LAYOUT_DEPTH_READ_ONLY_STENCIL_ATTACHMENT_OPTIMAL_KHR: u32 = 1000117000;
+pub const IMAGE_LAYOUT_DEPTH_ATTACHMENT_STENCIL_READ_ONLY_OPTIMAL_KHR: u32 = 1000117001;
 
 pub type ImageViewType = u32;
 pub const IMAGE_VIEW_TYPE_1D: u32 = 0;
@@ -478,6 +532,30 @@ pub const POLYGON_MODE_FILL: u32 = 0;
 pub const POLYGON_MODE_LINE: u32 = 1;
 pub const POLYGON_MODE_POINT: u32 = 2;
 
+pub type ConservativeRasterizationModeEXT = u32;
+pub const CONSERVATIVE_RASTERIZATION_MODE_DISABLED_EXT: u32 = 0;
+pub const CONSERVATIVE_RASTERIZATION_MODE_OVERESTIMATE_EXT: u32 = 1;
+pub const CONSERVATIVE_RASTERIZATION_MODE_UNDERESTIMATE_EXT: u32 = 2;
+
+pub type LineRasterizationModeEXT = u32;
+pub const LINE_RASTERIZATION_MODE_DEFAULT_EXT: u32 = 0;
+pub const LINE_RASTERIZATION_MODE_RECTANGULAR_EXT: u32 = 1;
+pub const LINE_RASTERIZATION_MODE_BRESENHAM_EXT: u32 = 2;
+pub const LINE_RASTERIZATION_MODE_RECTANGULAR_SMOOTH_EXT: u32 = 3;
+
+pub type PipelineExecutableStatisticFormatKHR = u32;
+pub const PIPELINE_EXECUTABLE_STATISTIC_FORMAT_BOOL32_KHR: u32 = 0;
+pub const PIPELINE_EXECUTABLE_STATISTIC_FORMAT_INT64_KHR: u32 = 1;
+pub const PIPELINE_EXECUTABLE_STATISTIC_FORMAT_UINT64_KHR: u32 = 2;
+pub const PIPELINE_EXECUTABLE_STATISTIC_FORMAT_FLOAT64_KHR: u32 = 3;
+
+pub type RayTracingShaderGroupTypeNV = u32;
+pub const RAY_TRACING_SHADER_GROUP_TYPE_GENERAL_NV: u32 = 0;
+pub const RAY_TRACING_SHADER_GROUP_TYPE_TRIANGLES_HIT_GROUP_NV: u32 = 1;
+pub const RAY_TRACING_SHADER_GROUP_TYPE_PROCEDURAL_HIT_GROUP_NV: u32 = 2;
+
+pub const SHADER_UNUSED_NV: u32 = !0;
+
 pub type FrontFace = u32;
 pub const FRONT_FACE_COUNTER_CLOCKWISE: u32 = 0;
 pub const FRONT_FACE_CLOCKWISE: u32 = 1;
@@ -607,6 +685,7 @@ pub const ATTACHMENT_STORE_OP_DONT_CARE: u32 = 1;
 pub type PipelineBindPoint = u32;
 pub const PIPELINE_BIND_POINT_GRAPHICS: u32 = 0;
 pub const PIPELINE_BIND_POINT_COMPUTE: u32 = 1;
+pub const PIPELINE_BIND_POINT_RAY_TRACING_NV: u32 = 1000165000;
 
 pub type CommandBufferLevel = u32;
 pub const COMMAND_BUFFER_LEVEL_PRIMARY: u32 = 0;
@@ -741,7 +820,11 @@ pub const PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT: u32 = 0x00002000;
 pub const PIPELINE_STAGE_HOST_BIT: u32 = 0x00004000;
 pub const PIPELINE_STAGE_ALL_GRAPHICS_BIT: u32 = 0x00008000;
 pub const PIPELINE_STAGE_ALL_COMMANDS_BIT: u32 = 0x00010000;
+pub const PIPELINE_STAGE_TASK_SHADER_BIT_EXT: u32 = 0x00080000;
+pub const PIPELINE_STAGE_MESH_SHADER_BIT_EXT: u32 = 0x00100000;
+pub const PIPELINE_STAGE_RAY_TRACING_SHADER_BIT_NV: u32 = 0x00200000;
 pub type PipelineStageFlags = Flags;
+pub type PipelineStageFlags2KHR = u64;
 pub type MemoryMapFlags = Flags;
 
 pub type ImageAspectFlagBits = u32;
@@ -806,6 +889,7 @@ pub const BUFFER_USAGE_INDEX_BUFFER_BIT: u32 = 0x00000040;
 pub const BUFFER_USAGE_VERTEX_BUFFER_BIT: u32 = 0x00000080;
 pub const BUFFER_USAGE_INDIRECT_BUFFER_BIT: u32 = 0x00000100;
 pub const BUFFER_USAGE_SHADER_DEVICE_ADDRESS_BIT: u32 = 0x00020000;
+pub const BUFFER_USAGE_RAY_TRACING_BIT_NV: u32 = 0x00000400;
 pub type BufferUsageFlags = Flags;
 pub type BufferViewCreateFlags = Flags;
 pub type ImageViewCreateFlags = Flags;
@@ -816,6 +900,9 @@ pub type PipelineCreateFlagBits = u32;
 pub const PIPELINE_CREATE_DISABLE_OPTIMIZATION_BIT: u32 = 0x00000001;
 pub const PIPELINE_CREATE_ALLOW_DERIVATIVES_BIT: u32 = 0x00000002;
 pub const PIPELINE_CREATE_DERIVATIVE_BIT: u32 = 0x00000004;
+pub const PIPELINE_CREATE_DISPATCH_BASE: u32 = 0x00000010;
+pub const PIPELINE_CREATE_CAPTURE_STATISTICS_BIT_KHR: u32 = 0x00000040;
+pub const PIPELINE_CREATE_CAPTURE_INTERNAL_REPRESENTATIONS_BIT_KHR: u32 = 0x00000080;
 pub type PipelineCreateFlags = Flags;
 pub type PipelineShaderStageCreateFlags = Flags;
 
@@ -826,6 +913,14 @@ pub const SHADER_STAGE_TESSELLATION_EVALUATION_BIT: u32 = 0x00000004;
 pub const SHADER_STAGE_GEOMETRY_BIT: u32 = 0x00000008;
 pub const SHADER_STAGE_FRAGMENT_BIT: u32 = 0x00000010;
 pub const SHADER_STAGE_COMPUTE_BIT: u32 = 0x00000020;
+pub const SHADER_STAGE_TASK_BIT_EXT: u32 = 0x00000040;
+pub const SHADER_STAGE_MESH_BIT_EXT: u32 = 0x00000080;
+pub const SHADER_STAGE_RAYGEN_BIT_NV: u32 = 0x00000100;
+pub const SHADER_STAGE_ANY_HIT_BIT_NV: u32 = 0x00000200;
+pub const SHADER_STAGE_CLOSEST_HIT_BIT_NV: u32 = 0x00000400;
+pub const SHADER_STAGE_MISS_BIT_NV: u32 = 0x00000800;
+pub const SHADER_STAGE_INTERSECTION_BIT_NV: u32 = 0x00001000;
+pub const SHADER_STAGE_CALLABLE_BIT_NV: u32 = 0x00002000;
 pub const SHADER_STAGE_ALL_GRAPHICS: u32 = 0x1F;
 pub const SHADER_STAGE_ALL: u32 = 0x7FFFFFFF;
 pub type PipelineVertexInputStateCreateFlags = Flags;
@@ -888,6 +983,7 @@ pub const ACCESS_HOST_WRITE_BIT: u32 = 0x00004000;
 pub const ACCESS_MEMORY_READ_BIT: u32 = 0x00008000;
 pub const ACCESS_MEMORY_WRITE_BIT: u32 = 0x00010000;
 pub type AccessFlags = Flags;
+pub type AccessFlags2KHR = u64;
 
 pub type DependencyFlagBits = u32;
 pub const DEPENDENCY_BY_REGION_BIT: u32 = 0x00000001;
@@ -948,6 +1044,7 @@ pub const COLOR_SPACE_HDR10_HLG_EXT: u32 = 1000104010;
 pub const COLOR_SPACE_ADOBERGB_LINEAR_EXT: u32 = 1000104011;
 pub const COLOR_SPACE_ADOBERGB_NONLINEAR_EXT: u32 = 1000104012;
 pub const COLOR_SPACE_PASS_THROUGH_EXT: u32 = 1000104013;
+pub const COLOR_SPACE_EXTENDED_SRGB_NONLINEAR_EXT: u32 = 1000104014;
 
 pub type PresentModeKHR = u32;
 pub const PRESENT_MODE_IMMEDIATE_KHR: u32 = 0;
@@ -1044,6 +1141,42 @@ pub type IOSSurfaceCreateFlagsMVK = u32;
 pub type DescriptorSetLayoutCreateFlagBits = u32;
 pub const DESCRIPTOR_SET_LAYOUT_CREATE_PUSH_DESCRIPTOR_BIT_KHR: u32 = 0x00000001;
 
+pub type ExternalMemoryHandleTypeFlagBitsKHR = u32;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_FD_BIT_KHR: u32 = 0x00000001;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_WIN32_BIT_KHR: u32 = 0x00000002;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT_KHR: u32 = 0x00000004;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_D3D11_TEXTURE_BIT_KHR: u32 = 0x00000008;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_D3D11_TEXTURE_KMT_BIT_KHR: u32 = 0x00000010;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_HEAP_BIT_KHR: u32 = 0x00000020;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_RESOURCE_BIT_KHR: u32 = 0x00000040;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_HOST_ALLOCATION_BIT_EXT: u32 = 0x00000080;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_HOST_MAPPED_FOREIGN_MEMORY_BIT_EXT: u32 = 0x00000100;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_DMA_BUF_BIT_EXT: u32 = 0x00000200;
+pub type ExternalMemoryHandleTypeFlagsKHR = Flags;
+
+pub type ExternalSemaphoreHandleTypeFlagBitsKHR = u32;
+pub const EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_FD_BIT_KHR: u32 = 0x00000001;
+pub const EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32_BIT_KHR: u32 = 0x00000002;
+pub const EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT_KHR: u32 = 0x00000004;
+pub const EXTERNAL_SEMAPHORE_HANDLE_TYPE_D3D12_FENCE_BIT_KHR: u32 = 0x00000008;
+pub const EXTERNAL_SEMAPHORE_HANDLE_TYPE_SYNC_FD_BIT_KHR: u32 = 0x00000010;
+pub type ExternalSemaphoreHandleTypeFlagsKHR = Flags;
+
+pub type SemaphoreImportFlagBitsKHR = u32;
+pub const SEMAPHORE_IMPORT_TEMPORARY_BIT_KHR: u32 = 0x00000001;
+pub type SemaphoreImportFlagsKHR = Flags;
+
+pub type ExternalFenceHandleTypeFlagBitsKHR = u32;
+pub const EXTERNAL_FENCE_HANDLE_TYPE_OPAQUE_FD_BIT_KHR: u32 = 0x00000001;
+pub const EXTERNAL_FENCE_HANDLE_TYPE_OPAQUE_WIN32_BIT_KHR: u32 = 0x00000002;
+pub const EXTERNAL_FENCE_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT_KHR: u32 = 0x00000004;
+pub const EXTERNAL_FENCE_HANDLE_TYPE_SYNC_FD_BIT_KHR: u32 = 0x00000008;
+pub type ExternalFenceHandleTypeFlagsKHR = Flags;
+
+pub type FenceImportFlagBitsKHR = u32;
+pub const FENCE_IMPORT_TEMPORARY_BIT_KHR: u32 = 0x00000001;
+pub type FenceImportFlagsKHR = Flags;
+
 pub type DescriptorUpdateTemplateTypeKHR = u32;
 pub const DESCRIPTOR_UPDATE_TEMPLATE_TYPE_DESCRIPTOR_SET_KHR: u32 = 0;
 pub const DESCRIPTOR_UPDATE_TEMPLATE_TYPE_PUSH_DESCRIPTORS_KHR: u32 = 1;
@@ -2286,6 +2419,13 @@ pub struct DrawIndirectCommand {
     pub firstInstance: u32,
 }
 
+#[repr(C)]
+pub struct DrawMeshTasksIndirectCommandEXT {
+    pub groupCountX: u32,
+    pub groupCountY: u32,
+    pub groupCountZ: u32,
+}
+
 #[repr(C)]
 pub struct SurfaceCapabilitiesKHR {
     pub minImageCount: u32,
@@ -2604,6 +2744,162 @@ pub struct PhysicalDeviceBufferAddressFeaturesEXT {
     pub bufferDeviceAddressMultiDevice: Bool32,
 }
 
+#[repr(C)]
+pub struct ExternalMemoryBufferCreateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub handleTypes: ExternalMemoryHandleTypeFlagsKHR,
+}
+
+#[repr(C)]
+pub struct ExternalMemoryImageCreateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub handleTypes: ExternalMemoryHandleTypeFlagsKHR,
+}
+
+#[repr(C)]
+pub struct ExportMemoryAllocateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub handleTypes: ExternalMemoryHandleTypeFlagsKHR,
+}
+
+#[repr(C)]
+pub struct ImportMemoryFdInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub handleType: ExternalMemoryHandleTypeFlagBitsKHR,
+    pub fd: i32,
+}
+
+#[repr(C)]
+pub struct MemoryGetFdInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub memory: DeviceMemory,
+    pub handleType: ExternalMemoryHandleTypeFlagBitsKHR,
+}
+
+#[repr(C)]
+pub struct MemoryFdPropertiesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub memoryTypeBits: u32,
+}
+
+#[repr(C)]
+pub struct ImportMemoryWin32HandleInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub handleType: ExternalMemoryHandleTypeFlagBitsKHR,
+    pub handle: *mut c_void,
+    pub name: *const u16,
+}
+
+#[repr(C)]
+pub struct MemoryGetWin32HandleInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub memory: DeviceMemory,
+    pub handleType: ExternalMemoryHandleTypeFlagBitsKHR,
+}
+
+#[repr(C)]
+pub struct MemoryWin32HandlePropertiesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub memoryTypeBits: u32,
+}
+
+#[repr(C)]
+pub struct ExportSemaphoreCreateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub handleTypes: ExternalSemaphoreHandleTypeFlagsKHR,
+}
+
+#[repr(C)]
+pub struct ImportSemaphoreFdInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub semaphore: Semaphore,
+    pub flags: SemaphoreImportFlagsKHR,
+    pub handleType: ExternalSemaphoreHandleTypeFlagBitsKHR,
+    pub fd: i32,
+}
+
+#[repr(C)]
+pub struct SemaphoreGetFdInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub semaphore: Semaphore,
+    pub handleType: ExternalSemaphoreHandleTypeFlagBitsKHR,
+}
+
+#[repr(C)]
+pub struct ImportSemaphoreWin32HandleInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub semaphore: Semaphore,
+    pub flags: SemaphoreImportFlagsKHR,
+    pub handleType: ExternalSemaphoreHandleTypeFlagBitsKHR,
+    pub handle: *mut c_void,
+    pub name: *const u16,
+}
+
+#[repr(C)]
+pub struct SemaphoreGetWin32HandleInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub semaphore: Semaphore,
+    pub handleType: ExternalSemaphoreHandleTypeFlagBitsKHR,
+}
+
+#[repr(C)]
+pub struct ExportFenceCreateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub handleTypes: ExternalFenceHandleTypeFlagsKHR,
+}
+
+#[repr(C)]
+pub struct ImportFenceFdInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub fence: Fence,
+    pub flags: FenceImportFlagsKHR,
+    pub handleType: ExternalFenceHandleTypeFlagBitsKHR,
+    pub fd: i32,
+}
+
+#[repr(C)]
+pub struct FenceGetFdInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub fence: Fence,
+    pub handleType: ExternalFenceHandleTypeFlagBitsKHR,
+}
+
+#[repr(C)]
+pub struct ImportFenceWin32HandleInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub fence: Fence,
+    pub flags: FenceImportFlagsKHR,
+    pub handleType: ExternalFenceHandleTypeFlagBitsKHR,
+    pub handle: *mut c_void,
+    pub name: *const u16,
+}
+
+#[repr(C)]
+pub struct FenceGetWin32HandleInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub fence: Fence,
+    pub handleType: ExternalFenceHandleTypeFlagBitsKHR,
+}
+
 #[repr(C)]
 pub struct PhysicalDeviceVariablePointersFeatures {
     pub sType: StructureType,
@@ -2647,6 +2943,322 @@ pub struct PhysicalDeviceShaderFloat16Int8Features {
     pub shaderInt8: Bool32,
 }
 
+#[repr(C)]
+pub struct PhysicalDeviceVertexAttributeDivisorFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub vertexAttributeInstanceRateDivisor: Bool32,
+    pub vertexAttributeInstanceRateZeroDivisor: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceVertexAttributeDivisorPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub maxVertexAttribDivisor: u32,
+}
+
+pub type PipelineRasterizationConservativeStateCreateFlagsEXT = Flags;
+
+#[repr(C)]
+pub struct PipelineRasterizationConservativeStateCreateInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub flags: PipelineRasterizationConservativeStateCreateFlagsEXT,
+    pub conservativeRasterizationMode: ConservativeRasterizationModeEXT,
+    pub extraPrimitiveOverestimationSize: f32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceConservativeRasterizationPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub primitiveOverestimationSize: f32,
+    pub maxExtraPrimitiveOverestimationSize: f32,
+    pub extraPrimitiveOverestimationSizeGranularity: f32,
+    pub primitiveUnderestimation: Bool32,
+    pub conservativePointAndLineRasterization: Bool32,
+    pub degenerateTrianglesRasterized: Bool32,
+    pub degenerateLinesRasterized: Bool32,
+    pub fullyCoveredFragmentShaderInputVariable: Bool32,
+    pub conservativeRasterizationPostDepthCoverage: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceLineRasterizationFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub rectangularLines: Bool32,
+    pub bresenhamLines: Bool32,
+    pub smoothLines: Bool32,
+    pub stippledRectangularLines: Bool32,
+    pub stippledBresenhamLines: Bool32,
+    pub stippledSmoothLines: Bool32,
+}
+
+#[repr(C)]
+pub struct PipelineRasterizationLineStateCreateInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub lineRasterizationMode: LineRasterizationModeEXT,
+    pub stippledLineEnable: Bool32,
+    pub lineStippleFactor: u32,
+    pub lineStipplePattern: u16,
+}
+
+#[repr(C)]
+pub struct PhysicalDevicePipelineExecutablePropertiesFeaturesKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub pipelineExecutableInfo: Bool32,
+}
+
+#[repr(C)]
+pub struct PipelineInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub pipeline: Pipeline,
+}
+
+#[repr(C)]
+pub struct PipelineExecutablePropertiesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub stages: ShaderStageFlags,
+    pub name: [c_char; MAX_DESCRIPTION_SIZE as usize],
+    pub description: [c_char; MAX_DESCRIPTION_SIZE as usize],
+    pub subgroupSize: u32,
+}
+
+#[repr(C)]
+pub struct PipelineExecutableInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub pipeline: Pipeline,
+    pub executableIndex: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union PipelineExecutableStatisticValueKHR {
+    pub b32: Bool32,
+    pub i64: i64,
+    pub u64: u64,
+    pub f64: f64,
+}
+
+#[repr(C)]
+pub struct PipelineExecutableStatisticKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub name: [c_char; MAX_DESCRIPTION_SIZE as usize],
+    pub description: [c_char; MAX_DESCRIPTION_SIZE as usize],
+    pub format: PipelineExecutableStatisticFormatKHR,
+    pub value: PipelineExecutableStatisticValueKHR,
+}
+
+#[repr(C)]
+pub struct PipelineExecutableInternalRepresentationKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub name: [c_char; MAX_DESCRIPTION_SIZE as usize],
+    pub description: [c_char; MAX_DESCRIPTION_SIZE as usize],
+    pub isText: Bool32,
+    pub dataSize: usize,
+    pub pData: *mut c_void,
+}
+
+#[repr(C)]
+pub struct RayTracingShaderGroupCreateInfoNV {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub type_: RayTracingShaderGroupTypeNV,
+    pub generalShader: u32,
+    pub closestHitShader: u32,
+    pub anyHitShader: u32,
+    pub intersectionShader: u32,
+}
+
+#[repr(C)]
+pub struct RayTracingPipelineCreateInfoNV {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub flags: PipelineCreateFlags,
+    pub stageCount: u32,
+    pub pStages: *const PipelineShaderStageCreateInfo,
+    pub groupCount: u32,
+    pub pGroups: *const RayTracingShaderGroupCreateInfoNV,
+    pub maxRecursionDepth: u32,
+    pub layout: PipelineLayout,
+    pub basePipelineHandle: Pipeline,
+    pub basePipelineIndex: i32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceRayTracingPropertiesNV {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub shaderGroupHandleSize: u32,
+    pub maxRecursionDepth: u32,
+    pub maxShaderGroupStride: u32,
+    pub shaderGroupBaseAlignment: u32,
+    pub maxGeometryCount: u64,
+    pub maxInstanceCount: u64,
+    pub maxTriangleCount: u64,
+    pub maxDescriptorSetAccelerationStructures: u32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceMeshShaderFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub taskShader: Bool32,
+    pub meshShader: Bool32,
+    pub multiviewMeshShader: Bool32,
+    pub primitiveFragmentShadingRateMeshShader: Bool32,
+    pub meshShaderQueries: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceSynchronization2FeaturesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub synchronization2: Bool32,
+}
+
+#[repr(C)]
+pub struct MemoryBarrier2KHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub srcStageMask: PipelineStageFlags2KHR,
+    pub srcAccessMask: AccessFlags2KHR,
+    pub dstStageMask: PipelineStageFlags2KHR,
+    pub dstAccessMask: AccessFlags2KHR,
+}
+
+#[repr(C)]
+pub struct BufferMemoryBarrier2KHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub srcStageMask: PipelineStageFlags2KHR,
+    pub srcAccessMask: AccessFlags2KHR,
+    pub dstStageMask: PipelineStageFlags2KHR,
+    pub dstAccessMask: AccessFlags2KHR,
+    pub srcQueueFamilyIndex: u32,
+    pub dstQueueFamilyIndex: u32,
+    pub buffer: Buffer,
+    pub offset: DeviceSize,
+    pub size: DeviceSize,
+}
+
+#[repr(C)]
+pub struct ImageMemoryBarrier2KHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub srcStageMask: PipelineStageFlags2KHR,
+    pub srcAccessMask: AccessFlags2KHR,
+    pub dstStageMask: PipelineStageFlags2KHR,
+    pub dstAccessMask: AccessFlags2KHR,
+    pub oldLayout: ImageLayout,
+    pub newLayout: ImageLayout,
+    pub srcQueueFamilyIndex: u32,
+    pub dstQueueFamilyIndex: u32,
+    pub image: Image,
+    pub subresourceRange: ImageSubresourceRange,
+}
+
+#[repr(C)]
+pub struct DependencyInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub dependencyFlags: DependencyFlags,
+    pub memoryBarrierCount: u32,
+    pub pMemoryBarriers: *const MemoryBarrier2KHR,
+    pub bufferMemoryBarrierCount: u32,
+    pub pBufferMemoryBarriers: *const BufferMemoryBarrier2KHR,
+    pub imageMemoryBarrierCount: u32,
+    pub pImageMemoryBarriers: *const ImageMemoryBarrier2KHR,
+}
+
+#[repr(C)]
+pub struct SemaphoreSubmitInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub semaphore: Semaphore,
+    pub value: u64,
+    pub stageMask: PipelineStageFlags2KHR,
+    pub deviceIndex: u32,
+}
+
+#[repr(C)]
+pub struct CommandBufferSubmitInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub commandBuffer: CommandBuffer,
+    pub deviceMask: u32,
+}
+
+#[repr(C)]
+pub struct SubmitInfo2KHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub flags: Flags,
+    pub waitSemaphoreInfoCount: u32,
+    pub pWaitSemaphoreInfos: *const SemaphoreSubmitInfoKHR,
+    pub commandBufferInfoCount: u32,
+    pub pCommandBufferInfos: *const CommandBufferSubmitInfoKHR,
+    pub signalSemaphoreInfoCount: u32,
+    pub pSignalSemaphoreInfos: *const SemaphoreSubmitInfoKHR,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceMeshShaderPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub maxTaskWorkGroupTotalCount: u32,
+    pub maxTaskWorkGroupCount: [u32; 3],
+    pub maxTaskWorkGroupInvocations: u32,
+    pub maxTaskWorkGroupSize: [u32; 3],
+    pub maxTaskPayloadSize: u32,
+    pub maxTaskSharedMemorySize: u32,
+    pub maxTaskPayloadAndSharedMemorySize: u32,
+    pub maxMeshWorkGroupTotalCount: u32,
+    pub maxMeshWorkGroupCount: [u32; 3],
+    pub maxMeshWorkGroupInvocations: u32,
+    pub maxMeshWorkGroupSize: [u32; 3],
+    pub maxMeshSharedMemorySize: u32,
+    pub maxMeshPayloadAndSharedMemorySize: u32,
+    pub maxMeshOutputMemorySize: u32,
+    pub maxMeshPayloadAndOutputMemorySize: u32,
+    pub maxMeshOutputComponents: u32,
+    pub maxMeshOutputVertices: u32,
+    pub maxMeshOutputPrimitives: u32,
+    pub maxMeshOutputLayers: u32,
+    pub maxMeshMultiviewViewCount: u32,
+    pub meshOutputPerVertexGranularity: u32,
+    pub meshOutputPerPrimitiveGranularity: u32,
+    pub maxPreferredTaskWorkGroupInvocations: u32,
+    pub maxPreferredMeshWorkGroupInvocations: u32,
+    pub prefersLocalInvocationVertexOutput: Bool32,
+    pub prefersLocalInvocationPrimitiveOutput: Bool32,
+    pub prefersCompactVertexOutput: Bool32,
+    pub prefersCompactPrimitiveOutput: Bool32,
+}
+
+#[repr(C)]
+pub struct VertexInputBindingDivisorDescriptionEXT {
+    pub binding: u32,
+    pub divisor: u32,
+}
+
+#[repr(C)]
+pub struct PipelineVertexInputDivisorStateCreateInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub vertexBindingDivisorCount: u32,
+    pub pVertexBindingDivisors: *const VertexInputBindingDivisorDescriptionEXT,
+}
+
 pub type ViSurfaceCreateFlagsNN = Flags;
 
 #[repr(C)]
@@ -2704,6 +3316,14 @@ pub struct MemoryDedicatedAllocateInfoKHR {
     pub buffer: Buffer,
 }
 
+#[repr(C)]
+pub struct ImageFormatListCreateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub viewFormatCount: u32,
+    pub pViewFormats: *const Format,
+}
+
 #[repr(C)]
 pub struct BufferMemoryRequirementsInfo2KHR {
     pub sType: StructureType,
@@ -2807,6 +3427,13 @@ pub struct SurfaceFullScreenExclusiveInfoEXT {
     pub fullScreenExclusive: FullScreenExclusiveEXT,
 }
 
+#[repr(C)]
+pub struct SurfaceFullScreenExclusiveWin32InfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub hmonitor: *const c_void,
+}
+
 macro_rules! ptrs {
     ($struct_name:ident, { $($name:ident => ($($param_n:ident: $param_ty:ty),*) -> $ret:ty,)+ }) => (
         pub struct $struct_name {
@@ -3014,6 +3641,8 @@ ptrs!(DevicePointers, {
     CmdDrawIndexedIndirect => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize, drawCount: u32, stride: u32) -> (),
     CmdDispatch => (commandBuffer: CommandBuffer, x: u32, y: u32, z: u32) -> (),
     CmdDispatchIndirect => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize) -> (),
+    CmdDispatchBase => (commandBuffer: CommandBuffer, baseGroupX: u32, baseGroupY: u32, baseGroupZ: u32, groupCountX: u32, groupCountY: u32, groupCountZ: u32) -> (),
+    CmdDispatchBaseKHR => (commandBuffer: CommandBuffer, baseGroupX: u32, baseGroupY: u32, baseGroupZ: u32, groupCountX: u32, groupCountY: u32, groupCountZ: u32) -> (),
     CmdCopyBuffer => (commandBuffer: CommandBuffer, srcBuffer: Buffer, dstBuffer: Buffer, regionCount: u32, pRegions: *const BufferCopy) -> (),
     CmdCopyImage => (commandBuffer: CommandBuffer, srcImage: Image, srcImageLayout: ImageLayout, dstImage: Image, dstImageLayout: ImageLayout, regionCount: u32, pRegions: *const ImageCopy) -> (),
     CmdBlitImage => (commandBuffer: CommandBuffer, srcImage: Image, srcImageLayout: ImageLayout, dstImage: Image, dstImageLayout: ImageLayout, regionCount: u32, pRegions: *const ImageBlit, filter: Filter) -> (),
@@ -3059,4 +3688,28 @@ ptrs!(DevicePointers, {
     AcquireFullScreenExclusiveModeEXT => (device: Device, swapchain: SwapchainKHR) -> Result,
     ReleaseFullScreenExclusiveModeEXT => (device: Device, swapchain: SwapchainKHR) -> Result,
     GetBufferDeviceAddressEXT => (device: Device, pInfo: *const BufferDeviceAddressInfo) -> DeviceAddress,
+    GetMemoryFdKHR => (device: Device, pGetFdInfo: *const MemoryGetFdInfoKHR, pFd: *mut i32) -> Result,
+    GetMemoryFdPropertiesKHR => (device: Device, handleType: ExternalMemoryHandleTypeFlagBitsKHR, fd: i32, pMemoryFdProperties: *mut MemoryFdPropertiesKHR) -> Result,
+    GetMemoryWin32HandleKHR => (device: Device, pGetWin32HandleInfo: *const MemoryGetWin32HandleInfoKHR, pHandle: *mut *mut c_void) -> Result,
+    GetMemoryWin32HandlePropertiesKHR => (device: Device, handleType: ExternalMemoryHandleTypeFlagBitsKHR, handle: *mut c_void, pMemoryWin32HandleProperties: *mut MemoryWin32HandlePropertiesKHR) -> Result,
+    ImportSemaphoreFdKHR => (device: Device, pImportSemaphoreFdInfo: *const ImportSemaphoreFdInfoKHR) -> Result,
+    GetSemaphoreFdKHR => (device: Device, pGetFdInfo: *const SemaphoreGetFdInfoKHR, pFd: *mut i32) -> Result,
+    ImportSemaphoreWin32HandleKHR => (device: Device, pImportSemaphoreWin32HandleInfo: *const ImportSemaphoreWin32HandleInfoKHR) -> Result,
+    GetSemaphoreWin32HandleKHR => (device: Device, pGetWin32HandleInfo: *const SemaphoreGetWin32HandleInfoKHR, pHandle: *mut *mut c_void) -> Result,
+    ImportFenceFdKHR => (device: Device, pImportFenceFdInfo: *const ImportFenceFdInfoKHR) -> Result,
+    GetFenceFdKHR => (device: Device, pGetFdInfo: *const FenceGetFdInfoKHR, pFd: *mut i32) -> Result,
+    ImportFenceWin32HandleKHR => (device: Device, pImportFenceWin32HandleInfo: *const ImportFenceWin32HandleInfoKHR) -> Result,
+    GetFenceWin32HandleKHR => (device: Device, pGetWin32HandleInfo: *const FenceGetWin32HandleInfoKHR, pHandle: *mut *mut c_void) -> Result,
+    GetPipelineExecutablePropertiesKHR => (device: Device, pPipelineInfo: *const PipelineInfoKHR, pExecutableCount: *mut u32, pProperties: *mut PipelineExecutablePropertiesKHR) -> Result,
+    GetPipelineExecutableStatisticsKHR => (device: Device, pExecutableInfo: *const PipelineExecutableInfoKHR, pStatisticCount: *mut u32, pStatistics: *mut PipelineExecutableStatisticKHR) -> Result,
+    GetPipelineExecutableInternalRepresentationsKHR => (device: Device, pExecutableInfo: *const PipelineExecutableInfoKHR, pInternalRepresentationCount: *mut u32, pInternalRepresentations: *mut PipelineExecutableInternalRepresentationKHR) -> Result,
+    CreateRayTracingPipelinesNV => (device: Device, pipelineCache: PipelineCache, createInfoCount: u32, pCreateInfos: *const RayTracingPipelineCreateInfoNV, pAllocator: *const AllocationCallbacks, pPipelines: *mut Pipeline) -> Result,
+    GetRayTracingShaderGroupHandlesNV => (device: Device, pipeline: Pipeline, firstGroup: u32, groupCount: u32, dataSize: usize, pData: *mut c_void) -> Result,
+    CmdTraceRaysNV => (commandBuffer: CommandBuffer, raygenShaderBindingTableBuffer: Buffer, raygenShaderBindingOffset: DeviceSize, missShaderBindingTableBuffer: Buffer, missShaderBindingOffset: DeviceSize, missShaderBindingStride: DeviceSize, hitShaderBindingTableBuffer: Buffer, hitShaderBindingOffset: DeviceSize, hitShaderBindingStride: DeviceSize, callableShaderBindingTableBuffer: Buffer, callableShaderBindingOffset: DeviceSize, callableShaderBindingStride: DeviceSize, width: u32, height: u32, depth: u32) -> (),
+    CmdDrawMeshTasksEXT => (commandBuffer: CommandBuffer, groupCountX: u32, groupCountY: u32, groupCountZ: u32) -> (),
+    CmdDrawMeshTasksIndirectEXT => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize, drawCount: u32, stride: u32) -> (),
+    CmdDrawIndirectCountKHR => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize, countBuffer: Buffer, countBufferOffset: DeviceSize, maxDrawCount: u32, stride: u32) -> (),
+    CmdDrawIndexedIndirectCountKHR => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize, countBuffer: Buffer, countBufferOffset: DeviceSize, maxDrawCount: u32, stride: u32) -> (),
+    CmdPipelineBarrier2KHR => (commandBuffer: CommandBuffer, pDependencyInfo: *const DependencyInfoKHR) -> (),
+    QueueSubmit2KHR => (queue: Queue, submitCount: u32, pSubmits: *const SubmitInfo2KHR, fence: Fence) -> Result,
 });
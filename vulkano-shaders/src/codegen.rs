@@ -467,6 +467,10 @@ fn capability_requirement(cap: &Capability) -> DeviceRequirement {
         Capability::CapabilityStorageInputOutput16 => {
             DeviceRequirement::Extensions(&["khr_16bit_storage"])
         }
+        Capability::CapabilityMeshShadingEXT => DeviceRequirement::Features(&["mesh_shader"]),
+        Capability::CapabilityPhysicalStorageBufferAddresses => {
+            DeviceRequirement::Features(&["buffer_device_address"])
+        }
     }
 }
 
@@ -488,6 +492,10 @@ fn storage_class_requirement(storage_class: &StorageClass) -> DeviceRequirement
         StorageClass::StorageClassStorageBuffer => {
             DeviceRequirement::Extensions(&["khr_storage_buffer_storage_class"])
         }
+        // The requirement is already expressed by the `PhysicalStorageBufferAddresses` capability.
+        StorageClass::StorageClassPhysicalStorageBuffer => DeviceRequirement::None,
+        // The requirement is already expressed by the `MeshShadingEXT` capability.
+        StorageClass::StorageClassTaskPayloadWorkgroupEXT => DeviceRequirement::None,
     }
 }
 
@@ -41,6 +41,7 @@ pub fn write_entry_point(doc: &Spirv, instruction: &Instruction) -> (TokenStream
     };
     let ignore_first_array_out = match *execution {
         ExecutionModel::ExecutionModelTessellationControl => true,
+        ExecutionModel::ExecutionModelMeshEXT => true,
         _ => false,
     };
 
@@ -126,9 +127,26 @@ pub fn write_entry_point(doc: &Spirv, instruction: &Instruction) -> (TokenStream
                     quote! { ::vulkano::pipeline::shader::GraphicsShaderType::Fragment }
                 }
 
+                ExecutionModel::ExecutionModelTaskEXT => {
+                    quote! { ::vulkano::pipeline::shader::GraphicsShaderType::Task }
+                }
+
+                ExecutionModel::ExecutionModelMeshEXT => {
+                    quote! { ::vulkano::pipeline::shader::GraphicsShaderType::Mesh }
+                }
+
                 ExecutionModel::ExecutionModelGLCompute => unreachable!(),
 
                 ExecutionModel::ExecutionModelKernel => panic!("Kernels are not supported"),
+
+                ExecutionModel::ExecutionModelRayGenerationNV
+                | ExecutionModel::ExecutionModelIntersectionNV
+                | ExecutionModel::ExecutionModelAnyHitNV
+                | ExecutionModel::ExecutionModelClosestHitNV
+                | ExecutionModel::ExecutionModelMissNV
+                | ExecutionModel::ExecutionModelCallableNV => {
+                    panic!("Ray tracing shaders are not supported")
+                }
             };
 
             let stage = match *execution {
@@ -152,8 +170,22 @@ pub fn write_entry_point(doc: &Spirv, instruction: &Instruction) -> (TokenStream
                     quote! { ShaderStages { fragment: true, .. ShaderStages::none() } }
                 }
 
+                ExecutionModel::ExecutionModelTaskEXT => {
+                    quote! { ShaderStages { task: true, .. ShaderStages::none() } }
+                }
+
+                ExecutionModel::ExecutionModelMeshEXT => {
+                    quote! { ShaderStages { mesh: true, .. ShaderStages::none() } }
+                }
+
                 ExecutionModel::ExecutionModelGLCompute => unreachable!(),
                 ExecutionModel::ExecutionModelKernel => unreachable!(),
+                ExecutionModel::ExecutionModelRayGenerationNV => unreachable!(),
+                ExecutionModel::ExecutionModelIntersectionNV => unreachable!(),
+                ExecutionModel::ExecutionModelAnyHitNV => unreachable!(),
+                ExecutionModel::ExecutionModelClosestHitNV => unreachable!(),
+                ExecutionModel::ExecutionModelMissNV => unreachable!(),
+                ExecutionModel::ExecutionModelCallableNV => unreachable!(),
             };
 
             let mut capitalized_ep_name_input = capitalized_ep_name.clone();
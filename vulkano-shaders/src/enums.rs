@@ -53,12 +53,21 @@ enumeration! {
         ExecutionModelFragment = 4,
         ExecutionModelGLCompute = 5,
         ExecutionModelKernel = 6,
+        ExecutionModelRayGenerationNV = 5313,
+        ExecutionModelIntersectionNV = 5314,
+        ExecutionModelAnyHitNV = 5315,
+        ExecutionModelClosestHitNV = 5316,
+        ExecutionModelMissNV = 5317,
+        ExecutionModelCallableNV = 5318,
+        ExecutionModelTaskEXT = 5364,
+        ExecutionModelMeshEXT = 5365,
     } ExecutionModel;
 
     typedef enum AddressingModel_ {
         AddressingModelLogical = 0,
         AddressingModelPhysical32 = 1,
         AddressingModelPhysical64 = 2,
+        AddressingModelPhysicalStorageBuffer64 = 5348,
     } AddressingModel;
 
     typedef enum MemoryModel_ {
@@ -99,6 +108,9 @@ enumeration! {
         ExecutionModeOutputTriangleStrip = 29,
         ExecutionModeVecTypeHint = 30,
         ExecutionModeContractionOff = 31,
+        ExecutionModeOutputLinesEXT = 5269,
+        ExecutionModeOutputPrimitivesEXT = 5270,
+        ExecutionModeOutputTrianglesEXT = 5298,
     } ExecutionMode;
 
     typedef enum StorageClass_ {
@@ -115,6 +127,8 @@ enumeration! {
         StorageClassAtomicCounter = 10,
         StorageClassImage = 11,
         StorageClassStorageBuffer = 12,
+        StorageClassPhysicalStorageBuffer = 5349,
+        StorageClassTaskPayloadWorkgroupEXT = 5402,
     } StorageClass;
 
     typedef enum Dim_ {
@@ -338,6 +352,7 @@ enumeration! {
         DecorationNoContraction = 42,
         DecorationInputAttachmentIndex = 43,
         DecorationAlignment = 44,
+        DecorationPerPrimitiveEXT = 5271,
     } Decoration;
 
     typedef enum BuiltIn_ {
@@ -382,6 +397,10 @@ enumeration! {
         BuiltInSubgroupLocalInvocationId = 41,
         BuiltInVertexIndex = 42,
         BuiltInInstanceIndex = 43,
+        BuiltInPrimitivePointIndicesEXT = 5294,
+        BuiltInPrimitiveLineIndicesEXT = 5295,
+        BuiltInPrimitiveTriangleIndicesEXT = 5296,
+        BuiltInCullPrimitiveEXT = 5299,
     } BuiltIn;
 
     typedef enum SelectionControlShift_ {
@@ -551,5 +570,7 @@ enumeration! {
         CapabilityStorageUniform16 = 4434,
         CapabilityStoragePushConstant16 = 4435,
         CapabilityStorageInputOutput16 = 4436,
+        CapabilityMeshShadingEXT = 5283,
+        CapabilityPhysicalStorageBufferAddresses = 5347,
     } Capability;
 }
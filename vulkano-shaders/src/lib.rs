@@ -116,6 +116,10 @@
 //!
 //! For details on what these shader types mean, [see Vulkano's documentation][pipeline].
 //!
+//! Ray tracing shaders are not supported, as vulkano doesn't support acceleration structure
+//! descriptors yet. Their entry points must be described manually, for example with
+//! `ShaderModule::compute_entry_point` and a layout whose stages are the ray tracing stage.
+//!
 //! ## `src: "..."`
 //!
 //! Provides the raw GLSL source to be compiled in the form of a string. Cannot
@@ -1,10 +1,18 @@
 #![doc(html_logo_url = "https://raw.githubusercontent.com/vulkano-rs/vulkano/master/logo.png")]
 
-/// Create a surface either using winit or a RawWindowHandle
-/// Its possible to disable either one using features
-///
-/// RawWindowHandle feature is called 'raw-window-handle_'
-/// winit featur is called 'winit_'
+//! Create a surface either using winit or a RawWindowHandle
+//! Its possible to disable either one using features
+//!
+//! RawWindowHandle feature is called 'raw-window-handle_'
+//! winit featur is called 'winit_'
+//!
+//! With winit, `create_vk_surface` creates a surface from a `Window`, and
+//! `VkSurfaceBuild::build_vk_surface` builds the window and its surface at once. With any other
+//! windowing library, `create_vk_surface_from_handle` creates a surface from a window that
+//! implements `HasRawWindowHandle`, which covers Win32, Xlib, Xcb, Wayland, Android and the
+//! MoltenVK surfaces. In both cases, the instance must have been created with the extensions
+//! returned by `required_extensions`, and `Surface::is_supported` tells which queue families can
+//! present to the surface.
 
 #[cfg(feature = "raw-window-handle_")]
 mod raw_window_handle;
@@ -15,3 +23,28 @@ pub use crate::raw_window_handle::*;
 mod winit;
 #[cfg(feature = "winit_")]
 pub use crate::winit::*;
+
+use vulkano::instance::InstanceExtensions;
+
+/// Returns the instance extensions that are needed to create surfaces, among the ones that are
+/// supported by the Vulkan implementation.
+pub fn required_extensions() -> InstanceExtensions {
+    let ideal = InstanceExtensions {
+        khr_surface: true,
+        khr_xlib_surface: true,
+        khr_xcb_surface: true,
+        khr_wayland_surface: true,
+        khr_android_surface: true,
+        khr_win32_surface: true,
+        mvk_ios_surface: true,
+        mvk_macos_surface: true,
+        khr_get_physical_device_properties2: true,
+        khr_get_surface_capabilities2: true,
+        ..InstanceExtensions::none()
+    };
+
+    match InstanceExtensions::supported_by_core() {
+        Ok(supported) => supported.intersection(&ideal),
+        Err(_) => InstanceExtensions::none(),
+    }
+}
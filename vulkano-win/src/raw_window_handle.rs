@@ -16,6 +16,9 @@ use vulkano::swapchain::SurfaceCreationError;
 
 /// Creates a vulkan surface from a generic window
 /// which implements HasRawWindowHandle and thus can reveal the os-dependent handle
+///
+/// Returns `SurfaceCreationError::UnsupportedWindow` if the kind of handle can't be used on the
+/// current platform.
 pub fn create_vk_surface_from_handle<W>(
     window: W,
     instance: Arc<Instance>,
@@ -63,9 +66,7 @@ where
             RawWindowHandle::Android(h) => handle_to_surface(h.a_native_window, instance, window),
             #[cfg(target_os = "windows")]
             RawWindowHandle::Windows(h) => handle_to_surface(h.hinstance, h.hwnd, instance, window),
-            #[cfg(target_os = "wasm")]
-            RawWindowHandle::Web(_) => unimplemented!(),
-            _ => unimplemented!(),
+            _ => Err(SurfaceCreationError::UnsupportedWindow),
         }
     }
 }
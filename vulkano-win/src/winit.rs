@@ -7,9 +7,10 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use vulkano::instance::Instance;
-use vulkano::instance::InstanceExtensions;
 use vulkano::swapchain::Surface;
 use vulkano::swapchain::SurfaceCreationError;
+#[cfg(target_os = "windows")]
+use vulkano::swapchain::Win32Monitor;
 use winit::error::OsError as WindowCreationError;
 use winit::event_loop::EventLoopWindowTarget;
 use winit::window::Window;
@@ -27,27 +28,6 @@ use objc::runtime::YES;
 #[cfg(target_os = "macos")]
 use std::mem;
 
-pub fn required_extensions() -> InstanceExtensions {
-    let ideal = InstanceExtensions {
-        khr_surface: true,
-        khr_xlib_surface: true,
-        khr_xcb_surface: true,
-        khr_wayland_surface: true,
-        khr_android_surface: true,
-        khr_win32_surface: true,
-        mvk_ios_surface: true,
-        mvk_macos_surface: true,
-        khr_get_physical_device_properties2: true,
-        khr_get_surface_capabilities2: true,
-        ..InstanceExtensions::none()
-    };
-
-    match InstanceExtensions::supported_by_core() {
-        Ok(supported) => supported.intersection(&ideal),
-        Err(_) => InstanceExtensions::none(),
-    }
-}
-
 /// Create a surface from the window type `W`. The surface borrows the window
 /// to prevent it from being dropped before the surface.
 pub fn create_vk_surface<W>(
@@ -203,6 +183,17 @@ unsafe fn winit_to_surface<W: SafeBorrow<Window>>(
     Surface::from_macos_moltenvk(instance, win.borrow().ns_view() as *const (), win)
 }
 
+/// Creates a `Win32Monitor` from a winit monitor, to be used when creating a swapchain with
+/// `FullscreenExclusive::AppControlled`.
+#[cfg(target_os = "windows")]
+pub fn create_win32_monitor_from_winit(
+    monitor_handle: &winit::monitor::MonitorHandle,
+) -> Win32Monitor {
+    use winit::platform::windows::MonitorHandleExtWindows;
+
+    unsafe { Win32Monitor::new(monitor_handle.hmonitor() as *const ()) }
+}
+
 /// An alternative to `Borrow<T>` with the requirement that all calls to
 /// `borrow` return the same object.
 pub unsafe trait SafeBorrow<T>: Borrow<T> {}
@@ -0,0 +1,213 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+// Measures the time it takes to record many dispatches that use the same pipeline and descriptor
+// set, with and without eliding the binds of the objects that are already bound.
+//
+// Run with `cargo bench --bench state_cacher`. The benchmark does nothing if no Vulkan device is
+// available.
+
+extern crate vulkano;
+
+use std::ffi::CStr;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use vulkano::buffer::BufferUsage;
+use vulkano::buffer::CpuAccessibleBuffer;
+use vulkano::command_buffer::AutoCommandBufferBuilder;
+use vulkano::command_buffer::CommandBufferStatistics;
+use vulkano::descriptor::descriptor::DescriptorBufferDesc;
+use vulkano::descriptor::descriptor::DescriptorDesc;
+use vulkano::descriptor::descriptor::DescriptorDescTy;
+use vulkano::descriptor::descriptor::ShaderStages;
+use vulkano::descriptor::descriptor_set::PersistentDescriptorSet;
+use vulkano::descriptor::pipeline_layout::PipelineLayoutAbstract;
+use vulkano::descriptor::pipeline_layout::PipelineLayoutDesc;
+use vulkano::descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+use vulkano::descriptor::DescriptorSet;
+use vulkano::device::Device;
+use vulkano::device::DeviceExtensions;
+use vulkano::device::Features;
+use vulkano::device::Queue;
+use vulkano::instance::Instance;
+use vulkano::instance::InstanceExtensions;
+use vulkano::instance::PhysicalDevice;
+use vulkano::pipeline::shader::ShaderModule;
+use vulkano::pipeline::ComputePipeline;
+use vulkano::pipeline::ComputePipelineAbstract;
+
+// Number of dispatches recorded in each command buffer.
+const DISPATCHES: u32 = 10_000;
+// Number of command buffers recorded for each configuration.
+const ITERATIONS: u32 = 20;
+
+fn main() {
+    let (device, queue) = match device_and_queue() {
+        Some(r) => r,
+        None => {
+            println!("no Vulkan device available, skipping the benchmark");
+            return;
+        }
+    };
+
+    let pipeline = pipeline(device.clone());
+    let buffer =
+        CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0).unwrap();
+    let layout = pipeline.descriptor_set_layout(0).unwrap();
+    let set = Arc::new(
+        PersistentDescriptorSet::start(layout.clone())
+            .add_buffer(buffer)
+            .unwrap()
+            .build()
+            .unwrap(),
+    );
+
+    for &elide_binds in [true, false].iter() {
+        let mut total = Duration::new(0, 0);
+        let mut statistics = CommandBufferStatistics::default();
+
+        for _ in 0..ITERATIONS {
+            let start = Instant::now();
+            statistics = record(
+                device.clone(),
+                &queue,
+                pipeline.clone(),
+                set.clone(),
+                elide_binds,
+            );
+            total += start.elapsed();
+        }
+
+        let per_dispatch = total / (ITERATIONS * DISPATCHES);
+        println!(
+            "elide binds: {:5} - {:?} per dispatch, {} binds elided per command buffer",
+            elide_binds, per_dispatch, statistics.elided_binds
+        );
+    }
+}
+
+// Records a command buffer with `DISPATCHES` dispatches and returns its statistics.
+fn record(
+    device: Arc<Device>,
+    queue: &Arc<Queue>,
+    pipeline: Arc<dyn ComputePipelineAbstract + Send + Sync>,
+    set: Arc<dyn DescriptorSet + Send + Sync>,
+    elide_binds: bool,
+) -> CommandBufferStatistics {
+    let mut builder = AutoCommandBufferBuilder::new(device, queue.family()).unwrap();
+    builder.set_elide_binds(elide_binds);
+
+    for _ in 0..DISPATCHES {
+        builder
+            .dispatch([1, 1, 1], pipeline.clone(), set.clone(), ())
+            .unwrap();
+    }
+
+    builder.build().unwrap().statistics()
+}
+
+fn device_and_queue() -> Option<(Arc<Device>, Arc<Queue>)> {
+    let instance = Instance::new(None, &InstanceExtensions::none(), None).ok()?;
+    let physical = PhysicalDevice::enumerate(&instance).next()?;
+    let queue_family = physical.queue_families().find(|q| q.supports_compute())?;
+    let (device, mut queues) = Device::new(
+        physical,
+        &Features::none(),
+        &DeviceExtensions::none(),
+        [(queue_family, 0.5)].iter().cloned(),
+    )
+    .ok()?;
+    Some((device, queues.next().unwrap()))
+}
+
+fn pipeline(device: Arc<Device>) -> Arc<dyn ComputePipelineAbstract + Send + Sync> {
+    let module = unsafe {
+        /*
+        #version 450
+
+        layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+        layout(constant_id = 83) const int VALUE = 0xdeadbeef;
+
+        layout(set = 0, binding = 0) buffer Output {
+            int write;
+        } write;
+
+        void main() {
+            write.write = VALUE;
+        }
+        */
+        const MODULE: [u8; 480] = [
+            3, 2, 35, 7, 0, 0, 1, 0, 1, 0, 8, 0, 14, 0, 0, 0, 0, 0, 0, 0, 17, 0, 2, 0, 1, 0, 0, 0,
+            11, 0, 6, 0, 1, 0, 0, 0, 71, 76, 83, 76, 46, 115, 116, 100, 46, 52, 53, 48, 0, 0, 0, 0,
+            14, 0, 3, 0, 0, 0, 0, 0, 1, 0, 0, 0, 15, 0, 5, 0, 5, 0, 0, 0, 4, 0, 0, 0, 109, 97, 105,
+            110, 0, 0, 0, 0, 16, 0, 6, 0, 4, 0, 0, 0, 17, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0,
+            0, 3, 0, 3, 0, 2, 0, 0, 0, 194, 1, 0, 0, 5, 0, 4, 0, 4, 0, 0, 0, 109, 97, 105, 110, 0,
+            0, 0, 0, 5, 0, 4, 0, 7, 0, 0, 0, 79, 117, 116, 112, 117, 116, 0, 0, 6, 0, 5, 0, 7, 0,
+            0, 0, 0, 0, 0, 0, 119, 114, 105, 116, 101, 0, 0, 0, 5, 0, 4, 0, 9, 0, 0, 0, 119, 114,
+            105, 116, 101, 0, 0, 0, 5, 0, 4, 0, 11, 0, 0, 0, 86, 65, 76, 85, 69, 0, 0, 0, 72, 0, 5,
+            0, 7, 0, 0, 0, 0, 0, 0, 0, 35, 0, 0, 0, 0, 0, 0, 0, 71, 0, 3, 0, 7, 0, 0, 0, 3, 0, 0,
+            0, 71, 0, 4, 0, 9, 0, 0, 0, 34, 0, 0, 0, 0, 0, 0, 0, 71, 0, 4, 0, 9, 0, 0, 0, 33, 0, 0,
+            0, 0, 0, 0, 0, 71, 0, 4, 0, 11, 0, 0, 0, 1, 0, 0, 0, 83, 0, 0, 0, 19, 0, 2, 0, 2, 0, 0,
+            0, 33, 0, 3, 0, 3, 0, 0, 0, 2, 0, 0, 0, 21, 0, 4, 0, 6, 0, 0, 0, 32, 0, 0, 0, 1, 0, 0,
+            0, 30, 0, 3, 0, 7, 0, 0, 0, 6, 0, 0, 0, 32, 0, 4, 0, 8, 0, 0, 0, 2, 0, 0, 0, 7, 0, 0,
+            0, 59, 0, 4, 0, 8, 0, 0, 0, 9, 0, 0, 0, 2, 0, 0, 0, 43, 0, 4, 0, 6, 0, 0, 0, 10, 0, 0,
+            0, 0, 0, 0, 0, 50, 0, 4, 0, 6, 0, 0, 0, 11, 0, 0, 0, 239, 190, 173, 222, 32, 0, 4, 0,
+            12, 0, 0, 0, 2, 0, 0, 0, 6, 0, 0, 0, 54, 0, 5, 0, 2, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0,
+            3, 0, 0, 0, 248, 0, 2, 0, 5, 0, 0, 0, 65, 0, 5, 0, 12, 0, 0, 0, 13, 0, 0, 0, 9, 0, 0,
+            0, 10, 0, 0, 0, 62, 0, 3, 0, 13, 0, 0, 0, 11, 0, 0, 0, 253, 0, 1, 0, 56, 0, 1, 0,
+        ];
+        ShaderModule::new(device.clone(), &MODULE).unwrap()
+    };
+
+    #[derive(Debug, Copy, Clone)]
+    struct Layout;
+    unsafe impl PipelineLayoutDesc for Layout {
+        fn num_sets(&self) -> usize {
+            1
+        }
+        fn num_bindings_in_set(&self, set: usize) -> Option<usize> {
+            match set {
+                0 => Some(1),
+                _ => None,
+            }
+        }
+        fn descriptor(&self, set: usize, binding: usize) -> Option<DescriptorDesc> {
+            match (set, binding) {
+                (0, 0) => Some(DescriptorDesc {
+                    ty: DescriptorDescTy::Buffer(DescriptorBufferDesc {
+                        dynamic: Some(false),
+                        storage: true,
+                    }),
+                    array_count: 1,
+                    stages: ShaderStages {
+                        compute: true,
+                        ..ShaderStages::none()
+                    },
+                    readonly: true,
+                }),
+                _ => None,
+            }
+        }
+        fn num_push_constants_ranges(&self) -> usize {
+            0
+        }
+        fn push_constants_range(&self, _: usize) -> Option<PipelineLayoutDescPcRange> {
+            None
+        }
+    }
+
+    static NAME: [u8; 5] = [109, 97, 105, 110, 0]; // "main"
+    let shader =
+        unsafe { module.compute_entry_point(CStr::from_ptr(NAME.as_ptr() as *const _), Layout) };
+
+    Arc::new(ComputePipeline::new(device, &shader, &()).unwrap())
+}
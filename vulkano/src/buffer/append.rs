@@ -0,0 +1,209 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Storage buffer that shaders append elements to.
+//!
+//! An `AppendBuffer` is made of two device-local buffers: a counter that holds the number of
+//! elements that have been written so far, and an array of elements with a fixed capacity.
+//! Shaders typically bind them as two storage buffers and reserve a slot by incrementing the
+//! counter with `atomicAdd`:
+//!
+//! ```glsl
+//! layout(set = 0, binding = 0) buffer Counter { uint count; };
+//! layout(set = 0, binding = 1) buffer Data { Particle particles[]; };
+//!
+//! void main() {
+//!     uint index = atomicAdd(count, 1);
+//!     if (index < particles.length()) {
+//!         particles[index] = ...;
+//!     }
+//! }
+//! ```
+//!
+//! Shaders are allowed to increment the counter past the capacity of the buffer, for example if
+//! they don't check the index. The count returned by `read_count` is therefore not clamped, while
+//! `read_back` only returns the elements that fit in the buffer.
+
+use std::sync::Arc;
+
+use buffer::BufferAccess;
+use buffer::BufferUsage;
+use buffer::DeviceLocalBuffer;
+use buffer::ReadBackError;
+use command_buffer::AutoCommandBufferBuilder;
+use command_buffer::FillBufferError;
+use device::Device;
+use device::DeviceOwned;
+use device::Queue;
+use memory::DeviceMemoryAllocError;
+
+/// Device-local array of elements with an atomic counter of the number of elements written.
+///
+/// See the module-level documentation for more information.
+#[derive(Debug)]
+pub struct AppendBuffer<T> {
+    // The number of elements that have been appended. Always contains a single value.
+    counter: Arc<DeviceLocalBuffer<[u32]>>,
+
+    // The elements.
+    data: Arc<DeviceLocalBuffer<[T]>>,
+
+    // Number of elements that fit in `data`.
+    capacity: usize,
+}
+
+impl<T> AppendBuffer<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Builds a new `AppendBuffer` that can hold up to `capacity` elements.
+    ///
+    /// `usage` is the usage of the element array. The `storage_buffer`, `transfer_source` and
+    /// `transfer_destination` usages are automatically added to both the counter and the
+    /// array, so that they can be written by shaders, reset and read back. The counter can
+    /// additionally be used as an indirect buffer.
+    ///
+    /// The content of the counter is undefined until `reset` is called.
+    pub fn new(
+        device: Arc<Device>,
+        capacity: usize,
+        usage: BufferUsage,
+    ) -> Result<AppendBuffer<T>, DeviceMemoryAllocError> {
+        let required_usage = BufferUsage {
+            storage_buffer: true,
+            transfer_source: true,
+            transfer_destination: true,
+            ..BufferUsage::none()
+        };
+
+        let counter = DeviceLocalBuffer::array(
+            device.clone(),
+            1,
+            BufferUsage {
+                indirect_buffer: true,
+                ..required_usage
+            },
+            device.active_queue_families(),
+        )?;
+
+        let data = DeviceLocalBuffer::array(
+            device.clone(),
+            capacity,
+            usage | required_usage,
+            device.active_queue_families(),
+        )?;
+
+        Ok(AppendBuffer {
+            counter: counter,
+            data: data,
+            capacity: capacity,
+        })
+    }
+
+    /// Returns the buffer that holds the number of elements appended so far.
+    #[inline]
+    pub fn counter(&self) -> &Arc<DeviceLocalBuffer<[u32]>> {
+        &self.counter
+    }
+
+    /// Returns the buffer that holds the elements.
+    #[inline]
+    pub fn data(&self) -> &Arc<DeviceLocalBuffer<[T]>> {
+        &self.data
+    }
+
+    /// Returns the maximum number of elements the buffer can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds a command to `builder` that sets the counter back to zero.
+    ///
+    /// The elements themselves are left untouched.
+    #[inline]
+    pub fn reset<P>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<P>,
+    ) -> Result<(), FillBufferError> {
+        builder.fill_buffer(self.counter.clone(), 0)?;
+        Ok(())
+    }
+
+    /// Reads the value of the counter back to the host.
+    ///
+    /// This blocks until the value has been copied. See `BufferAccess::read_back`.
+    #[inline]
+    pub fn read_count(&self, queue: Arc<Queue>) -> Result<u32, ReadBackError> {
+        let count = self.counter.clone().read_back(queue)?;
+        Ok(count[0])
+    }
+
+    /// Reads the elements that have been appended so far back to the host.
+    ///
+    /// If the counter is larger than the capacity of the buffer, only the first `capacity`
+    /// elements are returned. This blocks until the elements have been copied.
+    pub fn read_back(&self, queue: Arc<Queue>) -> Result<Vec<T>, ReadBackError> {
+        let count = self.read_count(queue.clone())? as usize;
+        let len = if count < self.capacity {
+            count
+        } else {
+            self.capacity
+        };
+
+        self.data
+            .clone()
+            .into_buffer_slice()
+            .slice(0..len)
+            .unwrap()
+            .read_back(queue)
+    }
+}
+
+unsafe impl<T> DeviceOwned for AppendBuffer<T>
+where
+    T: Send + Sync + 'static,
+{
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.data.device()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use buffer::AppendBuffer;
+    use buffer::BufferUsage;
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::CommandBuffer;
+    use sync::GpuFuture;
+
+    #[test]
+    fn reset_then_read_count() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer =
+            AppendBuffer::<[f32; 4]>::new(device.clone(), 128, BufferUsage::none()).unwrap();
+        assert_eq!(buffer.capacity(), 128);
+
+        let mut cbb = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        buffer.reset(&mut cbb).unwrap();
+        cbb.build()
+            .unwrap()
+            .execute(queue.clone())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        assert_eq!(buffer.read_count(queue.clone()).unwrap(), 0);
+        assert!(buffer.read_back(queue).unwrap().is_empty());
+    }
+}
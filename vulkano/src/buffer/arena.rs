@@ -0,0 +1,619 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Arena that places many small buffers into a few large device-local buffers.
+//!
+//! Creating one buffer and one memory allocation per object (for example one per mesh) quickly
+//! hits the `maxMemoryAllocationCount` limit of the implementation and is slow. A `BufferArena`
+//! instead creates large blocks and hands out sub-ranges of them. Each `ArenaBuffer` is locked
+//! individually by the GPU and can be used like any other buffer.
+//!
+//! The offset of each sub-buffer is aligned to the requirements of the usage of the arena (for
+//! example `minStorageBufferOffsetAlignment` for storage buffers) and to the alignment of the
+//! content type.
+//!
+//! When an `ArenaBuffer` is destroyed, its range is returned to the block it comes from and can
+//! be reused by later allocations. Blocks themselves are only freed once the arena and all the
+//! buffers allocated from them are destroyed.
+
+use smallvec::SmallVec;
+use std::cmp;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Range;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use buffer::sys::BufferCreationError;
+use buffer::sys::SparseLevel;
+use buffer::sys::UnsafeBuffer;
+use buffer::traits::BufferAccess;
+use buffer::traits::BufferInner;
+use buffer::traits::TypedBufferAccess;
+use buffer::BufferUsage;
+use device::Device;
+use device::DeviceOwned;
+use device::Queue;
+use image::ImageAccess;
+use memory::pool::AllocFromRequirementsFilter;
+use memory::pool::AllocLayout;
+use memory::pool::MappingRequirement;
+use memory::pool::MemoryPool;
+use memory::pool::MemoryPoolAlloc;
+use memory::pool::PotentialDedicatedAllocation;
+use memory::pool::StdMemoryPoolAlloc;
+use memory::DedicatedAlloc;
+use memory::DeviceMemoryAllocError;
+use sync::AccessError;
+use sync::Sharing;
+
+/// Default size in bytes of the blocks allocated by a `BufferArena`.
+const DEFAULT_BLOCK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Allocator of buffers in device-local memory that share a few large allocations.
+///
+/// # Example
+///
+/// ```
+/// use vulkano::buffer::BufferArena;
+/// use vulkano::buffer::BufferUsage;
+///
+/// # let device: std::sync::Arc<vulkano::device::Device> = return;
+/// let arena = BufferArena::new(device.clone(), BufferUsage::vertex_buffer_transfer_destination());
+///
+/// // Both buffers are likely to be in the same block of memory.
+/// let positions = arena.alloc_array::<[f32; 3]>(1024).unwrap();
+/// let normals = arena.alloc_array::<[f32; 3]>(1024).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct BufferArena {
+    // The device of the arena.
+    device: Arc<Device>,
+
+    // Usage of all the blocks of the arena.
+    usage: BufferUsage,
+
+    // Minimum size of a block.
+    block_size: usize,
+
+    // Minimum alignment of the offset of each sub-buffer, derived from the usage.
+    alignment: usize,
+
+    // The blocks that have been allocated so far.
+    blocks: Mutex<Vec<Arc<ArenaBlock>>>,
+}
+
+// One large buffer of the arena.
+#[derive(Debug)]
+struct ArenaBlock {
+    inner: UnsafeBuffer,
+
+    memory: PotentialDedicatedAllocation<StdMemoryPoolAlloc>,
+
+    // List of the ranges of the block that are free, sorted by offset. Two ranges are never
+    // adjacent.
+    free: Mutex<Vec<Range<usize>>>,
+}
+
+impl BufferArena {
+    /// Builds a new arena whose buffers have the given usage.
+    #[inline]
+    pub fn new(device: Arc<Device>, usage: BufferUsage) -> BufferArena {
+        BufferArena::with_block_size(device, usage, DEFAULT_BLOCK_SIZE)
+    }
+
+    /// Builds a new arena whose buffers have the given usage, and that allocates blocks of at
+    /// least `block_size` bytes.
+    ///
+    /// Sub-buffers larger than `block_size` get a block of their own.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `block_size` is 0.
+    ///
+    pub fn with_block_size(
+        device: Arc<Device>,
+        usage: BufferUsage,
+        block_size: usize,
+    ) -> BufferArena {
+        assert!(block_size >= 1);
+
+        let alignment = {
+            let limits = device.physical_device().limits();
+            let mut alignment = 1;
+            if usage.storage_buffer {
+                alignment = cmp::max(alignment, limits.min_storage_buffer_offset_alignment());
+            }
+            if usage.uniform_buffer {
+                alignment = cmp::max(alignment, limits.min_uniform_buffer_offset_alignment());
+            }
+            if usage.uniform_texel_buffer || usage.storage_texel_buffer {
+                alignment = cmp::max(alignment, limits.min_texel_buffer_offset_alignment());
+            }
+            alignment as usize
+        };
+
+        BufferArena {
+            device: device,
+            usage: usage,
+            block_size: block_size,
+            alignment: alignment,
+            blocks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the usage of the buffers allocated by this arena.
+    #[inline]
+    pub fn usage(&self) -> &BufferUsage {
+        &self.usage
+    }
+
+    /// Returns the number of blocks that have been allocated so far.
+    #[inline]
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.lock().unwrap().len()
+    }
+
+    /// Allocates a buffer that can hold a value of type `T`.
+    ///
+    /// The content of the buffer is undefined.
+    #[inline]
+    pub fn alloc<T>(&self) -> Result<Arc<ArenaBuffer<T>>, DeviceMemoryAllocError> {
+        unsafe { self.alloc_raw(mem::size_of::<T>(), mem::align_of::<T>()) }
+    }
+
+    /// Allocates a buffer that can hold `len` values of type `T`.
+    ///
+    /// The content of the buffer is undefined.
+    #[inline]
+    pub fn alloc_array<T>(
+        &self,
+        len: usize,
+    ) -> Result<Arc<ArenaBuffer<[T]>>, DeviceMemoryAllocError> {
+        unsafe { self.alloc_raw(len * mem::size_of::<T>(), mem::align_of::<T>()) }
+    }
+
+    /// Allocates a buffer of `size` bytes whose offset is a multiple of `alignment`, in addition
+    /// to the alignment required by the usage of the arena.
+    ///
+    /// # Safety
+    ///
+    /// You must ensure that the size that you pass is correct for `T`.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `alignment` is not a power of two.
+    ///
+    pub unsafe fn alloc_raw<T: ?Sized>(
+        &self,
+        size: usize,
+        alignment: usize,
+    ) -> Result<Arc<ArenaBuffer<T>>, DeviceMemoryAllocError> {
+        assert!(alignment.is_power_of_two());
+
+        let alignment = cmp::max(alignment, self.alignment);
+        // We always reserve at least one byte so that two sub-buffers never have the same offset.
+        let reserved = align_up(cmp::max(size, 1), alignment);
+
+        let mut blocks = self.blocks.lock().unwrap();
+
+        for block in blocks.iter() {
+            let offset = alloc_range(&mut block.free.lock().unwrap(), reserved, alignment);
+            if let Some(offset) = offset {
+                return Ok(Arc::new(ArenaBuffer::new(
+                    block.clone(),
+                    offset,
+                    size,
+                    reserved,
+                )));
+            }
+        }
+
+        let block = Arc::new(self.new_block(cmp::max(reserved, self.block_size))?);
+        let offset = alloc_range(&mut block.free.lock().unwrap(), reserved, alignment)
+            .expect("a new block is always large enough");
+        blocks.push(block.clone());
+
+        Ok(Arc::new(ArenaBuffer::new(block, offset, size, reserved)))
+    }
+
+    // Allocates a new block of `size` bytes.
+    fn new_block(&self, size: usize) -> Result<ArenaBlock, DeviceMemoryAllocError> {
+        let queue_families = self
+            .device
+            .active_queue_families()
+            .map(|f| f.id())
+            .collect::<SmallVec<[u32; 4]>>();
+
+        let (buffer, mem_reqs) = unsafe {
+            let sharing = if queue_families.len() >= 2 {
+                Sharing::Concurrent(queue_families.iter().cloned())
+            } else {
+                Sharing::Exclusive
+            };
+
+            match UnsafeBuffer::new(
+                self.device.clone(),
+                size,
+                self.usage,
+                sharing,
+                SparseLevel::none(),
+            ) {
+                Ok(b) => b,
+                Err(BufferCreationError::AllocError(err)) => return Err(err),
+                Err(_) => unreachable!(), // We don't use sparse binding, therefore the other
+                                          // errors can't happen
+            }
+        };
+
+        let mem = MemoryPool::alloc_from_requirements(
+            &Device::standard_pool(&self.device),
+            &mem_reqs,
+            AllocLayout::Linear,
+            MappingRequirement::DoNotMap,
+            DedicatedAlloc::Buffer(&buffer),
+            |t| {
+                if t.is_device_local() {
+                    AllocFromRequirementsFilter::Preferred
+                } else {
+                    AllocFromRequirementsFilter::Allowed
+                }
+            },
+        )?;
+        debug_assert!((mem.offset() % mem_reqs.alignment) == 0);
+        unsafe {
+            buffer.bind_memory(mem.memory(), mem.offset())?;
+        }
+
+        Ok(ArenaBlock {
+            inner: buffer,
+            memory: mem,
+            free: Mutex::new(vec![0..size]),
+        })
+    }
+}
+
+unsafe impl DeviceOwned for BufferArena {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+/// A buffer allocated from a `BufferArena`.
+///
+/// The range of the block it uses is given back to the arena when it is destroyed.
+#[derive(Debug)]
+pub struct ArenaBuffer<T: ?Sized> {
+    // The block this buffer is part of.
+    block: Arc<ArenaBlock>,
+
+    // Offset in bytes of the buffer within the block.
+    offset: usize,
+
+    // Size in bytes requested by the user.
+    size: usize,
+
+    // Number of bytes reserved in the block, starting at `offset`.
+    reserved: usize,
+
+    // Number of times this buffer is locked on the GPU side.
+    gpu_lock: Mutex<GpuAccess>,
+
+    // True if a command that writes to this region of the block has been recorded. Tracked per
+    // buffer rather than per block, as a region may be reused after being freed.
+    initialized: AtomicBool,
+
+    // Necessary to make it compile.
+    marker: PhantomData<Box<T>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum GpuAccess {
+    None,
+    NonExclusive { num: u32 },
+    Exclusive { num: u32 },
+}
+
+impl<T: ?Sized> ArenaBuffer<T> {
+    #[inline]
+    fn new(block: Arc<ArenaBlock>, offset: usize, size: usize, reserved: usize) -> ArenaBuffer<T> {
+        ArenaBuffer {
+            block: block,
+            offset: offset,
+            size: size,
+            reserved: reserved,
+            gpu_lock: Mutex::new(GpuAccess::None),
+            initialized: AtomicBool::new(false),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the offset in bytes of this buffer within the block it is part of.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<T: ?Sized> Drop for ArenaBuffer<T> {
+    #[inline]
+    fn drop(&mut self) {
+        let mut free = self.block.free.lock().unwrap();
+        free_range(&mut free, self.offset..self.offset + self.reserved);
+    }
+}
+
+unsafe impl<T: ?Sized> DeviceOwned for ArenaBuffer<T> {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.block.inner.device()
+    }
+}
+
+unsafe impl<T: ?Sized> BufferAccess for ArenaBuffer<T>
+where
+    T: 'static + Send + Sync,
+{
+    #[inline]
+    fn inner(&self) -> BufferInner {
+        BufferInner {
+            buffer: &self.block.inner,
+            offset: self.offset,
+        }
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    #[inline]
+    fn conflicts_buffer(&self, other: &dyn BufferAccess) -> bool {
+        self.conflict_key() == other.conflict_key() // TODO:
+    }
+
+    #[inline]
+    fn conflicts_image(&self, other: &dyn ImageAccess) -> bool {
+        false
+    }
+
+    #[inline]
+    fn conflict_key(&self) -> (u64, usize) {
+        (self.block.inner.key(), self.offset)
+    }
+
+    #[inline]
+    fn try_gpu_lock(&self, exclusive: bool, _: &Queue) -> Result<(), AccessError> {
+        let mut lock = self.gpu_lock.lock().unwrap();
+        match &mut *lock {
+            a @ &mut GpuAccess::None => {
+                if exclusive {
+                    *a = GpuAccess::Exclusive { num: 1 };
+                } else {
+                    *a = GpuAccess::NonExclusive { num: 1 };
+                }
+
+                Ok(())
+            }
+            &mut GpuAccess::NonExclusive { ref mut num } => {
+                if exclusive {
+                    Err(AccessError::AlreadyInUse)
+                } else {
+                    *num += 1;
+                    Ok(())
+                }
+            }
+            &mut GpuAccess::Exclusive { .. } => Err(AccessError::AlreadyInUse),
+        }
+    }
+
+    #[inline]
+    unsafe fn increase_gpu_lock(&self) {
+        let mut lock = self.gpu_lock.lock().unwrap();
+        match *lock {
+            GpuAccess::None => panic!(),
+            GpuAccess::NonExclusive { ref mut num } => {
+                debug_assert!(*num >= 1);
+                *num += 1;
+            }
+            GpuAccess::Exclusive { ref mut num } => {
+                debug_assert!(*num >= 1);
+                *num += 1;
+            }
+        }
+    }
+
+    #[inline]
+    unsafe fn unlock(&self) {
+        let mut lock = self.gpu_lock.lock().unwrap();
+
+        match *lock {
+            GpuAccess::None => panic!("Tried to unlock a buffer that isn't locked"),
+            GpuAccess::NonExclusive { ref mut num } => {
+                assert!(*num >= 1);
+                *num -= 1;
+                if *num >= 1 {
+                    return;
+                }
+            }
+            GpuAccess::Exclusive { ref mut num } => {
+                assert!(*num >= 1);
+                *num -= 1;
+                if *num >= 1 {
+                    return;
+                }
+            }
+        };
+
+        *lock = GpuAccess::None;
+    }
+
+    #[inline]
+    fn is_initialized(&self) -> bool {
+        self.initialized.load(Ordering::SeqCst)
+    }
+
+    #[inline]
+    fn mark_initialized(&self) {
+        self.initialized.store(true, Ordering::SeqCst);
+    }
+}
+
+unsafe impl<T: ?Sized> TypedBufferAccess for ArenaBuffer<T>
+where
+    T: 'static + Send + Sync,
+{
+    type Content = T;
+}
+
+impl<T: ?Sized> PartialEq for ArenaBuffer<T>
+where
+    T: 'static + Send + Sync,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner() == other.inner() && self.size() == other.size()
+    }
+}
+
+impl<T: ?Sized> Eq for ArenaBuffer<T> where T: 'static + Send + Sync {}
+
+impl<T: ?Sized> Hash for ArenaBuffer<T>
+where
+    T: 'static + Send + Sync,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().hash(state);
+        self.size().hash(state);
+    }
+}
+
+// Rounds `value` up to a multiple of `alignment`, which must be a power of two.
+#[inline]
+fn align_up(value: usize, alignment: usize) -> usize {
+    debug_assert!(alignment.is_power_of_two());
+    (value + alignment - 1) & !(alignment - 1)
+}
+
+// Finds a free range of `size` bytes whose start is a multiple of `alignment`, removes it from
+// `free` and returns its offset.
+fn alloc_range(free: &mut Vec<Range<usize>>, size: usize, alignment: usize) -> Option<usize> {
+    let (index, offset) = free.iter().enumerate().find_map(|(index, range)| {
+        let offset = align_up(range.start, alignment);
+        if offset + size <= range.end {
+            Some((index, offset))
+        } else {
+            None
+        }
+    })?;
+
+    let range = free.remove(index);
+    if offset + size < range.end {
+        free.insert(index, offset + size..range.end);
+    }
+    if range.start < offset {
+        free.insert(index, range.start..offset);
+    }
+
+    Some(offset)
+}
+
+// Gives back `range` to the list of free ranges, merging it with its neighbours.
+fn free_range(free: &mut Vec<Range<usize>>, range: Range<usize>) {
+    let index = free
+        .iter()
+        .position(|r| r.start > range.start)
+        .unwrap_or(free.len());
+    debug_assert!(index == 0 || free[index - 1].end <= range.start);
+    debug_assert!(index == free.len() || range.end <= free[index].start);
+
+    let merge_prev = index >= 1 && free[index - 1].end == range.start;
+    let merge_next = index < free.len() && free[index].start == range.end;
+
+    match (merge_prev, merge_next) {
+        (true, true) => {
+            let next = free.remove(index);
+            free[index - 1].end = next.end;
+        }
+        (true, false) => free[index - 1].end = range.end,
+        (false, true) => free[index].start = range.start,
+        (false, false) => free.insert(index, range),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::alloc_range;
+    use super::free_range;
+    use buffer::BufferAccess;
+    use buffer::BufferArena;
+    use buffer::BufferUsage;
+
+    #[test]
+    fn free_list_alignment() {
+        let mut free = vec![0..256];
+        assert_eq!(alloc_range(&mut free, 4, 1), Some(0));
+        assert_eq!(alloc_range(&mut free, 16, 64), Some(64));
+        assert_eq!(free, vec![4..64, 80..256]);
+        assert_eq!(alloc_range(&mut free, 60, 4), Some(4));
+        assert_eq!(alloc_range(&mut free, 512, 4), None);
+    }
+
+    #[test]
+    fn free_list_merge() {
+        let mut free = vec![0..256];
+        let a = alloc_range(&mut free, 64, 1).unwrap();
+        let b = alloc_range(&mut free, 64, 1).unwrap();
+        let c = alloc_range(&mut free, 64, 1).unwrap();
+        assert_eq!(free, vec![192..256]);
+
+        free_range(&mut free, a..a + 64);
+        free_range(&mut free, c..c + 64);
+        assert_eq!(free, vec![0..64, 128..256]);
+        free_range(&mut free, b..b + 64);
+        assert_eq!(free, vec![0..256]);
+    }
+
+    #[test]
+    fn sub_buffers_share_a_block() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let arena = BufferArena::with_block_size(device.clone(), BufferUsage::all(), 4096);
+        let alignment = device
+            .physical_device()
+            .limits()
+            .min_storage_buffer_offset_alignment() as usize;
+
+        let a = arena.alloc_array::<u32>(10).unwrap();
+        let b = arena.alloc::<[f32; 4]>().unwrap();
+        assert_eq!(arena.num_blocks(), 1);
+        assert_eq!(a.size(), 40);
+        assert_eq!(b.size(), 16);
+        assert_eq!(b.offset() % alignment, 0);
+        assert_ne!(a.conflict_key(), b.conflict_key());
+        assert_eq!(a.inner().buffer.key(), b.inner().buffer.key());
+
+        let big = arena.alloc_array::<u8>(8192).unwrap();
+        assert_eq!(arena.num_blocks(), 2);
+        assert_eq!(big.offset(), 0);
+
+        // The space of a freed buffer is reused.
+        let offset = a.offset();
+        drop(a);
+        let c = arena.alloc_array::<u32>(10).unwrap();
+        assert_eq!(c.offset(), offset);
+        assert_eq!(arena.num_blocks(), 2);
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Types whose values can be uploaded to a buffer.
+//!
+//! The constructors of the buffers that take some data from the CPU (for example
+//! `CpuAccessibleBuffer::from_data` or `CpuBufferPool::next`) require the type of the data to
+//! implement the `BufferContents` trait.
+//!
+//! By default this trait is implemented for all types. When the `strict-buffer-contents` feature
+//! of vulkano is enabled, it is only implemented for the primitive numeric types and for arrays
+//! and slices of them. Your own types must then implement it with the `impl_buffer_contents!`
+//! macro, which checks that the type doesn't contain any padding. This prevents you from
+//! accidentally uploading references, pointers or structs whose layout doesn't match the one
+//! expected by your shaders.
+
+/// Trait for types that can be copied to a buffer as raw bytes.
+///
+/// See the module-level documentation. Use the `impl_buffer_contents!` macro to implement this
+/// trait for your own types.
+///
+/// # Safety
+///
+/// - The type must not contain any padding bytes.
+/// - The type must not contain any reference, pointer or other type whose value is only
+///   meaningful in the memory of the CPU.
+/// - Any bit pattern must be a valid value of the type, since the GPU can write arbitrary data
+///   in a buffer.
+///
+pub unsafe trait BufferContents {}
+
+#[cfg(not(feature = "strict-buffer-contents"))]
+unsafe impl<T: ?Sized> BufferContents for T {}
+
+#[cfg(feature = "strict-buffer-contents")]
+mod strict {
+    use super::BufferContents;
+    use half::f16;
+
+    macro_rules! impl_primitive {
+        ($($ty:ty),*) => {
+            $(
+                unsafe impl BufferContents for $ty {}
+            )*
+        };
+    }
+
+    unsafe impl BufferContents for () {}
+
+    impl_primitive!(u8, u16, u32, u64, i8, i16, i32, i64, f16, f32, f64);
+
+    macro_rules! impl_array {
+        ($($sz:expr),*) => {
+            $(
+                unsafe impl<T> BufferContents for [T; $sz] where T: BufferContents {}
+            )*
+        };
+    }
+
+    impl_array!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 32, 64, 128, 256, 512, 1024);
+
+    unsafe impl<T> BufferContents for [T] where T: BufferContents {}
+}
+
+/// Implements the `BufferContents` trait for a struct.
+///
+/// You must pass the name of the struct, followed by the list of all its members and their
+/// types. The macro fails to compile if the struct contains padding between or after its members,
+/// or if one of the members doesn't implement `BufferContents` itself.
+///
+/// Note that the macro can't check that the struct is `#[repr(C)]`. Without this attribute, the
+/// compiler is free to reorder the members and the layout of the struct will probably not match
+/// the one expected by your shaders.
+///
+/// When the `strict-buffer-contents` feature is disabled, the trait is already implemented for
+/// all types and this macro only performs the checks.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate vulkano;
+/// # fn main() {}
+/// #[repr(C)]
+/// #[derive(Copy, Clone)]
+/// struct Particle {
+///     position: [f32; 3],
+///     lifetime: f32,
+///     velocity: [f32; 4],
+/// }
+///
+/// impl_buffer_contents!(Particle {
+///     position: [f32; 3],
+///     lifetime: f32,
+///     velocity: [f32; 4],
+/// });
+/// ```
+#[macro_export]
+macro_rules! impl_buffer_contents {
+    ($out:ty { $($member:ident: $member_ty:ty),* $(,)* }) => (
+        const _: () = {
+            // Checks the types of the members.
+            #[allow(dead_code)]
+            fn check_members(value: &$out) {
+                fn is_contents<T: ?Sized + $crate::buffer::BufferContents>(_: &T) {}
+                $(
+                    let member: &$member_ty = &value.$member;
+                    is_contents(member);
+                )*
+            }
+
+            // Fails to compile if the size of the struct is larger than the size of its members,
+            // which means that some members are missing or that the struct contains padding.
+            let _: [(); 0] = [(); ::std::mem::size_of::<$out>()
+                - (0 $(+ ::std::mem::size_of::<$member_ty>())*)];
+        };
+
+        $crate::__vulkano_impl_buffer_contents!($out);
+    )
+}
+
+#[cfg(feature = "strict-buffer-contents")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __vulkano_impl_buffer_contents {
+    ($out:ty) => {
+        unsafe impl $crate::buffer::BufferContents for $out {}
+    };
+}
+
+#[cfg(not(feature = "strict-buffer-contents"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __vulkano_impl_buffer_contents {
+    ($out:ty) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use buffer::BufferContents;
+
+    #[repr(C)]
+    struct Particle {
+        position: [f32; 3],
+        lifetime: f32,
+        velocity: [f32; 4],
+    }
+
+    impl_buffer_contents!(Particle {
+        position: [f32; 3],
+        lifetime: f32,
+        velocity: [f32; 4],
+    });
+
+    #[test]
+    fn implemented_for_arrays_and_structs() {
+        fn is_contents<T: ?Sized + BufferContents>() {}
+
+        is_contents::<u32>();
+        is_contents::<[f32; 4]>();
+        is_contents::<[[f32; 4]; 4]>();
+        is_contents::<[u8]>();
+        is_contents::<Particle>();
+        is_contents::<[Particle]>();
+    }
+}
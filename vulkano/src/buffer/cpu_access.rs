@@ -27,12 +27,14 @@ use std::mem;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::ptr;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::RwLock;
 use std::sync::RwLockReadGuard;
 use std::sync::RwLockWriteGuard;
+use std::time::Duration;
 
 use buffer::sys::BufferCreationError;
 use buffer::sys::SparseLevel;
@@ -40,11 +42,13 @@ use buffer::sys::UnsafeBuffer;
 use buffer::traits::BufferAccess;
 use buffer::traits::BufferInner;
 use buffer::traits::TypedBufferAccess;
+use buffer::BufferContents;
 use buffer::BufferUsage;
 use device::Device;
 use device::DeviceOwned;
 use device::Queue;
 use image::ImageAccess;
+use instance::MemoryType;
 use instance::QueueFamily;
 use memory::pool::AllocFromRequirementsFilter;
 use memory::pool::AllocLayout;
@@ -58,6 +62,9 @@ use memory::CpuAccess as MemCpuAccess;
 use memory::DedicatedAlloc;
 use memory::DeviceMemoryAllocError;
 use sync::AccessError;
+use sync::FenceSignalFuture;
+use sync::FlushError;
+use sync::GpuFuture;
 use sync::Sharing;
 
 /// Buffer whose content is accessible by the CPU.
@@ -66,6 +73,12 @@ use sync::Sharing;
 /// the `CpuAccessibleBuffer` prefers to allocate from host_cached memory. Host cached
 /// memory caches GPU data on the CPU side. This can be more performant in cases where
 /// the cpu needs to read data coming off the GPU.
+///
+/// Setting it to `false` makes the buffer prefer memory that is host-coherent but not cached,
+/// which is usually write-combined and is the best choice for uploading data to the GPU.
+///
+/// In both cases, if no memory type matches the preference, any host-visible memory type is used
+/// instead. Use `memory_type()` to find out which one was chosen.
 #[derive(Debug)]
 pub struct CpuAccessibleBuffer<T: ?Sized, A = PotentialDedicatedAllocation<StdMemoryPoolAlloc>> {
     // Inner content.
@@ -83,6 +96,9 @@ pub struct CpuAccessibleBuffer<T: ?Sized, A = PotentialDedicatedAllocation<StdMe
     // Queue families allowed to access this buffer.
     queue_families: SmallVec<[u32; 4]>,
 
+    // True if the content of the buffer has been written by the CPU or by a command.
+    initialized: AtomicBool,
+
     // Necessary to make it compile.
     marker: PhantomData<Box<T>>,
 }
@@ -108,7 +124,7 @@ impl<T> CpuAccessibleBuffer<T> {
         data: T,
     ) -> Result<Arc<CpuAccessibleBuffer<T>>, DeviceMemoryAllocError>
     where
-        T: Content + 'static,
+        T: Content + BufferContents + 'static,
     {
         unsafe {
             let uninitialized = CpuAccessibleBuffer::raw(
@@ -160,7 +176,7 @@ impl<T> CpuAccessibleBuffer<[T]> {
     ) -> Result<Arc<CpuAccessibleBuffer<[T]>>, DeviceMemoryAllocError>
     where
         I: ExactSizeIterator<Item = T>,
-        T: Content + 'static,
+        T: Content + BufferContents + 'static,
     {
         unsafe {
             let uninitialized =
@@ -243,21 +259,7 @@ impl<T: ?Sized> CpuAccessibleBuffer<T> {
             AllocLayout::Linear,
             MappingRequirement::Map,
             DedicatedAlloc::Buffer(&buffer),
-            |m| {
-                if m.is_host_cached() {
-                    if host_cached {
-                        AllocFromRequirementsFilter::Preferred
-                    } else {
-                        AllocFromRequirementsFilter::Allowed
-                    }
-                } else {
-                    if host_cached {
-                        AllocFromRequirementsFilter::Allowed
-                    } else {
-                        AllocFromRequirementsFilter::Preferred
-                    }
-                }
-            },
+            |m| host_memory_filter(m, host_cached),
         )?;
         debug_assert!((mem.offset() % mem_reqs.alignment) == 0);
         debug_assert!(mem.mapped_memory().is_some());
@@ -270,6 +272,7 @@ impl<T: ?Sized> CpuAccessibleBuffer<T> {
                 num: AtomicUsize::new(0),
             }),
             queue_families: queue_families,
+            initialized: AtomicBool::new(false),
             marker: PhantomData,
         }))
     }
@@ -292,6 +295,20 @@ impl<T: ?Sized, A> CpuAccessibleBuffer<T, A> {
     }
 }
 
+impl<T: ?Sized, A> CpuAccessibleBuffer<T, A>
+where
+    A: MemoryPoolAlloc,
+{
+    /// Returns the memory type the buffer was allocated from.
+    ///
+    /// This can be used to check whether the `host_cached` preference passed when creating the
+    /// buffer could be honored.
+    #[inline]
+    pub fn memory_type(&self) -> MemoryType {
+        self.memory.memory().memory_type()
+    }
+}
+
 impl<T: ?Sized, A> CpuAccessibleBuffer<T, A>
 where
     T: Content + 'static,
@@ -329,6 +346,33 @@ where
         })
     }
 
+    /// Waits until `future` is signaled, then locks the buffer in order to read its content from
+    /// the CPU.
+    ///
+    /// `future` is typically the future of the last submission that writes to the buffer. Once it
+    /// is signaled, the resources it uses are unlocked, and the content written by the GPU is
+    /// visible by the CPU. If `timeout` is `None` the wait is infinite, otherwise a
+    /// `FlushError::Timeout` is returned after the timeout has elapsed.
+    ///
+    /// Other than waiting, this function behaves the same as `read()`.
+    ///
+    /// > **Note**: The buffer doesn't keep track of the fence of the last submission that uses
+    /// > it, because that fence only exists once the submission has been flushed. While the GPU
+    /// > may still be using the buffer, `read()` returns `GpuWriteLocked` instead of blocking,
+    /// > and it is up to the caller to pass the future it holds to this function to block.
+    #[inline]
+    pub fn read_after<F>(
+        &self,
+        future: &FenceSignalFuture<F>,
+        timeout: Option<Duration>,
+    ) -> Result<ReadLock<T>, ReadLockError>
+    where
+        F: GpuFuture,
+    {
+        future.wait(timeout).map_err(ReadLockError::WaitError)?;
+        self.read()
+    }
+
     /// Locks the buffer in order to write its content from the CPU.
     ///
     /// If the buffer is currently in use by the GPU, this function will return an error. Similarly
@@ -355,12 +399,38 @@ where
 
         let offset = self.memory.offset();
         let range = offset..offset + self.inner.size();
+        self.initialized.store(true, Ordering::SeqCst);
 
         Ok(WriteLock {
             inner: unsafe { self.memory.mapped_memory().unwrap().read_write(range) },
             lock: lock,
         })
     }
+
+    /// Waits until `future` is signaled, then locks the buffer in order to write its content from
+    /// the CPU.
+    ///
+    /// `future` is typically the future of the last submission that uses the buffer. If `timeout`
+    /// is `None` the wait is infinite, otherwise a `FlushError::Timeout` is returned after the
+    /// timeout has elapsed.
+    ///
+    /// Other than waiting, this function behaves the same as `write()`.
+    ///
+    /// > **Note**: As with `read_after`, the buffer doesn't keep track of its last submission
+    /// > itself. While the GPU may still be using the buffer, `write()` returns `GpuLocked`
+    /// > instead of blocking.
+    #[inline]
+    pub fn write_after<F>(
+        &self,
+        future: &FenceSignalFuture<F>,
+        timeout: Option<Duration>,
+    ) -> Result<WriteLock<T>, WriteLockError>
+    where
+        F: GpuFuture,
+    {
+        future.wait(timeout).map_err(WriteLockError::WaitError)?;
+        self.write()
+    }
 }
 
 unsafe impl<T: ?Sized, A> BufferAccess for CpuAccessibleBuffer<T, A>
@@ -487,6 +557,16 @@ where
             };
         }
     }
+
+    #[inline]
+    fn is_initialized(&self) -> bool {
+        self.initialized.load(Ordering::SeqCst)
+    }
+
+    #[inline]
+    fn mark_initialized(&self) {
+        self.initialized.store(true, Ordering::SeqCst);
+    }
 }
 
 unsafe impl<T: ?Sized, A> TypedBufferAccess for CpuAccessibleBuffer<T, A>
@@ -528,8 +608,8 @@ where
 
 /// Object that can be used to read or write the content of a `CpuAccessibleBuffer`.
 ///
-/// Note that this object holds a rwlock read guard on the chunk. If another thread tries to access
-/// this buffer's content or tries to submit a GPU command that uses this buffer, it will block.
+/// Note that this object holds a rwlock read guard on the chunk. While it is alive, any attempt
+/// to write the buffer's content or to submit a GPU command that writes this buffer will fail.
 pub struct ReadLock<'a, T: ?Sized + 'a> {
     inner: MemCpuAccess<'a, T>,
     lock: RwLockReadGuard<'a, CurrentGpuAccess>,
@@ -558,6 +638,25 @@ impl<'a, T: ?Sized + 'a> Deref for ReadLock<'a, T> {
     }
 }
 
+// Memory type filter for buffers that are accessed by the CPU.
+//
+// Host-cached memory is preferred when the CPU reads the content written by the GPU, while
+// host-coherent uncached memory is preferred when the CPU only writes data for the GPU.
+#[inline]
+pub(crate) fn host_memory_filter(ty: MemoryType, host_cached: bool) -> AllocFromRequirementsFilter {
+    let preferred = if host_cached {
+        ty.is_host_cached()
+    } else {
+        ty.is_host_coherent() && !ty.is_host_cached()
+    };
+
+    if preferred {
+        AllocFromRequirementsFilter::Preferred
+    } else {
+        AllocFromRequirementsFilter::Allowed
+    }
+}
+
 /// Error when attempting to CPU-read a buffer.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ReadLockError {
@@ -565,9 +664,19 @@ pub enum ReadLockError {
     CpuWriteLocked,
     /// The buffer is already locked for write mode by the GPU.
     GpuWriteLocked,
+    /// An error happened while waiting for the future of `read_after`.
+    WaitError(FlushError),
 }
 
-impl error::Error for ReadLockError {}
+impl error::Error for ReadLockError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            ReadLockError::WaitError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl fmt::Display for ReadLockError {
     #[inline]
@@ -582,6 +691,7 @@ impl fmt::Display for ReadLockError {
                 ReadLockError::GpuWriteLocked => {
                     "the buffer is already locked for write mode by the GPU"
                 }
+                ReadLockError::WaitError(_) => "an error happened while waiting for the future",
             }
         )
     }
@@ -589,8 +699,10 @@ impl fmt::Display for ReadLockError {
 
 /// Object that can be used to read or write the content of a `CpuAccessibleBuffer`.
 ///
-/// Note that this object holds a rwlock write guard on the chunk. If another thread tries to access
-/// this buffer's content or tries to submit a GPU command that uses this buffer, it will block.
+/// Note that this object holds a rwlock write guard on the chunk. While it is alive, any attempt
+/// to access the buffer's content or to submit a GPU command that uses this buffer will fail.
+///
+/// If the memory is not host-coherent, the written range is flushed when the lock is dropped.
 pub struct WriteLock<'a, T: ?Sized + 'a> {
     inner: MemCpuAccess<'a, T>,
     lock: RwLockWriteGuard<'a, CurrentGpuAccess>,
@@ -633,9 +745,19 @@ pub enum WriteLockError {
     CpuLocked,
     /// The buffer is already locked by the GPU.
     GpuLocked,
+    /// An error happened while waiting for the future of `write_after`.
+    WaitError(FlushError),
 }
 
-impl error::Error for WriteLockError {}
+impl error::Error for WriteLockError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            WriteLockError::WaitError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl fmt::Display for WriteLockError {
     #[inline]
@@ -646,6 +768,7 @@ impl fmt::Display for WriteLockError {
             match *self {
                 WriteLockError::CpuLocked => "the buffer is already locked by the CPU",
                 WriteLockError::GpuLocked => "the buffer is already locked by the GPU",
+                WriteLockError::WaitError(_) => "an error happened while waiting for the future",
             }
         )
     }
@@ -653,8 +776,12 @@ impl fmt::Display for WriteLockError {
 
 #[cfg(test)]
 mod tests {
+    use buffer::cpu_access::ReadLockError;
+    use buffer::cpu_access::WriteLockError;
     use buffer::{BufferUsage, CpuAccessibleBuffer};
 
+    // Uses an iterator as the content of the buffer, which `strict-buffer-contents` rejects.
+    #[cfg(not(feature = "strict-buffer-contents"))]
     #[test]
     fn create_empty_buffer() {
         let (device, queue) = gfx_dev_and_queue!();
@@ -663,4 +790,76 @@ mod tests {
 
         let _ = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), false, EMPTY.iter());
     }
+
+    #[test]
+    fn memory_type_is_host_visible() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        for &host_cached in &[false, true] {
+            let buffer = CpuAccessibleBuffer::from_data(
+                device.clone(),
+                BufferUsage::all(),
+                host_cached,
+                0u32,
+            )
+            .unwrap();
+            assert!(buffer.memory_type().is_host_visible());
+        }
+    }
+
+    #[test]
+    fn read_back_array() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer =
+            CpuAccessibleBuffer::from_iter(device, BufferUsage::all(), false, 0..64u32).unwrap();
+
+        {
+            let mut content = buffer.write().unwrap();
+            content[3] = 100;
+        }
+
+        let content = buffer.read().unwrap();
+        assert_eq!(content.len(), 64);
+        assert_eq!(content[2], 2);
+        assert_eq!(content[3], 100);
+    }
+
+    #[test]
+    fn locks_are_exclusive() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer =
+            CpuAccessibleBuffer::from_data(device, BufferUsage::all(), false, 12u32).unwrap();
+
+        {
+            let _read = buffer.read().unwrap();
+            let _other_read = buffer.read().unwrap();
+            assert_eq!(buffer.write().err(), Some(WriteLockError::CpuLocked));
+        }
+
+        {
+            let _write = buffer.write().unwrap();
+            assert_eq!(buffer.read().err(), Some(ReadLockError::CpuWriteLocked));
+            assert_eq!(buffer.write().err(), Some(WriteLockError::CpuLocked));
+        }
+    }
+
+    #[test]
+    fn lock_after_future() {
+        use sync;
+        use sync::GpuFuture;
+
+        let (device, _) = gfx_dev_and_queue!();
+
+        let buffer =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0u32)
+                .unwrap();
+
+        let future = sync::now(device.clone())
+            .then_signal_fence_and_flush()
+            .unwrap();
+        *buffer.write_after(&future, None).unwrap() = 5;
+        assert_eq!(*buffer.read_after(&future, None).unwrap(), 5);
+    }
 }
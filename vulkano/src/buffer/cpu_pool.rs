@@ -13,6 +13,7 @@ use std::hash::Hasher;
 use std::iter;
 use std::marker::PhantomData;
 use std::mem;
+use std::ops::Deref;
 use std::ptr;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
@@ -20,24 +21,27 @@ use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::MutexGuard;
 
+use buffer::cpu_access::host_memory_filter;
+use buffer::cpu_access::ReadLockError;
 use buffer::sys::BufferCreationError;
 use buffer::sys::SparseLevel;
 use buffer::sys::UnsafeBuffer;
 use buffer::traits::BufferAccess;
 use buffer::traits::BufferInner;
 use buffer::traits::TypedBufferAccess;
+use buffer::BufferContents;
 use buffer::BufferUsage;
 use device::Device;
 use device::DeviceOwned;
 use device::Queue;
 use image::ImageAccess;
-use memory::pool::AllocFromRequirementsFilter;
 use memory::pool::AllocLayout;
 use memory::pool::MappingRequirement;
 use memory::pool::MemoryPool;
 use memory::pool::MemoryPoolAlloc;
 use memory::pool::PotentialDedicatedAllocation;
 use memory::pool::StdMemoryPool;
+use memory::CpuAccess as MemCpuAccess;
 use memory::DedicatedAlloc;
 use memory::DeviceMemoryAllocError;
 use sync::AccessError;
@@ -45,10 +49,6 @@ use sync::Sharing;
 
 use OomError;
 
-// TODO: Add `CpuBufferPoolSubbuffer::read` to read the content of a subbuffer.
-//       But that's hard to do because we must prevent `increase_gpu_lock` from working while a
-//       a buffer is locked.
-
 /// Ring buffer from which "sub-buffers" can be individually allocated.
 ///
 /// This buffer is especially suitable when you want to upload or download some data regularly
@@ -117,6 +117,9 @@ where
     // Buffer usage.
     usage: BufferUsage,
 
+    // Whether to prefer host-cached memory.
+    host_cached: bool,
+
     // Necessary to make it compile.
     marker: PhantomData<Box<T>>,
 }
@@ -157,6 +160,14 @@ struct ActualBufferChunk {
     // Number of `CpuBufferPoolSubbuffer` objects that point to this subbuffer and that have been
     // GPU-locked.
     num_gpu_accesses: usize,
+
+    // True if the GPU lock was acquired for exclusive access. Meaningless if `num_gpu_accesses`
+    // is 0.
+    gpu_exclusive: bool,
+
+    // Number of `ReadLock` objects currently alive for this subbuffer. The GPU can't be granted
+    // exclusive access while this is non-zero.
+    num_cpu_reads: usize,
 }
 
 /// A subbuffer allocated from a `CpuBufferPool`.
@@ -196,8 +207,25 @@ where
 
 impl<T> CpuBufferPool<T> {
     /// Builds a `CpuBufferPool`.
+    ///
+    /// The pool prefers host-coherent uncached memory, which is best suited for uploading data
+    /// to the GPU.
     #[inline]
     pub fn new(device: Arc<Device>, usage: BufferUsage) -> CpuBufferPool<T> {
+        CpuBufferPool::with_host_cached(device, usage, false)
+    }
+
+    /// Builds a `CpuBufferPool`, specifying whether it should prefer host-cached memory.
+    ///
+    /// Host-cached memory is faster to read from the CPU and is therefore the best choice for
+    /// pools whose content is written by the GPU and read back by the CPU. See also the
+    /// documentation of `CpuAccessibleBuffer`.
+    #[inline]
+    pub fn with_host_cached(
+        device: Arc<Device>,
+        usage: BufferUsage,
+        host_cached: bool,
+    ) -> CpuBufferPool<T> {
         let pool = Device::standard_pool(&device);
 
         CpuBufferPool {
@@ -205,6 +233,7 @@ impl<T> CpuBufferPool<T> {
             pool: pool,
             current_buffer: Mutex::new(None),
             usage: usage.clone(),
+            host_cached: host_cached,
             marker: PhantomData,
         }
     }
@@ -221,10 +250,10 @@ impl<T> CpuBufferPool<T> {
     /// Builds a `CpuBufferPool` meant for simple downloads.
     ///
     /// Shortcut for a pool that can only be used as transfer destination and with exclusive queue
-    /// family accesses.
+    /// family accesses. The pool prefers host-cached memory.
     #[inline]
     pub fn download(device: Arc<Device>) -> CpuBufferPool<T> {
-        CpuBufferPool::new(device, BufferUsage::transfer_destination())
+        CpuBufferPool::with_host_cached(device, BufferUsage::transfer_destination(), true)
     }
 
     /// Builds a `CpuBufferPool` meant for usage as a uniform buffer.
@@ -293,7 +322,10 @@ where
     /// > **Note**: You can think of it like a `Vec`. If you insert an element and the `Vec` is not
     /// > large enough, a new chunk of memory is automatically allocated.
     #[inline]
-    pub fn next(&self, data: T) -> Result<CpuBufferPoolSubbuffer<T, A>, DeviceMemoryAllocError> {
+    pub fn next(&self, data: T) -> Result<CpuBufferPoolSubbuffer<T, A>, DeviceMemoryAllocError>
+    where
+        T: BufferContents,
+    {
         Ok(CpuBufferPoolSubbuffer {
             chunk: self.chunk(iter::once(data))?,
         })
@@ -315,6 +347,7 @@ where
     where
         I: IntoIterator<Item = T>,
         I::IntoIter: ExactSizeIterator,
+        T: BufferContents,
     {
         let data = data.into_iter();
 
@@ -345,7 +378,10 @@ where
     /// A `CpuBufferPool` is always empty the first time you use it, so you shouldn't use
     /// `try_next` the first time you use it.
     #[inline]
-    pub fn try_next(&self, data: T) -> Option<CpuBufferPoolSubbuffer<T, A>> {
+    pub fn try_next(&self, data: T) -> Option<CpuBufferPoolSubbuffer<T, A>>
+    where
+        T: BufferContents,
+    {
         let mut mutex = self.current_buffer.lock().unwrap();
         self.try_next_impl(&mut mutex, iter::once(data))
             .map(|c| CpuBufferPoolSubbuffer { chunk: c })
@@ -391,7 +427,7 @@ where
                 AllocLayout::Linear,
                 MappingRequirement::Map,
                 DedicatedAlloc::Buffer(&buffer),
-                |_| AllocFromRequirementsFilter::Allowed,
+                |m| host_memory_filter(m, self.host_cached),
             )?;
             debug_assert!((mem.offset() % mem_reqs.alignment) == 0);
             debug_assert!(mem.mapped_memory().is_some());
@@ -549,6 +585,8 @@ where
             len: occupied_len,
             num_cpu_accesses: 1,
             num_gpu_accesses: 0,
+            gpu_exclusive: false,
+            num_cpu_reads: 0,
         });
 
         Ok(CpuBufferPoolChunk {
@@ -575,6 +613,7 @@ where
             pool: self.pool.clone(),
             current_buffer: Mutex::new(buf.clone()),
             usage: self.usage.clone(),
+            host_cached: self.host_cached,
             marker: PhantomData,
         }
     }
@@ -658,7 +697,7 @@ where
     }
 
     #[inline]
-    fn try_gpu_lock(&self, _: bool, _: &Queue) -> Result<(), AccessError> {
+    fn try_gpu_lock(&self, exclusive_access: bool, _: &Queue) -> Result<(), AccessError> {
         if self.requested_len == 0 {
             return Ok(());
         }
@@ -673,7 +712,12 @@ where
             return Err(AccessError::AlreadyInUse);
         }
 
+        if exclusive_access && chunk.num_cpu_reads != 0 {
+            return Err(AccessError::AlreadyInUse);
+        }
+
         chunk.num_gpu_accesses = 1;
+        chunk.gpu_exclusive = exclusive_access;
         Ok(())
     }
 
@@ -713,6 +757,61 @@ where
     }
 }
 
+impl<T, A> CpuBufferPoolChunk<T, A>
+where
+    A: MemoryPool,
+{
+    /// Locks the subbuffer in order to read its content from the CPU.
+    ///
+    /// If the subbuffer is currently used in exclusive mode by the GPU, this function will return
+    /// an error. While the returned lock is alive, any attempt to submit a command buffer that
+    /// uses the subbuffer in exclusive mode will fail.
+    ///
+    /// This is typically used with pools created with `CpuBufferPool::download`, once the GPU
+    /// has finished writing to the subbuffer.
+    #[inline]
+    pub fn read(&self) -> Result<ReadLock<[T]>, ReadLockError> {
+        Ok(ReadLock {
+            inner: self.read_impl()?,
+            chunks_in_use: &self.buffer.chunks_in_use,
+            index: self.index,
+            requested_len: self.requested_len,
+        })
+    }
+
+    // Marks the subbuffer as being read by the CPU and returns an access to its content. The
+    // caller is responsible for building a `ReadLock` that undoes that when dropped.
+    fn read_impl(&self) -> Result<MemCpuAccess<[T]>, ReadLockError> {
+        let mut chunks_in_use_lock = self.buffer.chunks_in_use.lock().unwrap();
+
+        if self.requested_len != 0 {
+            let chunk = chunks_in_use_lock
+                .iter_mut()
+                .find(|c| c.index == self.index)
+                .unwrap();
+
+            if chunk.num_gpu_accesses != 0 && chunk.gpu_exclusive {
+                return Err(ReadLockError::GpuWriteLocked);
+            }
+
+            chunk.num_cpu_reads += 1;
+        }
+
+        let mem_off = self.buffer.memory.offset();
+        let range_start = self.index * mem::size_of::<T>() + self.align_offset + mem_off;
+        let range_end = range_start + self.requested_len * mem::size_of::<T>();
+
+        unsafe {
+            Ok(self
+                .buffer
+                .memory
+                .mapped_memory()
+                .unwrap()
+                .read_write::<[T]>(range_start..range_end))
+        }
+    }
+}
+
 impl<T, A> Drop for CpuBufferPoolChunk<T, A>
 where
     A: MemoryPool,
@@ -778,6 +877,24 @@ where
     }
 }
 
+impl<T, A> CpuBufferPoolSubbuffer<T, A>
+where
+    A: MemoryPool,
+{
+    /// Locks the subbuffer in order to read its content from the CPU.
+    ///
+    /// See `CpuBufferPoolChunk::read` for more information.
+    #[inline]
+    pub fn read(&self) -> Result<ReadLock<T>, ReadLockError> {
+        Ok(ReadLock {
+            inner: self.chunk.read_impl()?.map(|ptr| ptr as *mut T),
+            chunks_in_use: &self.chunk.buffer.chunks_in_use,
+            index: self.chunk.index,
+            requested_len: self.chunk.requested_len,
+        })
+    }
+}
+
 impl<T, A> Clone for CpuBufferPoolSubbuffer<T, A>
 where
     A: MemoryPool,
@@ -874,8 +991,46 @@ where
     }
 }
 
+/// Object that can be used to read the content of a subbuffer of a `CpuBufferPool`.
+///
+/// While this object is alive, the GPU can't be granted exclusive access to the subbuffer.
+pub struct ReadLock<'a, T: ?Sized + 'a> {
+    inner: MemCpuAccess<'a, T>,
+    chunks_in_use: &'a Mutex<Vec<ActualBufferChunk>>,
+    index: usize,
+    requested_len: usize,
+}
+
+impl<'a, T: ?Sized + 'a> Deref for ReadLock<'a, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.inner.deref()
+    }
+}
+
+impl<'a, T: ?Sized + 'a> Drop for ReadLock<'a, T> {
+    fn drop(&mut self) {
+        // If `requested_len` is 0, then no entry was added in the chunks.
+        if self.requested_len == 0 {
+            return;
+        }
+
+        let mut chunks_in_use_lock = self.chunks_in_use.lock().unwrap();
+        let chunk = chunks_in_use_lock
+            .iter_mut()
+            .find(|c| c.index == self.index)
+            .unwrap();
+
+        debug_assert!(chunk.num_cpu_reads >= 1);
+        chunk.num_cpu_reads -= 1;
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use buffer::BufferAccess;
     use buffer::CpuBufferPool;
     use std::mem;
 
@@ -961,4 +1116,35 @@ mod tests {
         let _ = pool.chunk(vec![]).unwrap();
         let _ = pool.chunk(vec![0, 0]).unwrap();
     }
+
+    #[test]
+    fn read_subbuffer() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let pool = CpuBufferPool::<u32>::download(device);
+
+        let a = pool.next(12).unwrap();
+        let b = pool.chunk(vec![1, 2, 3]).unwrap();
+        assert_eq!(*a.read().unwrap(), 12);
+        assert_eq!(&*b.read().unwrap(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn read_prevents_exclusive_gpu_lock() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = CpuBufferPool::<u32>::download(device);
+        let sub = pool.next(12).unwrap();
+
+        {
+            let _read = sub.read().unwrap();
+            assert!(sub.try_gpu_lock(true, &queue).is_err());
+        }
+
+        sub.try_gpu_lock(true, &queue).unwrap();
+        assert!(sub.read().is_err());
+        unsafe {
+            sub.unlock();
+        }
+    }
 }
@@ -14,10 +14,16 @@
 //! write simultaneously, or write and write simultaneously will block with a semaphore.
 
 use smallvec::SmallVec;
+use std::error;
+use std::fmt;
+#[cfg(unix)]
+use std::fs::File;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::marker::PhantomData;
 use std::mem;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -27,11 +33,21 @@ use buffer::sys::UnsafeBuffer;
 use buffer::traits::BufferAccess;
 use buffer::traits::BufferInner;
 use buffer::traits::TypedBufferAccess;
+use buffer::BufferContents;
 use buffer::BufferUsage;
+use buffer::CpuAccessibleBuffer;
+use command_buffer::AutoCommandBuffer;
+use command_buffer::AutoCommandBufferBuilder;
+use command_buffer::BuildError;
+use command_buffer::CommandBuffer;
+use command_buffer::CommandBufferExecError;
+use command_buffer::CommandBufferExecFuture;
+use command_buffer::CopyBufferError;
 use device::Device;
 use device::DeviceOwned;
 use device::Queue;
 use image::ImageAccess;
+use instance::MemoryType;
 use instance::QueueFamily;
 use memory::pool::AllocFromRequirementsFilter;
 use memory::pool::AllocLayout;
@@ -41,9 +57,14 @@ use memory::pool::MemoryPoolAlloc;
 use memory::pool::PotentialDedicatedAllocation;
 use memory::pool::StdMemoryPoolAlloc;
 use memory::DedicatedAlloc;
+use memory::DeviceMemory;
 use memory::DeviceMemoryAllocError;
+use memory::ExternalMemoryHandleType;
+use memory::MemoryRequirements;
 use sync::AccessError;
+use sync::NowFuture;
 use sync::Sharing;
+use OomError;
 
 /// Buffer whose content is in device-local memory.
 ///
@@ -67,6 +88,9 @@ pub struct DeviceLocalBuffer<T: ?Sized, A = PotentialDedicatedAllocation<StdMemo
     // Number of times this buffer is locked on the GPU side.
     gpu_lock: Mutex<GpuAccess>,
 
+    // True if a command that writes to the buffer has been recorded.
+    initialized: AtomicBool,
+
     // Necessary to make it compile.
     marker: PhantomData<Box<T>>,
 }
@@ -78,6 +102,98 @@ enum GpuAccess {
     Exclusive { num: u32 },
 }
 
+// TODO: make this prettier
+type DeviceLocalBufferFromBufferFuture = CommandBufferExecFuture<NowFuture, AutoCommandBuffer>;
+
+impl<T: ?Sized> DeviceLocalBuffer<T> {
+    /// Builds a `DeviceLocalBuffer` from some data.
+    ///
+    /// This function builds a memory-mapped intermediate buffer, writes the data to it, builds a
+    /// command buffer that copies from this intermediate buffer to the final buffer, and finally
+    /// submits the command buffer as a future.
+    ///
+    /// This function returns two objects: the newly-created buffer, and a future representing
+    /// the initial upload operation. The buffer stays locked by the GPU until the upload is
+    /// finished, therefore you must either submit your operation after this future, or execute
+    /// this future and wait for it to be finished before submitting your own operation.
+    pub fn from_data(
+        data: T,
+        usage: BufferUsage,
+        queue: Arc<Queue>,
+    ) -> Result<
+        (Arc<DeviceLocalBuffer<T>>, DeviceLocalBufferFromBufferFuture),
+        DeviceLocalBufferCreationError,
+    >
+    where
+        T: BufferContents + 'static + Send + Sync + Sized,
+    {
+        let source = CpuAccessibleBuffer::from_data(
+            queue.device().clone(),
+            BufferUsage::transfer_source(),
+            false,
+            data,
+        )?;
+        DeviceLocalBuffer::from_buffer(source, usage, queue)
+    }
+
+    /// Builds a `DeviceLocalBuffer` that copies its data from another buffer.
+    ///
+    /// This function returns two objects: the newly-created buffer, and a future representing
+    /// the initial upload operation. The buffer stays locked by the GPU until the upload is
+    /// finished, therefore you must either submit your operation after this future, or execute
+    /// this future and wait for it to be finished before submitting your own operation.
+    pub fn from_buffer<B>(
+        source: B,
+        usage: BufferUsage,
+        queue: Arc<Queue>,
+    ) -> Result<
+        (Arc<DeviceLocalBuffer<T>>, DeviceLocalBufferFromBufferFuture),
+        DeviceLocalBufferCreationError,
+    >
+    where
+        B: BufferAccess + TypedBufferAccess<Content = T> + 'static + Clone + Send + Sync,
+        T: 'static + Send + Sync,
+    {
+        let mut cbb = AutoCommandBufferBuilder::new(source.device().clone(), queue.family())?;
+        let buffer = DeviceLocalBuffer::from_buffer_with_builder(source, usage, &mut cbb)?;
+        let future = cbb.build()?.execute(queue)?;
+        Ok((buffer, future))
+    }
+
+    /// Builds a `DeviceLocalBuffer` that copies its data from another buffer, and records the
+    /// copy in `builder` instead of submitting a command buffer of its own.
+    ///
+    /// This makes it possible to upload several buffers with a single submission. The buffer must
+    /// not be used before the command buffer built from `builder` has been submitted.
+    pub fn from_buffer_with_builder<B, P>(
+        source: B,
+        usage: BufferUsage,
+        builder: &mut AutoCommandBufferBuilder<P>,
+    ) -> Result<Arc<DeviceLocalBuffer<T>>, DeviceLocalBufferCreationError>
+    where
+        B: BufferAccess + TypedBufferAccess<Content = T> + 'static + Clone + Send + Sync,
+        T: 'static + Send + Sync,
+    {
+        unsafe {
+            // We automatically set `transfer_destination` to true in order to avoid annoying errors.
+            let actual_usage = BufferUsage {
+                transfer_destination: true,
+                ..usage
+            };
+
+            let buffer = DeviceLocalBuffer::raw(
+                source.device().clone(),
+                source.size(),
+                actual_usage,
+                source.device().active_queue_families(),
+            )?;
+
+            builder.copy_buffer(source, buffer.clone())?;
+            Ok(buffer)
+        }
+    }
+}
+
 impl<T> DeviceLocalBuffer<T> {
     /// Builds a new buffer. Only allowed for sized data.
     // TODO: unsafe because uninitialized data
@@ -109,6 +225,34 @@ impl<T> DeviceLocalBuffer<[T]> {
     {
         unsafe { DeviceLocalBuffer::raw(device, len * mem::size_of::<T>(), usage, queue_families) }
     }
+
+    /// Builds a `DeviceLocalBuffer` that contains an array `T`. The initial data comes from an
+    /// iterator that produces that list of Ts.
+    ///
+    /// See `from_data` for more information about the returned future.
+    pub fn from_iter<D>(
+        data: D,
+        usage: BufferUsage,
+        queue: Arc<Queue>,
+    ) -> Result<
+        (
+            Arc<DeviceLocalBuffer<[T]>>,
+            DeviceLocalBufferFromBufferFuture,
+        ),
+        DeviceLocalBufferCreationError,
+    >
+    where
+        D: ExactSizeIterator<Item = T>,
+        T: BufferContents + 'static + Send + Sync + Sized,
+    {
+        let source = CpuAccessibleBuffer::from_iter(
+            queue.device().clone(),
+            BufferUsage::transfer_source(),
+            false,
+            data,
+        )?;
+        DeviceLocalBuffer::from_buffer(source, usage, queue)
+    }
 }
 
 impl<T: ?Sized> DeviceLocalBuffer<T> {
@@ -169,9 +313,200 @@ impl<T: ?Sized> DeviceLocalBuffer<T> {
             memory: mem,
             queue_families: queue_families,
             gpu_lock: Mutex::new(GpuAccess::None),
+            initialized: AtomicBool::new(false),
+            marker: PhantomData,
+        }))
+    }
+
+    /// Same as `raw`, but the memory of the buffer is a dedicated allocation that can be
+    /// exported with `export_fd` as one of `handle_types`.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the extensions required by `handle_types` are not enabled on the device.
+    ///
+    /// # Safety
+    ///
+    /// You must ensure that the size that you pass is correct for `T`.
+    ///
+    pub unsafe fn raw_with_exportable_memory<'a, I>(
+        device: Arc<Device>,
+        size: usize,
+        usage: BufferUsage,
+        queue_families: I,
+        handle_types: ExternalMemoryHandleType,
+    ) -> Result<Arc<DeviceLocalBuffer<T>>, DeviceMemoryAllocError>
+    where
+        I: IntoIterator<Item = QueueFamily<'a>>,
+    {
+        let (buffer, mem_reqs, queue_families) = DeviceLocalBuffer::<T>::external_buffer(
+            &device,
+            size,
+            usage,
+            queue_families,
+            handle_types,
+        )?;
+
+        let mem_ty =
+            DeviceLocalBuffer::<T>::external_memory_type(&device, mem_reqs.memory_type_bits)
+                .expect("no memory type is compatible with the buffer");
+        let mem = DeviceMemory::dedicated_alloc_exportable(
+            device.clone(),
+            mem_ty,
+            mem_reqs.size,
+            DedicatedAlloc::Buffer(&buffer),
+            handle_types,
+        )?;
+        buffer.bind_memory(&mem, 0)?;
+
+        Ok(Arc::new(DeviceLocalBuffer {
+            inner: buffer,
+            memory: PotentialDedicatedAllocation::Dedicated(mem),
+            queue_families: queue_families,
+            gpu_lock: Mutex::new(GpuAccess::None),
+            initialized: AtomicBool::new(false),
             marker: PhantomData,
         }))
     }
+
+    /// Builds a buffer whose memory is imported from a POSIX file descriptor, for example a
+    /// dma-buf exported by a video decoder.
+    ///
+    /// The content of the imported memory is considered initialized. The memory type is picked
+    /// among the ones returned by `DeviceMemory::fd_memory_type_bits` that are compatible with the
+    /// buffer, preferring device-local memory. Returns `InvalidExternalHandle` if there is none.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one file descriptor handle type.
+    /// - Panics if the extensions required by `handle_type` are not enabled on the device.
+    ///
+    /// # Safety
+    ///
+    /// - You must ensure that the size that you pass is correct for `T`.
+    /// - `fd` must be a valid handle to memory of type `handle_type` that is large enough for
+    ///   the buffer. See `DeviceMemory::import_fd`.
+    /// - For the `opaque_fd` handle type, the memory must have been exported with the memory
+    ///   type that is picked, which is the case if it was exported by `raw_with_exportable_memory`
+    ///   with the same parameters on the same physical device.
+    ///
+    #[cfg(unix)]
+    pub unsafe fn raw_from_fd<'a, I>(
+        device: Arc<Device>,
+        size: usize,
+        usage: BufferUsage,
+        queue_families: I,
+        handle_type: ExternalMemoryHandleType,
+        fd: File,
+    ) -> Result<Arc<DeviceLocalBuffer<T>>, DeviceMemoryAllocError>
+    where
+        I: IntoIterator<Item = QueueFamily<'a>>,
+    {
+        let (buffer, mem_reqs, queue_families) = DeviceLocalBuffer::<T>::external_buffer(
+            &device,
+            size,
+            usage,
+            queue_families,
+            handle_type,
+        )?;
+
+        let fd_memory_type_bits = DeviceMemory::fd_memory_type_bits(&device, handle_type, &fd)?;
+        let mem_ty = DeviceLocalBuffer::<T>::external_memory_type(
+            &device,
+            mem_reqs.memory_type_bits & fd_memory_type_bits,
+        )
+        .ok_or(DeviceMemoryAllocError::InvalidExternalHandle)?;
+        let mem = DeviceMemory::import_fd(
+            device.clone(),
+            mem_ty,
+            mem_reqs.size,
+            DedicatedAlloc::Buffer(&buffer),
+            handle_type,
+            fd,
+        )?;
+        buffer.bind_memory(&mem, 0)?;
+
+        Ok(Arc::new(DeviceLocalBuffer {
+            inner: buffer,
+            memory: PotentialDedicatedAllocation::Dedicated(mem),
+            queue_families: queue_families,
+            gpu_lock: Mutex::new(GpuAccess::None),
+            initialized: AtomicBool::new(true),
+            marker: PhantomData,
+        }))
+    }
+
+    // Creates the buffer of `raw_with_exportable_memory` and `raw_from_fd`.
+    unsafe fn external_buffer<'a, I>(
+        device: &Arc<Device>,
+        size: usize,
+        usage: BufferUsage,
+        queue_families: I,
+        handle_types: ExternalMemoryHandleType,
+    ) -> Result<(UnsafeBuffer, MemoryRequirements, SmallVec<[u32; 4]>), DeviceMemoryAllocError>
+    where
+        I: IntoIterator<Item = QueueFamily<'a>>,
+    {
+        let queue_families = queue_families
+            .into_iter()
+            .map(|f| f.id())
+            .collect::<SmallVec<[u32; 4]>>();
+
+        let sharing = if queue_families.len() >= 2 {
+            Sharing::Concurrent(queue_families.iter().cloned())
+        } else {
+            Sharing::Exclusive
+        };
+
+        match UnsafeBuffer::new_with_external_memory(
+            device.clone(),
+            size,
+            usage,
+            sharing,
+            SparseLevel::none(),
+            handle_types,
+        ) {
+            Ok((buffer, mem_reqs)) => Ok((buffer, mem_reqs, queue_families)),
+            Err(BufferCreationError::AllocError(err)) => Err(err),
+            Err(_) => unreachable!(), // We don't use sparse binding, therefore the other
+                                      // errors can't happen
+        }
+    }
+
+    // Picks the memory type of an external allocation among `memory_type_bits`, preferring
+    // device-local memory. Returns `None` if no memory type is allowed.
+    fn external_memory_type(device: &Arc<Device>, memory_type_bits: u32) -> Option<MemoryType> {
+        let mut types = device
+            .physical_device()
+            .memory_types()
+            .filter(|t| (memory_type_bits & (1 << t.id())) != 0);
+        let first = types.next()?;
+        if first.is_device_local() {
+            return Some(first);
+        }
+        Some(types.find(|t| t.is_device_local()).unwrap_or(first))
+    }
+}
+
+impl<T: ?Sized> DeviceLocalBuffer<T> {
+    /// Exports the memory of the buffer as a POSIX file descriptor.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the buffer wasn't created with `raw_with_exportable_memory` with
+    ///   `handle_type`.
+    /// - Panics if `handle_type` doesn't contain exactly one file descriptor handle type.
+    ///
+    #[cfg(unix)]
+    pub fn export_fd(
+        &self,
+        handle_type: ExternalMemoryHandleType,
+    ) -> Result<File, DeviceMemoryAllocError> {
+        match self.memory {
+            PotentialDedicatedAllocation::Dedicated(ref mem) => mem.export_fd(handle_type),
+            _ => panic!("the memory of the buffer is not exportable"),
+        }
+    }
 }
 
 impl<T: ?Sized, A> DeviceLocalBuffer<T, A> {
@@ -295,6 +630,16 @@ where
 
         *lock = GpuAccess::None;
     }
+
+    #[inline]
+    fn is_initialized(&self) -> bool {
+        self.initialized.load(Ordering::SeqCst)
+    }
+
+    #[inline]
+    fn mark_initialized(&self) {
+        self.initialized.store(true, Ordering::SeqCst);
+    }
 }
 
 unsafe impl<T: ?Sized, A> TypedBufferAccess for DeviceLocalBuffer<T, A>
@@ -326,3 +671,186 @@ where
         self.size().hash(state);
     }
 }
+
+/// Error that can happen when creating a `DeviceLocalBuffer` with initial data.
+#[derive(Debug, Clone)]
+pub enum DeviceLocalBufferCreationError {
+    /// Not enough memory to allocate the buffer or the staging buffer.
+    AllocError(DeviceMemoryAllocError),
+    /// Not enough memory to create the command buffer.
+    OomError(OomError),
+    /// The copy command is invalid.
+    CopyBufferError(CopyBufferError),
+    /// Error while building the command buffer.
+    BuildError(BuildError),
+    /// Error while submitting the command buffer.
+    CommandBufferExecError(CommandBufferExecError),
+}
+
+impl error::Error for DeviceLocalBufferCreationError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            DeviceLocalBufferCreationError::AllocError(ref err) => Some(err),
+            DeviceLocalBufferCreationError::OomError(ref err) => Some(err),
+            DeviceLocalBufferCreationError::CopyBufferError(ref err) => Some(err),
+            DeviceLocalBufferCreationError::BuildError(ref err) => Some(err),
+            DeviceLocalBufferCreationError::CommandBufferExecError(ref err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for DeviceLocalBufferCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                DeviceLocalBufferCreationError::AllocError(_) => "failed to allocate the buffer",
+                DeviceLocalBufferCreationError::OomError(_) => "not enough memory available",
+                DeviceLocalBufferCreationError::CopyBufferError(_) => {
+                    "the copy command is invalid"
+                }
+                DeviceLocalBufferCreationError::BuildError(_) => {
+                    "error while building the command buffer"
+                }
+                DeviceLocalBufferCreationError::CommandBufferExecError(_) => {
+                    "error while submitting the command buffer"
+                }
+            }
+        )
+    }
+}
+
+impl From<DeviceMemoryAllocError> for DeviceLocalBufferCreationError {
+    #[inline]
+    fn from(err: DeviceMemoryAllocError) -> DeviceLocalBufferCreationError {
+        DeviceLocalBufferCreationError::AllocError(err)
+    }
+}
+
+impl From<OomError> for DeviceLocalBufferCreationError {
+    #[inline]
+    fn from(err: OomError) -> DeviceLocalBufferCreationError {
+        DeviceLocalBufferCreationError::OomError(err)
+    }
+}
+
+impl From<CopyBufferError> for DeviceLocalBufferCreationError {
+    #[inline]
+    fn from(err: CopyBufferError) -> DeviceLocalBufferCreationError {
+        DeviceLocalBufferCreationError::CopyBufferError(err)
+    }
+}
+
+impl From<BuildError> for DeviceLocalBufferCreationError {
+    #[inline]
+    fn from(err: BuildError) -> DeviceLocalBufferCreationError {
+        DeviceLocalBufferCreationError::BuildError(err)
+    }
+}
+
+impl From<CommandBufferExecError> for DeviceLocalBufferCreationError {
+    #[inline]
+    fn from(err: CommandBufferExecError) -> DeviceLocalBufferCreationError {
+        DeviceLocalBufferCreationError::CommandBufferExecError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use buffer::cpu_access::CpuAccessibleBuffer;
+    use buffer::device_local::DeviceLocalBuffer;
+    use buffer::BufferUsage;
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::CommandBuffer;
+    use sync::GpuFuture;
+
+    #[test]
+    fn from_data_working() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let (buffer, upload) =
+            DeviceLocalBuffer::from_data(12u32, BufferUsage::all(), queue.clone()).unwrap();
+
+        let destination =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0).unwrap();
+
+        let mut cbb = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        cbb.copy_buffer(buffer, destination.clone()).unwrap();
+        upload
+            .then_execute(queue.clone(), cbb.build().unwrap())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        let destination_content = destination.read().unwrap();
+        assert_eq!(*destination_content, 12);
+    }
+
+    #[test]
+    fn from_iter_working() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let (buffer, upload) = DeviceLocalBuffer::from_iter(
+            (0..512u32).map(|n| n * 2),
+            BufferUsage::all(),
+            queue.clone(),
+        )
+        .unwrap();
+
+        let destination = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage::all(),
+            false,
+            (0..512).map(|_| 0u32),
+        )
+        .unwrap();
+
+        let mut cbb = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        cbb.copy_buffer(buffer, destination.clone()).unwrap();
+        upload
+            .then_execute(queue.clone(), cbb.build().unwrap())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        let destination_content = destination.read().unwrap();
+        for (n, &v) in destination_content.iter().enumerate() {
+            assert_eq!(n * 2, v as usize);
+        }
+    }
+
+    #[test]
+    fn from_buffer_with_builder_working() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let source =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 7u32)
+                .unwrap();
+        let destination =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0).unwrap();
+
+        let mut cbb = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        let buffer =
+            DeviceLocalBuffer::from_buffer_with_builder(source, BufferUsage::all(), &mut cbb)
+                .unwrap();
+        cbb.copy_buffer(buffer, destination.clone()).unwrap();
+        cbb.build()
+            .unwrap()
+            .execute(queue.clone())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        let destination_content = destination.read().unwrap();
+        assert_eq!(*destination_content, 7);
+    }
+}
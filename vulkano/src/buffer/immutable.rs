@@ -33,6 +33,7 @@ use buffer::sys::UnsafeBuffer;
 use buffer::traits::BufferAccess;
 use buffer::traits::BufferInner;
 use buffer::traits::TypedBufferAccess;
+use buffer::BufferContents;
 use buffer::BufferUsage;
 use buffer::CpuAccessibleBuffer;
 use command_buffer::AutoCommandBuffer;
@@ -58,7 +59,7 @@ use sync::NowFuture;
 use sync::Sharing;
 
 /// Buffer that is written once then read for as long as it is alive.
-// TODO: implement Debug
+#[derive(Debug)]
 pub struct ImmutableBuffer<T: ?Sized, A = PotentialDedicatedAllocation<StdMemoryPoolAlloc>> {
     // Inner content.
     inner: UnsafeBuffer,
@@ -97,7 +98,7 @@ impl<T: ?Sized> ImmutableBuffer<T> {
         queue: Arc<Queue>,
     ) -> Result<(Arc<ImmutableBuffer<T>>, ImmutableBufferFromBufferFuture), DeviceMemoryAllocError>
     where
-        T: 'static + Send + Sync + Sized,
+        T: BufferContents + 'static + Send + Sync + Sized,
     {
         let source = CpuAccessibleBuffer::from_data(
             queue.device().clone(),
@@ -184,6 +185,10 @@ impl<T> ImmutableBuffer<T> {
 }
 
 impl<T> ImmutableBuffer<[T]> {
+    /// Builds an `ImmutableBuffer` that contains an array `T`. The initial data comes from an
+    /// iterator that produces that list of Ts.
+    ///
+    /// See `from_data` for more information about the returned future.
     pub fn from_iter<D>(
         data: D,
         usage: BufferUsage,
@@ -191,7 +196,7 @@ impl<T> ImmutableBuffer<[T]> {
     ) -> Result<(Arc<ImmutableBuffer<[T]>>, ImmutableBufferFromBufferFuture), DeviceMemoryAllocError>
     where
         D: ExactSizeIterator<Item = T>,
-        T: 'static + Send + Sync + Sized,
+        T: BufferContents + 'static + Send + Sync + Sized,
     {
         let source = CpuAccessibleBuffer::from_iter(
             queue.device().clone(),
@@ -428,7 +433,7 @@ impl<T: ?Sized, A> Hash for ImmutableBuffer<T, A> {
 }
 
 /// Access to the immutable buffer that can be used for the initial upload.
-//#[derive(Debug)]      // TODO:
+#[derive(Debug)]
 pub struct ImmutableBufferInitialization<
     T: ?Sized,
     A = PotentialDedicatedAllocation<StdMemoryPoolAlloc>,
@@ -469,10 +474,12 @@ unsafe impl<T: ?Sized, A> BufferAccess for ImmutableBufferInitialization<T, A> {
             return Err(AccessError::AlreadyInUse);
         }
 
-        if !self.used.compare_and_swap(false, true, Ordering::Relaxed) {
-            Ok(())
-        } else {
-            Err(AccessError::AlreadyInUse)
+        match self
+            .used
+            .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
+        {
+            Ok(_) => Ok(()),
+            Err(_) => Err(AccessError::AlreadyInUse),
         }
     }
 
@@ -529,6 +536,7 @@ impl<T: ?Sized, A> Hash for ImmutableBufferInitialization<T, A> {
 mod tests {
     use buffer::cpu_access::CpuAccessibleBuffer;
     use buffer::immutable::ImmutableBuffer;
+    use buffer::BufferAccess;
     use buffer::BufferUsage;
     use command_buffer::AutoCommandBufferBuilder;
     use command_buffer::CommandBuffer;
@@ -693,6 +701,27 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn initialization_access_usable_once() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let (buffer, init) = unsafe {
+            ImmutableBuffer::<u32>::uninitialized(device.clone(), BufferUsage::all()).unwrap()
+        };
+
+        assert!(buffer.try_gpu_lock(false, &queue).is_err());
+        assert!(init.try_gpu_lock(true, &queue).is_ok());
+        assert!(init.clone().try_gpu_lock(true, &queue).is_err());
+
+        unsafe {
+            init.unlock();
+        }
+
+        assert!(buffer.try_gpu_lock(false, &queue).is_ok());
+        assert!(buffer.try_gpu_lock(true, &queue).is_err());
+        assert!(init.try_gpu_lock(true, &queue).is_err());
+    }
+
     #[test]
     fn create_buffer_zero_size_data() {
         let (device, queue) = gfx_dev_and_queue!();
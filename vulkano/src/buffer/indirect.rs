@@ -0,0 +1,171 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Buffer of indirect commands.
+//!
+//! An `IndirectBuffer` wraps a buffer containing an array of `DrawIndirectCommand`,
+//! `DrawIndexedIndirectCommand` or `DispatchIndirectCommand`, and checks when it is created that
+//! the buffer can be used as the source of indirect commands. It can then be passed to the
+//! indirect draw and dispatch commands of `AutoCommandBufferBuilder`, which read the number of
+//! commands from its length.
+
+use std::sync::Arc;
+
+use buffer::BufferAccess;
+use buffer::BufferInner;
+use buffer::TypedBufferAccess;
+use command_buffer::validity::check_indirect_buffer;
+use command_buffer::validity::CheckIndirectBufferError;
+use device::Device;
+use device::DeviceOwned;
+use device::Queue;
+use image::ImageAccess;
+use sync::AccessError;
+
+/// Array of indirect commands of type `T`, in a buffer that has the indirect buffer usage.
+///
+/// See the module-level documentation for more information.
+pub struct IndirectBuffer<T> {
+    buffer: Arc<dyn TypedBufferAccess<Content = [T]> + Send + Sync>,
+}
+
+impl<T> IndirectBuffer<T> {
+    /// Wraps `buffer`, which must have the indirect buffer usage.
+    pub fn new<B>(buffer: B) -> Result<IndirectBuffer<T>, CheckIndirectBufferError>
+    where
+        B: TypedBufferAccess<Content = [T]> + Send + Sync + 'static,
+    {
+        check_indirect_buffer(buffer.device(), &buffer)?;
+
+        Ok(IndirectBuffer {
+            buffer: Arc::new(buffer),
+        })
+    }
+
+    /// Returns the wrapped buffer.
+    #[inline]
+    pub fn buffer(&self) -> &Arc<dyn TypedBufferAccess<Content = [T]> + Send + Sync> {
+        &self.buffer
+    }
+}
+
+impl<T> Clone for IndirectBuffer<T> {
+    #[inline]
+    fn clone(&self) -> IndirectBuffer<T> {
+        IndirectBuffer {
+            buffer: self.buffer.clone(),
+        }
+    }
+}
+
+unsafe impl<T> BufferAccess for IndirectBuffer<T> {
+    #[inline]
+    fn inner(&self) -> BufferInner {
+        self.buffer.inner()
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        self.buffer.size()
+    }
+
+    #[inline]
+    fn conflicts_buffer(&self, other: &dyn BufferAccess) -> bool {
+        self.buffer.conflicts_buffer(other)
+    }
+
+    #[inline]
+    fn conflicts_image(&self, other: &dyn ImageAccess) -> bool {
+        self.buffer.conflicts_image(other)
+    }
+
+    #[inline]
+    fn conflict_key(&self) -> (u64, usize) {
+        self.buffer.conflict_key()
+    }
+
+    #[inline]
+    fn try_gpu_lock(&self, exclusive_access: bool, queue: &Queue) -> Result<(), AccessError> {
+        self.buffer.try_gpu_lock(exclusive_access, queue)
+    }
+
+    #[inline]
+    unsafe fn increase_gpu_lock(&self) {
+        self.buffer.increase_gpu_lock()
+    }
+
+    #[inline]
+    unsafe fn unlock(&self) {
+        self.buffer.unlock()
+    }
+
+    #[inline]
+    fn is_initialized(&self) -> bool {
+        self.buffer.is_initialized()
+    }
+
+    #[inline]
+    fn mark_initialized(&self) {
+        self.buffer.mark_initialized()
+    }
+}
+
+unsafe impl<T> TypedBufferAccess for IndirectBuffer<T> {
+    type Content = [T];
+}
+
+unsafe impl<T> DeviceOwned for IndirectBuffer<T> {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.buffer.device()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use buffer::IndirectBuffer;
+    use buffer::TypedBufferAccess;
+    use command_buffer::validity::CheckIndirectBufferError;
+    use command_buffer::DrawIndirectCommand;
+
+    #[test]
+    fn requires_indirect_usage() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let command = || DrawIndirectCommand {
+            vertex_count: 3,
+            instance_count: 1,
+            first_vertex: 0,
+            first_instance: 0,
+        };
+
+        let buffer = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage::vertex_buffer(),
+            false,
+            (0..2).map(|_| command()),
+        )
+        .unwrap();
+        match IndirectBuffer::new(buffer) {
+            Err(CheckIndirectBufferError::BufferMissingUsage) => (),
+            _ => panic!(),
+        }
+
+        let buffer = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage::indirect_buffer(),
+            false,
+            (0..2).map(|_| command()),
+        )
+        .unwrap();
+        assert_eq!(IndirectBuffer::new(buffer).unwrap().len(), 2);
+    }
+}
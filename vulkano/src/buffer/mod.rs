@@ -35,6 +35,12 @@
 //!   transfer data between the CPU and the GPU at a high rate.
 //! - A [`CpuAccessibleBuffer`](cpu_access/struct.CpuAccessibleBuffer.html) is a simple buffer that
 //!   can be used to prototype. It may be removed from vulkano in the far future.
+//! - A [`BufferArena`](arena/struct.BufferArena.html) places many small device-local buffers in a
+//!   few large allocations, which avoids hitting the limit on the number of memory allocations.
+//! - An [`AppendBuffer`](append/struct.AppendBuffer.html) is a device-local array with an atomic
+//!   counter, meant to be filled by shaders.
+//! - An [`IndirectBuffer`](indirect/struct.IndirectBuffer.html) wraps a buffer of indirect draw or
+//!   dispatch commands and checks that it has the indirect buffer usage.
 //!
 //! Here is a quick way to choose which buffer to use. Do you often need to read or write
 //! the content of the buffer? If so, use a `CpuBufferPool`. Otherwise, do you need to be able to
@@ -77,10 +83,17 @@
 //! for how to create a buffer view.
 //!
 
+pub use self::append::AppendBuffer;
+pub use self::arena::ArenaBuffer;
+pub use self::arena::BufferArena;
+pub use self::contents::BufferContents;
 pub use self::cpu_access::CpuAccessibleBuffer;
 pub use self::cpu_pool::CpuBufferPool;
 pub use self::device_local::DeviceLocalBuffer;
+pub use self::device_local::DeviceLocalBufferCreationError;
 pub use self::immutable::ImmutableBuffer;
+pub use self::indirect::IndirectBuffer;
+pub use self::readback::ReadBackError;
 pub use self::slice::BufferSlice;
 pub use self::sys::BufferCreationError;
 pub use self::traits::BufferAccess;
@@ -90,13 +103,19 @@ pub use self::usage::BufferUsage;
 pub use self::view::BufferView;
 pub use self::view::BufferViewRef;
 
+pub mod append;
+pub mod arena;
+#[macro_use]
+pub mod contents;
 pub mod cpu_access;
 pub mod cpu_pool;
 pub mod device_local;
 pub mod immutable;
+pub mod indirect;
 pub mod sys;
 pub mod view;
 
+mod readback;
 mod slice;
 mod traits;
 mod usage;
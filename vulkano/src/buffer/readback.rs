@@ -0,0 +1,197 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Copying the content of a buffer back to the host.
+//!
+//! See the `read_back` method of `BufferAccess`.
+
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use buffer::cpu_access::ReadLockError;
+use buffer::BufferUsage;
+use buffer::CpuAccessibleBuffer;
+use buffer::TypedBufferAccess;
+use command_buffer::AutoCommandBufferBuilder;
+use command_buffer::BuildError;
+use command_buffer::CommandBuffer;
+use command_buffer::CommandBufferExecError;
+use command_buffer::CopyBufferError;
+use device::Queue;
+use memory::DeviceMemoryAllocError;
+use sync::FlushError;
+use sync::GpuFuture;
+use OomError;
+
+/// Copies the content of `buffer` to a host-visible staging buffer, waits for the copy to finish
+/// and returns the elements.
+pub(crate) fn read_back<B, T>(buffer: B, queue: Arc<Queue>) -> Result<Vec<T>, ReadBackError>
+where
+    B: TypedBufferAccess<Content = [T]> + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    let len = buffer.len();
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+
+    let staging = unsafe {
+        CpuAccessibleBuffer::<[T]>::uninitialized_array(
+            buffer.device().clone(),
+            len,
+            BufferUsage::transfer_destination(),
+            true,
+        )?
+    };
+
+    let mut cbb = AutoCommandBufferBuilder::new(buffer.device().clone(), queue.family())?;
+    cbb.copy_buffer(buffer, staging.clone())?;
+    let cb = cbb.build()?;
+
+    let future = cb.execute(queue)?.then_signal_fence_and_flush()?;
+    future.wait(None)?;
+    // Dropping the future releases the GPU lock on the staging buffer.
+    drop(future);
+
+    let content = staging.read()?;
+    Ok(content.to_vec())
+}
+
+/// Error that can happen when reading back the content of a buffer.
+#[derive(Debug, Clone)]
+pub enum ReadBackError {
+    /// Not enough memory to allocate the staging buffer.
+    AllocError(DeviceMemoryAllocError),
+    /// Not enough memory to create the command buffer.
+    OomError(OomError),
+    /// The copy command is invalid.
+    CopyBufferError(CopyBufferError),
+    /// Error while building the command buffer.
+    BuildError(BuildError),
+    /// Error while submitting the command buffer.
+    CommandBufferExecError(CommandBufferExecError),
+    /// Error while flushing or waiting for the submission.
+    FlushError(FlushError),
+    /// The staging buffer couldn't be locked for reading.
+    ReadLockError(ReadLockError),
+}
+
+impl error::Error for ReadBackError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            ReadBackError::AllocError(ref err) => Some(err),
+            ReadBackError::OomError(ref err) => Some(err),
+            ReadBackError::CopyBufferError(ref err) => Some(err),
+            ReadBackError::BuildError(ref err) => Some(err),
+            ReadBackError::CommandBufferExecError(ref err) => Some(err),
+            ReadBackError::FlushError(ref err) => Some(err),
+            ReadBackError::ReadLockError(ref err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for ReadBackError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                ReadBackError::AllocError(_) => "failed to allocate the staging buffer",
+                ReadBackError::OomError(_) => "not enough memory available",
+                ReadBackError::CopyBufferError(_) => "the copy command is invalid",
+                ReadBackError::BuildError(_) => "error while building the command buffer",
+                ReadBackError::CommandBufferExecError(_) => {
+                    "error while submitting the command buffer"
+                }
+                ReadBackError::FlushError(_) => "error while waiting for the copy to finish",
+                ReadBackError::ReadLockError(_) => "the staging buffer couldn't be read",
+            }
+        )
+    }
+}
+
+impl From<DeviceMemoryAllocError> for ReadBackError {
+    #[inline]
+    fn from(err: DeviceMemoryAllocError) -> ReadBackError {
+        ReadBackError::AllocError(err)
+    }
+}
+
+impl From<OomError> for ReadBackError {
+    #[inline]
+    fn from(err: OomError) -> ReadBackError {
+        ReadBackError::OomError(err)
+    }
+}
+
+impl From<CopyBufferError> for ReadBackError {
+    #[inline]
+    fn from(err: CopyBufferError) -> ReadBackError {
+        ReadBackError::CopyBufferError(err)
+    }
+}
+
+impl From<BuildError> for ReadBackError {
+    #[inline]
+    fn from(err: BuildError) -> ReadBackError {
+        ReadBackError::BuildError(err)
+    }
+}
+
+impl From<CommandBufferExecError> for ReadBackError {
+    #[inline]
+    fn from(err: CommandBufferExecError) -> ReadBackError {
+        ReadBackError::CommandBufferExecError(err)
+    }
+}
+
+impl From<FlushError> for ReadBackError {
+    #[inline]
+    fn from(err: FlushError) -> ReadBackError {
+        ReadBackError::FlushError(err)
+    }
+}
+
+impl From<ReadLockError> for ReadBackError {
+    #[inline]
+    fn from(err: ReadLockError) -> ReadBackError {
+        ReadBackError::ReadLockError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use buffer::BufferAccess;
+    use buffer::BufferUsage;
+    use buffer::DeviceLocalBuffer;
+    use sync::GpuFuture;
+
+    #[test]
+    fn read_back_device_local() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let (buffer, future) = DeviceLocalBuffer::from_iter(
+            (0..64u32).map(|n| n * 3),
+            BufferUsage::transfer_source(),
+            queue.clone(),
+        )
+        .unwrap();
+        future
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        let data = buffer.read_back(queue).unwrap();
+        assert_eq!(data, (0..64u32).map(|n| n * 3).collect::<Vec<_>>());
+    }
+}
@@ -32,18 +32,26 @@ use sync::AccessError;
 ///
 /// Creating a slice:
 ///
-/// ```ignore       // FIXME: unignore
-/// use vulkano::buffer::BufferSlice;
+/// ```
+/// use vulkano::buffer::BufferAccess;
 /// # let buffer: std::sync::Arc<vulkano::buffer::DeviceLocalBuffer<[u8]>> = return;
-/// let _slice = BufferSlice::from(&buffer);
+/// let _slice = buffer.as_buffer_slice();
 /// ```
 ///
 /// Selecting a slice of a buffer that contains `[T]`:
 ///
-/// ```ignore       // FIXME: unignore
-/// use vulkano::buffer::BufferSlice;
+/// ```
+/// use vulkano::buffer::BufferAccess;
+/// # let buffer: std::sync::Arc<vulkano::buffer::DeviceLocalBuffer<[u8]>> = return;
+/// let _slice = buffer.as_buffer_slice().slice(12 .. 14).unwrap();
+/// ```
+///
+/// Splitting a slice in two:
+///
+/// ```
+/// use vulkano::buffer::BufferAccess;
 /// # let buffer: std::sync::Arc<vulkano::buffer::DeviceLocalBuffer<[u8]>> = return;
-/// let _slice = BufferSlice::from(&buffer).slice(12 .. 14).unwrap();
+/// let (_first, _second) = buffer.as_buffer_slice().split_at(4).unwrap();
 /// ```
 ///
 pub struct BufferSlice<T: ?Sized, B> {
@@ -198,10 +206,10 @@ impl<T, B> BufferSlice<[T], B> {
 
     /// Reduces the slice to just a range of the array.
     ///
-    /// Returns `None` if out of range.
+    /// Returns `None` if out of range, or if the start of the range is after its end.
     #[inline]
     pub fn slice(self, range: Range<usize>) -> Option<BufferSlice<[T], B>> {
-        if range.end > self.len() {
+        if range.end > self.len() || range.start > range.end {
             return None;
         }
 
@@ -212,6 +220,38 @@ impl<T, B> BufferSlice<[T], B> {
             size: (range.end - range.start) * mem::size_of::<T>(),
         })
     }
+
+    /// Splits the slice in two at the given element index. The first slice contains the
+    /// elements `[0, mid)` and the second slice contains the elements `[mid, len)`.
+    ///
+    /// Returns `None` if `mid` is greater than the number of elements.
+    #[inline]
+    pub fn split_at(self, mid: usize) -> Option<(BufferSlice<[T], B>, BufferSlice<[T], B>)>
+    where
+        B: Clone,
+    {
+        if mid > self.len() {
+            return None;
+        }
+
+        let mid_bytes = mid * mem::size_of::<T>();
+
+        let first = BufferSlice {
+            marker: PhantomData,
+            resource: self.resource.clone(),
+            offset: self.offset,
+            size: mid_bytes,
+        };
+
+        let second = BufferSlice {
+            marker: PhantomData,
+            resource: self.resource,
+            offset: self.offset + mid_bytes,
+            size: self.size - mid_bytes,
+        };
+
+        Some((first, second))
+    }
 }
 
 unsafe impl<T: ?Sized, B> BufferAccess for BufferSlice<T, B>
@@ -261,6 +301,18 @@ where
     unsafe fn unlock(&self) {
         self.resource.unlock()
     }
+
+    // The initialization state is tracked for the whole buffer, so writing to a slice marks the
+    // entire buffer as initialized.
+    #[inline]
+    fn is_initialized(&self) -> bool {
+        self.resource.is_initialized()
+    }
+
+    #[inline]
+    fn mark_initialized(&self) {
+        self.resource.mark_initialized()
+    }
 }
 
 unsafe impl<T: ?Sized, B> TypedBufferAccess for BufferSlice<T, B>
@@ -324,3 +376,51 @@ macro_rules! buffer_slice_field {
         unsafe { $slice.slice_custom(|s| &s.$field) }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use buffer::BufferAccess;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use std::mem;
+
+    #[test]
+    fn slice_bounds() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let buffer =
+            CpuAccessibleBuffer::from_iter(device, BufferUsage::all(), false, 0..16u32).unwrap();
+
+        let slice = buffer.as_buffer_slice().slice(2..6).unwrap();
+        assert_eq!(slice.len(), 4);
+        assert_eq!(slice.offset(), 2 * mem::size_of::<u32>());
+
+        let element = slice.clone().index(3).unwrap();
+        assert_eq!(element.offset(), 5 * mem::size_of::<u32>());
+        assert_eq!(element.size(), mem::size_of::<u32>());
+
+        assert!(slice.clone().index(4).is_none());
+        assert!(slice.clone().slice(2..5).is_none());
+        assert!(slice.slice(3..2).is_none());
+    }
+
+    #[test]
+    fn split_at() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let buffer =
+            CpuAccessibleBuffer::from_iter(device, BufferUsage::all(), false, 0..16u32).unwrap();
+
+        let (first, second) = buffer.as_buffer_slice().split_at(5).unwrap();
+        assert_eq!(first.len(), 5);
+        assert_eq!(second.len(), 11);
+        assert_eq!(first.inner().offset, 0);
+        assert_eq!(second.inner().offset, 5 * mem::size_of::<u32>());
+
+        let (empty, all) = buffer.as_buffer_slice().split_at(0).unwrap();
+        assert_eq!(empty.len(), 0);
+        assert_eq!(all.len(), 16);
+
+        assert!(buffer.as_buffer_slice().split_at(17).is_none());
+    }
+}
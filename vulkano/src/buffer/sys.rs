@@ -33,12 +33,14 @@ use std::mem;
 use std::mem::MaybeUninit;
 use std::ptr;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use buffer::BufferUsage;
 use device::Device;
 use device::DeviceOwned;
 use memory::DeviceMemory;
 use memory::DeviceMemoryAllocError;
+use memory::ExternalMemoryHandleType;
 use memory::MemoryRequirements;
 use sync::Sharing;
 
@@ -54,6 +56,18 @@ pub struct UnsafeBuffer {
     device: Arc<Device>,
     size: usize,
     usage: vk::BufferUsageFlags,
+    exclusive_sharing: bool,
+    // Queue family ownership transfers submitted so far.
+    queue_family_transfer: Mutex<QueueFamilyTransfer>,
+}
+
+// State of the queue family ownership transfers of a buffer.
+#[derive(Debug, Copy, Clone, Default)]
+struct QueueFamilyTransfer {
+    // Queue family the buffer was last released to, if any.
+    owner: Option<u32>,
+    // Queue family the buffer was last released from, if it hasn't been acquired yet.
+    pending_acquire: Option<u32>,
 }
 
 impl UnsafeBuffer {
@@ -66,12 +80,54 @@ impl UnsafeBuffer {
     /// - Panics if `sparse.sparse` is false and `sparse.sparse_residency` or `sparse.sparse_aliased` is true.
     /// - Panics if `usage` is empty.
     ///
+    #[inline]
     pub unsafe fn new<'a, I>(
+        device: Arc<Device>,
+        size: usize,
+        usage: BufferUsage,
+        sharing: Sharing<I>,
+        sparse: SparseLevel,
+    ) -> Result<(UnsafeBuffer, MemoryRequirements), BufferCreationError>
+    where
+        I: Iterator<Item = u32>,
+    {
+        UnsafeBuffer::new_impl(device, size, usage, sharing, sparse, None)
+    }
+
+    /// Same as `new`, but the memory bound to the buffer can be exported to or imported from
+    /// one of `handle_types`.
+    ///
+    /// The memory must then be allocated with `DeviceMemory::dedicated_alloc_exportable` or
+    /// imported with `DeviceMemory::import_fd` or `DeviceMemory::import_win32_handle`.
+    ///
+    /// # Panic
+    ///
+    /// - Same panic conditions as `new`.
+    /// - Panics if the extensions required by `handle_types` are not enabled on the device.
+    ///
+    #[inline]
+    pub unsafe fn new_with_external_memory<'a, I>(
+        device: Arc<Device>,
+        size: usize,
+        usage: BufferUsage,
+        sharing: Sharing<I>,
+        sparse: SparseLevel,
+        handle_types: ExternalMemoryHandleType,
+    ) -> Result<(UnsafeBuffer, MemoryRequirements), BufferCreationError>
+    where
+        I: Iterator<Item = u32>,
+    {
+        handle_types.check_extensions(device.loaded_extensions());
+        UnsafeBuffer::new_impl(device, size, usage, sharing, sparse, Some(handle_types))
+    }
+
+    unsafe fn new_impl<'a, I>(
         device: Arc<Device>,
         size: usize,
         mut usage: BufferUsage,
         sharing: Sharing<I>,
         sparse: SparseLevel,
+        external_memory: Option<ExternalMemoryHandleType>,
     ) -> Result<(UnsafeBuffer, MemoryRequirements), BufferCreationError>
     where
         I: Iterator<Item = u32>,
@@ -112,6 +168,9 @@ impl UnsafeBuffer {
                 return Err(BufferCreationError::DeviceAddressFeatureNotEnabled);
             }
         }
+        if usage.ray_tracing && !device.loaded_extensions().nv_ray_tracing {
+            usage.ray_tracing = false;
+        }
         let usage_bits = usage.to_vulkan_bits();
 
         // Checking for empty BufferUsage.
@@ -120,15 +179,30 @@ impl UnsafeBuffer {
             "Can't create buffer with empty BufferUsage"
         );
 
+        let exclusive_sharing = match sharing {
+            Sharing::Exclusive => true,
+            Sharing::Concurrent(_) => false,
+        };
+
         let buffer = {
             let (sh_mode, sh_indices) = match sharing {
                 Sharing::Exclusive => (vk::SHARING_MODE_EXCLUSIVE, SmallVec::<[u32; 8]>::new()),
                 Sharing::Concurrent(ids) => (vk::SHARING_MODE_CONCURRENT, ids.collect()),
             };
 
+            let external_memory_info =
+                external_memory.map(|handle_types| vk::ExternalMemoryBufferCreateInfoKHR {
+                    sType: vk::STRUCTURE_TYPE_EXTERNAL_MEMORY_BUFFER_CREATE_INFO_KHR,
+                    pNext: ptr::null(),
+                    handleTypes: handle_types.to_vulkan_bits(),
+                });
+
             let infos = vk::BufferCreateInfo {
                 sType: vk::STRUCTURE_TYPE_BUFFER_CREATE_INFO,
-                pNext: ptr::null(),
+                pNext: external_memory_info
+                    .as_ref()
+                    .map(|i| i as *const vk::ExternalMemoryBufferCreateInfoKHR)
+                    .unwrap_or(ptr::null()) as *const _,
                 flags: sparse.to_flags(),
                 size: size as u64,
                 usage: usage_bits,
@@ -234,6 +308,8 @@ impl UnsafeBuffer {
             device: device.clone(),
             size: size as usize,
             usage: usage_bits,
+            exclusive_sharing: exclusive_sharing,
+            queue_family_transfer: Mutex::new(QueueFamilyTransfer::default()),
         };
 
         Ok((obj, mem_reqs))
@@ -336,6 +412,52 @@ impl UnsafeBuffer {
         (self.usage & vk::BUFFER_USAGE_SHADER_DEVICE_ADDRESS_BIT) != 0
     }
 
+    #[inline]
+    pub fn usage_ray_tracing(&self) -> bool {
+        (self.usage & vk::BUFFER_USAGE_RAY_TRACING_BIT_NV) != 0
+    }
+
+    /// Returns true if the buffer was created with `Sharing::Exclusive`.
+    ///
+    /// An exclusive buffer can only be accessed by one queue family at a time, and its
+    /// ownership must be explicitly transferred in order to use it from another queue family.
+    #[inline]
+    pub fn is_sharing_exclusive(&self) -> bool {
+        self.exclusive_sharing
+    }
+
+    /// Returns the id of the queue family that the buffer has been released to by the last
+    /// queue family ownership transfer submitted to a queue, or `None` if no transfer has been
+    /// submitted so far.
+    #[inline]
+    pub fn queue_family_owner(&self) -> Option<u32> {
+        self.queue_family_transfer.lock().unwrap().owner
+    }
+
+    /// Returns the id of the queue family that the buffer has been released from by the last
+    /// queue family ownership release submitted to a queue, if the matching acquire hasn't been
+    /// submitted yet.
+    #[inline]
+    pub fn pending_acquire_source(&self) -> Option<u32> {
+        self.queue_family_transfer.lock().unwrap().pending_acquire
+    }
+
+    // Records that a command buffer releasing the buffer from the queue family `source` to the
+    // queue family `destination` has been submitted.
+    #[inline]
+    pub(crate) fn set_queue_family_released(&self, source: u32, destination: u32) {
+        *self.queue_family_transfer.lock().unwrap() = QueueFamilyTransfer {
+            owner: Some(destination),
+            pending_acquire: Some(source),
+        };
+    }
+
+    // Records that a command buffer acquiring the buffer has been submitted.
+    #[inline]
+    pub(crate) fn set_queue_family_acquired(&self) {
+        self.queue_family_transfer.lock().unwrap().pending_acquire = None;
+    }
+
     /// Returns a key unique to each `UnsafeBuffer`. Can be used for the `conflicts_key` method.
     #[inline]
     pub fn key(&self) -> u64 {
@@ -12,9 +12,12 @@ use std::hash::Hasher;
 use std::num::NonZeroU64;
 use std::ops::Range;
 use std::ptr;
+use std::sync::Arc;
 
+use buffer::readback;
 use buffer::sys::{DeviceAddressUsageNotEnabledError, UnsafeBuffer};
 use buffer::BufferSlice;
+use buffer::ReadBackError;
 use device::DeviceOwned;
 use device::Queue;
 use image::ImageAccess;
@@ -84,6 +87,24 @@ pub unsafe trait BufferAccess: DeviceOwned {
         self.slice(index..(index + 1))
     }
 
+    /// Copies the content of the buffer to the host and returns it.
+    ///
+    /// This allocates a host-visible staging buffer, submits a command buffer that copies `self`
+    /// into it on `queue`, and blocks until the copy is finished. The buffer must have been
+    /// created with the `transfer_source` usage.
+    ///
+    /// This is mostly useful for retrieving the results of a compute shader or for debugging.
+    /// Since it waits for the GPU to be idle with regard to this submission, it shouldn't be used
+    /// in performance-critical code.
+    #[inline]
+    fn read_back<T>(self, queue: Arc<Queue>) -> Result<Vec<T>, ReadBackError>
+    where
+        Self: Sized + TypedBufferAccess<Content = [T]> + Send + Sync + 'static,
+        T: Clone + Send + Sync + 'static,
+    {
+        readback::read_back(self, queue)
+    }
+
     /// Returns true if an access to `self` potentially overlaps the same memory as an access to
     /// `other`.
     ///
@@ -145,8 +166,37 @@ pub unsafe trait BufferAccess: DeviceOwned {
     /// Must only be called once per previous lock.
     unsafe fn unlock(&self);
 
+    /// Returns false if the content of the buffer is known to have never been written since the
+    /// buffer was created.
+    ///
+    /// Buffers created with an uninitialized constructor (for example `DeviceLocalBuffer::new`)
+    /// start uninitialized. In debug builds, the command buffer builders return an error when a
+    /// command reads from such a buffer, unless a command written earlier in the same command
+    /// buffer writes to it.
+    ///
+    /// The default implementation always returns true.
+    #[inline]
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    /// Marks the content of the buffer as initialized.
+    ///
+    /// In debug builds, this is called when a command buffer that writes to the buffer, either as
+    /// the destination of a transfer or from a shader, is submitted. Recording the command isn't
+    /// enough, as the command buffer could be submitted later or never. The default
+    /// implementation does nothing.
+    #[inline]
+    fn mark_initialized(&self) {}
+
     /// Gets the device address for this buffer.
     ///
+    /// The buffer must have been created with the `device_address` usage, which requires the
+    /// `buffer_device_address` feature and the `ext_buffer_device_address` extension to be
+    /// enabled on the device. The returned address can then be written to another buffer or
+    /// passed as a push constant, and dereferenced in a shader that uses the
+    /// `PhysicalStorageBufferAddresses` capability.
+    ///
     /// # Safety
     ///
     /// No lock checking or waiting is performed. This is nevertheless still safe because the
@@ -233,6 +283,16 @@ where
     unsafe fn unlock(&self) {
         (**self).unlock()
     }
+
+    #[inline]
+    fn is_initialized(&self) -> bool {
+        (**self).is_initialized()
+    }
+
+    #[inline]
+    fn mark_initialized(&self) {
+        (**self).mark_initialized()
+    }
 }
 
 /// Extension trait for `BufferAccess`. Indicates the type of the content of the buffer.
@@ -27,9 +27,14 @@ pub struct BufferUsage {
     pub index_buffer: bool,
     pub vertex_buffer: bool,
     pub indirect_buffer: bool,
-    /// Requires the `buffer_device_address` feature. If that feature is not enabled, this will
-    /// be silently ignored.
+    /// Requires the `buffer_device_address` feature, which is provided by the
+    /// `ext_buffer_device_address` extension. If that feature is not enabled, this will be
+    /// silently ignored.
     pub device_address: bool,
+    /// Allows the buffer to be used as a shader binding table for ray tracing. Requires the
+    /// `nv_ray_tracing` extension. If that extension is not enabled, this will be silently
+    /// ignored.
+    pub ray_tracing: bool,
 }
 
 impl BufferUsage {
@@ -66,12 +71,15 @@ impl BufferUsage {
         if self.device_address {
             result |= vk::BUFFER_USAGE_SHADER_DEVICE_ADDRESS_BIT;
         }
+        if self.ray_tracing {
+            result |= vk::BUFFER_USAGE_RAY_TRACING_BIT_NV;
+        }
         result
     }
 
     /// Builds a `BufferUsage` with all values set to false.
     #[inline]
-    pub fn none() -> BufferUsage {
+    pub const fn none() -> BufferUsage {
         BufferUsage {
             transfer_source: false,
             transfer_destination: false,
@@ -83,12 +91,13 @@ impl BufferUsage {
             vertex_buffer: false,
             indirect_buffer: false,
             device_address: false,
+            ray_tracing: false,
         }
     }
 
     /// Builds a `BufferUsage` with all values set to true. Can be used for quick prototyping.
     #[inline]
-    pub fn all() -> BufferUsage {
+    pub const fn all() -> BufferUsage {
         BufferUsage {
             transfer_source: true,
             transfer_destination: true,
@@ -100,12 +109,13 @@ impl BufferUsage {
             vertex_buffer: true,
             indirect_buffer: true,
             device_address: true,
+            ray_tracing: true,
         }
     }
 
     /// Builds a `BufferUsage` with `transfer_source` set to true and the rest to false.
     #[inline]
-    pub fn transfer_source() -> BufferUsage {
+    pub const fn transfer_source() -> BufferUsage {
         BufferUsage {
             transfer_source: true,
             ..BufferUsage::none()
@@ -114,7 +124,7 @@ impl BufferUsage {
 
     /// Builds a `BufferUsage` with `transfer_destination` set to true and the rest to false.
     #[inline]
-    pub fn transfer_destination() -> BufferUsage {
+    pub const fn transfer_destination() -> BufferUsage {
         BufferUsage {
             transfer_destination: true,
             ..BufferUsage::none()
@@ -123,7 +133,7 @@ impl BufferUsage {
 
     /// Builds a `BufferUsage` with `vertex_buffer` set to true and the rest to false.
     #[inline]
-    pub fn vertex_buffer() -> BufferUsage {
+    pub const fn vertex_buffer() -> BufferUsage {
         BufferUsage {
             vertex_buffer: true,
             ..BufferUsage::none()
@@ -133,7 +143,7 @@ impl BufferUsage {
     /// Builds a `BufferUsage` with `vertex_buffer` and `transfer_destination` set to true and the rest
     /// to false.
     #[inline]
-    pub fn vertex_buffer_transfer_destination() -> BufferUsage {
+    pub const fn vertex_buffer_transfer_destination() -> BufferUsage {
         BufferUsage {
             vertex_buffer: true,
             transfer_destination: true,
@@ -143,7 +153,7 @@ impl BufferUsage {
 
     /// Builds a `BufferUsage` with `index_buffer` set to true and the rest to false.
     #[inline]
-    pub fn index_buffer() -> BufferUsage {
+    pub const fn index_buffer() -> BufferUsage {
         BufferUsage {
             index_buffer: true,
             ..BufferUsage::none()
@@ -152,7 +162,7 @@ impl BufferUsage {
 
     /// Builds a `BufferUsage` with `index_buffer` and `transfer_destination` set to true and the rest to false.
     #[inline]
-    pub fn index_buffer_transfer_destination() -> BufferUsage {
+    pub const fn index_buffer_transfer_destination() -> BufferUsage {
         BufferUsage {
             index_buffer: true,
             transfer_destination: true,
@@ -162,7 +172,7 @@ impl BufferUsage {
 
     /// Builds a `BufferUsage` with `uniform_buffer` set to true and the rest to false.
     #[inline]
-    pub fn uniform_buffer() -> BufferUsage {
+    pub const fn uniform_buffer() -> BufferUsage {
         BufferUsage {
             uniform_buffer: true,
             ..BufferUsage::none()
@@ -172,7 +182,7 @@ impl BufferUsage {
     /// Builds a `BufferUsage` with `uniform_buffer` and `transfer_destination` set to true and the rest
     /// to false.
     #[inline]
-    pub fn uniform_buffer_transfer_destination() -> BufferUsage {
+    pub const fn uniform_buffer_transfer_destination() -> BufferUsage {
         BufferUsage {
             uniform_buffer: true,
             transfer_destination: true,
@@ -180,9 +190,47 @@ impl BufferUsage {
         }
     }
 
+    /// Builds a `BufferUsage` with `storage_buffer` set to true and the rest to false.
+    #[inline]
+    pub const fn storage_buffer() -> BufferUsage {
+        BufferUsage {
+            storage_buffer: true,
+            ..BufferUsage::none()
+        }
+    }
+
+    /// Builds a `BufferUsage` with `storage_buffer` and `transfer_destination` set to true and the rest
+    /// to false.
+    #[inline]
+    pub const fn storage_buffer_transfer_destination() -> BufferUsage {
+        BufferUsage {
+            storage_buffer: true,
+            transfer_destination: true,
+            ..BufferUsage::none()
+        }
+    }
+
+    /// Builds a `BufferUsage` with `uniform_texel_buffer` set to true and the rest to false.
+    #[inline]
+    pub const fn uniform_texel_buffer() -> BufferUsage {
+        BufferUsage {
+            uniform_texel_buffer: true,
+            ..BufferUsage::none()
+        }
+    }
+
+    /// Builds a `BufferUsage` with `storage_texel_buffer` set to true and the rest to false.
+    #[inline]
+    pub const fn storage_texel_buffer() -> BufferUsage {
+        BufferUsage {
+            storage_texel_buffer: true,
+            ..BufferUsage::none()
+        }
+    }
+
     /// Builds a `BufferUsage` with `indirect_buffer` set to true and the rest to false.
     #[inline]
-    pub fn indirect_buffer() -> BufferUsage {
+    pub const fn indirect_buffer() -> BufferUsage {
         BufferUsage {
             indirect_buffer: true,
             ..BufferUsage::none()
@@ -192,7 +240,7 @@ impl BufferUsage {
     /// Builds a `BufferUsage` with `indirect_buffer` and `transfer_destination` set to true and the rest
     /// to false.
     #[inline]
-    pub fn indirect_buffer_transfer_destination() -> BufferUsage {
+    pub const fn indirect_buffer_transfer_destination() -> BufferUsage {
         BufferUsage {
             indirect_buffer: true,
             transfer_destination: true,
@@ -202,12 +250,37 @@ impl BufferUsage {
 
     /// Builds a `BufferUsage` with `device_address` set to true and the rest to false.
     #[inline]
-    pub fn device_address() -> BufferUsage {
+    pub const fn device_address() -> BufferUsage {
         BufferUsage {
             device_address: true,
             ..BufferUsage::none()
         }
     }
+
+    /// Builds a `BufferUsage` with `ray_tracing` set to true and the rest to false.
+    #[inline]
+    pub const fn ray_tracing() -> BufferUsage {
+        BufferUsage {
+            ray_tracing: true,
+            ..BufferUsage::none()
+        }
+    }
+
+    /// Returns true if all the usages that are enabled in `other` are also enabled in `self`.
+    #[inline]
+    pub const fn contains(&self, other: &BufferUsage) -> bool {
+        (self.transfer_source || !other.transfer_source)
+            && (self.transfer_destination || !other.transfer_destination)
+            && (self.uniform_texel_buffer || !other.uniform_texel_buffer)
+            && (self.storage_texel_buffer || !other.storage_texel_buffer)
+            && (self.uniform_buffer || !other.uniform_buffer)
+            && (self.storage_buffer || !other.storage_buffer)
+            && (self.index_buffer || !other.index_buffer)
+            && (self.vertex_buffer || !other.vertex_buffer)
+            && (self.indirect_buffer || !other.indirect_buffer)
+            && (self.device_address || !other.device_address)
+            && (self.ray_tracing || !other.ray_tracing)
+    }
 }
 
 impl BitOr for BufferUsage {
@@ -226,6 +299,24 @@ impl BitOr for BufferUsage {
             vertex_buffer: self.vertex_buffer || rhs.vertex_buffer,
             indirect_buffer: self.indirect_buffer || rhs.indirect_buffer,
             device_address: self.device_address || rhs.device_address,
+            ray_tracing: self.ray_tracing || rhs.ray_tracing,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use buffer::BufferUsage;
+
+    const STORAGE: BufferUsage = BufferUsage::storage_buffer();
+
+    #[test]
+    fn bitor_and_contains() {
+        let usage = STORAGE | BufferUsage::transfer_destination();
+        assert_eq!(usage, BufferUsage::storage_buffer_transfer_destination());
+        assert!(usage.contains(&STORAGE));
+        assert!(usage.contains(&BufferUsage::none()));
+        assert!(!usage.contains(&BufferUsage::vertex_buffer()));
+        assert!(BufferUsage::all().contains(&usage));
+    }
+}
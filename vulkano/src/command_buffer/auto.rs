@@ -7,10 +7,13 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+use fnv::FnvHashMap;
 use std::error;
+use std::ffi::CStr;
 use std::fmt;
 use std::iter;
 use std::mem;
+use std::ops::Range;
 use std::slice;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
@@ -18,6 +21,8 @@ use std::sync::Arc;
 
 use buffer::BufferAccess;
 use buffer::TypedBufferAccess;
+use command_buffer::culling;
+use command_buffer::culling::CheckCullDrawsError;
 use command_buffer::pool::standard::StandardCommandPoolAlloc;
 use command_buffer::pool::standard::StandardCommandPoolBuilder;
 use command_buffer::pool::CommandPoolBuilderAlloc;
@@ -31,30 +36,42 @@ use command_buffer::sys::KindSecondaryRenderPass;
 use command_buffer::sys::UnsafeCommandBuffer;
 use command_buffer::sys::UnsafeCommandBufferBuilderBufferImageCopy;
 use command_buffer::sys::UnsafeCommandBufferBuilderColorImageClear;
+use command_buffer::sys::UnsafeCommandBufferBuilderDepthStencilImageClear;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageAspect;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageBlit;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageCopy;
+use command_buffer::sys::UnsafeCommandBufferBuilderImageResolve;
 use command_buffer::validity::*;
+use command_buffer::BufferCopyRegion;
+use command_buffer::BufferImageCopyRegion;
 use command_buffer::CommandBuffer;
 use command_buffer::CommandBufferExecError;
+use command_buffer::DispatchIndirectCommand;
 use command_buffer::DrawIndexedIndirectCommand;
 use command_buffer::DrawIndirectCommand;
+use command_buffer::DrawMeshTasksIndirectCommand;
 use command_buffer::DynamicState;
+use command_buffer::ImageBlitRegion;
+use command_buffer::ImageCopyRegion;
+use command_buffer::PipelineBarrier;
 use command_buffer::StateCacher;
 use command_buffer::StateCacherOutcome;
+use descriptor::descriptor::ShaderStages;
 use descriptor::descriptor_set::DescriptorSetsCollection;
+use descriptor::descriptor_set::PersistentDescriptorSet;
+use descriptor::descriptor_set::PersistentDescriptorSetBuildError;
+use descriptor::descriptor_set::PersistentDescriptorSetError;
 use descriptor::pipeline_layout::PipelineLayoutAbstract;
+use descriptor::pipeline_layout::TypedPipelineLayout;
 use device::Device;
 use device::DeviceOwned;
 use device::Queue;
 use format::AcceptsPixels;
 use format::ClearValue;
 use format::Format;
-use format::FormatTy;
 use framebuffer::EmptySinglePassRenderPassDesc;
 use framebuffer::Framebuffer;
 use framebuffer::FramebufferAbstract;
-use framebuffer::LoadOp;
 use framebuffer::RenderPass;
 use framebuffer::RenderPassAbstract;
 use framebuffer::RenderPassCompatible;
@@ -62,19 +79,34 @@ use framebuffer::RenderPassDescClearValues;
 use framebuffer::Subpass;
 use framebuffer::SubpassContents;
 use image::ImageAccess;
+use image::ImageAspect;
 use image::ImageLayout;
+use image::SwapchainImage;
 use instance::QueueFamily;
+use pipeline::depth_stencil::DynamicStencilValue;
 use pipeline::input_assembly::Index;
+use pipeline::raster::DepthBias;
 use pipeline::vertex::VertexSource;
+use pipeline::viewport::Scissor;
+use pipeline::viewport::Viewport;
 use pipeline::ComputePipelineAbstract;
 use pipeline::GraphicsPipelineAbstract;
+use pipeline::PipelineBindPoint;
+use pipeline::RayTracingPipelineAbstract;
+use pipeline::ShaderBindingTable;
+use pipeline::TypedGraphicsPipeline;
 use query::QueryPipelineStatisticFlags;
+use query::QueryPool;
+use query::QueryType;
 use sampler::Filter;
 use sync::AccessCheckError;
 use sync::AccessFlagBits;
+use sync::Event;
 use sync::GpuFuture;
 use sync::PipelineStages;
+use vk;
 use OomError;
+use VulkanObject;
 
 /// Note that command buffers allocated from the default command pool (`Arc<StandardCommandPool>`)
 /// don't implement the `Send` and `Sync` traits. If you use this pool, then the
@@ -84,6 +116,12 @@ pub struct AutoCommandBufferBuilder<P = StandardCommandPoolBuilder> {
     inner: SyncCommandBufferBuilder<P>,
     state_cacher: StateCacher,
 
+    // Dynamic states set with the `set_*` methods that haven't been used by a draw command yet.
+    dynamic_state: DynamicState,
+
+    // Id of the queue family the command buffer is created for.
+    queue_family_id: u32,
+
     // True if the queue family supports graphics operations.
     graphics_allowed: bool,
 
@@ -102,6 +140,23 @@ pub struct AutoCommandBufferBuilder<P = StandardCommandPoolBuilder> {
 
     // Flags passed when creating the command buffer.
     flags: Flags,
+
+    // Queries that have been begun and not ended yet, indexed by their type.
+    query_state: FnvHashMap<vk::QueryType, QueryState>,
+
+    // Number of debug label regions that have been begun and not ended yet.
+    debug_label_depth: u32,
+
+    // Statistics about the commands that were recorded.
+    statistics: CommandBufferStatistics,
+}
+
+// The state of an active query.
+struct QueryState {
+    query_pool: vk::QueryPool,
+    query: u32,
+    // Index of the subpass the query was begun in, if it was begun inside a render pass.
+    subpass: Option<u32>,
 }
 
 impl AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
@@ -430,20 +485,56 @@ impl AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
             Ok(AutoCommandBufferBuilder {
                 inner: inner?,
                 state_cacher,
+                dynamic_state: DynamicState::none(),
+                queue_family_id: queue_family.id(),
                 graphics_allowed,
                 compute_allowed,
                 render_pass,
                 secondary_cb,
                 subpass_secondary: false,
                 flags,
+                query_state: FnvHashMap::default(),
+                debug_label_depth: 0,
+                statistics: CommandBufferStatistics::default(),
             })
         }
     }
 }
 
 impl<P> AutoCommandBufferBuilder<P> {
+    /// Returns the queue family the command buffer is created for.
+    #[inline]
+    pub fn queue_family(&self) -> QueueFamily {
+        self.device()
+            .physical_device()
+            .queue_family_by_id(self.queue_family_id)
+            .unwrap()
+    }
+
+    /// Returns the index of the subpass that commands are currently recorded in, or `None` if
+    /// we're outside of a render pass.
+    ///
+    /// The index starts at 0 after `begin_render_pass`, and is incremented by every call to
+    /// `next_subpass`.
+    #[inline]
+    pub fn current_subpass(&self) -> Option<u32> {
+        self.render_pass.as_ref().map(|&(_, index)| index)
+    }
+
+    /// Sets whether the pipelines, descriptor sets, vertex and index buffers that are already
+    /// bound are skipped when recording the following commands. This is enabled by default, and
+    /// disabling it is mostly useful to measure its effect.
+    ///
+    /// See `StateCacher::set_elide_binds`.
+    #[inline]
+    pub fn set_elide_binds(&mut self, elide_binds: bool) {
+        self.state_cacher.set_elide_binds(elide_binds);
+    }
+
     #[inline]
-    fn ensure_outside_render_pass(&self) -> Result<(), AutoCommandBufferBuilderContextError> {
+    pub(super) fn ensure_outside_render_pass(
+        &self,
+    ) -> Result<(), AutoCommandBufferBuilderContextError> {
         if self.render_pass.is_none() {
             Ok(())
         } else {
@@ -452,7 +543,7 @@ impl<P> AutoCommandBufferBuilder<P> {
     }
 
     #[inline]
-    fn ensure_inside_render_pass_secondary(
+    pub(super) fn ensure_inside_render_pass_secondary(
         &self,
     ) -> Result<(), AutoCommandBufferBuilderContextError> {
         if self.render_pass.is_some() {
@@ -467,13 +558,9 @@ impl<P> AutoCommandBufferBuilder<P> {
     }
 
     #[inline]
-    fn ensure_inside_render_pass_inline<Gp>(
+    pub(super) fn ensure_inside_inline_subpass(
         &self,
-        pipeline: &Gp,
-    ) -> Result<(), AutoCommandBufferBuilderContextError>
-    where
-        Gp: ?Sized + GraphicsPipelineAbstract,
-    {
+    ) -> Result<(), AutoCommandBufferBuilderContextError> {
         if self.render_pass.is_none() {
             return Err(AutoCommandBufferBuilderContextError::ForbiddenOutsideRenderPass);
         }
@@ -482,6 +569,19 @@ impl<P> AutoCommandBufferBuilder<P> {
             return Err(AutoCommandBufferBuilderContextError::WrongSubpassType);
         }
 
+        Ok(())
+    }
+
+    #[inline]
+    fn ensure_inside_render_pass_inline<Gp>(
+        &self,
+        pipeline: &Gp,
+    ) -> Result<(), AutoCommandBufferBuilderContextError>
+    where
+        Gp: ?Sized + GraphicsPipelineAbstract,
+    {
+        self.ensure_inside_inline_subpass()?;
+
         let local_render_pass = self.render_pass.as_ref().unwrap();
 
         if pipeline.subpass_index() != local_render_pass.1 {
@@ -505,6 +605,10 @@ impl<P> AutoCommandBufferBuilder<P> {
             return Err(AutoCommandBufferBuilderContextError::ForbiddenInsideRenderPass.into());
         }
 
+        if !self.query_state.is_empty() {
+            return Err(AutoCommandBufferBuilderContextError::QueryIsActive.into());
+        }
+
         let submit_state = match self.flags {
             Flags::None => SubmitState::ExclusiveUse {
                 in_use: AtomicBool::new(false),
@@ -515,9 +619,16 @@ impl<P> AutoCommandBufferBuilder<P> {
             },
         };
 
+        let inner = self.inner.build()?;
+        let statistics = CommandBufferStatistics {
+            pipeline_barriers: inner.num_pipeline_barriers(),
+            ..self.statistics
+        };
+
         Ok(AutoCommandBuffer {
-            inner: self.inner.build()?,
+            inner,
             submit_state,
+            statistics,
         })
     }
 
@@ -527,7 +638,9 @@ impl<P> AutoCommandBufferBuilder<P> {
     /// you're inside the first subpass of the render pass. If `secondary` is false, you will only
     /// be able to add inline draw commands and not secondary command buffers.
     ///
-    /// C must contain exactly one clear value for each attachment in the framebuffer.
+    /// C must contain exactly one clear value for each attachment in the framebuffer. The clear
+    /// value of an attachment that is cleared must match the type of its format, and the other
+    /// attachments must have `ClearValue::None`. See `check_clear_values`.
     ///
     /// You must call this before you can add draw commands.
     #[inline]
@@ -551,64 +664,21 @@ impl<P> AutoCommandBufferBuilder<P> {
 
             self.ensure_outside_render_pass()?;
 
-            let clear_values = framebuffer.convert_clear_values(clear_values);
-            let clear_values = clear_values.collect::<Vec<_>>().into_iter(); // TODO: necessary for Send + Sync ; needs an API rework of convert_clear_values
-            let mut clear_values_copy = clear_values.clone().enumerate(); // TODO: Proper errors for clear value errors instead of panics
-
-            for (atch_i, atch_desc) in framebuffer.attachment_descs().enumerate() {
-                match clear_values_copy.next() {
-                    Some((clear_i, clear_value)) => {
-                        if atch_desc.load == LoadOp::Clear {
-                            match clear_value {
-                                ClearValue::None => panic!("Bad ClearValue! index: {}, attachment index: {}, expected: {:?}, got: None",
-                                    clear_i, atch_i, atch_desc.format.ty()),
-                                ClearValue::Float(_) => if atch_desc.format.ty() != FormatTy::Float {
-                                   panic!("Bad ClearValue! index: {}, attachment index: {}, expected: {:?}, got: Float",
-                                       clear_i, atch_i, atch_desc.format.ty());
-                                }
-                                ClearValue::Int(_) => if atch_desc.format.ty() != FormatTy::Sint {
-                                    panic!("Bad ClearValue! index: {}, attachment index: {}, expected: {:?}, got: Int",
-                                       clear_i, atch_i, atch_desc.format.ty());
-                                }
-                                ClearValue::Uint(_) => if atch_desc.format.ty() != FormatTy::Uint {
-                                    panic!("Bad ClearValue! index: {}, attachment index: {}, expected: {:?}, got: Uint",
-                                       clear_i, atch_i, atch_desc.format.ty());
-                                }
-                                ClearValue::Depth(_) => if atch_desc.format.ty() != FormatTy::Depth {
-                                    panic!("Bad ClearValue! index: {}, attachment index: {}, expected: {:?}, got: Depth",
-                                       clear_i, atch_i, atch_desc.format.ty());
-                                }
-                                ClearValue::Stencil(_) => if atch_desc.format.ty() != FormatTy::Stencil {
-                                    panic!("Bad ClearValue! index: {}, attachment index: {}, expected: {:?}, got: Stencil",
-                                       clear_i, atch_i, atch_desc.format.ty());
-                                }
-                                ClearValue::DepthStencil(_) => if atch_desc.format.ty() != FormatTy::DepthStencil {
-                                    panic!("Bad ClearValue! index: {}, attachment index: {}, expected: {:?}, got: DepthStencil",
-                                       clear_i, atch_i, atch_desc.format.ty());
-                                }
-                            }
-                        } else {
-                            if clear_value != ClearValue::None {
-                                panic!("Bad ClearValue! index: {}, attachment index: {}, expected: None, got: {:?}",
-                                   clear_i, atch_i, clear_value);
-                            }
-                        }
-                    }
-                    None => panic!("Not enough clear values"),
-                }
-            }
-
-            if clear_values_copy.count() != 0 {
-                panic!("Too many clear values")
-            }
+            let clear_values = framebuffer
+                .convert_clear_values(clear_values)
+                .collect::<Vec<_>>();
+            check_clear_values(&framebuffer, &clear_values)?;
 
             let contents = if secondary {
                 SubpassContents::SecondaryCommandBuffers
             } else {
                 SubpassContents::Inline
             };
-            self.inner
-                .begin_render_pass(framebuffer.clone(), contents, clear_values)?;
+            self.inner.begin_render_pass(
+                framebuffer.clone(),
+                contents,
+                clear_values.into_iter(),
+            )?;
             self.render_pass = Some((Box::new(framebuffer) as Box<_>, 0));
             self.subpass_secondary = secondary;
             Ok(self)
@@ -654,51 +724,95 @@ impl<P> AutoCommandBufferBuilder<P> {
         S: ImageAccess + Send + Sync + 'static,
         D: ImageAccess + Send + Sync + 'static,
     {
-        unsafe {
-            self.ensure_outside_render_pass()?;
-
-            check_copy_image(
-                self.device(),
-                &source,
+        self.copy_image_regions(
+            source,
+            destination,
+            iter::once(ImageCopyRegion {
                 source_offset,
                 source_base_array_layer,
                 source_mip_level,
-                &destination,
                 destination_offset,
                 destination_base_array_layer,
                 destination_mip_level,
                 extent,
                 layer_count,
-            )?;
+            }),
+        )
+    }
 
-            let copy = UnsafeCommandBufferBuilderImageCopy {
-                // TODO: Allowing choosing a subset of the image aspects, but note that if color
-                // is included, neither depth nor stencil may.
-                aspect: UnsafeCommandBufferBuilderImageAspect {
-                    color: source.has_color(),
-                    depth: !source.has_color() && source.has_depth() && destination.has_depth(),
-                    stencil: !source.has_color()
-                        && source.has_stencil()
-                        && destination.has_stencil(),
-                },
-                source_mip_level,
-                destination_mip_level,
-                source_base_array_layer,
-                destination_base_array_layer,
-                layer_count,
-                source_offset,
-                destination_offset,
-                extent,
+    /// Adds a command that copies multiple regions from an image to another.
+    ///
+    /// The restrictions are the same as `copy_image`, and are checked for each region.
+    ///
+    /// The source and the destination can be the same image, in which case none of the regions
+    /// that are read must overlap one of the regions that are written and the image is accessed
+    /// in the `General` layout.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the source or the destination was not created with `device`.
+    ///
+    pub fn copy_image_regions<S, D, R>(
+        &mut self,
+        source: S,
+        destination: D,
+        regions: R,
+    ) -> Result<&mut Self, CopyImageError>
+    where
+        S: ImageAccess + Send + Sync + 'static,
+        D: ImageAccess + Send + Sync + 'static,
+        R: IntoIterator<Item = ImageCopyRegion>,
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+
+            let regions: Vec<_> = regions.into_iter().collect();
+            check_copy_image_regions(self.device(), &source, &destination, &regions)?;
+
+            // TODO: Allowing choosing a subset of the image aspects, but note that if color
+            // is included, neither depth nor stencil may.
+            let aspect = UnsafeCommandBufferBuilderImageAspect {
+                color: source.has_color(),
+                depth: !source.has_color() && source.has_depth() && destination.has_depth(),
+                stencil: !source.has_color() && source.has_stencil() && destination.has_stencil(),
             };
 
+            let copies: Vec<_> = regions
+                .into_iter()
+                .map(|region| UnsafeCommandBufferBuilderImageCopy {
+                    aspect,
+                    source_mip_level: region.source_mip_level,
+                    destination_mip_level: region.destination_mip_level,
+                    source_base_array_layer: region.source_base_array_layer,
+                    destination_base_array_layer: region.destination_base_array_layer,
+                    layer_count: region.layer_count,
+                    source_offset: region.source_offset,
+                    destination_offset: region.destination_offset,
+                    extent: region.extent,
+                })
+                .collect();
+
+            // An image can't be in two layouts at once, so copies within the same image use the
+            // `General` layout.
             // TODO: Allow choosing layouts, but note that only Transfer*Optimal and General are
             // valid.
+            let (source_layout, destination_layout) = if source.inner().image.internal_object()
+                == destination.inner().image.internal_object()
+            {
+                (ImageLayout::General, ImageLayout::General)
+            } else {
+                (
+                    ImageLayout::TransferSrcOptimal,
+                    ImageLayout::TransferDstOptimal,
+                )
+            };
+
             self.inner.copy_image(
                 source,
-                ImageLayout::TransferSrcOptimal,
+                source_layout,
                 destination,
-                ImageLayout::TransferDstOptimal,
-                iter::once(copy),
+                destination_layout,
+                copies.into_iter(),
             )?;
             Ok(self)
         }
@@ -778,15 +892,12 @@ impl<P> AutoCommandBufferBuilder<P> {
             )?;
 
             let blit = UnsafeCommandBufferBuilderImageBlit {
-                // TODO:
-                aspect: if source.has_color() {
-                    UnsafeCommandBufferBuilderImageAspect {
-                        color: true,
-                        depth: false,
-                        stencil: false,
-                    }
-                } else {
-                    unimplemented!()
+                // Depth/stencil blits require both images to have the same format, so this blits
+                // all the aspects of the source.
+                aspect: UnsafeCommandBufferBuilderImageAspect {
+                    color: source.has_color(),
+                    depth: !source.has_color() && source.has_depth(),
+                    stencil: !source.has_color() && source.has_stencil(),
                 },
                 source_mip_level,
                 destination_mip_level,
@@ -811,6 +922,225 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Adds a command that blits multiple regions from an image to another.
+    ///
+    /// The restrictions are the same as `blit_image`, and are checked for each region. Contrary
+    /// to `blit_image`, the aspect to blit is chosen by each region, so that for example only the
+    /// depth of a depth-stencil image is blitted.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the source or the destination was not created with `device`.
+    ///
+    pub fn blit_image_regions<S, D, R>(
+        &mut self,
+        source: S,
+        destination: D,
+        regions: R,
+        filter: Filter,
+    ) -> Result<&mut Self, BlitImageError>
+    where
+        S: ImageAccess + Send + Sync + 'static,
+        D: ImageAccess + Send + Sync + 'static,
+        R: IntoIterator<Item = ImageBlitRegion>,
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.ensure_outside_render_pass()?;
+
+            let regions: Vec<_> = regions.into_iter().collect();
+            check_blit_image_regions(self.device(), &source, &destination, &regions, filter)?;
+
+            let blits: Vec<_> = regions
+                .into_iter()
+                .map(|region| UnsafeCommandBufferBuilderImageBlit {
+                    aspect: region.aspect.into(),
+                    source_mip_level: region.source_mip_level,
+                    destination_mip_level: region.destination_mip_level,
+                    source_base_array_layer: region.source_base_array_layer,
+                    destination_base_array_layer: region.destination_base_array_layer,
+                    layer_count: region.layer_count,
+                    source_top_left: region.source_top_left,
+                    source_bottom_right: region.source_bottom_right,
+                    destination_top_left: region.destination_top_left,
+                    destination_bottom_right: region.destination_bottom_right,
+                })
+                .collect();
+
+            self.inner.blit_image(
+                source,
+                ImageLayout::TransferSrcOptimal,
+                destination, // TODO: let choose layout
+                ImageLayout::TransferDstOptimal,
+                blits.into_iter(),
+                filter,
+            )?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that generates all the mipmap levels of an image from the first one.
+    ///
+    /// Each level is blitted to the next one with a linear filter, which requires the format of
+    /// the image to support blits and linear filtering. The image must have been created with
+    /// both the transfer source and transfer destination usages.
+    ///
+    /// The content of the first mipmap level is preserved and the content of the other levels
+    /// is overwritten.
+    pub fn generate_mipmaps<I>(&mut self, image: I) -> Result<&mut Self, GenerateMipmapsError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.ensure_outside_render_pass()?;
+
+            check_generate_mipmaps(self.device(), &image)?;
+
+            self.inner.generate_mipmaps(image)?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that copies the first layer and mipmap level of `source` to a swapchain
+    /// image, which is typically the last step of a frame rendered to an offscreen image.
+    ///
+    /// If the two images have the same format and dimensions, the image is copied with
+    /// `copy_image`. Otherwise it is blitted with `filter` in order to convert the format and
+    /// scale it to the dimensions of the swapchain image, which requires both formats to support
+    /// blits. The swapchain must have been created with the transfer destination usage.
+    ///
+    /// The layout transitions are handled automatically, and the swapchain image is transitioned
+    /// back to the `PresentSrc` layout at the end of the command buffer.
+    pub fn copy_image_to_swapchain_image<S, W>(
+        &mut self,
+        source: S,
+        destination: Arc<SwapchainImage<W>>,
+        filter: Filter,
+    ) -> Result<&mut Self, CopyImageToSwapchainImageError>
+    where
+        S: ImageAccess + Send + Sync + 'static,
+        W: Send + Sync + 'static,
+    {
+        let source_dimensions = source.dimensions().width_height();
+        let destination_dimensions = destination.dimensions().width_height();
+
+        if source.format() == destination.format() && source_dimensions == destination_dimensions {
+            self.copy_image(
+                source,
+                [0, 0, 0],
+                0,
+                0,
+                destination,
+                [0, 0, 0],
+                0,
+                0,
+                [source_dimensions[0], source_dimensions[1], 1],
+                1,
+            )?;
+        } else {
+            self.blit_image(
+                source,
+                [0, 0, 0],
+                [source_dimensions[0] as i32, source_dimensions[1] as i32, 1],
+                0,
+                0,
+                destination,
+                [0, 0, 0],
+                [
+                    destination_dimensions[0] as i32,
+                    destination_dimensions[1] as i32,
+                    1,
+                ],
+                0,
+                0,
+                1,
+                filter,
+            )?;
+        }
+
+        Ok(self)
+    }
+
+    /// Adds a command that resolves a multisampled color image into a non-multisampled one.
+    ///
+    /// Each sample of the source region is averaged (or combined in a way that depends on the
+    /// implementation) and written to the destination region, which has the same size. The source
+    /// and the destination must have the same format.
+    ///
+    /// If `layer_count` is greater than 1, the resolve will happen between each individual layer
+    /// as if they were separate images.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the source or the destination was not created with `device`.
+    ///
+    pub fn resolve_image<S, D>(
+        &mut self,
+        source: S,
+        source_offset: [i32; 3],
+        source_base_array_layer: u32,
+        source_mip_level: u32,
+        destination: D,
+        destination_offset: [i32; 3],
+        destination_base_array_layer: u32,
+        destination_mip_level: u32,
+        extent: [u32; 3],
+        layer_count: u32,
+    ) -> Result<&mut Self, ResolveImageError>
+    where
+        S: ImageAccess + Send + Sync + 'static,
+        D: ImageAccess + Send + Sync + 'static,
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.ensure_outside_render_pass()?;
+
+            check_resolve_image(
+                self.device(),
+                &source,
+                source_offset,
+                source_base_array_layer,
+                source_mip_level,
+                &destination,
+                destination_offset,
+                destination_base_array_layer,
+                destination_mip_level,
+                extent,
+                layer_count,
+            )?;
+
+            let resolve = UnsafeCommandBufferBuilderImageResolve {
+                source_mip_level,
+                destination_mip_level,
+                source_base_array_layer,
+                destination_base_array_layer,
+                layer_count,
+                source_offset,
+                destination_offset,
+                extent,
+            };
+
+            self.inner.resolve_image(
+                source,
+                ImageLayout::TransferSrcOptimal,
+                destination, // TODO: let choose layout
+                ImageLayout::TransferDstOptimal,
+                iter::once(resolve),
+            )?;
+            Ok(self)
+        }
+    }
+
     /// Adds a command that clears all the layers and mipmap levels of a color image with a
     /// specific value.
     ///
@@ -855,6 +1185,11 @@ impl<P> AutoCommandBufferBuilder<P> {
                 return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
             }
 
+            match color {
+                ClearValue::Float(_) | ClearValue::Int(_) | ClearValue::Uint(_) => {}
+                _ => panic!("The clear color is not a color value"),
+            };
+
             self.ensure_outside_render_pass()?;
             check_clear_color_image(
                 self.device(),
@@ -863,13 +1198,9 @@ impl<P> AutoCommandBufferBuilder<P> {
                 num_layers,
                 first_mipmap,
                 num_mipmaps,
+                color,
             )?;
 
-            match color {
-                ClearValue::Float(_) | ClearValue::Int(_) | ClearValue::Uint(_) => {}
-                _ => panic!("The clear color is not a color value"),
-            };
-
             let region = UnsafeCommandBufferBuilderColorImageClear {
                 base_mip_level: first_mipmap,
                 level_count: num_mipmaps,
@@ -888,6 +1219,102 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Adds a command that clears all the layers and mipmap levels of a depth and/or stencil
+    /// image with a specific value.
+    ///
+    /// `ClearValue::Depth` only clears the depth aspect of the image and `ClearValue::Stencil`
+    /// only clears the stencil aspect. `ClearValue::DepthStencil` clears both.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `clear_value` is not a depth and/or stencil value.
+    ///
+    pub fn clear_depth_stencil_image<I>(
+        &mut self,
+        image: I,
+        clear_value: ClearValue,
+    ) -> Result<&mut Self, ClearDepthStencilImageError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
+    {
+        let layers = image.dimensions().array_layers();
+        let levels = image.mipmap_levels();
+
+        self.clear_depth_stencil_image_dimensions(image, 0, layers, 0, levels, clear_value)
+    }
+
+    /// Adds a command that clears a depth and/or stencil image with a specific value.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `clear_value` is not a depth and/or stencil value.
+    ///
+    pub fn clear_depth_stencil_image_dimensions<I>(
+        &mut self,
+        image: I,
+        first_layer: u32,
+        num_layers: u32,
+        first_mipmap: u32,
+        num_mipmaps: u32,
+        clear_value: ClearValue,
+    ) -> Result<&mut Self, ClearDepthStencilImageError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            let aspect = match clear_value {
+                ClearValue::Depth(_) => UnsafeCommandBufferBuilderImageAspect {
+                    color: false,
+                    depth: true,
+                    stencil: false,
+                },
+                ClearValue::Stencil(_) => UnsafeCommandBufferBuilderImageAspect {
+                    color: false,
+                    depth: false,
+                    stencil: true,
+                },
+                ClearValue::DepthStencil(_) => UnsafeCommandBufferBuilderImageAspect {
+                    color: false,
+                    depth: true,
+                    stencil: true,
+                },
+                _ => panic!("The clear value is not a depth and/or stencil value"),
+            };
+
+            self.ensure_outside_render_pass()?;
+            check_clear_depth_stencil_image(
+                self.device(),
+                &image,
+                first_layer,
+                num_layers,
+                first_mipmap,
+                num_mipmaps,
+                clear_value,
+            )?;
+
+            let region = UnsafeCommandBufferBuilderDepthStencilImageClear {
+                aspect,
+                base_mip_level: first_mipmap,
+                level_count: num_mipmaps,
+                base_array_layer: first_layer,
+                layer_count: num_layers,
+            };
+
+            // TODO: let choose layout
+            self.inner.clear_depth_stencil_image(
+                image,
+                ImageLayout::TransferDstOptimal,
+                clear_value,
+                iter::once(region),
+            )?;
+            Ok(self)
+        }
+    }
+
     /// Adds a command that copies from a buffer to another.
     ///
     /// This command will copy from the source to the destination. If their size is not equal, then
@@ -912,6 +1339,39 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Adds a command that copies multiple regions from a buffer to another.
+    ///
+    /// The offsets and sizes of the regions are in bytes. Contrary to `copy_buffer`, the source
+    /// and the destination can be the same buffer, as long as none of the regions that are read
+    /// overlap one of the regions that are written.
+    #[inline]
+    pub fn copy_buffer_regions<S, D, R>(
+        &mut self,
+        source: S,
+        destination: D,
+        regions: R,
+    ) -> Result<&mut Self, CopyBufferError>
+    where
+        S: BufferAccess + Send + Sync + 'static,
+        D: BufferAccess + Send + Sync + 'static,
+        R: IntoIterator<Item = BufferCopyRegion>,
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+
+            let regions: Vec<_> = regions.into_iter().collect();
+            check_copy_buffer_regions(self.device(), &source, &destination, &regions)?;
+
+            let regions: Vec<_> = regions
+                .into_iter()
+                .map(|r| (r.source_offset, r.destination_offset, r.size))
+                .collect();
+            self.inner
+                .copy_buffer(source, destination, regions.into_iter())?;
+            Ok(self)
+        }
+    }
+
     /// Adds a command that copies from a buffer to an image.
     pub fn copy_buffer_to_image<S, D, Px>(
         &mut self,
@@ -944,47 +1404,59 @@ impl<P> AutoCommandBufferBuilder<P> {
         S: TypedBufferAccess<Content = [Px]> + Send + Sync + 'static,
         D: ImageAccess + Send + Sync + 'static,
         Format: AcceptsPixels<Px>,
+    {
+        let region = BufferImageCopyRegion {
+            buffer_offset: 0,
+            buffer_row_length: 0,
+            buffer_image_height: 0,
+            image_aspect: default_copy_aspect(&destination),
+            image_mip_level: mipmap,
+            image_base_array_layer: first_layer,
+            image_layer_count: num_layers,
+            image_offset: offset,
+            image_extent: size,
+        };
+
+        self.copy_buffer_to_image_regions(source, destination, iter::once(region))
+    }
+
+    /// Adds a command that copies regions of a buffer to regions of an image.
+    ///
+    /// Contrary to `copy_buffer_to_image_dimensions`, each region can start at an offset in the
+    /// buffer and have a row pitch that is larger than the width of the copy. This makes it
+    /// possible to upload a sub-rectangle of a larger image, for example to a texture atlas.
+    pub fn copy_buffer_to_image_regions<S, D, Px, R>(
+        &mut self,
+        source: S,
+        destination: D,
+        regions: R,
+    ) -> Result<&mut Self, CopyBufferImageError>
+    where
+        S: TypedBufferAccess<Content = [Px]> + Send + Sync + 'static,
+        D: ImageAccess + Send + Sync + 'static,
+        Format: AcceptsPixels<Px>,
+        R: IntoIterator<Item = BufferImageCopyRegion>,
     {
         unsafe {
             self.ensure_outside_render_pass()?;
 
-            check_copy_buffer_image(
-                self.device(),
-                &source,
-                &destination,
-                CheckCopyBufferImageTy::BufferToImage,
-                offset,
-                size,
-                first_layer,
-                num_layers,
-                mipmap,
-            )?;
-
-            let copy = UnsafeCommandBufferBuilderBufferImageCopy {
-                buffer_offset: 0,
-                buffer_row_length: 0,
-                buffer_image_height: 0,
-                image_aspect: if destination.has_color() {
-                    UnsafeCommandBufferBuilderImageAspect {
-                        color: true,
-                        depth: false,
-                        stencil: false,
-                    }
-                } else {
-                    unimplemented!()
-                },
-                image_mip_level: mipmap,
-                image_base_array_layer: first_layer,
-                image_layer_count: num_layers,
-                image_offset: [offset[0] as i32, offset[1] as i32, offset[2] as i32],
-                image_extent: size,
-            };
+            let mut copies = Vec::new();
+            for region in regions {
+                check_copy_buffer_image_region(
+                    self.device(),
+                    &source,
+                    &destination,
+                    CheckCopyBufferImageTy::BufferToImage,
+                    &region,
+                )?;
+                copies.push(buffer_image_copy(region, mem::size_of::<Px>()));
+            }
 
             self.inner.copy_buffer_to_image(
                 source,
                 destination,
                 ImageLayout::TransferDstOptimal, // TODO: let choose layout
-                iter::once(copy),
+                copies.into_iter(),
             )?;
             Ok(self)
         }
@@ -1026,48 +1498,70 @@ impl<P> AutoCommandBufferBuilder<P> {
         S: ImageAccess + Send + Sync + 'static,
         D: TypedBufferAccess<Content = [Px]> + Send + Sync + 'static,
         Format: AcceptsPixels<Px>,
+    {
+        let region = BufferImageCopyRegion {
+            buffer_offset: 0,
+            buffer_row_length: 0,
+            buffer_image_height: 0,
+            image_aspect: default_copy_aspect(&source),
+            image_mip_level: mipmap,
+            image_base_array_layer: first_layer,
+            image_layer_count: num_layers,
+            image_offset: offset,
+            image_extent: size,
+        };
+
+        self.copy_image_to_buffer_regions(source, destination, iter::once(region))
+    }
+
+    /// Adds a command that copies regions of an image to regions of a buffer.
+    ///
+    /// Each region can start at an offset in the buffer and have a row pitch that is larger than
+    /// the width of the copy. Use a row length of 0 to get tightly-packed data, for example when
+    /// reading back a screenshot.
+    pub fn copy_image_to_buffer_regions<S, D, Px, R>(
+        &mut self,
+        source: S,
+        destination: D,
+        regions: R,
+    ) -> Result<&mut Self, CopyBufferImageError>
+    where
+        S: ImageAccess + Send + Sync + 'static,
+        D: TypedBufferAccess<Content = [Px]> + Send + Sync + 'static,
+        Format: AcceptsPixels<Px>,
+        R: IntoIterator<Item = BufferImageCopyRegion>,
     {
         unsafe {
             self.ensure_outside_render_pass()?;
 
-            check_copy_buffer_image(
-                self.device(),
-                &destination,
-                &source,
-                CheckCopyBufferImageTy::ImageToBuffer,
-                offset,
-                size,
-                first_layer,
-                num_layers,
-                mipmap,
-            )?;
-
-            let copy = UnsafeCommandBufferBuilderBufferImageCopy {
-                buffer_offset: 0,
-                buffer_row_length: 0,
-                buffer_image_height: 0,
-                image_aspect: UnsafeCommandBufferBuilderImageAspect {
-                    color: source.has_color(),
-                    depth: source.has_depth(),
-                    stencil: source.has_stencil(),
-                },
-                image_mip_level: mipmap,
-                image_base_array_layer: first_layer,
-                image_layer_count: num_layers,
-                image_offset: [offset[0] as i32, offset[1] as i32, offset[2] as i32],
-                image_extent: size,
-            };
+            let mut copies = Vec::new();
+            for region in regions {
+                check_copy_buffer_image_region(
+                    self.device(),
+                    &destination,
+                    &source,
+                    CheckCopyBufferImageTy::ImageToBuffer,
+                    &region,
+                )?;
+                copies.push(buffer_image_copy(region, mem::size_of::<Px>()));
+            }
 
             self.inner.copy_image_to_buffer(
                 source,
                 ImageLayout::TransferSrcOptimal,
                 destination, // TODO: let choose layout
-                iter::once(copy),
+                copies.into_iter(),
             )?;
             Ok(self)
         }
     }
 
+    /// Dispatches a compute shader with `dimensions` work groups in each dimension.
+    ///
+    /// `pipeline` is bound if it isn't already, then `sets` and `constants` are bound with the
+    /// same checks as when drawing: the descriptor sets and push constants must match the
+    /// layout of the pipeline. Only descriptor sets that changed since the last dispatch are
+    /// re-bound.
     #[inline]
     pub fn dispatch<Cp, S, Pc>(
         &mut self,
@@ -1094,208 +1588,774 @@ impl<P> AutoCommandBufferBuilder<P> {
                 self.state_cacher.bind_compute_pipeline(&pipeline)
             {
                 self.inner.bind_pipeline_compute(pipeline.clone());
+            } else {
+                self.statistics.elided_binds += 1;
             }
 
-            push_constants(&mut self.inner, pipeline.clone(), constants);
+            push_constants(
+                &mut self.inner,
+                &mut self.statistics,
+                pipeline.clone(),
+                constants,
+            );
             descriptor_sets(
                 &mut self.inner,
                 &mut self.state_cacher,
-                false,
+                &mut self.statistics,
+                PipelineBindPoint::Compute,
                 pipeline.clone(),
                 sets,
             )?;
 
             self.inner.dispatch(dimensions);
+            self.statistics.dispatches += 1;
             Ok(self)
         }
     }
 
-    /// Draw once, using the `vertex_buffer`.
+    /// Same as `dispatch`, except that the coordinates of the work groups start at `base`
+    /// instead of zero. The value of the `WorkgroupId` built-in in the shader ranges from `base`
+    /// to `base + dimensions`.
     ///
-    /// To use only some data in the buffer, wrap it in a `vulkano::buffer::BufferSlice`.
+    /// Requires Vulkan 1.1 or the `khr_device_group` extension, and a pipeline created with
+    /// `ComputePipeline::with_dispatch_base`.
     #[inline]
-    pub fn draw<V, Gp, S, Pc>(
+    pub fn dispatch_base<Cp, S, Pc>(
         &mut self,
-        pipeline: Gp,
-        dynamic: &DynamicState,
-        vertex_buffer: V,
+        base: [u32; 3],
+        dimensions: [u32; 3],
+        pipeline: Cp,
         sets: S,
         constants: Pc,
-    ) -> Result<&mut Self, DrawError>
+    ) -> Result<&mut Self, DispatchError>
     where
-        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+        Cp: ComputePipelineAbstract + Send + Sync + 'static + Clone, // TODO: meh for Clone
         S: DescriptorSetsCollection,
     {
         unsafe {
-            // TODO: must check that pipeline is compatible with render pass
+            if !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
 
-            self.ensure_inside_render_pass_inline(&pipeline)?;
-            check_dynamic_state_validity(&pipeline, dynamic)?;
+            self.ensure_outside_render_pass()?;
             check_push_constants_validity(&pipeline, &constants)?;
             check_descriptor_sets_validity(&pipeline, &sets)?;
-            let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+            check_dispatch_base(pipeline.device(), &pipeline, base, dimensions)?;
 
             if let StateCacherOutcome::NeedChange =
-                self.state_cacher.bind_graphics_pipeline(&pipeline)
+                self.state_cacher.bind_compute_pipeline(&pipeline)
             {
-                self.inner.bind_pipeline_graphics(pipeline.clone());
+                self.inner.bind_pipeline_compute(pipeline.clone());
+            } else {
+                self.statistics.elided_binds += 1;
             }
 
-            let dynamic = self.state_cacher.dynamic_state(dynamic);
-
-            push_constants(&mut self.inner, pipeline.clone(), constants);
-            set_state(&mut self.inner, &dynamic);
+            push_constants(
+                &mut self.inner,
+                &mut self.statistics,
+                pipeline.clone(),
+                constants,
+            );
             descriptor_sets(
                 &mut self.inner,
                 &mut self.state_cacher,
-                true,
+                &mut self.statistics,
+                PipelineBindPoint::Compute,
                 pipeline.clone(),
                 sets,
             )?;
-            vertex_buffers(
-                &mut self.inner,
-                &mut self.state_cacher,
-                vb_infos.vertex_buffers,
-            )?;
-
-            debug_assert!(self.graphics_allowed);
 
-            self.inner.draw(
-                vb_infos.vertex_count as u32,
-                vb_infos.instance_count as u32,
-                0,
-                0,
-            );
+            self.inner.dispatch_base(base, dimensions);
+            self.statistics.dispatches += 1;
             Ok(self)
         }
     }
 
-    /// Draw once, using the `vertex_buffer` and the `index_buffer`.
+    /// Dispatches a compute shader, reading the number of work groups from the
+    /// `vulkano::command_buffer::DispatchIndirectCommand` struct in `indirect_buffer`.
     ///
     /// To use only some data in a buffer, wrap it in a `vulkano::buffer::BufferSlice`.
     #[inline]
-    pub fn draw_indexed<V, Gp, S, Pc, Ib, I>(
+    pub fn dispatch_indirect<Cp, S, Pc, Inb>(
         &mut self,
-        pipeline: Gp,
-        dynamic: &DynamicState,
-        vertex_buffer: V,
-        index_buffer: Ib,
+        indirect_buffer: Inb,
+        pipeline: Cp,
         sets: S,
         constants: Pc,
-    ) -> Result<&mut Self, DrawIndexedError>
+    ) -> Result<&mut Self, DispatchIndirectError>
     where
-        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+        Cp: ComputePipelineAbstract + Send + Sync + 'static + Clone, // TODO: meh for Clone
         S: DescriptorSetsCollection,
-        Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
-        I: Index + 'static,
+        Inb: BufferAccess
+            + TypedBufferAccess<Content = [DispatchIndirectCommand]>
+            + Send
+            + Sync
+            + 'static,
     {
         unsafe {
-            // TODO: must check that pipeline is compatible with render pass
+            if !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
 
-            self.ensure_inside_render_pass_inline(&pipeline)?;
-            let ib_infos = check_index_buffer(self.device(), &index_buffer)?;
-            check_dynamic_state_validity(&pipeline, dynamic)?;
+            self.ensure_outside_render_pass()?;
             check_push_constants_validity(&pipeline, &constants)?;
             check_descriptor_sets_validity(&pipeline, &sets)?;
-            let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
-
-            if let StateCacherOutcome::NeedChange =
-                self.state_cacher.bind_graphics_pipeline(&pipeline)
-            {
-                self.inner.bind_pipeline_graphics(pipeline.clone());
-            }
+            check_indirect_buffer(self.device(), &indirect_buffer)?;
 
             if let StateCacherOutcome::NeedChange =
-                self.state_cacher.bind_index_buffer(&index_buffer, I::ty())
+                self.state_cacher.bind_compute_pipeline(&pipeline)
             {
-                self.inner.bind_index_buffer(index_buffer, I::ty())?;
+                self.inner.bind_pipeline_compute(pipeline.clone());
+            } else {
+                self.statistics.elided_binds += 1;
             }
 
-            let dynamic = self.state_cacher.dynamic_state(dynamic);
-
-            push_constants(&mut self.inner, pipeline.clone(), constants);
-            set_state(&mut self.inner, &dynamic);
+            push_constants(
+                &mut self.inner,
+                &mut self.statistics,
+                pipeline.clone(),
+                constants,
+            );
             descriptor_sets(
                 &mut self.inner,
                 &mut self.state_cacher,
-                true,
+                &mut self.statistics,
+                PipelineBindPoint::Compute,
                 pipeline.clone(),
                 sets,
             )?;
-            vertex_buffers(
-                &mut self.inner,
-                &mut self.state_cacher,
-                vb_infos.vertex_buffers,
-            )?;
-            // TODO: how to handle an index out of range of the vertex buffers?
-
-            debug_assert!(self.graphics_allowed);
 
-            self.inner.draw_indexed(
-                ib_infos.num_indices as u32,
-                vb_infos.instance_count as u32,
-                0,
-                0,
-                0,
-            );
+            self.inner.dispatch_indirect(indirect_buffer)?;
+            self.statistics.dispatches += 1;
             Ok(self)
         }
     }
 
-    /// Performs multiple draws, one draw for each `vulkano::command_buffer::DrawIndirectCommand` struct in `indirect_buffer`.
-    /// The `vertex_buffer` is used by all draws.
+    /// Adds the commands that cull the first `draw_count` draws of `input_draws` against the
+    /// frustum described by `planes`. See the `culling` module.
     ///
-    /// To use only some data in a buffer, wrap it in a `vulkano::buffer::BufferSlice`.
-    #[inline]
-    pub fn draw_indirect<V, Gp, S, Pc, Ib>(
+    /// The draws whose bounds in `bounds` are visible are written to `output_draws`, and their
+    /// number is written to `count_buffer`. Once this command has executed, `output_draws` and
+    /// `count_buffer` can be passed to `draw_indexed_indirect_count`.
+    ///
+    /// All the buffers must have the storage buffer usage. `count_buffer` must also have the
+    /// transfer destination usage, because it is reset with `fill_buffer` before the culling. A
+    /// `FillBufferError` is returned otherwise.
+    pub fn cull_draws<Bb, Ib, Ob, Cb>(
         &mut self,
-        pipeline: Gp,
-        dynamic: &DynamicState,
-        vertex_buffer: V,
-        indirect_buffer: Ib,
-        sets: S,
-        constants: Pc,
-    ) -> Result<&mut Self, DrawIndirectError>
+        pipeline: &culling::CullingPipeline,
+        bounds: Bb,
+        input_draws: Ib,
+        output_draws: Ob,
+        count_buffer: Cb,
+        planes: [[f32; 4]; 6],
+        draw_count: u32,
+    ) -> Result<&mut Self, CullDrawsError>
     where
-        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
-        S: DescriptorSetsCollection,
-        Ib: BufferAccess
-            + TypedBufferAccess<Content = [DrawIndirectCommand]>
-            + Send
-            + Sync
-            + 'static,
+        Bb: TypedBufferAccess<Content = [culling::CullingBounds]> + Send + Sync + 'static,
+        Ib: TypedBufferAccess<Content = [DrawIndexedIndirectCommand]> + Send + Sync + 'static,
+        Ob: TypedBufferAccess<Content = [DrawIndexedIndirectCommand]> + Send + Sync + 'static,
+        Cb: TypedBufferAccess<Content = u32> + Send + Sync + 'static + Clone,
     {
-        unsafe {
+        culling::check_cull_draws(
+            bounds.len(),
+            input_draws.len(),
+            output_draws.len(),
+            draw_count,
+        )?;
+
+        let layout = pipeline.pipeline().descriptor_set_layout(0).unwrap();
+        let set = PersistentDescriptorSet::start(layout.clone())
+            .add_buffer(bounds)?
+            .add_buffer(input_draws)?
+            .add_buffer(output_draws)?
+            .add_buffer(count_buffer.clone())?
+            .build()?;
+
+        self.fill_buffer(count_buffer, 0)?;
+
+        if draw_count == 0 {
+            return Ok(self);
+        }
+
+        let num_groups = draw_count / culling::CULLING_WORK_GROUP_SIZE
+            + if draw_count % culling::CULLING_WORK_GROUP_SIZE != 0 {
+                1
+            } else {
+                0
+            };
+        self.dispatch(
+            [num_groups, 1, 1],
+            pipeline.pipeline().clone(),
+            set,
+            culling::CullingPushConstants { planes, draw_count },
+        )?;
+        Ok(self)
+    }
+
+    /// Binds descriptor sets for the draw commands that follow.
+    ///
+    /// The sets are bound to the graphics bind point, with the layout of `pipeline`. Since the
+    /// compute pipelines use another bind point, only graphics pipelines are accepted. The sets
+    /// must be compatible with the layout of the pipeline.
+    ///
+    /// Just like `bind_vertex_buffers`, this is only needed if you want to detect errors before
+    /// recording the draw commands, which bind the sets passed to them themselves.
+    ///
+    /// Binding the sets of a compute pipeline to the graphics bind point doesn't compile:
+    ///
+    /// ```compile_fail
+    /// # use std::sync::Arc;
+    /// # use vulkano::command_buffer::AutoCommandBufferBuilder;
+    /// # use vulkano::pipeline::ComputePipelineAbstract;
+    /// # let mut builder: AutoCommandBufferBuilder = return;
+    /// # let pipeline: Arc<ComputePipelineAbstract + Send + Sync> = return;
+    /// builder.bind_descriptor_sets_graphics(pipeline, ()).unwrap();
+    /// ```
+    #[inline]
+    pub fn bind_descriptor_sets_graphics<Gp, S>(
+        &mut self,
+        pipeline: Gp,
+        sets: S,
+    ) -> Result<&mut Self, BindDescriptorSetsError>
+    where
+        Gp: GraphicsPipelineAbstract + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_descriptor_sets_validity(&pipeline, &sets)?;
+            descriptor_sets(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                PipelineBindPoint::Graphics,
+                pipeline,
+                sets,
+            )?;
+            Ok(self)
+        }
+    }
+
+    /// Binds descriptor sets for the dispatch commands that follow.
+    ///
+    /// This is the same as `bind_descriptor_sets_graphics`, except that the sets are bound to
+    /// the compute bind point and that only compute pipelines are accepted.
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use vulkano::command_buffer::AutoCommandBufferBuilder;
+    /// # use vulkano::pipeline::ComputePipelineAbstract;
+    /// # let mut builder: AutoCommandBufferBuilder = return;
+    /// # let pipeline: Arc<ComputePipelineAbstract + Send + Sync> = return;
+    /// builder.bind_descriptor_sets_compute(pipeline, ()).unwrap();
+    /// ```
+    #[inline]
+    pub fn bind_descriptor_sets_compute<Cp, S>(
+        &mut self,
+        pipeline: Cp,
+        sets: S,
+    ) -> Result<&mut Self, BindDescriptorSetsError>
+    where
+        Cp: ComputePipelineAbstract + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+    {
+        unsafe {
+            if !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_descriptor_sets_validity(&pipeline, &sets)?;
+            descriptor_sets(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                PipelineBindPoint::Compute,
+                pipeline,
+                sets,
+            )?;
+            Ok(self)
+        }
+    }
+
+    /// Binds vertex buffers for the draw commands that follow.
+    ///
+    /// The buffers are checked against the vertex input definition of `pipeline`: they must have
+    /// the `vertex_buffer` usage, there must be one buffer per binding, and their size must be a
+    /// multiple of the stride of their binding.
+    ///
+    /// The draw commands perform the same checks and bind their vertex buffers themselves, but
+    /// they skip the binding if the same buffers are already bound. Calling this method is
+    /// therefore only needed if you want to detect errors before recording the draw commands.
+    #[inline]
+    pub fn bind_vertex_buffers<Gp, V>(
+        &mut self,
+        pipeline: &Gp,
+        vertex_buffer: V,
+    ) -> Result<&mut Self, BindVertexBuffersError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V>,
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            let vb_infos = check_vertex_buffers(pipeline, vertex_buffer)?;
+            vertex_buffers(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                vb_infos.vertex_buffers,
+            )?;
+            Ok(self)
+        }
+    }
+
+    /// Binds an index buffer for the indexed draw commands that follow.
+    ///
+    /// The buffer must have the `index_buffer` usage, and its offset must be a multiple of the
+    /// size of `I`. The index type passed to Vulkan is deduced from `I`.
+    ///
+    /// Just like `bind_vertex_buffers`, this is only needed if you want to detect errors before
+    /// recording the draw commands.
+    #[inline]
+    pub fn bind_index_buffer<Ib, I>(
+        &mut self,
+        index_buffer: Ib,
+    ) -> Result<&mut Self, BindIndexBufferError>
+    where
+        Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
+        I: Index + 'static,
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_index_buffer(self.device(), &index_buffer)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_index_buffer(&index_buffer, I::ty())
+            {
+                self.inner.bind_index_buffer(index_buffer, I::ty())?;
+            } else {
+                self.statistics.elided_binds += 1;
+            }
+
+            Ok(self)
+        }
+    }
+
+    /// Sets the viewports used by the draw commands that follow, starting with the first one.
+    ///
+    /// The bound pipeline must have been created with dynamic viewports, otherwise the next draw
+    /// command returns an error. The number of viewports must match the number of viewports of
+    /// the pipeline.
+    ///
+    /// Viewports passed in the `DynamicState` of a draw command take precedence over the ones set
+    /// with this method.
+    #[inline]
+    pub fn set_viewport<I>(&mut self, viewports: I) -> &mut Self
+    where
+        I: IntoIterator<Item = Viewport>,
+    {
+        self.dynamic_state.viewports = Some(viewports.into_iter().collect());
+        self
+    }
+
+    /// Sets the scissor boxes used by the draw commands that follow, starting with the first one.
+    ///
+    /// The bound pipeline must have been created with dynamic scissors, otherwise the next draw
+    /// command returns an error.
+    #[inline]
+    pub fn set_scissor<I>(&mut self, scissors: I) -> &mut Self
+    where
+        I: IntoIterator<Item = Scissor>,
+    {
+        self.dynamic_state.scissors = Some(scissors.into_iter().collect());
+        self
+    }
+
+    /// Sets the line width used by the draw commands that follow.
+    ///
+    /// The bound pipeline must have been created with a dynamic line width. Values other than
+    /// 1.0 require the `wide_lines` feature.
+    #[inline]
+    pub fn set_line_width(&mut self, line_width: f32) -> &mut Self {
+        self.dynamic_state.line_width = Some(line_width);
+        self
+    }
+
+    /// Sets the depth bias used by the draw commands that follow.
+    ///
+    /// The bound pipeline must have been created with a dynamic depth bias. A `clamp` different
+    /// from 0.0 requires the `depth_bias_clamp` feature.
+    #[inline]
+    pub fn set_depth_bias(
+        &mut self,
+        constant_factor: f32,
+        clamp: f32,
+        slope_factor: f32,
+    ) -> &mut Self {
+        self.dynamic_state.depth_bias = Some(DepthBias {
+            constant_factor,
+            clamp,
+            slope_factor,
+        });
+        self
+    }
+
+    /// Sets the depth bounds used by the draw commands that follow.
+    ///
+    /// The bound pipeline must have been created with dynamic depth bounds. The bounds must be
+    /// between 0.0 and 1.0.
+    #[inline]
+    pub fn set_depth_bounds(&mut self, bounds: Range<f32>) -> &mut Self {
+        self.dynamic_state.depth_bounds = Some(bounds);
+        self
+    }
+
+    /// Sets the blend constants used by the draw commands that follow.
+    ///
+    /// The bound pipeline must have been created with dynamic blend constants.
+    #[inline]
+    pub fn set_blend_constants(&mut self, constants: [f32; 4]) -> &mut Self {
+        self.dynamic_state.blend_constants = Some(constants);
+        self
+    }
+
+    /// Sets the stencil compare mask used by the draw commands that follow.
+    ///
+    /// The bound pipeline must have been created with a dynamic stencil compare mask.
+    #[inline]
+    pub fn set_stencil_compare_mask(&mut self, compare_mask: DynamicStencilValue) -> &mut Self {
+        self.dynamic_state.compare_mask = Some(compare_mask);
+        self
+    }
+
+    /// Sets the stencil write mask used by the draw commands that follow.
+    ///
+    /// The bound pipeline must have been created with a dynamic stencil write mask.
+    #[inline]
+    pub fn set_stencil_write_mask(&mut self, write_mask: DynamicStencilValue) -> &mut Self {
+        self.dynamic_state.write_mask = Some(write_mask);
+        self
+    }
+
+    /// Sets the stencil reference used by the draw commands that follow.
+    ///
+    /// The bound pipeline must have been created with a dynamic stencil reference.
+    #[inline]
+    pub fn set_stencil_reference(&mut self, reference: DynamicStencilValue) -> &mut Self {
+        self.dynamic_state.reference = Some(reference);
+        self
+    }
+
+    // Builds the dynamic state to use for a draw command with `pipeline`.
+    //
+    // The states passed to the draw command take precedence over the ones set with the `set_*`
+    // methods. States that the pipeline declares as dynamic and that were already set by a
+    // previous draw command are reused.
+    //
+    // Dynamic state isn't inherited from the primary command buffer, so in a secondary command
+    // buffer every state that the pipeline declares as dynamic must have been set in the
+    // secondary command buffer itself.
+    fn draw_dynamic_state<Gp>(
+        &self,
+        pipeline: &Gp,
+        dynamic: &DynamicState,
+    ) -> Result<DynamicState, AutoCommandBufferBuilderContextError>
+    where
+        Gp: GraphicsPipelineAbstract,
+    {
+        let current = self.state_cacher.current_dynamic_state();
+        let mut state = DynamicState::none();
+
+        macro_rules! merge {
+            ($field:ident, $has_dynamic:ident) => {
+                state.$field = dynamic
+                    .$field
+                    .clone()
+                    .or_else(|| self.dynamic_state.$field.clone());
+                if state.$field.is_none() && pipeline.$has_dynamic() {
+                    state.$field = current.$field.clone();
+
+                    if state.$field.is_none() && self.secondary_cb {
+                        return Err(AutoCommandBufferBuilderContextError::DynamicStateNotInherited);
+                    }
+                }
+            };
+        }
+
+        merge!(line_width, has_dynamic_line_width);
+        merge!(viewports, has_dynamic_viewports);
+        merge!(scissors, has_dynamic_scissors);
+        merge!(compare_mask, has_dynamic_stencil_compare_mask);
+        merge!(write_mask, has_dynamic_stencil_write_mask);
+        merge!(reference, has_dynamic_stencil_reference);
+        merge!(depth_bias, has_dynamic_depth_bias);
+        merge!(depth_bounds, has_dynamic_depth_bounds);
+        merge!(blend_constants, has_dynamic_blend_constants);
+
+        Ok(state)
+    }
+
+    /// Draw once, using the `vertex_buffer`.
+    ///
+    /// To use only some data in the buffer, wrap it in a `vulkano::buffer::BufferSlice`.
+    #[inline]
+    pub fn draw<V, Gp, S, Pc>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+        S: DescriptorSetsCollection,
+    {
+        unsafe {
+            // TODO: must check that pipeline is compatible with render pass
+
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            let dynamic = self.draw_dynamic_state(&pipeline, dynamic)?;
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
+            check_push_constants_validity(&pipeline, &constants)?;
+            check_descriptor_sets_validity(&pipeline, &sets)?;
+            let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            } else {
+                self.statistics.elided_binds += 1;
+            }
+
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
+
+            push_constants(
+                &mut self.inner,
+                &mut self.statistics,
+                pipeline.clone(),
+                constants,
+            );
+            set_state(&mut self.inner, &dynamic);
+            self.dynamic_state = DynamicState::none();
+            descriptor_sets(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                PipelineBindPoint::Graphics,
+                pipeline.clone(),
+                sets,
+            )?;
+            vertex_buffers(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                vb_infos.vertex_buffers,
+            )?;
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner.draw(
+                vb_infos.vertex_count as u32,
+                vb_infos.instance_count as u32,
+                0,
+                0,
+            );
+            self.statistics.draws += 1;
+            Ok(self)
+        }
+    }
+
+    /// Draw once, using the `vertex_buffer`, with a pipeline whose vertex buffers, descriptor sets
+    /// and push constants have types known at compile time.
+    ///
+    /// This is the same as `draw`, except that passing a vertex buffer, descriptor sets or push
+    /// constants whose types don't match the ones of the `TypedGraphicsPipeline` fails to compile.
+    #[inline]
+    pub fn draw_typed<V, Gp, L>(
+        &mut self,
+        pipeline: &TypedGraphicsPipeline<Gp, V, L>,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        sets: L::Sets,
+        constants: L::PushConstants,
+    ) -> Result<&mut Self, DrawError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone,
+        L: TypedPipelineLayout,
+    {
+        self.draw(
+            pipeline.pipeline().clone(),
+            dynamic,
+            vertex_buffer,
+            sets,
+            constants,
+        )
+    }
+
+    /// Draw once, using the `vertex_buffer` and the `index_buffer`.
+    ///
+    /// To use only some data in a buffer, wrap it in a `vulkano::buffer::BufferSlice`.
+    #[inline]
+    pub fn draw_indexed<V, Gp, S, Pc, Ib, I>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        index_buffer: Ib,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawIndexedError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+        S: DescriptorSetsCollection,
+        Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
+        I: Index + 'static,
+    {
+        unsafe {
+            // TODO: must check that pipeline is compatible with render pass
+
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            let ib_infos = check_index_buffer(self.device(), &index_buffer)?;
+            let dynamic = self.draw_dynamic_state(&pipeline, dynamic)?;
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
+            check_push_constants_validity(&pipeline, &constants)?;
+            check_descriptor_sets_validity(&pipeline, &sets)?;
+            let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            } else {
+                self.statistics.elided_binds += 1;
+            }
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_index_buffer(&index_buffer, I::ty())
+            {
+                self.inner.bind_index_buffer(index_buffer, I::ty())?;
+            } else {
+                self.statistics.elided_binds += 1;
+            }
+
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
+
+            push_constants(
+                &mut self.inner,
+                &mut self.statistics,
+                pipeline.clone(),
+                constants,
+            );
+            set_state(&mut self.inner, &dynamic);
+            self.dynamic_state = DynamicState::none();
+            descriptor_sets(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                PipelineBindPoint::Graphics,
+                pipeline.clone(),
+                sets,
+            )?;
+            vertex_buffers(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                vb_infos.vertex_buffers,
+            )?;
+            // TODO: how to handle an index out of range of the vertex buffers?
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner.draw_indexed(
+                ib_infos.num_indices as u32,
+                vb_infos.instance_count as u32,
+                0,
+                0,
+                0,
+            );
+            self.statistics.draws += 1;
+            Ok(self)
+        }
+    }
+
+    /// Performs multiple draws, one draw for each `vulkano::command_buffer::DrawIndirectCommand` struct in `indirect_buffer`.
+    /// The `vertex_buffer` is used by all draws.
+    ///
+    /// To use only some data in a buffer, wrap it in a `vulkano::buffer::BufferSlice`.
+    #[inline]
+    pub fn draw_indirect<V, Gp, S, Pc, Ib>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        indirect_buffer: Ib,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawIndirectError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+        S: DescriptorSetsCollection,
+        Ib: BufferAccess
+            + TypedBufferAccess<Content = [DrawIndirectCommand]>
+            + Send
+            + Sync
+            + 'static,
+    {
+        unsafe {
             // TODO: must check that pipeline is compatible with render pass
 
             self.ensure_inside_render_pass_inline(&pipeline)?;
-            check_dynamic_state_validity(&pipeline, dynamic)?;
+            let dynamic = self.draw_dynamic_state(&pipeline, dynamic)?;
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
             check_push_constants_validity(&pipeline, &constants)?;
             check_descriptor_sets_validity(&pipeline, &sets)?;
             let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+            check_indirect_buffer(self.device(), &indirect_buffer)?;
 
             let draw_count = indirect_buffer.len() as u32;
+            check_indirect_draw_count(self.device(), draw_count)?;
 
             if let StateCacherOutcome::NeedChange =
                 self.state_cacher.bind_graphics_pipeline(&pipeline)
             {
                 self.inner.bind_pipeline_graphics(pipeline.clone());
+            } else {
+                self.statistics.elided_binds += 1;
             }
 
-            let dynamic = self.state_cacher.dynamic_state(dynamic);
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
 
-            push_constants(&mut self.inner, pipeline.clone(), constants);
+            push_constants(
+                &mut self.inner,
+                &mut self.statistics,
+                pipeline.clone(),
+                constants,
+            );
             set_state(&mut self.inner, &dynamic);
+            self.dynamic_state = DynamicState::none();
             descriptor_sets(
                 &mut self.inner,
                 &mut self.state_cacher,
-                true,
+                &mut self.statistics,
+                PipelineBindPoint::Graphics,
                 pipeline.clone(),
                 sets,
             )?;
             vertex_buffers(
                 &mut self.inner,
                 &mut self.state_cacher,
+                &mut self.statistics,
                 vb_infos.vertex_buffers,
             )?;
 
@@ -1306,6 +2366,7 @@ impl<P> AutoCommandBufferBuilder<P> {
                 draw_count,
                 mem::size_of::<DrawIndirectCommand>() as u32,
             )?;
+            self.statistics.draws += 1;
             Ok(self)
         }
     }
@@ -1340,50 +2401,381 @@ impl<P> AutoCommandBufferBuilder<P> {
             // TODO: must check that pipeline is compatible with render pass
 
             self.ensure_inside_render_pass_inline(&pipeline)?;
-            let ib_infos = check_index_buffer(self.device(), &index_buffer)?;
-            check_dynamic_state_validity(&pipeline, dynamic)?;
+            let ib_infos = check_index_buffer(self.device(), &index_buffer)?;
+            let dynamic = self.draw_dynamic_state(&pipeline, dynamic)?;
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
+            check_push_constants_validity(&pipeline, &constants)?;
+            check_descriptor_sets_validity(&pipeline, &sets)?;
+            let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+            check_indirect_buffer(self.device(), &indirect_buffer)?;
+
+            let draw_count = indirect_buffer.len() as u32;
+            check_indirect_draw_count(self.device(), draw_count)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            } else {
+                self.statistics.elided_binds += 1;
+            }
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_index_buffer(&index_buffer, I::ty())
+            {
+                self.inner.bind_index_buffer(index_buffer, I::ty())?;
+            } else {
+                self.statistics.elided_binds += 1;
+            }
+
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
+
+            push_constants(
+                &mut self.inner,
+                &mut self.statistics,
+                pipeline.clone(),
+                constants,
+            );
+            set_state(&mut self.inner, &dynamic);
+            self.dynamic_state = DynamicState::none();
+            descriptor_sets(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                PipelineBindPoint::Graphics,
+                pipeline.clone(),
+                sets,
+            )?;
+            vertex_buffers(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                vb_infos.vertex_buffers,
+            )?;
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner.draw_indexed_indirect(
+                indirect_buffer,
+                draw_count,
+                mem::size_of::<DrawIndexedIndirectCommand>() as u32,
+            )?;
+            self.statistics.draws += 1;
+            Ok(self)
+        }
+    }
+
+    /// Same as `draw_indirect`, except that the number of draws is read by the GPU from
+    /// `count_buffer`, and is at most the number of `DrawIndirectCommand` structs in
+    /// `indirect_buffer`, which must not exceed the `max_draw_indirect_count` limit.
+    ///
+    /// Requires the `khr_draw_indirect_count` extension.
+    #[inline]
+    pub fn draw_indirect_count<V, Gp, S, Pc, Ib, Cb>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        indirect_buffer: Ib,
+        count_buffer: Cb,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawIndirectError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+        S: DescriptorSetsCollection,
+        Ib: BufferAccess
+            + TypedBufferAccess<Content = [DrawIndirectCommand]>
+            + Send
+            + Sync
+            + 'static,
+        Cb: BufferAccess + TypedBufferAccess<Content = u32> + Send + Sync + 'static,
+    {
+        unsafe {
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            let dynamic = self.draw_dynamic_state(&pipeline, dynamic)?;
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
+            check_push_constants_validity(&pipeline, &constants)?;
+            check_descriptor_sets_validity(&pipeline, &sets)?;
+            let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+            check_indirect_buffer(self.device(), &indirect_buffer)?;
+            let max_draw_count = indirect_buffer.len() as u32;
+            check_indirect_count_buffer(self.device(), &count_buffer, max_draw_count)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            } else {
+                self.statistics.elided_binds += 1;
+            }
+
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
+
+            push_constants(
+                &mut self.inner,
+                &mut self.statistics,
+                pipeline.clone(),
+                constants,
+            );
+            set_state(&mut self.inner, &dynamic);
+            self.dynamic_state = DynamicState::none();
+            descriptor_sets(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                PipelineBindPoint::Graphics,
+                pipeline.clone(),
+                sets,
+            )?;
+            vertex_buffers(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                vb_infos.vertex_buffers,
+            )?;
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner.draw_indirect_count(
+                indirect_buffer,
+                count_buffer,
+                max_draw_count,
+                mem::size_of::<DrawIndirectCommand>() as u32,
+            )?;
+            self.statistics.draws += 1;
+            Ok(self)
+        }
+    }
+
+    /// Same as `draw_indexed_indirect`, except that the number of draws is read by the GPU from
+    /// `count_buffer`, and is at most the number of `DrawIndexedIndirectCommand` structs in
+    /// `indirect_buffer`, which must not exceed the `max_draw_indirect_count` limit.
+    ///
+    /// Requires the `khr_draw_indirect_count` extension.
+    #[inline]
+    pub fn draw_indexed_indirect_count<V, Gp, S, Pc, Ib, Inb, Cb, I>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        index_buffer: Ib,
+        indirect_buffer: Inb,
+        count_buffer: Cb,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawIndexedIndirectError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+        S: DescriptorSetsCollection,
+        Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
+        Inb: BufferAccess
+            + TypedBufferAccess<Content = [DrawIndexedIndirectCommand]>
+            + Send
+            + Sync
+            + 'static,
+        Cb: BufferAccess + TypedBufferAccess<Content = u32> + Send + Sync + 'static,
+        I: Index + 'static,
+    {
+        unsafe {
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            let ib_infos = check_index_buffer(self.device(), &index_buffer)?;
+            let dynamic = self.draw_dynamic_state(&pipeline, dynamic)?;
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
+            check_push_constants_validity(&pipeline, &constants)?;
+            check_descriptor_sets_validity(&pipeline, &sets)?;
+            let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+            check_indirect_buffer(self.device(), &indirect_buffer)?;
+            let max_draw_count = indirect_buffer.len() as u32;
+            check_indirect_count_buffer(self.device(), &count_buffer, max_draw_count)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            } else {
+                self.statistics.elided_binds += 1;
+            }
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_index_buffer(&index_buffer, I::ty())
+            {
+                self.inner.bind_index_buffer(index_buffer, I::ty())?;
+            } else {
+                self.statistics.elided_binds += 1;
+            }
+
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
+
+            push_constants(
+                &mut self.inner,
+                &mut self.statistics,
+                pipeline.clone(),
+                constants,
+            );
+            set_state(&mut self.inner, &dynamic);
+            self.dynamic_state = DynamicState::none();
+            descriptor_sets(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                PipelineBindPoint::Graphics,
+                pipeline.clone(),
+                sets,
+            )?;
+            vertex_buffers(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                vb_infos.vertex_buffers,
+            )?;
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner.draw_indexed_indirect_count(
+                indirect_buffer,
+                count_buffer,
+                max_draw_count,
+                mem::size_of::<DrawIndexedIndirectCommand>() as u32,
+            )?;
+            self.statistics.draws += 1;
+            Ok(self)
+        }
+    }
+
+    /// Draws mesh tasks with a pipeline that has a mesh shader.
+    ///
+    /// `group_count` is the number of workgroups of the task shader that are launched, or of the
+    /// mesh shader if the pipeline doesn't have a task shader.
+    #[inline]
+    pub fn draw_mesh_tasks<Gp, S, Pc>(
+        &mut self,
+        group_count: [u32; 3],
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawMeshTasksError>
+    where
+        Gp: GraphicsPipelineAbstract + Send + Sync + 'static + Clone, // TODO: meh for Clone
+        S: DescriptorSetsCollection,
+    {
+        unsafe {
+            // TODO: must check that pipeline is compatible with render pass
+
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            check_draw_mesh_tasks(self.device(), &pipeline, Some(group_count))?;
+            let dynamic = self.draw_dynamic_state(&pipeline, dynamic)?;
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
+            check_push_constants_validity(&pipeline, &constants)?;
+            check_descriptor_sets_validity(&pipeline, &sets)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            } else {
+                self.statistics.elided_binds += 1;
+            }
+
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
+
+            push_constants(
+                &mut self.inner,
+                &mut self.statistics,
+                pipeline.clone(),
+                constants,
+            );
+            set_state(&mut self.inner, &dynamic);
+            self.dynamic_state = DynamicState::none();
+            descriptor_sets(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                PipelineBindPoint::Graphics,
+                pipeline.clone(),
+                sets,
+            )?;
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner.draw_mesh_tasks(group_count);
+            self.statistics.draws += 1;
+            Ok(self)
+        }
+    }
+
+    /// Performs multiple mesh tasks draws, one for each
+    /// `vulkano::command_buffer::DrawMeshTasksIndirectCommand` struct in `indirect_buffer`.
+    ///
+    /// To use only some data in the buffer, wrap it in a `vulkano::buffer::BufferSlice`.
+    #[inline]
+    pub fn draw_mesh_tasks_indirect<Gp, S, Pc, Ib>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        indirect_buffer: Ib,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawMeshTasksIndirectError>
+    where
+        Gp: GraphicsPipelineAbstract + Send + Sync + 'static + Clone, // TODO: meh for Clone
+        S: DescriptorSetsCollection,
+        Ib: BufferAccess
+            + TypedBufferAccess<Content = [DrawMeshTasksIndirectCommand]>
+            + Send
+            + Sync
+            + 'static,
+    {
+        unsafe {
+            // TODO: must check that pipeline is compatible with render pass
+
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            check_draw_mesh_tasks(self.device(), &pipeline, None)?;
+            let dynamic = self.draw_dynamic_state(&pipeline, dynamic)?;
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
             check_push_constants_validity(&pipeline, &constants)?;
             check_descriptor_sets_validity(&pipeline, &sets)?;
-            let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+            check_indirect_buffer(self.device(), &indirect_buffer)?;
 
             let draw_count = indirect_buffer.len() as u32;
+            check_indirect_draw_count(self.device(), draw_count)?;
 
             if let StateCacherOutcome::NeedChange =
                 self.state_cacher.bind_graphics_pipeline(&pipeline)
             {
                 self.inner.bind_pipeline_graphics(pipeline.clone());
+            } else {
+                self.statistics.elided_binds += 1;
             }
 
-            if let StateCacherOutcome::NeedChange =
-                self.state_cacher.bind_index_buffer(&index_buffer, I::ty())
-            {
-                self.inner.bind_index_buffer(index_buffer, I::ty())?;
-            }
-
-            let dynamic = self.state_cacher.dynamic_state(dynamic);
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
 
-            push_constants(&mut self.inner, pipeline.clone(), constants);
+            push_constants(
+                &mut self.inner,
+                &mut self.statistics,
+                pipeline.clone(),
+                constants,
+            );
             set_state(&mut self.inner, &dynamic);
+            self.dynamic_state = DynamicState::none();
             descriptor_sets(
                 &mut self.inner,
                 &mut self.state_cacher,
-                true,
+                &mut self.statistics,
+                PipelineBindPoint::Graphics,
                 pipeline.clone(),
                 sets,
             )?;
-            vertex_buffers(
-                &mut self.inner,
-                &mut self.state_cacher,
-                vb_infos.vertex_buffers,
-            )?;
 
             debug_assert!(self.graphics_allowed);
 
-            self.inner.draw_indexed_indirect(
+            self.inner.draw_mesh_tasks_indirect(
                 indirect_buffer,
                 draw_count,
-                mem::size_of::<DrawIndexedIndirectCommand>() as u32,
+                mem::size_of::<DrawMeshTasksIndirectCommand>() as u32,
             )?;
+            self.statistics.draws += 1;
             Ok(self)
         }
     }
@@ -1420,8 +2812,75 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Adds a command that opens a debug label region, that ends with `end_debug_label`. Debugging
+    /// tools such as RenderDoc show the commands added in between under `name`.
+    ///
+    /// The `ext_debug_utils` extension must be enabled on the instance.
+    #[inline]
+    pub fn begin_debug_label(
+        &mut self,
+        name: &CStr,
+        color: [f32; 4],
+    ) -> Result<&mut Self, DebugLabelError> {
+        unsafe {
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_debug_label(self.device(), color)?;
+            self.inner.debug_label_begin(name, color);
+            self.debug_label_depth += 1;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that closes the debug label region opened by the last
+    /// `begin_debug_label`.
+    ///
+    /// Returns an error if there is no region opened by a previous `begin_debug_label` in this
+    /// command buffer.
+    #[inline]
+    pub fn end_debug_label(&mut self) -> Result<&mut Self, DebugLabelError> {
+        unsafe {
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_end_debug_label(self.device(), self.debug_label_depth)?;
+            self.inner.debug_label_end();
+            self.debug_label_depth -= 1;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that inserts a single debug label, which is shown by debugging tools such as
+    /// RenderDoc between the commands added before and after it.
+    ///
+    /// The `ext_debug_utils` extension must be enabled on the instance.
+    #[inline]
+    pub fn insert_debug_label(
+        &mut self,
+        name: &CStr,
+        color: [f32; 4],
+    ) -> Result<&mut Self, DebugLabelError> {
+        unsafe {
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_debug_label(self.device(), color)?;
+            self.inner.debug_label_insert(name, color);
+            Ok(self)
+        }
+    }
+
     /// Adds a command that executes a secondary command buffer.
     ///
+    /// The secondary command buffer doesn't inherit the dynamic state, such as the viewports and
+    /// scissors, that was set in this command buffer, and the dynamic state of this command
+    /// buffer is unknown after it has executed. Draw commands added after this one must therefore
+    /// specify all the dynamic states that their pipeline uses again.
+    ///
     /// **This function is unsafe for now because safety checks and synchronization are not
     /// implemented.**
     // TODO: implement correctly
@@ -1460,38 +2919,535 @@ impl<P> AutoCommandBufferBuilder<P> {
             for cmd_buffer in command_buffers {
                 builder.add(cmd_buffer);
             }
-            builder.submit()?;
-        }
+            builder.submit()?;
+        }
+
+        self.state_cacher.invalidate();
+
+        Ok(self)
+    }
+
+    /// Adds a command that writes the content of a buffer.
+    ///
+    /// This function is similar to the `memset` function in C. The `data` parameter is a number
+    /// that will be repeatedly written through the entire buffer.
+    ///
+    /// The offset and the size of the buffer must both be multiples of 4.
+    ///
+    /// > **Note**: This function is technically safe because buffers can only contain integers or
+    /// > floating point numbers, which are always valid whatever their memory representation is.
+    /// > But unless your buffer actually contains only 32-bits integers, you are encouraged to use
+    /// > this function only for zeroing the content of a buffer by passing `0` for the data.
+    // TODO: not safe because of signalling NaNs
+    #[inline]
+    pub fn fill_buffer<B>(&mut self, buffer: B, data: u32) -> Result<&mut Self, FillBufferError>
+    where
+        B: BufferAccess + Send + Sync + 'static,
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            check_fill_buffer(self.device(), &buffer)?;
+            self.inner.fill_buffer(buffer, data);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that updates the push constants of `pipeline_layout` that are accessed by
+    /// `stages`, starting at `offset`, with the content of `data`.
+    ///
+    /// The pipeline layout must have push constants ranges that contain all the updated bytes
+    /// for each stage of `stages`. The offset and size of `data` must be multiples of 4.
+    ///
+    /// > **Note**: The draw and dispatch commands also update all the push constants of the
+    /// > layout of their pipeline with the push constants passed to them.
+    #[inline]
+    pub fn push_constants<Pl, D>(
+        &mut self,
+        pipeline_layout: Pl,
+        stages: ShaderStages,
+        offset: u32,
+        data: &D,
+    ) -> Result<&mut Self, PushConstantsError>
+    where
+        Pl: PipelineLayoutAbstract + Send + Sync + 'static,
+        D: ?Sized + Send + Sync + 'static,
+    {
+        let size = mem::size_of_val(data) as u32;
+        check_push_constants_range(self.device(), &pipeline_layout, stages, offset, size)?;
+
+        unsafe {
+            self.inner
+                .push_constants(pipeline_layout, stages, offset, size, data);
+        }
+        self.statistics.push_constants_bytes += size as u64;
+
+        Ok(self)
+    }
+
+    /// Adds a command that releases the ownership of `buffer` from the queue family of this
+    /// command buffer to the `destination` queue family.
+    ///
+    /// Buffers created with exclusive sharing can only be used by one queue family at a time.
+    /// In order to use such a buffer from another queue family, for example after uploading its
+    /// content on a dedicated transfer queue, its ownership must be released by a command buffer
+    /// of the current owner with this function, then acquired by a command buffer of the
+    /// destination queue family with `acquire_buffer_ownership`. The acquiring command buffer
+    /// must be submitted after the releasing one has finished, usually by waiting on a semaphore.
+    ///
+    /// The queue family that the buffer is released to is tracked by the buffer, and using
+    /// these functions out of order returns an error. The transfer is only recorded in the buffer
+    /// when the command buffer is submitted, which means that the releasing command buffer must
+    /// be submitted before the acquiring one is built.
+    #[inline]
+    pub fn release_buffer_ownership<B>(
+        &mut self,
+        buffer: B,
+        destination: QueueFamily,
+    ) -> Result<&mut Self, QueueFamilyTransferError>
+    where
+        B: BufferAccess + Send + Sync + 'static,
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            let source = self.queue_family();
+            check_buffer_ownership_release(self.device(), &buffer, source, destination)?;
+            self.inner
+                .queue_family_transfer_buffer(buffer, source.id(), destination.id(), true)?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that acquires the ownership of `buffer` from the `source` queue family
+    /// for the queue family of this command buffer.
+    ///
+    /// The ownership must have been released with `release_buffer_ownership` beforehand. See
+    /// the documentation of `release_buffer_ownership` for more information.
+    #[inline]
+    pub fn acquire_buffer_ownership<B>(
+        &mut self,
+        buffer: B,
+        source: QueueFamily,
+    ) -> Result<&mut Self, QueueFamilyTransferError>
+    where
+        B: BufferAccess + Send + Sync + 'static,
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            let destination = self.queue_family();
+            check_buffer_ownership_acquire(self.device(), &buffer, source, destination)?;
+            self.inner.queue_family_transfer_buffer(
+                buffer,
+                source.id(),
+                destination.id(),
+                false,
+            )?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that releases the ownership of `image` from the queue family of this
+    /// command buffer to the `destination` queue family, and transitions it from `old_layout` to
+    /// `new_layout`.
+    ///
+    /// This works like `release_buffer_ownership`, except that the image is also transitioned to
+    /// the layout that the destination queue family will use. `old_layout` is the layout the
+    /// image must be in before the transfer, and `new_layout` must be the layout it is expected
+    /// to be in at the end of a command buffer. The matching `acquire_image_ownership` must use
+    /// the same layouts.
+    ///
+    /// The image must not be used by any other command until the ownership has been acquired.
+    /// Doing so returns an error when the command recording the use is added.
+    #[inline]
+    pub fn release_image_ownership<I>(
+        &mut self,
+        image: I,
+        destination: QueueFamily,
+        old_layout: ImageLayout,
+        new_layout: ImageLayout,
+    ) -> Result<&mut Self, QueueFamilyTransferError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            let source = self.queue_family();
+            check_image_ownership_release(
+                self.device(),
+                &image,
+                source,
+                destination,
+                old_layout,
+                new_layout,
+            )?;
+            self.inner.queue_family_transfer_image(
+                image,
+                source.id(),
+                destination.id(),
+                old_layout,
+                new_layout,
+                true,
+            )?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that acquires the ownership of `image` from the `source` queue family for
+    /// the queue family of this command buffer.
+    ///
+    /// The ownership must have been released with `release_image_ownership` beforehand, with the
+    /// same layouts. See the documentation of `release_image_ownership` for more information.
+    #[inline]
+    pub fn acquire_image_ownership<I>(
+        &mut self,
+        image: I,
+        source: QueueFamily,
+        old_layout: ImageLayout,
+        new_layout: ImageLayout,
+    ) -> Result<&mut Self, QueueFamilyTransferError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            let destination = self.queue_family();
+            check_image_ownership_acquire(
+                self.device(),
+                &image,
+                source,
+                destination,
+                old_layout,
+                new_layout,
+            )?;
+            self.inner.queue_family_transfer_image(
+                image,
+                source.id(),
+                destination.id(),
+                old_layout,
+                new_layout,
+                false,
+            )?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a global memory barrier between the commands added before and the commands added
+    /// after this one.
+    ///
+    /// Pipeline barriers between commands that access the same buffers or images are already
+    /// inserted automatically, and this is usually not needed. It is meant for accesses that the
+    /// builder doesn't know about, for example to memory shared by multiple resources or to
+    /// resources used through their raw Vulkan handle. The barrier doesn't refer to any resource
+    /// and doesn't change the barriers that are inserted automatically.
+    #[inline]
+    pub fn memory_barrier(
+        &mut self,
+        source_stage: PipelineStages,
+        source_access: AccessFlagBits,
+        destination_stage: PipelineStages,
+        destination_access: AccessFlagBits,
+        by_region: bool,
+    ) -> Result<&mut Self, MemoryBarrierError> {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            check_memory_barrier(
+                self.device(),
+                source_stage,
+                source_access,
+                destination_stage,
+                destination_access,
+            )?;
+            self.inner.memory_barrier(
+                source_stage,
+                source_access,
+                destination_stage,
+                destination_access,
+                by_region,
+            );
+            Ok(self)
+        }
+    }
+
+    /// Adds an explicit pipeline barrier between the commands added before and the commands added
+    /// after this one.
+    ///
+    /// This is the same as `memory_barrier`, except that the barrier can also contain memory
+    /// barriers that only apply to some buffers or images. The accesses of each of them are
+    /// checked against the stages of the barrier. The buffers and images are kept alive by the
+    /// command buffer, but the barriers that are inserted automatically for them are unchanged.
+    #[inline]
+    pub fn pipeline_barrier(
+        &mut self,
+        barrier: PipelineBarrier,
+    ) -> Result<&mut Self, MemoryBarrierError> {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            check_pipeline_barrier(self.device(), &barrier)?;
+            self.inner.pipeline_barrier(barrier);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets `event` once the commands added before it have completed `stages`.
+    ///
+    /// Combined with `wait_events`, this allows synchronizing two parts of the same queue while
+    /// letting the commands added in between run in parallel with them.
+    #[inline]
+    pub fn set_event(
+        &mut self,
+        event: Arc<Event>,
+        stages: PipelineStages,
+    ) -> Result<&mut Self, SetEventError> {
+        unsafe {
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.ensure_outside_render_pass()?;
+            check_event_command(self.device(), &event, stages)?;
+            check_memory_barrier(
+                self.device(),
+                stages,
+                AccessFlagBits::none(),
+                stages,
+                AccessFlagBits::none(),
+            )?;
+            self.inner.set_event(event, stages);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that resets `event` once the commands added before it have completed
+    /// `stages`.
+    ///
+    /// # Safety
+    ///
+    /// - The event must not be waited on by a `wait_events` command that is being executed.
+    #[inline]
+    pub unsafe fn reset_event(
+        &mut self,
+        event: Arc<Event>,
+        stages: PipelineStages,
+    ) -> Result<&mut Self, ResetEventError> {
+        if !self.graphics_allowed && !self.compute_allowed {
+            return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+        }
+
+        self.ensure_outside_render_pass()?;
+        check_event_command(self.device(), &event, stages)?;
+        check_memory_barrier(
+            self.device(),
+            stages,
+            AccessFlagBits::none(),
+            stages,
+            AccessFlagBits::none(),
+        )?;
+        self.inner.reset_event(event, stages);
+        Ok(self)
+    }
+
+    /// Adds a command that waits until all of `events` are set, then makes the accesses of
+    /// `source_stage` performed before they were set available to the accesses of
+    /// `destination_stage` performed by the commands added after it.
+    ///
+    /// As with `memory_barrier`, the barrier doesn't refer to any resource, and the barriers that
+    /// are inserted automatically for the buffers and images used by the commands are unchanged.
+    ///
+    /// # Safety
+    ///
+    /// - `source_stage` must contain the stages passed to the `set_event` commands that set the
+    ///   events, and the `host` stage if an event is set with `Event::set`.
+    /// - The events must be set by a previous command of the same queue or by the host, otherwise
+    ///   the command buffer waits forever. Implementations may also lose the device if an event
+    ///   isn't set within a certain amount of time.
+    #[inline]
+    pub unsafe fn wait_events<I>(
+        &mut self,
+        events: I,
+        source_stage: PipelineStages,
+        source_access: AccessFlagBits,
+        destination_stage: PipelineStages,
+        destination_access: AccessFlagBits,
+    ) -> Result<&mut Self, WaitEventsError>
+    where
+        I: IntoIterator<Item = Arc<Event>>,
+    {
+        if !self.graphics_allowed && !self.compute_allowed {
+            return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+        }
+
+        self.ensure_outside_render_pass()?;
+        let events: Vec<_> = events.into_iter().collect();
+        check_wait_events(self.device(), events.iter().map(|e| &**e))?;
+        check_memory_barrier(
+            self.device(),
+            source_stage,
+            source_access,
+            destination_stage,
+            destination_access,
+        )?;
+        self.inner.wait_events(
+            events,
+            source_stage,
+            source_access,
+            destination_stage,
+            destination_access,
+        );
+        Ok(self)
+    }
+
+    /// Adds a command that begins a query.
+    ///
+    /// The query stays active until `end_query` is called with the same query, and only one query
+    /// of each type can be active at a time. Its results can be read with
+    /// `QueryPool::get_results` once the command buffer has been executed.
+    ///
+    /// The `precise` flag can only be used with occlusion queries, and requires the
+    /// `occlusion_query_precise` feature.
+    ///
+    /// # Safety
+    ///
+    /// - The query must be unavailable, which is ensured by resetting it with `reset_query_pool`
+    ///   before it is begun.
+    #[inline]
+    pub unsafe fn begin_query(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        query: u32,
+        precise: bool,
+    ) -> Result<&mut Self, BeginQueryError> {
+        match query_pool.ty() {
+            QueryType::Occlusion => {
+                if !self.graphics_allowed {
+                    return Err(
+                        AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into(),
+                    );
+                }
+            }
+            QueryType::PipelineStatistics(_) => {
+                if !self.graphics_allowed && !self.compute_allowed {
+                    return Err(
+                        AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into(),
+                    );
+                }
+            }
+            QueryType::Timestamp => (),
+        }
+
+        check_begin_query(self.device(), &query_pool, query, precise)?;
+
+        let ty = query_pool.ty().into();
+        if self.query_state.contains_key(&ty) {
+            return Err(AutoCommandBufferBuilderContextError::QueryIsActive.into());
+        }
+
+        self.query_state.insert(
+            ty,
+            QueryState {
+                query_pool: query_pool.inner().internal_object(),
+                query,
+                subpass: self.render_pass.as_ref().map(|&(_, index)| index),
+            },
+        );
+        self.inner.begin_query(query_pool, query, precise);
+        Ok(self)
+    }
+
+    /// Adds a command that ends a query that was begun with `begin_query`.
+    ///
+    /// If the query was begun inside a render pass, it must be ended in the same subpass.
+    #[inline]
+    pub fn end_query(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        query: u32,
+    ) -> Result<&mut Self, AutoCommandBufferBuilderContextError> {
+        unsafe {
+            let ty = query_pool.ty().into();
+            match self.query_state.get(&ty) {
+                Some(state)
+                    if state.query_pool == query_pool.inner().internal_object()
+                        && state.query == query =>
+                {
+                    if state.subpass != self.render_pass.as_ref().map(|&(_, index)| index) {
+                        return Err(AutoCommandBufferBuilderContextError::WrongSubpassIndex);
+                    }
+                }
+                _ => return Err(AutoCommandBufferBuilderContextError::QueryNotActive),
+            }
 
-        self.state_cacher.invalidate();
+            self.query_state.remove(&ty);
+            self.inner.end_query(query_pool, query);
+            Ok(self)
+        }
+    }
 
+    /// Adds a command that writes a timestamp to a query once all the previous commands have
+    /// reached `stage`.
+    ///
+    /// The query pool must have been created for timestamp queries, and `stage` must contain
+    /// exactly one pipeline stage. Use `QueryPool::timestamp_to_nanoseconds` to convert the
+    /// difference between two timestamps to a duration.
+    ///
+    /// # Safety
+    ///
+    /// - The query must be unavailable, which is ensured by resetting it with `reset_query_pool`
+    ///   before the timestamp is written.
+    #[inline]
+    pub unsafe fn write_timestamp(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        query: u32,
+        stage: PipelineStages,
+    ) -> Result<&mut Self, WriteTimestampError> {
+        check_write_timestamp(
+            self.device(),
+            self.queue_family(),
+            &query_pool,
+            query,
+            stage,
+        )?;
+        self.inner.write_timestamp(query_pool, query, stage);
         Ok(self)
     }
 
-    /// Adds a command that writes the content of a buffer.
+    /// Adds a command that resets the queries in `queries`, which makes them unavailable.
     ///
-    /// This function is similar to the `memset` function in C. The `data` parameter is a number
-    /// that will be repeatedly written through the entire buffer.
+    /// Queries must be reset before they are begun or before a timestamp is written to them.
     ///
-    /// > **Note**: This function is technically safe because buffers can only contain integers or
-    /// > floating point numbers, which are always valid whatever their memory representation is.
-    /// > But unless your buffer actually contains only 32-bits integers, you are encouraged to use
-    /// > this function only for zeroing the content of a buffer by passing `0` for the data.
-    // TODO: not safe because of signalling NaNs
+    /// # Safety
+    ///
+    /// - The queries must not be in use by a command buffer that is being executed.
     #[inline]
-    pub fn fill_buffer<B>(&mut self, buffer: B, data: u32) -> Result<&mut Self, FillBufferError>
-    where
-        B: BufferAccess + Send + Sync + 'static,
-    {
-        unsafe {
-            self.ensure_outside_render_pass()?;
-            check_fill_buffer(self.device(), &buffer)?;
-            self.inner.fill_buffer(buffer, data);
-            Ok(self)
+    pub unsafe fn reset_query_pool(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        queries: Range<u32>,
+    ) -> Result<&mut Self, ResetQueryPoolError> {
+        self.ensure_outside_render_pass()?;
+        check_reset_query_pool(self.device(), &query_pool, queries.clone())?;
+
+        let pool = query_pool.inner().internal_object();
+        if self
+            .query_state
+            .values()
+            .any(|state| state.query_pool == pool && queries.contains(&state.query))
+        {
+            return Err(AutoCommandBufferBuilderContextError::QueryIsActive.into());
         }
+
+        self.inner.reset_query_pool(query_pool, queries);
+        Ok(self)
     }
 
     /// Adds a command that jumps to the next subpass of the current render pass.
+    ///
+    /// If `secondary` is true, then you will only be able to add secondary command buffers while
+    /// you're inside the new subpass. If `secondary` is false, you will only be able to add
+    /// inline draw commands and not secondary command buffers.
+    ///
+    /// Returns `NumSubpassesMismatch` if the current subpass is the last one of the render pass.
+    /// Likewise, `end_render_pass` returns this error if you haven't gone through all the
+    /// subpasses. The index of the current subpass is returned by `current_subpass`.
     #[inline]
     pub fn next_subpass(
         &mut self,
@@ -1532,10 +3488,86 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Adds a command that traces rays with a ray tracing pipeline.
+    ///
+    /// `shader_binding_table` must have been built by `pipeline` with
+    /// `RayTracingPipeline::shader_binding_table`, and its data must have been copied at the start
+    /// of `shader_binding_table_buffer`. The ray generation shader is invoked once for each
+    /// element of `dimensions`.
+    ///
+    /// Ray tracing requires the `nv_ray_tracing` extension.
+    #[inline]
+    pub fn trace_rays<Rp, S, Pc, B>(
+        &mut self,
+        dimensions: [u32; 3],
+        pipeline: Rp,
+        sets: S,
+        constants: Pc,
+        shader_binding_table: &ShaderBindingTable,
+        shader_binding_table_buffer: B,
+    ) -> Result<&mut Self, TraceRaysError>
+    where
+        Rp: RayTracingPipelineAbstract + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+        B: BufferAccess + Send + Sync + 'static,
+    {
+        unsafe {
+            if !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.ensure_outside_render_pass()?;
+            check_push_constants_validity(&pipeline, &constants)?;
+            check_descriptor_sets_validity(&pipeline, &sets)?;
+            check_trace_rays(
+                pipeline.device(),
+                &pipeline,
+                &shader_binding_table_buffer,
+                shader_binding_table,
+                dimensions,
+            )?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_ray_tracing_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_ray_tracing(pipeline.clone());
+            } else {
+                self.statistics.elided_binds += 1;
+            }
+
+            push_constants(
+                &mut self.inner,
+                &mut self.statistics,
+                pipeline.clone(),
+                constants,
+            );
+            descriptor_sets(
+                &mut self.inner,
+                &mut self.state_cacher,
+                &mut self.statistics,
+                PipelineBindPoint::RayTracing,
+                pipeline.clone(),
+                sets,
+            )?;
+
+            self.inner.trace_rays(
+                shader_binding_table_buffer,
+                shader_binding_table.regions(),
+                dimensions,
+            )?;
+            self.statistics.dispatches += 1;
+            Ok(self)
+        }
+    }
+
     /// Adds a command that writes data to a buffer.
     ///
     /// If `data` is larger than the buffer, only the part of `data` that fits is written. If the
     /// buffer is larger than `data`, only the start of the buffer is written.
+    ///
+    /// The offset of the buffer and the number of bytes written must both be multiples of 4, and
+    /// at most 65536 bytes can be written with a single command. Use a staging buffer and
+    /// `copy_buffer` for larger uploads.
     // TODO: allow unsized values
     #[inline]
     pub fn update_buffer<B, D>(
@@ -1550,20 +3582,47 @@ impl<P> AutoCommandBufferBuilder<P> {
         unsafe {
             self.ensure_outside_render_pass()?;
             check_update_buffer(self.device(), &buffer, &data)?;
-
-            let size_of_data = mem::size_of_val(&data);
-            if buffer.size() >= size_of_data {
-                self.inner.update_buffer(buffer, data);
-            } else {
-                unimplemented!() // TODO:
-                                 //self.inner.update_buffer(buffer.slice(0 .. size_of_data), data);
-            }
-
+            self.inner.update_buffer(buffer, data);
             Ok(self)
         }
     }
 }
 
+// Aspect copied by the `copy_*_dimensions` functions. Depth-stencil images only have their depth
+// aspect copied.
+fn default_copy_aspect<I>(image: &I) -> ImageAspect
+where
+    I: ?Sized + ImageAccess,
+{
+    if image.has_color() {
+        ImageAspect::Color
+    } else if image.has_depth() {
+        ImageAspect::Depth
+    } else {
+        ImageAspect::Stencil
+    }
+}
+
+// Turns a `BufferImageCopyRegion`, whose buffer offset is in elements, into a low-level copy.
+fn buffer_image_copy(
+    region: BufferImageCopyRegion,
+    element_size: usize,
+) -> UnsafeCommandBufferBuilderBufferImageCopy {
+    let offset = region.image_offset;
+
+    UnsafeCommandBufferBuilderBufferImageCopy {
+        buffer_offset: region.buffer_offset * element_size,
+        buffer_row_length: region.buffer_row_length,
+        buffer_image_height: region.buffer_image_height,
+        image_aspect: region.image_aspect.into(),
+        image_mip_level: region.image_mip_level,
+        image_base_array_layer: region.image_base_array_layer,
+        image_layer_count: region.image_layer_count,
+        image_offset: [offset[0] as i32, offset[1] as i32, offset[2] as i32],
+        image_extent: region.image_extent,
+    }
+}
+
 unsafe impl<P> DeviceOwned for AutoCommandBufferBuilder<P> {
     #[inline]
     fn device(&self) -> &Arc<Device> {
@@ -1574,6 +3633,7 @@ unsafe impl<P> DeviceOwned for AutoCommandBufferBuilder<P> {
 // Shortcut function to set the push constants.
 unsafe fn push_constants<P, Pl, Pc>(
     destination: &mut SyncCommandBufferBuilder<P>,
+    statistics: &mut CommandBufferStatistics,
     pipeline: Pl,
     push_constants: Pc,
 ) where
@@ -1600,6 +3660,7 @@ unsafe fn push_constants<P, Pl, Pc>(
             range.size as u32,
             data,
         );
+        statistics.push_constants_bytes += range.size as u64;
     }
 }
 
@@ -1630,12 +3691,25 @@ unsafe fn set_state<P>(destination: &mut SyncCommandBufferBuilder<P>, dynamic: &
     if let Some(reference) = dynamic.reference {
         destination.set_stencil_reference(reference);
     }
+
+    if let Some(bias) = dynamic.depth_bias {
+        destination.set_depth_bias(bias.constant_factor, bias.clamp, bias.slope_factor);
+    }
+
+    if let Some(ref bounds) = dynamic.depth_bounds {
+        destination.set_depth_bounds(bounds.start, bounds.end);
+    }
+
+    if let Some(constants) = dynamic.blend_constants {
+        destination.set_blend_constants(constants);
+    }
 }
 
 // Shortcut function to bind vertex buffers.
 unsafe fn vertex_buffers<P>(
     destination: &mut SyncCommandBufferBuilder<P>,
     state_cacher: &mut StateCacher,
+    statistics: &mut CommandBufferStatistics,
     vertex_buffers: Vec<Box<dyn BufferAccess + Send + Sync>>,
 ) -> Result<(), SyncCommandBufferBuilderError> {
     let binding_range = {
@@ -1645,7 +3719,12 @@ unsafe fn vertex_buffers<P>(
         }
         match compare.compare() {
             Some(r) => r,
-            None => return Ok(()),
+            None => {
+                if !vertex_buffers.is_empty() {
+                    statistics.elided_binds += 1;
+                }
+                return Ok(());
+            }
         }
     };
 
@@ -1667,7 +3746,8 @@ unsafe fn vertex_buffers<P>(
 unsafe fn descriptor_sets<P, Pl, S>(
     destination: &mut SyncCommandBufferBuilder<P>,
     state_cacher: &mut StateCacher,
-    gfx: bool,
+    statistics: &mut CommandBufferStatistics,
+    bind_point: PipelineBindPoint,
     pipeline: Pl,
     sets: S,
 ) -> Result<(), SyncCommandBufferBuilderError>
@@ -1678,7 +3758,7 @@ where
     let sets = sets.into_vec();
 
     let first_binding = {
-        let mut compare = state_cacher.bind_descriptor_sets(gfx);
+        let mut compare = state_cacher.bind_descriptor_sets_at(bind_point);
         for set in sets.iter() {
             compare.add(set);
         }
@@ -1686,7 +3766,12 @@ where
     };
 
     let first_binding = match first_binding {
-        None => return Ok(()),
+        None => {
+            if !sets.is_empty() {
+                statistics.elided_binds += 1;
+            }
+            return Ok(());
+        }
         Some(fb) => fb,
     };
 
@@ -1694,7 +3779,7 @@ where
     for set in sets.into_iter().skip(first_binding as usize) {
         sets_binder.add(set);
     }
-    sets_binder.submit(gfx, pipeline.clone(), first_binding, iter::empty())?;
+    sets_binder.submit(bind_point, pipeline.clone(), first_binding, iter::empty())?;
     Ok(())
 }
 
@@ -1703,6 +3788,36 @@ pub struct AutoCommandBuffer<P = StandardCommandPoolAlloc> {
 
     // Tracks usage of the command buffer on the GPU.
     submit_state: SubmitState,
+
+    // Statistics about the commands that were recorded.
+    statistics: CommandBufferStatistics,
+}
+
+impl<P> AutoCommandBuffer<P> {
+    /// Returns statistics about the commands that were recorded in the command buffer.
+    #[inline]
+    pub fn statistics(&self) -> CommandBufferStatistics {
+        self.statistics
+    }
+}
+
+/// Statistics about the commands recorded in an `AutoCommandBuffer`.
+///
+/// They can help finding out which parts of a program record more commands than needed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct CommandBufferStatistics {
+    /// Number of draw commands, including the indirect and mesh shading ones.
+    pub draws: u32,
+    /// Number of dispatch commands, including the indirect ones and `trace_rays`.
+    pub dispatches: u32,
+    /// Number of pipeline barriers that were added automatically in order to synchronize the
+    /// commands. This doesn't include the barriers that were added explicitly.
+    pub pipeline_barriers: u32,
+    /// Number of pipeline, descriptor sets, vertex buffers and index buffer binds that were
+    /// skipped because the same objects were already bound.
+    pub elided_binds: u32,
+    /// Number of bytes of push constants that were updated.
+    pub push_constants_bytes: u64,
 }
 
 // Whether the command buffer can be submitted.
@@ -1819,6 +3934,11 @@ unsafe impl<P> CommandBuffer for AutoCommandBuffer<P> {
         self.inner
             .check_image_access(image, layout, exclusive, queue)
     }
+
+    #[inline]
+    fn waited_events(&self) -> &[Arc<Event>] {
+        self.inner.waited_events()
+    }
 }
 
 unsafe impl<P> DeviceOwned for AutoCommandBuffer<P> {
@@ -1877,8 +3997,47 @@ err_gen!(BuildError {
     OomError,
 });
 
+err_gen!(SetEventError {
+    AutoCommandBufferBuilderContextError,
+    CheckEventCommandError,
+    CheckMemoryBarrierError,
+});
+
+err_gen!(ResetEventError {
+    AutoCommandBufferBuilderContextError,
+    CheckEventCommandError,
+    CheckMemoryBarrierError,
+});
+
+err_gen!(WaitEventsError {
+    AutoCommandBufferBuilderContextError,
+    CheckWaitEventsError,
+    CheckMemoryBarrierError,
+});
+
+err_gen!(BeginQueryError {
+    AutoCommandBufferBuilderContextError,
+    CheckBeginQueryError,
+});
+
+err_gen!(WriteTimestampError {
+    AutoCommandBufferBuilderContextError,
+    CheckWriteTimestampError,
+});
+
+err_gen!(ResetQueryPoolError {
+    AutoCommandBufferBuilderContextError,
+    CheckResetQueryPoolError,
+});
+
+err_gen!(DebugLabelError {
+    AutoCommandBufferBuilderContextError,
+    CheckDebugLabelError,
+});
+
 err_gen!(BeginRenderPassError {
     AutoCommandBufferBuilderContextError,
+    CheckClearValuesError,
     SyncCommandBufferBuilderError,
 });
 
@@ -1888,18 +4047,67 @@ err_gen!(CopyImageError {
     SyncCommandBufferBuilderError,
 });
 
+err_gen!(BindIndexBufferError {
+    AutoCommandBufferBuilderContextError,
+    CheckIndexBufferError,
+    SyncCommandBufferBuilderError,
+});
+
+err_gen!(BindDescriptorSetsError {
+    AutoCommandBufferBuilderContextError,
+    CheckDescriptorSetsValidityError,
+    SyncCommandBufferBuilderError,
+});
+
+err_gen!(BindVertexBuffersError {
+    AutoCommandBufferBuilderContextError,
+    CheckVertexBufferError,
+    SyncCommandBufferBuilderError,
+});
+
 err_gen!(BlitImageError {
     AutoCommandBufferBuilderContextError,
     CheckBlitImageError,
     SyncCommandBufferBuilderError,
 });
 
+err_gen!(CopyImageToSwapchainImageError {
+    CopyImageError,
+    BlitImageError,
+});
+
+err_gen!(CullDrawsError {
+    CheckCullDrawsError,
+    PersistentDescriptorSetError,
+    PersistentDescriptorSetBuildError,
+    FillBufferError,
+    DispatchError,
+});
+
+err_gen!(GenerateMipmapsError {
+    AutoCommandBufferBuilderContextError,
+    CheckGenerateMipmapsError,
+    SyncCommandBufferBuilderError,
+});
+
+err_gen!(ResolveImageError {
+    AutoCommandBufferBuilderContextError,
+    CheckResolveImageError,
+    SyncCommandBufferBuilderError,
+});
+
 err_gen!(ClearColorImageError {
     AutoCommandBufferBuilderContextError,
     CheckClearColorImageError,
     SyncCommandBufferBuilderError,
 });
 
+err_gen!(ClearDepthStencilImageError {
+    AutoCommandBufferBuilderContextError,
+    CheckClearDepthStencilImageError,
+    SyncCommandBufferBuilderError,
+});
+
 err_gen!(CopyBufferError {
     AutoCommandBufferBuilderContextError,
     CheckCopyBufferError,
@@ -1917,6 +4125,11 @@ err_gen!(FillBufferError {
     CheckFillBufferError,
 });
 
+err_gen!(MemoryBarrierError {
+    AutoCommandBufferBuilderContextError,
+    CheckMemoryBarrierError,
+});
+
 err_gen!(DispatchError {
     AutoCommandBufferBuilderContextError,
     CheckPushConstantsValidityError,
@@ -1925,6 +4138,14 @@ err_gen!(DispatchError {
     SyncCommandBufferBuilderError,
 });
 
+err_gen!(DispatchIndirectError {
+    AutoCommandBufferBuilderContextError,
+    CheckPushConstantsValidityError,
+    CheckDescriptorSetsValidityError,
+    CheckIndirectBufferError,
+    SyncCommandBufferBuilderError,
+});
+
 err_gen!(DrawError {
     AutoCommandBufferBuilderContextError,
     CheckDynamicStateValidityError,
@@ -1950,6 +4171,7 @@ err_gen!(DrawIndirectError {
     CheckPushConstantsValidityError,
     CheckDescriptorSetsValidityError,
     CheckVertexBufferError,
+    CheckIndirectBufferError,
     SyncCommandBufferBuilderError,
 });
 
@@ -1960,6 +4182,26 @@ err_gen!(DrawIndexedIndirectError {
     CheckDescriptorSetsValidityError,
     CheckVertexBufferError,
     CheckIndexBufferError,
+    CheckIndirectBufferError,
+    SyncCommandBufferBuilderError,
+});
+
+err_gen!(DrawMeshTasksError {
+    AutoCommandBufferBuilderContextError,
+    CheckDrawMeshTasksError,
+    CheckDynamicStateValidityError,
+    CheckPushConstantsValidityError,
+    CheckDescriptorSetsValidityError,
+    SyncCommandBufferBuilderError,
+});
+
+err_gen!(DrawMeshTasksIndirectError {
+    AutoCommandBufferBuilderContextError,
+    CheckDrawMeshTasksError,
+    CheckDynamicStateValidityError,
+    CheckPushConstantsValidityError,
+    CheckDescriptorSetsValidityError,
+    CheckIndirectBufferError,
     SyncCommandBufferBuilderError,
 });
 
@@ -1968,6 +4210,24 @@ err_gen!(ExecuteCommandsError {
     SyncCommandBufferBuilderError,
 });
 
+err_gen!(PushConstantsError {
+    CheckPushConstantsRangeError,
+});
+
+err_gen!(QueueFamilyTransferError {
+    AutoCommandBufferBuilderContextError,
+    CheckQueueFamilyTransferError,
+    SyncCommandBufferBuilderError,
+});
+
+err_gen!(TraceRaysError {
+    AutoCommandBufferBuilderContextError,
+    CheckPushConstantsValidityError,
+    CheckDescriptorSetsValidityError,
+    CheckTraceRaysError,
+    SyncCommandBufferBuilderError,
+});
+
 err_gen!(UpdateBufferError {
     AutoCommandBufferBuilderContextError,
     CheckUpdateBufferError,
@@ -2000,6 +4260,15 @@ pub enum AutoCommandBufferBuilderContextError {
     /// Tried to use a graphics pipeline whose render pass is incompatible with the current render
     /// pass.
     IncompatibleRenderPass,
+    /// Tried to begin a query while a query of the same type is active, to reset an active query
+    /// or to build a command buffer with active queries.
+    QueryIsActive,
+    /// Tried to end a query that isn't active.
+    QueryNotActive,
+    /// Tried to draw in a secondary command buffer with a pipeline that has a dynamic state that
+    /// wasn't set in this command buffer. Secondary command buffers don't inherit the dynamic
+    /// state of the primary command buffer that executes them.
+    DynamicStateNotInherited,
 }
 
 impl error::Error for AutoCommandBufferBuilderContextError {}
@@ -2040,7 +4309,256 @@ impl fmt::Display for AutoCommandBufferBuilderContextError {
                     "tried to use a graphics pipeline whose render pass is incompatible with the \
                  current render pass"
                 }
+                AutoCommandBufferBuilderContextError::QueryIsActive => {
+                    "tried to begin a query while a query of the same type is active, to reset an \
+                 active query or to build a command buffer with active queries"
+                }
+                AutoCommandBufferBuilderContextError::QueryNotActive => {
+                    "tried to end a query that isn't active"
+                }
+                AutoCommandBufferBuilderContextError::DynamicStateNotInherited => {
+                    "tried to draw in a secondary command buffer with a dynamic state that wasn't \
+                 set in this command buffer"
+                }
             }
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::AutoCommandBufferBuilderContextError;
+    use buffer::BufferAccess;
+    use buffer::BufferSlice;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use command_buffer::validity::CheckDebugLabelError;
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::BufferCopyRegion;
+    use command_buffer::CommandBuffer;
+    use command_buffer::DebugLabelError;
+    use command_buffer::DynamicState;
+    use descriptor::pipeline_layout::EmptyPipelineDesc;
+    use format::Format;
+    use framebuffer::Subpass;
+    use pipeline::shader::EmptyShaderInterfaceDef;
+    use pipeline::shader::GraphicsShaderType;
+    use pipeline::shader::ShaderModule;
+    use pipeline::vertex::BufferlessDefinition;
+    use pipeline::viewport::Viewport;
+    use pipeline::GraphicsPipeline;
+    use std::ffi::CStr;
+    use std::sync::Arc;
+    use sync::GpuFuture;
+
+    #[test]
+    fn buffer_ownership_release_applied_at_submission() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let other = match device
+            .physical_device()
+            .queue_families()
+            .find(|f| f.id() != queue.family().id())
+        {
+            Some(f) => f,
+            None => return,
+        };
+
+        let buffer =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0u32)
+                .unwrap();
+
+        let mut cbb = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        cbb.release_buffer_ownership(buffer.clone(), other).unwrap();
+        let cb = cbb.build().unwrap();
+
+        // Recording the release doesn't change the owner of the buffer.
+        assert_eq!(buffer.inner().buffer.queue_family_owner(), None);
+
+        cb.execute(queue.clone())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        assert_eq!(buffer.inner().buffer.queue_family_owner(), Some(other.id()));
+        assert_eq!(
+            buffer.inner().buffer.pending_acquire_source(),
+            Some(queue.family().id())
+        );
+    }
+
+    #[test]
+    fn end_debug_label_requires_open_label() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let mut cbb = AutoCommandBufferBuilder::new(device, queue.family()).unwrap();
+        match cbb.end_debug_label() {
+            Err(DebugLabelError::CheckDebugLabelError(CheckDebugLabelError::NoLabelOpen)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn statistics_counted() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0u32)
+                .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        builder.fill_buffer(buffer.clone(), 1).unwrap();
+        builder.fill_buffer(buffer, 2).unwrap();
+        let statistics = builder.build().unwrap().statistics();
+
+        // The second fill must wait for the first one.
+        assert_eq!(statistics.pipeline_barriers, 1);
+        assert_eq!(statistics.draws, 0);
+        assert_eq!(statistics.dispatches, 0);
+        assert_eq!(statistics.elided_binds, 0);
+    }
+
+    #[test]
+    fn dynamic_state_not_inherited() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        /*
+        #version 450
+
+        void main() {
+            gl_Position = vec4(0.0);
+        }
+        */
+        const VS: [u32; 166] = [
+            119734787, 65536, 524296, 20, 0, 131089, 1, 393227, 1, 1280527431, 1685353262,
+            808793134, 0, 196622, 0, 1, 393231, 0, 4, 1852399981, 0, 13, 196611, 2, 450, 262149, 4,
+            1852399981, 0, 393221, 11, 1348430951, 1700164197, 2019914866, 0, 393222, 11, 0,
+            1348430951, 1953067887, 7237481, 458758, 11, 1, 1348430951, 1953393007, 1702521171, 0,
+            458758, 11, 2, 1130327143, 1148217708, 1635021673, 6644590, 458758, 11, 3, 1130327143,
+            1147956341, 1635021673, 6644590, 196613, 13, 0, 327752, 11, 0, 11, 0, 327752, 11, 1,
+            11, 1, 327752, 11, 2, 11, 3, 327752, 11, 3, 11, 4, 196679, 11, 2, 131091, 2, 196641, 3,
+            2, 196630, 6, 32, 262167, 7, 6, 4, 262165, 8, 32, 0, 262187, 8, 9, 1, 262172, 10, 6, 9,
+            393246, 11, 7, 6, 10, 10, 262176, 12, 3, 11, 262203, 12, 13, 3, 262165, 14, 32, 1,
+            262187, 14, 15, 0, 262187, 6, 16, 0, 458796, 7, 17, 16, 16, 16, 16, 262176, 18, 3, 7,
+            327734, 2, 4, 0, 3, 131320, 5, 327745, 18, 19, 13, 15, 196670, 19, 17, 65789, 65592,
+        ];
+
+        /*
+        #version 450
+
+        void main() {
+        }
+        */
+        const FS: [u32; 45] = [
+            119734787, 65536, 524296, 6, 0, 131089, 1, 393227, 1, 1280527431, 1685353262,
+            808793134, 0, 196622, 0, 1, 327695, 4, 4, 1852399981, 0, 196624, 4, 7, 196611, 2, 450,
+            262149, 4, 1852399981, 0, 131091, 2, 196641, 3, 2, 327734, 2, 4, 0, 3, 131320, 5,
+            65789, 65592,
+        ];
+
+        let render_pass = Arc::new(
+            ::single_pass_renderpass!(device.clone(),
+                attachments: {
+                    color: {
+                        load: DontCare,
+                        store: Store,
+                        format: Format::R8G8B8A8Unorm,
+                        samples: 1,
+                    }
+                },
+                pass: {
+                    color: [color],
+                    depth_stencil: {}
+                }
+            )
+            .unwrap(),
+        );
+
+        let pipeline = unsafe {
+            let vs_module = ShaderModule::from_words(device.clone(), &VS).unwrap();
+            let fs_module = ShaderModule::from_words(device.clone(), &FS).unwrap();
+            let name = CStr::from_bytes_with_nul_unchecked(b"main\0");
+            let vs = vs_module.graphics_entry_point::<(), _, _, _>(
+                name,
+                EmptyShaderInterfaceDef,
+                EmptyShaderInterfaceDef,
+                EmptyPipelineDesc,
+                GraphicsShaderType::Vertex,
+            );
+            let fs = fs_module.graphics_entry_point::<(), _, _, _>(
+                name,
+                EmptyShaderInterfaceDef,
+                EmptyShaderInterfaceDef,
+                EmptyPipelineDesc,
+                GraphicsShaderType::Fragment,
+            );
+
+            GraphicsPipeline::start()
+                .vertex_input(BufferlessDefinition)
+                .vertex_shader(vs, ())
+                .viewports_dynamic_scissors_irrelevant(1)
+                .fragment_shader(fs, ())
+                .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
+                .build(device.clone())
+                .unwrap()
+        };
+
+        let viewport = DynamicState {
+            viewports: Some(vec![Viewport {
+                origin: [0.0, 0.0],
+                dimensions: [1.0, 1.0],
+                depth_range: 0.0..1.0,
+            }]),
+            ..DynamicState::none()
+        };
+
+        // Dynamic state that was set in the primary command buffer isn't inherited by a secondary
+        // command buffer.
+        let builder = AutoCommandBufferBuilder::secondary_graphics(
+            device.clone(),
+            queue.family(),
+            Subpass::from(render_pass.clone(), 0).unwrap(),
+        )
+        .unwrap();
+        match builder.draw_dynamic_state(&pipeline, &DynamicState::none()) {
+            Err(AutoCommandBufferBuilderContextError::DynamicStateNotInherited) => (),
+            _ => panic!(),
+        }
+        assert!(builder.draw_dynamic_state(&pipeline, &viewport).is_ok());
+
+        let builder = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        assert!(builder
+            .draw_dynamic_state(&pipeline, &DynamicState::none())
+            .is_ok());
+    }
+
+    #[test]
+    fn copy_buffer_regions_between_slices_of_same_buffer() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage::all(),
+            false,
+            (0..16).map(|_| 0u8),
+        )
+        .unwrap();
+        let (source, destination) = BufferSlice::from_typed_buffer_access(buffer)
+            .split_at(8)
+            .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        assert!(builder
+            .copy_buffer_regions(
+                source,
+                destination,
+                Some(BufferCopyRegion {
+                    source_offset: 0,
+                    destination_offset: 0,
+                    size: 8,
+                }),
+            )
+            .is_ok());
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use smallvec::SmallVec;
+
+use buffer::BufferAccess;
+use command_buffer::sys::UnsafeCommandBufferBuilderPipelineBarrier;
+use image::ImageAccess;
+use image::ImageLayout;
+use sync::AccessFlagBits;
+use sync::PipelineStages;
+
+/// Pipeline barrier added explicitly with `AutoCommandBufferBuilder::pipeline_barrier`.
+///
+/// All the memory, buffer and image barriers of a pipeline barrier share the same source and
+/// destination stages, which are passed when the barrier is created. Each of them has its own
+/// source and destination accesses, which must be supported by these stages.
+///
+/// The buffers and images are kept alive for as long as the command buffer is alive. Contrary
+/// to the barriers that are inserted automatically, an explicit barrier is not taken into
+/// account when determining the barriers needed by the other commands.
+///
+/// # Example
+///
+/// ```
+/// use vulkano::command_buffer::PipelineBarrier;
+/// use vulkano::sync::AccessFlagBits;
+/// use vulkano::sync::PipelineStages;
+/// # let buffer: std::sync::Arc<vulkano::buffer::DeviceLocalBuffer<[u32]>> = return;
+///
+/// let barrier = PipelineBarrier::new(
+///     PipelineStages { compute_shader: true, ..PipelineStages::none() },
+///     PipelineStages { draw_indirect: true, ..PipelineStages::none() },
+/// )
+/// .buffer_barrier(
+///     buffer.clone(),
+///     AccessFlagBits { shader_write: true, ..AccessFlagBits::none() },
+///     AccessFlagBits { indirect_command_read: true, ..AccessFlagBits::none() },
+/// );
+/// ```
+pub struct PipelineBarrier {
+    source_stage: PipelineStages,
+    destination_stage: PipelineStages,
+    by_region: bool,
+    memory_barriers: SmallVec<[(AccessFlagBits, AccessFlagBits); 2]>,
+    buffer_barriers: SmallVec<[BufferBarrier; 4]>,
+    image_barriers: SmallVec<[ImageBarrier; 4]>,
+}
+
+struct BufferBarrier {
+    buffer: Box<dyn BufferAccess + Send + Sync>,
+    source_access: AccessFlagBits,
+    destination_access: AccessFlagBits,
+}
+
+struct ImageBarrier {
+    image: Box<dyn ImageAccess + Send + Sync>,
+    layout: ImageLayout,
+    source_access: AccessFlagBits,
+    destination_access: AccessFlagBits,
+}
+
+impl PipelineBarrier {
+    /// Builds a pipeline barrier that makes the commands added after it wait until the commands
+    /// added before it have completed `source_stage`, before starting `destination_stage`.
+    ///
+    /// Without any memory, buffer or image barrier, this is only an execution dependency.
+    #[inline]
+    pub fn new(source_stage: PipelineStages, destination_stage: PipelineStages) -> PipelineBarrier {
+        PipelineBarrier {
+            source_stage,
+            destination_stage,
+            by_region: false,
+            memory_barriers: SmallVec::new(),
+            buffer_barriers: SmallVec::new(),
+            image_barriers: SmallVec::new(),
+        }
+    }
+
+    /// Makes the dependency framebuffer-local, which is cheaper on tiled architectures.
+    #[inline]
+    pub fn by_region(mut self) -> PipelineBarrier {
+        self.by_region = true;
+        self
+    }
+
+    /// Adds a global memory barrier, which applies to all the memory accessed by the device.
+    #[inline]
+    pub fn memory_barrier(
+        mut self,
+        source_access: AccessFlagBits,
+        destination_access: AccessFlagBits,
+    ) -> PipelineBarrier {
+        self.memory_barriers
+            .push((source_access, destination_access));
+        self
+    }
+
+    /// Adds a memory barrier that only applies to `buffer`.
+    #[inline]
+    pub fn buffer_barrier<B>(
+        mut self,
+        buffer: B,
+        source_access: AccessFlagBits,
+        destination_access: AccessFlagBits,
+    ) -> PipelineBarrier
+    where
+        B: BufferAccess + Send + Sync + 'static,
+    {
+        self.buffer_barriers.push(BufferBarrier {
+            buffer: Box::new(buffer),
+            source_access,
+            destination_access,
+        });
+        self
+    }
+
+    /// Adds a memory barrier that only applies to `image`. The layout of the image is not
+    /// changed.
+    ///
+    /// # Safety
+    ///
+    /// - All the mipmap levels and array layers of `image` must be in `layout` at the point of
+    ///   the command buffer where the barrier is added.
+    #[inline]
+    pub unsafe fn image_barrier<I>(
+        mut self,
+        image: I,
+        layout: ImageLayout,
+        source_access: AccessFlagBits,
+        destination_access: AccessFlagBits,
+    ) -> PipelineBarrier
+    where
+        I: ImageAccess + Send + Sync + 'static,
+    {
+        self.image_barriers.push(ImageBarrier {
+            image: Box::new(image),
+            layout,
+            source_access,
+            destination_access,
+        });
+        self
+    }
+
+    /// Returns the source and destination stages of the barrier.
+    #[inline]
+    pub fn stages(&self) -> (PipelineStages, PipelineStages) {
+        (self.source_stage, self.destination_stage)
+    }
+
+    /// Returns the source and destination accesses of all the memory, buffer and image barriers.
+    #[inline]
+    pub(crate) fn accesses<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = (AccessFlagBits, AccessFlagBits)> + 'a {
+        self.memory_barriers
+            .iter()
+            .cloned()
+            .chain(
+                self.buffer_barriers
+                    .iter()
+                    .map(|b| (b.source_access, b.destination_access)),
+            )
+            .chain(
+                self.image_barriers
+                    .iter()
+                    .map(|b| (b.source_access, b.destination_access)),
+            )
+    }
+
+    /// Returns the buffers of the buffer barriers.
+    #[inline]
+    pub(crate) fn buffers<'a>(&'a self) -> impl Iterator<Item = &'a dyn BufferAccess> + 'a {
+        self.buffer_barriers
+            .iter()
+            .map(|b| &*b.buffer as &dyn BufferAccess)
+    }
+
+    /// Returns the images and layouts of the image barriers.
+    #[inline]
+    pub(crate) fn images<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = (&'a dyn ImageAccess, ImageLayout)> + 'a {
+        self.image_barriers
+            .iter()
+            .map(|b| (&*b.image as &dyn ImageAccess, b.layout))
+    }
+
+    /// Builds the low-level barrier that corresponds to this one.
+    ///
+    /// # Safety
+    ///
+    /// - The barrier must have been checked with `check_pipeline_barrier`.
+    pub(crate) unsafe fn build(&self) -> UnsafeCommandBufferBuilderPipelineBarrier {
+        let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+        barrier.add_execution_dependency(self.source_stage, self.destination_stage, self.by_region);
+
+        for &(source_access, destination_access) in self.memory_barriers.iter() {
+            barrier.add_memory_barrier(
+                self.source_stage,
+                source_access,
+                self.destination_stage,
+                destination_access,
+                self.by_region,
+            );
+        }
+
+        for b in self.buffer_barriers.iter() {
+            barrier.add_buffer_memory_barrier(
+                &*b.buffer,
+                self.source_stage,
+                b.source_access,
+                self.destination_stage,
+                b.destination_access,
+                self.by_region,
+                None,
+                0,
+                b.buffer.size(),
+            );
+        }
+
+        for b in self.image_barriers.iter() {
+            barrier.add_image_memory_barrier(
+                &*b.image,
+                0..b.image.mipmap_levels(),
+                0..b.image.dimensions().array_layers(),
+                self.source_stage,
+                b.source_access,
+                self.destination_stage,
+                b.destination_access,
+                self.by_region,
+                None,
+                b.layout,
+                b.layout,
+            );
+        }
+
+        barrier
+    }
+}
@@ -0,0 +1,643 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Frustum culling of indirect draws on the GPU.
+//!
+//! This module is a building block for GPU-driven rendering. Each object of a scene is described
+//! by a `DrawIndexedIndirectCommand` and a bounding sphere. A compute shader tests each sphere
+//! against the planes of the view frustum, and writes the draw commands of the visible objects
+//! next to each other in an output buffer while counting them. The output and the count can
+//! then be passed directly to `draw_indexed_indirect_count`.
+//!
+//! The shader is provided already compiled to SPIR-V as `CULLING_SHADER_SPIRV`, and
+//! `CullingPipeline::new` creates a compute pipeline from it. Its GLSL source is provided as
+//! `CULLING_SHADER_SOURCE` for reference.
+//!
+//! The culling is then recorded with `AutoCommandBufferBuilder::cull_draws`, which takes:
+//!
+//! - A buffer of `CullingBounds`, one for each object.
+//! - A buffer of `DrawIndexedIndirectCommand`, one for each object.
+//! - A buffer of `DrawIndexedIndirectCommand` that receives the draws of the visible objects.
+//! - A buffer containing a single `u32` that receives the number of visible objects. It is reset
+//!   before the culling.
+//!
+//! All these buffers must have the storage buffer usage. The count buffer must also have the
+//! transfer destination usage, since it is reset with `fill_buffer`.
+//!
+//! # Example
+//!
+//! ```
+//! use vulkano::command_buffer::culling;
+//! use vulkano::command_buffer::culling::CullingPipeline;
+//! use vulkano::command_buffer::AutoCommandBufferBuilder;
+//! # use std::sync::Arc;
+//! # use vulkano::buffer::DeviceLocalBuffer;
+//! # use vulkano::command_buffer::DrawIndexedIndirectCommand;
+//! # use vulkano::command_buffer::DynamicState;
+//! # use vulkano::pipeline::GraphicsPipelineAbstract;
+//! # let device: Arc<vulkano::device::Device> = return;
+//! # let mut builder: AutoCommandBufferBuilder = return;
+//! # let bounds: Arc<DeviceLocalBuffer<[culling::CullingBounds]>> = return;
+//! # let draws: Arc<DeviceLocalBuffer<[DrawIndexedIndirectCommand]>> = return;
+//! # let visible_draws: Arc<DeviceLocalBuffer<[DrawIndexedIndirectCommand]>> = return;
+//! # let count_buffer: Arc<DeviceLocalBuffer<u32>> = return;
+//! # let graphics_pipeline: Arc<GraphicsPipelineAbstract + Send + Sync> = return;
+//! # let dynamic_state: DynamicState = return;
+//! # let vertex_buffer: Arc<DeviceLocalBuffer<[[f32; 3]]>> = return;
+//! # let index_buffer: Arc<DeviceLocalBuffer<[u32]>> = return;
+//! # let view_projection: [[f32; 4]; 4] = return;
+//! # let num_objects: u32 = return;
+//! let culling_pipeline = CullingPipeline::new(device.clone()).unwrap();
+//! let planes = culling::frustum_planes(view_projection);
+//!
+//! builder
+//!     .cull_draws(
+//!         &culling_pipeline,
+//!         bounds,
+//!         draws,
+//!         visible_draws.clone(),
+//!         count_buffer.clone(),
+//!         planes,
+//!         num_objects,
+//!     )
+//!     .unwrap();
+//!
+//! // Later, inside a render pass.
+//! builder
+//!     .draw_indexed_indirect_count(
+//!         graphics_pipeline,
+//!         &dynamic_state,
+//!         vec![vertex_buffer],
+//!         index_buffer,
+//!         visible_draws,
+//!         count_buffer,
+//!         (),
+//!         (),
+//!     )
+//!     .unwrap();
+//! ```
+
+use std::error;
+use std::ffi::CStr;
+use std::fmt;
+use std::mem;
+use std::sync::Arc;
+
+use descriptor::descriptor::DescriptorBufferDesc;
+use descriptor::descriptor::DescriptorDesc;
+use descriptor::descriptor::DescriptorDescTy;
+use descriptor::descriptor::ShaderStages;
+use descriptor::pipeline_layout::PipelineLayout;
+use descriptor::pipeline_layout::PipelineLayoutDesc;
+use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+use device::Device;
+use pipeline::shader::ShaderModule;
+use pipeline::ComputePipeline;
+use pipeline::ComputePipelineCreationError;
+
+/// GLSL source of the culling compute shader. See the documentation of the module.
+pub const CULLING_SHADER_SOURCE: &str = "
+#version 450
+
+layout(local_size_x = 64, local_size_y = 1, local_size_z = 1) in;
+
+struct DrawIndexedIndirectCommand {
+    uint index_count;
+    uint instance_count;
+    uint first_index;
+    int vertex_offset;
+    uint first_instance;
+};
+
+layout(set = 0, binding = 0) readonly buffer Bounds {
+    vec4 bounds[];
+};
+
+layout(set = 0, binding = 1) readonly buffer InputDraws {
+    DrawIndexedIndirectCommand input_draws[];
+};
+
+layout(set = 0, binding = 2) writeonly buffer OutputDraws {
+    DrawIndexedIndirectCommand output_draws[];
+};
+
+layout(set = 0, binding = 3) buffer Count {
+    uint count;
+};
+
+layout(push_constant) uniform PushConstants {
+    vec4 planes[6];
+    uint draw_count;
+} push_constants;
+
+void main() {
+    uint index = gl_GlobalInvocationID.x;
+    if (index >= push_constants.draw_count) {
+        return;
+    }
+
+    vec4 sphere = bounds[index];
+    for (int i = 0; i < 6; i++) {
+        vec4 plane = push_constants.planes[i];
+        if (dot(plane.xyz, sphere.xyz) + plane.w < -sphere.w) {
+            return;
+        }
+    }
+
+    uint slot = atomicAdd(count, 1);
+    output_draws[slot] = input_draws[index];
+}
+";
+
+/// SPIR-V code of the culling shader, compiled from `CULLING_SHADER_SOURCE`.
+pub static CULLING_SHADER_SPIRV: [u32; 780] = [
+    119734787, 65536, 524296, 105, 0, 131089, 1, 393227, 1, 1280527431, 1685353262, 808793134, 0,
+    196622, 0, 1, 393231, 5, 4, 1852399981, 0, 11, 393232, 4, 17, 64, 1, 1, 196611, 2, 450, 262149,
+    4, 1852399981, 0, 262149, 8, 1701080681, 120, 524293, 11, 1197436007, 1633841004, 1986939244,
+    1952539503, 1231974249, 68, 393221, 21, 1752397136, 1936617283, 1953390964, 115, 327686, 21, 0,
+    1851878512, 29541, 393222, 21, 1, 2002874980, 1970234207, 29806, 393221, 23, 1752397168,
+    1852793695, 1851880563, 29556, 262149, 35, 1701343347, 25970, 262149, 37, 1853189954, 29540,
+    327686, 37, 0, 1853189986, 29540, 196613, 39, 0, 196613, 46, 105, 262149, 55, 1851878512, 101,
+    262149, 80, 1953459315, 0, 262149, 81, 1853189955, 116, 327686, 81, 0, 1853189987, 116, 196613,
+    83, 0, 589829, 88, 2002874948, 1701080649, 1231316344, 1919509614, 1131701093, 1634561391,
+    25710, 393222, 88, 0, 1701080681, 1868783480, 7630453, 458758, 88, 1, 1953721961, 1701015137,
+    1970234207, 29806, 393222, 88, 2, 1936877926, 1852399476, 7890276, 458758, 88, 3, 1953654134,
+    1868527717, 1702061670, 116, 458758, 88, 4, 1936877926, 1852399476, 1851880563, 25955, 327685,
+    90, 1886680399, 1917088885, 7567201, 458758, 90, 0, 1886680431, 1683977333, 1937203570, 0,
+    196613, 92, 0, 327685, 95, 1970302537, 1634878580, 29559, 393222, 95, 0, 1970302569,
+    1919180660, 7567201, 196613, 97, 0, 262215, 11, 11, 28, 262215, 20, 6, 16, 327752, 21, 0, 35,
+    0, 327752, 21, 1, 35, 96, 196679, 21, 2, 262215, 36, 6, 16, 262216, 37, 0, 24, 327752, 37, 0,
+    35, 0, 196679, 37, 3, 262215, 39, 34, 0, 262215, 39, 33, 0, 327752, 81, 0, 35, 0, 196679, 81,
+    3, 262215, 83, 34, 0, 262215, 83, 33, 3, 327752, 88, 0, 35, 0, 327752, 88, 1, 35, 4, 327752,
+    88, 2, 35, 8, 327752, 88, 3, 35, 12, 327752, 88, 4, 35, 16, 262215, 89, 6, 20, 262216, 90, 0,
+    25, 327752, 90, 0, 35, 0, 196679, 90, 3, 262215, 92, 34, 0, 262215, 92, 33, 2, 262215, 94, 6,
+    20, 262216, 95, 0, 24, 327752, 95, 0, 35, 0, 196679, 95, 3, 262215, 97, 34, 0, 262215, 97, 33,
+    1, 262215, 104, 11, 25, 131091, 2, 196641, 3, 2, 262165, 6, 32, 0, 262176, 7, 7, 6, 262167, 9,
+    6, 3, 262176, 10, 1, 9, 262203, 10, 11, 1, 262187, 6, 12, 0, 262176, 13, 1, 6, 196630, 17, 32,
+    262167, 18, 17, 4, 262187, 6, 19, 6, 262172, 20, 18, 19, 262174, 21, 20, 6, 262176, 22, 9, 21,
+    262203, 22, 23, 9, 262165, 24, 32, 1, 262187, 24, 25, 1, 262176, 26, 9, 6, 131092, 29, 262176,
+    34, 7, 18, 196637, 36, 18, 196638, 37, 36, 262176, 38, 2, 37, 262203, 38, 39, 2, 262187, 24,
+    40, 0, 262176, 42, 2, 18, 262176, 45, 7, 24, 262187, 24, 53, 6, 262176, 57, 9, 18, 262167, 60,
+    17, 3, 262187, 6, 66, 3, 262176, 67, 7, 17, 196638, 81, 6, 262176, 82, 2, 81, 262203, 82, 83,
+    2, 262176, 84, 2, 6, 262187, 6, 86, 1, 458782, 88, 6, 6, 6, 24, 6, 196637, 89, 88, 196638, 90,
+    89, 262176, 91, 2, 90, 262203, 91, 92, 2, 196637, 94, 88, 196638, 95, 94, 262176, 96, 2, 95,
+    262203, 96, 97, 2, 262176, 99, 2, 88, 262187, 6, 103, 64, 393260, 9, 104, 103, 86, 86, 327734,
+    2, 4, 0, 3, 131320, 5, 262203, 7, 8, 7, 262203, 34, 35, 7, 262203, 45, 46, 7, 262203, 34, 55,
+    7, 262203, 7, 80, 7, 327745, 13, 14, 11, 12, 262205, 6, 15, 14, 196670, 8, 15, 262205, 6, 16,
+    8, 327745, 26, 27, 23, 25, 262205, 6, 28, 27, 327854, 29, 30, 16, 28, 196855, 32, 0, 262394,
+    30, 31, 32, 131320, 31, 65789, 131320, 32, 262205, 6, 41, 8, 393281, 42, 43, 39, 40, 41,
+    262205, 18, 44, 43, 196670, 35, 44, 196670, 46, 40, 131321, 47, 131320, 47, 262390, 49, 50, 0,
+    131321, 51, 131320, 51, 262205, 24, 52, 46, 327857, 29, 54, 52, 53, 262394, 54, 48, 49, 131320,
+    48, 262205, 24, 56, 46, 393281, 57, 58, 23, 40, 56, 262205, 18, 59, 58, 196670, 55, 59, 262205,
+    18, 61, 55, 524367, 60, 62, 61, 61, 0, 1, 2, 262205, 18, 63, 35, 524367, 60, 64, 63, 63, 0, 1,
+    2, 327828, 17, 65, 62, 64, 327745, 67, 68, 55, 66, 262205, 17, 69, 68, 327809, 17, 70, 65, 69,
+    327745, 67, 71, 35, 66, 262205, 17, 72, 71, 262271, 17, 73, 72, 327864, 29, 74, 70, 73, 196855,
+    76, 0, 262394, 74, 75, 76, 131320, 75, 65789, 131320, 76, 131321, 50, 131320, 50, 262205, 24,
+    78, 46, 327808, 24, 79, 78, 25, 196670, 46, 79, 131321, 47, 131320, 49, 327745, 84, 85, 83, 40,
+    458986, 6, 87, 85, 86, 12, 86, 196670, 80, 87, 262205, 6, 93, 80, 262205, 6, 98, 8, 393281, 99,
+    100, 97, 40, 98, 262205, 88, 101, 100, 393281, 99, 102, 92, 40, 93, 196670, 102, 101, 65789,
+    65592,
+];
+
+/// Number of objects processed by each work group of the culling shader.
+pub const CULLING_WORK_GROUP_SIZE: u32 = 64;
+
+/// Bounding sphere of an object, as read by the culling shader.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct CullingBounds {
+    /// Center of the sphere, in the same space as the frustum planes.
+    pub center: [f32; 3],
+    /// Radius of the sphere.
+    pub radius: f32,
+}
+
+impl_buffer_contents!(CullingBounds {
+    center: [f32; 3],
+    radius: f32,
+});
+
+/// Push constants of the culling shader.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CullingPushConstants {
+    /// Planes of the frustum, as returned by `frustum_planes`.
+    pub planes: [[f32; 4]; 6],
+    /// Number of objects to process.
+    pub draw_count: u32,
+}
+
+/// Compute pipeline created from `CULLING_SHADER_SPIRV`, to pass to `cull_draws`.
+#[derive(Clone)]
+pub struct CullingPipeline {
+    pipeline: Arc<ComputePipeline<PipelineLayout<CullingPipelineLayout>>>,
+}
+
+impl CullingPipeline {
+    /// Creates the culling pipeline.
+    pub fn new(device: Arc<Device>) -> Result<CullingPipeline, ComputePipelineCreationError> {
+        unsafe {
+            let module = ShaderModule::from_words(device.clone(), &CULLING_SHADER_SPIRV)?;
+            let name = CStr::from_bytes_with_nul_unchecked(b"main\0");
+            let shader = module.compute_entry_point(name, CullingPipelineLayout);
+            let pipeline = ComputePipeline::new(device, &shader, &())?;
+
+            Ok(CullingPipeline {
+                pipeline: Arc::new(pipeline),
+            })
+        }
+    }
+
+    /// Returns the compute pipeline.
+    #[inline]
+    pub fn pipeline(&self) -> &Arc<ComputePipeline<PipelineLayout<CullingPipelineLayout>>> {
+        &self.pipeline
+    }
+}
+
+/// Description of the layout of the culling pipeline.
+#[derive(Debug, Copy, Clone)]
+pub struct CullingPipelineLayout;
+
+unsafe impl PipelineLayoutDesc for CullingPipelineLayout {
+    #[inline]
+    fn num_sets(&self) -> usize {
+        1
+    }
+
+    #[inline]
+    fn num_bindings_in_set(&self, set: usize) -> Option<usize> {
+        match set {
+            0 => Some(4),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn descriptor(&self, set: usize, binding: usize) -> Option<DescriptorDesc> {
+        if set != 0 || binding >= 4 {
+            return None;
+        }
+
+        Some(DescriptorDesc {
+            ty: DescriptorDescTy::Buffer(DescriptorBufferDesc {
+                dynamic: Some(false),
+                storage: true,
+            }),
+            array_count: 1,
+            stages: ShaderStages {
+                compute: true,
+                ..ShaderStages::none()
+            },
+            readonly: binding < 2,
+        })
+    }
+
+    #[inline]
+    fn num_push_constants_ranges(&self) -> usize {
+        1
+    }
+
+    #[inline]
+    fn push_constants_range(&self, num: usize) -> Option<PipelineLayoutDescPcRange> {
+        if num != 0 {
+            return None;
+        }
+
+        Some(PipelineLayoutDescPcRange {
+            offset: 0,
+            size: mem::size_of::<CullingPushConstants>(),
+            stages: ShaderStages {
+                compute: true,
+                ..ShaderStages::none()
+            },
+        })
+    }
+}
+
+/// Checks whether the buffers passed to `cull_draws` contain at least `draw_count` elements.
+pub fn check_cull_draws(
+    bounds_len: usize,
+    input_draws_len: usize,
+    output_draws_len: usize,
+    draw_count: u32,
+) -> Result<(), CheckCullDrawsError> {
+    let required = draw_count as usize;
+
+    if bounds_len < required {
+        return Err(CheckCullDrawsError::BoundsBufferTooSmall {
+            required: required,
+            obtained: bounds_len,
+        });
+    }
+
+    if input_draws_len < required {
+        return Err(CheckCullDrawsError::InputDrawsBufferTooSmall {
+            required: required,
+            obtained: input_draws_len,
+        });
+    }
+
+    if output_draws_len < required {
+        return Err(CheckCullDrawsError::OutputDrawsBufferTooSmall {
+            required: required,
+            obtained: output_draws_len,
+        });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking whether culling draws is valid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckCullDrawsError {
+    /// The buffer of bounds contains fewer elements than the number of draws.
+    BoundsBufferTooSmall {
+        /// Number of draws.
+        required: usize,
+        /// Number of elements in the buffer.
+        obtained: usize,
+    },
+    /// The buffer of input draws contains fewer elements than the number of draws.
+    InputDrawsBufferTooSmall {
+        /// Number of draws.
+        required: usize,
+        /// Number of elements in the buffer.
+        obtained: usize,
+    },
+    /// The buffer of output draws contains fewer elements than the number of draws.
+    OutputDrawsBufferTooSmall {
+        /// Number of draws.
+        required: usize,
+        /// Number of elements in the buffer.
+        obtained: usize,
+    },
+}
+
+impl error::Error for CheckCullDrawsError {}
+
+impl fmt::Display for CheckCullDrawsError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckCullDrawsError::BoundsBufferTooSmall { .. } => {
+                    "the buffer of bounds contains fewer elements than the number of draws"
+                }
+                CheckCullDrawsError::InputDrawsBufferTooSmall { .. } => {
+                    "the buffer of input draws contains fewer elements than the number of draws"
+                }
+                CheckCullDrawsError::OutputDrawsBufferTooSmall { .. } => {
+                    "the buffer of output draws contains fewer elements than the number of draws"
+                }
+            }
+        )
+    }
+}
+
+/// Extracts the planes of the view frustum from a view-projection matrix.
+///
+/// The matrix is in column-major order, and must map the frustum to the clip space of Vulkan,
+/// whose depth goes from 0 to 1. Each plane is returned as `[a, b, c, d]`, with a normal of
+/// length 1 that points inside the frustum. A point `p` is inside the frustum if
+/// `a * p.x + b * p.y + c * p.z + d >= 0` for all the planes. The order of the planes is left,
+/// right, bottom, top, near and far.
+pub fn frustum_planes(matrix: [[f32; 4]; 4]) -> [[f32; 4]; 6] {
+    let row = |i: usize| [matrix[0][i], matrix[1][i], matrix[2][i], matrix[3][i]];
+    let add = |a: [f32; 4], b: [f32; 4]| [a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3]];
+    let sub = |a: [f32; 4], b: [f32; 4]| [a[0] - b[0], a[1] - b[1], a[2] - b[2], a[3] - b[3]];
+    let normalize = |p: [f32; 4]| {
+        let length = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+        if length == 0.0 {
+            p
+        } else {
+            [p[0] / length, p[1] / length, p[2] / length, p[3] / length]
+        }
+    };
+
+    [
+        normalize(add(row(3), row(0))),
+        normalize(sub(row(3), row(0))),
+        normalize(add(row(3), row(1))),
+        normalize(sub(row(3), row(1))),
+        normalize(row(2)),
+        normalize(sub(row(3), row(2))),
+    ]
+}
+
+/// Returns true if the sphere is at least partially inside the frustum described by `planes`.
+///
+/// This performs the same test as the culling shader.
+pub fn is_visible(planes: &[[f32; 4]; 6], bounds: &CullingBounds) -> bool {
+    planes.iter().all(|plane| {
+        let distance = plane[0] * bounds.center[0]
+            + plane[1] * bounds.center[1]
+            + plane[2] * bounds.center[2]
+            + plane[3];
+        distance >= -bounds.radius
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use command_buffer::culling::check_cull_draws;
+    use command_buffer::culling::frustum_planes;
+    use command_buffer::culling::is_visible;
+    use command_buffer::culling::CheckCullDrawsError;
+    use command_buffer::culling::CullingBounds;
+    use command_buffer::culling::CullingPipeline;
+    use command_buffer::culling::CULLING_SHADER_SPIRV;
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::DrawIndexedIndirectCommand;
+    use sync::now;
+    use sync::GpuFuture;
+
+    const IDENTITY: [[f32; 4]; 4] = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ];
+
+    fn draw(first_instance: u32) -> DrawIndexedIndirectCommand {
+        DrawIndexedIndirectCommand {
+            index_count: 3,
+            instance_count: 1,
+            first_index: 0,
+            vertex_offset: 0,
+            first_instance: first_instance,
+        }
+    }
+
+    #[test]
+    fn spirv_magic_number() {
+        assert_eq!(CULLING_SHADER_SPIRV[0], 0x07230203);
+    }
+
+    #[test]
+    fn buffer_sizes_checked() {
+        assert_eq!(check_cull_draws(4, 4, 4, 4), Ok(()));
+        assert_eq!(
+            check_cull_draws(3, 4, 4, 4),
+            Err(CheckCullDrawsError::BoundsBufferTooSmall {
+                required: 4,
+                obtained: 3,
+            })
+        );
+        assert_eq!(
+            check_cull_draws(4, 3, 4, 4),
+            Err(CheckCullDrawsError::InputDrawsBufferTooSmall {
+                required: 4,
+                obtained: 3,
+            })
+        );
+        assert_eq!(
+            check_cull_draws(4, 4, 3, 4),
+            Err(CheckCullDrawsError::OutputDrawsBufferTooSmall {
+                required: 4,
+                obtained: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn draws_culled_on_gpu() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pipeline = CullingPipeline::new(device.clone()).unwrap();
+
+        let bounds = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage::all(),
+            false,
+            vec![
+                CullingBounds {
+                    center: [0.0, 0.0, 0.5],
+                    radius: 0.1,
+                },
+                CullingBounds {
+                    center: [0.0, -3.0, 0.5],
+                    radius: 1.0,
+                },
+                CullingBounds {
+                    center: [1.2, 0.0, 0.5],
+                    radius: 0.5,
+                },
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let input_draws = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage::all(),
+            false,
+            (0..3).map(draw),
+        )
+        .unwrap();
+        let output_draws = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage::all(),
+            false,
+            (0..3).map(|_| draw(u32::max_value())),
+        )
+        .unwrap();
+        let count = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 7u32)
+            .unwrap();
+
+        let mut cbb =
+            AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), queue.family())
+                .unwrap();
+        cbb.cull_draws(
+            &pipeline,
+            bounds,
+            input_draws,
+            output_draws.clone(),
+            count.clone(),
+            frustum_planes(IDENTITY),
+            3,
+        )
+        .unwrap();
+        let cb = cbb.build().unwrap();
+
+        let future = now(device.clone())
+            .then_execute(queue.clone(), cb)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+        future.wait(None).unwrap();
+
+        assert_eq!(*count.read().unwrap(), 2);
+        // The order of the visible draws depends on the order of execution of the invocations.
+        let mut visible = output_draws.read().unwrap()[..2]
+            .iter()
+            .map(|d| d.first_instance)
+            .collect::<Vec<_>>();
+        visible.sort();
+        assert_eq!(visible, vec![0, 2]);
+    }
+
+    #[test]
+    fn planes_of_clip_space() {
+        let identity = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+
+        assert_eq!(
+            frustum_planes(identity),
+            [
+                [1.0, 0.0, 0.0, 1.0],
+                [-1.0, 0.0, 0.0, 1.0],
+                [0.0, 1.0, 0.0, 1.0],
+                [0.0, -1.0, 0.0, 1.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, -1.0, 1.0],
+            ]
+        );
+    }
+
+    #[test]
+    fn spheres_culled() {
+        let identity = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        let planes = frustum_planes(identity);
+
+        let inside = CullingBounds {
+            center: [0.0, 0.0, 0.5],
+            radius: 0.1,
+        };
+        let straddling = CullingBounds {
+            center: [1.2, 0.0, 0.5],
+            radius: 0.5,
+        };
+        let outside = CullingBounds {
+            center: [0.0, -3.0, 0.5],
+            radius: 1.0,
+        };
+        let behind = CullingBounds {
+            center: [0.0, 0.0, -2.0],
+            radius: 1.0,
+        };
+
+        assert!(is_visible(&planes, &inside));
+        assert!(is_visible(&planes, &straddling));
+        assert!(!is_visible(&planes, &outside));
+        assert!(!is_visible(&planes, &behind));
+    }
+}
@@ -34,6 +34,58 @@
 //!   operations, it might be a good idea to build a secondary command buffer once at
 //!   initialization and then reuse it afterwards.
 //!
+//! # Reusing command buffers
+//!
+//! The flags passed when creating an `AutoCommandBufferBuilder` determine how many times the
+//! resulting command buffer can be submitted:
+//!
+//! - `primary_one_time_submit` creates a command buffer that can only be submitted once.
+//! - `primary` creates a command buffer that can be submitted multiple times, but only once at a
+//!   time. Submitting it again while a previous submission is still in progress returns an error.
+//! - `primary_simultaneous_use` creates a command buffer that can be submitted multiple times,
+//!   even while previous submissions are still in progress.
+//!
+//! The same applies to the `secondary_*` constructors. The command buffers of the standard
+//! command pool are reset individually and reused once the `AutoCommandBuffer` is destroyed, but
+//! an `AutoCommandBuffer` itself can't be reset and recorded again. When
+//! using an `UnsafeCommandPool` directly, a command buffer can be reset with
+//! `reset_command_buffer` if the pool was created with `reset_cb` set to true.
+//!
+//! # Recording in parallel
+//!
+//! Vulkan command pools can only be used by one thread at a time. The default command pool
+//! returned by `Device::standard_command_pool` handles this by using a separate Vulkan pool for
+//! each thread, which is why an `AutoCommandBufferBuilder` that uses it can't be sent to another
+//! thread. Each thread can build its own secondary command buffers instead, which can be sent
+//! once built. The primary command buffer then gathers them with `execute_commands_from_vec`,
+//! and all of them are submitted at once with the primary command buffer.
+//!
+//! ```
+//! use std::thread;
+//! use vulkano::command_buffer::AutoCommandBufferBuilder;
+//!
+//! # let device: std::sync::Arc<vulkano::device::Device> = return;
+//! # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+//! let threads = (0..4).map(|_| {
+//!     let device = device.clone();
+//!     let queue_family = queue.family().id();
+//!     thread::spawn(move || {
+//!         let queue_family = device.physical_device().queue_family_by_id(queue_family).unwrap();
+//!         let builder = AutoCommandBufferBuilder::secondary_compute(device.clone(), queue_family)
+//!             .unwrap();
+//!         // add commands here
+//!         builder.build().unwrap()
+//!     })
+//! }).collect::<Vec<_>>();
+//!
+//! let secondaries = threads.into_iter().map(|t| t.join().unwrap()).collect();
+//!
+//! let mut builder = AutoCommandBufferBuilder::primary_one_time_submit(device.clone(),
+//!                                                                     queue.family()).unwrap();
+//! unsafe { builder.execute_commands_from_vec(secondaries).unwrap(); }
+//! let command_buffer = builder.build().unwrap();
+//! ```
+//!
 //! # The `AutoCommandBufferBuilder`
 //!
 //! The most basic (and recommended) way to create a command buffer is to create a
@@ -60,6 +112,26 @@
 //! let _future = cb.execute(queue.clone());
 //! ```
 //!
+//! # Resource lifetime
+//!
+//! Command buffers hold an `Arc` or a clone of every buffer, image, pipeline and descriptor set
+//! that is used by their commands, from the moment the command is added to the builder until the
+//! command buffer is destroyed. The `GpuFuture` returned by `execute` holds the command buffer,
+//! and destroying a future that has been flushed blocks until the GPU has finished executing it.
+//! This means that the resources can't be destroyed while they are still in use, even if all the
+//! other references to them are dropped.
+//!
+//! When a fence is signaled at the end of the submission with `then_signal_fence`, calling
+//! `cleanup_finished` on the future after the fence is signaled releases the command buffer, and
+//! the resources it holds, without blocking.
+//!
+//! # Synchronization
+//!
+//! The `AutoCommandBufferBuilder` keeps track of the way each command accesses the buffers and
+//! images it uses, and automatically inserts the pipeline barriers and image layout transitions
+//! needed between two commands that access the same resource. Accesses to memory that the builder
+//! can't see can be synchronized with the `memory_barrier` method.
+//!
 //! # Internal architecture of vulkano
 //!
 //! The `commands_raw` and `commands_extra` modules contain structs that correspond to various
@@ -76,31 +148,65 @@
 pub use self::auto::AutoCommandBuffer;
 pub use self::auto::AutoCommandBufferBuilder;
 pub use self::auto::AutoCommandBufferBuilderContextError;
+pub use self::auto::BeginQueryError;
 pub use self::auto::BeginRenderPassError;
+pub use self::auto::BindDescriptorSetsError;
+pub use self::auto::BindIndexBufferError;
+pub use self::auto::BindVertexBuffersError;
 pub use self::auto::BlitImageError;
 pub use self::auto::BuildError;
 pub use self::auto::ClearColorImageError;
+pub use self::auto::ClearDepthStencilImageError;
+pub use self::auto::CommandBufferStatistics;
 pub use self::auto::CopyBufferError;
 pub use self::auto::CopyBufferImageError;
 pub use self::auto::CopyImageError;
+pub use self::auto::CopyImageToSwapchainImageError;
+pub use self::auto::CullDrawsError;
+pub use self::auto::DebugLabelError;
 pub use self::auto::DispatchError;
+pub use self::auto::DispatchIndirectError;
 pub use self::auto::DrawError;
 pub use self::auto::DrawIndexedError;
 pub use self::auto::DrawIndexedIndirectError;
 pub use self::auto::DrawIndirectError;
+pub use self::auto::DrawMeshTasksError;
+pub use self::auto::DrawMeshTasksIndirectError;
 pub use self::auto::ExecuteCommandsError;
 pub use self::auto::FillBufferError;
+pub use self::auto::GenerateMipmapsError;
+pub use self::auto::MemoryBarrierError;
+pub use self::auto::PushConstantsError;
+pub use self::auto::QueueFamilyTransferError;
+pub use self::auto::ResetEventError;
+pub use self::auto::ResetQueryPoolError;
+pub use self::auto::ResolveImageError;
+pub use self::auto::SetEventError;
+pub use self::auto::TraceRaysError;
 pub use self::auto::UpdateBufferError;
+pub use self::auto::WaitEventsError;
+pub use self::auto::WriteTimestampError;
+pub use self::barrier::PipelineBarrier;
 pub use self::state_cacher::StateCacher;
 pub use self::state_cacher::StateCacherOutcome;
 pub use self::traits::CommandBuffer;
 pub use self::traits::CommandBufferExecError;
 pub use self::traits::CommandBufferExecFuture;
+pub use self::typed::InlineCommandsState;
+pub use self::typed::InsideRenderPass;
+pub use self::typed::InsideRenderPassSecondary;
+pub use self::typed::OutsideRenderPass;
+pub use self::typed::TypedAutoCommandBufferBuilder;
+
+use std::ops::Range;
 
+use image::ImageAspect;
 use pipeline::depth_stencil::DynamicStencilValue;
+use pipeline::raster::DepthBias;
 use pipeline::viewport::Scissor;
 use pipeline::viewport::Viewport;
 
+pub mod culling;
 pub mod pool;
 pub mod submit;
 pub mod synced;
@@ -108,9 +214,13 @@ pub mod sys;
 pub mod validity;
 
 mod auto;
+mod barrier;
 mod state_cacher;
 mod traits;
+mod typed;
 
+/// Parameters of a single draw of a `draw_indirect` command, as read by the GPU from the
+/// indirect buffer.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct DrawIndirectCommand {
@@ -120,16 +230,34 @@ pub struct DrawIndirectCommand {
     pub first_instance: u32,
 }
 
+impl_buffer_contents!(DrawIndirectCommand {
+    vertex_count: u32,
+    instance_count: u32,
+    first_vertex: u32,
+    first_instance: u32,
+});
+
+/// Parameters of a single draw of a `draw_indexed_indirect` command, as read by the GPU from the
+/// indirect buffer.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct DrawIndexedIndirectCommand {
     pub index_count: u32,
     pub instance_count: u32,
     pub first_index: u32,
-    pub vertex_offset: u32,
+    pub vertex_offset: i32,
     pub first_instance: u32,
 }
 
+impl_buffer_contents!(DrawIndexedIndirectCommand {
+    index_count: u32,
+    instance_count: u32,
+    first_index: u32,
+    vertex_offset: i32,
+    first_instance: u32,
+});
+
+/// Parameters of a `dispatch_indirect` command, as read by the GPU from the indirect buffer.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct DispatchIndirectCommand {
@@ -138,6 +266,119 @@ pub struct DispatchIndirectCommand {
     pub z: u32,
 }
 
+impl_buffer_contents!(DispatchIndirectCommand {
+    x: u32,
+    y: u32,
+    z: u32,
+});
+
+/// Parameters of a single draw of a `draw_mesh_tasks_indirect` command, as read by the GPU from
+/// the indirect buffer.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DrawMeshTasksIndirectCommand {
+    pub group_count_x: u32,
+    pub group_count_y: u32,
+    pub group_count_z: u32,
+}
+
+impl_buffer_contents!(DrawMeshTasksIndirectCommand {
+    group_count_x: u32,
+    group_count_y: u32,
+    group_count_z: u32,
+});
+
+/// Region of a copy between a buffer and an image.
+///
+/// Used by `copy_buffer_to_image_regions` and `copy_image_to_buffer_regions`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BufferImageCopyRegion {
+    /// Offset of the first texel in the buffer, in number of elements of the buffer.
+    pub buffer_offset: usize,
+    /// Number of texels between the start of two consecutive rows in the buffer. If 0, the rows
+    /// are tightly packed and this is equal to `image_extent[0]`.
+    pub buffer_row_length: u32,
+    /// Number of rows between the start of two consecutive depth slices or array layers in the
+    /// buffer. If 0, the slices are tightly packed and this is equal to `image_extent[1]`.
+    pub buffer_image_height: u32,
+    /// Aspect of the image to copy. Depth and stencil aspects must be copied separately.
+    pub image_aspect: ImageAspect,
+    /// Mipmap level of the image to copy.
+    pub image_mip_level: u32,
+    /// First array layer of the image to copy.
+    pub image_base_array_layer: u32,
+    /// Number of array layers to copy.
+    pub image_layer_count: u32,
+    /// Coordinates of the first texel of the image to copy.
+    pub image_offset: [u32; 3],
+    /// Size of the copy in the image, in texels.
+    pub image_extent: [u32; 3],
+}
+
+/// A region of a buffer to copy to another buffer, or to another region of the same buffer.
+///
+/// Used by `copy_buffer_regions`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BufferCopyRegion {
+    /// Offset of the first byte to copy in the source.
+    pub source_offset: usize,
+    /// Offset of the first byte to write in the destination.
+    pub destination_offset: usize,
+    /// Number of bytes to copy.
+    pub size: usize,
+}
+
+/// A region of an image to copy to another image, or to another region of the same image.
+///
+/// Used by `copy_image_regions`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ImageCopyRegion {
+    /// Coordinates of the first texel to copy in the source.
+    pub source_offset: [i32; 3],
+    /// First array layer of the source to copy.
+    pub source_base_array_layer: u32,
+    /// Mipmap level of the source to copy.
+    pub source_mip_level: u32,
+    /// Coordinates of the first texel to write in the destination.
+    pub destination_offset: [i32; 3],
+    /// First array layer of the destination to write.
+    pub destination_base_array_layer: u32,
+    /// Mipmap level of the destination to write.
+    pub destination_mip_level: u32,
+    /// Size of the copy, in texels.
+    pub extent: [u32; 3],
+    /// Number of array layers to copy.
+    pub layer_count: u32,
+}
+
+/// A region of an image to blit to another image.
+///
+/// Used by `blit_image_regions`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ImageBlitRegion {
+    /// Aspect of the images to blit. The depth and stencil aspects of an image must be blitted
+    /// with separate regions.
+    pub aspect: ImageAspect,
+    /// Coordinates of the first corner of the area to blit in the source.
+    pub source_top_left: [i32; 3],
+    /// Coordinates of the opposite corner of the area to blit in the source.
+    pub source_bottom_right: [i32; 3],
+    /// First array layer of the source to blit.
+    pub source_base_array_layer: u32,
+    /// Mipmap level of the source to blit.
+    pub source_mip_level: u32,
+    /// Coordinates of the first corner of the area to write in the destination.
+    pub destination_top_left: [i32; 3],
+    /// Coordinates of the opposite corner of the area to write in the destination.
+    pub destination_bottom_right: [i32; 3],
+    /// First array layer of the destination to write.
+    pub destination_base_array_layer: u32,
+    /// Mipmap level of the destination to write.
+    pub destination_mip_level: u32,
+    /// Number of array layers to blit.
+    pub layer_count: u32,
+}
+
 /// The dynamic state to use for a draw command.
 // TODO: probably not the right location
 #[derive(Debug, Clone)]
@@ -148,6 +389,9 @@ pub struct DynamicState {
     pub compare_mask: Option<DynamicStencilValue>,
     pub write_mask: Option<DynamicStencilValue>,
     pub reference: Option<DynamicStencilValue>,
+    pub depth_bias: Option<DepthBias>,
+    pub depth_bounds: Option<Range<f32>>,
+    pub blend_constants: Option<[f32; 4]>,
 }
 
 impl DynamicState {
@@ -160,6 +404,9 @@ impl DynamicState {
             compare_mask: None,
             write_mask: None,
             reference: None,
+            depth_bias: None,
+            depth_bounds: None,
+            blend_constants: None,
         }
     }
 }
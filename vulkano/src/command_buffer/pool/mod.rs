@@ -22,6 +22,7 @@ use device::DeviceOwned;
 use OomError;
 
 pub use self::standard::StandardCommandPool;
+pub use self::sys::CommandBufferResetError;
 pub use self::sys::CommandPoolTrimError;
 pub use self::sys::UnsafeCommandPool;
 pub use self::sys::UnsafeCommandPoolAlloc;
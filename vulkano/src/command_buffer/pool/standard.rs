@@ -265,6 +265,9 @@ impl Drop for StandardCommandPoolAlloc {
         // Safe because `self.cmd` is wrapped in a `ManuallyDrop`.
         let cmd: UnsafeCommandPoolAlloc = unsafe { ptr::read(&*self.cmd) };
 
+        // The command buffer will be reused, possibly for a different purpose.
+        self.device.forget_object_name(&cmd);
+
         if self.secondary {
             self.pool.available_secondary_command_buffers.push(cmd);
         } else {
@@ -280,6 +283,7 @@ mod tests {
     use command_buffer::pool::StandardCommandPool;
     use device::Device;
     use std::sync::Arc;
+    use std::thread;
     use VulkanObject;
 
     #[test]
@@ -297,6 +301,32 @@ mod tests {
         assert_eq!(raw, cb2.inner().internal_object());
     }
 
+    #[test]
+    fn separate_pool_per_thread() {
+        let (device, _) = gfx_dev_and_queue!();
+        let queue_family = device.physical_device().queue_families().next().unwrap();
+
+        let pool = Device::standard_command_pool(&device, queue_family);
+
+        // Keeps the pool of this thread alive, so that `cb` is available for reuse.
+        let _keep_alive = pool.alloc(false, 1).unwrap().next().unwrap();
+        let cb = pool.alloc(false, 1).unwrap().next().unwrap();
+        let raw = cb.inner().internal_object();
+        drop(cb);
+
+        let other_raw = {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                let cb = pool.alloc(false, 1).unwrap().next().unwrap();
+                cb.inner().internal_object()
+            })
+            .join()
+            .unwrap()
+        };
+
+        assert_ne!(raw, other_raw);
+    }
+
     #[test]
     fn pool_kept_alive_by_allocs() {
         let (device, queue) = gfx_dev_and_queue!();
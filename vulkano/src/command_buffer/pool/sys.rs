@@ -41,6 +41,9 @@ pub struct UnsafeCommandPool {
     // Index of the associated queue family in the physical device.
     queue_family_index: u32,
 
+    // True if the command buffers can be reset individually.
+    reset_cb: bool,
+
     // We don't want `UnsafeCommandPool` to implement Sync.
     // This marker unimplements both Send and Sync, but we reimplement Send manually right under.
     dummy_avoid_sync: PhantomData<*const u8>,
@@ -112,6 +115,7 @@ impl UnsafeCommandPool {
             pool: pool,
             device: device.clone(),
             queue_family_index: queue_family.id(),
+            reset_cb: reset_cb,
             dummy_avoid_sync: PhantomData,
         })
     }
@@ -137,6 +141,44 @@ impl UnsafeCommandPool {
         Ok(())
     }
 
+    /// Resets an individual command buffer allocated from this pool.
+    ///
+    /// If `release_resources` is true, it is a hint to the implementation that it should free all
+    /// the memory internally allocated for this command buffer.
+    ///
+    /// Returns an error if the pool was not created with `reset_cb` set to true.
+    ///
+    /// # Safety
+    ///
+    /// - The command buffer must have been allocated from this pool.
+    /// - The command buffer must not be in use by the GPU. It jumps to the initial state.
+    ///
+    pub unsafe fn reset_command_buffer(
+        &self,
+        command_buffer: &UnsafeCommandPoolAlloc,
+        release_resources: bool,
+    ) -> Result<(), CommandBufferResetError> {
+        if !self.reset_cb {
+            return Err(CommandBufferResetError::ResetNotAllowed);
+        }
+
+        let flags = if release_resources {
+            vk::COMMAND_BUFFER_RESET_RELEASE_RESOURCES_BIT
+        } else {
+            0
+        };
+
+        let vk = self.device.pointers();
+        check_errors(vk.ResetCommandBuffer(command_buffer.0, flags))?;
+        Ok(())
+    }
+
+    /// Returns true if the command buffers allocated from this pool can be reset individually.
+    #[inline]
+    pub fn can_reset_command_buffers(&self) -> bool {
+        self.reset_cb
+    }
+
     /// Trims a command pool, which recycles unused internal memory from the command pool back to
     /// the system.
     ///
@@ -216,7 +258,12 @@ impl UnsafeCommandPool {
     where
         I: Iterator<Item = UnsafeCommandPoolAlloc>,
     {
-        let command_buffers: SmallVec<[_; 4]> = command_buffers.map(|cb| cb.0).collect();
+        let command_buffers: SmallVec<[_; 4]> = command_buffers
+            .map(|cb| {
+                self.device.forget_object_name(&cb);
+                cb.0
+            })
+            .collect();
         let vk = self.device.pointers();
         vk.FreeCommandBuffers(
             self.device.internal_object(),
@@ -331,8 +378,55 @@ impl From<Error> for CommandPoolTrimError {
     }
 }
 
+/// Error that can happen when resetting an individual command buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CommandBufferResetError {
+    /// The pool was not created with the flag that allows resetting individual command buffers.
+    ResetNotAllowed,
+    /// Not enough memory.
+    OomError(OomError),
+}
+
+impl error::Error for CommandBufferResetError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            CommandBufferResetError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CommandBufferResetError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CommandBufferResetError::ResetNotAllowed => {
+                    "the pool doesn't allow resetting individual command buffers"
+                }
+                CommandBufferResetError::OomError(_) => "no memory available",
+            }
+        )
+    }
+}
+
+impl From<Error> for CommandBufferResetError {
+    #[inline]
+    fn from(err: Error) -> CommandBufferResetError {
+        match err {
+            Error::OutOfHostMemory => CommandBufferResetError::OomError(From::from(err)),
+            Error::OutOfDeviceMemory => CommandBufferResetError::OomError(From::from(err)),
+            _ => panic!("Unexpected error value: {}", err as i32),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use command_buffer::pool::CommandBufferResetError;
     use command_buffer::pool::CommandPoolTrimError;
     use command_buffer::pool::UnsafeCommandPool;
 
@@ -383,4 +477,32 @@ mod tests {
         let iter = pool.alloc_command_buffers(false, 12).unwrap();
         assert_eq!(iter.count(), 12);
     }
+
+    #[test]
+    fn reset_command_buffer() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = UnsafeCommandPool::new(device.clone(), queue.family(), false, true).unwrap();
+        let cb = pool
+            .alloc_command_buffers(false, 1)
+            .unwrap()
+            .next()
+            .unwrap();
+        unsafe {
+            pool.reset_command_buffer(&cb, false).unwrap();
+        }
+
+        let pool = UnsafeCommandPool::new(device, queue.family(), false, false).unwrap();
+        let cb = pool
+            .alloc_command_buffers(false, 1)
+            .unwrap()
+            .next()
+            .unwrap();
+        unsafe {
+            assert_eq!(
+                pool.reset_command_buffer(&cb, false),
+                Err(CommandBufferResetError::ResetNotAllowed)
+            );
+        }
+    }
 }
@@ -9,10 +9,14 @@
 
 use buffer::BufferAccess;
 use command_buffer::DynamicState;
+use descriptor::pipeline_layout::PipelineLayoutAbstract;
+use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
 use descriptor::DescriptorSet;
 use pipeline::input_assembly::IndexType;
 use pipeline::ComputePipelineAbstract;
 use pipeline::GraphicsPipelineAbstract;
+use pipeline::PipelineBindPoint;
+use pipeline::RayTracingPipelineAbstract;
 use smallvec::SmallVec;
 use std::ops::Range;
 use vk;
@@ -30,10 +34,20 @@ pub struct StateCacher {
     compute_pipeline: vk::Pipeline,
     // The graphics pipeline currently bound. 0 if nothing bound.
     graphics_pipeline: vk::Pipeline,
+    // The ray tracing pipeline currently bound. 0 if nothing bound.
+    ray_tracing_pipeline: vk::Pipeline,
     // The descriptor sets for the compute pipeline.
     compute_descriptor_sets: SmallVec<[vk::DescriptorSet; 12]>,
     // The descriptor sets for the graphics pipeline.
     graphics_descriptor_sets: SmallVec<[vk::DescriptorSet; 12]>,
+    // The descriptor sets for the ray tracing pipeline.
+    ray_tracing_descriptor_sets: SmallVec<[vk::DescriptorSet; 12]>,
+    // The layout of the compute pipeline currently bound.
+    compute_layout: CachedLayout,
+    // The layout of the graphics pipeline currently bound.
+    graphics_layout: CachedLayout,
+    // The layout of the ray tracing pipeline currently bound.
+    ray_tracing_layout: CachedLayout,
     // If the user starts comparing descriptor sets, but drops the helper struct in the middle of
     // the processing then we will end up in a weird state. This bool is true when we start
     // comparing sets, and is set to false when we end up comparing. If it was true when we start
@@ -45,6 +59,8 @@ pub struct StateCacher {
     poisoned_vertex_buffers: bool,
     // The index buffer, offset, and index type currently bound. `None` if nothing bound.
     index_buffer: Option<(vk::Buffer, usize, IndexType)>,
+    // If false, the objects are bound again even if they are already bound.
+    elide_binds: bool,
 }
 
 /// Outcome of an operation.
@@ -64,15 +80,46 @@ impl StateCacher {
             dynamic_state: DynamicState::none(),
             compute_pipeline: 0,
             graphics_pipeline: 0,
+            ray_tracing_pipeline: 0,
             compute_descriptor_sets: SmallVec::new(),
             graphics_descriptor_sets: SmallVec::new(),
+            ray_tracing_descriptor_sets: SmallVec::new(),
+            compute_layout: CachedLayout::new(),
+            graphics_layout: CachedLayout::new(),
+            ray_tracing_layout: CachedLayout::new(),
             poisoned_descriptor_sets: false,
             vertex_buffers: SmallVec::new(),
             poisoned_vertex_buffers: false,
             index_buffer: None,
+            elide_binds: true,
         }
     }
 
+    /// Sets whether the binds of objects that are already bound are elided, which is the case
+    /// by default. If `false`, the pipelines, descriptor sets, vertex and index buffers are
+    /// always bound again, while the dynamic state is still tracked.
+    #[inline]
+    pub fn set_elide_binds(&mut self, elide_binds: bool) {
+        self.elide_binds = elide_binds;
+    }
+
+    // Forgets the bound objects if binds must not be elided.
+    #[inline]
+    fn forget_binds_if_disabled(&mut self) {
+        if self.elide_binds {
+            return;
+        }
+
+        self.compute_pipeline = 0;
+        self.graphics_pipeline = 0;
+        self.ray_tracing_pipeline = 0;
+        self.compute_descriptor_sets = SmallVec::new();
+        self.graphics_descriptor_sets = SmallVec::new();
+        self.ray_tracing_descriptor_sets = SmallVec::new();
+        self.vertex_buffers = SmallVec::new();
+        self.index_buffer = None;
+    }
+
     /// Resets the cache to its default state. You **must** call this after executing a secondary
     /// command buffer.
     #[inline]
@@ -80,8 +127,13 @@ impl StateCacher {
         self.dynamic_state = DynamicState::none();
         self.compute_pipeline = 0;
         self.graphics_pipeline = 0;
+        self.ray_tracing_pipeline = 0;
         self.compute_descriptor_sets = SmallVec::new();
         self.graphics_descriptor_sets = SmallVec::new();
+        self.ray_tracing_descriptor_sets = SmallVec::new();
+        self.compute_layout = CachedLayout::new();
+        self.graphics_layout = CachedLayout::new();
+        self.ray_tracing_layout = CachedLayout::new();
         self.vertex_buffers = SmallVec::new();
         self.index_buffer = None;
     }
@@ -111,10 +163,20 @@ impl StateCacher {
         cmp!(compare_mask);
         cmp!(reference);
         cmp!(write_mask);
+        cmp!(depth_bias);
+        cmp!(depth_bounds);
+        cmp!(blend_constants);
 
         changed
     }
 
+    /// Returns the dynamic state that is currently set in the command buffer builder, as far as
+    /// the state cacher knows.
+    #[inline]
+    pub fn current_dynamic_state(&self) -> &DynamicState {
+        &self.dynamic_state
+    }
+
     /// Starts the process of comparing a list of descriptor sets to the descriptor sets currently
     /// in cache.
     ///
@@ -126,19 +188,36 @@ impl StateCacher {
     /// changes are going to be performed after the `compare` function returns.
     #[inline]
     pub fn bind_descriptor_sets(&mut self, graphics: bool) -> StateCacherDescriptorSets {
+        self.bind_descriptor_sets_at(if graphics {
+            PipelineBindPoint::Graphics
+        } else {
+            PipelineBindPoint::Compute
+        })
+    }
+
+    /// Same as `bind_descriptor_sets`, but compares with the descriptor sets bound to any
+    /// pipeline bind point, including `PipelineBindPoint::RayTracing`.
+    #[inline]
+    pub fn bind_descriptor_sets_at(
+        &mut self,
+        bind_point: PipelineBindPoint,
+    ) -> StateCacherDescriptorSets {
+        self.forget_binds_if_disabled();
+
         if self.poisoned_descriptor_sets {
             self.compute_descriptor_sets = SmallVec::new();
             self.graphics_descriptor_sets = SmallVec::new();
+            self.ray_tracing_descriptor_sets = SmallVec::new();
         }
 
         self.poisoned_descriptor_sets = true;
 
         StateCacherDescriptorSets {
             poisoned: &mut self.poisoned_descriptor_sets,
-            state: if graphics {
-                &mut self.graphics_descriptor_sets
-            } else {
-                &mut self.compute_descriptor_sets
+            state: match bind_point {
+                PipelineBindPoint::Graphics => &mut self.graphics_descriptor_sets,
+                PipelineBindPoint::Compute => &mut self.compute_descriptor_sets,
+                PipelineBindPoint::RayTracing => &mut self.ray_tracing_descriptor_sets,
             },
             offset: 0,
             found_diff: None,
@@ -155,11 +234,47 @@ impl StateCacher {
     where
         P: GraphicsPipelineAbstract,
     {
+        self.forget_binds_if_disabled();
+
         let inner = GraphicsPipelineAbstract::inner(pipeline).internal_object();
         if inner == self.graphics_pipeline {
             StateCacherOutcome::AlreadyOk
         } else {
             self.graphics_pipeline = inner;
+
+            // Binding a pipeline overwrites the states that it doesn't declare as dynamic.
+            if !pipeline.has_dynamic_line_width() {
+                self.dynamic_state.line_width = None;
+            }
+            if !pipeline.has_dynamic_viewports() {
+                self.dynamic_state.viewports = None;
+            }
+            if !pipeline.has_dynamic_scissors() {
+                self.dynamic_state.scissors = None;
+            }
+            if !pipeline.has_dynamic_stencil_compare_mask() {
+                self.dynamic_state.compare_mask = None;
+            }
+            if !pipeline.has_dynamic_stencil_write_mask() {
+                self.dynamic_state.write_mask = None;
+            }
+            if !pipeline.has_dynamic_stencil_reference() {
+                self.dynamic_state.reference = None;
+            }
+            if !pipeline.has_dynamic_depth_bias() {
+                self.dynamic_state.depth_bias = None;
+            }
+            if !pipeline.has_dynamic_depth_bounds() {
+                self.dynamic_state.depth_bounds = None;
+            }
+            if !pipeline.has_dynamic_blend_constants() {
+                self.dynamic_state.blend_constants = None;
+            }
+
+            // Descriptor sets bound with an incompatible layout are disturbed.
+            let compatible = self.graphics_layout.update(pipeline);
+            self.graphics_descriptor_sets.truncate(compatible);
+
             StateCacherOutcome::NeedChange
         }
     }
@@ -174,11 +289,38 @@ impl StateCacher {
     where
         P: ComputePipelineAbstract,
     {
+        self.forget_binds_if_disabled();
+
         let inner = pipeline.inner().internal_object();
         if inner == self.compute_pipeline {
             StateCacherOutcome::AlreadyOk
         } else {
             self.compute_pipeline = inner;
+            let compatible = self.compute_layout.update(pipeline);
+            self.compute_descriptor_sets.truncate(compatible);
+            StateCacherOutcome::NeedChange
+        }
+    }
+
+    /// Checks whether we need to bind a ray tracing pipeline. Returns
+    /// `StateCacherOutcome::AlreadyOk` if the pipeline was already bound earlier, and
+    /// `StateCacherOutcome::NeedChange` if you need to actually bind the pipeline.
+    ///
+    /// This function also updates the state cacher. The state cacher assumes that the state
+    /// changes are going to be performed after this function returns.
+    pub fn bind_ray_tracing_pipeline<P>(&mut self, pipeline: &P) -> StateCacherOutcome
+    where
+        P: RayTracingPipelineAbstract,
+    {
+        self.forget_binds_if_disabled();
+
+        let inner = RayTracingPipelineAbstract::inner(pipeline).internal_object();
+        if inner == self.ray_tracing_pipeline {
+            StateCacherOutcome::AlreadyOk
+        } else {
+            self.ray_tracing_pipeline = inner;
+            let compatible = self.ray_tracing_layout.update(pipeline);
+            self.ray_tracing_descriptor_sets.truncate(compatible);
             StateCacherOutcome::NeedChange
         }
     }
@@ -194,6 +336,8 @@ impl StateCacher {
     /// changes are going to be performed after the `compare` function returns.
     #[inline]
     pub fn bind_vertex_buffers(&mut self) -> StateCacherVertexBuffers {
+        self.forget_binds_if_disabled();
+
         if self.poisoned_vertex_buffers {
             self.vertex_buffers = SmallVec::new();
         }
@@ -219,6 +363,8 @@ impl StateCacher {
     where
         B: ?Sized + BufferAccess,
     {
+        self.forget_binds_if_disabled();
+
         let value = {
             let inner = index_buffer.inner();
             (inner.buffer.internal_object(), inner.offset, ty)
@@ -233,6 +379,57 @@ impl StateCacher {
     }
 }
 
+// The parts of a pipeline layout that determine whether the descriptor sets bound with it remain
+// valid after binding a pipeline with another layout.
+struct CachedLayout {
+    set_layouts: SmallVec<[vk::DescriptorSetLayout; 12]>,
+    push_constants: SmallVec<[PipelineLayoutDescPcRange; 6]>,
+}
+
+impl CachedLayout {
+    #[inline]
+    fn new() -> CachedLayout {
+        CachedLayout {
+            set_layouts: SmallVec::new(),
+            push_constants: SmallVec::new(),
+        }
+    }
+
+    // Replaces the cached layout with the layout of `pipeline`, and returns the number of sets,
+    // starting from set 0, for which the two layouts are compatible.
+    fn update<P>(&mut self, pipeline: &P) -> usize
+    where
+        P: ?Sized + PipelineLayoutAbstract,
+    {
+        let set_layouts: SmallVec<[vk::DescriptorSetLayout; 12]> = (0..pipeline.num_sets())
+            .map(|set| {
+                pipeline
+                    .descriptor_set_layout(set)
+                    .map(|layout| layout.internal_object())
+                    .unwrap_or(0)
+            })
+            .collect();
+        let push_constants: SmallVec<[PipelineLayoutDescPcRange; 6]> = (0..pipeline
+            .num_push_constants_ranges())
+            .filter_map(|num| pipeline.push_constants_range(num))
+            .collect();
+
+        let compatible = if push_constants == self.push_constants {
+            self.set_layouts
+                .iter()
+                .zip(set_layouts.iter())
+                .take_while(|&(a, b)| a == b && *a != 0)
+                .count()
+        } else {
+            0
+        };
+
+        self.set_layouts = set_layouts;
+        self.push_constants = push_constants;
+        compatible
+    }
+}
+
 /// Helper struct for comparing descriptor sets.
 ///
 /// > **Note**: For reliability reasons, if you drop/leak this struct before calling `compare` then
@@ -362,7 +559,19 @@ mod tests {
     use buffer::BufferUsage;
     use buffer::CpuAccessibleBuffer;
     use command_buffer::state_cacher::StateCacher;
-
+    use command_buffer::DynamicState;
+    use descriptor::descriptor::DescriptorDesc;
+    use descriptor::descriptor::DescriptorDescTy;
+    use descriptor::descriptor::ShaderStages;
+    use descriptor::descriptor_set::PersistentDescriptorSet;
+    use descriptor::descriptor_set::UnsafeDescriptorSetLayout;
+    use pipeline::PipelineBindPoint;
+    use sampler::Sampler;
+    use std::iter;
+    use std::sync::Arc;
+
+    // Uses an iterator as the content of the buffer, which `strict-buffer-contents` rejects.
+    #[cfg(not(feature = "strict-buffer-contents"))]
     #[test]
     fn vb_caching_single() {
         let (device, queue) = gfx_dev_and_queue!();
@@ -391,6 +600,8 @@ mod tests {
         }
     }
 
+    // Uses an iterator as the content of the buffer, which `strict-buffer-contents` rejects.
+    #[cfg(not(feature = "strict-buffer-contents"))]
     #[test]
     fn vb_caching_invalidated() {
         let (device, queue) = gfx_dev_and_queue!();
@@ -427,6 +638,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn vb_caching_elision_disabled() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        const EMPTY: [i32; 0] = [];
+        let buf = CpuAccessibleBuffer::from_iter(
+            device,
+            BufferUsage::vertex_buffer(),
+            false,
+            EMPTY.iter().cloned(),
+        )
+        .unwrap();
+
+        let mut cacher = StateCacher::new();
+        cacher.set_elide_binds(false);
+
+        for _ in 0..3 {
+            let mut bind_vb = cacher.bind_vertex_buffers();
+            bind_vb.add(&buf);
+            assert_eq!(bind_vb.compare(), Some(0..1));
+        }
+    }
+
+    // Uses an iterator as the content of the buffer, which `strict-buffer-contents` rejects.
+    #[cfg(not(feature = "strict-buffer-contents"))]
     #[test]
     fn vb_caching_multi() {
         let (device, queue) = gfx_dev_and_queue!();
@@ -491,4 +727,72 @@ mod tests {
             assert_eq!(bind_vb.compare(), Some(0..1));
         }
     }
+
+    #[test]
+    fn dynamic_state_caching() {
+        let mut cacher = StateCacher::new();
+
+        let state = DynamicState {
+            line_width: Some(2.0),
+            blend_constants: Some([0.5; 4]),
+            ..DynamicState::none()
+        };
+
+        let changed = cacher.dynamic_state(&state);
+        assert_eq!(changed.line_width, Some(2.0));
+        assert_eq!(changed.blend_constants, Some([0.5; 4]));
+
+        let state = DynamicState {
+            blend_constants: Some([0.5; 4]),
+            ..state
+        };
+        let changed = cacher.dynamic_state(&state);
+        assert_eq!(changed.line_width, None);
+        assert_eq!(changed.blend_constants, None);
+        assert_eq!(cacher.current_dynamic_state().line_width, Some(2.0));
+    }
+
+    #[test]
+    fn descriptor_sets_caching_per_bind_point() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let desc = DescriptorDesc {
+            ty: DescriptorDescTy::Sampler,
+            array_count: 1,
+            stages: ShaderStages::all(),
+            readonly: false,
+        };
+        let layout = Arc::new(
+            UnsafeDescriptorSetLayout::new(device.clone(), iter::once(Some(desc))).unwrap(),
+        );
+        let set = PersistentDescriptorSet::start(layout)
+            .add_sampler(Sampler::simple_repeat_linear(device))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut cacher = StateCacher::new();
+
+        for &bind_point in &[
+            PipelineBindPoint::Graphics,
+            PipelineBindPoint::Compute,
+            PipelineBindPoint::RayTracing,
+        ] {
+            let mut compare = cacher.bind_descriptor_sets_at(bind_point);
+            compare.add(&set);
+            assert_eq!(compare.compare(), Some(0));
+        }
+
+        for _ in 0..3 {
+            let mut compare = cacher.bind_descriptor_sets_at(PipelineBindPoint::RayTracing);
+            compare.add(&set);
+            assert_eq!(compare.compare(), None);
+        }
+
+        cacher.invalidate();
+
+        let mut compare = cacher.bind_descriptor_sets_at(PipelineBindPoint::RayTracing);
+        compare.add(&set);
+        assert_eq!(compare.compare(), Some(0));
+    }
 }
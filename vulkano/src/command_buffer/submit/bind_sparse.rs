@@ -12,11 +12,16 @@ use std::error;
 use std::fmt;
 use std::marker::PhantomData;
 use std::ptr;
+use std::sync::Arc;
 
 use buffer::sys::UnsafeBuffer;
+use device::DeviceOwned;
 use device::Queue;
 use image::sys::UnsafeImage;
+use image::ImageAspect;
+use image::SparseImageMemoryRequirements;
 use memory::DeviceMemory;
+use memory::MemoryRequirements;
 use sync::Fence;
 use sync::Semaphore;
 
@@ -448,23 +453,341 @@ impl<'a> SubmitBindSparseImageOpaqueBindBuilder<'a> {
 pub struct SubmitBindSparseImageBindBuilder<'a> {
     image: vk::Image,
     binds: SmallVec<[vk::SparseImageMemoryBind; 1]>,
-    marker: PhantomData<&'a ()>,
+    resource: &'a UnsafeImage,
 }
 
 impl<'a> SubmitBindSparseImageBindBuilder<'a> {
     ///
     /// # Safety
     ///
-    /// - `image` must be an image with sparse binding enabled.
+    /// - `image` must be an image with sparse residency enabled.
     pub unsafe fn new(image: &'a UnsafeImage) -> SubmitBindSparseImageBindBuilder {
+        debug_assert!(image.flags().sparse_residency);
+
         SubmitBindSparseImageBindBuilder {
             image: image.internal_object(),
             binds: SmallVec::new(),
-            marker: PhantomData,
+            resource: image,
+        }
+    }
+
+    /// Safe version of `new`. Returns an error if `image` wasn't created with the sparse
+    /// residency flag.
+    ///
+    /// The regions passed to `bind` and `unbind` are then checked against the sparse memory
+    /// requirements of the image.
+    pub fn with_image(
+        image: &'a UnsafeImage,
+    ) -> Result<SubmitBindSparseImageBindBuilder<'a>, SparseImageBindError> {
+        if !image.flags().sparse_residency {
+            return Err(SparseImageBindError::NotSparseResidency);
+        }
+
+        Ok(unsafe { SubmitBindSparseImageBindBuilder::new(image) })
+    }
+
+    /// Binds a region of `memory` to the blocks of one mip level and array layer of the image
+    /// that are covered by `offset` and `extent`, in texels.
+    ///
+    /// Contrary to `add_bind`, the region is checked against the sparse memory requirements of
+    /// the image, and `memory` is kept alive until the image is destroyed.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `memory` wasn't allocated from the same device as the image.
+    ///
+    pub fn bind(
+        &mut self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+        offset: [i32; 3],
+        extent: [u32; 3],
+        memory: Arc<DeviceMemory>,
+        memory_offset: usize,
+    ) -> Result<(), SparseImageBindError> {
+        assert_eq!(
+            memory.device().internal_object(),
+            self.resource.device().internal_object()
+        );
+
+        let num_blocks = self.check_region(aspect, mip_level, array_layer, offset, extent)?;
+        let MemoryRequirements {
+            alignment,
+            memory_type_bits,
+            ..
+        } = self.resource.memory_requirements();
+
+        if memory_type_bits & (1 << memory.memory_type().id()) == 0 {
+            return Err(SparseImageBindError::MemoryTypeNotAllowed);
+        }
+
+        if memory_offset % alignment != 0 {
+            return Err(SparseImageBindError::UnalignedMemoryOffset);
+        }
+
+        if memory_offset > memory.size() || num_blocks * alignment > memory.size() - memory_offset {
+            return Err(SparseImageBindError::MemoryTooSmall);
+        }
+
+        unsafe {
+            self.add_bind(
+                aspect,
+                mip_level,
+                array_layer,
+                offset,
+                extent,
+                &memory,
+                memory_offset,
+            );
+        }
+        self.resource.keep_sparse_memory_alive(memory);
+        Ok(())
+    }
+
+    /// Unbinds the blocks of one mip level and array layer of the image that are covered by
+    /// `offset` and `extent`, in texels.
+    ///
+    /// Contrary to `add_unbind`, the region is checked against the sparse memory requirements of
+    /// the image.
+    pub fn unbind(
+        &mut self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+        offset: [i32; 3],
+        extent: [u32; 3],
+    ) -> Result<(), SparseImageBindError> {
+        self.check_region(aspect, mip_level, array_layer, offset, extent)?;
+
+        unsafe {
+            self.add_unbind(aspect, mip_level, array_layer, offset, extent);
+        }
+        Ok(())
+    }
+
+    // Checks a region passed to `bind` or `unbind`, and returns the number of sparse blocks it
+    // covers.
+    fn check_region(
+        &self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+        offset: [i32; 3],
+        extent: [u32; 3],
+    ) -> Result<usize, SparseImageBindError> {
+        let image = self.resource;
+
+        if array_layer >= image.dimensions().array_layers() {
+            return Err(SparseImageBindError::ArrayLayerOutOfRange);
         }
+
+        let mip_dimensions = match image.dimensions().mipmap_dimensions(mip_level) {
+            Some(dimensions) if mip_level < image.mipmap_levels() => {
+                dimensions.width_height_depth()
+            }
+            _ => return Err(SparseImageBindError::MipLevelOutOfRange),
+        };
+
+        let requirements = image
+            .sparse_memory_requirements()
+            .into_iter()
+            .find(|r| match aspect {
+                ImageAspect::Color => r.format_properties.color,
+                ImageAspect::Depth => r.format_properties.depth,
+                ImageAspect::Stencil => r.format_properties.stencil,
+            });
+        let SparseImageMemoryRequirements {
+            format_properties,
+            image_mip_tail_first_lod,
+            ..
+        } = match requirements {
+            Some(requirements) => requirements,
+            None => return Err(SparseImageBindError::AspectNotSparse),
+        };
+
+        if mip_level >= image_mip_tail_first_lod {
+            return Err(SparseImageBindError::MipTailLevel);
+        }
+
+        let granularity = format_properties.image_granularity;
+        let mut num_blocks = 1;
+
+        for i in 0..3 {
+            if offset[i] < 0
+                || extent[i] == 0
+                || extent[i] > mip_dimensions[i]
+                || offset[i] as u32 > mip_dimensions[i] - extent[i]
+            {
+                return Err(SparseImageBindError::RegionOutOfRange);
+            }
+
+            let end = offset[i] as u32 + extent[i];
+            if offset[i] as u32 % granularity[i] != 0
+                || (extent[i] % granularity[i] != 0 && end != mip_dimensions[i])
+            {
+                return Err(SparseImageBindError::UnalignedRegion);
+            }
+
+            num_blocks *= ((extent[i] + granularity[i] - 1) / granularity[i]) as usize;
+        }
+
+        Ok(num_blocks)
+    }
+
+    /// Binds a region of memory to the blocks of one mip level and array layer of the image
+    /// that are covered by `offset` and `extent`, in texels.
+    ///
+    /// # Safety
+    ///
+    /// - `offset` must be a multiple of the `image_granularity` of the sparse properties of the
+    ///   aspect. `extent` must be a multiple of it too, unless the region touches the edge of the
+    ///   mip level.
+    /// - The mip level must not be part of the mip tail. The mip tail is bound with a
+    ///   `SubmitBindSparseImageOpaqueBindBuilder` instead.
+    /// - The memory must be large enough, be allocated from a memory type allowed by the memory
+    ///   requirements of the image, and `memory_offset` must respect their alignment.
+    /// - The memory must be kept alive until the image is destroyed or the region is unbound.
+    ///
+    pub unsafe fn add_bind(
+        &mut self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+        offset: [i32; 3],
+        extent: [u32; 3],
+        memory: &DeviceMemory,
+        memory_offset: usize,
+    ) {
+        self.binds.push(vk::SparseImageMemoryBind {
+            subresource: vk::ImageSubresource {
+                aspectMask: aspect.into(),
+                mipLevel: mip_level,
+                arrayLayer: array_layer,
+            },
+            offset: vk::Offset3D {
+                x: offset[0],
+                y: offset[1],
+                z: offset[2],
+            },
+            extent: vk::Extent3D {
+                width: extent[0],
+                height: extent[1],
+                depth: extent[2],
+            },
+            memory: memory.internal_object(),
+            memoryOffset: memory_offset as vk::DeviceSize,
+            flags: 0, // The metadata can only be bound with an opaque bind.
+        });
     }
 
-    // TODO: finish
+    /// Unbinds the blocks of one mip level and array layer of the image that are covered by
+    /// `offset` and `extent`, in texels. Reading from unbound blocks returns undefined values on
+    /// implementations that don't guarantee strict non-resident accesses.
+    ///
+    /// # Safety
+    ///
+    /// - Same requirements on `offset`, `extent` and the mip level as `add_bind`.
+    ///
+    pub unsafe fn add_unbind(
+        &mut self,
+        aspect: ImageAspect,
+        mip_level: u32,
+        array_layer: u32,
+        offset: [i32; 3],
+        extent: [u32; 3],
+    ) {
+        self.binds.push(vk::SparseImageMemoryBind {
+            subresource: vk::ImageSubresource {
+                aspectMask: aspect.into(),
+                mipLevel: mip_level,
+                arrayLayer: array_layer,
+            },
+            offset: vk::Offset3D {
+                x: offset[0],
+                y: offset[1],
+                z: offset[2],
+            },
+            extent: vk::Extent3D {
+                width: extent[0],
+                height: extent[1],
+                depth: extent[2],
+            },
+            memory: 0,
+            memoryOffset: 0,
+            flags: 0,
+        });
+    }
+}
+
+/// Error that can happen when binding or unbinding a region of a sparse image with
+/// `SubmitBindSparseImageBindBuilder`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SparseImageBindError {
+    /// The image wasn't created with the sparse residency flag.
+    NotSparseResidency,
+    /// The mip level is out of the range of the image.
+    MipLevelOutOfRange,
+    /// The array layer is out of the range of the image.
+    ArrayLayerOutOfRange,
+    /// The image has no sparse memory requirements for the aspect.
+    AspectNotSparse,
+    /// The mip level is part of the mip tail, which must be bound with a
+    /// `SubmitBindSparseImageOpaqueBindBuilder` instead.
+    MipTailLevel,
+    /// The region is empty or out of the range of the mip level.
+    RegionOutOfRange,
+    /// The offset or extent of the region is not a multiple of the image granularity, and the
+    /// region doesn't end at the edge of the mip level.
+    UnalignedRegion,
+    /// The memory type of the memory is not allowed by the memory requirements of the image.
+    MemoryTypeNotAllowed,
+    /// The offset in the memory is not a multiple of the sparse block size.
+    UnalignedMemoryOffset,
+    /// The memory is too small for the blocks of the region.
+    MemoryTooSmall,
+}
+
+impl error::Error for SparseImageBindError {}
+
+impl fmt::Display for SparseImageBindError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                SparseImageBindError::NotSparseResidency => {
+                    "the image wasn't created with the sparse residency flag"
+                }
+                SparseImageBindError::MipLevelOutOfRange => {
+                    "the mip level is out of the range of the image"
+                }
+                SparseImageBindError::ArrayLayerOutOfRange => {
+                    "the array layer is out of the range of the image"
+                }
+                SparseImageBindError::AspectNotSparse => {
+                    "the image has no sparse memory requirements for the aspect"
+                }
+                SparseImageBindError::MipTailLevel => "the mip level is part of the mip tail",
+                SparseImageBindError::RegionOutOfRange => {
+                    "the region is empty or out of the range of the mip level"
+                }
+                SparseImageBindError::UnalignedRegion => {
+                    "the region is not aligned to the image granularity"
+                }
+                SparseImageBindError::MemoryTypeNotAllowed => {
+                    "the memory type is not allowed by the memory requirements of the image"
+                }
+                SparseImageBindError::UnalignedMemoryOffset => {
+                    "the offset in the memory is not a multiple of the sparse block size"
+                }
+                SparseImageBindError::MemoryTooSmall => {
+                    "the memory is too small for the blocks of the region"
+                }
+            }
+        )
+    }
 }
 
 /// Error that can happen when submitting the present prototype.
@@ -513,3 +836,51 @@ impl From<Error> for SubmitBindSparseError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::iter::Empty;
+
+    use command_buffer::submit::SparseImageBindError;
+    use command_buffer::submit::SubmitBindSparseImageBindBuilder;
+    use format::Format;
+    use image::sys::UnsafeImage;
+    use image::ImageDimensions;
+    use image::ImageUsage;
+    use sync::Sharing;
+
+    #[test]
+    fn requires_sparse_residency() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let usage = ImageUsage {
+            sampled: true,
+            ..ImageUsage::none()
+        };
+
+        let (image, _) = unsafe {
+            UnsafeImage::new(
+                device,
+                usage,
+                Format::R8G8B8A8Unorm,
+                ImageDimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                    array_layers: 1,
+                    cubemap_compatible: false,
+                },
+                1,
+                1,
+                Sharing::Exclusive::<Empty<_>>,
+                false,
+                false,
+            )
+        }
+        .unwrap();
+
+        match SubmitBindSparseImageBindBuilder::with_image(&image) {
+            Err(SparseImageBindError::NotSparseResidency) => (),
+            _ => panic!(),
+        };
+    }
+}
@@ -12,6 +12,8 @@ use std::error;
 use std::fmt;
 use std::marker::PhantomData;
 use std::ptr;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 
 use device::DeviceOwned;
 use device::Queue;
@@ -23,6 +25,7 @@ use check_errors;
 use vk;
 use Error;
 use OomError;
+use Success;
 use SynchronizedVulkanObject;
 use VulkanObject;
 
@@ -34,6 +37,8 @@ pub struct SubmitPresentBuilder<'a> {
     image_indices: SmallVec<[u32; 4]>,
     present_regions: SmallVec<[vk::PresentRegionKHR; 4]>,
     rect_layers: SmallVec<[vk::RectLayerKHR; 4]>,
+    // Set for each swapchain whose present returns `VK_SUBOPTIMAL_KHR`.
+    suboptimal_flags: SmallVec<[&'a AtomicBool; 4]>,
     marker: PhantomData<&'a ()>,
 }
 
@@ -47,6 +52,7 @@ impl<'a> SubmitPresentBuilder<'a> {
             image_indices: SmallVec::new(),
             present_regions: SmallVec::new(),
             rect_layers: SmallVec::new(),
+            suboptimal_flags: SmallVec::new(),
             marker: PhantomData,
         }
     }
@@ -124,10 +130,14 @@ impl<'a> SubmitPresentBuilder<'a> {
 
         self.swapchains.push(swapchain.internal_object());
         self.image_indices.push(image_num);
+        self.suboptimal_flags.push(swapchain.suboptimal_flag());
     }
 
     /// Submits the command. Calls `vkQueuePresentKHR`.
     ///
+    /// If the present of a swapchain returns `VK_SUBOPTIMAL_KHR`, `Swapchain::is_suboptimal`
+    /// returns true afterwards.
+    ///
     /// # Panic
     ///
     /// Panics if no swapchain image has been added to the builder.
@@ -181,8 +191,10 @@ impl<'a> SubmitPresentBuilder<'a> {
 
             check_errors(vk.QueuePresentKHR(*queue, &infos))?;
 
-            for result in results {
-                check_errors(result)?;
+            for (result, suboptimal) in results.into_iter().zip(self.suboptimal_flags.iter()) {
+                if let Success::Suboptimal = check_errors(result)? {
+                    suboptimal.store(true, Ordering::SeqCst);
+                }
             }
 
             Ok(())
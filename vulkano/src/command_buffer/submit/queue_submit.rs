@@ -12,6 +12,7 @@ use std::error;
 use std::fmt;
 use std::marker::PhantomData;
 use std::ptr;
+use std::sync::Arc;
 
 use command_buffer::sys::UnsafeCommandBuffer;
 use device::Queue;
@@ -27,7 +28,44 @@ use SynchronizedVulkanObject;
 use VulkanObject;
 
 /// Prototype for a submission that executes command buffers.
-// TODO: example here
+///
+/// All the command buffers and semaphores of a builder are submitted with a single call to
+/// `vkQueueSubmit`. Since each submission has a cost, prefer adding multiple command buffers to
+/// the same builder or merging builders together with `merge` over submitting them one by one.
+/// Access to the queue is synchronized internally, so builders can be submitted from multiple
+/// threads.
+///
+/// > **Note**: The futures of the `sync` module use this builder and merge their submissions
+/// > automatically, until they are flushed.
+///
+/// # Example
+///
+/// ```
+/// use vulkano::command_buffer::submit::SubmitCommandBufferBuilder;
+/// use vulkano::sync::Fence;
+/// # let device: std::sync::Arc<vulkano::device::Device> = return;
+/// # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+/// # let first: vulkano::command_buffer::sys::UnsafeCommandBuffer<()> = return;
+/// # let second: vulkano::command_buffer::sys::UnsafeCommandBuffer<()> = return;
+///
+/// unsafe {
+///     let fence = Fence::from_pool(device.clone()).unwrap();
+///
+///     let mut builder = SubmitCommandBufferBuilder::new();
+///     builder.add_command_buffer(&first);
+///
+///     let mut other = SubmitCommandBufferBuilder::new();
+///     other.add_command_buffer(&second);
+///     other.set_fence_signal(&fence);
+///
+///     // Both command buffers are submitted at once.
+///     let builder = builder.merge(other);
+///     assert_eq!(builder.num_command_buffers(), 2);
+///     builder.submit(&queue).unwrap();
+///
+///     fence.wait(None).unwrap();
+/// }
+/// ```
 #[derive(Debug)]
 pub struct SubmitCommandBufferBuilder<'a> {
     wait_semaphores: SmallVec<[vk::Semaphore; 16]>,
@@ -170,6 +208,14 @@ impl<'a> SubmitCommandBufferBuilder<'a> {
         self.command_buffers.push(command_buffer.internal_object());
     }
 
+    /// Returns the number of command buffers to execute.
+    ///
+    /// In other words, this is the number of times `add_command_buffer` has been called.
+    #[inline]
+    pub fn num_command_buffers(&self) -> usize {
+        self.command_buffers.len()
+    }
+
     /// Returns the number of semaphores to signal.
     ///
     /// In other words, this is the number of times `add_signal_semaphore` has been called.
@@ -200,7 +246,12 @@ impl<'a> SubmitCommandBufferBuilder<'a> {
     /// > **Note**: This is an expensive operation, so you may want to merge as many builders as
     /// > possible together and avoid submitting them one by one.
     ///
+    /// If the `synchronization2` feature is enabled, this uses `vkQueueSubmit2KHR`.
     pub fn submit(self, queue: &Queue) -> Result<(), SubmitCommandBufferError> {
+        if queue.device().uses_synchronization2() {
+            return self.submit2(queue);
+        }
+
         unsafe {
             let vk = queue.device().pointers();
             let queue = queue.internal_object_guard();
@@ -224,6 +275,70 @@ impl<'a> SubmitCommandBufferBuilder<'a> {
         }
     }
 
+    // Implementation of `submit` with `vkQueueSubmit2KHR`.
+    fn submit2(self, queue: &Queue) -> Result<(), SubmitCommandBufferError> {
+        unsafe {
+            let vk = queue.device().pointers();
+            let queue = queue.internal_object_guard();
+
+            debug_assert_eq!(self.wait_semaphores.len(), self.destination_stages.len());
+
+            let wait_semaphores: SmallVec<[vk::SemaphoreSubmitInfoKHR; 16]> = self
+                .wait_semaphores
+                .iter()
+                .zip(self.destination_stages.iter())
+                .map(|(&semaphore, &stages)| vk::SemaphoreSubmitInfoKHR {
+                    sType: vk::STRUCTURE_TYPE_SEMAPHORE_SUBMIT_INFO_KHR,
+                    pNext: ptr::null(),
+                    semaphore: semaphore,
+                    value: 0,
+                    stageMask: stages as vk::PipelineStageFlags2KHR,
+                    deviceIndex: 0,
+                })
+                .collect();
+
+            let command_buffers: SmallVec<[vk::CommandBufferSubmitInfoKHR; 4]> = self
+                .command_buffers
+                .iter()
+                .map(|&command_buffer| vk::CommandBufferSubmitInfoKHR {
+                    sType: vk::STRUCTURE_TYPE_COMMAND_BUFFER_SUBMIT_INFO_KHR,
+                    pNext: ptr::null(),
+                    commandBuffer: command_buffer,
+                    deviceMask: 0,
+                })
+                .collect();
+
+            // The legacy submission signals the semaphores once all the commands have completed.
+            let signal_semaphores: SmallVec<[vk::SemaphoreSubmitInfoKHR; 16]> = self
+                .signal_semaphores
+                .iter()
+                .map(|&semaphore| vk::SemaphoreSubmitInfoKHR {
+                    sType: vk::STRUCTURE_TYPE_SEMAPHORE_SUBMIT_INFO_KHR,
+                    pNext: ptr::null(),
+                    semaphore: semaphore,
+                    value: 0,
+                    stageMask: vk::PIPELINE_STAGE_ALL_COMMANDS_BIT as vk::PipelineStageFlags2KHR,
+                    deviceIndex: 0,
+                })
+                .collect();
+
+            let batch = vk::SubmitInfo2KHR {
+                sType: vk::STRUCTURE_TYPE_SUBMIT_INFO_2_KHR,
+                pNext: ptr::null(),
+                flags: 0,
+                waitSemaphoreInfoCount: wait_semaphores.len() as u32,
+                pWaitSemaphoreInfos: wait_semaphores.as_ptr(),
+                commandBufferInfoCount: command_buffers.len() as u32,
+                pCommandBufferInfos: command_buffers.as_ptr(),
+                signalSemaphoreInfoCount: signal_semaphores.len() as u32,
+                pSignalSemaphoreInfos: signal_semaphores.as_ptr(),
+            };
+
+            check_errors(vk.QueueSubmit2KHR(*queue, 1, &batch, self.fence))?;
+            Ok(())
+        }
+    }
+
     /// Merges this builder with another builder.
     ///
     /// # Panic
@@ -249,6 +364,117 @@ impl<'a> SubmitCommandBufferBuilder<'a> {
     }
 }
 
+/// Accumulates the submissions to a queue and submits them together.
+///
+/// The builders added with `add` are merged into one pending submission. Calling `end_frame` at
+/// the end of each frame submits it if auto-flush is enabled, which is the default. Otherwise,
+/// the pending submission is only submitted by `flush`.
+///
+/// A builder that can't be merged with the pending submission, because both of them signal a
+/// fence or because it waits on a semaphore signaled by the pending submission, causes the pending
+/// submission to be submitted first.
+///
+/// Dropping the batch discards the pending submission without submitting it.
+#[derive(Debug)]
+pub struct SubmitCommandBufferBatch<'a> {
+    queue: Arc<Queue>,
+    pending: Option<SubmitCommandBufferBuilder<'a>>,
+    auto_flush: bool,
+}
+
+impl<'a> SubmitCommandBufferBatch<'a> {
+    /// Builds a new empty batch for the given queue, with auto-flush enabled.
+    #[inline]
+    pub fn new(queue: Arc<Queue>) -> SubmitCommandBufferBatch<'a> {
+        SubmitCommandBufferBatch {
+            queue: queue,
+            pending: None,
+            auto_flush: true,
+        }
+    }
+
+    /// Returns the queue the batch submits to.
+    #[inline]
+    pub fn queue(&self) -> &Arc<Queue> {
+        &self.queue
+    }
+
+    /// Returns true if `end_frame` submits the pending submission.
+    #[inline]
+    pub fn auto_flush(&self) -> bool {
+        self.auto_flush
+    }
+
+    /// Sets whether `end_frame` submits the pending submission.
+    #[inline]
+    pub fn set_auto_flush(&mut self, auto_flush: bool) {
+        self.auto_flush = auto_flush;
+    }
+
+    /// Returns true if there is nothing to submit.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_none()
+    }
+
+    /// Returns the number of command buffers of the pending submission.
+    #[inline]
+    pub fn num_command_buffers(&self) -> usize {
+        self.pending
+            .as_ref()
+            .map(|pending| pending.num_command_buffers())
+            .unwrap_or(0)
+    }
+
+    /// Adds a builder to the pending submission.
+    ///
+    /// Returns an error if the pending submission had to be submitted first and that failed.
+    pub fn add(
+        &mut self,
+        builder: SubmitCommandBufferBuilder<'a>,
+    ) -> Result<(), SubmitCommandBufferError> {
+        let must_flush = match self.pending {
+            Some(ref pending) => {
+                (pending.fence != 0 && builder.fence != 0)
+                    || builder
+                        .wait_semaphores
+                        .iter()
+                        .any(|semaphore| pending.signal_semaphores.contains(semaphore))
+            }
+            None => false,
+        };
+
+        if must_flush {
+            self.flush()?;
+        }
+
+        self.pending = Some(match self.pending.take() {
+            Some(pending) => pending.merge(builder),
+            None => builder,
+        });
+
+        Ok(())
+    }
+
+    /// Submits the pending submission, if any.
+    pub fn flush(&mut self) -> Result<(), SubmitCommandBufferError> {
+        match self.pending.take() {
+            Some(pending) => pending.submit(&self.queue),
+            None => Ok(()),
+        }
+    }
+
+    /// Marks the end of a frame. Submits the pending submission if auto-flush is enabled.
+    #[inline]
+    pub fn end_frame(&mut self) -> Result<(), SubmitCommandBufferError> {
+        if self.auto_flush {
+            self.flush()
+        } else {
+            Ok(())
+        }
+    }
+}
+
 /// Error that can happen when submitting the prototype.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u32)]
@@ -361,4 +587,89 @@ mod tests {
             });
         }
     }
+
+    #[test]
+    fn merge_into_one_batch() {
+        unsafe {
+            let (device, queue) = gfx_dev_and_queue!();
+
+            let fence = Fence::alloc(device.clone()).unwrap();
+            let semaphore1 = Semaphore::alloc(device.clone()).unwrap();
+            let semaphore2 = Semaphore::alloc(device.clone()).unwrap();
+
+            let mut builder1 = SubmitCommandBufferBuilder::new();
+            builder1.add_signal_semaphore(&semaphore1);
+            let mut builder2 = SubmitCommandBufferBuilder::new();
+            builder2.add_signal_semaphore(&semaphore2);
+            builder2.set_fence_signal(&fence);
+
+            let builder = builder1.merge(builder2);
+            assert_eq!(builder.num_signal_semaphores(), 2);
+            assert_eq!(builder.num_command_buffers(), 0);
+            assert!(builder.has_fence());
+
+            builder.submit(&queue).unwrap();
+            fence.wait(Some(Duration::from_secs(5))).unwrap();
+        }
+    }
+
+    #[test]
+    fn batch_flushed_at_frame_end() {
+        unsafe {
+            let (device, queue) = gfx_dev_and_queue!();
+
+            let fence = Fence::alloc(device.clone()).unwrap();
+            let semaphore = Semaphore::alloc(device.clone()).unwrap();
+
+            let mut batch = SubmitCommandBufferBatch::new(queue.clone());
+            let mut builder1 = SubmitCommandBufferBuilder::new();
+            builder1.add_signal_semaphore(&semaphore);
+            batch.add(builder1).unwrap();
+            let mut builder2 = SubmitCommandBufferBuilder::new();
+            builder2.set_fence_signal(&fence);
+            batch.add(builder2).unwrap();
+            assert!(!batch.is_empty());
+
+            batch.set_auto_flush(false);
+            batch.end_frame().unwrap();
+            assert!(!batch.is_empty());
+            assert!(!fence.ready().unwrap());
+
+            batch.set_auto_flush(true);
+            batch.end_frame().unwrap();
+            assert!(batch.is_empty());
+            fence.wait(Some(Duration::from_secs(5))).unwrap();
+        }
+    }
+
+    #[test]
+    fn batch_flushed_before_waiting_on_pending_signal() {
+        unsafe {
+            let (device, queue) = gfx_dev_and_queue!();
+
+            let fence = Fence::alloc(device.clone()).unwrap();
+            let semaphore = Semaphore::alloc(device.clone()).unwrap();
+
+            let mut batch = SubmitCommandBufferBatch::new(queue.clone());
+            let mut builder1 = SubmitCommandBufferBuilder::new();
+            builder1.add_signal_semaphore(&semaphore);
+            builder1.set_fence_signal(&fence);
+            batch.add(builder1).unwrap();
+
+            // Waiting on the semaphore in the same submission would never complete.
+            let mut builder2 = SubmitCommandBufferBuilder::new();
+            builder2.add_wait_semaphore(
+                &semaphore,
+                PipelineStages {
+                    all_commands: true,
+                    ..PipelineStages::none()
+                },
+            );
+            batch.add(builder2).unwrap();
+
+            fence.wait(Some(Duration::from_secs(5))).unwrap();
+            batch.flush().unwrap();
+            queue.wait().unwrap();
+        }
+    }
 }
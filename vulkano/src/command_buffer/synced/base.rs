@@ -37,6 +37,7 @@ use std::cell::RefCell;
 use sync::AccessCheckError;
 use sync::AccessError;
 use sync::AccessFlagBits;
+use sync::Event;
 use sync::GpuFuture;
 use sync::PipelineStages;
 use OomError;
@@ -76,6 +77,13 @@ pub struct SyncCommandBufferBuilder<P> {
 
     // True if we're a secondary command buffer.
     is_secondary: bool,
+
+    // Number of pipeline barriers that were added to the inner builder.
+    num_pipeline_barriers: u32,
+
+    // Events waited on by the `vkCmdWaitEvents` commands, including the ones of the secondary
+    // command buffers that are executed.
+    waited_events: Vec<Arc<Event>>,
 }
 
 // # How pipeline stages work in Vulkan
@@ -153,6 +161,22 @@ pub enum SyncCommandBufferBuilderError {
         command2_param: Cow<'static, str>,
         command2_offset: usize,
     },
+
+    /// A command reads from a buffer whose content has never been written. Only checked in debug
+    /// builds. See `BufferAccess::is_initialized`.
+    UninitializedBuffer {
+        command_name: &'static str,
+        command_param: Cow<'static, str>,
+        command_offset: usize,
+    },
+
+    /// A command uses an image whose ownership has been released to another queue family, but
+    /// not acquired yet. See `UnsafeImage::pending_acquire_layouts`.
+    ImageNotAcquired {
+        command_name: &'static str,
+        command_param: Cow<'static, str>,
+        command_offset: usize,
+    },
 }
 
 impl error::Error for SyncCommandBufferBuilderError {}
@@ -165,6 +189,13 @@ impl fmt::Display for SyncCommandBufferBuilderError {
             "{}",
             match *self {
                 SyncCommandBufferBuilderError::Conflict { .. } => "unsolvable conflict",
+                SyncCommandBufferBuilderError::UninitializedBuffer { .. } => {
+                    "a command reads from a buffer that has never been written"
+                }
+                SyncCommandBufferBuilderError::ImageNotAcquired { .. } => {
+                    "a command uses an image that has been released to another queue family but \
+                     not acquired yet"
+                }
             }
         )
     }
@@ -348,6 +379,30 @@ struct ResourceState {
 
     // Current layout at this stage of the building.
     current_layout: ImageLayout,
+
+    // Last queue family ownership transfer of the resource recorded in the command buffer.
+    queue_family_transfer: Option<QueueFamilyTransfer>,
+
+    // True if a command of the command buffer writes to the buffer, which initializes it once the
+    // command buffer is submitted. Always false for images.
+    initializes: bool,
+}
+
+// Queue family ownership transfer of a resource recorded in a command buffer. It is applied to
+// the resource when the command buffer is submitted, and not when it is recorded, since the
+// command buffer could be submitted later or never.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(super) enum QueueFamilyTransfer {
+    // The ownership is released from `source` to `destination`. For images, also contains the
+    // layouts of the transition.
+    Release {
+        source: u32,
+        destination: u32,
+        old_layout: ImageLayout,
+        new_layout: ImageLayout,
+    },
+    // The ownership is acquired by the queue family it was released to.
+    Acquire,
 }
 
 impl ResourceState {
@@ -361,6 +416,8 @@ impl ResourceState {
             exclusive: self.exclusive_any,
             initial_layout: self.initial_layout,
             final_layout: self.current_layout,
+            queue_family_transfer: self.queue_family_transfer,
+            initializes: self.initializes,
         }
     }
 }
@@ -426,6 +483,8 @@ impl<P> SyncCommandBufferBuilder<P> {
                 commands: Vec::new(),
             })),
             is_secondary,
+            num_pipeline_barriers: 0,
+            waited_events: Vec::new(),
         }
     }
 
@@ -461,6 +520,16 @@ impl<P> SyncCommandBufferBuilder<P> {
         cmd_lock.latest_render_pass_enter = None;
     }
 
+    // Call this for the events that a command waits on, so that they can be reported if the
+    // command buffer is blocked.
+    #[inline]
+    pub(super) fn add_waited_events<'a, I>(&mut self, events: I)
+    where
+        I: IntoIterator<Item = &'a Arc<Event>>,
+    {
+        self.waited_events.extend(events.into_iter().cloned());
+    }
+
     // After a command is added to the list of pending commands, this function must be called for
     // each resource used by the command that has just been added.
     // The function will take care of handling the pipeline barrier or flushing.
@@ -482,6 +551,32 @@ impl<P> SyncCommandBufferBuilder<P> {
         access: AccessFlagBits,
         start_layout: ImageLayout,
         end_layout: ImageLayout,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        self.prev_cmd_resource_transfer(
+            resource_ty,
+            resource_index,
+            exclusive,
+            stages,
+            access,
+            start_layout,
+            end_layout,
+            None,
+        )
+    }
+
+    // Same as `prev_cmd_resource`, except that the previous command also transfers the queue
+    // family ownership of the resource. The transfer is applied to the resource when the command
+    // buffer is submitted.
+    pub(super) fn prev_cmd_resource_transfer(
+        &mut self,
+        resource_ty: KeyTy,
+        resource_index: usize,
+        exclusive: bool,
+        stages: PipelineStages,
+        access: AccessFlagBits,
+        start_layout: ImageLayout,
+        end_layout: ImageLayout,
+        queue_family_transfer: Option<QueueFamilyTransfer>,
     ) -> Result<(), SyncCommandBufferBuilderError> {
         // Anti-dumbness checks.
         debug_assert!(exclusive || start_layout == end_layout);
@@ -507,6 +602,61 @@ impl<P> SyncCommandBufferBuilder<P> {
             resource_index,
         };
 
+        // Only the commands that actually write to the buffer initialize it. Other exclusive
+        // accesses, such as queue family ownership transfers, don't.
+        let initializes = access.transfer_write || access.shader_write;
+
+        // The buffers are only marked as initialized when the command buffer that writes to them
+        // is submitted, so reading a buffer is also allowed after a write recorded earlier in this
+        // command buffer.
+        if cfg!(debug_assertions) && resource_ty == KeyTy::Buffer && !exclusive {
+            let written = self
+                .resources
+                .get(&key)
+                .map_or(false, |state| state.initializes);
+
+            let commands_lock = self.commands.lock().unwrap();
+            let command = &commands_lock.commands[latest_command_id];
+
+            if !written && !command.buffer(resource_index).is_initialized() {
+                return Err(SyncCommandBufferBuilderError::UninitializedBuffer {
+                    command_name: command.name(),
+                    command_param: command.buffer_name(resource_index),
+                    command_offset: latest_command_id,
+                });
+            }
+        }
+
+        if resource_ty == KeyTy::Image {
+            // The transfers recorded earlier in this command buffer take precedence over the
+            // transfers that have already been submitted.
+            let prev_transfer = self
+                .resources
+                .get(&key)
+                .and_then(|state| state.queue_family_transfer);
+
+            let commands_lock = self.commands.lock().unwrap();
+            let command = &commands_lock.commands[latest_command_id];
+            let image = command.image(resource_index);
+
+            let released = match prev_transfer {
+                Some(QueueFamilyTransfer::Release { .. }) => true,
+                Some(QueueFamilyTransfer::Acquire) => false,
+                None => image.inner().image.pending_acquire_layouts().is_some(),
+            };
+
+            // The acquire itself is the only command that can use an image that has been released
+            // by a submitted command buffer.
+            let allowed = queue_family_transfer.is_some() && prev_transfer.is_none();
+            if released && !allowed {
+                return Err(SyncCommandBufferBuilderError::ImageNotAcquired {
+                    command_name: command.name(),
+                    command_param: command.image_name(resource_index),
+                    command_offset: latest_command_id,
+                });
+            }
+        }
+
         // Note that the call to `entry()` will lock the mutex, so we can't keep it locked
         // throughout the function.
         match self.resources.entry(key) {
@@ -536,6 +686,9 @@ impl<P> SyncCommandBufferBuilder<P> {
                     {
                         unsafe {
                             // Flush the pending barrier.
+                            if !self.pending_barrier.is_empty() {
+                                self.num_pipeline_barriers += 1;
+                            }
                             self.inner.pipeline_barrier(&self.pending_barrier);
                             self.pending_barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
 
@@ -643,6 +796,10 @@ impl<P> SyncCommandBufferBuilder<P> {
                         // layout to `Undefined`, a buffer read must not touch it.
                         entry.current_layout = end_layout;
                     }
+                    if queue_family_transfer.is_some() {
+                        entry.queue_family_transfer = queue_family_transfer;
+                    }
+                    entry.initializes |= initializes;
                 } else {
                     // There is no collision. Simply merge the stages and accesses.
                     // TODO: what about simplifying the newly-constructed stages/accesses?
@@ -727,6 +884,8 @@ impl<P> SyncCommandBufferBuilder<P> {
                     exclusive: actually_exclusive,
                     initial_layout: actual_start_layout,
                     current_layout: end_layout, // TODO: what if we reach the end with Undefined? that's not correct?
+                    queue_family_transfer,
+                    initializes,
                 });
             }
         }
@@ -747,6 +906,9 @@ impl<P> SyncCommandBufferBuilder<P> {
 
         // The commands that haven't been sent to the inner command buffer yet need to be sent.
         unsafe {
+            if !self.pending_barrier.is_empty() {
+                self.num_pipeline_barriers += 1;
+            }
             self.inner.pipeline_barrier(&self.pending_barrier);
             let f = commands_lock.first_unflushed;
             for command in &mut commands_lock.commands[f..] {
@@ -793,6 +955,9 @@ impl<P> SyncCommandBufferBuilder<P> {
                     state.current_layout = requested_layout;
                 }
 
+                if !barrier.is_empty() {
+                    self.num_pipeline_barriers += 1;
+                }
                 self.inner.pipeline_barrier(&barrier);
             }
         }
@@ -823,6 +988,8 @@ impl<P> SyncCommandBufferBuilder<P> {
             inner: self.inner.build()?,
             resources: final_resources_states,
             commands: final_commands,
+            num_pipeline_barriers: self.num_pipeline_barriers,
+            waited_events: self.waited_events,
         })
     }
 }
@@ -847,6 +1014,12 @@ pub struct SyncCommandBuffer<P> {
     // being used. Each element of `resources` has a copy of this `Arc`, but we need to keep one
     // here in case `resources` is empty.
     commands: Arc<Mutex<Vec<Box<dyn FinalCommand + Send + Sync>>>>,
+
+    // Number of pipeline barriers that were added automatically.
+    num_pipeline_barriers: u32,
+
+    // Events waited on by the commands of the command buffer.
+    waited_events: Vec<Arc<Event>>,
 }
 
 // Usage of a resource in a finished command buffer.
@@ -866,6 +1039,13 @@ struct ResourceFinalState {
 
     // Layout the image will be in at the end of the command buffer.
     final_layout: ImageLayout, // TODO: maybe wrap in an Option to mean that the layout doesn't change? because of buffers?
+
+    // Queue family ownership transfer to apply to the resource when the command buffer is
+    // submitted.
+    queue_family_transfer: Option<QueueFamilyTransfer>,
+
+    // True if the buffer must be marked as initialized when the command buffer is submitted.
+    initializes: bool,
 }
 
 /// Equivalent to `Command`, but with less methods. Typically contains less things than the
@@ -1088,6 +1268,19 @@ impl<P> AsRef<UnsafeCommandBuffer<P>> for SyncCommandBuffer<P> {
 }
 
 impl<P> SyncCommandBuffer<P> {
+    /// Returns the number of pipeline barriers that the builder added in order to synchronize
+    /// the commands.
+    #[inline]
+    pub fn num_pipeline_barriers(&self) -> u32 {
+        self.num_pipeline_barriers
+    }
+
+    /// Returns the events that the `vkCmdWaitEvents` commands of the command buffer wait on.
+    #[inline]
+    pub fn waited_events(&self) -> &[Arc<Event>] {
+        &self.waited_events
+    }
+
     /// Tries to lock the resources used by the command buffer.
     ///
     /// > **Note**: You should call this in the implementation of the `CommandBuffer` trait.
@@ -1228,9 +1421,103 @@ impl<P> SyncCommandBuffer<P> {
 
         // TODO: pipeline barriers if necessary?
 
+        if ret_value.is_ok() {
+            self.apply_queue_family_transfers(&commands_lock);
+            if cfg!(debug_assertions) {
+                self.mark_buffers_initialized(&commands_lock);
+            }
+        }
+
         ret_value
     }
 
+    // Marks the buffers that the command buffer writes to as initialized. Called when the command
+    // buffer is submitted.
+    fn mark_buffers_initialized(&self, commands_lock: &[Box<dyn FinalCommand + Send + Sync>]) {
+        for (key, entry) in self.resources.iter() {
+            if !entry.initializes {
+                continue;
+            }
+
+            match *key {
+                CbKey::Command {
+                    ref command_ids,
+                    resource_ty: KeyTy::Buffer,
+                    resource_index,
+                    ..
+                } => {
+                    commands_lock[command_ids[0]]
+                        .buffer(resource_index)
+                        .mark_initialized();
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    // Applies the queue family ownership transfers of the command buffer to its resources. Called
+    // when the command buffer is submitted.
+    fn apply_queue_family_transfers(&self, commands_lock: &[Box<dyn FinalCommand + Send + Sync>]) {
+        for (key, entry) in self.resources.iter() {
+            let transfer = match entry.queue_family_transfer {
+                Some(transfer) => transfer,
+                None => continue,
+            };
+
+            let (command_ids, resource_ty, resource_index) = match *key {
+                CbKey::Command {
+                    ref command_ids,
+                    resource_ty,
+                    resource_index,
+                    ..
+                } => (command_ids, resource_ty, resource_index),
+                _ => unreachable!(),
+            };
+
+            let cmd = &commands_lock[command_ids[0]];
+            match (resource_ty, transfer) {
+                (
+                    KeyTy::Buffer,
+                    QueueFamilyTransfer::Release {
+                        source,
+                        destination,
+                        ..
+                    },
+                ) => {
+                    let buf = cmd.buffer(resource_index);
+                    buf.inner()
+                        .buffer
+                        .set_queue_family_released(source, destination);
+                }
+                (KeyTy::Buffer, QueueFamilyTransfer::Acquire) => {
+                    let buf = cmd.buffer(resource_index);
+                    buf.inner().buffer.set_queue_family_acquired();
+                }
+                (
+                    KeyTy::Image,
+                    QueueFamilyTransfer::Release {
+                        source,
+                        destination,
+                        old_layout,
+                        new_layout,
+                    },
+                ) => {
+                    let img = cmd.image(resource_index);
+                    img.inner().image.set_queue_family_released(
+                        source,
+                        destination,
+                        old_layout,
+                        new_layout,
+                    );
+                }
+                (KeyTy::Image, QueueFamilyTransfer::Acquire) => {
+                    let img = cmd.image(resource_index);
+                    img.inner().image.set_queue_family_acquired();
+                }
+            }
+        }
+    }
+
     /// Unlocks the resources used by the command buffer.
     ///
     /// > **Note**: You should call this in the implementation of the `CommandBuffer` trait.
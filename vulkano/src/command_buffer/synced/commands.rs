@@ -10,7 +10,11 @@
 use smallvec::SmallVec;
 use std::any::Any;
 use std::borrow::Cow;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::iter;
 use std::mem;
+use std::ops::Range;
 use std::ptr;
 use std::sync::Arc;
 
@@ -18,16 +22,22 @@ use buffer::BufferAccess;
 use command_buffer::synced::base::Command;
 use command_buffer::synced::base::FinalCommand;
 use command_buffer::synced::base::KeyTy;
+use command_buffer::synced::base::QueueFamilyTransfer;
 use command_buffer::synced::base::SyncCommandBufferBuilder;
 use command_buffer::synced::base::SyncCommandBufferBuilderError;
 use command_buffer::sys::UnsafeCommandBufferBuilder;
 use command_buffer::sys::UnsafeCommandBufferBuilderBindVertexBuffer;
 use command_buffer::sys::UnsafeCommandBufferBuilderBufferImageCopy;
 use command_buffer::sys::UnsafeCommandBufferBuilderColorImageClear;
+use command_buffer::sys::UnsafeCommandBufferBuilderDepthStencilImageClear;
 use command_buffer::sys::UnsafeCommandBufferBuilderExecuteCommands;
+use command_buffer::sys::UnsafeCommandBufferBuilderImageAspect;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageBlit;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageCopy;
+use command_buffer::sys::UnsafeCommandBufferBuilderImageResolve;
+use command_buffer::sys::UnsafeCommandBufferBuilderPipelineBarrier;
 use command_buffer::CommandBuffer;
+use command_buffer::PipelineBarrier;
 use descriptor::descriptor::DescriptorDescTy;
 use descriptor::descriptor::ShaderStages;
 use descriptor::descriptor_set::DescriptorSet;
@@ -44,12 +54,56 @@ use pipeline::viewport::Scissor;
 use pipeline::viewport::Viewport;
 use pipeline::ComputePipelineAbstract;
 use pipeline::GraphicsPipelineAbstract;
+use pipeline::PipelineBindPoint;
+use pipeline::RayTracingPipelineAbstract;
+use pipeline::ShaderBindingTableRegions;
+use query::QueryPool;
 use sampler::Filter;
 use sync::AccessFlagBits;
 use sync::Event;
 use sync::PipelineStages;
+use VulkanObject;
 
 impl<P> SyncCommandBufferBuilder<P> {
+    /// Calls `vkCmdBeginQuery` on the builder.
+    #[inline]
+    pub unsafe fn begin_query(&mut self, query_pool: Arc<QueryPool>, query: u32, precise: bool) {
+        struct Cmd {
+            query_pool: Arc<QueryPool>,
+            query: u32,
+            precise: bool,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdBeginQuery"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.begin_query(
+                    self.query_pool.inner().query(self.query).unwrap(),
+                    self.precise,
+                );
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin(Arc<QueryPool>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdBeginQuery"
+                    }
+                }
+                Box::new(Fin(self.query_pool))
+            }
+        }
+
+        self.append_command(Cmd {
+            query_pool,
+            query,
+            precise,
+        });
+    }
+
     /// Calls `vkBeginRenderPass` on the builder.
     // TODO: it shouldn't be possible to get an error if the framebuffer checked conflicts already
     // TODO: after begin_render_pass has been called, flushing should be forbidden and an error
@@ -308,6 +362,45 @@ impl<P> SyncCommandBufferBuilder<P> {
         self.append_command(Cmd { pipeline });
     }
 
+    /// Calls `vkCmdBindPipeline` on the builder with a ray tracing pipeline.
+    #[inline]
+    pub unsafe fn bind_pipeline_ray_tracing<Rp>(&mut self, pipeline: Rp)
+    where
+        Rp: RayTracingPipelineAbstract + Send + Sync + 'static,
+    {
+        struct Cmd<Rp> {
+            pipeline: Rp,
+        }
+
+        impl<P, Rp> Command<P> for Cmd<Rp>
+        where
+            Rp: RayTracingPipelineAbstract + Send + Sync + 'static,
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdBindPipeline"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.bind_pipeline_ray_tracing(&self.pipeline);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<Rp>(Rp);
+                impl<Rp> FinalCommand for Fin<Rp>
+                where
+                    Rp: Send + Sync + 'static,
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdBindPipeline"
+                    }
+                }
+                Box::new(Fin(self.pipeline))
+            }
+        }
+
+        self.append_command(Cmd { pipeline });
+    }
+
     /// Starts the process of binding descriptor sets. Returns an intermediate struct which can be
     /// used to add the sets.
     #[inline]
@@ -333,6 +426,9 @@ impl<P> SyncCommandBufferBuilder<P> {
     ///
     /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
     /// usage of the command anyway.
+    ///
+    /// The source and the destination can be the same image if both layouts are equal and the
+    /// regions don't overlap, which isn't checked.
     #[inline]
     pub unsafe fn copy_image<S, D, R>(
         &mut self,
@@ -434,6 +530,10 @@ impl<P> SyncCommandBufferBuilder<P> {
             }
         }
 
+        let same_image = source.inner().image.internal_object()
+            == destination.inner().image.internal_object()
+            && source_layout == destination_layout;
+
         self.append_command(Cmd {
             source: Some(source),
             source_layout,
@@ -441,6 +541,29 @@ impl<P> SyncCommandBufferBuilder<P> {
             destination_layout,
             regions: Some(regions),
         });
+
+        // Copying within the same image is allowed as long as the regions don't overlap, in which
+        // case the image is only declared once as being both read and written.
+        if same_image {
+            self.prev_cmd_resource(
+                KeyTy::Image,
+                1,
+                true,
+                PipelineStages {
+                    transfer: true,
+                    ..PipelineStages::none()
+                },
+                AccessFlagBits {
+                    transfer_read: true,
+                    transfer_write: true,
+                    ..AccessFlagBits::none()
+                },
+                destination_layout,
+                destination_layout,
+            )?;
+            return Ok(());
+        }
+
         self.prev_cmd_resource(
             KeyTy::Image,
             0,
@@ -623,140 +746,48 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
-    /// Calls `vkCmdClearColorImage` on the builder.
-    ///
-    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
-    /// usage of the command anyway.
-    pub unsafe fn clear_color_image<I, R>(
-        &mut self,
-        image: I,
-        layout: ImageLayout,
-        color: ClearValue,
-        regions: R,
-    ) -> Result<(), SyncCommandBufferBuilderError>
-    where
-        I: ImageAccess + Send + Sync + 'static,
-        R: Iterator<Item = UnsafeCommandBufferBuilderColorImageClear> + Send + Sync + 'static,
-    {
-        struct Cmd<I, R> {
-            image: Option<I>,
-            layout: ImageLayout,
-            color: ClearValue,
-            regions: Option<R>,
-        }
-
-        impl<P, I, R> Command<P> for Cmd<I, R>
-        where
-            I: ImageAccess + Send + Sync + 'static,
-            R: Iterator<Item = UnsafeCommandBufferBuilderColorImageClear> + Send + Sync + 'static,
-        {
-            fn name(&self) -> &'static str {
-                "vkCmdClearColorImage"
-            }
-
-            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.clear_color_image(
-                    self.image.as_ref().unwrap(),
-                    self.layout,
-                    self.color,
-                    self.regions.take().unwrap(),
-                );
-            }
-
-            fn into_final_command(mut self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
-                struct Fin<I>(I);
-                impl<I> FinalCommand for Fin<I>
-                where
-                    I: ImageAccess + Send + Sync + 'static,
-                {
-                    fn name(&self) -> &'static str {
-                        "vkCmdClearColorImage"
-                    }
-                    fn image(&self, num: usize) -> &dyn ImageAccess {
-                        assert_eq!(num, 0);
-                        &self.0
-                    }
-                    fn image_name(&self, num: usize) -> Cow<'static, str> {
-                        assert_eq!(num, 0);
-                        "target".into()
-                    }
-                }
-
-                // Note: borrow checker somehow doesn't accept `self.image` without using an Option.
-                Box::new(Fin(self.image.take().unwrap()))
-            }
-
-            fn image(&self, num: usize) -> &dyn ImageAccess {
-                assert_eq!(num, 0);
-                self.image.as_ref().unwrap()
-            }
-
-            fn image_name(&self, num: usize) -> Cow<'static, str> {
-                assert_eq!(num, 0);
-                "target".into()
-            }
-        }
-
-        self.append_command(Cmd {
-            image: Some(image),
-            layout,
-            color,
-            regions: Some(regions),
-        });
-        self.prev_cmd_resource(
-            KeyTy::Image,
-            0,
-            true,
-            PipelineStages {
-                transfer: true,
-                ..PipelineStages::none()
-            },
-            AccessFlagBits {
-                transfer_write: true,
-                ..AccessFlagBits::none()
-            },
-            layout,
-            layout,
-        )?;
-        Ok(())
-    }
-
-    /// Calls `vkCmdCopyBuffer` on the builder.
+    /// Calls `vkCmdResolveImage` on the builder.
     ///
     /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
     /// usage of the command anyway.
     #[inline]
-    pub unsafe fn copy_buffer<S, D, R>(
+    pub unsafe fn resolve_image<S, D, R>(
         &mut self,
         source: S,
+        source_layout: ImageLayout,
         destination: D,
+        destination_layout: ImageLayout,
         regions: R,
     ) -> Result<(), SyncCommandBufferBuilderError>
     where
-        S: BufferAccess + Send + Sync + 'static,
-        D: BufferAccess + Send + Sync + 'static,
-        R: Iterator<Item = (usize, usize, usize)> + Send + Sync + 'static,
+        S: ImageAccess + Send + Sync + 'static,
+        D: ImageAccess + Send + Sync + 'static,
+        R: Iterator<Item = UnsafeCommandBufferBuilderImageResolve> + Send + Sync + 'static,
     {
         struct Cmd<S, D, R> {
             source: Option<S>,
+            source_layout: ImageLayout,
             destination: Option<D>,
+            destination_layout: ImageLayout,
             regions: Option<R>,
         }
 
         impl<P, S, D, R> Command<P> for Cmd<S, D, R>
         where
-            S: BufferAccess + Send + Sync + 'static,
-            D: BufferAccess + Send + Sync + 'static,
-            R: Iterator<Item = (usize, usize, usize)>,
+            S: ImageAccess + Send + Sync + 'static,
+            D: ImageAccess + Send + Sync + 'static,
+            R: Iterator<Item = UnsafeCommandBufferBuilderImageResolve>,
         {
             fn name(&self) -> &'static str {
-                "vkCmdCopyBuffer"
+                "vkCmdResolveImage"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.copy_buffer(
+                out.resolve_image(
                     self.source.as_ref().unwrap(),
+                    self.source_layout,
                     self.destination.as_ref().unwrap(),
+                    self.destination_layout,
                     self.regions.take().unwrap(),
                 );
             }
@@ -765,27 +796,32 @@ impl<P> SyncCommandBufferBuilder<P> {
                 struct Fin<S, D>(S, D);
                 impl<S, D> FinalCommand for Fin<S, D>
                 where
-                    S: BufferAccess + Send + Sync + 'static,
-                    D: BufferAccess + Send + Sync + 'static,
+                    S: ImageAccess + Send + Sync + 'static,
+                    D: ImageAccess + Send + Sync + 'static,
                 {
                     fn name(&self) -> &'static str {
-                        "vkCmdCopyBuffer"
+                        "vkCmdResolveImage"
                     }
-                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
-                        match num {
-                            0 => &self.0,
-                            1 => &self.1,
-                            _ => panic!(),
+                    fn image(&self, num: usize) -> &dyn ImageAccess {
+                        if num == 0 {
+                            &self.0
+                        } else if num == 1 {
+                            &self.1
+                        } else {
+                            panic!()
                         }
                     }
-                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
-                        match num {
-                            0 => "source".into(),
-                            1 => "destination".into(),
-                            _ => panic!(),
+                    fn image_name(&self, num: usize) -> Cow<'static, str> {
+                        if num == 0 {
+                            "source".into()
+                        } else if num == 1 {
+                            "destination".into()
+                        } else {
+                            panic!()
                         }
                     }
                 }
+
                 // Note: borrow checker somehow doesn't accept `self.source` and `self.destination`
                 // without using an Option.
                 Box::new(Fin(
@@ -794,30 +830,36 @@ impl<P> SyncCommandBufferBuilder<P> {
                 ))
             }
 
-            fn buffer(&self, num: usize) -> &dyn BufferAccess {
-                match num {
-                    0 => self.source.as_ref().unwrap(),
-                    1 => self.destination.as_ref().unwrap(),
-                    _ => panic!(),
+            fn image(&self, num: usize) -> &dyn ImageAccess {
+                if num == 0 {
+                    self.source.as_ref().unwrap()
+                } else if num == 1 {
+                    self.destination.as_ref().unwrap()
+                } else {
+                    panic!()
                 }
             }
 
-            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
-                match num {
-                    0 => "source".into(),
-                    1 => "destination".into(),
-                    _ => panic!(),
+            fn image_name(&self, num: usize) -> Cow<'static, str> {
+                if num == 0 {
+                    "source".into()
+                } else if num == 1 {
+                    "destination".into()
+                } else {
+                    panic!()
                 }
             }
         }
 
         self.append_command(Cmd {
             source: Some(source),
+            source_layout,
             destination: Some(destination),
+            destination_layout,
             regions: Some(regions),
         });
         self.prev_cmd_resource(
-            KeyTy::Buffer,
+            KeyTy::Image,
             0,
             false,
             PipelineStages {
@@ -828,11 +870,11 @@ impl<P> SyncCommandBufferBuilder<P> {
                 transfer_read: true,
                 ..AccessFlagBits::none()
             },
-            ImageLayout::Undefined,
-            ImageLayout::Undefined,
+            source_layout,
+            source_layout,
         )?;
         self.prev_cmd_resource(
-            KeyTy::Buffer,
+            KeyTy::Image,
             1,
             true,
             PipelineStages {
@@ -843,17 +885,549 @@ impl<P> SyncCommandBufferBuilder<P> {
                 transfer_write: true,
                 ..AccessFlagBits::none()
             },
-            ImageLayout::Undefined,
-            ImageLayout::Undefined,
+            destination_layout,
+            destination_layout,
         )?;
         Ok(())
     }
 
-    /// Calls `vkCmdCopyBufferToImage` on the builder.
+    /// Generates the mipmaps of an image by calling `vkCmdBlitImage` from each level to the next
+    /// one, with pipeline barriers in between.
     ///
-    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
-    /// usage of the command anyway.
-    #[inline]
+    /// The image must be in the `TransferDstOptimal` layout, and is left in the
+    /// `TransferSrcOptimal` layout.
+    pub unsafe fn generate_mipmaps<I>(
+        &mut self,
+        image: I,
+    ) -> Result<(), SyncCommandBufferBuilderError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
+    {
+        struct Cmd<I> {
+            image: Option<I>,
+        }
+
+        impl<P, I> Command<P> for Cmd<I>
+        where
+            I: ImageAccess + Send + Sync + 'static,
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdBlitImage"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                let image = self.image.as_ref().unwrap();
+                let dimensions = image.dimensions();
+                let layers = dimensions.array_layers();
+
+                let transfer = PipelineStages {
+                    transfer: true,
+                    ..PipelineStages::none()
+                };
+                let write = AccessFlagBits {
+                    transfer_write: true,
+                    ..AccessFlagBits::none()
+                };
+                let read = AccessFlagBits {
+                    transfer_read: true,
+                    ..AccessFlagBits::none()
+                };
+
+                // Moves a level that has been written to the layout in which it can be read.
+                let to_source = |out: &mut UnsafeCommandBufferBuilder<P>, level: u32| {
+                    let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+                    barrier.add_image_memory_barrier(
+                        image,
+                        level..level + 1,
+                        0..layers,
+                        transfer,
+                        write,
+                        transfer,
+                        read,
+                        false,
+                        None,
+                        ImageLayout::TransferDstOptimal,
+                        ImageLayout::TransferSrcOptimal,
+                    );
+                    out.pipeline_barrier(&barrier);
+                };
+
+                for level in 1..image.mipmap_levels() {
+                    to_source(out, level - 1);
+
+                    let [sw, sh, sd] = dimensions
+                        .mipmap_dimensions(level - 1)
+                        .unwrap()
+                        .width_height_depth();
+                    let [dw, dh, dd] = dimensions
+                        .mipmap_dimensions(level)
+                        .unwrap()
+                        .width_height_depth();
+
+                    let blit = UnsafeCommandBufferBuilderImageBlit {
+                        aspect: UnsafeCommandBufferBuilderImageAspect {
+                            color: true,
+                            depth: false,
+                            stencil: false,
+                        },
+                        source_mip_level: level - 1,
+                        destination_mip_level: level,
+                        source_base_array_layer: 0,
+                        destination_base_array_layer: 0,
+                        layer_count: layers,
+                        source_top_left: [0, 0, 0],
+                        source_bottom_right: [sw as i32, sh as i32, sd as i32],
+                        destination_top_left: [0, 0, 0],
+                        destination_bottom_right: [dw as i32, dh as i32, dd as i32],
+                    };
+
+                    out.blit_image(
+                        image,
+                        ImageLayout::TransferSrcOptimal,
+                        image,
+                        ImageLayout::TransferDstOptimal,
+                        iter::once(blit),
+                        Filter::Linear,
+                    );
+                }
+
+                to_source(out, image.mipmap_levels() - 1);
+            }
+
+            fn into_final_command(mut self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<I>(I);
+                impl<I> FinalCommand for Fin<I>
+                where
+                    I: ImageAccess + Send + Sync + 'static,
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdBlitImage"
+                    }
+                    fn image(&self, num: usize) -> &dyn ImageAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn image_name(&self, num: usize) -> Cow<'static, str> {
+                        assert_eq!(num, 0);
+                        "image".into()
+                    }
+                }
+
+                // Note: borrow checker somehow doesn't accept `self.image` without using an Option.
+                Box::new(Fin(self.image.take().unwrap()))
+            }
+
+            fn image(&self, num: usize) -> &dyn ImageAccess {
+                assert_eq!(num, 0);
+                self.image.as_ref().unwrap()
+            }
+
+            fn image_name(&self, num: usize) -> Cow<'static, str> {
+                assert_eq!(num, 0);
+                "image".into()
+            }
+        }
+
+        self.append_command(Cmd { image: Some(image) });
+        self.prev_cmd_resource(
+            KeyTy::Image,
+            0,
+            true,
+            PipelineStages {
+                transfer: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits {
+                transfer_read: true,
+                transfer_write: true,
+                ..AccessFlagBits::none()
+            },
+            ImageLayout::TransferDstOptimal,
+            ImageLayout::TransferSrcOptimal,
+        )?;
+        Ok(())
+    }
+
+    /// Calls `vkCmdClearColorImage` on the builder.
+    ///
+    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
+    /// usage of the command anyway.
+    pub unsafe fn clear_color_image<I, R>(
+        &mut self,
+        image: I,
+        layout: ImageLayout,
+        color: ClearValue,
+        regions: R,
+    ) -> Result<(), SyncCommandBufferBuilderError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
+        R: Iterator<Item = UnsafeCommandBufferBuilderColorImageClear> + Send + Sync + 'static,
+    {
+        struct Cmd<I, R> {
+            image: Option<I>,
+            layout: ImageLayout,
+            color: ClearValue,
+            regions: Option<R>,
+        }
+
+        impl<P, I, R> Command<P> for Cmd<I, R>
+        where
+            I: ImageAccess + Send + Sync + 'static,
+            R: Iterator<Item = UnsafeCommandBufferBuilderColorImageClear> + Send + Sync + 'static,
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdClearColorImage"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.clear_color_image(
+                    self.image.as_ref().unwrap(),
+                    self.layout,
+                    self.color,
+                    self.regions.take().unwrap(),
+                );
+            }
+
+            fn into_final_command(mut self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<I>(I);
+                impl<I> FinalCommand for Fin<I>
+                where
+                    I: ImageAccess + Send + Sync + 'static,
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdClearColorImage"
+                    }
+                    fn image(&self, num: usize) -> &dyn ImageAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn image_name(&self, num: usize) -> Cow<'static, str> {
+                        assert_eq!(num, 0);
+                        "target".into()
+                    }
+                }
+
+                // Note: borrow checker somehow doesn't accept `self.image` without using an Option.
+                Box::new(Fin(self.image.take().unwrap()))
+            }
+
+            fn image(&self, num: usize) -> &dyn ImageAccess {
+                assert_eq!(num, 0);
+                self.image.as_ref().unwrap()
+            }
+
+            fn image_name(&self, num: usize) -> Cow<'static, str> {
+                assert_eq!(num, 0);
+                "target".into()
+            }
+        }
+
+        self.append_command(Cmd {
+            image: Some(image),
+            layout,
+            color,
+            regions: Some(regions),
+        });
+        self.prev_cmd_resource(
+            KeyTy::Image,
+            0,
+            true,
+            PipelineStages {
+                transfer: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits {
+                transfer_write: true,
+                ..AccessFlagBits::none()
+            },
+            layout,
+            layout,
+        )?;
+        Ok(())
+    }
+
+    /// Calls `vkCmdClearDepthStencilImage` on the builder.
+    ///
+    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
+    /// usage of the command anyway.
+    pub unsafe fn clear_depth_stencil_image<I, R>(
+        &mut self,
+        image: I,
+        layout: ImageLayout,
+        clear_value: ClearValue,
+        regions: R,
+    ) -> Result<(), SyncCommandBufferBuilderError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
+        R: Iterator<Item = UnsafeCommandBufferBuilderDepthStencilImageClear>
+            + Send
+            + Sync
+            + 'static,
+    {
+        struct Cmd<I, R> {
+            image: Option<I>,
+            layout: ImageLayout,
+            clear_value: ClearValue,
+            regions: Option<R>,
+        }
+
+        impl<P, I, R> Command<P> for Cmd<I, R>
+        where
+            I: ImageAccess + Send + Sync + 'static,
+            R: Iterator<Item = UnsafeCommandBufferBuilderDepthStencilImageClear>
+                + Send
+                + Sync
+                + 'static,
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdClearDepthStencilImage"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.clear_depth_stencil_image(
+                    self.image.as_ref().unwrap(),
+                    self.layout,
+                    self.clear_value,
+                    self.regions.take().unwrap(),
+                );
+            }
+
+            fn into_final_command(mut self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<I>(I);
+                impl<I> FinalCommand for Fin<I>
+                where
+                    I: ImageAccess + Send + Sync + 'static,
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdClearDepthStencilImage"
+                    }
+                    fn image(&self, num: usize) -> &dyn ImageAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn image_name(&self, num: usize) -> Cow<'static, str> {
+                        assert_eq!(num, 0);
+                        "target".into()
+                    }
+                }
+
+                // Note: borrow checker somehow doesn't accept `self.image` without using an Option.
+                Box::new(Fin(self.image.take().unwrap()))
+            }
+
+            fn image(&self, num: usize) -> &dyn ImageAccess {
+                assert_eq!(num, 0);
+                self.image.as_ref().unwrap()
+            }
+
+            fn image_name(&self, num: usize) -> Cow<'static, str> {
+                assert_eq!(num, 0);
+                "target".into()
+            }
+        }
+
+        self.append_command(Cmd {
+            image: Some(image),
+            layout,
+            clear_value,
+            regions: Some(regions),
+        });
+        self.prev_cmd_resource(
+            KeyTy::Image,
+            0,
+            true,
+            PipelineStages {
+                transfer: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits {
+                transfer_write: true,
+                ..AccessFlagBits::none()
+            },
+            layout,
+            layout,
+        )?;
+        Ok(())
+    }
+
+    /// Calls `vkCmdCopyBuffer` on the builder.
+    ///
+    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
+    /// usage of the command anyway.
+    ///
+    /// The source and the destination can be the same buffer if the regions that are read don't
+    /// overlap the regions that are written. Returns a conflict error otherwise.
+    #[inline]
+    pub unsafe fn copy_buffer<S, D, R>(
+        &mut self,
+        source: S,
+        destination: D,
+        regions: R,
+    ) -> Result<(), SyncCommandBufferBuilderError>
+    where
+        S: BufferAccess + Send + Sync + 'static,
+        D: BufferAccess + Send + Sync + 'static,
+        R: Iterator<Item = (usize, usize, usize)> + Send + Sync + 'static,
+    {
+        struct Cmd<S, D> {
+            source: Option<S>,
+            destination: Option<D>,
+            regions: Option<SmallVec<[(usize, usize, usize); 8]>>,
+        }
+
+        impl<P, S, D> Command<P> for Cmd<S, D>
+        where
+            S: BufferAccess + Send + Sync + 'static,
+            D: BufferAccess + Send + Sync + 'static,
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdCopyBuffer"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.copy_buffer(
+                    self.source.as_ref().unwrap(),
+                    self.destination.as_ref().unwrap(),
+                    self.regions.take().unwrap().into_iter(),
+                );
+            }
+
+            fn into_final_command(mut self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<S, D>(S, D);
+                impl<S, D> FinalCommand for Fin<S, D>
+                where
+                    S: BufferAccess + Send + Sync + 'static,
+                    D: BufferAccess + Send + Sync + 'static,
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdCopyBuffer"
+                    }
+                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                        match num {
+                            0 => &self.0,
+                            1 => &self.1,
+                            _ => panic!(),
+                        }
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        match num {
+                            0 => "source".into(),
+                            1 => "destination".into(),
+                            _ => panic!(),
+                        }
+                    }
+                }
+                // Note: borrow checker somehow doesn't accept `self.source` and `self.destination`
+                // without using an Option.
+                Box::new(Fin(
+                    self.source.take().unwrap(),
+                    self.destination.take().unwrap(),
+                ))
+            }
+
+            fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                match num {
+                    0 => self.source.as_ref().unwrap(),
+                    1 => self.destination.as_ref().unwrap(),
+                    _ => panic!(),
+                }
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                match num {
+                    0 => "source".into(),
+                    1 => "destination".into(),
+                    _ => panic!(),
+                }
+            }
+        }
+
+        let regions: SmallVec<[_; 8]> = regions.collect();
+
+        // Copying within the same buffer is allowed as long as the regions don't overlap, in which
+        // case the buffer is only declared once as being both read and written. The source and the
+        // destination can be different slices of the buffer, so the regions are compared in
+        // absolute offsets.
+        let (same_buffer, source_offset, destination_offset) = {
+            let (s, d) = (source.inner(), destination.inner());
+            (
+                s.buffer.internal_object() == d.buffer.internal_object(),
+                s.offset,
+                d.offset,
+            )
+        };
+        let overlapping = regions.iter().any(|&(src, _, src_size)| {
+            let src = source_offset + src;
+            regions.iter().any(|&(_, dst, dst_size)| {
+                let dst = destination_offset + dst;
+                src < dst + dst_size && dst < src + src_size
+            })
+        });
+
+        self.append_command(Cmd {
+            source: Some(source),
+            destination: Some(destination),
+            regions: Some(regions),
+        });
+
+        if same_buffer && !overlapping {
+            self.prev_cmd_resource(
+                KeyTy::Buffer,
+                1,
+                true,
+                PipelineStages {
+                    transfer: true,
+                    ..PipelineStages::none()
+                },
+                AccessFlagBits {
+                    transfer_read: true,
+                    transfer_write: true,
+                    ..AccessFlagBits::none()
+                },
+                ImageLayout::Undefined,
+                ImageLayout::Undefined,
+            )?;
+            return Ok(());
+        }
+
+        self.prev_cmd_resource(
+            KeyTy::Buffer,
+            0,
+            false,
+            PipelineStages {
+                transfer: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits {
+                transfer_read: true,
+                ..AccessFlagBits::none()
+            },
+            ImageLayout::Undefined,
+            ImageLayout::Undefined,
+        )?;
+        self.prev_cmd_resource(
+            KeyTy::Buffer,
+            1,
+            true,
+            PipelineStages {
+                transfer: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits {
+                transfer_write: true,
+                ..AccessFlagBits::none()
+            },
+            ImageLayout::Undefined,
+            ImageLayout::Undefined,
+        )?;
+        Ok(())
+    }
+
+    /// Calls `vkCmdCopyBufferToImage` on the builder.
+    ///
+    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
+    /// usage of the command anyway.
+    #[inline]
     pub unsafe fn copy_buffer_to_image<S, D, R>(
         &mut self,
         source: S,
@@ -1127,6 +1701,84 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
+    /// Calls `vkCmdBeginDebugUtilsLabelEXT` on the builder.
+    #[inline]
+    pub unsafe fn debug_label_begin(&mut self, name: &CStr, color: [f32; 4]) {
+        struct Cmd {
+            name: CString,
+            color: [f32; 4],
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdBeginDebugUtilsLabelEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.debug_marker_begin(&self.name, self.color);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdBeginDebugUtilsLabelEXT")
+            }
+        }
+
+        self.append_command(Cmd {
+            name: name.to_owned(),
+            color: color,
+        });
+    }
+
+    /// Calls `vkCmdEndDebugUtilsLabelEXT` on the builder.
+    #[inline]
+    pub unsafe fn debug_label_end(&mut self) {
+        struct Cmd;
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdEndDebugUtilsLabelEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.debug_marker_end();
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdEndDebugUtilsLabelEXT")
+            }
+        }
+
+        self.append_command(Cmd);
+    }
+
+    /// Calls `vkCmdInsertDebugUtilsLabelEXT` on the builder.
+    #[inline]
+    pub unsafe fn debug_label_insert(&mut self, name: &CStr, color: [f32; 4]) {
+        struct Cmd {
+            name: CString,
+            color: [f32; 4],
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdInsertDebugUtilsLabelEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.debug_marker_insert(&self.name, self.color);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdInsertDebugUtilsLabelEXT")
+            }
+        }
+
+        self.append_command(Cmd {
+            name: name.to_owned(),
+            color: color,
+        });
+    }
+
     /// Calls `vkCmdDispatch` on the builder.
     #[inline]
     pub unsafe fn dispatch(&mut self, dimensions: [u32; 3]) {
@@ -1151,6 +1803,31 @@ impl<P> SyncCommandBufferBuilder<P> {
         self.append_command(Cmd { dimensions });
     }
 
+    /// Calls `vkCmdDispatchBase` on the builder.
+    #[inline]
+    pub unsafe fn dispatch_base(&mut self, base: [u32; 3], dimensions: [u32; 3]) {
+        struct Cmd {
+            base: [u32; 3],
+            dimensions: [u32; 3],
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdDispatchBase"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.dispatch_base(self.base, self.dimensions);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdDispatchBase")
+            }
+        }
+
+        self.append_command(Cmd { base, dimensions });
+    }
+
     /// Calls `vkCmdDispatchIndirect` on the builder.
     #[inline]
     pub unsafe fn dispatch_indirect<B>(
@@ -1360,50 +2037,376 @@ impl<P> SyncCommandBufferBuilder<P> {
                         &self.0
                     }
                     fn buffer_name(&self, num: usize) -> Cow<'static, str> {
-                        assert_eq!(num, 0);
-                        "indirect buffer".into()
+                        assert_eq!(num, 0);
+                        "indirect buffer".into()
+                    }
+                }
+                Box::new(Fin(self.buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                assert_eq!(num, 0);
+                &self.buffer
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                assert_eq!(num, 0);
+                "indirect buffer".into()
+            }
+        }
+
+        self.append_command(Cmd {
+            buffer,
+            draw_count,
+            stride,
+        });
+        self.prev_cmd_resource(
+            KeyTy::Buffer,
+            0,
+            false,
+            PipelineStages {
+                draw_indirect: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits {
+                indirect_command_read: true,
+                ..AccessFlagBits::none()
+            },
+            ImageLayout::Undefined,
+            ImageLayout::Undefined,
+        )?;
+        Ok(())
+    }
+
+    /// Calls `vkCmdDrawIndexedIndirect` on the builder.
+    #[inline]
+    pub unsafe fn draw_indexed_indirect<B>(
+        &mut self,
+        buffer: B,
+        draw_count: u32,
+        stride: u32,
+    ) -> Result<(), SyncCommandBufferBuilderError>
+    where
+        B: BufferAccess + Send + Sync + 'static,
+    {
+        struct Cmd<B> {
+            buffer: B,
+            draw_count: u32,
+            stride: u32,
+        }
+
+        impl<P, B> Command<P> for Cmd<B>
+        where
+            B: BufferAccess + Send + Sync + 'static,
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdDrawIndexedIndirect"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.draw_indexed_indirect(&self.buffer, self.draw_count, self.stride);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<B>(B);
+                impl<B> FinalCommand for Fin<B>
+                where
+                    B: BufferAccess + Send + Sync + 'static,
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdDrawIndexedIndirect"
+                    }
+                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        assert_eq!(num, 0);
+                        "indirect buffer".into()
+                    }
+                }
+                Box::new(Fin(self.buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                assert_eq!(num, 0);
+                &self.buffer
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                assert_eq!(num, 0);
+                "indirect buffer".into()
+            }
+        }
+
+        self.append_command(Cmd {
+            buffer,
+            draw_count,
+            stride,
+        });
+        self.prev_cmd_resource(
+            KeyTy::Buffer,
+            0,
+            false,
+            PipelineStages {
+                draw_indirect: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits {
+                indirect_command_read: true,
+                ..AccessFlagBits::none()
+            },
+            ImageLayout::Undefined,
+            ImageLayout::Undefined,
+        )?;
+        Ok(())
+    }
+
+    /// Calls `vkCmdDrawIndirectCountKHR` on the builder.
+    #[inline]
+    pub unsafe fn draw_indirect_count<B, C>(
+        &mut self,
+        buffer: B,
+        count_buffer: C,
+        max_draw_count: u32,
+        stride: u32,
+    ) -> Result<(), SyncCommandBufferBuilderError>
+    where
+        B: BufferAccess + Send + Sync + 'static,
+        C: BufferAccess + Send + Sync + 'static,
+    {
+        struct Cmd<B, C> {
+            buffer: B,
+            count_buffer: C,
+            max_draw_count: u32,
+            stride: u32,
+        }
+
+        impl<P, B, C> Command<P> for Cmd<B, C>
+        where
+            B: BufferAccess + Send + Sync + 'static,
+            C: BufferAccess + Send + Sync + 'static,
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdDrawIndirectCountKHR"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.draw_indirect_count(
+                    &self.buffer,
+                    &self.count_buffer,
+                    self.max_draw_count,
+                    self.stride,
+                );
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<B, C>(B, C);
+                impl<B, C> FinalCommand for Fin<B, C>
+                where
+                    B: BufferAccess + Send + Sync + 'static,
+                    C: BufferAccess + Send + Sync + 'static,
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdDrawIndirectCountKHR"
+                    }
+                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                        match num {
+                            0 => &self.0,
+                            1 => &self.1,
+                            _ => panic!(),
+                        }
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        match num {
+                            0 => "indirect buffer".into(),
+                            1 => "count buffer".into(),
+                            _ => panic!(),
+                        }
+                    }
+                }
+                Box::new(Fin(self.buffer, self.count_buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                match num {
+                    0 => &self.buffer,
+                    1 => &self.count_buffer,
+                    _ => panic!(),
+                }
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                match num {
+                    0 => "indirect buffer".into(),
+                    1 => "count buffer".into(),
+                    _ => panic!(),
+                }
+            }
+        }
+
+        self.append_command(Cmd {
+            buffer,
+            count_buffer,
+            max_draw_count,
+            stride,
+        });
+        for num in 0..2 {
+            self.prev_cmd_resource(
+                KeyTy::Buffer,
+                num,
+                false,
+                PipelineStages {
+                    draw_indirect: true,
+                    ..PipelineStages::none()
+                },
+                AccessFlagBits {
+                    indirect_command_read: true,
+                    ..AccessFlagBits::none()
+                },
+                ImageLayout::Undefined,
+                ImageLayout::Undefined,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Calls `vkCmdDrawIndexedIndirectCountKHR` on the builder.
+    #[inline]
+    pub unsafe fn draw_indexed_indirect_count<B, C>(
+        &mut self,
+        buffer: B,
+        count_buffer: C,
+        max_draw_count: u32,
+        stride: u32,
+    ) -> Result<(), SyncCommandBufferBuilderError>
+    where
+        B: BufferAccess + Send + Sync + 'static,
+        C: BufferAccess + Send + Sync + 'static,
+    {
+        struct Cmd<B, C> {
+            buffer: B,
+            count_buffer: C,
+            max_draw_count: u32,
+            stride: u32,
+        }
+
+        impl<P, B, C> Command<P> for Cmd<B, C>
+        where
+            B: BufferAccess + Send + Sync + 'static,
+            C: BufferAccess + Send + Sync + 'static,
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdDrawIndexedIndirectCountKHR"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.draw_indexed_indirect_count(
+                    &self.buffer,
+                    &self.count_buffer,
+                    self.max_draw_count,
+                    self.stride,
+                );
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<B, C>(B, C);
+                impl<B, C> FinalCommand for Fin<B, C>
+                where
+                    B: BufferAccess + Send + Sync + 'static,
+                    C: BufferAccess + Send + Sync + 'static,
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdDrawIndexedIndirectCountKHR"
+                    }
+                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                        match num {
+                            0 => &self.0,
+                            1 => &self.1,
+                            _ => panic!(),
+                        }
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        match num {
+                            0 => "indirect buffer".into(),
+                            1 => "count buffer".into(),
+                            _ => panic!(),
+                        }
                     }
                 }
-                Box::new(Fin(self.buffer))
+                Box::new(Fin(self.buffer, self.count_buffer))
             }
 
             fn buffer(&self, num: usize) -> &dyn BufferAccess {
-                assert_eq!(num, 0);
-                &self.buffer
+                match num {
+                    0 => &self.buffer,
+                    1 => &self.count_buffer,
+                    _ => panic!(),
+                }
             }
 
             fn buffer_name(&self, num: usize) -> Cow<'static, str> {
-                assert_eq!(num, 0);
-                "indirect buffer".into()
+                match num {
+                    0 => "indirect buffer".into(),
+                    1 => "count buffer".into(),
+                    _ => panic!(),
+                }
             }
         }
 
         self.append_command(Cmd {
             buffer,
-            draw_count,
+            count_buffer,
+            max_draw_count,
             stride,
         });
-        self.prev_cmd_resource(
-            KeyTy::Buffer,
-            0,
-            false,
-            PipelineStages {
-                draw_indirect: true,
-                ..PipelineStages::none()
-            },
-            AccessFlagBits {
-                indirect_command_read: true,
-                ..AccessFlagBits::none()
-            },
-            ImageLayout::Undefined,
-            ImageLayout::Undefined,
-        )?;
+        for num in 0..2 {
+            self.prev_cmd_resource(
+                KeyTy::Buffer,
+                num,
+                false,
+                PipelineStages {
+                    draw_indirect: true,
+                    ..PipelineStages::none()
+                },
+                AccessFlagBits {
+                    indirect_command_read: true,
+                    ..AccessFlagBits::none()
+                },
+                ImageLayout::Undefined,
+                ImageLayout::Undefined,
+            )?;
+        }
         Ok(())
     }
 
-    /// Calls `vkCmdDrawIndexedIndirect` on the builder.
+    /// Calls `vkCmdDrawMeshTasksEXT` on the builder.
     #[inline]
-    pub unsafe fn draw_indexed_indirect<B>(
+    pub unsafe fn draw_mesh_tasks(&mut self, group_count: [u32; 3]) {
+        struct Cmd {
+            group_count: [u32; 3],
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdDrawMeshTasksEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.draw_mesh_tasks(self.group_count);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdDrawMeshTasksEXT")
+            }
+        }
+
+        self.append_command(Cmd { group_count });
+    }
+
+    /// Calls `vkCmdDrawMeshTasksIndirectEXT` on the builder.
+    #[inline]
+    pub unsafe fn draw_mesh_tasks_indirect<B>(
         &mut self,
         buffer: B,
         draw_count: u32,
@@ -1423,11 +2426,11 @@ impl<P> SyncCommandBufferBuilder<P> {
             B: BufferAccess + Send + Sync + 'static,
         {
             fn name(&self) -> &'static str {
-                "vkCmdDrawIndexedIndirect"
+                "vkCmdDrawMeshTasksIndirectEXT"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.draw_indexed_indirect(&self.buffer, self.draw_count, self.stride);
+                out.draw_mesh_tasks_indirect(&self.buffer, self.draw_count, self.stride);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
@@ -1437,7 +2440,7 @@ impl<P> SyncCommandBufferBuilder<P> {
                     B: BufferAccess + Send + Sync + 'static,
                 {
                     fn name(&self) -> &'static str {
-                        "vkCmdDrawIndexedIndirect"
+                        "vkCmdDrawMeshTasksIndirectEXT"
                     }
                     fn buffer(&self, num: usize) -> &dyn BufferAccess {
                         assert_eq!(num, 0);
@@ -1508,6 +2511,37 @@ impl<P> SyncCommandBufferBuilder<P> {
         self.prev_cmd_left_render_pass();
     }
 
+    /// Calls `vkCmdEndQuery` on the builder.
+    #[inline]
+    pub unsafe fn end_query(&mut self, query_pool: Arc<QueryPool>, query: u32) {
+        struct Cmd {
+            query_pool: Arc<QueryPool>,
+            query: u32,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdEndQuery"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.end_query(self.query_pool.inner().query(self.query).unwrap());
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin(Arc<QueryPool>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdEndQuery"
+                    }
+                }
+                Box::new(Fin(self.query_pool))
+            }
+        }
+
+        self.append_command(Cmd { query_pool, query });
+    }
+
     /// Starts the process of executing secondary command buffers. Returns an intermediate struct
     /// which can be used to add the command buffers.
     #[inline]
@@ -1591,6 +2625,91 @@ impl<P> SyncCommandBufferBuilder<P> {
         .unwrap();
     }
 
+    /// Calls `vkCmdPipelineBarrier` on the builder with a global memory barrier.
+    ///
+    /// The barrier doesn't refer to any resource, and is therefore not taken into account when
+    /// determining the barriers that are automatically inserted between commands.
+    #[inline]
+    pub unsafe fn memory_barrier(
+        &mut self,
+        source_stage: PipelineStages,
+        source_access: AccessFlagBits,
+        destination_stage: PipelineStages,
+        destination_access: AccessFlagBits,
+        by_region: bool,
+    ) {
+        struct Cmd {
+            source_stage: PipelineStages,
+            source_access: AccessFlagBits,
+            destination_stage: PipelineStages,
+            destination_access: AccessFlagBits,
+            by_region: bool,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdPipelineBarrier"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+                barrier.add_memory_barrier(
+                    self.source_stage,
+                    self.source_access,
+                    self.destination_stage,
+                    self.destination_access,
+                    self.by_region,
+                );
+                out.pipeline_barrier(&barrier);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdPipelineBarrier")
+            }
+        }
+
+        self.append_command(Cmd {
+            source_stage,
+            source_access,
+            destination_stage,
+            destination_access,
+            by_region,
+        });
+    }
+
+    /// Calls `vkCmdPipelineBarrier` on the builder with an explicit pipeline barrier.
+    ///
+    /// As with `memory_barrier`, the barrier is not taken into account when determining the
+    /// barriers that are automatically inserted between commands.
+    #[inline]
+    pub unsafe fn pipeline_barrier(&mut self, barrier: PipelineBarrier) {
+        struct Cmd {
+            barrier: PipelineBarrier,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdPipelineBarrier"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.pipeline_barrier(&self.barrier.build());
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin(PipelineBarrier);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdPipelineBarrier"
+                    }
+                }
+                Box::new(Fin(self.barrier))
+            }
+        }
+
+        self.append_command(Cmd { barrier });
+    }
+
     /// Calls `vkCmdNextSubpass` on the builder.
     #[inline]
     pub unsafe fn next_subpass(&mut self, subpass_contents: SubpassContents) {
@@ -1603,88 +2722,354 @@ impl<P> SyncCommandBufferBuilder<P> {
                 "vkCmdNextSubpass"
             }
 
-            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.next_subpass(self.subpass_contents);
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.next_subpass(self.subpass_contents);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdNextSubpass")
+            }
+        }
+
+        self.append_command(Cmd { subpass_contents });
+    }
+
+    /// Calls `vkCmdPushConstants` on the builder.
+    #[inline]
+    pub unsafe fn push_constants<Pl, D>(
+        &mut self,
+        pipeline_layout: Pl,
+        stages: ShaderStages,
+        offset: u32,
+        size: u32,
+        data: &D,
+    ) where
+        Pl: PipelineLayoutAbstract + Send + Sync + 'static,
+        D: ?Sized + Send + Sync + 'static,
+    {
+        struct Cmd<Pl> {
+            pipeline_layout: Pl,
+            stages: ShaderStages,
+            offset: u32,
+            size: u32,
+            data: Box<[u8]>,
+        }
+
+        impl<P, Pl> Command<P> for Cmd<Pl>
+        where
+            Pl: PipelineLayoutAbstract + Send + Sync + 'static,
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdPushConstants"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.push_constants::<_, [u8]>(
+                    &self.pipeline_layout,
+                    self.stages,
+                    self.offset,
+                    self.size,
+                    &self.data,
+                );
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<Pl>(Pl);
+                impl<Pl> FinalCommand for Fin<Pl>
+                where
+                    Pl: Send + Sync + 'static,
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdPushConstants"
+                    }
+                }
+                Box::new(Fin(self.pipeline_layout))
+            }
+        }
+
+        debug_assert!(mem::size_of_val(data) >= size as usize);
+
+        let mut out = Vec::with_capacity(size as usize);
+        ptr::copy::<u8>(
+            data as *const D as *const u8,
+            out.as_mut_ptr(),
+            size as usize,
+        );
+        out.set_len(size as usize);
+
+        self.append_command(Cmd {
+            pipeline_layout,
+            stages,
+            offset,
+            size,
+            data: out.into(),
+        });
+    }
+
+    /// Calls `vkCmdPipelineBarrier` on the builder with a buffer memory barrier that transfers the
+    /// ownership of `buffer` from the queue family `source` to the queue family `destination`.
+    ///
+    /// If `release` is true, this is the release half of the transfer and must be recorded in a
+    /// command buffer of the source queue family. Otherwise this is the acquire half and must be
+    /// recorded in a command buffer of the destination queue family. The transfer is recorded in
+    /// the buffer when the command buffer is submitted.
+    #[inline]
+    pub unsafe fn queue_family_transfer_buffer<B>(
+        &mut self,
+        buffer: B,
+        source: u32,
+        destination: u32,
+        release: bool,
+    ) -> Result<(), SyncCommandBufferBuilderError>
+    where
+        B: BufferAccess + Send + Sync + 'static,
+    {
+        struct Cmd<B> {
+            buffer: B,
+            source: u32,
+            destination: u32,
+            release: bool,
+        }
+
+        impl<P, B> Command<P> for Cmd<B>
+        where
+            B: BufferAccess + Send + Sync + 'static,
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdPipelineBarrier"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                let (stage, access) = queue_family_transfer_access();
+
+                // The other half of the barrier is ignored by Vulkan, since its access happens on
+                // the other queue family.
+                let (src_stage, src_access, dst_stage, dst_access) = if self.release {
+                    let other_stage = PipelineStages {
+                        bottom_of_pipe: true,
+                        ..PipelineStages::none()
+                    };
+                    (stage, access, other_stage, AccessFlagBits::none())
+                } else {
+                    let other_stage = PipelineStages {
+                        top_of_pipe: true,
+                        ..PipelineStages::none()
+                    };
+                    (other_stage, AccessFlagBits::none(), stage, access)
+                };
+
+                let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+                barrier.add_buffer_memory_barrier(
+                    &self.buffer,
+                    src_stage,
+                    src_access,
+                    dst_stage,
+                    dst_access,
+                    false,
+                    Some((self.source, self.destination)),
+                    0,
+                    self.buffer.size(),
+                );
+                out.pipeline_barrier(&barrier);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<B>(B);
+                impl<B> FinalCommand for Fin<B>
+                where
+                    B: BufferAccess + Send + Sync + 'static,
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdPipelineBarrier"
+                    }
+                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                        "buffer".into()
+                    }
+                }
+                Box::new(Fin(self.buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                assert_eq!(num, 0);
+                &self.buffer
             }
 
-            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
-                Box::new("vkCmdNextSubpass")
+            fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                "buffer".into()
             }
         }
 
-        self.append_command(Cmd { subpass_contents });
+        self.append_command(Cmd {
+            buffer,
+            source,
+            destination,
+            release,
+        });
+        let transfer = if release {
+            QueueFamilyTransfer::Release {
+                source,
+                destination,
+                old_layout: ImageLayout::Undefined,
+                new_layout: ImageLayout::Undefined,
+            }
+        } else {
+            QueueFamilyTransfer::Acquire
+        };
+        let (stage, access) = queue_family_transfer_access();
+        self.prev_cmd_resource_transfer(
+            KeyTy::Buffer,
+            0,
+            true,
+            stage,
+            access,
+            ImageLayout::Undefined,
+            ImageLayout::Undefined,
+            Some(transfer),
+        )?;
+        Ok(())
     }
 
-    /// Calls `vkCmdPushConstants` on the builder.
+    /// Calls `vkCmdPipelineBarrier` on the builder with an image memory barrier that transfers the
+    /// ownership of `image` from the queue family `source` to the queue family `destination`, and
+    /// transitions it from `old_layout` to `new_layout`.
+    ///
+    /// Same as `queue_family_transfer_buffer`, except that the release and the acquire must use
+    /// the same layouts. Using the image with any other command between the release and the
+    /// acquire returns an error.
     #[inline]
-    pub unsafe fn push_constants<Pl, D>(
+    pub unsafe fn queue_family_transfer_image<I>(
         &mut self,
-        pipeline_layout: Pl,
-        stages: ShaderStages,
-        offset: u32,
-        size: u32,
-        data: &D,
-    ) where
-        Pl: PipelineLayoutAbstract + Send + Sync + 'static,
-        D: ?Sized + Send + Sync + 'static,
+        image: I,
+        source: u32,
+        destination: u32,
+        old_layout: ImageLayout,
+        new_layout: ImageLayout,
+        release: bool,
+    ) -> Result<(), SyncCommandBufferBuilderError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
     {
-        struct Cmd<Pl> {
-            pipeline_layout: Pl,
-            stages: ShaderStages,
-            offset: u32,
-            size: u32,
-            data: Box<[u8]>,
+        struct Cmd<I> {
+            image: I,
+            source: u32,
+            destination: u32,
+            old_layout: ImageLayout,
+            new_layout: ImageLayout,
+            release: bool,
         }
 
-        impl<P, Pl> Command<P> for Cmd<Pl>
+        impl<P, I> Command<P> for Cmd<I>
         where
-            Pl: PipelineLayoutAbstract + Send + Sync + 'static,
+            I: ImageAccess + Send + Sync + 'static,
         {
             fn name(&self) -> &'static str {
-                "vkCmdPushConstants"
+                "vkCmdPipelineBarrier"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.push_constants::<_, [u8]>(
-                    &self.pipeline_layout,
-                    self.stages,
-                    self.offset,
-                    self.size,
-                    &self.data,
+                let (stage, access) = queue_family_transfer_access();
+
+                // The other half of the barrier is ignored by Vulkan, since its access happens on
+                // the other queue family.
+                let (src_stage, src_access, dst_stage, dst_access) = if self.release {
+                    let other_stage = PipelineStages {
+                        bottom_of_pipe: true,
+                        ..PipelineStages::none()
+                    };
+                    (stage, access, other_stage, AccessFlagBits::none())
+                } else {
+                    let other_stage = PipelineStages {
+                        top_of_pipe: true,
+                        ..PipelineStages::none()
+                    };
+                    (other_stage, AccessFlagBits::none(), stage, access)
+                };
+
+                let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+                barrier.add_image_memory_barrier(
+                    &self.image,
+                    0..self.image.mipmap_levels(),
+                    0..self.image.dimensions().array_layers(),
+                    src_stage,
+                    src_access,
+                    dst_stage,
+                    dst_access,
+                    false,
+                    Some((self.source, self.destination)),
+                    self.old_layout,
+                    self.new_layout,
                 );
+                out.pipeline_barrier(&barrier);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
-                struct Fin<Pl>(Pl);
-                impl<Pl> FinalCommand for Fin<Pl>
+                struct Fin<I>(I);
+                impl<I> FinalCommand for Fin<I>
                 where
-                    Pl: Send + Sync + 'static,
+                    I: ImageAccess + Send + Sync + 'static,
                 {
                     fn name(&self) -> &'static str {
-                        "vkCmdPushConstants"
+                        "vkCmdPipelineBarrier"
+                    }
+                    fn image(&self, num: usize) -> &dyn ImageAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn image_name(&self, _: usize) -> Cow<'static, str> {
+                        "image".into()
                     }
                 }
-                Box::new(Fin(self.pipeline_layout))
+                Box::new(Fin(self.image))
             }
-        }
 
-        debug_assert!(mem::size_of_val(data) >= size as usize);
+            fn image(&self, num: usize) -> &dyn ImageAccess {
+                assert_eq!(num, 0);
+                &self.image
+            }
 
-        let mut out = Vec::with_capacity(size as usize);
-        ptr::copy::<u8>(
-            data as *const D as *const u8,
-            out.as_mut_ptr(),
-            size as usize,
-        );
-        out.set_len(size as usize);
+            fn image_name(&self, _: usize) -> Cow<'static, str> {
+                "image".into()
+            }
+        }
 
         self.append_command(Cmd {
-            pipeline_layout,
-            stages,
-            offset,
-            size,
-            data: out.into(),
+            image,
+            source,
+            destination,
+            old_layout,
+            new_layout,
+            release,
         });
+
+        // On the acquire side, the layout transition happens before the image is available to
+        // the command buffer.
+        let start_layout = if release { old_layout } else { new_layout };
+        let transfer = if release {
+            QueueFamilyTransfer::Release {
+                source,
+                destination,
+                old_layout,
+                new_layout,
+            }
+        } else {
+            QueueFamilyTransfer::Acquire
+        };
+        let (stage, access) = queue_family_transfer_access();
+        self.prev_cmd_resource_transfer(
+            KeyTy::Image,
+            0,
+            true,
+            stage,
+            access,
+            start_layout,
+            new_layout,
+            Some(transfer),
+        )?;
+        Ok(())
     }
 
     /// Calls `vkCmdResetEvent` on the builder.
@@ -1718,6 +3103,45 @@ impl<P> SyncCommandBufferBuilder<P> {
         self.append_command(Cmd { event, stages });
     }
 
+    /// Calls `vkCmdResetQueryPool` on the builder.
+    #[inline]
+    pub unsafe fn reset_query_pool(&mut self, query_pool: Arc<QueryPool>, queries: Range<u32>) {
+        struct Cmd {
+            query_pool: Arc<QueryPool>,
+            queries: Range<u32>,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdResetQueryPool"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.reset_query_pool(
+                    self.query_pool
+                        .inner()
+                        .queries_range(self.queries.start, self.queries.end - self.queries.start)
+                        .unwrap(),
+                );
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin(Arc<QueryPool>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdResetQueryPool"
+                    }
+                }
+                Box::new(Fin(self.query_pool))
+            }
+        }
+
+        self.append_command(Cmd {
+            query_pool,
+            queries,
+        });
+    }
+
     /// Calls `vkCmdSetBlendConstants` on the builder.
     #[inline]
     pub unsafe fn set_blend_constants(&mut self, constants: [f32; 4]) {
@@ -2008,6 +3432,90 @@ impl<P> SyncCommandBufferBuilder<P> {
         });
     }
 
+    /// Calls `vkCmdTraceRaysNV` on the builder.
+    #[inline]
+    pub unsafe fn trace_rays<B>(
+        &mut self,
+        buffer: B,
+        regions: ShaderBindingTableRegions,
+        dimensions: [u32; 3],
+    ) -> Result<(), SyncCommandBufferBuilderError>
+    where
+        B: BufferAccess + Send + Sync + 'static,
+    {
+        struct Cmd<B> {
+            buffer: B,
+            regions: ShaderBindingTableRegions,
+            dimensions: [u32; 3],
+        }
+
+        impl<P, B> Command<P> for Cmd<B>
+        where
+            B: BufferAccess + Send + Sync + 'static,
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdTraceRaysNV"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.trace_rays(&self.buffer, self.regions, self.dimensions);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<B>(B);
+                impl<B> FinalCommand for Fin<B>
+                where
+                    B: BufferAccess + Send + Sync + 'static,
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdTraceRaysNV"
+                    }
+                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        assert_eq!(num, 0);
+                        "shader binding table".into()
+                    }
+                }
+                Box::new(Fin(self.buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                assert_eq!(num, 0);
+                &self.buffer
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                assert_eq!(num, 0);
+                "shader binding table".into()
+            }
+        }
+
+        self.append_command(Cmd {
+            buffer,
+            regions,
+            dimensions,
+        });
+        self.prev_cmd_resource(
+            KeyTy::Buffer,
+            0,
+            false,
+            PipelineStages {
+                ray_tracing_shader: true,
+                ..PipelineStages::none()
+            },
+            AccessFlagBits {
+                shader_read: true,
+                ..AccessFlagBits::none()
+            },
+            ImageLayout::Undefined,
+            ImageLayout::Undefined,
+        )?;
+        Ok(())
+    }
+
     /// Calls `vkCmdUpdateBuffer` on the builder.
     #[inline]
     pub unsafe fn update_buffer<B, D>(&mut self, buffer: B, data: D)
@@ -2081,6 +3589,124 @@ impl<P> SyncCommandBufferBuilder<P> {
         )
         .unwrap();
     }
+
+    /// Calls `vkCmdWaitEvents` on the builder, with a global memory barrier.
+    #[inline]
+    pub unsafe fn wait_events(
+        &mut self,
+        events: Vec<Arc<Event>>,
+        source_stage: PipelineStages,
+        source_access: AccessFlagBits,
+        destination_stage: PipelineStages,
+        destination_access: AccessFlagBits,
+    ) {
+        struct Cmd {
+            events: Vec<Arc<Event>>,
+            source_stage: PipelineStages,
+            source_access: AccessFlagBits,
+            destination_stage: PipelineStages,
+            destination_access: AccessFlagBits,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdWaitEvents"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+                barrier.add_memory_barrier(
+                    self.source_stage,
+                    self.source_access,
+                    self.destination_stage,
+                    self.destination_access,
+                    false,
+                );
+                out.wait_events(self.events.iter().map(|e| &**e), &barrier);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin(Vec<Arc<Event>>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdWaitEvents"
+                    }
+                }
+                Box::new(Fin(self.events))
+            }
+        }
+
+        self.add_waited_events(&events);
+        self.append_command(Cmd {
+            events,
+            source_stage,
+            source_access,
+            destination_stage,
+            destination_access,
+        });
+    }
+
+    /// Calls `vkCmdWriteTimestamp` on the builder.
+    #[inline]
+    pub unsafe fn write_timestamp(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        query: u32,
+        stages: PipelineStages,
+    ) {
+        struct Cmd {
+            query_pool: Arc<QueryPool>,
+            query: u32,
+            stages: PipelineStages,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdWriteTimestamp"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.write_timestamp(
+                    self.query_pool.inner().query(self.query).unwrap(),
+                    self.stages,
+                );
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin(Arc<QueryPool>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdWriteTimestamp"
+                    }
+                }
+                Box::new(Fin(self.query_pool))
+            }
+        }
+
+        self.append_command(Cmd {
+            query_pool,
+            query,
+            stages,
+        });
+    }
+}
+
+// Stages and accesses used on the side of the current queue family of an ownership transfer.
+// Since we don't know how the buffer is used by the other commands of the queue, we synchronize
+// with everything.
+#[inline]
+fn queue_family_transfer_access() -> (PipelineStages, AccessFlagBits) {
+    (
+        PipelineStages {
+            all_commands: true,
+            ..PipelineStages::none()
+        },
+        AccessFlagBits {
+            memory_read: true,
+            memory_write: true,
+            ..AccessFlagBits::none()
+        },
+    )
 }
 
 pub struct SyncCommandBufferBuilderBindDescriptorSets<'b, P: 'b> {
@@ -2101,7 +3727,7 @@ impl<'b, P> SyncCommandBufferBuilderBindDescriptorSets<'b, P> {
     #[inline]
     pub unsafe fn submit<Pl, I>(
         self,
-        graphics: bool,
+        pipeline_bind_point: PipelineBindPoint,
         pipeline_layout: Pl,
         first_binding: u32,
         dynamic_offsets: I,
@@ -2116,7 +3742,7 @@ impl<'b, P> SyncCommandBufferBuilderBindDescriptorSets<'b, P> {
 
         struct Cmd<Pl, I> {
             inner: SmallVec<[Box<dyn DescriptorSet + Send + Sync>; 12]>,
-            graphics: bool,
+            pipeline_bind_point: PipelineBindPoint,
             pipeline_layout: Pl,
             first_binding: u32,
             dynamic_offsets: Option<I>,
@@ -2133,7 +3759,7 @@ impl<'b, P> SyncCommandBufferBuilderBindDescriptorSets<'b, P> {
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
                 out.bind_descriptor_sets(
-                    self.graphics,
+                    self.pipeline_bind_point,
                     &self.pipeline_layout,
                     self.first_binding,
                     self.inner.iter().map(|s| s.inner()),
@@ -2292,7 +3918,7 @@ impl<'b, P> SyncCommandBufferBuilderBindDescriptorSets<'b, P> {
 
         self.builder.append_command(Cmd {
             inner: self.inner,
-            graphics,
+            pipeline_bind_point,
             pipeline_layout,
             first_binding,
             dynamic_offsets: Some(dynamic_offsets),
@@ -2437,6 +4063,8 @@ impl<'a, P> SyncCommandBufferBuilderExecuteCommands<'a, P> {
         C: CommandBuffer + Send + Sync + 'static,
     {
         self.inner.add(&command_buffer);
+        self.builder
+            .add_waited_events(command_buffer.waited_events());
         self.command_buffers
             .push(Box::new(command_buffer) as Box<_>);
     }
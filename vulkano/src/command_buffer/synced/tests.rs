@@ -8,14 +8,24 @@
 // according to those terms.
 
 use std::iter;
+use std::sync::Arc;
 
+use buffer::BufferAccess;
 use buffer::BufferUsage;
 use buffer::CpuAccessibleBuffer;
+use buffer::DeviceLocalBuffer;
 use command_buffer::synced::base::SyncCommandBufferBuilder;
 use command_buffer::synced::base::SyncCommandBufferBuilderError;
 use command_buffer::sys::Flags;
 use command_buffer::sys::Kind;
+use command_buffer::sys::UnsafeCommandBufferBuilderColorImageClear;
 use device::Device;
+use format::ClearValue;
+use format::Format;
+use image::Dimensions;
+use image::ImageAccess;
+use image::ImageLayout;
+use image::StorageImage;
 
 #[test]
 fn basic_creation() {
@@ -42,3 +52,200 @@ fn basic_conflict() {
         };
     }
 }
+
+#[test]
+fn copy_within_same_buffer() {
+    unsafe {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = Device::standard_command_pool(&device, queue.family());
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+
+        let buf =
+            CpuAccessibleBuffer::from_data(device, BufferUsage::all(), false, [0u32; 4]).unwrap();
+
+        sync.copy_buffer(buf.clone(), buf.clone(), iter::once((0, 8, 8)))
+            .unwrap();
+        sync.build().unwrap();
+    }
+}
+
+#[test]
+fn resources_kept_alive() {
+    unsafe {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = Device::standard_command_pool(&device, queue.family());
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+
+        let buf = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), false, 0u32).unwrap();
+        let weak = Arc::downgrade(&buf);
+        sync.fill_buffer(buf, 0);
+        assert!(weak.upgrade().is_some());
+
+        let cb = sync.build().unwrap();
+        assert!(weak.upgrade().is_some());
+
+        drop(cb);
+        assert!(weak.upgrade().is_none());
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn uninitialized_buffer_read() {
+    unsafe {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = Device::standard_command_pool(&device, queue.family());
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+
+        let src = DeviceLocalBuffer::<u32>::new(
+            device.clone(),
+            BufferUsage::all(),
+            iter::once(queue.family()),
+        )
+        .unwrap();
+        let dst = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), false, 0u32).unwrap();
+        assert!(!src.is_initialized());
+
+        match sync.copy_buffer(src.clone(), dst.clone(), iter::once((0, 0, 4))) {
+            Err(SyncCommandBufferBuilderError::UninitializedBuffer { .. }) => {}
+            _ => panic!(),
+        };
+
+        // Reading after a write recorded in the same command buffer is allowed, but the buffer is
+        // only marked as initialized once the command buffer is submitted.
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+        sync.fill_buffer(src.clone(), 0);
+        sync.copy_buffer(src.clone(), dst.clone(), iter::once((0, 0, 4)))
+            .unwrap();
+        let _ = sync.build().unwrap();
+        assert!(!src.is_initialized());
+
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+        match sync.copy_buffer(src, dst, iter::once((0, 0, 4))) {
+            Err(SyncCommandBufferBuilderError::UninitializedBuffer { .. }) => {}
+            _ => panic!(),
+        };
+    }
+}
+
+#[test]
+fn image_used_before_acquire() {
+    unsafe {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = Device::standard_command_pool(&device, queue.family());
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+
+        let image = StorageImage::new(
+            device,
+            Dimensions::Dim2d {
+                width: 32,
+                height: 32,
+            },
+            Format::R8G8B8A8Unorm,
+            Some(queue.family()),
+        )
+        .unwrap();
+
+        let family = queue.family().id();
+        sync.queue_family_transfer_image(
+            image.clone(),
+            family,
+            family + 1,
+            ImageLayout::General,
+            ImageLayout::General,
+            true,
+        )
+        .unwrap();
+
+        // The release is only applied to the image when the command buffer is submitted.
+        assert!(ImageAccess::inner(&image)
+            .image
+            .pending_acquire_layouts()
+            .is_none());
+
+        let region = UnsafeCommandBufferBuilderColorImageClear {
+            base_mip_level: 0,
+            level_count: 1,
+            base_array_layer: 0,
+            layer_count: 1,
+        };
+        match sync.clear_color_image(
+            image,
+            ImageLayout::General,
+            ClearValue::Float([0.0; 4]),
+            iter::once(region),
+        ) {
+            Err(SyncCommandBufferBuilderError::ImageNotAcquired { .. }) => {}
+            _ => panic!(),
+        };
+    }
+}
+
+#[test]
+fn image_used_after_acquire() {
+    unsafe {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = Device::standard_command_pool(&device, queue.family());
+
+        let image = StorageImage::new(
+            device,
+            Dimensions::Dim2d {
+                width: 32,
+                height: 32,
+            },
+            Format::R8G8B8A8Unorm,
+            Some(queue.family()),
+        )
+        .unwrap();
+
+        // Pretend that a command buffer releasing the image to this queue family was submitted.
+        let family = queue.family().id();
+        ImageAccess::inner(&image).image.set_queue_family_released(
+            family + 1,
+            family,
+            ImageLayout::General,
+            ImageLayout::General,
+        );
+
+        let region = UnsafeCommandBufferBuilderColorImageClear {
+            base_mip_level: 0,
+            level_count: 1,
+            base_array_layer: 0,
+            layer_count: 1,
+        };
+
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+        match sync.clear_color_image(
+            image.clone(),
+            ImageLayout::General,
+            ClearValue::Float([0.0; 4]),
+            iter::once(region),
+        ) {
+            Err(SyncCommandBufferBuilderError::ImageNotAcquired { .. }) => {}
+            _ => panic!(),
+        };
+
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+        sync.queue_family_transfer_image(
+            image.clone(),
+            family + 1,
+            family,
+            ImageLayout::General,
+            ImageLayout::General,
+            false,
+        )
+        .unwrap();
+        sync.clear_color_image(
+            image,
+            ImageLayout::General,
+            ClearValue::Float([0.0; 4]),
+            iter::once(region),
+        )
+        .unwrap();
+    }
+}
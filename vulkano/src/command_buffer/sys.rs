@@ -8,6 +8,7 @@
 // according to those terms.
 
 use smallvec::SmallVec;
+use std::cmp;
 use std::fmt;
 use std::mem;
 use std::ops::Range;
@@ -37,6 +38,7 @@ use framebuffer::RenderPassAbstract;
 use framebuffer::Subpass;
 use framebuffer::SubpassContents;
 use image::ImageAccess;
+use image::ImageAspect;
 use image::ImageLayout;
 use instance::QueueFamily;
 use pipeline::depth_stencil::StencilFaceFlags;
@@ -45,6 +47,10 @@ use pipeline::viewport::Scissor;
 use pipeline::viewport::Viewport;
 use pipeline::ComputePipelineAbstract;
 use pipeline::GraphicsPipelineAbstract;
+use pipeline::PipelineBindPoint;
+use pipeline::RayTracingPipelineAbstract;
+use pipeline::ShaderBindingTableRegion;
+use pipeline::ShaderBindingTableRegions;
 use query::QueryPipelineStatisticFlags;
 use query::UnsafeQueriesRange;
 use query::UnsafeQuery;
@@ -53,6 +59,7 @@ use std::ffi::CStr;
 use sync::AccessFlagBits;
 use sync::Event;
 use sync::PipelineStages;
+use version::Version;
 use vk;
 use OomError;
 use VulkanObject;
@@ -478,7 +485,7 @@ impl<P> UnsafeCommandBufferBuilder<P> {
     #[inline]
     pub unsafe fn bind_descriptor_sets<'s, Pl, S, I>(
         &mut self,
-        graphics: bool,
+        pipeline_bind_point: PipelineBindPoint,
         pipeline_layout: &Pl,
         first_binding: u32,
         sets: S,
@@ -500,15 +507,9 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         let num_bindings = sets.len() as u32;
         debug_assert!(first_binding + num_bindings <= pipeline_layout.num_sets() as u32);
 
-        let bind_point = if graphics {
-            vk::PIPELINE_BIND_POINT_GRAPHICS
-        } else {
-            vk::PIPELINE_BIND_POINT_COMPUTE
-        };
-
         vk.CmdBindDescriptorSets(
             cmd,
-            bind_point,
+            pipeline_bind_point as u32,
             pipeline_layout.sys().internal_object(),
             first_binding,
             num_bindings,
@@ -566,6 +567,18 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         vk.CmdBindPipeline(cmd, vk::PIPELINE_BIND_POINT_GRAPHICS, inner);
     }
 
+    /// Calls `vkCmdBindPipeline` on the builder with a ray tracing pipeline.
+    #[inline]
+    pub unsafe fn bind_pipeline_ray_tracing<Rp>(&mut self, pipeline: &Rp)
+    where
+        Rp: ?Sized + RayTracingPipelineAbstract,
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        let inner = RayTracingPipelineAbstract::inner(pipeline).internal_object();
+        vk.CmdBindPipeline(cmd, vk::PIPELINE_BIND_POINT_RAY_TRACING_NV, inner);
+    }
+
     /// Calls `vkCmdBindVertexBuffers` on the builder.
     ///
     /// Does nothing if the list of buffers is empty, as it would be a no-op and isn't a valid
@@ -661,7 +674,7 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                     copy.destination_base_array_layer + copy.layer_count
                         <= destination.num_layers as u32
                 );
-                debug_assert!(copy.source_mip_level < destination.num_mipmap_levels as u32);
+                debug_assert!(copy.source_mip_level < source.num_mipmap_levels as u32);
                 debug_assert!(copy.destination_mip_level < destination.num_mipmap_levels as u32);
 
                 if copy.layer_count == 0 {
@@ -671,7 +684,7 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                 Some(vk::ImageCopy {
                     srcSubresource: vk::ImageSubresourceLayers {
                         aspectMask: copy.aspect.to_vk_bits(),
-                        mipLevel: copy.source_mip_level,
+                        mipLevel: copy.source_mip_level + source.first_mipmap_level as u32,
                         baseArrayLayer: copy.source_base_array_layer + source.first_layer as u32,
                         layerCount: copy.layer_count,
                     },
@@ -682,7 +695,8 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                     },
                     dstSubresource: vk::ImageSubresourceLayers {
                         aspectMask: copy.aspect.to_vk_bits(),
-                        mipLevel: copy.destination_mip_level,
+                        mipLevel: copy.destination_mip_level
+                            + destination.first_mipmap_level as u32,
                         baseArrayLayer: copy.destination_base_array_layer
                             + destination.first_layer as u32,
                         layerCount: copy.layer_count,
@@ -778,7 +792,7 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                     blit.destination_base_array_layer + blit.layer_count
                         <= destination.num_layers as u32
                 );
-                debug_assert!(blit.source_mip_level < destination.num_mipmap_levels as u32);
+                debug_assert!(blit.source_mip_level < source.num_mipmap_levels as u32);
                 debug_assert!(blit.destination_mip_level < destination.num_mipmap_levels as u32);
 
                 if blit.layer_count == 0 {
@@ -788,7 +802,7 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                 Some(vk::ImageBlit {
                     srcSubresource: vk::ImageSubresourceLayers {
                         aspectMask: blit.aspect.to_vk_bits(),
-                        mipLevel: blit.source_mip_level,
+                        mipLevel: blit.source_mip_level + source.first_mipmap_level as u32,
                         baseArrayLayer: blit.source_base_array_layer + source.first_layer as u32,
                         layerCount: blit.layer_count,
                     },
@@ -806,7 +820,8 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                     ],
                     dstSubresource: vk::ImageSubresourceLayers {
                         aspectMask: blit.aspect.to_vk_bits(),
-                        mipLevel: blit.destination_mip_level,
+                        mipLevel: blit.destination_mip_level
+                            + destination.first_mipmap_level as u32,
                         baseArrayLayer: blit.destination_base_array_layer
                             + destination.first_layer as u32,
                         layerCount: blit.layer_count,
@@ -845,6 +860,109 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         );
     }
 
+    /// Calls `vkCmdResolveImage` on the builder.
+    ///
+    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
+    /// usage of the command anyway.
+    #[inline]
+    pub unsafe fn resolve_image<S, D, R>(
+        &mut self,
+        source: &S,
+        source_layout: ImageLayout,
+        destination: &D,
+        destination_layout: ImageLayout,
+        regions: R,
+    ) where
+        S: ?Sized + ImageAccess,
+        D: ?Sized + ImageAccess,
+        R: Iterator<Item = UnsafeCommandBufferBuilderImageResolve>,
+    {
+        debug_assert_eq!(source.format(), destination.format());
+        debug_assert!(source.samples() > 1);
+        debug_assert_eq!(destination.samples(), 1);
+
+        let source = source.inner();
+        debug_assert!(source.image.usage_transfer_source());
+        debug_assert!(
+            source_layout == ImageLayout::General
+                || source_layout == ImageLayout::TransferSrcOptimal
+        );
+
+        let destination = destination.inner();
+        debug_assert!(destination.image.usage_transfer_destination());
+        debug_assert!(
+            destination_layout == ImageLayout::General
+                || destination_layout == ImageLayout::TransferDstOptimal
+        );
+
+        let regions: SmallVec<[_; 8]> = regions
+            .filter_map(|resolve| {
+                debug_assert!(
+                    resolve.source_base_array_layer + resolve.layer_count
+                        <= source.num_layers as u32
+                );
+                debug_assert!(
+                    resolve.destination_base_array_layer + resolve.layer_count
+                        <= destination.num_layers as u32
+                );
+                debug_assert!(resolve.source_mip_level < source.num_mipmap_levels as u32);
+                debug_assert!(resolve.destination_mip_level < destination.num_mipmap_levels as u32);
+
+                if resolve.layer_count == 0 {
+                    return None;
+                }
+
+                Some(vk::ImageResolve {
+                    srcSubresource: vk::ImageSubresourceLayers {
+                        aspectMask: vk::IMAGE_ASPECT_COLOR_BIT,
+                        mipLevel: resolve.source_mip_level + source.first_mipmap_level as u32,
+                        baseArrayLayer: resolve.source_base_array_layer + source.first_layer as u32,
+                        layerCount: resolve.layer_count,
+                    },
+                    srcOffset: vk::Offset3D {
+                        x: resolve.source_offset[0],
+                        y: resolve.source_offset[1],
+                        z: resolve.source_offset[2],
+                    },
+                    dstSubresource: vk::ImageSubresourceLayers {
+                        aspectMask: vk::IMAGE_ASPECT_COLOR_BIT,
+                        mipLevel: resolve.destination_mip_level
+                            + destination.first_mipmap_level as u32,
+                        baseArrayLayer: resolve.destination_base_array_layer
+                            + destination.first_layer as u32,
+                        layerCount: resolve.layer_count,
+                    },
+                    dstOffset: vk::Offset3D {
+                        x: resolve.destination_offset[0],
+                        y: resolve.destination_offset[1],
+                        z: resolve.destination_offset[2],
+                    },
+                    extent: vk::Extent3D {
+                        width: resolve.extent[0],
+                        height: resolve.extent[1],
+                        depth: resolve.extent[2],
+                    },
+                })
+            })
+            .collect();
+
+        if regions.is_empty() {
+            return;
+        }
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdResolveImage(
+            cmd,
+            source.image.internal_object(),
+            source_layout as u32,
+            destination.image.internal_object(),
+            destination_layout as u32,
+            regions.len() as u32,
+            regions.as_ptr(),
+        );
+    }
+
     // TODO: missing structs
     /*/// Calls `vkCmdClearAttachments` on the builder.
     ///
@@ -939,6 +1057,85 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         );
     }
 
+    /// Calls `vkCmdClearDepthStencilImage` on the builder.
+    ///
+    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
+    /// usage of the command anyway.
+    pub unsafe fn clear_depth_stencil_image<I, R>(
+        &mut self,
+        image: &I,
+        layout: ImageLayout,
+        clear_value: ClearValue,
+        regions: R,
+    ) where
+        I: ?Sized + ImageAccess,
+        R: Iterator<Item = UnsafeCommandBufferBuilderDepthStencilImageClear>,
+    {
+        debug_assert!(image.format().ty().is_depth_and_or_stencil());
+
+        let image = image.inner();
+        debug_assert!(image.image.usage_transfer_destination());
+        debug_assert!(layout == ImageLayout::General || layout == ImageLayout::TransferDstOptimal);
+
+        let clear_value = match clear_value {
+            ClearValue::Depth(val) => vk::ClearDepthStencilValue {
+                depth: val,
+                stencil: 0,
+            },
+            ClearValue::Stencil(val) => vk::ClearDepthStencilValue {
+                depth: 0.0,
+                stencil: val,
+            },
+            ClearValue::DepthStencil((depth, stencil)) => vk::ClearDepthStencilValue {
+                depth: depth,
+                stencil: stencil,
+            },
+            _ => vk::ClearDepthStencilValue {
+                depth: 0.0,
+                stencil: 0,
+            },
+        };
+
+        let regions: SmallVec<[_; 8]> = regions
+            .filter_map(|region| {
+                debug_assert!(
+                    region.layer_count + region.base_array_layer <= image.num_layers as u32
+                );
+                debug_assert!(
+                    region.level_count + region.base_mip_level <= image.num_mipmap_levels as u32
+                );
+                debug_assert!(!region.aspect.color);
+
+                if region.layer_count == 0 || region.level_count == 0 {
+                    return None;
+                }
+
+                Some(vk::ImageSubresourceRange {
+                    aspectMask: region.aspect.to_vk_bits(),
+                    baseMipLevel: region.base_mip_level + image.first_mipmap_level as u32,
+                    levelCount: region.level_count,
+                    baseArrayLayer: region.base_array_layer + image.first_layer as u32,
+                    layerCount: region.layer_count,
+                })
+            })
+            .collect();
+
+        if regions.is_empty() {
+            return;
+        }
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdClearDepthStencilImage(
+            cmd,
+            image.image.internal_object(),
+            layout as u32,
+            &clear_value,
+            regions.len() as u32,
+            regions.as_ptr(),
+        );
+    }
+
     /// Calls `vkCmdCopyBuffer` on the builder.
     ///
     /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
@@ -1177,6 +1374,50 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         vk.CmdDispatch(cmd, dimensions[0], dimensions[1], dimensions[2]);
     }
 
+    /// Calls `vkCmdDispatchBase`, or `vkCmdDispatchBaseKHR` before Vulkan 1.1, on the builder.
+    #[inline]
+    pub unsafe fn dispatch_base(&mut self, base: [u32; 3], dimensions: [u32; 3]) {
+        debug_assert!({
+            let max_dims = self
+                .device()
+                .physical_device()
+                .limits()
+                .max_compute_work_group_count();
+            (0..3).all(|i| base[i] as u64 + dimensions[i] as u64 <= max_dims[i] as u64)
+        });
+
+        let vulkan_1_1 = Version {
+            major: 1,
+            minor: 1,
+            patch: 0,
+        };
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        if self.device().api_version() >= vulkan_1_1 {
+            vk.CmdDispatchBase(
+                cmd,
+                base[0],
+                base[1],
+                base[2],
+                dimensions[0],
+                dimensions[1],
+                dimensions[2],
+            );
+        } else {
+            debug_assert!(self.device().loaded_extensions().khr_device_group);
+            vk.CmdDispatchBaseKHR(
+                cmd,
+                base[0],
+                base[1],
+                base[2],
+                dimensions[0],
+                dimensions[1],
+                dimensions[2],
+            );
+        }
+    }
+
     /// Calls `vkCmdDispatchIndirect` on the builder.
     #[inline]
     pub unsafe fn dispatch_indirect<B>(&mut self, buffer: &B)
@@ -1290,6 +1531,122 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         );
     }
 
+    /// Calls `vkCmdDrawIndirectCountKHR` on the builder.
+    #[inline]
+    pub unsafe fn draw_indirect_count<B, C>(
+        &mut self,
+        buffer: &B,
+        count_buffer: &C,
+        max_draw_count: u32,
+        stride: u32,
+    ) where
+        B: ?Sized + BufferAccess,
+        C: ?Sized + BufferAccess,
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        debug_assert!(
+            (stride % 4) == 0 && stride as usize >= mem::size_of::<vk::DrawIndirectCommand>()
+        );
+
+        let inner = buffer.inner();
+        debug_assert!(inner.offset < inner.buffer.size());
+        debug_assert!(inner.buffer.usage_indirect_buffer());
+
+        let count_inner = count_buffer.inner();
+        debug_assert!(count_inner.offset < count_inner.buffer.size());
+        debug_assert!(count_inner.buffer.usage_indirect_buffer());
+        debug_assert_eq!(count_inner.offset % 4, 0);
+
+        vk.CmdDrawIndirectCountKHR(
+            cmd,
+            inner.buffer.internal_object(),
+            inner.offset as vk::DeviceSize,
+            count_inner.buffer.internal_object(),
+            count_inner.offset as vk::DeviceSize,
+            max_draw_count,
+            stride,
+        );
+    }
+
+    /// Calls `vkCmdDrawIndexedIndirectCountKHR` on the builder.
+    #[inline]
+    pub unsafe fn draw_indexed_indirect_count<B, C>(
+        &mut self,
+        buffer: &B,
+        count_buffer: &C,
+        max_draw_count: u32,
+        stride: u32,
+    ) where
+        B: ?Sized + BufferAccess,
+        C: ?Sized + BufferAccess,
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        debug_assert!(
+            (stride % 4) == 0
+                && stride as usize >= mem::size_of::<vk::DrawIndexedIndirectCommand>()
+        );
+
+        let inner = buffer.inner();
+        debug_assert!(inner.offset < inner.buffer.size());
+        debug_assert!(inner.buffer.usage_indirect_buffer());
+
+        let count_inner = count_buffer.inner();
+        debug_assert!(count_inner.offset < count_inner.buffer.size());
+        debug_assert!(count_inner.buffer.usage_indirect_buffer());
+        debug_assert_eq!(count_inner.offset % 4, 0);
+
+        vk.CmdDrawIndexedIndirectCountKHR(
+            cmd,
+            inner.buffer.internal_object(),
+            inner.offset as vk::DeviceSize,
+            count_inner.buffer.internal_object(),
+            count_inner.offset as vk::DeviceSize,
+            max_draw_count,
+            stride,
+        );
+    }
+
+    /// Calls `vkCmdDrawMeshTasksEXT` on the builder.
+    #[inline]
+    pub unsafe fn draw_mesh_tasks(&mut self, group_count: [u32; 3]) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdDrawMeshTasksEXT(cmd, group_count[0], group_count[1], group_count[2]);
+    }
+
+    /// Calls `vkCmdDrawMeshTasksIndirectEXT` on the builder.
+    #[inline]
+    pub unsafe fn draw_mesh_tasks_indirect<B>(&mut self, buffer: &B, draw_count: u32, stride: u32)
+    where
+        B: ?Sized + BufferAccess,
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        debug_assert!(
+            draw_count <= 1
+                || ((stride % 4) == 0)
+                    && stride as usize >= mem::size_of::<vk::DrawMeshTasksIndirectCommandEXT>()
+        );
+
+        let inner = buffer.inner();
+        debug_assert!(inner.offset < inner.buffer.size());
+        debug_assert!(inner.buffer.usage_indirect_buffer());
+        debug_assert_eq!(inner.offset % 4, 0);
+
+        vk.CmdDrawMeshTasksIndirectEXT(
+            cmd,
+            inner.buffer.internal_object(),
+            inner.offset as vk::DeviceSize,
+            draw_count,
+            stride,
+        );
+    }
+
     /// Calls `vkCmdEndQuery` on the builder.
     #[inline]
     pub unsafe fn end_query(&mut self, query: UnsafeQuery) {
@@ -1339,6 +1696,7 @@ impl<P> UnsafeCommandBufferBuilder<P> {
             } = buffer.inner();
             debug_assert!(buffer_inner.usage_transfer_destination());
             debug_assert_eq!(offset % 4, 0);
+            debug_assert_eq!(size % 4, 0);
             (buffer_inner.internal_object(), offset)
         };
 
@@ -1370,6 +1728,11 @@ impl<P> UnsafeCommandBufferBuilder<P> {
             return;
         }
 
+        if self.device().uses_synchronization2() {
+            self.pipeline_barrier2(command);
+            return;
+        }
+
         let vk = self.device().pointers();
         let cmd = self.internal_object();
 
@@ -1390,6 +1753,106 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         );
     }
 
+    // Implementation of `pipeline_barrier` with `vkCmdPipelineBarrier2KHR`, where each barrier
+    // only waits for its own stages.
+    unsafe fn pipeline_barrier2(&mut self, command: &UnsafeCommandBufferBuilderPipelineBarrier) {
+        debug_assert_eq!(
+            command.memory_barriers.len(),
+            command.memory_barrier_stages.len()
+        );
+        debug_assert_eq!(
+            command.buffer_barriers.len(),
+            command.buffer_barrier_stages.len()
+        );
+        debug_assert_eq!(
+            command.image_barriers.len(),
+            command.image_barrier_stages.len()
+        );
+
+        // Execution dependencies are memory barriers without any access.
+        let memory_barriers: SmallVec<[vk::MemoryBarrier2KHR; 4]> = command
+            .execution_dependencies
+            .iter()
+            .map(|&(src, dst)| (src, 0, dst, 0))
+            .chain(
+                command
+                    .memory_barriers
+                    .iter()
+                    .zip(command.memory_barrier_stages.iter())
+                    .map(|(b, &(src, dst))| (src, b.srcAccessMask, dst, b.dstAccessMask)),
+            )
+            .map(|(src, src_access, dst, dst_access)| vk::MemoryBarrier2KHR {
+                sType: vk::STRUCTURE_TYPE_MEMORY_BARRIER_2_KHR,
+                pNext: ptr::null(),
+                srcStageMask: src as vk::PipelineStageFlags2KHR,
+                srcAccessMask: src_access as vk::AccessFlags2KHR,
+                dstStageMask: dst as vk::PipelineStageFlags2KHR,
+                dstAccessMask: dst_access as vk::AccessFlags2KHR,
+            })
+            .collect();
+
+        let buffer_barriers: SmallVec<[vk::BufferMemoryBarrier2KHR; 8]> = command
+            .buffer_barriers
+            .iter()
+            .zip(command.buffer_barrier_stages.iter())
+            .map(|(b, &(src, dst))| vk::BufferMemoryBarrier2KHR {
+                sType: vk::STRUCTURE_TYPE_BUFFER_MEMORY_BARRIER_2_KHR,
+                pNext: ptr::null(),
+                srcStageMask: src as vk::PipelineStageFlags2KHR,
+                srcAccessMask: b.srcAccessMask as vk::AccessFlags2KHR,
+                dstStageMask: dst as vk::PipelineStageFlags2KHR,
+                dstAccessMask: b.dstAccessMask as vk::AccessFlags2KHR,
+                srcQueueFamilyIndex: b.srcQueueFamilyIndex,
+                dstQueueFamilyIndex: b.dstQueueFamilyIndex,
+                buffer: b.buffer,
+                offset: b.offset,
+                size: b.size,
+            })
+            .collect();
+
+        let image_barriers: SmallVec<[vk::ImageMemoryBarrier2KHR; 8]> = command
+            .image_barriers
+            .iter()
+            .zip(command.image_barrier_stages.iter())
+            .map(|(b, &(src, dst))| vk::ImageMemoryBarrier2KHR {
+                sType: vk::STRUCTURE_TYPE_IMAGE_MEMORY_BARRIER_2_KHR,
+                pNext: ptr::null(),
+                srcStageMask: src as vk::PipelineStageFlags2KHR,
+                srcAccessMask: b.srcAccessMask as vk::AccessFlags2KHR,
+                dstStageMask: dst as vk::PipelineStageFlags2KHR,
+                dstAccessMask: b.dstAccessMask as vk::AccessFlags2KHR,
+                oldLayout: b.oldLayout,
+                newLayout: b.newLayout,
+                srcQueueFamilyIndex: b.srcQueueFamilyIndex,
+                dstQueueFamilyIndex: b.dstQueueFamilyIndex,
+                image: b.image,
+                subresourceRange: vk::ImageSubresourceRange {
+                    aspectMask: b.subresourceRange.aspectMask,
+                    baseMipLevel: b.subresourceRange.baseMipLevel,
+                    levelCount: b.subresourceRange.levelCount,
+                    baseArrayLayer: b.subresourceRange.baseArrayLayer,
+                    layerCount: b.subresourceRange.layerCount,
+                },
+            })
+            .collect();
+
+        let infos = vk::DependencyInfoKHR {
+            sType: vk::STRUCTURE_TYPE_DEPENDENCY_INFO_KHR,
+            pNext: ptr::null(),
+            dependencyFlags: command.dependency_flags,
+            memoryBarrierCount: memory_barriers.len() as u32,
+            pMemoryBarriers: memory_barriers.as_ptr(),
+            bufferMemoryBarrierCount: buffer_barriers.len() as u32,
+            pBufferMemoryBarriers: buffer_barriers.as_ptr(),
+            imageMemoryBarrierCount: image_barriers.len() as u32,
+            pImageMemoryBarriers: image_barriers.as_ptr(),
+        };
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdPipelineBarrier2KHR(cmd, &infos);
+    }
+
     /// Calls `vkCmdPushConstants` on the builder.
     #[inline]
     pub unsafe fn push_constants<Pl, D>(
@@ -1593,6 +2056,60 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         );
     }
 
+    /// Calls `vkCmdTraceRaysNV` on the builder.
+    ///
+    /// The regions are relative to the start of `buffer`, which must contain the shader binding
+    /// table.
+    #[inline]
+    pub unsafe fn trace_rays<B>(
+        &mut self,
+        buffer: &B,
+        regions: ShaderBindingTableRegions,
+        dimensions: [u32; 3],
+    ) where
+        B: ?Sized + BufferAccess,
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        let inner = buffer.inner();
+        let region = |region: ShaderBindingTableRegion| {
+            let handle = if region.len == 0 {
+                0
+            } else {
+                inner.buffer.internal_object()
+            };
+            (
+                handle,
+                (inner.offset + region.offset) as vk::DeviceSize,
+                region.stride as vk::DeviceSize,
+            )
+        };
+
+        let (raygen_buffer, raygen_offset, _) = region(regions.raygen);
+        let (miss_buffer, miss_offset, miss_stride) = region(regions.miss);
+        let (hit_buffer, hit_offset, hit_stride) = region(regions.hit);
+        let (callable_buffer, callable_offset, callable_stride) = region(regions.callable);
+
+        vk.CmdTraceRaysNV(
+            cmd,
+            raygen_buffer,
+            raygen_offset,
+            miss_buffer,
+            miss_offset,
+            miss_stride,
+            hit_buffer,
+            hit_offset,
+            hit_stride,
+            callable_buffer,
+            callable_offset,
+            callable_stride,
+            dimensions[0],
+            dimensions[1],
+            dimensions[2],
+        );
+    }
+
     /// Calls `vkCmdUpdateBuffer` on the builder.
     #[inline]
     pub unsafe fn update_buffer<B, D>(&mut self, buffer: &B, data: &D)
@@ -1603,10 +2120,9 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         let vk = self.device().pointers();
         let cmd = self.internal_object();
 
-        let size = buffer.size();
+        let size = cmp::min(buffer.size(), mem::size_of_val(data));
         debug_assert_eq!(size % 4, 0);
         debug_assert!(size <= 65536);
-        debug_assert!(size <= mem::size_of_val(data));
 
         let (buffer_handle, offset) = {
             let BufferInner {
@@ -1627,6 +2143,42 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         );
     }
 
+    /// Calls `vkCmdWaitEvents` on the builder.
+    ///
+    /// The stages and the memory barriers of `command` are used, while its dependency flags are
+    /// ignored.
+    #[inline]
+    pub unsafe fn wait_events<'a, I>(
+        &mut self,
+        events: I,
+        command: &UnsafeCommandBufferBuilderPipelineBarrier,
+    ) where
+        I: IntoIterator<Item = &'a Event>,
+    {
+        let events: SmallVec<[vk::Event; 4]> =
+            events.into_iter().map(|e| e.internal_object()).collect();
+
+        debug_assert!(!events.is_empty());
+        debug_assert_ne!(command.src_stage_mask, 0);
+        debug_assert_ne!(command.dst_stage_mask, 0);
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdWaitEvents(
+            cmd,
+            events.len() as u32,
+            events.as_ptr(),
+            command.src_stage_mask,
+            command.dst_stage_mask,
+            command.memory_barriers.len() as u32,
+            command.memory_barriers.as_ptr(),
+            command.buffer_barriers.len() as u32,
+            command.buffer_barriers.as_ptr(),
+            command.image_barriers.len() as u32,
+            command.image_barriers.as_ptr(),
+        );
+    }
+
     /// Calls `vkCmdWriteTimestamp` on the builder.
     #[inline]
     pub unsafe fn write_timestamp(&mut self, query: UnsafeQuery, stages: PipelineStages) {
@@ -1779,6 +2331,17 @@ pub struct UnsafeCommandBufferBuilderImageAspect {
     pub stencil: bool,
 }
 
+impl From<ImageAspect> for UnsafeCommandBufferBuilderImageAspect {
+    #[inline]
+    fn from(aspect: ImageAspect) -> Self {
+        UnsafeCommandBufferBuilderImageAspect {
+            color: aspect == ImageAspect::Color,
+            depth: aspect == ImageAspect::Depth,
+            stencil: aspect == ImageAspect::Stencil,
+        }
+    }
+}
+
 impl UnsafeCommandBufferBuilderImageAspect {
     pub(crate) fn to_vk_bits(&self) -> vk::ImageAspectFlagBits {
         let mut out = 0;
@@ -1804,6 +2367,16 @@ pub struct UnsafeCommandBufferBuilderColorImageClear {
     pub layer_count: u32,
 }
 
+// TODO: move somewhere else?
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UnsafeCommandBufferBuilderDepthStencilImageClear {
+    pub aspect: UnsafeCommandBufferBuilderImageAspect,
+    pub base_mip_level: u32,
+    pub level_count: u32,
+    pub base_array_layer: u32,
+    pub layer_count: u32,
+}
+
 // TODO: move somewhere else?
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct UnsafeCommandBufferBuilderBufferImageCopy {
@@ -1832,6 +2405,20 @@ pub struct UnsafeCommandBufferBuilderImageCopy {
     pub extent: [u32; 3],
 }
 
+// TODO: move somewhere else?
+/// Region of a multisampled color image to resolve. Only the color aspect can be resolved.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UnsafeCommandBufferBuilderImageResolve {
+    pub source_mip_level: u32,
+    pub destination_mip_level: u32,
+    pub source_base_array_layer: u32,
+    pub destination_base_array_layer: u32,
+    pub layer_count: u32,
+    pub source_offset: [i32; 3],
+    pub destination_offset: [i32; 3],
+    pub extent: [u32; 3],
+}
+
 // TODO: move somewhere else?
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct UnsafeCommandBufferBuilderImageBlit {
@@ -1867,6 +2454,12 @@ pub struct UnsafeCommandBufferBuilderPipelineBarrier {
     memory_barriers: SmallVec<[vk::MemoryBarrier; 2]>,
     buffer_barriers: SmallVec<[vk::BufferMemoryBarrier; 8]>,
     image_barriers: SmallVec<[vk::ImageMemoryBarrier; 8]>,
+    // The legacy command uses the union of the stages of all the barriers. With
+    // `khr_synchronization2`, each barrier has its own stages, which are stored here.
+    execution_dependencies: SmallVec<[(vk::PipelineStageFlags, vk::PipelineStageFlags); 2]>,
+    memory_barrier_stages: SmallVec<[(vk::PipelineStageFlags, vk::PipelineStageFlags); 2]>,
+    buffer_barrier_stages: SmallVec<[(vk::PipelineStageFlags, vk::PipelineStageFlags); 8]>,
+    image_barrier_stages: SmallVec<[(vk::PipelineStageFlags, vk::PipelineStageFlags); 8]>,
 }
 
 impl UnsafeCommandBufferBuilderPipelineBarrier {
@@ -1880,6 +2473,10 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
             memory_barriers: SmallVec::new(),
             buffer_barriers: SmallVec::new(),
             image_barriers: SmallVec::new(),
+            execution_dependencies: SmallVec::new(),
+            memory_barrier_stages: SmallVec::new(),
+            buffer_barrier_stages: SmallVec::new(),
+            image_barrier_stages: SmallVec::new(),
         }
     }
 
@@ -1901,6 +2498,15 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
         self.buffer_barriers
             .extend(other.buffer_barriers.into_iter());
         self.image_barriers.extend(other.image_barriers.into_iter());
+
+        self.execution_dependencies
+            .extend(other.execution_dependencies.into_iter());
+        self.memory_barrier_stages
+            .extend(other.memory_barrier_stages.into_iter());
+        self.buffer_barrier_stages
+            .extend(other.buffer_barrier_stages.into_iter());
+        self.image_barrier_stages
+            .extend(other.image_barrier_stages.into_iter());
     }
 
     /// Adds an execution dependency. This means that all the stages in `source` of the previous
@@ -1919,6 +2525,18 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
         destination: PipelineStages,
         by_region: bool,
     ) {
+        let stages = self.add_stages(source, destination, by_region);
+        self.execution_dependencies.push(stages);
+    }
+
+    // Adds the stages to the stages of the whole command, and returns them as Vulkan bits.
+    #[inline]
+    fn add_stages(
+        &mut self,
+        source: PipelineStages,
+        destination: PipelineStages,
+        by_region: bool,
+    ) -> (vk::PipelineStageFlags, vk::PipelineStageFlags) {
         if !by_region {
             self.dependency_flags = 0;
         }
@@ -1926,8 +2544,11 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
         debug_assert_ne!(source, PipelineStages::none());
         debug_assert_ne!(destination, PipelineStages::none());
 
-        self.src_stage_mask |= source.into_vulkan_bits();
-        self.dst_stage_mask |= destination.into_vulkan_bits();
+        let source = source.into_vulkan_bits();
+        let destination = destination.into_vulkan_bits();
+        self.src_stage_mask |= source;
+        self.dst_stage_mask |= destination;
+        (source, destination)
     }
 
     /// Adds a memory barrier. This means that all the memory writes by the given source stages
@@ -1951,7 +2572,8 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
         debug_assert!(source_access.is_compatible_with(&source_stage));
         debug_assert!(destination_access.is_compatible_with(&destination_stage));
 
-        self.add_execution_dependency(source_stage, destination_stage, by_region);
+        let stages = self.add_stages(source_stage, destination_stage, by_region);
+        self.memory_barrier_stages.push(stages);
 
         self.memory_barriers.push(vk::MemoryBarrier {
             sType: vk::STRUCTURE_TYPE_MEMORY_BARRIER,
@@ -1993,7 +2615,8 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
         debug_assert!(source_access.is_compatible_with(&source_stage));
         debug_assert!(destination_access.is_compatible_with(&destination_stage));
 
-        self.add_execution_dependency(source_stage, destination_stage, by_region);
+        let stages = self.add_stages(source_stage, destination_stage, by_region);
+        self.buffer_barrier_stages.push(stages);
 
         debug_assert!(size <= buffer.size());
         let BufferInner {
@@ -2057,7 +2680,8 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
         debug_assert!(source_access.is_compatible_with(&source_stage));
         debug_assert!(destination_access.is_compatible_with(&destination_stage));
 
-        self.add_execution_dependency(source_stage, destination_stage, by_region);
+        let stages = self.add_stages(source_stage, destination_stage, by_region);
+        self.image_barrier_stages.push(stages);
 
         debug_assert_ne!(new_layout, ImageLayout::Undefined);
         debug_assert_ne!(new_layout, ImageLayout::Preinitialized);
@@ -2140,3 +2764,68 @@ unsafe impl<P> VulkanObject for UnsafeCommandBuffer<P> {
         self.cmd_raw
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use command_buffer::sys::UnsafeCommandBufferBuilderPipelineBarrier;
+    use sync::AccessFlagBits;
+    use sync::PipelineStages;
+    use vk;
+
+    #[test]
+    fn barrier_stages_kept_per_barrier() {
+        let transfer = PipelineStages {
+            transfer: true,
+            ..PipelineStages::none()
+        };
+        let compute = PipelineStages {
+            compute_shader: true,
+            ..PipelineStages::none()
+        };
+        let fragment = PipelineStages {
+            fragment_shader: true,
+            ..PipelineStages::none()
+        };
+
+        unsafe {
+            let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+            barrier.add_memory_barrier(
+                transfer,
+                AccessFlagBits {
+                    transfer_write: true,
+                    ..AccessFlagBits::none()
+                },
+                compute,
+                AccessFlagBits {
+                    shader_read: true,
+                    ..AccessFlagBits::none()
+                },
+                false,
+            );
+
+            let mut other = UnsafeCommandBufferBuilderPipelineBarrier::new();
+            other.add_execution_dependency(compute, fragment, true);
+            barrier.merge(other);
+
+            assert_eq!(
+                barrier.src_stage_mask,
+                vk::PIPELINE_STAGE_TRANSFER_BIT | vk::PIPELINE_STAGE_COMPUTE_SHADER_BIT
+            );
+            assert_eq!(
+                barrier.memory_barrier_stages.as_slice(),
+                &[(
+                    vk::PIPELINE_STAGE_TRANSFER_BIT,
+                    vk::PIPELINE_STAGE_COMPUTE_SHADER_BIT
+                )]
+            );
+            assert_eq!(
+                barrier.execution_dependencies.as_slice(),
+                &[(
+                    vk::PIPELINE_STAGE_COMPUTE_SHADER_BIT,
+                    vk::PIPELINE_STAGE_FRAGMENT_SHADER_BIT
+                )]
+            );
+            assert_eq!(barrier.dependency_flags, 0);
+        }
+    }
+}
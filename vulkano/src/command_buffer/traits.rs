@@ -28,6 +28,7 @@ use sync::now;
 use sync::AccessCheckError;
 use sync::AccessError;
 use sync::AccessFlagBits;
+use sync::Event;
 use sync::FlushError;
 use sync::GpuFuture;
 use sync::NowFuture;
@@ -164,6 +165,15 @@ pub unsafe trait CommandBuffer: DeviceOwned {
         queue: &Queue,
     ) -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError>;
 
+    /// Returns the events that the commands of this command buffer wait on.
+    ///
+    /// This is used to report the events that are not set when waiting for the execution of the
+    /// command buffer takes too long. The default implementation returns an empty list.
+    #[inline]
+    fn waited_events(&self) -> &[Arc<Event>] {
+        &[]
+    }
+
     // FIXME: lots of other methods
 }
 
@@ -213,6 +223,11 @@ where
     ) -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
         (**self).check_image_access(image, layout, exclusive, queue)
     }
+
+    #[inline]
+    fn waited_events(&self) -> &[Arc<Event>] {
+        (**self).waited_events()
+    }
 }
 
 /// Represents a command buffer being executed by the GPU and the moment when the execution
@@ -351,6 +366,35 @@ where
                 .check_image_access(image, layout, exclusive, queue),
         }
     }
+
+    #[inline]
+    fn describe_dependencies(&self, out: &mut Vec<String>) {
+        let name = self
+            .device()
+            .object_name(self.command_buffer.inner())
+            .map(|name| format!(" named {:?}", name))
+            .unwrap_or_default();
+        out.push(format!(
+            "execution of a command buffer{} on queue {} of family {}{}",
+            name,
+            self.queue.id_within_family(),
+            self.queue.family().id(),
+            if self.finished.load(Ordering::SeqCst) {
+                " (finished)"
+            } else if !*self.submitted.lock().unwrap() {
+                " (not submitted)"
+            } else {
+                ""
+            }
+        ));
+        self.previous.describe_dependencies(out);
+    }
+
+    #[inline]
+    fn waited_events(&self, out: &mut Vec<Arc<Event>>) {
+        out.extend(self.command_buffer.waited_events().iter().cloned());
+        self.previous.waited_events(out);
+    }
 }
 
 unsafe impl<F, Cb> DeviceOwned for CommandBufferExecFuture<F, Cb>
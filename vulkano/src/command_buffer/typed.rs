@@ -0,0 +1,1277 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::sync::Arc;
+
+use buffer::BufferAccess;
+use buffer::TypedBufferAccess;
+use command_buffer::pool::standard::StandardCommandPoolBuilder;
+use command_buffer::pool::CommandPoolBuilderAlloc;
+use command_buffer::AutoCommandBuffer;
+use command_buffer::AutoCommandBufferBuilder;
+use command_buffer::AutoCommandBufferBuilderContextError;
+use command_buffer::BeginQueryError;
+use command_buffer::BeginRenderPassError;
+use command_buffer::BlitImageError;
+use command_buffer::BuildError;
+use command_buffer::ClearColorImageError;
+use command_buffer::ClearDepthStencilImageError;
+use command_buffer::CommandBuffer;
+use command_buffer::CopyBufferError;
+use command_buffer::CopyBufferImageError;
+use command_buffer::CopyImageError;
+use command_buffer::CopyImageToSwapchainImageError;
+use command_buffer::DispatchError;
+use command_buffer::DispatchIndirectCommand;
+use command_buffer::DispatchIndirectError;
+use command_buffer::DrawError;
+use command_buffer::DrawIndexedError;
+use command_buffer::DrawIndexedIndirectCommand;
+use command_buffer::DrawIndexedIndirectError;
+use command_buffer::DrawIndirectCommand;
+use command_buffer::DrawIndirectError;
+use command_buffer::DrawMeshTasksError;
+use command_buffer::DrawMeshTasksIndirectCommand;
+use command_buffer::DrawMeshTasksIndirectError;
+use command_buffer::DynamicState;
+use command_buffer::ExecuteCommandsError;
+use command_buffer::FillBufferError;
+use command_buffer::GenerateMipmapsError;
+use command_buffer::MemoryBarrierError;
+use command_buffer::PipelineBarrier;
+use command_buffer::PushConstantsError;
+use command_buffer::ResetQueryPoolError;
+use command_buffer::UpdateBufferError;
+use command_buffer::WriteTimestampError;
+use descriptor::descriptor::ShaderStages;
+use descriptor::descriptor_set::DescriptorSetsCollection;
+use descriptor::pipeline_layout::PipelineLayoutAbstract;
+use descriptor::pipeline_layout::TypedPipelineLayout;
+use format::AcceptsPixels;
+use format::ClearValue;
+use format::Format;
+use framebuffer::FramebufferAbstract;
+use framebuffer::RenderPassDescClearValues;
+use image::ImageAccess;
+use image::SwapchainImage;
+use pipeline::depth_stencil::DynamicStencilValue;
+use pipeline::input_assembly::Index;
+use pipeline::vertex::VertexSource;
+use pipeline::viewport::Scissor;
+use pipeline::viewport::Viewport;
+use pipeline::ComputePipelineAbstract;
+use pipeline::GraphicsPipelineAbstract;
+use pipeline::TypedGraphicsPipeline;
+use query::QueryPool;
+use sampler::Filter;
+use sync::AccessFlagBits;
+use sync::PipelineStages;
+
+/// Marker for a `TypedAutoCommandBufferBuilder` that is outside of a render pass.
+pub enum OutsideRenderPass {}
+
+/// Marker for a `TypedAutoCommandBufferBuilder` that is inside a subpass that accepts inline
+/// commands.
+pub enum InsideRenderPass {}
+
+/// Marker for a `TypedAutoCommandBufferBuilder` that is inside a subpass whose commands are
+/// recorded in secondary command buffers.
+pub enum InsideRenderPassSecondary {}
+
+/// Implemented on the markers of the states in which commands can be added directly to a
+/// `TypedAutoCommandBufferBuilder`, which excludes the subpasses whose commands are recorded in
+/// secondary command buffers.
+///
+/// The dynamic state, push constants and query commands are available in these states.
+pub trait InlineCommandsState {}
+
+impl InlineCommandsState for OutsideRenderPass {}
+
+impl InlineCommandsState for InsideRenderPass {}
+
+/// Wrapper around an `AutoCommandBufferBuilder` whose type encodes whether it is inside or
+/// outside of a render pass.
+///
+/// Commands that are only valid outside of a render pass (copies, clears, dispatches) are only
+/// available on `TypedAutoCommandBufferBuilder<OutsideRenderPass>`, draw commands are only
+/// available on `TypedAutoCommandBufferBuilder<InsideRenderPass>`, and the subpasses whose
+/// commands are recorded in secondary command buffers only accept `execute_commands` on
+/// `TypedAutoCommandBufferBuilder<InsideRenderPassSecondary>`. Entering and leaving a render
+/// pass or a subpass consumes the builder and returns a builder of the other type. Adding a
+/// command in the wrong state therefore fails to compile, instead of returning a
+/// `ForbiddenInsideRenderPass`, `ForbiddenOutsideRenderPass` or `WrongSubpassType` error.
+///
+/// The other checks, such as the compatibility of a pipeline with the current subpass, are still
+/// performed when the command is added.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use vulkano::buffer::BufferAccess;
+/// # use vulkano::command_buffer::AutoCommandBufferBuilder;
+/// # use vulkano::command_buffer::DynamicState;
+/// # use vulkano::command_buffer::TypedAutoCommandBufferBuilder;
+/// # use vulkano::format::ClearValue;
+/// # use vulkano::framebuffer::FramebufferAbstract;
+/// # use vulkano::pipeline::GraphicsPipelineAbstract;
+/// # let device: Arc<vulkano::device::Device> = return;
+/// # let queue: Arc<vulkano::device::Queue> = return;
+/// # let framebuffer: Arc<dyn FramebufferAbstract + Send + Sync> = return;
+/// # let pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync> = return;
+/// # let vertex_buffer: Arc<dyn BufferAccess + Send + Sync> = return;
+/// # let dynamic_state: DynamicState = return;
+/// let builder = AutoCommandBufferBuilder::primary_one_time_submit(device, queue.family()).unwrap();
+/// let builder = TypedAutoCommandBufferBuilder::new(builder).unwrap();
+///
+/// let mut builder = builder
+///     .begin_render_pass(framebuffer, vec![ClearValue::Float([0.0; 4])])
+///     .unwrap();
+/// builder
+///     .draw(pipeline, &dynamic_state, vec![vertex_buffer], (), ())
+///     .unwrap();
+/// let command_buffer = builder.end_render_pass().unwrap().build().unwrap();
+/// ```
+///
+/// Trying to leave a render pass that hasn't been entered doesn't compile:
+///
+/// ```compile_fail
+/// # use vulkano::command_buffer::OutsideRenderPass;
+/// # use vulkano::command_buffer::TypedAutoCommandBufferBuilder;
+/// # let builder: TypedAutoCommandBufferBuilder<OutsideRenderPass> = return;
+/// let builder = builder.end_render_pass().unwrap();
+/// ```
+pub struct TypedAutoCommandBufferBuilder<L, P = StandardCommandPoolBuilder> {
+    inner: AutoCommandBufferBuilder<P>,
+    marker: PhantomData<L>,
+}
+
+impl<L, P> TypedAutoCommandBufferBuilder<L, P> {
+    #[inline]
+    fn from_builder(inner: AutoCommandBufferBuilder<P>) -> TypedAutoCommandBufferBuilder<L, P> {
+        TypedAutoCommandBufferBuilder {
+            inner: inner,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped builder.
+    #[inline]
+    pub fn inner(&self) -> &AutoCommandBufferBuilder<P> {
+        &self.inner
+    }
+
+    /// Destroys the wrapper and returns the builder, whose state is then only checked at runtime.
+    #[inline]
+    pub fn into_inner(self) -> AutoCommandBufferBuilder<P> {
+        self.inner
+    }
+}
+
+impl<L, P> TypedAutoCommandBufferBuilder<L, P>
+where
+    L: InlineCommandsState,
+{
+    /// See `AutoCommandBufferBuilder::set_viewport`.
+    #[inline]
+    pub fn set_viewport<I>(&mut self, viewports: I) -> &mut Self
+    where
+        I: IntoIterator<Item = Viewport>,
+    {
+        self.inner.set_viewport(viewports);
+        self
+    }
+
+    /// See `AutoCommandBufferBuilder::set_scissor`.
+    #[inline]
+    pub fn set_scissor<I>(&mut self, scissors: I) -> &mut Self
+    where
+        I: IntoIterator<Item = Scissor>,
+    {
+        self.inner.set_scissor(scissors);
+        self
+    }
+
+    /// See `AutoCommandBufferBuilder::set_line_width`.
+    #[inline]
+    pub fn set_line_width(&mut self, line_width: f32) -> &mut Self {
+        self.inner.set_line_width(line_width);
+        self
+    }
+
+    /// See `AutoCommandBufferBuilder::set_depth_bias`.
+    #[inline]
+    pub fn set_depth_bias(
+        &mut self,
+        constant_factor: f32,
+        clamp: f32,
+        slope_factor: f32,
+    ) -> &mut Self {
+        self.inner
+            .set_depth_bias(constant_factor, clamp, slope_factor);
+        self
+    }
+
+    /// See `AutoCommandBufferBuilder::set_depth_bounds`.
+    #[inline]
+    pub fn set_depth_bounds(&mut self, bounds: Range<f32>) -> &mut Self {
+        self.inner.set_depth_bounds(bounds);
+        self
+    }
+
+    /// See `AutoCommandBufferBuilder::set_blend_constants`.
+    #[inline]
+    pub fn set_blend_constants(&mut self, constants: [f32; 4]) -> &mut Self {
+        self.inner.set_blend_constants(constants);
+        self
+    }
+
+    /// See `AutoCommandBufferBuilder::set_stencil_compare_mask`.
+    #[inline]
+    pub fn set_stencil_compare_mask(&mut self, compare_mask: DynamicStencilValue) -> &mut Self {
+        self.inner.set_stencil_compare_mask(compare_mask);
+        self
+    }
+
+    /// See `AutoCommandBufferBuilder::set_stencil_write_mask`.
+    #[inline]
+    pub fn set_stencil_write_mask(&mut self, write_mask: DynamicStencilValue) -> &mut Self {
+        self.inner.set_stencil_write_mask(write_mask);
+        self
+    }
+
+    /// See `AutoCommandBufferBuilder::set_stencil_reference`.
+    #[inline]
+    pub fn set_stencil_reference(&mut self, reference: DynamicStencilValue) -> &mut Self {
+        self.inner.set_stencil_reference(reference);
+        self
+    }
+
+    /// See `AutoCommandBufferBuilder::push_constants`.
+    #[inline]
+    pub fn push_constants<Pl, D>(
+        &mut self,
+        pipeline_layout: Pl,
+        stages: ShaderStages,
+        offset: u32,
+        data: &D,
+    ) -> Result<&mut Self, PushConstantsError>
+    where
+        Pl: PipelineLayoutAbstract + Send + Sync + 'static,
+        D: ?Sized + Send + Sync + 'static,
+    {
+        self.inner
+            .push_constants(pipeline_layout, stages, offset, data)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::begin_query`.
+    #[inline]
+    pub unsafe fn begin_query(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        query: u32,
+        precise: bool,
+    ) -> Result<&mut Self, BeginQueryError> {
+        self.inner.begin_query(query_pool, query, precise)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::end_query`.
+    #[inline]
+    pub fn end_query(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        query: u32,
+    ) -> Result<&mut Self, AutoCommandBufferBuilderContextError> {
+        self.inner.end_query(query_pool, query)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::write_timestamp`.
+    #[inline]
+    pub unsafe fn write_timestamp(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        query: u32,
+        stage: PipelineStages,
+    ) -> Result<&mut Self, WriteTimestampError> {
+        self.inner.write_timestamp(query_pool, query, stage)?;
+        Ok(self)
+    }
+}
+
+impl<P> TypedAutoCommandBufferBuilder<OutsideRenderPass, P> {
+    /// Wraps around a builder that is outside of a render pass.
+    ///
+    /// Returns an error if the builder is inside a render pass, which is always the case for
+    /// secondary graphics command buffers.
+    #[inline]
+    pub fn new(
+        builder: AutoCommandBufferBuilder<P>,
+    ) -> Result<
+        TypedAutoCommandBufferBuilder<OutsideRenderPass, P>,
+        AutoCommandBufferBuilderContextError,
+    > {
+        builder.ensure_outside_render_pass()?;
+        Ok(TypedAutoCommandBufferBuilder::from_builder(builder))
+    }
+
+    /// Builds the command buffer.
+    #[inline]
+    pub fn build(self) -> Result<AutoCommandBuffer<P::Alloc>, BuildError>
+    where
+        P: CommandPoolBuilderAlloc,
+    {
+        self.inner.build()
+    }
+
+    /// Adds a command that enters a render pass, whose first subpass accepts inline commands.
+    ///
+    /// See `AutoCommandBufferBuilder::begin_render_pass`.
+    #[inline]
+    pub fn begin_render_pass<F, C>(
+        mut self,
+        framebuffer: F,
+        clear_values: C,
+    ) -> Result<TypedAutoCommandBufferBuilder<InsideRenderPass, P>, BeginRenderPassError>
+    where
+        F: FramebufferAbstract + RenderPassDescClearValues<C> + Clone + Send + Sync + 'static,
+    {
+        self.inner
+            .begin_render_pass(framebuffer, false, clear_values)?;
+        Ok(TypedAutoCommandBufferBuilder::from_builder(self.inner))
+    }
+
+    /// Adds a command that enters a render pass, whose first subpass is recorded in secondary
+    /// command buffers.
+    ///
+    /// See `AutoCommandBufferBuilder::begin_render_pass`.
+    #[inline]
+    pub fn begin_render_pass_secondary<F, C>(
+        mut self,
+        framebuffer: F,
+        clear_values: C,
+    ) -> Result<TypedAutoCommandBufferBuilder<InsideRenderPassSecondary, P>, BeginRenderPassError>
+    where
+        F: FramebufferAbstract + RenderPassDescClearValues<C> + Clone + Send + Sync + 'static,
+    {
+        self.inner
+            .begin_render_pass(framebuffer, true, clear_values)?;
+        Ok(TypedAutoCommandBufferBuilder::from_builder(self.inner))
+    }
+
+    /// See `AutoCommandBufferBuilder::execute_commands`.
+    ///
+    /// # Safety
+    ///
+    /// The same as `AutoCommandBufferBuilder::execute_commands`, whose safety checks and
+    /// synchronization are not implemented yet.
+    #[inline]
+    pub unsafe fn execute_commands<C>(
+        &mut self,
+        command_buffer: C,
+    ) -> Result<&mut Self, ExecuteCommandsError>
+    where
+        C: CommandBuffer + Send + Sync + 'static,
+    {
+        self.inner.execute_commands(command_buffer)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::copy_image`.
+    #[inline]
+    pub fn copy_image<S, D>(
+        &mut self,
+        source: S,
+        source_offset: [i32; 3],
+        source_base_array_layer: u32,
+        source_mip_level: u32,
+        destination: D,
+        destination_offset: [i32; 3],
+        destination_base_array_layer: u32,
+        destination_mip_level: u32,
+        extent: [u32; 3],
+        layer_count: u32,
+    ) -> Result<&mut Self, CopyImageError>
+    where
+        S: ImageAccess + Send + Sync + 'static,
+        D: ImageAccess + Send + Sync + 'static,
+    {
+        self.inner.copy_image(
+            source,
+            source_offset,
+            source_base_array_layer,
+            source_mip_level,
+            destination,
+            destination_offset,
+            destination_base_array_layer,
+            destination_mip_level,
+            extent,
+            layer_count,
+        )?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::blit_image`.
+    #[inline]
+    pub fn blit_image<S, D>(
+        &mut self,
+        source: S,
+        source_top_left: [i32; 3],
+        source_bottom_right: [i32; 3],
+        source_base_array_layer: u32,
+        source_mip_level: u32,
+        destination: D,
+        destination_top_left: [i32; 3],
+        destination_bottom_right: [i32; 3],
+        destination_base_array_layer: u32,
+        destination_mip_level: u32,
+        layer_count: u32,
+        filter: Filter,
+    ) -> Result<&mut Self, BlitImageError>
+    where
+        S: ImageAccess + Send + Sync + 'static,
+        D: ImageAccess + Send + Sync + 'static,
+    {
+        self.inner.blit_image(
+            source,
+            source_top_left,
+            source_bottom_right,
+            source_base_array_layer,
+            source_mip_level,
+            destination,
+            destination_top_left,
+            destination_bottom_right,
+            destination_base_array_layer,
+            destination_mip_level,
+            layer_count,
+            filter,
+        )?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::copy_image_to_swapchain_image`.
+    #[inline]
+    pub fn copy_image_to_swapchain_image<S, W>(
+        &mut self,
+        source: S,
+        destination: Arc<SwapchainImage<W>>,
+        filter: Filter,
+    ) -> Result<&mut Self, CopyImageToSwapchainImageError>
+    where
+        S: ImageAccess + Send + Sync + 'static,
+        W: Send + Sync + 'static,
+    {
+        self.inner
+            .copy_image_to_swapchain_image(source, destination, filter)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::generate_mipmaps`.
+    #[inline]
+    pub fn generate_mipmaps<I>(&mut self, image: I) -> Result<&mut Self, GenerateMipmapsError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
+    {
+        self.inner.generate_mipmaps(image)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::clear_color_image`.
+    #[inline]
+    pub fn clear_color_image<I>(
+        &mut self,
+        image: I,
+        color: ClearValue,
+    ) -> Result<&mut Self, ClearColorImageError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
+    {
+        self.inner.clear_color_image(image, color)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::clear_depth_stencil_image`.
+    #[inline]
+    pub fn clear_depth_stencil_image<I>(
+        &mut self,
+        image: I,
+        clear_value: ClearValue,
+    ) -> Result<&mut Self, ClearDepthStencilImageError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
+    {
+        self.inner.clear_depth_stencil_image(image, clear_value)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::copy_buffer`.
+    #[inline]
+    pub fn copy_buffer<S, D, T>(
+        &mut self,
+        source: S,
+        destination: D,
+    ) -> Result<&mut Self, CopyBufferError>
+    where
+        S: TypedBufferAccess<Content = T> + Send + Sync + 'static,
+        D: TypedBufferAccess<Content = T> + Send + Sync + 'static,
+        T: ?Sized,
+    {
+        self.inner.copy_buffer(source, destination)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::copy_buffer_to_image`.
+    #[inline]
+    pub fn copy_buffer_to_image<S, D, Px>(
+        &mut self,
+        source: S,
+        destination: D,
+    ) -> Result<&mut Self, CopyBufferImageError>
+    where
+        S: TypedBufferAccess<Content = [Px]> + Send + Sync + 'static,
+        D: ImageAccess + Send + Sync + 'static,
+        Format: AcceptsPixels<Px>,
+    {
+        self.inner.copy_buffer_to_image(source, destination)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::copy_image_to_buffer`.
+    #[inline]
+    pub fn copy_image_to_buffer<S, D, Px>(
+        &mut self,
+        source: S,
+        destination: D,
+    ) -> Result<&mut Self, CopyBufferImageError>
+    where
+        S: ImageAccess + Send + Sync + 'static,
+        D: TypedBufferAccess<Content = [Px]> + Send + Sync + 'static,
+        Format: AcceptsPixels<Px>,
+    {
+        self.inner.copy_image_to_buffer(source, destination)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::dispatch`.
+    #[inline]
+    pub fn dispatch<Cp, S, Pc>(
+        &mut self,
+        dimensions: [u32; 3],
+        pipeline: Cp,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DispatchError>
+    where
+        Cp: ComputePipelineAbstract + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+    {
+        self.inner.dispatch(dimensions, pipeline, sets, constants)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::dispatch_base`.
+    #[inline]
+    pub fn dispatch_base<Cp, S, Pc>(
+        &mut self,
+        base: [u32; 3],
+        dimensions: [u32; 3],
+        pipeline: Cp,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DispatchError>
+    where
+        Cp: ComputePipelineAbstract + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+    {
+        self.inner
+            .dispatch_base(base, dimensions, pipeline, sets, constants)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::dispatch_indirect`.
+    #[inline]
+    pub fn dispatch_indirect<Cp, S, Pc, Inb>(
+        &mut self,
+        indirect_buffer: Inb,
+        pipeline: Cp,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DispatchIndirectError>
+    where
+        Cp: ComputePipelineAbstract + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+        Inb: BufferAccess
+            + TypedBufferAccess<Content = [DispatchIndirectCommand]>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.inner
+            .dispatch_indirect(indirect_buffer, pipeline, sets, constants)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::fill_buffer`.
+    #[inline]
+    pub fn fill_buffer<B>(&mut self, buffer: B, data: u32) -> Result<&mut Self, FillBufferError>
+    where
+        B: BufferAccess + Send + Sync + 'static,
+    {
+        self.inner.fill_buffer(buffer, data)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::update_buffer`.
+    #[inline]
+    pub fn update_buffer<B, D>(
+        &mut self,
+        buffer: B,
+        data: D,
+    ) -> Result<&mut Self, UpdateBufferError>
+    where
+        B: TypedBufferAccess<Content = D> + Send + Sync + 'static,
+        D: Send + Sync + 'static,
+    {
+        self.inner.update_buffer(buffer, data)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::memory_barrier`.
+    #[inline]
+    pub fn memory_barrier(
+        &mut self,
+        source_stage: PipelineStages,
+        source_access: AccessFlagBits,
+        destination_stage: PipelineStages,
+        destination_access: AccessFlagBits,
+        by_region: bool,
+    ) -> Result<&mut Self, MemoryBarrierError> {
+        self.inner.memory_barrier(
+            source_stage,
+            source_access,
+            destination_stage,
+            destination_access,
+            by_region,
+        )?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::pipeline_barrier`.
+    #[inline]
+    pub fn pipeline_barrier(
+        &mut self,
+        barrier: PipelineBarrier,
+    ) -> Result<&mut Self, MemoryBarrierError> {
+        self.inner.pipeline_barrier(barrier)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::reset_query_pool`.
+    #[inline]
+    pub unsafe fn reset_query_pool(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        queries: Range<u32>,
+    ) -> Result<&mut Self, ResetQueryPoolError> {
+        self.inner.reset_query_pool(query_pool, queries)?;
+        Ok(self)
+    }
+}
+
+impl<P> TypedAutoCommandBufferBuilder<InsideRenderPass, P> {
+    /// Wraps around a builder that is inside a subpass that accepts inline commands.
+    ///
+    /// This includes secondary graphics command buffers, which can't leave their render pass.
+    #[inline]
+    pub fn from_inside_render_pass(
+        builder: AutoCommandBufferBuilder<P>,
+    ) -> Result<
+        TypedAutoCommandBufferBuilder<InsideRenderPass, P>,
+        AutoCommandBufferBuilderContextError,
+    > {
+        builder.ensure_inside_inline_subpass()?;
+        Ok(TypedAutoCommandBufferBuilder::from_builder(builder))
+    }
+
+    /// Adds a command that jumps to the next subpass of the current render pass, which accepts
+    /// inline commands.
+    ///
+    /// See `AutoCommandBufferBuilder::next_subpass`.
+    #[inline]
+    pub fn next_subpass(&mut self) -> Result<&mut Self, AutoCommandBufferBuilderContextError> {
+        self.inner.next_subpass(false)?;
+        Ok(self)
+    }
+
+    /// Adds a command that jumps to the next subpass of the current render pass, which is
+    /// recorded in secondary command buffers.
+    ///
+    /// See `AutoCommandBufferBuilder::next_subpass`.
+    #[inline]
+    pub fn next_subpass_secondary(
+        mut self,
+    ) -> Result<
+        TypedAutoCommandBufferBuilder<InsideRenderPassSecondary, P>,
+        AutoCommandBufferBuilderContextError,
+    > {
+        self.inner.next_subpass(true)?;
+        Ok(TypedAutoCommandBufferBuilder::from_builder(self.inner))
+    }
+
+    /// Adds a command that ends the current render pass.
+    ///
+    /// See `AutoCommandBufferBuilder::end_render_pass`.
+    #[inline]
+    pub fn end_render_pass(
+        mut self,
+    ) -> Result<
+        TypedAutoCommandBufferBuilder<OutsideRenderPass, P>,
+        AutoCommandBufferBuilderContextError,
+    > {
+        self.inner.end_render_pass()?;
+        Ok(TypedAutoCommandBufferBuilder::from_builder(self.inner))
+    }
+
+    /// See `AutoCommandBufferBuilder::draw`.
+    #[inline]
+    pub fn draw<V, Gp, S, Pc>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+    {
+        self.inner
+            .draw(pipeline, dynamic, vertex_buffer, sets, constants)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::draw_typed`.
+    #[inline]
+    pub fn draw_typed<V, Gp, L>(
+        &mut self,
+        pipeline: &TypedGraphicsPipeline<Gp, V, L>,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        sets: L::Sets,
+        constants: L::PushConstants,
+    ) -> Result<&mut Self, DrawError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone,
+        L: TypedPipelineLayout,
+    {
+        self.inner
+            .draw_typed(pipeline, dynamic, vertex_buffer, sets, constants)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::draw_indexed`.
+    #[inline]
+    pub fn draw_indexed<V, Gp, S, Pc, Ib, I>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        index_buffer: Ib,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawIndexedError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+        Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
+        I: Index + 'static,
+    {
+        self.inner.draw_indexed(
+            pipeline,
+            dynamic,
+            vertex_buffer,
+            index_buffer,
+            sets,
+            constants,
+        )?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::draw_indirect`.
+    #[inline]
+    pub fn draw_indirect<V, Gp, S, Pc, Ib>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        indirect_buffer: Ib,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawIndirectError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+        Ib: BufferAccess
+            + TypedBufferAccess<Content = [DrawIndirectCommand]>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.inner.draw_indirect(
+            pipeline,
+            dynamic,
+            vertex_buffer,
+            indirect_buffer,
+            sets,
+            constants,
+        )?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::draw_indexed_indirect`.
+    #[inline]
+    pub fn draw_indexed_indirect<V, Gp, S, Pc, Ib, Inb, I>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        index_buffer: Ib,
+        indirect_buffer: Inb,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawIndexedIndirectError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+        Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
+        Inb: BufferAccess
+            + TypedBufferAccess<Content = [DrawIndexedIndirectCommand]>
+            + Send
+            + Sync
+            + 'static,
+        I: Index + 'static,
+    {
+        self.inner.draw_indexed_indirect(
+            pipeline,
+            dynamic,
+            vertex_buffer,
+            index_buffer,
+            indirect_buffer,
+            sets,
+            constants,
+        )?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::draw_indirect_count`.
+    #[inline]
+    pub fn draw_indirect_count<V, Gp, S, Pc, Ib, Cb>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        indirect_buffer: Ib,
+        count_buffer: Cb,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawIndirectError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+        Ib: BufferAccess
+            + TypedBufferAccess<Content = [DrawIndirectCommand]>
+            + Send
+            + Sync
+            + 'static,
+        Cb: BufferAccess + TypedBufferAccess<Content = u32> + Send + Sync + 'static,
+    {
+        self.inner.draw_indirect_count(
+            pipeline,
+            dynamic,
+            vertex_buffer,
+            indirect_buffer,
+            count_buffer,
+            sets,
+            constants,
+        )?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::draw_indexed_indirect_count`.
+    #[inline]
+    pub fn draw_indexed_indirect_count<V, Gp, S, Pc, Ib, Inb, Cb, I>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        vertex_buffer: V,
+        index_buffer: Ib,
+        indirect_buffer: Inb,
+        count_buffer: Cb,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawIndexedIndirectError>
+    where
+        Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+        Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
+        Inb: BufferAccess
+            + TypedBufferAccess<Content = [DrawIndexedIndirectCommand]>
+            + Send
+            + Sync
+            + 'static,
+        Cb: BufferAccess + TypedBufferAccess<Content = u32> + Send + Sync + 'static,
+        I: Index + 'static,
+    {
+        self.inner.draw_indexed_indirect_count(
+            pipeline,
+            dynamic,
+            vertex_buffer,
+            index_buffer,
+            indirect_buffer,
+            count_buffer,
+            sets,
+            constants,
+        )?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::draw_mesh_tasks`.
+    #[inline]
+    pub fn draw_mesh_tasks<Gp, S, Pc>(
+        &mut self,
+        group_count: [u32; 3],
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawMeshTasksError>
+    where
+        Gp: GraphicsPipelineAbstract + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+    {
+        self.inner
+            .draw_mesh_tasks(group_count, pipeline, dynamic, sets, constants)?;
+        Ok(self)
+    }
+
+    /// See `AutoCommandBufferBuilder::draw_mesh_tasks_indirect`.
+    #[inline]
+    pub fn draw_mesh_tasks_indirect<Gp, S, Pc, Ib>(
+        &mut self,
+        pipeline: Gp,
+        dynamic: &DynamicState,
+        indirect_buffer: Ib,
+        sets: S,
+        constants: Pc,
+    ) -> Result<&mut Self, DrawMeshTasksIndirectError>
+    where
+        Gp: GraphicsPipelineAbstract + Send + Sync + 'static + Clone,
+        S: DescriptorSetsCollection,
+        Ib: BufferAccess
+            + TypedBufferAccess<Content = [DrawMeshTasksIndirectCommand]>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.inner
+            .draw_mesh_tasks_indirect(pipeline, dynamic, indirect_buffer, sets, constants)?;
+        Ok(self)
+    }
+}
+
+impl<P> TypedAutoCommandBufferBuilder<InsideRenderPassSecondary, P> {
+    /// Wraps around a builder that is inside a subpass whose commands are recorded in secondary
+    /// command buffers.
+    #[inline]
+    pub fn from_inside_render_pass_secondary(
+        builder: AutoCommandBufferBuilder<P>,
+    ) -> Result<
+        TypedAutoCommandBufferBuilder<InsideRenderPassSecondary, P>,
+        AutoCommandBufferBuilderContextError,
+    > {
+        builder.ensure_inside_render_pass_secondary()?;
+        Ok(TypedAutoCommandBufferBuilder::from_builder(builder))
+    }
+
+    /// Adds a command that jumps to the next subpass of the current render pass, which accepts
+    /// inline commands.
+    ///
+    /// See `AutoCommandBufferBuilder::next_subpass`.
+    #[inline]
+    pub fn next_subpass_inline(
+        mut self,
+    ) -> Result<
+        TypedAutoCommandBufferBuilder<InsideRenderPass, P>,
+        AutoCommandBufferBuilderContextError,
+    > {
+        self.inner.next_subpass(false)?;
+        Ok(TypedAutoCommandBufferBuilder::from_builder(self.inner))
+    }
+
+    /// Adds a command that jumps to the next subpass of the current render pass, which is
+    /// recorded in secondary command buffers as well.
+    ///
+    /// See `AutoCommandBufferBuilder::next_subpass`.
+    #[inline]
+    pub fn next_subpass_secondary(
+        &mut self,
+    ) -> Result<&mut Self, AutoCommandBufferBuilderContextError> {
+        self.inner.next_subpass(true)?;
+        Ok(self)
+    }
+
+    /// Adds a command that ends the current render pass.
+    ///
+    /// See `AutoCommandBufferBuilder::end_render_pass`.
+    #[inline]
+    pub fn end_render_pass(
+        mut self,
+    ) -> Result<
+        TypedAutoCommandBufferBuilder<OutsideRenderPass, P>,
+        AutoCommandBufferBuilderContextError,
+    > {
+        self.inner.end_render_pass()?;
+        Ok(TypedAutoCommandBufferBuilder::from_builder(self.inner))
+    }
+
+    /// See `AutoCommandBufferBuilder::execute_commands`.
+    ///
+    /// # Safety
+    ///
+    /// The same as `AutoCommandBufferBuilder::execute_commands`, whose safety checks and
+    /// synchronization are not implemented yet.
+    #[inline]
+    pub unsafe fn execute_commands<C>(
+        &mut self,
+        command_buffer: C,
+    ) -> Result<&mut Self, ExecuteCommandsError>
+    where
+        C: CommandBuffer + Send + Sync + 'static,
+    {
+        self.inner.execute_commands(command_buffer)?;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::AutoCommandBufferBuilderContextError;
+    use command_buffer::CommandBuffer;
+    use command_buffer::TypedAutoCommandBufferBuilder;
+    use format::ClearValue;
+    use format::Format;
+    use framebuffer::Framebuffer;
+    use image::AttachmentImage;
+    use std::sync::Arc;
+    use sync::GpuFuture;
+
+    #[test]
+    fn copy_outside_render_pass() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let source =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 12u32)
+                .unwrap();
+        let destination =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0u32)
+                .unwrap();
+
+        let builder = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        let mut builder = TypedAutoCommandBufferBuilder::new(builder).unwrap();
+        builder.copy_buffer(source, destination.clone()).unwrap();
+        let _ = builder
+            .build()
+            .unwrap()
+            .execute(queue.clone())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+
+        assert_eq!(*destination.read().unwrap(), 12);
+    }
+
+    #[test]
+    fn inside_requires_render_pass() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let builder = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        match TypedAutoCommandBufferBuilder::from_inside_render_pass(builder) {
+            Err(AutoCommandBufferBuilderContextError::ForbiddenOutsideRenderPass) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn subpasses_tracked() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let render_pass = Arc::new(
+            ::ordered_passes_renderpass!(device.clone(),
+                attachments: {
+                    color: {
+                        load: DontCare,
+                        store: Store,
+                        format: Format::R8G8B8A8Unorm,
+                        samples: 1,
+                    }
+                },
+                passes: [
+                    { color: [color], depth_stencil: {}, input: [] },
+                    { color: [color], depth_stencil: {}, input: [] }
+                ]
+            )
+            .unwrap(),
+        );
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm).unwrap();
+        let framebuffer = Arc::new(
+            Framebuffer::start(render_pass)
+                .add(image)
+                .unwrap()
+                .build()
+                .unwrap(),
+        );
+
+        // Ending the render pass before going through all the subpasses is an error.
+        let builder = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        let builder = TypedAutoCommandBufferBuilder::new(builder)
+            .unwrap()
+            .begin_render_pass(framebuffer.clone(), vec![ClearValue::None])
+            .unwrap();
+        match builder.end_render_pass() {
+            Err(AutoCommandBufferBuilderContextError::NumSubpassesMismatch {
+                actual: 2,
+                current: 0,
+            }) => (),
+            _ => panic!(),
+        }
+
+        let builder = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        assert_eq!(builder.current_subpass(), None);
+        let mut builder = TypedAutoCommandBufferBuilder::new(builder)
+            .unwrap()
+            .begin_render_pass(framebuffer, vec![ClearValue::None])
+            .unwrap();
+        assert_eq!(builder.inner().current_subpass(), Some(0));
+        builder.next_subpass().unwrap();
+        assert_eq!(builder.inner().current_subpass(), Some(1));
+
+        // There is no third subpass.
+        match builder.next_subpass() {
+            Err(AutoCommandBufferBuilderContextError::NumSubpassesMismatch {
+                actual: 2,
+                current: 1,
+            }) => (),
+            _ => panic!(),
+        }
+
+        let builder = builder.end_render_pass().unwrap();
+        assert_eq!(builder.inner().current_subpass(), None);
+        builder.build().unwrap();
+    }
+
+    #[test]
+    fn secondary_subpasses_tracked() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let render_pass = Arc::new(
+            ::ordered_passes_renderpass!(device.clone(),
+                attachments: {
+                    color: {
+                        load: DontCare,
+                        store: Store,
+                        format: Format::R8G8B8A8Unorm,
+                        samples: 1,
+                    }
+                },
+                passes: [
+                    { color: [color], depth_stencil: {}, input: [] },
+                    { color: [color], depth_stencil: {}, input: [] }
+                ]
+            )
+            .unwrap(),
+        );
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm).unwrap();
+        let framebuffer = Arc::new(
+            Framebuffer::start(render_pass)
+                .add(image)
+                .unwrap()
+                .build()
+                .unwrap(),
+        );
+
+        let builder = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        match TypedAutoCommandBufferBuilder::from_inside_render_pass_secondary(builder) {
+            Err(AutoCommandBufferBuilderContextError::ForbiddenOutsideRenderPass) => (),
+            _ => panic!(),
+        }
+
+        let builder = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        let builder = TypedAutoCommandBufferBuilder::new(builder)
+            .unwrap()
+            .begin_render_pass_secondary(framebuffer, vec![ClearValue::None])
+            .unwrap();
+        assert_eq!(builder.inner().current_subpass(), Some(0));
+
+        // The subpass isn't inline.
+        let builder = builder.into_inner();
+        match TypedAutoCommandBufferBuilder::from_inside_render_pass(builder) {
+            Err(AutoCommandBufferBuilderContextError::WrongSubpassType) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn secondary_then_inline_subpass() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let render_pass = Arc::new(
+            ::ordered_passes_renderpass!(device.clone(),
+                attachments: {
+                    color: {
+                        load: DontCare,
+                        store: Store,
+                        format: Format::R8G8B8A8Unorm,
+                        samples: 1,
+                    }
+                },
+                passes: [
+                    { color: [color], depth_stencil: {}, input: [] },
+                    { color: [color], depth_stencil: {}, input: [] }
+                ]
+            )
+            .unwrap(),
+        );
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm).unwrap();
+        let framebuffer = Arc::new(
+            Framebuffer::start(render_pass)
+                .add(image)
+                .unwrap()
+                .build()
+                .unwrap(),
+        );
+
+        let builder = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        let builder = TypedAutoCommandBufferBuilder::new(builder)
+            .unwrap()
+            .begin_render_pass_secondary(framebuffer, vec![ClearValue::None])
+            .unwrap()
+            .next_subpass_inline()
+            .unwrap();
+        assert_eq!(builder.inner().current_subpass(), Some(1));
+        builder.end_render_pass().unwrap().build().unwrap();
+    }
+}
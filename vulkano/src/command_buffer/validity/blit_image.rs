@@ -10,6 +10,7 @@
 use std::error;
 use std::fmt;
 
+use command_buffer::ImageBlitRegion;
 use device::Device;
 use format::FormatTy;
 use image::ImageAccess;
@@ -89,6 +90,10 @@ where
         }
     }
 
+    if filter == Filter::Linear && !source_inner.image.supports_linear_filtering() {
+        return Err(CheckBlitImageError::LinearFilterNotSupported);
+    }
+
     let types_should_be_same = source_format_ty == FormatTy::Uint
         || destination_format_ty == FormatTy::Uint
         || source_format_ty == FormatTy::Sint
@@ -218,7 +223,54 @@ where
     Ok(())
 }
 
-/// Error that can happen from `check_clear_color_image`.
+/// Checks whether a blit image command with explicit regions is valid.
+///
+/// Each region is checked like with `check_blit_image`. In addition, the aspect of each region
+/// must exist in the formats of both the source and the destination.
+///
+/// # Panic
+///
+/// - Panics if the source or the destination was not created with `device`.
+///
+pub fn check_blit_image_regions<S, D>(
+    device: &Device,
+    source: &S,
+    destination: &D,
+    regions: &[ImageBlitRegion],
+    filter: Filter,
+) -> Result<(), CheckBlitImageError>
+where
+    S: ?Sized + ImageAccess,
+    D: ?Sized + ImageAccess,
+{
+    for (region_index, region) in regions.iter().enumerate() {
+        if !region.aspect.is_in_format(source.format())
+            || !region.aspect.is_in_format(destination.format())
+        {
+            return Err(CheckBlitImageError::AspectNotInFormat { region_index });
+        }
+
+        check_blit_image(
+            device,
+            source,
+            region.source_top_left,
+            region.source_bottom_right,
+            region.source_base_array_layer,
+            region.source_mip_level,
+            destination,
+            region.destination_top_left,
+            region.destination_bottom_right,
+            region.destination_base_array_layer,
+            region.destination_mip_level,
+            region.layer_count,
+            filter,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_blit_image`.
 #[derive(Debug, Copy, Clone)]
 pub enum CheckBlitImageError {
     /// The source is missing the transfer source usage.
@@ -233,6 +285,8 @@ pub enum CheckBlitImageError {
     DepthStencilNearestMandatory,
     /// The format of the source and destination must be equal when blitting depth/stencil images.
     DepthStencilFormatMismatch,
+    /// The format of the source image doesn't support linear filtering.
+    LinearFilterNotSupported,
     /// The types of the source format and the destination format aren't compatible.
     IncompatibleFormatsTypes {
         source_format_ty: FormatTy,
@@ -246,6 +300,11 @@ pub enum CheckBlitImageError {
     DestinationCoordinatesOutOfRange,
     /// The top-left and/or bottom-right coordinates are incompatible with the image type.
     IncompatibleRangeForImageType,
+    /// The aspect of a region doesn't exist in the format of the source or of the destination.
+    AspectNotInFormat {
+        /// Index of the region.
+        region_index: usize,
+    },
 }
 
 impl error::Error for CheckBlitImageError {}
@@ -276,6 +335,9 @@ impl fmt::Display for CheckBlitImageError {
                     "the format of the source and destination must be equal when blitting \
                  depth/stencil images"
                 }
+                CheckBlitImageError::LinearFilterNotSupported => {
+                    "the format of the source image doesn't support linear filtering"
+                }
                 CheckBlitImageError::IncompatibleFormatsTypes { .. } => {
                     "the types of the source format and the destination format aren't compatible"
                 }
@@ -293,7 +355,102 @@ impl fmt::Display for CheckBlitImageError {
                 CheckBlitImageError::IncompatibleRangeForImageType => {
                     "the top-left and/or bottom-right coordinates are incompatible with the image type"
                 }
+                CheckBlitImageError::AspectNotInFormat { .. } => {
+                    "the aspect of a region doesn't exist in the format of the source or of the \
+                     destination"
+                }
             }
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use format::Format;
+    use image::AttachmentImage;
+    use image::ImageAspect;
+    use image::ImageUsage;
+
+    #[test]
+    fn multisampled_source() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let usage = ImageUsage {
+            transfer_source: true,
+            transfer_destination: true,
+            ..ImageUsage::none()
+        };
+        let source = AttachmentImage::multisampled_with_usage(
+            device.clone(),
+            [32, 32],
+            4,
+            Format::R8G8B8A8Unorm,
+            usage,
+        )
+        .unwrap();
+        let destination =
+            AttachmentImage::with_usage(device.clone(), [32, 32], Format::R8G8B8A8Unorm, usage)
+                .unwrap();
+
+        match check_blit_image(
+            &device,
+            &source,
+            [0, 0, 0],
+            [32, 32, 1],
+            0,
+            0,
+            &destination,
+            [0, 0, 0],
+            [32, 32, 1],
+            0,
+            0,
+            1,
+            Filter::Nearest,
+        ) {
+            Err(CheckBlitImageError::UnexpectedMultisampled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn region_aspect_not_in_format() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let usage = ImageUsage {
+            transfer_source: true,
+            transfer_destination: true,
+            ..ImageUsage::none()
+        };
+        let source =
+            AttachmentImage::with_usage(device.clone(), [32, 32], Format::R8G8B8A8Unorm, usage)
+                .unwrap();
+        let destination =
+            AttachmentImage::with_usage(device.clone(), [32, 32], Format::R8G8B8A8Unorm, usage)
+                .unwrap();
+
+        let region = |aspect| ImageBlitRegion {
+            aspect,
+            source_top_left: [0, 0, 0],
+            source_bottom_right: [32, 32, 1],
+            source_base_array_layer: 0,
+            source_mip_level: 0,
+            destination_top_left: [0, 0, 0],
+            destination_bottom_right: [16, 16, 1],
+            destination_base_array_layer: 0,
+            destination_mip_level: 0,
+            layer_count: 1,
+        };
+
+        match check_blit_image_regions(
+            &device,
+            &source,
+            &destination,
+            &[region(ImageAspect::Color), region(ImageAspect::Depth)],
+            Filter::Nearest,
+        ) {
+            Err(CheckBlitImageError::AspectNotInFormat { region_index: 1 }) => (),
+            _ => panic!(),
+        }
+    }
+}
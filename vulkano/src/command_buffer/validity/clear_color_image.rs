@@ -11,6 +11,8 @@ use std::error;
 use std::fmt;
 
 use device::Device;
+use format::ClearValue;
+use format::FormatTy;
 use image::ImageAccess;
 use VulkanObject;
 
@@ -27,6 +29,7 @@ pub fn check_clear_color_image<I>(
     num_layers: u32,
     first_mipmap: u32,
     num_mipmaps: u32,
+    color: ClearValue,
 ) -> Result<(), CheckClearColorImageError>
 where
     I: ?Sized + ImageAccess,
@@ -40,6 +43,18 @@ where
         return Err(CheckClearColorImageError::MissingTransferUsage);
     }
 
+    // Depth, stencil and compressed images can't be cleared with `vkCmdClearColorImage`.
+    let format_ty = image.format().ty();
+    match (format_ty, color) {
+        (FormatTy::Float, ClearValue::Float(_)) => (),
+        (FormatTy::Uint, ClearValue::Uint(_)) => (),
+        (FormatTy::Sint, ClearValue::Int(_)) => (),
+        (FormatTy::Float, _) | (FormatTy::Uint, _) | (FormatTy::Sint, _) => {
+            return Err(CheckClearColorImageError::ClearValueTypeMismatch);
+        }
+        _ => return Err(CheckClearColorImageError::NotColorFormat),
+    }
+
     if first_layer + num_layers > image.dimensions().array_layers() {
         return Err(CheckClearColorImageError::OutOfRange);
     }
@@ -56,6 +71,12 @@ where
 pub enum CheckClearColorImageError {
     /// The image is missing the transfer destination usage.
     MissingTransferUsage,
+    /// The image doesn't have a color format, or has a compressed format.
+    NotColorFormat,
+    /// The type of the clear value doesn't match the format of the image. `Unorm`, `Snorm`,
+    /// `Sfloat` and `Srgb` formats must be cleared with `ClearValue::Float`, `Uint` formats with
+    /// `ClearValue::Uint` and `Sint` formats with `ClearValue::Int`.
+    ClearValueTypeMismatch,
     /// The array layers and mipmap levels are out of range.
     OutOfRange,
 }
@@ -72,6 +93,12 @@ impl fmt::Display for CheckClearColorImageError {
                 CheckClearColorImageError::MissingTransferUsage => {
                     "the image is missing the transfer destination usage"
                 }
+                CheckClearColorImageError::NotColorFormat => {
+                    "the image doesn't have a color format, or has a compressed format"
+                }
+                CheckClearColorImageError::ClearValueTypeMismatch => {
+                    "the type of the clear value doesn't match the format of the image"
+                }
                 CheckClearColorImageError::OutOfRange => {
                     "the array layers and mipmap levels are out of range"
                 }
@@ -79,3 +106,28 @@ impl fmt::Display for CheckClearColorImageError {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use format::Format;
+    use image::AttachmentImage;
+    use image::ImageUsage;
+
+    #[test]
+    fn integer_format_float_value() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let usage = ImageUsage {
+            transfer_destination: true,
+            ..ImageUsage::none()
+        };
+        let image =
+            AttachmentImage::with_usage(device.clone(), [32, 32], Format::R8Uint, usage).unwrap();
+
+        match check_clear_color_image(&device, &image, 0, 1, 0, 1, ClearValue::Float([0.0; 4])) {
+            Err(CheckClearColorImageError::ClearValueTypeMismatch) => (),
+            _ => panic!(),
+        }
+    }
+}
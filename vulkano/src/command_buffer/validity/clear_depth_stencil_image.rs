@@ -0,0 +1,136 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use device::Device;
+use format::ClearValue;
+use image::ImageAccess;
+use VulkanObject;
+
+/// Checks whether a clear depth-stencil image command is valid.
+///
+/// # Panic
+///
+/// - Panics if the destination was not created with `device`.
+///
+pub fn check_clear_depth_stencil_image<I>(
+    device: &Device,
+    image: &I,
+    first_layer: u32,
+    num_layers: u32,
+    first_mipmap: u32,
+    num_mipmaps: u32,
+    clear_value: ClearValue,
+) -> Result<(), CheckClearDepthStencilImageError>
+where
+    I: ?Sized + ImageAccess,
+{
+    assert_eq!(
+        image.inner().image.device().internal_object(),
+        device.internal_object()
+    );
+
+    if !image.inner().image.usage_transfer_destination() {
+        return Err(CheckClearDepthStencilImageError::MissingTransferUsage);
+    }
+
+    if !image.has_depth() && !image.has_stencil() {
+        return Err(CheckClearDepthStencilImageError::NotDepthStencilFormat);
+    }
+
+    // Only the aspects that exist in the image can be cleared.
+    let valid_value = match clear_value {
+        ClearValue::Depth(_) => image.has_depth(),
+        ClearValue::Stencil(_) => image.has_stencil(),
+        ClearValue::DepthStencil(_) => image.has_depth() && image.has_stencil(),
+        _ => false,
+    };
+
+    if !valid_value {
+        return Err(CheckClearDepthStencilImageError::ClearValueTypeMismatch);
+    }
+
+    if first_layer + num_layers > image.dimensions().array_layers() {
+        return Err(CheckClearDepthStencilImageError::OutOfRange);
+    }
+
+    if first_mipmap + num_mipmaps > image.mipmap_levels() {
+        return Err(CheckClearDepthStencilImageError::OutOfRange);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_clear_depth_stencil_image`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckClearDepthStencilImageError {
+    /// The image is missing the transfer destination usage.
+    MissingTransferUsage,
+    /// The image doesn't have a depth and/or stencil format.
+    NotDepthStencilFormat,
+    /// The clear value isn't a depth and/or stencil value, or it clears an aspect that the format
+    /// of the image doesn't have.
+    ClearValueTypeMismatch,
+    /// The array layers and mipmap levels are out of range.
+    OutOfRange,
+}
+
+impl error::Error for CheckClearDepthStencilImageError {}
+
+impl fmt::Display for CheckClearDepthStencilImageError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckClearDepthStencilImageError::MissingTransferUsage => {
+                    "the image is missing the transfer destination usage"
+                }
+                CheckClearDepthStencilImageError::NotDepthStencilFormat => {
+                    "the image doesn't have a depth and/or stencil format"
+                }
+                CheckClearDepthStencilImageError::ClearValueTypeMismatch => {
+                    "the clear value isn't a depth and/or stencil value that matches the format of \
+                     the image"
+                }
+                CheckClearDepthStencilImageError::OutOfRange => {
+                    "the array layers and mipmap levels are out of range"
+                }
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use format::Format;
+    use image::AttachmentImage;
+    use image::ImageUsage;
+
+    #[test]
+    fn stencil_value_on_depth_image() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let usage = ImageUsage {
+            transfer_destination: true,
+            ..ImageUsage::none()
+        };
+        let image =
+            AttachmentImage::with_usage(device.clone(), [32, 32], Format::D16Unorm, usage).unwrap();
+
+        match check_clear_depth_stencil_image(&device, &image, 0, 1, 0, 1, ClearValue::Stencil(0)) {
+            Err(CheckClearDepthStencilImageError::ClearValueTypeMismatch) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use format::ClearValue;
+use format::FormatTy;
+use framebuffer::LoadOp;
+use framebuffer::RenderPassDesc;
+
+/// Checks whether the clear values passed when beginning a render pass match its attachments.
+///
+/// There must be exactly one clear value per attachment. Attachments that are cleared when the
+/// render pass begins must have a clear value whose type matches the type of their format, and
+/// other attachments must have `ClearValue::None`.
+pub fn check_clear_values<R>(
+    render_pass: &R,
+    clear_values: &[ClearValue],
+) -> Result<(), CheckClearValuesError>
+where
+    R: ?Sized + RenderPassDesc,
+{
+    if clear_values.len() != render_pass.num_attachments() {
+        return Err(CheckClearValuesError::WrongCount {
+            expected: render_pass.num_attachments(),
+            obtained: clear_values.len(),
+        });
+    }
+
+    for (attachment_index, clear_value) in clear_values.iter().enumerate() {
+        let desc = render_pass.attachment_desc(attachment_index).unwrap();
+        let ty = desc.format.ty();
+
+        if desc.load != LoadOp::Clear {
+            if *clear_value != ClearValue::None {
+                return Err(CheckClearValuesError::UnexpectedClearValue { attachment_index });
+            }
+
+            continue;
+        }
+
+        let matches = match *clear_value {
+            ClearValue::None => false,
+            ClearValue::Float(_) => ty == FormatTy::Float,
+            ClearValue::Int(_) => ty == FormatTy::Sint,
+            ClearValue::Uint(_) => ty == FormatTy::Uint,
+            ClearValue::Depth(_) => ty == FormatTy::Depth,
+            ClearValue::Stencil(_) => ty == FormatTy::Stencil,
+            ClearValue::DepthStencil(_) => ty == FormatTy::DepthStencil,
+        };
+
+        if !matches {
+            return Err(CheckClearValuesError::WrongType {
+                attachment_index,
+                expected: ty,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking the clear values of a render pass.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckClearValuesError {
+    /// The number of clear values doesn't match the number of attachments.
+    WrongCount {
+        /// Number of attachments in the render pass.
+        expected: usize,
+        /// Number of clear values that were passed.
+        obtained: usize,
+    },
+    /// The clear value of an attachment that is cleared doesn't match the type of its format.
+    WrongType {
+        /// Index of the attachment.
+        attachment_index: usize,
+        /// Type of the format of the attachment.
+        expected: FormatTy,
+    },
+    /// An attachment that isn't cleared was given a clear value other than `ClearValue::None`.
+    UnexpectedClearValue {
+        /// Index of the attachment.
+        attachment_index: usize,
+    },
+}
+
+impl error::Error for CheckClearValuesError {}
+
+impl fmt::Display for CheckClearValuesError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckClearValuesError::WrongCount { .. } => {
+                    "the number of clear values doesn't match the number of attachments"
+                }
+                CheckClearValuesError::WrongType { .. } => {
+                    "the clear value of an attachment doesn't match the type of its format"
+                }
+                CheckClearValuesError::UnexpectedClearValue { .. } => {
+                    "an attachment that isn't cleared was given a clear value"
+                }
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use format::Format;
+
+    #[test]
+    fn clear_values_checked() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let rp = ::single_pass_renderpass!(device.clone(),
+            attachments: {
+                color: {
+                    load: Clear,
+                    store: Store,
+                    format: Format::R8G8B8A8Unorm,
+                    samples: 1,
+                },
+                depth: {
+                    load: DontCare,
+                    store: DontCare,
+                    format: Format::D16Unorm,
+                    samples: 1,
+                }
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {depth}
+            }
+        )
+        .unwrap();
+
+        assert_eq!(
+            check_clear_values(&rp, &[ClearValue::Float([0.0; 4]), ClearValue::None]),
+            Ok(())
+        );
+        assert_eq!(
+            check_clear_values(&rp, &[ClearValue::Float([0.0; 4])]),
+            Err(CheckClearValuesError::WrongCount {
+                expected: 2,
+                obtained: 1,
+            })
+        );
+        assert_eq!(
+            check_clear_values(&rp, &[ClearValue::Uint([0; 4]), ClearValue::None]),
+            Err(CheckClearValuesError::WrongType {
+                attachment_index: 0,
+                expected: FormatTy::Float,
+            })
+        );
+        assert_eq!(
+            check_clear_values(&rp, &[ClearValue::Float([0.0; 4]), ClearValue::Depth(1.0)]),
+            Err(CheckClearValuesError::UnexpectedClearValue {
+                attachment_index: 1
+            })
+        );
+    }
+}
@@ -11,7 +11,9 @@ use std::cmp;
 use std::error;
 use std::fmt;
 
+use buffer::BufferAccess;
 use buffer::TypedBufferAccess;
+use command_buffer::BufferCopyRegion;
 use device::Device;
 use device::DeviceOwned;
 use VulkanObject;
@@ -60,6 +62,83 @@ where
     Ok(CheckCopyBuffer { copy_size })
 }
 
+/// Checks whether a copy buffer command with explicit regions is valid.
+///
+/// Contrary to `check_copy_buffer`, the source and the destination can be the same buffer, as
+/// long as none of the regions that are read overlap one of the regions that are written.
+///
+/// # Panic
+///
+/// - Panics if the source and destination were not created with `device`.
+///
+pub fn check_copy_buffer_regions<S, D>(
+    device: &Device,
+    source: &S,
+    destination: &D,
+    regions: &[BufferCopyRegion],
+) -> Result<(), CheckCopyBufferError>
+where
+    S: ?Sized + BufferAccess,
+    D: ?Sized + BufferAccess,
+{
+    let source_inner = source.inner();
+    let destination_inner = destination.inner();
+
+    assert_eq!(
+        source_inner.buffer.device().internal_object(),
+        device.internal_object()
+    );
+    assert_eq!(
+        destination_inner.buffer.device().internal_object(),
+        device.internal_object()
+    );
+
+    if !source_inner.buffer.usage_transfer_source() {
+        return Err(CheckCopyBufferError::SourceMissingTransferUsage);
+    }
+
+    if !destination_inner.buffer.usage_transfer_destination() {
+        return Err(CheckCopyBufferError::DestinationMissingTransferUsage);
+    }
+
+    for (region_index, region) in regions.iter().enumerate() {
+        if region.size == 0 {
+            return Err(CheckCopyBufferError::EmptyRegion { region_index });
+        }
+
+        if region.size > source.size() || region.source_offset > source.size() - region.size {
+            return Err(CheckCopyBufferError::SourceRegionOutOfRange { region_index });
+        }
+
+        if region.size > destination.size()
+            || region.destination_offset > destination.size() - region.size
+        {
+            return Err(CheckCopyBufferError::DestinationRegionOutOfRange { region_index });
+        }
+    }
+
+    if source_inner.buffer.internal_object() == destination_inner.buffer.internal_object() {
+        for (source_region_index, s) in regions.iter().enumerate() {
+            let source_start = source_inner.offset + s.source_offset;
+            let source_end = source_start + s.size;
+
+            for (destination_region_index, d) in regions.iter().enumerate() {
+                let destination_start = destination_inner.offset + d.destination_offset;
+                let destination_end = destination_start + d.size;
+
+                if source_start < destination_end && destination_start < source_end {
+                    return Err(CheckCopyBufferError::OverlappingRegions {
+                        source_region_index,
+                        destination_region_index,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Information returned if `check_copy_buffer` succeeds.
 pub struct CheckCopyBuffer {
     /// Size of the transfer in bytes.
@@ -70,7 +149,7 @@ pub struct CheckCopyBuffer {
 }
 
 /// Error that can happen from `check_copy_buffer`.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum CheckCopyBufferError {
     /// The source buffer is missing the transfer source usage.
     SourceMissingTransferUsage,
@@ -78,6 +157,29 @@ pub enum CheckCopyBufferError {
     DestinationMissingTransferUsage,
     /// The source and destination are overlapping.
     OverlappingRanges,
+    /// A region has a size of 0.
+    EmptyRegion {
+        /// Index of the region.
+        region_index: usize,
+    },
+    /// A region is out of the range of the source.
+    SourceRegionOutOfRange {
+        /// Index of the region.
+        region_index: usize,
+    },
+    /// A region is out of the range of the destination.
+    DestinationRegionOutOfRange {
+        /// Index of the region.
+        region_index: usize,
+    },
+    /// The source and destination are the same buffer, and a region that is read overlaps a
+    /// region that is written.
+    OverlappingRegions {
+        /// Index of the region whose source range overlaps.
+        source_region_index: usize,
+        /// Index of the region whose destination range overlaps.
+        destination_region_index: usize,
+    },
 }
 
 impl error::Error for CheckCopyBufferError {}
@@ -97,7 +199,69 @@ impl fmt::Display for CheckCopyBufferError {
                 }
                 CheckCopyBufferError::OverlappingRanges =>
                     "the source and destination are overlapping",
+                CheckCopyBufferError::EmptyRegion { .. } => "a region has a size of 0",
+                CheckCopyBufferError::SourceRegionOutOfRange { .. } => {
+                    "a region is out of the range of the source"
+                }
+                CheckCopyBufferError::DestinationRegionOutOfRange { .. } => {
+                    "a region is out of the range of the destination"
+                }
+                CheckCopyBufferError::OverlappingRegions { .. } => {
+                    "the source and destination are the same buffer, and a region that is read \
+                     overlaps a region that is written"
+                }
             }
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+
+    #[test]
+    fn regions_within_same_buffer() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let buffer = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage::all(),
+            false,
+            (0..16).map(|_| 0u8),
+        )
+        .unwrap();
+
+        let region = |source_offset, destination_offset, size| BufferCopyRegion {
+            source_offset,
+            destination_offset,
+            size,
+        };
+
+        assert_eq!(
+            check_copy_buffer_regions(&device, &buffer, &buffer, &[region(0, 8, 8)]),
+            Ok(())
+        );
+        assert_eq!(
+            check_copy_buffer_regions(
+                &device,
+                &buffer,
+                &buffer,
+                &[region(0, 8, 4), region(4, 2, 4)]
+            ),
+            Err(CheckCopyBufferError::OverlappingRegions {
+                source_region_index: 0,
+                destination_region_index: 1,
+            })
+        );
+        assert_eq!(
+            check_copy_buffer_regions(&device, &buffer, &buffer, &[region(12, 0, 8)]),
+            Err(CheckCopyBufferError::SourceRegionOutOfRange { region_index: 0 })
+        );
+        assert_eq!(
+            check_copy_buffer_regions(&device, &buffer, &buffer, &[region(0, 8, 0)]),
+            Err(CheckCopyBufferError::EmptyRegion { region_index: 0 })
+        );
+    }
+}
@@ -10,6 +10,7 @@
 use std::error;
 use std::fmt;
 
+use command_buffer::ImageCopyRegion;
 use device::Device;
 use format::FormatTy;
 use format::PossibleCompressedFormatDesc;
@@ -19,8 +20,6 @@ use VulkanObject;
 
 /// Checks whether a copy image command is valid.
 ///
-/// Note that this doesn't check whether `layer_count` is equal to 0. TODO: change that?
-///
 /// # Panic
 ///
 /// - Panics if the source or the destination was not created with `device`.
@@ -38,6 +37,42 @@ pub fn check_copy_image<S, D>(
     extent: [u32; 3],
     layer_count: u32,
 ) -> Result<(), CheckCopyImageError>
+where
+    S: ?Sized + ImageAccess,
+    D: ?Sized + ImageAccess,
+{
+    check_copy_image_regions(
+        device,
+        source,
+        destination,
+        &[ImageCopyRegion {
+            source_offset,
+            source_base_array_layer,
+            source_mip_level,
+            destination_offset,
+            destination_base_array_layer,
+            destination_mip_level,
+            extent,
+            layer_count,
+        }],
+    )
+}
+
+/// Checks whether a copy image command with multiple regions is valid.
+///
+/// The source and the destination can be the same image, as long as none of the regions that are
+/// read overlap one of the regions that are written.
+///
+/// # Panic
+///
+/// - Panics if the source or the destination was not created with `device`.
+///
+pub fn check_copy_image_regions<S, D>(
+    device: &Device,
+    source: &S,
+    destination: &D,
+    regions: &[ImageCopyRegion],
+) -> Result<(), CheckCopyImageError>
 where
     S: ?Sized + ImageAccess,
     D: ?Sized + ImageAccess,
@@ -67,7 +102,6 @@ where
     }
 
     let source_format_ty = source.format().ty();
-    let destination_format_ty = destination.format().ty();
 
     if source_format_ty.is_depth_and_or_stencil() {
         if source.format() != destination.format() {
@@ -88,24 +122,79 @@ where
         });
     }
 
-    let source_dimensions = match source.dimensions().mipmap_dimensions(source_mip_level) {
+    for (region_index, region) in regions.iter().enumerate() {
+        if region.layer_count == 0 || region.extent.iter().any(|&e| e == 0) {
+            return Err(CheckCopyImageError::EmptyRegion { region_index });
+        }
+
+        check_region(source, destination, region)?;
+    }
+
+    if source_inner.image.internal_object() == destination_inner.image.internal_object() {
+        // The regions are relative to the source and the destination, which can start at
+        // different layers and mipmap levels of the image.
+        let absolute = |r: &ImageCopyRegion| ImageCopyRegion {
+            source_base_array_layer: r.source_base_array_layer + source_inner.first_layer as u32,
+            source_mip_level: r.source_mip_level + source_inner.first_mipmap_level as u32,
+            destination_base_array_layer: r.destination_base_array_layer
+                + destination_inner.first_layer as u32,
+            destination_mip_level: r.destination_mip_level
+                + destination_inner.first_mipmap_level as u32,
+            ..*r
+        };
+
+        for (source_region_index, s) in regions.iter().enumerate() {
+            for (destination_region_index, d) in regions.iter().enumerate() {
+                if regions_overlap(&absolute(s), &absolute(d)) {
+                    return Err(CheckCopyImageError::OverlappingRegions {
+                        source_region_index,
+                        destination_region_index,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Checks the coordinates of a region against the dimensions of the images.
+fn check_region<S, D>(
+    source: &S,
+    destination: &D,
+    region: &ImageCopyRegion,
+) -> Result<(), CheckCopyImageError>
+where
+    S: ?Sized + ImageAccess,
+    D: ?Sized + ImageAccess,
+{
+    let source_offset = region.source_offset;
+    let destination_offset = region.destination_offset;
+    let extent = region.extent;
+
+    let source_dimensions = match source
+        .dimensions()
+        .mipmap_dimensions(region.source_mip_level)
+    {
         Some(d) => d,
         None => return Err(CheckCopyImageError::SourceCoordinatesOutOfRange),
     };
 
     let destination_dimensions = match destination
         .dimensions()
-        .mipmap_dimensions(destination_mip_level)
+        .mipmap_dimensions(region.destination_mip_level)
     {
         Some(d) => d,
         None => return Err(CheckCopyImageError::DestinationCoordinatesOutOfRange),
     };
 
-    if source_base_array_layer + layer_count > source_dimensions.array_layers() {
+    if region.source_base_array_layer + region.layer_count > source_dimensions.array_layers() {
         return Err(CheckCopyImageError::SourceCoordinatesOutOfRange);
     }
 
-    if destination_base_array_layer + layer_count > destination_dimensions.array_layers() {
+    if region.destination_base_array_layer + region.layer_count
+        > destination_dimensions.array_layers()
+    {
         return Err(CheckCopyImageError::DestinationCoordinatesOutOfRange);
     }
 
@@ -176,6 +265,32 @@ where
     Ok(())
 }
 
+// Returns true if the part of the image read by `source` overlaps the part written by
+// `destination`, supposing that both are regions of the same image.
+fn regions_overlap(source: &ImageCopyRegion, destination: &ImageCopyRegion) -> bool {
+    if source.source_mip_level != destination.destination_mip_level {
+        return false;
+    }
+
+    let source_layers =
+        source.source_base_array_layer..source.source_base_array_layer + source.layer_count;
+    let destination_layers = destination.destination_base_array_layer
+        ..destination.destination_base_array_layer + destination.layer_count;
+    if source_layers.start >= destination_layers.end
+        || destination_layers.start >= source_layers.end
+    {
+        return false;
+    }
+
+    (0..3).all(|axis| {
+        let source_start = source.source_offset[axis] as i64;
+        let source_end = source_start + source.extent[axis] as i64;
+        let destination_start = destination.destination_offset[axis] as i64;
+        let destination_end = destination_start + destination.extent[axis] as i64;
+        source_start < destination_end && destination_start < source_end
+    })
+}
+
 /// Error that can happen from `check_copy_image`.
 #[derive(Debug, Copy, Clone)]
 pub enum CheckCopyImageError {
@@ -198,6 +313,19 @@ pub enum CheckCopyImageError {
     DestinationCoordinatesOutOfRange,
     /// The offsets or extent are incompatible with the image type.
     IncompatibleRangeForImageType,
+    /// A region has no layer or an extent of 0.
+    EmptyRegion {
+        /// Index of the region.
+        region_index: usize,
+    },
+    /// The source and destination are the same image, and a region that is read overlaps a
+    /// region that is written.
+    OverlappingRegions {
+        /// Index of the region whose source overlaps.
+        source_region_index: usize,
+        /// Index of the region whose destination overlaps.
+        destination_region_index: usize,
+    },
 }
 
 impl error::Error for CheckCopyImageError {}
@@ -236,7 +364,50 @@ impl fmt::Display for CheckCopyImageError {
                 CheckCopyImageError::IncompatibleRangeForImageType => {
                     "the offsets or extent are incompatible with the image type"
                 }
+                CheckCopyImageError::EmptyRegion { .. } => {
+                    "a region has no layer or an extent of 0"
+                }
+                CheckCopyImageError::OverlappingRegions { .. } => {
+                    "the source and destination are the same image, and a region that is read \
+                     overlaps a region that is written"
+                }
             }
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::regions_overlap;
+    use command_buffer::ImageCopyRegion;
+
+    #[test]
+    fn overlapping_regions() {
+        let region = ImageCopyRegion {
+            source_offset: [0, 0, 0],
+            source_base_array_layer: 0,
+            source_mip_level: 0,
+            destination_offset: [8, 0, 0],
+            destination_base_array_layer: 0,
+            destination_mip_level: 0,
+            extent: [8, 8, 1],
+            layer_count: 1,
+        };
+        assert!(!regions_overlap(&region, &region));
+
+        let other = ImageCopyRegion {
+            source_offset: [4, 4, 0],
+            destination_offset: [12, 4, 0],
+            ..region
+        };
+        assert!(regions_overlap(&other, &region));
+        assert!(!regions_overlap(&region, &other));
+
+        let other_mip = ImageCopyRegion {
+            destination_offset: [0, 0, 0],
+            destination_mip_level: 1,
+            ..region
+        };
+        assert!(!regions_overlap(&region, &other_mip));
+    }
+}
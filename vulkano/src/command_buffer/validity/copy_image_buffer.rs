@@ -9,14 +9,17 @@
 
 use std::error;
 use std::fmt;
+use std::mem;
 
 use buffer::TypedBufferAccess;
+use command_buffer::BufferImageCopyRegion;
 use device::Device;
 use device::DeviceOwned;
 use format::AcceptsPixels;
 use format::Format;
 use format::IncompatiblePixelsType;
 use image::ImageAccess;
+use image::ImageAspect;
 use VulkanObject;
 
 /// Type of operation to check.
@@ -44,6 +47,53 @@ pub fn check_copy_buffer_image<B, I, P>(
     image_num_layers: u32,
     image_mipmap: u32,
 ) -> Result<(), CheckCopyBufferImageError>
+where
+    I: ?Sized + ImageAccess,
+    B: ?Sized + TypedBufferAccess<Content = [P]>,
+    Format: AcceptsPixels<P>, // TODO: use a trait on the image itself instead
+{
+    let image_aspect = if image.has_color() {
+        ImageAspect::Color
+    } else if image.has_depth() {
+        ImageAspect::Depth
+    } else {
+        ImageAspect::Stencil
+    };
+
+    check_copy_buffer_image_region(
+        device,
+        buffer,
+        image,
+        ty,
+        &BufferImageCopyRegion {
+            buffer_offset: 0,
+            buffer_row_length: 0,
+            buffer_image_height: 0,
+            image_aspect: image_aspect,
+            image_mip_level: image_mipmap,
+            image_base_array_layer: image_first_layer,
+            image_layer_count: image_num_layers,
+            image_offset: image_offset,
+            image_extent: image_size,
+        },
+    )
+}
+
+/// Checks whether one region of a copy buffer-image command is valid. Contrary to
+/// `check_copy_buffer_image`, the region can start at an offset in the buffer and have rows and
+/// slices that are not tightly packed.
+///
+/// # Panic
+///
+/// - Panics if the buffer and image were not created with `device`.
+///
+pub fn check_copy_buffer_image_region<B, I, P>(
+    device: &Device,
+    buffer: &B,
+    image: &I,
+    ty: CheckCopyBufferImageTy,
+    region: &BufferImageCopyRegion,
+) -> Result<(), CheckCopyBufferImageError>
 where
     I: ?Sized + ImageAccess,
     B: ?Sized + TypedBufferAccess<Content = [P]>,
@@ -84,12 +134,19 @@ where
         return Err(CheckCopyBufferImageError::UnexpectedMultisampled);
     }
 
-    let image_dimensions = match image.dimensions().mipmap_dimensions(image_mipmap) {
+    if !region.image_aspect.is_in_format(image.format()) {
+        return Err(CheckCopyBufferImageError::AspectNotInFormat);
+    }
+
+    let image_offset = region.image_offset;
+    let image_size = region.image_extent;
+
+    let image_dimensions = match image.dimensions().mipmap_dimensions(region.image_mip_level) {
         Some(d) => d,
         None => return Err(CheckCopyBufferImageError::ImageCoordinatesOutOfRange),
     };
 
-    if image_first_layer + image_num_layers > image_dimensions.array_layers() {
+    if region.image_base_array_layer + region.image_layer_count > image_dimensions.array_layers() {
         return Err(CheckCopyBufferImageError::ImageCoordinatesOutOfRange);
     }
 
@@ -105,10 +162,61 @@ where
         return Err(CheckCopyBufferImageError::ImageCoordinatesOutOfRange);
     }
 
+    // For block-compressed formats, the copy operates on whole blocks. The offset must be at the
+    // start of a block, and the size must be a multiple of the block size except at the edges of
+    // the image.
+    {
+        let (block_width, block_height) = image.format().block_dimensions();
+
+        if image_offset[0] % block_width != 0 || image_offset[1] % block_height != 0 {
+            return Err(CheckCopyBufferImageError::UnalignedBlockCoordinates);
+        }
+
+        if image_size[0] % block_width != 0
+            && image_offset[0] + image_size[0] != image_dimensions.width()
+        {
+            return Err(CheckCopyBufferImageError::UnalignedBlockCoordinates);
+        }
+
+        if image_size[1] % block_height != 0
+            && image_offset[1] + image_size[1] != image_dimensions.height()
+        {
+            return Err(CheckCopyBufferImageError::UnalignedBlockCoordinates);
+        }
+
+        if region.buffer_row_length % block_width != 0
+            || region.buffer_image_height % block_height != 0
+        {
+            return Err(CheckCopyBufferImageError::InvalidBufferRowLength);
+        }
+
+        if let Some(block_size) = image.format().size() {
+            let offset = buffer_inner.offset + region.buffer_offset * mem::size_of::<P>();
+            if offset % block_size != 0 {
+                return Err(CheckCopyBufferImageError::UnalignedBufferOffset);
+            }
+        }
+    }
+
+    if region.buffer_row_length != 0 && region.buffer_row_length < image_size[0] {
+        return Err(CheckCopyBufferImageError::InvalidBufferRowLength);
+    }
+
+    if region.buffer_image_height != 0 && region.buffer_image_height < image_size[1] {
+        return Err(CheckCopyBufferImageError::InvalidBufferRowLength);
+    }
+
     image.format().ensure_accepts()?;
 
     {
-        let required_len = required_len_for_format(image.format(), image_size, image_num_layers);
+        let required_len = region.buffer_offset
+            + required_len_for_format(
+                image.format(),
+                image_size,
+                region.image_layer_count,
+                region.buffer_row_length,
+                region.buffer_image_height,
+            );
         if required_len > buffer.len() {
             return Err(CheckCopyBufferImageError::BufferTooSmall {
                 required_len: required_len,
@@ -124,15 +232,43 @@ where
 
 /// Computes the minimum required len in elements for buffer with image data in specified
 /// format of specified size.
-fn required_len_for_format<P>(format: Format, image_size: [u32; 3], image_num_layers: u32) -> usize
+///
+/// A `row_length` or `image_height` of 0 means that the rows or slices are tightly packed. Only
+/// the last row needs to fit in the buffer, not the padding that follows it.
+fn required_len_for_format<P>(
+    format: Format,
+    image_size: [u32; 3],
+    image_num_layers: u32,
+    row_length: u32,
+    image_height: u32,
+) -> usize
 where
     Format: AcceptsPixels<P>,
 {
     let (block_width, block_height) = format.block_dimensions();
-    let num_blocks = (image_size[0] + block_width - 1) / block_width
-        * ((image_size[1] + block_height - 1) / block_height)
-        * image_size[2]
-        * image_num_layers;
+    let row_length = if row_length == 0 {
+        image_size[0]
+    } else {
+        row_length
+    };
+    let image_height = if image_height == 0 {
+        image_size[1]
+    } else {
+        image_height
+    };
+
+    let num_slices = image_size[2] * image_num_layers;
+    if image_size[0] == 0 || image_size[1] == 0 || num_slices == 0 {
+        return 0;
+    }
+
+    let blocks_per_row = (row_length + block_width - 1) / block_width;
+    let rows_per_slice = (image_height + block_height - 1) / block_height;
+    let num_rows = (image_size[1] + block_height - 1) / block_height;
+    let last_row_blocks = (image_size[0] + block_width - 1) / block_width;
+
+    let num_blocks =
+        ((num_slices - 1) * rows_per_slice + (num_rows - 1)) * blocks_per_row + last_row_blocks;
     let required_len = num_blocks as usize * format.rate() as usize;
 
     return required_len;
@@ -147,43 +283,60 @@ mod tests {
     fn test_required_len_for_format() {
         // issue #1292
         assert_eq!(
-            required_len_for_format::<u8>(Format::BC1_RGBUnormBlock, [2048, 2048, 1], 1),
+            required_len_for_format::<u8>(Format::BC1_RGBUnormBlock, [2048, 2048, 1], 1, 0, 0),
             2097152
         );
         // other test cases
         assert_eq!(
-            required_len_for_format::<u8>(Format::R8G8B8A8Unorm, [2048, 2048, 1], 1),
+            required_len_for_format::<u8>(Format::R8G8B8A8Unorm, [2048, 2048, 1], 1, 0, 0),
             16777216
         );
         assert_eq!(
-            required_len_for_format::<u8>(Format::R4G4UnormPack8, [512, 512, 1], 1),
+            required_len_for_format::<u8>(Format::R4G4UnormPack8, [512, 512, 1], 1, 0, 0),
             262144
         );
         assert_eq!(
-            required_len_for_format::<u8>(Format::R8G8B8Uscaled, [512, 512, 1], 1),
+            required_len_for_format::<u8>(Format::R8G8B8Uscaled, [512, 512, 1], 1, 0, 0),
             786432
         );
         assert_eq!(
-            required_len_for_format::<u8>(Format::R32G32Uint, [512, 512, 1], 1),
+            required_len_for_format::<u8>(Format::R32G32Uint, [512, 512, 1], 1, 0, 0),
             2097152
         );
         assert_eq!(
-            required_len_for_format::<u32>(Format::R32G32Uint, [512, 512, 1], 1),
+            required_len_for_format::<u32>(Format::R32G32Uint, [512, 512, 1], 1, 0, 0),
             524288
         );
         assert_eq!(
-            required_len_for_format::<[u32; 2]>(Format::R32G32Uint, [512, 512, 1], 1),
+            required_len_for_format::<[u32; 2]>(Format::R32G32Uint, [512, 512, 1], 1, 0, 0),
             262144
         );
         assert_eq!(
-            required_len_for_format::<u8>(Format::ASTC_8x8UnormBlock, [512, 512, 1], 1),
+            required_len_for_format::<u8>(Format::ASTC_8x8UnormBlock, [512, 512, 1], 1, 0, 0),
             65536
         );
         assert_eq!(
-            required_len_for_format::<u8>(Format::ASTC_12x12SrgbBlock, [512, 512, 1], 1),
+            required_len_for_format::<u8>(Format::ASTC_12x12SrgbBlock, [512, 512, 1], 1, 0, 0),
             29584
         );
     }
+
+    #[test]
+    fn required_len_with_pitch() {
+        // The padding after the last row isn't required.
+        assert_eq!(
+            required_len_for_format::<u8>(Format::R8G8B8A8Unorm, [16, 16, 1], 1, 32, 0),
+            (15 * 32 + 16) * 4
+        );
+        assert_eq!(
+            required_len_for_format::<u8>(Format::R8Unorm, [16, 16, 1], 2, 16, 20),
+            20 * 16 + 16 * 16
+        );
+        assert_eq!(
+            required_len_for_format::<u8>(Format::BC1_RGBUnormBlock, [8, 8, 1], 1, 16, 0),
+            (4 + 2) * 8
+        );
+    }
 }
 
 /// Error that can happen from `check_copy_buffer_image`.
@@ -199,6 +352,16 @@ pub enum CheckCopyBufferImageError {
     UnexpectedMultisampled,
     /// The image coordinates are out of range.
     ImageCoordinatesOutOfRange,
+    /// The aspect of the copy doesn't exist in the format of the image.
+    AspectNotInFormat,
+    /// The buffer row length or image height is not 0 and is smaller than the extent of the
+    /// copy, or isn't a multiple of the block size of the format.
+    InvalidBufferRowLength,
+    /// The offset or the size of the copy in the image isn't a multiple of the block size of
+    /// the format.
+    UnalignedBlockCoordinates,
+    /// The offset of the buffer isn't a multiple of the size of a texel or block of the format.
+    UnalignedBufferOffset,
     /// The type of pixels in the buffer isn't compatible with the image format.
     WrongPixelType(IncompatiblePixelsType),
     /// The buffer is too small for the copy operation.
@@ -241,6 +404,21 @@ impl fmt::Display for CheckCopyBufferImageError {
                 CheckCopyBufferImageError::ImageCoordinatesOutOfRange => {
                     "the image coordinates are out of range"
                 }
+                CheckCopyBufferImageError::AspectNotInFormat => {
+                    "the aspect of the copy doesn't exist in the format of the image"
+                }
+                CheckCopyBufferImageError::InvalidBufferRowLength => {
+                    "the buffer row length or image height is smaller than the extent of the copy, \
+                     or isn't a multiple of the block size of the format"
+                }
+                CheckCopyBufferImageError::UnalignedBlockCoordinates => {
+                    "the offset or the size of the copy in the image isn't a multiple of the block \
+                     size of the format"
+                }
+                CheckCopyBufferImageError::UnalignedBufferOffset => {
+                    "the offset of the buffer isn't a multiple of the size of a texel or block of \
+                     the format"
+                }
                 CheckCopyBufferImageError::WrongPixelType(_) => {
                     "the type of pixels in the buffer isn't compatible with the image format"
                 }
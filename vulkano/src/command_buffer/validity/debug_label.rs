@@ -0,0 +1,106 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use device::Device;
+
+/// Checks whether a debug label command can be added.
+pub fn check_debug_label(device: &Device, color: [f32; 4]) -> Result<(), CheckDebugLabelError> {
+    if !device.instance().loaded_extensions().ext_debug_utils {
+        return Err(CheckDebugLabelError::ExtensionNotEnabled);
+    }
+
+    if color.iter().any(|&c| c < 0.0 || c > 1.0) {
+        return Err(CheckDebugLabelError::ColorOutOfRange);
+    }
+
+    Ok(())
+}
+
+/// Checks whether a command that ends a debug label region can be added, given the number of
+/// regions that are open in the command buffer.
+pub fn check_end_debug_label(
+    device: &Device,
+    open_labels: u32,
+) -> Result<(), CheckDebugLabelError> {
+    if open_labels == 0 {
+        return Err(CheckDebugLabelError::NoLabelOpen);
+    }
+
+    if !device.instance().loaded_extensions().ext_debug_utils {
+        return Err(CheckDebugLabelError::ExtensionNotEnabled);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when adding a debug label command.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckDebugLabelError {
+    /// The `ext_debug_utils` extension is not enabled on the instance.
+    ExtensionNotEnabled,
+    /// The components of the color must be between 0.0 and 1.0.
+    ColorOutOfRange,
+    /// There is no debug label region open in the command buffer.
+    NoLabelOpen,
+}
+
+impl error::Error for CheckDebugLabelError {}
+
+impl fmt::Display for CheckDebugLabelError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckDebugLabelError::ExtensionNotEnabled => {
+                    "the `ext_debug_utils` extension is not enabled on the instance"
+                }
+                CheckDebugLabelError::ColorOutOfRange => {
+                    "the components of the color must be between 0.0 and 1.0"
+                }
+                CheckDebugLabelError::NoLabelOpen => {
+                    "there is no debug label region open in the command buffer"
+                }
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requires_extension() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        assert_eq!(
+            check_debug_label(&device, [1.0; 4]),
+            Err(CheckDebugLabelError::ExtensionNotEnabled)
+        );
+    }
+
+    #[test]
+    fn end_requires_open_label() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        assert_eq!(
+            check_end_debug_label(&device, 0),
+            Err(CheckDebugLabelError::NoLabelOpen)
+        );
+        assert_eq!(
+            check_end_debug_label(&device, 1),
+            Err(CheckDebugLabelError::ExtensionNotEnabled)
+        );
+    }
+}
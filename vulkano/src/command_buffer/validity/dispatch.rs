@@ -11,6 +11,8 @@ use std::error;
 use std::fmt;
 
 use device::Device;
+use pipeline::ComputePipelineAbstract;
+use version::Version;
 
 /// Checks whether the dispatch dimensions are supported by the device.
 pub fn check_dispatch(device: &Device, dimensions: [u32; 3]) -> Result<(), CheckDispatchError> {
@@ -29,6 +31,66 @@ pub fn check_dispatch(device: &Device, dimensions: [u32; 3]) -> Result<(), Check
     Ok(())
 }
 
+/// Checks whether a dispatch with non-zero base workgroup coordinates is supported by the device
+/// and by `pipeline`.
+///
+/// The workgroups whose coordinates range from `base` to `base + dimensions` must all be within
+/// the device's limits.
+pub fn check_dispatch_base<Cp>(
+    device: &Device,
+    pipeline: &Cp,
+    base: [u32; 3],
+    dimensions: [u32; 3],
+) -> Result<(), CheckDispatchError>
+where
+    Cp: ?Sized + ComputePipelineAbstract,
+{
+    if !dispatch_base_supported(device) {
+        return Err(CheckDispatchError::DispatchBaseNotSupported);
+    }
+
+    if !pipeline.dispatch_base() {
+        return Err(CheckDispatchError::PipelineDispatchBaseNotEnabled);
+    }
+
+    let max = device
+        .physical_device()
+        .limits()
+        .max_compute_work_group_count();
+
+    check_base_dimensions(base, dimensions, max)
+}
+
+/// Returns true if `device` supports dispatching with a base workgroup, which requires Vulkan 1.1
+/// or the `khr_device_group` extension.
+pub fn dispatch_base_supported(device: &Device) -> bool {
+    let vulkan_1_1 = Version {
+        major: 1,
+        minor: 1,
+        patch: 0,
+    };
+
+    device.api_version() >= vulkan_1_1 || device.loaded_extensions().khr_device_group
+}
+
+fn check_base_dimensions(
+    base: [u32; 3],
+    dimensions: [u32; 3],
+    max: [u32; 3],
+) -> Result<(), CheckDispatchError> {
+    for i in 0..3 {
+        if base[i] as u64 + dimensions[i] as u64 > max[i] as u64 {
+            return Err(CheckDispatchError::UnsupportedBaseDimensions {
+                base,
+                requested: dimensions,
+                max_supported: max,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 /// Error that can happen when checking dispatch command validity.
 #[derive(Debug, Copy, Clone)]
 pub enum CheckDispatchError {
@@ -39,6 +101,20 @@ pub enum CheckDispatchError {
         /// The actual supported dimensions.
         max_supported: [u32; 3],
     },
+    /// The base workgroup plus the dimensions are too large for the device's limits.
+    UnsupportedBaseDimensions {
+        /// The requested base workgroup.
+        base: [u32; 3],
+        /// The requested dimensions.
+        requested: [u32; 3],
+        /// The actual supported dimensions.
+        max_supported: [u32; 3],
+    },
+    /// Dispatching with a base workgroup requires Vulkan 1.1 or the `khr_device_group`
+    /// extension.
+    DispatchBaseNotSupported,
+    /// The pipeline was not created with `ComputePipeline::with_dispatch_base`.
+    PipelineDispatchBaseNotEnabled,
 }
 
 impl error::Error for CheckDispatchError {}
@@ -53,6 +129,16 @@ impl fmt::Display for CheckDispatchError {
                 CheckDispatchError::UnsupportedDimensions { .. } => {
                     "the dimensions are too large for the device's limits"
                 }
+                CheckDispatchError::UnsupportedBaseDimensions { .. } => {
+                    "the base workgroup plus the dimensions are too large for the device's limits"
+                }
+                CheckDispatchError::DispatchBaseNotSupported => {
+                    "dispatching with a base workgroup requires Vulkan 1.1 or the \
+                     `khr_device_group` extension"
+                }
+                CheckDispatchError::PipelineDispatchBaseNotEnabled => {
+                    "the pipeline was not created with dispatching with a base workgroup enabled"
+                }
             }
         )
     }
@@ -85,4 +171,16 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[test]
+    fn base_dimensions_checked() {
+        assert!(super::check_base_dimensions([4, 0, 0], [4, 1, 1], [8, 8, 8]).is_ok());
+
+        match super::check_base_dimensions([6, 0, 0], [4, 1, 1], [8, 8, 8]) {
+            Err(validity::CheckDispatchError::UnsupportedBaseDimensions { base, .. }) => {
+                assert_eq!(base, [6, 0, 0]);
+            }
+            _ => panic!(),
+        }
+    }
 }
@@ -0,0 +1,183 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+use std::mem;
+use std::ptr;
+
+use device::Device;
+use pipeline::GraphicsPipelineAbstract;
+use vk;
+use VulkanObject;
+
+/// Checks whether mesh tasks can be drawn with `pipeline`.
+///
+/// The number of workgroups is checked against the limits of the task shader if the pipeline has
+/// one, and against the limits of the mesh shader otherwise. Pass `None` as `group_count` for
+/// indirect draws, whose number of workgroups is only known by the GPU.
+pub fn check_draw_mesh_tasks<Gp>(
+    device: &Device,
+    pipeline: &Gp,
+    group_count: Option<[u32; 3]>,
+) -> Result<(), CheckDrawMeshTasksError>
+where
+    Gp: ?Sized + GraphicsPipelineAbstract,
+{
+    if !device.loaded_extensions().ext_mesh_shader {
+        return Err(CheckDrawMeshTasksError::ExtensionNotEnabled);
+    }
+
+    if !pipeline.has_mesh_shader() {
+        return Err(CheckDrawMeshTasksError::NotMeshPipeline);
+    }
+
+    let group_count = match group_count {
+        Some(group_count) => group_count,
+        None => return Ok(()),
+    };
+
+    let properties = mesh_shader_properties(device);
+    if pipeline.has_task_shader() {
+        check_group_count(
+            group_count,
+            properties.maxTaskWorkGroupCount,
+            properties.maxTaskWorkGroupTotalCount,
+        )
+    } else {
+        check_group_count(
+            group_count,
+            properties.maxMeshWorkGroupCount,
+            properties.maxMeshWorkGroupTotalCount,
+        )
+    }
+}
+
+fn check_group_count(
+    group_count: [u32; 3],
+    max: [u32; 3],
+    max_total: u32,
+) -> Result<(), CheckDrawMeshTasksError> {
+    if group_count[0] > max[0] || group_count[1] > max[1] || group_count[2] > max[2] {
+        return Err(CheckDrawMeshTasksError::MaxGroupCountExceeded {
+            requested: group_count,
+            max_supported: max,
+        });
+    }
+
+    let total = group_count[0] as u64 * group_count[1] as u64 * group_count[2] as u64;
+    if total > max_total as u64 {
+        return Err(CheckDrawMeshTasksError::MaxTotalGroupCountExceeded {
+            requested: total,
+            max_supported: max_total,
+        });
+    }
+
+    Ok(())
+}
+
+/// Queries the mesh shader properties of the physical device of `device`.
+fn mesh_shader_properties(device: &Device) -> vk::PhysicalDeviceMeshShaderPropertiesEXT {
+    unsafe {
+        let vk_i = device.instance().pointers();
+
+        let mut mesh_shader_properties = vk::PhysicalDeviceMeshShaderPropertiesEXT {
+            sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_MESH_SHADER_PROPERTIES_EXT,
+            pNext: ptr::null_mut(),
+            ..mem::zeroed()
+        };
+
+        let mut output = vk::PhysicalDeviceProperties2KHR {
+            sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_PROPERTIES_2_KHR,
+            pNext: &mut mesh_shader_properties as *mut _ as *mut _,
+            properties: mem::zeroed(),
+        };
+
+        vk_i.GetPhysicalDeviceProperties2KHR(
+            device.physical_device().internal_object(),
+            &mut output,
+        );
+
+        mesh_shader_properties
+    }
+}
+
+/// Error that can happen when checking whether drawing mesh tasks is valid.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckDrawMeshTasksError {
+    /// The `ext_mesh_shader` extension is not enabled.
+    ExtensionNotEnabled,
+    /// The pipeline doesn't have a mesh shader.
+    NotMeshPipeline,
+    /// The number of workgroups in one of the dimensions is too large for the device's limits.
+    MaxGroupCountExceeded {
+        /// The requested number of workgroups.
+        requested: [u32; 3],
+        /// The maximum number of workgroups in each dimension.
+        max_supported: [u32; 3],
+    },
+    /// The total number of workgroups is too large for the device's limits.
+    MaxTotalGroupCountExceeded {
+        /// The requested total number of workgroups.
+        requested: u64,
+        /// The maximum total number of workgroups.
+        max_supported: u32,
+    },
+}
+
+impl error::Error for CheckDrawMeshTasksError {}
+
+impl fmt::Display for CheckDrawMeshTasksError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckDrawMeshTasksError::ExtensionNotEnabled => {
+                    "the `ext_mesh_shader` extension is not enabled"
+                }
+                CheckDrawMeshTasksError::NotMeshPipeline => {
+                    "the pipeline doesn't have a mesh shader"
+                }
+                CheckDrawMeshTasksError::MaxGroupCountExceeded { .. } => {
+                    "the number of workgroups in one of the dimensions is too large for the \
+                     device's limits"
+                }
+                CheckDrawMeshTasksError::MaxTotalGroupCountExceeded { .. } => {
+                    "the total number of workgroups is too large for the device's limits"
+                }
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_group_count;
+    use super::CheckDrawMeshTasksError;
+
+    #[test]
+    fn group_count_limits() {
+        assert!(check_group_count([4, 4, 1], [8, 8, 8], 16).is_ok());
+
+        match check_group_count([16, 1, 1], [8, 8, 8], 64) {
+            Err(CheckDrawMeshTasksError::MaxGroupCountExceeded { .. }) => (),
+            _ => panic!(),
+        }
+
+        match check_group_count([8, 8, 2], [8, 8, 8], 64) {
+            Err(CheckDrawMeshTasksError::MaxTotalGroupCountExceeded {
+                requested: 128,
+                max_supported: 64,
+            }) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -45,6 +45,14 @@ where
                     obtained: viewports.len(),
                 });
             }
+
+            if viewports.iter().any(|v| v.is_flipped_y())
+                && !device.loaded_extensions().khr_maintenance1
+            {
+                return Err(
+                    CheckDynamicStateValidityError::NegativeViewportHeightWithoutMaintenance1,
+                );
+            }
         } else {
             return Err(CheckDynamicStateValidityError::ViewportsMissing);
         }
@@ -101,6 +109,44 @@ where
         }
     }
 
+    if pipeline.has_dynamic_depth_bias() {
+        if let Some(bias) = state.depth_bias {
+            if bias.clamp != 0.0 && !device.enabled_features().depth_bias_clamp {
+                return Err(CheckDynamicStateValidityError::DepthBiasClampFeatureNotEnabled);
+            }
+        } else {
+            return Err(CheckDynamicStateValidityError::DepthBiasMissing);
+        }
+    } else {
+        if state.depth_bias.is_some() {
+            return Err(CheckDynamicStateValidityError::DepthBiasNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_depth_bounds() {
+        if let Some(ref bounds) = state.depth_bounds {
+            if bounds.start < 0.0 || bounds.start > 1.0 || bounds.end < 0.0 || bounds.end > 1.0 {
+                return Err(CheckDynamicStateValidityError::DepthBoundsOutOfRange);
+            }
+        } else {
+            return Err(CheckDynamicStateValidityError::DepthBoundsMissing);
+        }
+    } else {
+        if state.depth_bounds.is_some() {
+            return Err(CheckDynamicStateValidityError::DepthBoundsNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_blend_constants() {
+        if let None = state.blend_constants {
+            return Err(CheckDynamicStateValidityError::BlendConstantsMissing);
+        }
+    } else {
+        if state.blend_constants.is_some() {
+            return Err(CheckDynamicStateValidityError::BlendConstantsNotDynamic);
+        }
+    }
+
     Ok(())
 }
 
@@ -125,6 +171,9 @@ pub enum CheckDynamicStateValidityError {
         /// Number of viewports that were passed.
         obtained: usize,
     },
+    /// The `khr_maintenance1` extension must be enabled in order to use viewports with a negative
+    /// height.
+    NegativeViewportHeightWithoutMaintenance1,
     /// Passed dynamic scissors, while the pipeline doesn't have scissors set as dynamic.
     ScissorsNotDynamic,
     /// The pipeline has dynamic scissors, but no scissors were passed.
@@ -148,6 +197,24 @@ pub enum CheckDynamicStateValidityError {
     ReferenceNotDynamic,
     /// The pipeline has dynamic reference, but no reference was passed.
     ReferenceMissing,
+    /// Passed a dynamic depth bias, while the pipeline doesn't have depth bias set as dynamic.
+    DepthBiasNotDynamic,
+    /// The pipeline has a dynamic depth bias, but no depth bias was passed.
+    DepthBiasMissing,
+    /// The `depth_bias_clamp` feature must be enabled in order to use a depth bias clamp
+    /// different from 0.0.
+    DepthBiasClampFeatureNotEnabled,
+    /// Passed dynamic depth bounds, while the pipeline doesn't have depth bounds set as dynamic.
+    DepthBoundsNotDynamic,
+    /// The pipeline has dynamic depth bounds, but no depth bounds were passed.
+    DepthBoundsMissing,
+    /// The dynamic depth bounds must be between 0.0 and 1.0.
+    DepthBoundsOutOfRange,
+    /// Passed dynamic blend constants, while the pipeline doesn't have blend constants set as
+    /// dynamic.
+    BlendConstantsNotDynamic,
+    /// The pipeline has dynamic blend constants, but no blend constants were passed.
+    BlendConstantsMissing,
 }
 
 impl error::Error for CheckDynamicStateValidityError {}
@@ -180,6 +247,10 @@ impl fmt::Display for CheckDynamicStateValidityError {
                 CheckDynamicStateValidityError::ViewportsCountMismatch { .. } => {
                     "the number of dynamic viewports doesn't match the expected number of viewports"
                 }
+                CheckDynamicStateValidityError::NegativeViewportHeightWithoutMaintenance1 => {
+                    "the `khr_maintenance1` extension must be enabled in order to use viewports \
+                 with a negative height"
+                }
                 CheckDynamicStateValidityError::ScissorsNotDynamic => {
                     "passed dynamic scissors, while the pipeline doesn't have scissors set as dynamic"
                 }
@@ -207,6 +278,34 @@ impl fmt::Display for CheckDynamicStateValidityError {
                 CheckDynamicStateValidityError::ReferenceMissing => {
                     "the pipeline has dynamic reference, but no reference was passed"
                 }
+                CheckDynamicStateValidityError::DepthBiasNotDynamic => {
+                    "passed a dynamic depth bias, while the pipeline doesn't have depth bias set as \
+                     dynamic"
+                }
+                CheckDynamicStateValidityError::DepthBiasMissing => {
+                    "the pipeline has a dynamic depth bias, but no depth bias was passed"
+                }
+                CheckDynamicStateValidityError::DepthBiasClampFeatureNotEnabled => {
+                    "the `depth_bias_clamp` feature must be enabled in order to use a depth bias \
+                     clamp different from 0.0"
+                }
+                CheckDynamicStateValidityError::DepthBoundsNotDynamic => {
+                    "passed dynamic depth bounds, while the pipeline doesn't have depth bounds set \
+                     as dynamic"
+                }
+                CheckDynamicStateValidityError::DepthBoundsMissing => {
+                    "the pipeline has dynamic depth bounds, but no depth bounds were passed"
+                }
+                CheckDynamicStateValidityError::DepthBoundsOutOfRange => {
+                    "the dynamic depth bounds must be between 0.0 and 1.0"
+                }
+                CheckDynamicStateValidityError::BlendConstantsNotDynamic => {
+                    "passed dynamic blend constants, while the pipeline doesn't have blend \
+                     constants set as dynamic"
+                }
+                CheckDynamicStateValidityError::BlendConstantsMissing => {
+                    "the pipeline has dynamic blend constants, but no blend constants were passed"
+                }
             }
         )
     }
@@ -0,0 +1,141 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use device::Device;
+use device::DeviceOwned;
+use sync::Event;
+use sync::PipelineStages;
+use VulkanObject;
+
+/// Checks whether an event can be set or reset by a command buffer once `stages` have completed.
+///
+/// The stages themselves must also be checked with `check_memory_barrier`, which verifies that
+/// they are not empty and that the features they require are enabled.
+///
+/// # Panic
+///
+/// - Panics if the event was not created with `device`.
+///
+pub fn check_event_command(
+    device: &Device,
+    event: &Event,
+    stages: PipelineStages,
+) -> Result<(), CheckEventCommandError> {
+    assert_eq!(event.device().internal_object(), device.internal_object());
+
+    if stages.host {
+        return Err(CheckEventCommandError::HostStage);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when setting or resetting an event from a command buffer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckEventCommandError {
+    /// The host stage can't be used by a command buffer.
+    HostStage,
+}
+
+impl error::Error for CheckEventCommandError {}
+
+impl fmt::Display for CheckEventCommandError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckEventCommandError::HostStage => {
+                    "the host stage can't be used by a command buffer"
+                }
+            }
+        )
+    }
+}
+
+/// Checks whether a command buffer can wait on `events`.
+///
+/// # Panic
+///
+/// - Panics if one of the events was not created with `device`.
+///
+pub fn check_wait_events<'a, I>(device: &Device, events: I) -> Result<(), CheckWaitEventsError>
+where
+    I: IntoIterator<Item = &'a Event>,
+{
+    let mut num_events = 0;
+
+    for event in events {
+        assert_eq!(event.device().internal_object(), device.internal_object());
+        num_events += 1;
+    }
+
+    if num_events == 0 {
+        return Err(CheckWaitEventsError::NoEvents);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when waiting on events from a command buffer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckWaitEventsError {
+    /// No event was given.
+    NoEvents,
+}
+
+impl error::Error for CheckWaitEventsError {}
+
+impl fmt::Display for CheckWaitEventsError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckWaitEventsError::NoEvents => "no event was given",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_commands_checked() {
+        let (device, _) = gfx_dev_and_queue!();
+        let event = Event::alloc(device.clone()).unwrap();
+
+        let transfer = PipelineStages {
+            transfer: true,
+            ..PipelineStages::none()
+        };
+        let host = PipelineStages {
+            host: true,
+            ..PipelineStages::none()
+        };
+
+        assert_eq!(check_event_command(&device, &event, transfer), Ok(()));
+        assert_eq!(
+            check_event_command(&device, &event, host),
+            Err(CheckEventCommandError::HostStage)
+        );
+        assert_eq!(check_wait_events(&device, Some(&event)), Ok(()));
+        assert_eq!(
+            check_wait_events(&device, None),
+            Err(CheckWaitEventsError::NoEvents)
+        );
+    }
+}
@@ -34,7 +34,7 @@ where
         return Err(CheckFillBufferError::BufferMissingUsage);
     }
 
-    if buffer.inner().offset % 4 != 0 {
+    if buffer.inner().offset % 4 != 0 || buffer.size() % 4 != 0 {
         return Err(CheckFillBufferError::WrongAlignment);
     }
 
@@ -72,6 +72,7 @@ impl fmt::Display for CheckFillBufferError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use buffer::BufferAccess;
     use buffer::BufferUsage;
     use buffer::CpuAccessibleBuffer;
 
@@ -92,6 +93,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn wrong_alignment() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let buffer = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage::transfer_destination(),
+            false,
+            0..100u8,
+        )
+        .unwrap();
+
+        match check_fill_buffer(&device, &buffer.slice(4..50).unwrap()) {
+            Err(CheckFillBufferError::WrongAlignment) => (),
+            _ => panic!(),
+        }
+
+        match check_fill_buffer(&device, &buffer.slice(2..50).unwrap()) {
+            Err(CheckFillBufferError::WrongAlignment) => (),
+            _ => panic!(),
+        }
+
+        match check_fill_buffer(&device, &buffer.slice(4..52).unwrap()) {
+            Ok(_) => (),
+            _ => panic!(),
+        }
+    }
+
     #[test]
     fn wrong_device() {
         let (dev1, queue) = gfx_dev_and_queue!();
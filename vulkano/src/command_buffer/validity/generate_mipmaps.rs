@@ -0,0 +1,165 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use device::Device;
+use format::Format;
+use format::FormatTy;
+use image::ImageAccess;
+use VulkanObject;
+
+/// Checks whether the mipmaps of an image can be generated by blitting each level to the next
+/// one with a linear filter.
+///
+/// # Panic
+///
+/// - Panics if the image was not created with `device`.
+///
+pub fn check_generate_mipmaps<I>(
+    device: &Device,
+    image: &I,
+) -> Result<(), CheckGenerateMipmapsError>
+where
+    I: ?Sized + ImageAccess,
+{
+    let inner = image.inner();
+
+    assert_eq!(
+        inner.image.device().internal_object(),
+        device.internal_object()
+    );
+
+    if !inner.image.usage_transfer_source() {
+        return Err(CheckGenerateMipmapsError::MissingTransferSourceUsage);
+    }
+
+    if !inner.image.usage_transfer_destination() {
+        return Err(CheckGenerateMipmapsError::MissingTransferDestinationUsage);
+    }
+
+    // Depth and stencil images can only be blitted with the nearest filter.
+    if !image.has_color()
+        || !inner.image.supports_blit_source()
+        || !inner.image.supports_blit_destination()
+    {
+        return Err(CheckGenerateMipmapsError::FormatNotSupported);
+    }
+
+    if !inner.image.supports_linear_filtering() {
+        return Err(CheckGenerateMipmapsError::LinearFilterNotSupported);
+    }
+
+    if image.samples() != 1 {
+        return Err(CheckGenerateMipmapsError::UnexpectedMultisampled);
+    }
+
+    Ok(())
+}
+
+/// Checks whether the mipmaps of an image of the given format could be generated, before the
+/// image is created.
+///
+/// This only checks the properties of the format. `check_generate_mipmaps` must still be called
+/// on the image once it is created.
+pub fn check_generate_mipmaps_format(
+    device: &Device,
+    format: Format,
+) -> Result<(), CheckGenerateMipmapsError> {
+    let features = format
+        .properties(device.physical_device())
+        .optimal_tiling_features;
+
+    let has_color = match format.ty() {
+        FormatTy::Float | FormatTy::Uint | FormatTy::Sint | FormatTy::Compressed => true,
+        FormatTy::Depth | FormatTy::Stencil | FormatTy::DepthStencil => false,
+    };
+    if !has_color || !features.blit_src || !features.blit_dst {
+        return Err(CheckGenerateMipmapsError::FormatNotSupported);
+    }
+
+    if !features.sampled_image_filter_linear {
+        return Err(CheckGenerateMipmapsError::LinearFilterNotSupported);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_generate_mipmaps`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckGenerateMipmapsError {
+    /// The image is missing the transfer source usage.
+    MissingTransferSourceUsage,
+    /// The image is missing the transfer destination usage.
+    MissingTransferDestinationUsage,
+    /// The format of the image doesn't support blit operations, or isn't a color format.
+    FormatNotSupported,
+    /// The format of the image doesn't support linear filtering.
+    LinearFilterNotSupported,
+    /// Blitting multisampled images is forbidden.
+    UnexpectedMultisampled,
+}
+
+impl error::Error for CheckGenerateMipmapsError {}
+
+impl fmt::Display for CheckGenerateMipmapsError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckGenerateMipmapsError::MissingTransferSourceUsage => {
+                    "the image is missing the transfer source usage"
+                }
+                CheckGenerateMipmapsError::MissingTransferDestinationUsage => {
+                    "the image is missing the transfer destination usage"
+                }
+                CheckGenerateMipmapsError::FormatNotSupported => {
+                    "the format of the image doesn't support blit operations, or isn't a color format"
+                }
+                CheckGenerateMipmapsError::LinearFilterNotSupported => {
+                    "the format of the image doesn't support linear filtering"
+                }
+                CheckGenerateMipmapsError::UnexpectedMultisampled => {
+                    "blitting multisampled images is forbidden"
+                }
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use format::Format;
+    use image::AttachmentImage;
+
+    #[test]
+    fn missing_transfer_usage() {
+        let (device, _) = gfx_dev_and_queue!();
+        let image = AttachmentImage::new(device.clone(), [32, 32], Format::R8G8B8A8Unorm).unwrap();
+
+        match check_generate_mipmaps(&device, &image) {
+            Err(CheckGenerateMipmapsError::MissingTransferSourceUsage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn format_not_supported() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match check_generate_mipmaps_format(&device, Format::D16Unorm) {
+            Err(CheckGenerateMipmapsError::FormatNotSupported) => (),
+            _ => panic!(),
+        }
+    }
+}
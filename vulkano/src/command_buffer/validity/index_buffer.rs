@@ -9,6 +9,7 @@
 
 use std::error;
 use std::fmt;
+use std::mem;
 
 use buffer::BufferAccess;
 use buffer::TypedBufferAccess;
@@ -40,8 +41,11 @@ where
         return Err(CheckIndexBufferError::BufferMissingUsage);
     }
 
-    // TODO: The sum of offset and the address of the range of VkDeviceMemory object that is
-    //       backing buffer, must be a multiple of the type indicated by indexType
+    // The buffer itself is bound to memory at an offset that satisfies its alignment
+    // requirements, so we only need to check the offset within the buffer.
+    if buffer.inner().offset % mem::size_of::<I>() != 0 {
+        return Err(CheckIndexBufferError::WrongAlignment);
+    }
 
     // TODO: fullDrawIndexUint32 feature
 
@@ -133,6 +137,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn wrong_alignment() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let buffer = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage::index_buffer(),
+            false,
+            0..6u8,
+        )
+        .unwrap();
+        let slice = unsafe { buffer.slice(1..5).unwrap().reinterpret::<[u16]>() };
+
+        match check_index_buffer(&device, &slice) {
+            Err(CheckIndexBufferError::WrongAlignment) => (),
+            _ => panic!(),
+        }
+    }
+
     #[test]
     fn wrong_device() {
         let (dev1, queue) = gfx_dev_and_queue!();
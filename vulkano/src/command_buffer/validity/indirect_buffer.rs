@@ -0,0 +1,223 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use buffer::BufferAccess;
+use device::Device;
+use device::DeviceOwned;
+use VulkanObject;
+
+/// Checks whether an indirect buffer can be bound.
+///
+/// # Panic
+///
+/// - Panics if the buffer was not created with `device`.
+///
+pub fn check_indirect_buffer<B>(device: &Device, buffer: &B) -> Result<(), CheckIndirectBufferError>
+where
+    B: ?Sized + BufferAccess,
+{
+    assert_eq!(
+        buffer.inner().buffer.device().internal_object(),
+        device.internal_object()
+    );
+
+    if !buffer.inner().buffer.usage_indirect_buffer() {
+        return Err(CheckIndirectBufferError::BufferMissingUsage);
+    }
+
+    Ok(())
+}
+
+/// Checks whether a buffer can be used as the count buffer of an indirect draw command, which
+/// reads the number of draws from the buffer, and whether the device supports performing up to
+/// `max_draw_count` draws with this command.
+///
+/// # Panic
+///
+/// - Panics if the buffer was not created with `device`.
+///
+pub fn check_indirect_count_buffer<B>(
+    device: &Device,
+    count_buffer: &B,
+    max_draw_count: u32,
+) -> Result<(), CheckIndirectBufferError>
+where
+    B: ?Sized + BufferAccess,
+{
+    assert_eq!(
+        count_buffer.inner().buffer.device().internal_object(),
+        device.internal_object()
+    );
+
+    if !device.loaded_extensions().khr_draw_indirect_count {
+        return Err(CheckIndirectBufferError::DrawIndirectCountExtensionNotEnabled);
+    }
+
+    if !count_buffer.inner().buffer.usage_indirect_buffer() {
+        return Err(CheckIndirectBufferError::CountBufferMissingUsage);
+    }
+
+    if count_buffer.inner().offset % 4 != 0 {
+        return Err(CheckIndirectBufferError::CountBufferNotAligned);
+    }
+
+    let limit = device.physical_device().limits().max_draw_indirect_count();
+
+    if max_draw_count > limit {
+        return Err(CheckIndirectBufferError::MaxDrawIndirectCountExceeded {
+            limit,
+            requested: max_draw_count,
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks whether the device supports performing `draw_count` draws with a single indirect draw
+/// command.
+pub fn check_indirect_draw_count(
+    device: &Device,
+    draw_count: u32,
+) -> Result<(), CheckIndirectBufferError> {
+    if draw_count > 1 && !device.enabled_features().multi_draw_indirect {
+        return Err(CheckIndirectBufferError::MultiDrawIndirectFeatureNotEnabled);
+    }
+
+    let limit = device.physical_device().limits().max_draw_indirect_count();
+
+    if draw_count > limit {
+        return Err(CheckIndirectBufferError::MaxDrawIndirectCountExceeded {
+            limit,
+            requested: draw_count,
+        });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking whether binding an indirect buffer is valid.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckIndirectBufferError {
+    /// The "indirect buffer" usage must be enabled on the indirect buffer.
+    BufferMissingUsage,
+    /// The indirect buffer contains more than one draw, but the `multi_draw_indirect` feature is
+    /// not enabled on the device.
+    MultiDrawIndirectFeatureNotEnabled,
+    /// The indirect buffer contains more draws than the device supports in a single command.
+    MaxDrawIndirectCountExceeded {
+        /// The maximum number of draws supported by the device.
+        limit: u32,
+        /// The number of draws in the indirect buffer, or the maximum number of draws read from
+        /// the count buffer.
+        requested: u32,
+    },
+    /// The `khr_draw_indirect_count` extension must be enabled to read the number of draws from
+    /// a count buffer.
+    DrawIndirectCountExtensionNotEnabled,
+    /// The "indirect buffer" usage must be enabled on the count buffer.
+    CountBufferMissingUsage,
+    /// The offset of the count buffer must be a multiple of 4.
+    CountBufferNotAligned,
+}
+
+impl error::Error for CheckIndirectBufferError {}
+
+impl fmt::Display for CheckIndirectBufferError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckIndirectBufferError::BufferMissingUsage => {
+                    "the indirect buffer usage must be enabled on the indirect buffer"
+                }
+                CheckIndirectBufferError::MultiDrawIndirectFeatureNotEnabled => {
+                    "the multi_draw_indirect feature must be enabled to perform more than one draw"
+                }
+                CheckIndirectBufferError::MaxDrawIndirectCountExceeded { .. } => {
+                    "the number of draws exceeds the max_draw_indirect_count limit"
+                }
+                CheckIndirectBufferError::DrawIndirectCountExtensionNotEnabled => {
+                    "the khr_draw_indirect_count extension must be enabled to use a count buffer"
+                }
+                CheckIndirectBufferError::CountBufferMissingUsage => {
+                    "the indirect buffer usage must be enabled on the count buffer"
+                }
+                CheckIndirectBufferError::CountBufferNotAligned => {
+                    "the offset of the count buffer must be a multiple of 4"
+                }
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+
+    #[test]
+    fn missing_usage() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let buffer = CpuAccessibleBuffer::from_data(
+            device.clone(),
+            BufferUsage::vertex_buffer(),
+            false,
+            0u32,
+        )
+        .unwrap();
+
+        match check_indirect_buffer(&device, &buffer) {
+            Err(CheckIndirectBufferError::BufferMissingUsage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn wrong_device() {
+        let (dev1, queue) = gfx_dev_and_queue!();
+        let (dev2, _) = gfx_dev_and_queue!();
+        let buffer = CpuAccessibleBuffer::from_data(dev1, BufferUsage::all(), false, 0u32).unwrap();
+
+        assert_should_panic!({
+            let _ = check_indirect_buffer(&dev2, &buffer);
+        });
+    }
+
+    #[test]
+    fn multi_draw_requires_feature() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        assert!(check_indirect_draw_count(&device, 0).is_ok());
+        assert!(check_indirect_draw_count(&device, 1).is_ok());
+
+        match check_indirect_draw_count(&device, 2) {
+            Err(CheckIndirectBufferError::MultiDrawIndirectFeatureNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn count_buffer_requires_extension() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let buffer =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0u32)
+                .unwrap();
+
+        match check_indirect_count_buffer(&device, &buffer, 1) {
+            Err(CheckIndirectBufferError::DrawIndirectCountExtensionNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use command_buffer::PipelineBarrier;
+use device::Device;
+use image::ImageLayout;
+use sync::AccessFlagBits;
+use sync::PipelineStages;
+use VulkanObject;
+
+/// Checks whether a manual memory barrier is valid.
+pub fn check_memory_barrier(
+    device: &Device,
+    source_stage: PipelineStages,
+    source_access: AccessFlagBits,
+    destination_stage: PipelineStages,
+    destination_access: AccessFlagBits,
+) -> Result<(), CheckMemoryBarrierError> {
+    if source_stage == PipelineStages::none() || destination_stage == PipelineStages::none() {
+        return Err(CheckMemoryBarrierError::NoStages);
+    }
+
+    if !source_access.is_compatible_with(&source_stage) {
+        return Err(CheckMemoryBarrierError::SourceAccessNotSupportedByStages);
+    }
+
+    if !destination_access.is_compatible_with(&destination_stage) {
+        return Err(CheckMemoryBarrierError::DestinationAccessNotSupportedByStages);
+    }
+
+    let stages = source_stage | destination_stage;
+    let features = device.enabled_features();
+
+    if stages.geometry_shader && !features.geometry_shader {
+        return Err(CheckMemoryBarrierError::GeometryShaderFeatureNotEnabled);
+    }
+
+    if (stages.tessellation_control_shader || stages.tessellation_evaluation_shader)
+        && !features.tessellation_shader
+    {
+        return Err(CheckMemoryBarrierError::TessellationShaderFeatureNotEnabled);
+    }
+
+    if stages.task_shader && !features.task_shader {
+        return Err(CheckMemoryBarrierError::TaskShaderFeatureNotEnabled);
+    }
+
+    if stages.mesh_shader && !features.mesh_shader {
+        return Err(CheckMemoryBarrierError::MeshShaderFeatureNotEnabled);
+    }
+
+    if stages.ray_tracing_shader && !device.loaded_extensions().nv_ray_tracing {
+        return Err(CheckMemoryBarrierError::RayTracingExtensionNotEnabled);
+    }
+
+    Ok(())
+}
+
+/// Checks whether an explicit pipeline barrier is valid.
+///
+/// # Panic
+///
+/// - Panics if a buffer or an image of the barrier doesn't belong to `device`.
+///
+pub fn check_pipeline_barrier(
+    device: &Device,
+    barrier: &PipelineBarrier,
+) -> Result<(), CheckMemoryBarrierError> {
+    let (source_stage, destination_stage) = barrier.stages();
+    check_memory_barrier(
+        device,
+        source_stage,
+        AccessFlagBits::none(),
+        destination_stage,
+        AccessFlagBits::none(),
+    )?;
+
+    for (source_access, destination_access) in barrier.accesses() {
+        if !source_access.is_compatible_with(&source_stage) {
+            return Err(CheckMemoryBarrierError::SourceAccessNotSupportedByStages);
+        }
+
+        if !destination_access.is_compatible_with(&destination_stage) {
+            return Err(CheckMemoryBarrierError::DestinationAccessNotSupportedByStages);
+        }
+    }
+
+    for buffer in barrier.buffers() {
+        assert_eq!(buffer.device().internal_object(), device.internal_object());
+    }
+
+    for (image, layout) in barrier.images() {
+        assert_eq!(
+            image.inner().image.device().internal_object(),
+            device.internal_object()
+        );
+
+        if layout == ImageLayout::Undefined || layout == ImageLayout::Preinitialized {
+            return Err(CheckMemoryBarrierError::InvalidImageLayout);
+        }
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when attempting to add a `memory_barrier` command.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckMemoryBarrierError {
+    /// The source or the destination stages are empty.
+    NoStages,
+    /// The source accesses can't be performed by the source stages.
+    SourceAccessNotSupportedByStages,
+    /// The destination accesses can't be performed by the destination stages.
+    DestinationAccessNotSupportedByStages,
+    /// The geometry shader stage is used but the `geometry_shader` feature is not enabled.
+    GeometryShaderFeatureNotEnabled,
+    /// A tessellation stage is used but the `tessellation_shader` feature is not enabled.
+    TessellationShaderFeatureNotEnabled,
+    /// The task shader stage is used but the `task_shader` feature is not enabled.
+    TaskShaderFeatureNotEnabled,
+    /// The mesh shader stage is used but the `mesh_shader` feature is not enabled.
+    MeshShaderFeatureNotEnabled,
+    /// The ray tracing shader stage is used but the `nv_ray_tracing` extension is not enabled.
+    RayTracingExtensionNotEnabled,
+    /// The layout of an image barrier is `Undefined` or `Preinitialized`.
+    InvalidImageLayout,
+}
+
+impl error::Error for CheckMemoryBarrierError {}
+
+impl fmt::Display for CheckMemoryBarrierError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckMemoryBarrierError::NoStages => {
+                    "the source or the destination stages are empty"
+                }
+                CheckMemoryBarrierError::SourceAccessNotSupportedByStages => {
+                    "the source accesses can't be performed by the source stages"
+                }
+                CheckMemoryBarrierError::DestinationAccessNotSupportedByStages => {
+                    "the destination accesses can't be performed by the destination stages"
+                }
+                CheckMemoryBarrierError::GeometryShaderFeatureNotEnabled => {
+                    "the geometry shader stage is used but the `geometry_shader` feature is not \
+                     enabled"
+                }
+                CheckMemoryBarrierError::TessellationShaderFeatureNotEnabled => {
+                    "a tessellation stage is used but the `tessellation_shader` feature is not \
+                     enabled"
+                }
+                CheckMemoryBarrierError::TaskShaderFeatureNotEnabled => {
+                    "the task shader stage is used but the `task_shader` feature is not enabled"
+                }
+                CheckMemoryBarrierError::MeshShaderFeatureNotEnabled => {
+                    "the mesh shader stage is used but the `mesh_shader` feature is not enabled"
+                }
+                CheckMemoryBarrierError::RayTracingExtensionNotEnabled => {
+                    "the ray tracing shader stage is used but the `nv_ray_tracing` extension is \
+                     not enabled"
+                }
+                CheckMemoryBarrierError::InvalidImageLayout => {
+                    "the layout of an image barrier is `Undefined` or `Preinitialized`"
+                }
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn access_must_match_stages() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let transfer = PipelineStages {
+            transfer: true,
+            ..PipelineStages::none()
+        };
+        let compute = PipelineStages {
+            compute_shader: true,
+            ..PipelineStages::none()
+        };
+        let transfer_write = AccessFlagBits {
+            transfer_write: true,
+            ..AccessFlagBits::none()
+        };
+        let shader_read = AccessFlagBits {
+            shader_read: true,
+            ..AccessFlagBits::none()
+        };
+
+        assert_eq!(
+            check_memory_barrier(&device, transfer, transfer_write, compute, shader_read),
+            Ok(())
+        );
+        assert_eq!(
+            check_memory_barrier(&device, compute, transfer_write, compute, shader_read),
+            Err(CheckMemoryBarrierError::SourceAccessNotSupportedByStages)
+        );
+        assert_eq!(
+            check_memory_barrier(
+                &device,
+                transfer,
+                transfer_write,
+                PipelineStages::none(),
+                AccessFlagBits::none()
+            ),
+            Err(CheckMemoryBarrierError::NoStages)
+        );
+    }
+
+    #[test]
+    fn ray_tracing_requires_extension() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let compute = PipelineStages {
+            compute_shader: true,
+            ..PipelineStages::none()
+        };
+        let ray_tracing = PipelineStages {
+            ray_tracing_shader: true,
+            ..PipelineStages::none()
+        };
+
+        assert_eq!(
+            check_memory_barrier(
+                &device,
+                compute,
+                AccessFlagBits::none(),
+                ray_tracing,
+                AccessFlagBits::none()
+            ),
+            Err(CheckMemoryBarrierError::RayTracingExtensionNotEnabled)
+        );
+        assert_eq!(
+            check_pipeline_barrier(&device, &PipelineBarrier::new(ray_tracing, compute)),
+            Err(CheckMemoryBarrierError::RayTracingExtensionNotEnabled)
+        );
+    }
+
+    #[test]
+    fn pipeline_barrier_accesses_checked() {
+        use buffer::BufferUsage;
+        use buffer::CpuAccessibleBuffer;
+        use format::Format;
+        use image::AttachmentImage;
+
+        let (device, _) = gfx_dev_and_queue!();
+
+        let buffer =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0u32)
+                .unwrap();
+        let image = AttachmentImage::new(device.clone(), [4, 4], Format::R8G8B8A8Unorm).unwrap();
+
+        let compute = PipelineStages {
+            compute_shader: true,
+            ..PipelineStages::none()
+        };
+        let draw_indirect = PipelineStages {
+            draw_indirect: true,
+            ..PipelineStages::none()
+        };
+        let shader_write = AccessFlagBits {
+            shader_write: true,
+            ..AccessFlagBits::none()
+        };
+        let indirect_read = AccessFlagBits {
+            indirect_command_read: true,
+            ..AccessFlagBits::none()
+        };
+
+        let barrier = PipelineBarrier::new(compute, draw_indirect).buffer_barrier(
+            buffer.clone(),
+            shader_write,
+            indirect_read,
+        );
+        assert_eq!(check_pipeline_barrier(&device, &barrier), Ok(()));
+
+        let barrier = PipelineBarrier::new(compute, draw_indirect).buffer_barrier(
+            buffer.clone(),
+            shader_write,
+            shader_write,
+        );
+        assert_eq!(
+            check_pipeline_barrier(&device, &barrier),
+            Err(CheckMemoryBarrierError::DestinationAccessNotSupportedByStages)
+        );
+
+        let barrier = unsafe {
+            PipelineBarrier::new(compute, compute).image_barrier(
+                image,
+                ImageLayout::Undefined,
+                shader_write,
+                shader_write,
+            )
+        };
+        assert_eq!(
+            check_pipeline_barrier(&device, &barrier),
+            Err(CheckMemoryBarrierError::InvalidImageLayout)
+        );
+    }
+}
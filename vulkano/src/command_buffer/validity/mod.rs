@@ -9,32 +9,81 @@
 
 //! Functions that check the validity of commands.
 
-pub use self::blit_image::{check_blit_image, CheckBlitImageError};
+pub use self::blit_image::{check_blit_image, check_blit_image_regions, CheckBlitImageError};
 pub use self::clear_color_image::{check_clear_color_image, CheckClearColorImageError};
-pub use self::copy_buffer::{check_copy_buffer, CheckCopyBuffer, CheckCopyBufferError};
-pub use self::copy_image::{check_copy_image, CheckCopyImageError};
+pub use self::clear_depth_stencil_image::{
+    check_clear_depth_stencil_image, CheckClearDepthStencilImageError,
+};
+pub use self::clear_values::{check_clear_values, CheckClearValuesError};
+pub use self::copy_buffer::{
+    check_copy_buffer, check_copy_buffer_regions, CheckCopyBuffer, CheckCopyBufferError,
+};
+pub use self::copy_image::{check_copy_image, check_copy_image_regions, CheckCopyImageError};
 pub use self::copy_image_buffer::{
-    check_copy_buffer_image, CheckCopyBufferImageError, CheckCopyBufferImageTy,
+    check_copy_buffer_image, check_copy_buffer_image_region, CheckCopyBufferImageError,
+    CheckCopyBufferImageTy,
 };
+pub use self::debug_label::{check_debug_label, check_end_debug_label, CheckDebugLabelError};
 pub use self::descriptor_sets::{check_descriptor_sets_validity, CheckDescriptorSetsValidityError};
-pub use self::dispatch::{check_dispatch, CheckDispatchError};
+pub use self::dispatch::{
+    check_dispatch, check_dispatch_base, dispatch_base_supported, CheckDispatchError,
+};
+pub use self::draw_mesh_tasks::{check_draw_mesh_tasks, CheckDrawMeshTasksError};
 pub use self::dynamic_state::{check_dynamic_state_validity, CheckDynamicStateValidityError};
+pub use self::event::{
+    check_event_command, check_wait_events, CheckEventCommandError, CheckWaitEventsError,
+};
 pub use self::fill_buffer::{check_fill_buffer, CheckFillBufferError};
+pub use self::generate_mipmaps::{
+    check_generate_mipmaps, check_generate_mipmaps_format, CheckGenerateMipmapsError,
+};
 pub use self::index_buffer::{check_index_buffer, CheckIndexBuffer, CheckIndexBufferError};
-pub use self::push_constants::{check_push_constants_validity, CheckPushConstantsValidityError};
+pub use self::indirect_buffer::{
+    check_indirect_buffer, check_indirect_count_buffer, check_indirect_draw_count,
+    CheckIndirectBufferError,
+};
+pub use self::memory_barrier::{
+    check_memory_barrier, check_pipeline_barrier, CheckMemoryBarrierError,
+};
+pub use self::push_constants::{
+    check_push_constants_range, check_push_constants_validity, CheckPushConstantsRangeError,
+    CheckPushConstantsValidityError,
+};
+pub use self::query::{
+    check_begin_query, check_reset_query_pool, check_write_timestamp, CheckBeginQueryError,
+    CheckResetQueryPoolError, CheckWriteTimestampError,
+};
+pub use self::queue_family_transfer::{
+    check_buffer_ownership_acquire, check_buffer_ownership_release, check_image_ownership_acquire,
+    check_image_ownership_release, CheckQueueFamilyTransferError,
+};
+pub use self::resolve_image::{check_resolve_image, CheckResolveImageError};
+pub use self::trace_rays::{check_trace_rays, CheckTraceRaysError};
 pub use self::update_buffer::{check_update_buffer, CheckUpdateBufferError};
 pub use self::vertex_buffers::{check_vertex_buffers, CheckVertexBuffer, CheckVertexBufferError};
 
 mod blit_image;
 mod clear_color_image;
+mod clear_depth_stencil_image;
+mod clear_values;
 mod copy_buffer;
 mod copy_image;
 mod copy_image_buffer;
+mod debug_label;
 mod descriptor_sets;
 mod dispatch;
+mod draw_mesh_tasks;
 mod dynamic_state;
+mod event;
 mod fill_buffer;
+mod generate_mipmaps;
 mod index_buffer;
+mod indirect_buffer;
+mod memory_barrier;
 mod push_constants;
+mod query;
+mod queue_family_transfer;
+mod resolve_image;
+mod trace_rays;
 mod update_buffer;
 mod vertex_buffers;
@@ -9,11 +9,20 @@
 
 use std::error;
 use std::fmt;
+use std::mem;
 
+use descriptor::descriptor::ShaderStages;
 use descriptor::pipeline_layout::PipelineLayoutAbstract;
 use descriptor::pipeline_layout::PipelineLayoutPushConstantsCompatible;
+use device::Device;
+use VulkanObject;
 
 /// Checks whether push constants are compatible with the pipeline.
+///
+/// The draw and dispatch commands update every push constants range of the layout of their
+/// pipeline with the corresponding bytes of the push constants, so these must be large enough
+/// to contain all the ranges. Because all the ranges are updated, a draw or dispatch never
+/// reads push constants that weren't set.
 pub fn check_push_constants_validity<Pl, Pc>(
     pipeline: &Pl,
     push_constants: &Pc,
@@ -26,14 +35,36 @@ where
         return Err(CheckPushConstantsValidityError::IncompatiblePushConstants);
     }
 
+    let required = (0..pipeline.num_push_constants_ranges())
+        .filter_map(|num| pipeline.push_constants_range(num))
+        .map(|range| range.offset + range.size)
+        .max()
+        .unwrap_or(0);
+    let obtained = mem::size_of_val(push_constants);
+
+    if obtained < required {
+        return Err(CheckPushConstantsValidityError::NotEnoughData {
+            required: required,
+            obtained: obtained,
+        });
+    }
+
     Ok(())
 }
 
 /// Error that can happen when checking push constants validity.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum CheckPushConstantsValidityError {
     /// The push constants are incompatible with the pipeline layout.
     IncompatiblePushConstants,
+    /// The push constants are smaller than the end of the last push constants range of the
+    /// pipeline layout.
+    NotEnoughData {
+        /// End in bytes of the last push constants range.
+        required: usize,
+        /// Size in bytes of the push constants.
+        obtained: usize,
+    },
 }
 
 impl error::Error for CheckPushConstantsValidityError {}
@@ -48,7 +79,231 @@ impl fmt::Display for CheckPushConstantsValidityError {
                 CheckPushConstantsValidityError::IncompatiblePushConstants => {
                     "the push constants are incompatible with the pipeline layout"
                 }
+                CheckPushConstantsValidityError::NotEnoughData { .. } => {
+                    "the push constants are smaller than the end of the last push constants range \
+                     of the pipeline layout"
+                }
+            }
+        )
+    }
+}
+
+/// Checks whether a range of push constants can be updated with the given layout.
+///
+/// # Panic
+///
+/// - Panics if the pipeline layout was not created with `device`.
+///
+pub fn check_push_constants_range<Pl>(
+    device: &Device,
+    pipeline_layout: &Pl,
+    stages: ShaderStages,
+    offset: u32,
+    size: u32,
+) -> Result<(), CheckPushConstantsRangeError>
+where
+    Pl: ?Sized + PipelineLayoutAbstract,
+{
+    assert_eq!(
+        pipeline_layout.device().internal_object(),
+        device.internal_object()
+    );
+
+    if offset % 4 != 0 {
+        return Err(CheckPushConstantsRangeError::OffsetNotAligned);
+    }
+
+    if size == 0 || size % 4 != 0 {
+        return Err(CheckPushConstantsRangeError::InvalidSize);
+    }
+
+    let limit = device.physical_device().limits().max_push_constants_size();
+    if offset as u64 + size as u64 > limit as u64 {
+        return Err(CheckPushConstantsRangeError::MaxPushConstantsSizeExceeded {
+            limit: limit,
+            requested: offset as u64 + size as u64,
+        });
+    }
+
+    if stages == ShaderStages::none() {
+        return Err(CheckPushConstantsRangeError::NoStages);
+    }
+
+    let end = (offset + size) as usize;
+    let offset = offset as usize;
+    let mut covered = ShaderStages::none();
+
+    for num in 0..pipeline_layout.num_push_constants_ranges() {
+        let range = match pipeline_layout.push_constants_range(num) {
+            Some(r) => r,
+            None => continue,
+        };
+
+        // Each stage can only access one range, which must contain all the bytes to update.
+        if range.stages.intersects(&stages) {
+            if offset < range.offset || end > range.offset + range.size {
+                return Err(CheckPushConstantsRangeError::RangeNotInLayout);
+            }
+            covered = covered | range.stages;
+        }
+
+        // The bytes to update that belong to a range must be updated for all of its stages.
+        if offset < range.offset + range.size
+            && range.offset < end
+            && stages.is_superset_of(&range.stages).is_err()
+        {
+            return Err(CheckPushConstantsRangeError::MissingStages);
+        }
+    }
+
+    if covered.is_superset_of(&stages).is_err() {
+        return Err(CheckPushConstantsRangeError::RangeNotInLayout);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking a range of push constants.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckPushConstantsRangeError {
+    /// The offset must be a multiple of 4.
+    OffsetNotAligned,
+    /// The size must be a non-zero multiple of 4.
+    InvalidSize,
+    /// The range goes beyond the `max_push_constants_size` limit.
+    MaxPushConstantsSizeExceeded {
+        /// The limit.
+        limit: u32,
+        /// The end of the requested range.
+        requested: u64,
+    },
+    /// At least one shader stage must be specified.
+    NoStages,
+    /// The pipeline layout doesn't have a push constants range that contains the updated bytes
+    /// for one of the stages.
+    RangeNotInLayout,
+    /// The updated bytes belong to a push constants range of the layout whose stages are not all
+    /// included in the stages passed to the command.
+    MissingStages,
+}
+
+impl error::Error for CheckPushConstantsRangeError {}
+
+impl fmt::Display for CheckPushConstantsRangeError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckPushConstantsRangeError::OffsetNotAligned => {
+                    "the offset must be a multiple of 4"
+                }
+                CheckPushConstantsRangeError::InvalidSize => {
+                    "the size must be a non-zero multiple of 4"
+                }
+                CheckPushConstantsRangeError::MaxPushConstantsSizeExceeded { .. } => {
+                    "the range goes beyond the `max_push_constants_size` limit"
+                }
+                CheckPushConstantsRangeError::NoStages => {
+                    "at least one shader stage must be specified"
+                }
+                CheckPushConstantsRangeError::RangeNotInLayout => {
+                    "the pipeline layout doesn't have a push constants range that contains the \
+                     updated bytes for one of the stages"
+                }
+                CheckPushConstantsRangeError::MissingStages => {
+                    "the updated bytes belong to a push constants range of the layout whose stages \
+                     are not all included in the stages passed to the command"
+                }
             }
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use descriptor::pipeline_layout::PipelineLayoutDesc;
+    use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+    use descriptor::pipeline_layout::RuntimePipelineDesc;
+    use std::iter;
+
+    #[test]
+    fn range_checks() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let vertex = ShaderStages {
+            vertex: true,
+            ..ShaderStages::none()
+        };
+        let fragment = ShaderStages {
+            fragment: true,
+            ..ShaderStages::none()
+        };
+        let ranges = vec![
+            PipelineLayoutDescPcRange {
+                offset: 0,
+                size: 16,
+                stages: vertex,
+            },
+            PipelineLayoutDescPcRange {
+                offset: 16,
+                size: 16,
+                stages: fragment,
+            },
+        ];
+        let layout = RuntimePipelineDesc::new(iter::empty::<iter::Empty<_>>(), ranges)
+            .unwrap()
+            .build(device.clone())
+            .unwrap();
+
+        assert_eq!(
+            check_push_constants_range(&device, &layout, vertex, 4, 8),
+            Ok(())
+        );
+        assert_eq!(
+            check_push_constants_range(&device, &layout, vertex, 2, 8),
+            Err(CheckPushConstantsRangeError::OffsetNotAligned)
+        );
+        assert_eq!(
+            check_push_constants_range(&device, &layout, vertex, 8, 16),
+            Err(CheckPushConstantsRangeError::RangeNotInLayout)
+        );
+        assert_eq!(
+            check_push_constants_range(&device, &layout, fragment, 0, 4),
+            Err(CheckPushConstantsRangeError::RangeNotInLayout)
+        );
+    }
+
+    #[test]
+    fn validity_checks_size() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let ranges = vec![PipelineLayoutDescPcRange {
+            offset: 16,
+            size: 16,
+            stages: ShaderStages::all(),
+        }];
+        let layout = RuntimePipelineDesc::new(iter::empty::<iter::Empty<_>>(), ranges)
+            .unwrap()
+            .build(device)
+            .unwrap();
+
+        assert_eq!(check_push_constants_validity(&layout, &[0u32; 8]), Ok(()));
+        assert_eq!(
+            check_push_constants_validity(&layout, &[0u32; 4]),
+            Err(CheckPushConstantsValidityError::NotEnoughData {
+                required: 32,
+                obtained: 16,
+            })
+        );
+        assert_eq!(
+            check_push_constants_validity(&layout, &()),
+            Err(CheckPushConstantsValidityError::NotEnoughData {
+                required: 32,
+                obtained: 0,
+            })
+        );
+    }
+}
@@ -0,0 +1,271 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+use std::ops::Range;
+
+use device::Device;
+use device::DeviceOwned;
+use instance::QueueFamily;
+use query::QueryPool;
+use query::QueryType;
+use sync::PipelineStages;
+use VulkanObject;
+
+/// Checks whether a query of `query_pool` can be started.
+///
+/// # Panic
+///
+/// - Panics if the query pool was not created with `device`.
+///
+pub fn check_begin_query(
+    device: &Device,
+    query_pool: &QueryPool,
+    query: u32,
+    precise: bool,
+) -> Result<(), CheckBeginQueryError> {
+    assert_eq!(
+        device.internal_object(),
+        query_pool.device().internal_object()
+    );
+
+    if query >= query_pool.num_queries() {
+        return Err(CheckBeginQueryError::OutOfRange);
+    }
+
+    match query_pool.ty() {
+        QueryType::Occlusion => {
+            if precise && !device.enabled_features().occlusion_query_precise {
+                return Err(CheckBeginQueryError::OcclusionQueryPreciseFeatureNotEnabled);
+            }
+        }
+        QueryType::PipelineStatistics(_) => {
+            if precise {
+                return Err(CheckBeginQueryError::InvalidFlags);
+            }
+        }
+        QueryType::Timestamp => {
+            return Err(CheckBeginQueryError::NotPermitted);
+        }
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when beginning a query.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckBeginQueryError {
+    /// The query index is out of the range of the pool.
+    OutOfRange,
+    /// Timestamp queries can't be begun, they must be written with `write_timestamp`.
+    NotPermitted,
+    /// The `precise` flag was used with a query that isn't an occlusion query.
+    InvalidFlags,
+    /// The `precise` flag was used but the `occlusion_query_precise` feature is not enabled.
+    OcclusionQueryPreciseFeatureNotEnabled,
+}
+
+impl error::Error for CheckBeginQueryError {}
+
+impl fmt::Display for CheckBeginQueryError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckBeginQueryError::OutOfRange => {
+                    "the query index is out of the range of the pool"
+                }
+                CheckBeginQueryError::NotPermitted => {
+                    "timestamp queries can't be begun, they must be written with `write_timestamp`"
+                }
+                CheckBeginQueryError::InvalidFlags => {
+                    "the `precise` flag was used with a query that isn't an occlusion query"
+                }
+                CheckBeginQueryError::OcclusionQueryPreciseFeatureNotEnabled => {
+                    "the `precise` flag was used but the `occlusion_query_precise` feature is not \
+                     enabled"
+                }
+            }
+        )
+    }
+}
+
+/// Checks whether a timestamp can be written to a query of `query_pool`.
+///
+/// `stage` must contain exactly one pipeline stage.
+///
+/// # Panic
+///
+/// - Panics if the query pool was not created with `device`.
+///
+pub fn check_write_timestamp(
+    device: &Device,
+    queue_family: QueueFamily,
+    query_pool: &QueryPool,
+    query: u32,
+    stage: PipelineStages,
+) -> Result<(), CheckWriteTimestampError> {
+    assert_eq!(
+        device.internal_object(),
+        query_pool.device().internal_object()
+    );
+
+    if query >= query_pool.num_queries() {
+        return Err(CheckWriteTimestampError::OutOfRange);
+    }
+
+    match query_pool.ty() {
+        QueryType::Timestamp => (),
+        _ => return Err(CheckWriteTimestampError::NotTimestampPool),
+    }
+
+    if stage.into_vulkan_bits().count_ones() != 1 {
+        return Err(CheckWriteTimestampError::InvalidStage);
+    }
+
+    if queue_family.timestamp_valid_bits().is_none() {
+        return Err(CheckWriteTimestampError::NoTimestampSupport);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when writing a timestamp.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckWriteTimestampError {
+    /// The query index is out of the range of the pool.
+    OutOfRange,
+    /// The query pool wasn't created for timestamp queries.
+    NotTimestampPool,
+    /// The stage doesn't contain exactly one pipeline stage.
+    InvalidStage,
+    /// The queue family doesn't support timestamps.
+    NoTimestampSupport,
+}
+
+impl error::Error for CheckWriteTimestampError {}
+
+impl fmt::Display for CheckWriteTimestampError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckWriteTimestampError::OutOfRange => {
+                    "the query index is out of the range of the pool"
+                }
+                CheckWriteTimestampError::NotTimestampPool => {
+                    "the query pool wasn't created for timestamp queries"
+                }
+                CheckWriteTimestampError::InvalidStage => {
+                    "the stage doesn't contain exactly one pipeline stage"
+                }
+                CheckWriteTimestampError::NoTimestampSupport => {
+                    "the queue family doesn't support timestamps"
+                }
+            }
+        )
+    }
+}
+
+/// Checks whether the queries in `queries` can be reset.
+///
+/// # Panic
+///
+/// - Panics if the query pool was not created with `device`.
+///
+pub fn check_reset_query_pool(
+    device: &Device,
+    query_pool: &QueryPool,
+    queries: Range<u32>,
+) -> Result<(), CheckResetQueryPoolError> {
+    assert_eq!(
+        device.internal_object(),
+        query_pool.device().internal_object()
+    );
+
+    if queries.start >= queries.end || queries.end > query_pool.num_queries() {
+        return Err(CheckResetQueryPoolError::OutOfRange);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when resetting queries.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckResetQueryPoolError {
+    /// The range of queries is empty or is out of the range of the pool.
+    OutOfRange,
+}
+
+impl error::Error for CheckResetQueryPoolError {}
+
+impl fmt::Display for CheckResetQueryPoolError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckResetQueryPoolError::OutOfRange => {
+                    "the range of queries is empty or is out of the range of the pool"
+                }
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_types_checked() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let occlusion = QueryPool::new(device.clone(), QueryType::Occlusion, 2).unwrap();
+        let timestamp = QueryPool::new(device.clone(), QueryType::Timestamp, 2).unwrap();
+        let bottom = PipelineStages {
+            bottom_of_pipe: true,
+            ..PipelineStages::none()
+        };
+
+        assert_eq!(check_begin_query(&device, &occlusion, 1, false), Ok(()));
+        assert_eq!(
+            check_begin_query(&device, &occlusion, 2, false),
+            Err(CheckBeginQueryError::OutOfRange)
+        );
+        assert_eq!(
+            check_begin_query(&device, &timestamp, 0, false),
+            Err(CheckBeginQueryError::NotPermitted)
+        );
+        assert_eq!(
+            check_write_timestamp(&device, queue.family(), &occlusion, 0, bottom),
+            Err(CheckWriteTimestampError::NotTimestampPool)
+        );
+        assert_eq!(
+            check_write_timestamp(
+                &device,
+                queue.family(),
+                &timestamp,
+                0,
+                PipelineStages::none()
+            ),
+            Err(CheckWriteTimestampError::InvalidStage)
+        );
+        assert_eq!(
+            check_reset_query_pool(&device, &timestamp, 1..3),
+            Err(CheckResetQueryPoolError::OutOfRange)
+        );
+    }
+}
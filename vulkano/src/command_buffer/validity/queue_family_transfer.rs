@@ -0,0 +1,473 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use buffer::BufferAccess;
+use device::Device;
+use device::DeviceOwned;
+use image::ImageAccess;
+use image::ImageLayout;
+use instance::QueueFamily;
+use VulkanObject;
+
+/// Checks whether the ownership of a buffer can be released from `source` to `destination`.
+///
+/// # Panic
+///
+/// - Panics if the buffer was not created with `device`.
+/// - Panics if the queue families don't belong to the physical device of `device`.
+///
+pub fn check_buffer_ownership_release<B>(
+    device: &Device,
+    buffer: &B,
+    source: QueueFamily,
+    destination: QueueFamily,
+) -> Result<(), CheckQueueFamilyTransferError>
+where
+    B: ?Sized + BufferAccess,
+{
+    let inner = buffer.inner().buffer;
+    check_common(
+        device,
+        inner.device(),
+        inner.is_sharing_exclusive(),
+        source,
+        destination,
+    )?;
+
+    if inner.pending_acquire_source().is_some() {
+        return Err(CheckQueueFamilyTransferError::AcquireMissing);
+    }
+
+    match inner.queue_family_owner() {
+        Some(owner) if owner != source.id() => {
+            Err(CheckQueueFamilyTransferError::WrongOwner { owner: Some(owner) })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks whether the ownership of a buffer can be acquired by `destination` from `source`.
+///
+/// The command buffer containing the matching release must have been submitted before.
+///
+/// # Panic
+///
+/// - Panics if the buffer was not created with `device`.
+/// - Panics if the queue families don't belong to the physical device of `device`.
+///
+pub fn check_buffer_ownership_acquire<B>(
+    device: &Device,
+    buffer: &B,
+    source: QueueFamily,
+    destination: QueueFamily,
+) -> Result<(), CheckQueueFamilyTransferError>
+where
+    B: ?Sized + BufferAccess,
+{
+    let inner = buffer.inner().buffer;
+    check_common(
+        device,
+        inner.device(),
+        inner.is_sharing_exclusive(),
+        source,
+        destination,
+    )?;
+
+    match (inner.queue_family_owner(), inner.pending_acquire_source()) {
+        (Some(owner), Some(release_source)) if owner == destination.id() => {
+            if release_source != source.id() {
+                return Err(CheckQueueFamilyTransferError::WrongSource {
+                    source: release_source,
+                });
+            }
+            Ok(())
+        }
+        (owner, _) => Err(CheckQueueFamilyTransferError::WrongOwner { owner }),
+    }
+}
+
+/// Checks whether the ownership of an image can be released from `source` to `destination`,
+/// while transitioning it from `old_layout` to `new_layout`.
+///
+/// Since the image must not be used by the command buffer after being released, `new_layout`
+/// must be the layout that the image is expected to be in at the end of a command buffer.
+///
+/// # Panic
+///
+/// - Panics if the image was not created with `device`.
+/// - Panics if the queue families don't belong to the physical device of `device`.
+///
+pub fn check_image_ownership_release<I>(
+    device: &Device,
+    image: &I,
+    source: QueueFamily,
+    destination: QueueFamily,
+    old_layout: ImageLayout,
+    new_layout: ImageLayout,
+) -> Result<(), CheckQueueFamilyTransferError>
+where
+    I: ?Sized + ImageAccess,
+{
+    let inner = image.inner().image;
+    check_common(
+        device,
+        inner.device(),
+        inner.is_sharing_exclusive(),
+        source,
+        destination,
+    )?;
+    check_layouts(old_layout, new_layout)?;
+
+    if new_layout != image.final_layout_requirement() {
+        return Err(CheckQueueFamilyTransferError::LayoutMismatch);
+    }
+
+    if inner.pending_acquire_layouts().is_some() {
+        return Err(CheckQueueFamilyTransferError::AcquireMissing);
+    }
+
+    match inner.queue_family_owner() {
+        Some(owner) if owner != source.id() => {
+            Err(CheckQueueFamilyTransferError::WrongOwner { owner: Some(owner) })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks whether the ownership of an image can be acquired by `destination` from `source`,
+/// while transitioning it from `old_layout` to `new_layout`.
+///
+/// The command buffer containing the matching release must have been submitted before, with the
+/// same layouts. `new_layout` must be the layout that the image is expected to be in at the start
+/// of a command buffer.
+///
+/// # Panic
+///
+/// - Panics if the image was not created with `device`.
+/// - Panics if the queue families don't belong to the physical device of `device`.
+///
+pub fn check_image_ownership_acquire<I>(
+    device: &Device,
+    image: &I,
+    source: QueueFamily,
+    destination: QueueFamily,
+    old_layout: ImageLayout,
+    new_layout: ImageLayout,
+) -> Result<(), CheckQueueFamilyTransferError>
+where
+    I: ?Sized + ImageAccess,
+{
+    let inner = image.inner().image;
+    check_common(
+        device,
+        inner.device(),
+        inner.is_sharing_exclusive(),
+        source,
+        destination,
+    )?;
+    check_layouts(old_layout, new_layout)?;
+
+    match (inner.queue_family_owner(), inner.pending_acquire_source()) {
+        (Some(owner), Some(release_source)) if owner == destination.id() => {
+            if release_source != source.id() {
+                return Err(CheckQueueFamilyTransferError::WrongSource {
+                    source: release_source,
+                });
+            }
+            if inner.pending_acquire_layouts() != Some((old_layout, new_layout))
+                || new_layout != image.initial_layout_requirement()
+            {
+                return Err(CheckQueueFamilyTransferError::LayoutMismatch);
+            }
+            Ok(())
+        }
+        (owner, _) => Err(CheckQueueFamilyTransferError::WrongOwner { owner }),
+    }
+}
+
+fn check_common(
+    device: &Device,
+    resource_device: &Device,
+    exclusive_sharing: bool,
+    source: QueueFamily,
+    destination: QueueFamily,
+) -> Result<(), CheckQueueFamilyTransferError> {
+    assert_eq!(resource_device.internal_object(), device.internal_object());
+    assert_eq!(
+        source.physical_device().internal_object(),
+        device.physical_device().internal_object()
+    );
+    assert_eq!(
+        destination.physical_device().internal_object(),
+        device.physical_device().internal_object()
+    );
+
+    if source.id() == destination.id() {
+        return Err(CheckQueueFamilyTransferError::SameQueueFamily);
+    }
+
+    if !exclusive_sharing {
+        return Err(CheckQueueFamilyTransferError::SharingNotExclusive);
+    }
+
+    Ok(())
+}
+
+fn check_layouts(
+    old_layout: ImageLayout,
+    new_layout: ImageLayout,
+) -> Result<(), CheckQueueFamilyTransferError> {
+    match (old_layout, new_layout) {
+        (ImageLayout::Undefined, _)
+        | (ImageLayout::Preinitialized, _)
+        | (_, ImageLayout::Undefined)
+        | (_, ImageLayout::Preinitialized) => Err(CheckQueueFamilyTransferError::InvalidLayout),
+        _ => Ok(()),
+    }
+}
+
+/// Error that can happen when attempting to add a queue family ownership transfer command.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckQueueFamilyTransferError {
+    /// The source and destination queue families are the same.
+    SameQueueFamily,
+    /// The buffer or image was created with concurrent sharing, and therefore can be used by
+    /// multiple queue families without transferring its ownership.
+    SharingNotExclusive,
+    /// The buffer or image isn't owned by the expected queue family. When acquiring, this means
+    /// that no command buffer releasing the ownership to the destination queue family has been
+    /// submitted, or that the ownership has already been acquired.
+    WrongOwner {
+        /// Id of the queue family the buffer or image was last released to, if any.
+        owner: Option<u32>,
+    },
+    /// The buffer or image was released by another queue family than the source of the acquire.
+    WrongSource {
+        /// Id of the queue family the buffer or image was released from.
+        source: u32,
+    },
+    /// The old or new layout of an image is `Undefined` or `Preinitialized`.
+    InvalidLayout,
+    /// The layouts of an image acquire don't match the layouts of its release, or the new layout
+    /// isn't the layout the image is expected to be in at the end of the releasing command
+    /// buffer or at the start of the acquiring command buffer.
+    LayoutMismatch,
+    /// The buffer or image was released to another queue family, but the command buffer
+    /// containing the matching acquire hasn't been submitted yet.
+    AcquireMissing,
+}
+
+impl error::Error for CheckQueueFamilyTransferError {}
+
+impl fmt::Display for CheckQueueFamilyTransferError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckQueueFamilyTransferError::SameQueueFamily => {
+                    "the source and destination queue families are the same"
+                }
+                CheckQueueFamilyTransferError::SharingNotExclusive => {
+                    "the buffer or image was not created with exclusive sharing"
+                }
+                CheckQueueFamilyTransferError::WrongOwner { .. } => {
+                    "the buffer or image isn't owned by the expected queue family"
+                }
+                CheckQueueFamilyTransferError::WrongSource { .. } => {
+                    "the buffer or image was released by another queue family than the source"
+                }
+                CheckQueueFamilyTransferError::InvalidLayout => {
+                    "the old or new layout of the image is `Undefined` or `Preinitialized`"
+                }
+                CheckQueueFamilyTransferError::LayoutMismatch => {
+                    "the layouts of the image don't match the release or the layout requirements \
+                     of the image"
+                }
+                CheckQueueFamilyTransferError::AcquireMissing => {
+                    "the buffer or image was released to another queue family but not acquired yet"
+                }
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use format::Format;
+    use image::Dimensions;
+    use image::StorageImage;
+
+    #[test]
+    fn same_queue_family() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let buffer =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0u32)
+                .unwrap();
+
+        match check_buffer_ownership_release(&device, &buffer, queue.family(), queue.family()) {
+            Err(CheckQueueFamilyTransferError::SameQueueFamily) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn acquire_requires_release() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let other = match device
+            .physical_device()
+            .queue_families()
+            .find(|f| f.id() != queue.family().id())
+        {
+            Some(f) => f,
+            None => return,
+        };
+
+        let buffer =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0u32)
+                .unwrap();
+
+        match check_buffer_ownership_acquire(&device, &buffer, queue.family(), other) {
+            Err(CheckQueueFamilyTransferError::WrongOwner { owner: None }) => (),
+            _ => panic!(),
+        }
+
+        check_buffer_ownership_release(&device, &buffer, queue.family(), other).unwrap();
+        buffer
+            .inner()
+            .buffer
+            .set_queue_family_released(queue.family().id(), other.id());
+        check_buffer_ownership_acquire(&device, &buffer, queue.family(), other).unwrap();
+
+        // Releasing again before acquiring is an error.
+        match check_buffer_ownership_release(&device, &buffer, other, queue.family()) {
+            Err(CheckQueueFamilyTransferError::AcquireMissing) => (),
+            _ => panic!(),
+        }
+
+        // The buffer now belongs to `other`, so it can't be released by the original family.
+        buffer.inner().buffer.set_queue_family_acquired();
+        match check_buffer_ownership_release(&device, &buffer, queue.family(), other) {
+            Err(CheckQueueFamilyTransferError::WrongOwner { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn acquire_checks_source() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let mut others = device
+            .physical_device()
+            .queue_families()
+            .filter(|f| f.id() != queue.family().id());
+        let (other1, other2) = match (others.next(), others.next()) {
+            (Some(f1), Some(f2)) => (f1, f2),
+            _ => return,
+        };
+
+        let buffer =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0u32)
+                .unwrap();
+        buffer
+            .inner()
+            .buffer
+            .set_queue_family_released(queue.family().id(), other1.id());
+
+        match check_buffer_ownership_acquire(&device, &buffer, other2, other1) {
+            Err(CheckQueueFamilyTransferError::WrongSource { source }) => {
+                assert_eq!(source, queue.family().id())
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn image_acquire_layouts() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let other = match device
+            .physical_device()
+            .queue_families()
+            .find(|f| f.id() != queue.family().id())
+        {
+            Some(f) => f,
+            None => return,
+        };
+
+        let image = StorageImage::new(
+            device.clone(),
+            Dimensions::Dim2d {
+                width: 32,
+                height: 32,
+            },
+            Format::R8G8B8A8Unorm,
+            Some(queue.family()),
+        )
+        .unwrap();
+
+        let old_layout = ImageLayout::TransferDstOptimal;
+        let new_layout = ImageLayout::General;
+        check_image_ownership_release(
+            &device,
+            &image,
+            queue.family(),
+            other,
+            old_layout,
+            new_layout,
+        )
+        .unwrap();
+        ImageAccess::inner(&image).image.set_queue_family_released(
+            queue.family().id(),
+            other.id(),
+            old_layout,
+            new_layout,
+        );
+
+        match check_image_ownership_acquire(
+            &device,
+            &image,
+            queue.family(),
+            other,
+            ImageLayout::ShaderReadOnlyOptimal,
+            new_layout,
+        ) {
+            Err(CheckQueueFamilyTransferError::LayoutMismatch) => (),
+            _ => panic!(),
+        }
+
+        // Releasing again before acquiring is an error.
+        match check_image_ownership_release(
+            &device,
+            &image,
+            other,
+            queue.family(),
+            new_layout,
+            new_layout,
+        ) {
+            Err(CheckQueueFamilyTransferError::AcquireMissing) => (),
+            _ => panic!(),
+        }
+
+        check_image_ownership_acquire(
+            &device,
+            &image,
+            queue.family(),
+            other,
+            old_layout,
+            new_layout,
+        )
+        .unwrap();
+    }
+}
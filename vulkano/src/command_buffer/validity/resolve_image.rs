@@ -0,0 +1,228 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use device::Device;
+use image::ImageAccess;
+use VulkanObject;
+
+/// Checks whether a resolve image command is valid.
+///
+/// # Panic
+///
+/// - Panics if the source or the destination was not created with `device`.
+///
+pub fn check_resolve_image<S, D>(
+    device: &Device,
+    source: &S,
+    source_offset: [i32; 3],
+    source_base_array_layer: u32,
+    source_mip_level: u32,
+    destination: &D,
+    destination_offset: [i32; 3],
+    destination_base_array_layer: u32,
+    destination_mip_level: u32,
+    extent: [u32; 3],
+    layer_count: u32,
+) -> Result<(), CheckResolveImageError>
+where
+    S: ?Sized + ImageAccess,
+    D: ?Sized + ImageAccess,
+{
+    let source_inner = source.inner();
+    let destination_inner = destination.inner();
+
+    assert_eq!(
+        source_inner.image.device().internal_object(),
+        device.internal_object()
+    );
+    assert_eq!(
+        destination_inner.image.device().internal_object(),
+        device.internal_object()
+    );
+
+    if !source_inner.image.usage_transfer_source() {
+        return Err(CheckResolveImageError::MissingTransferSourceUsage);
+    }
+
+    if !destination_inner.image.usage_transfer_destination() {
+        return Err(CheckResolveImageError::MissingTransferDestinationUsage);
+    }
+
+    if source.samples() == 1 {
+        return Err(CheckResolveImageError::ExpectedMultisampled);
+    }
+
+    if destination.samples() != 1 {
+        return Err(CheckResolveImageError::UnexpectedMultisampled);
+    }
+
+    if !source.has_color() {
+        return Err(CheckResolveImageError::NotColorFormat);
+    }
+
+    if source.format() != destination.format() {
+        return Err(CheckResolveImageError::FormatMismatch);
+    }
+
+    // The destination is written as if it were a color attachment.
+    if !destination
+        .format()
+        .properties(device.physical_device())
+        .optimal_tiling_features
+        .color_attachment
+    {
+        return Err(CheckResolveImageError::DestinationFormatNotSupported);
+    }
+
+    let source_dimensions = match source.dimensions().mipmap_dimensions(source_mip_level) {
+        Some(d) => d,
+        None => return Err(CheckResolveImageError::SourceCoordinatesOutOfRange),
+    };
+
+    let destination_dimensions = match destination
+        .dimensions()
+        .mipmap_dimensions(destination_mip_level)
+    {
+        Some(d) => d,
+        None => return Err(CheckResolveImageError::DestinationCoordinatesOutOfRange),
+    };
+
+    if source_base_array_layer + layer_count > source_dimensions.array_layers() {
+        return Err(CheckResolveImageError::SourceCoordinatesOutOfRange);
+    }
+
+    if destination_base_array_layer + layer_count > destination_dimensions.array_layers() {
+        return Err(CheckResolveImageError::DestinationCoordinatesOutOfRange);
+    }
+
+    let source_size = source_dimensions.width_height_depth();
+    let destination_size = destination_dimensions.width_height_depth();
+
+    for dim in 0..3 {
+        if source_offset[dim] < 0 || source_offset[dim] as u32 + extent[dim] > source_size[dim] {
+            return Err(CheckResolveImageError::SourceCoordinatesOutOfRange);
+        }
+
+        if destination_offset[dim] < 0
+            || destination_offset[dim] as u32 + extent[dim] > destination_size[dim]
+        {
+            return Err(CheckResolveImageError::DestinationCoordinatesOutOfRange);
+        }
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_resolve_image`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckResolveImageError {
+    /// The source is missing the transfer source usage.
+    MissingTransferSourceUsage,
+    /// The destination is missing the transfer destination usage.
+    MissingTransferDestinationUsage,
+    /// The source must be multisampled.
+    ExpectedMultisampled,
+    /// The destination must not be multisampled.
+    UnexpectedMultisampled,
+    /// Only color images can be resolved.
+    NotColorFormat,
+    /// The source and destination must have the same format.
+    FormatMismatch,
+    /// The format of the destination doesn't support being used as a color attachment.
+    DestinationFormatNotSupported,
+    /// The offsets, array layers and/or mipmap levels are out of range in the source image.
+    SourceCoordinatesOutOfRange,
+    /// The offsets, array layers and/or mipmap levels are out of range in the destination image.
+    DestinationCoordinatesOutOfRange,
+}
+
+impl error::Error for CheckResolveImageError {}
+
+impl fmt::Display for CheckResolveImageError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckResolveImageError::MissingTransferSourceUsage => {
+                    "the source is missing the transfer source usage"
+                }
+                CheckResolveImageError::MissingTransferDestinationUsage => {
+                    "the destination is missing the transfer destination usage"
+                }
+                CheckResolveImageError::ExpectedMultisampled => "the source must be multisampled",
+                CheckResolveImageError::UnexpectedMultisampled => {
+                    "the destination must not be multisampled"
+                }
+                CheckResolveImageError::NotColorFormat => "only color images can be resolved",
+                CheckResolveImageError::FormatMismatch => {
+                    "the source and destination must have the same format"
+                }
+                CheckResolveImageError::DestinationFormatNotSupported => {
+                    "the format of the destination doesn't support being used as a color \
+                     attachment"
+                }
+                CheckResolveImageError::SourceCoordinatesOutOfRange => {
+                    "the offsets, array layers and/or mipmap levels are out of range in the source \
+                     image"
+                }
+                CheckResolveImageError::DestinationCoordinatesOutOfRange => {
+                    "the offsets, array layers and/or mipmap levels are out of range in the \
+                     destination image"
+                }
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use format::Format;
+    use image::AttachmentImage;
+    use image::ImageUsage;
+
+    #[test]
+    fn single_sampled_source() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let usage = ImageUsage {
+            transfer_source: true,
+            transfer_destination: true,
+            ..ImageUsage::none()
+        };
+        let source =
+            AttachmentImage::with_usage(device.clone(), [32, 32], Format::R8G8B8A8Unorm, usage)
+                .unwrap();
+        let destination =
+            AttachmentImage::with_usage(device.clone(), [32, 32], Format::R8G8B8A8Unorm, usage)
+                .unwrap();
+
+        match check_resolve_image(
+            &device,
+            &source,
+            [0, 0, 0],
+            0,
+            0,
+            &destination,
+            [0, 0, 0],
+            0,
+            0,
+            [32, 32, 1],
+            1,
+        ) {
+            Err(CheckResolveImageError::ExpectedMultisampled) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use buffer::BufferAccess;
+use device::Device;
+use device::DeviceOwned;
+use pipeline::RayTracingPipelineAbstract;
+use pipeline::ShaderBindingTable;
+use VulkanObject;
+
+/// Checks whether rays can be traced with `pipeline` and a shader binding table stored at the
+/// start of `buffer`.
+///
+/// # Panic
+///
+/// - Panics if the buffer was not created with `device`.
+///
+pub fn check_trace_rays<P, B>(
+    device: &Device,
+    pipeline: &P,
+    buffer: &B,
+    shader_binding_table: &ShaderBindingTable,
+    dimensions: [u32; 3],
+) -> Result<(), CheckTraceRaysError>
+where
+    P: ?Sized + RayTracingPipelineAbstract,
+    B: ?Sized + BufferAccess,
+{
+    assert_eq!(
+        buffer.inner().buffer.device().internal_object(),
+        device.internal_object()
+    );
+
+    if !device.loaded_extensions().nv_ray_tracing {
+        return Err(CheckTraceRaysError::ExtensionNotEnabled);
+    }
+
+    if !shader_binding_table.is_for_pipeline(pipeline) {
+        return Err(CheckTraceRaysError::WrongPipeline);
+    }
+
+    if !buffer.inner().buffer.usage_ray_tracing() {
+        return Err(CheckTraceRaysError::BufferMissingUsage);
+    }
+
+    if buffer.size() < shader_binding_table.data().len() {
+        return Err(CheckTraceRaysError::BufferTooSmall {
+            required: shader_binding_table.data().len(),
+            obtained: buffer.size(),
+        });
+    }
+
+    if buffer.inner().offset % shader_binding_table.base_alignment() != 0 {
+        return Err(CheckTraceRaysError::BufferNotAligned);
+    }
+
+    let max = device
+        .physical_device()
+        .limits()
+        .max_compute_work_group_count();
+
+    if dimensions[0] > max[0] || dimensions[1] > max[1] || dimensions[2] > max[2] {
+        return Err(CheckTraceRaysError::UnsupportedDimensions {
+            requested: dimensions,
+            max_supported: max,
+        });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking whether tracing rays is valid.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckTraceRaysError {
+    /// The `nv_ray_tracing` extension is not enabled.
+    ExtensionNotEnabled,
+    /// The shader binding table was not built by the pipeline.
+    WrongPipeline,
+    /// The "ray tracing" usage must be enabled on the shader binding table buffer.
+    BufferMissingUsage,
+    /// The buffer is too small to contain the shader binding table.
+    BufferTooSmall {
+        /// Size of the shader binding table.
+        required: usize,
+        /// Size of the buffer.
+        obtained: usize,
+    },
+    /// The start of the buffer is not aligned to the `shaderGroupBaseAlignment` limit.
+    BufferNotAligned,
+    /// The dimensions are too large for the device's limits.
+    UnsupportedDimensions {
+        /// The requested dimensions.
+        requested: [u32; 3],
+        /// The actual supported dimensions.
+        max_supported: [u32; 3],
+    },
+}
+
+impl error::Error for CheckTraceRaysError {}
+
+impl fmt::Display for CheckTraceRaysError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                CheckTraceRaysError::ExtensionNotEnabled => {
+                    "the `nv_ray_tracing` extension is not enabled"
+                }
+                CheckTraceRaysError::WrongPipeline => {
+                    "the shader binding table was not built by the pipeline"
+                }
+                CheckTraceRaysError::BufferMissingUsage => {
+                    "the ray tracing usage must be enabled on the shader binding table buffer"
+                }
+                CheckTraceRaysError::BufferTooSmall { .. } => {
+                    "the buffer is too small to contain the shader binding table"
+                }
+                CheckTraceRaysError::BufferNotAligned => {
+                    "the start of the buffer is not aligned to the shader group base alignment"
+                }
+                CheckTraceRaysError::UnsupportedDimensions { .. } => {
+                    "the dimensions are too large for the device's limits"
+                }
+            }
+        )
+    }
+}
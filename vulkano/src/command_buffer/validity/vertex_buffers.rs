@@ -13,10 +13,14 @@ use std::fmt;
 use buffer::BufferAccess;
 use device::DeviceOwned;
 use pipeline::vertex::VertexSource;
+use pipeline::GraphicsPipelineAbstract;
 use VulkanObject;
 
 /// Checks whether vertex buffers can be bound.
 ///
+/// The buffers must match the vertex input definition of `pipeline`: there must be one buffer
+/// per binding, and the size of each buffer must be a multiple of the stride of its binding.
+///
 /// # Panic
 ///
 /// - Panics if one of the vertex buffers was not created with the same device as `pipeline`.
@@ -26,9 +30,21 @@ pub fn check_vertex_buffers<P, V>(
     vertex_buffers: V,
 ) -> Result<CheckVertexBuffer, CheckVertexBufferError>
 where
-    P: DeviceOwned + VertexSource<V>,
+    P: GraphicsPipelineAbstract + VertexSource<V>,
 {
+    if pipeline.has_mesh_shader() {
+        return Err(CheckVertexBufferError::MeshPipeline);
+    }
+
     let (vertex_buffers, vertex_count, instance_count) = pipeline.decode(vertex_buffers);
+    let bindings = pipeline.vertex_input_bindings();
+
+    if vertex_buffers.len() != bindings.len() {
+        return Err(CheckVertexBufferError::WrongNumberOfBuffers {
+            expected: bindings.len(),
+            obtained: vertex_buffers.len(),
+        });
+    }
 
     for (num, buf) in vertex_buffers.iter().enumerate() {
         assert_eq!(
@@ -39,6 +55,19 @@ where
         if !buf.inner().buffer.usage_vertex_buffer() {
             return Err(CheckVertexBufferError::BufferMissingUsage { num_buffer: num });
         }
+
+        let stride = bindings
+            .iter()
+            .find(|&&(binding, _, _)| binding as usize == num)
+            .map(|&(_, stride, _)| stride);
+        if let Some(stride) = stride {
+            if stride != 0 && buf.size() % stride != 0 {
+                return Err(CheckVertexBufferError::StrideMismatch {
+                    num_buffer: num,
+                    stride,
+                });
+            }
+        }
     }
 
     Ok(CheckVertexBuffer {
@@ -61,11 +90,31 @@ pub struct CheckVertexBuffer {
 /// Error that can happen when checking whether the vertex buffers are valid.
 #[derive(Debug, Copy, Clone)]
 pub enum CheckVertexBufferError {
+    /// The pipeline has a mesh shader, and must be drawn with the `draw_mesh_tasks` commands.
+    MeshPipeline,
+
     /// The "vertex buffer" usage must be enabled on the buffer.
     BufferMissingUsage {
         /// Index of the buffer that is missing usage.
         num_buffer: usize,
     },
+
+    /// The number of vertex buffers doesn't match the number of bindings of the pipeline.
+    WrongNumberOfBuffers {
+        /// Number of bindings of the pipeline.
+        expected: usize,
+        /// Number of buffers that were passed.
+        obtained: usize,
+    },
+
+    /// The size of a vertex buffer is not a multiple of the stride of its binding, which means
+    /// that its elements don't have the layout expected by the pipeline.
+    StrideMismatch {
+        /// Index of the buffer.
+        num_buffer: usize,
+        /// Stride of the binding in the pipeline, in bytes.
+        stride: usize,
+    },
 }
 
 impl error::Error for CheckVertexBufferError {}
@@ -77,9 +126,18 @@ impl fmt::Display for CheckVertexBufferError {
             fmt,
             "{}",
             match *self {
+                CheckVertexBufferError::MeshPipeline => {
+                    "the pipeline has a mesh shader and must be drawn with the mesh tasks commands"
+                }
                 CheckVertexBufferError::BufferMissingUsage { .. } => {
                     "the vertex buffer usage is missing on a vertex buffer"
                 }
+                CheckVertexBufferError::WrongNumberOfBuffers { .. } => {
+                    "the number of vertex buffers doesn't match the vertex input of the pipeline"
+                }
+                CheckVertexBufferError::StrideMismatch { .. } => {
+                    "the size of a vertex buffer is not a multiple of the stride of its binding"
+                }
             }
         )
     }
@@ -91,7 +91,8 @@ impl DescriptorDesc {
     ///  tessellation_evaluation: true,
     ///  geometry: true,
     ///  fragment: true,
-    ///  compute: true
+    ///  compute: true,
+    ///  ..ShaderStages::none()
     ///}, readonly: false };
     ///let desc_sub = DescriptorDesc{ ty: Sampler, array_count: 1, stages: ShaderStages{
     ///  vertex: true,
@@ -99,7 +100,8 @@ impl DescriptorDesc {
     ///  tessellation_evaluation: false,
     ///  geometry: false,
     ///  fragment: true,
-    ///  compute: false
+    ///  compute: false,
+    ///  ..ShaderStages::none()
     ///}, readonly: true };
     ///
     ///assert_eq!(desc_super.is_superset_of(&desc_sub).unwrap(), ());
@@ -143,7 +145,8 @@ impl DescriptorDesc {
     ///  tessellation_evaluation: false,
     ///  geometry: true,
     ///  fragment: false,
-    ///  compute: true
+    ///  compute: true,
+    ///  ..ShaderStages::none()
     ///}, readonly: false };
     ///
     ///let desc_part2 = DescriptorDesc{ ty: Sampler, array_count: 1, stages: ShaderStages{
@@ -152,7 +155,8 @@ impl DescriptorDesc {
     ///  tessellation_evaluation: true,
     ///  geometry: false,
     ///  fragment: true,
-    ///  compute: true
+    ///  compute: true,
+    ///  ..ShaderStages::none()
     ///}, readonly: true };
     ///
     ///let desc_union = DescriptorDesc{ ty: Sampler, array_count: 2, stages: ShaderStages{
@@ -161,7 +165,8 @@ impl DescriptorDesc {
     ///  tessellation_evaluation: true,
     ///  geometry: true,
     ///  fragment: true,
-    ///  compute: true
+    ///  compute: true,
+    ///  ..ShaderStages::none()
     ///}, readonly: false };
     ///
     ///assert_eq!(desc_part1.union(&desc_part2), Some(desc_union));
@@ -659,10 +664,30 @@ pub struct ShaderStages {
     pub fragment: bool,
     /// `True` means that the descriptor will be used by the compute shader.
     pub compute: bool,
+    /// `True` means that the descriptor will be used by the task shader.
+    pub task: bool,
+    /// `True` means that the descriptor will be used by the mesh shader.
+    pub mesh: bool,
+    /// `True` means that the descriptor will be used by the ray generation shader.
+    pub raygen: bool,
+    /// `True` means that the descriptor will be used by the any-hit shader.
+    pub any_hit: bool,
+    /// `True` means that the descriptor will be used by the closest-hit shader.
+    pub closest_hit: bool,
+    /// `True` means that the descriptor will be used by the miss shader.
+    pub miss: bool,
+    /// `True` means that the descriptor will be used by the intersection shader.
+    pub intersection: bool,
+    /// `True` means that the descriptor will be used by the callable shader.
+    pub callable: bool,
 }
 
 impl ShaderStages {
-    /// Creates a `ShaderStages` struct will all stages set to `true`.
+    /// Creates a `ShaderStages` struct will all the graphics and compute stages set to `true`.
+    ///
+    /// The task, mesh and ray tracing stages are left to `false`, as they require the
+    /// `ext_mesh_shader` and `nv_ray_tracing` extensions. Use `all_mesh()` and
+    /// `all_ray_tracing()` to add them.
     // TODO: add example
     #[inline]
     pub fn all() -> ShaderStages {
@@ -673,6 +698,7 @@ impl ShaderStages {
             geometry: true,
             fragment: true,
             compute: true,
+            ..ShaderStages::none()
         }
     }
 
@@ -687,6 +713,14 @@ impl ShaderStages {
             geometry: false,
             fragment: false,
             compute: false,
+            task: false,
+            mesh: false,
+            raygen: false,
+            any_hit: false,
+            closest_hit: false,
+            miss: false,
+            intersection: false,
+            callable: false,
         }
     }
 
@@ -700,7 +734,7 @@ impl ShaderStages {
             tessellation_evaluation: true,
             geometry: true,
             fragment: true,
-            compute: false,
+            ..ShaderStages::none()
         }
     }
 
@@ -709,12 +743,34 @@ impl ShaderStages {
     #[inline]
     pub fn compute() -> ShaderStages {
         ShaderStages {
-            vertex: false,
-            tessellation_control: false,
-            tessellation_evaluation: false,
-            geometry: false,
-            fragment: false,
             compute: true,
+            ..ShaderStages::none()
+        }
+    }
+
+    /// Creates a `ShaderStages` struct with the task and mesh stages set to `true`.
+    // TODO: add example
+    #[inline]
+    pub fn all_mesh() -> ShaderStages {
+        ShaderStages {
+            task: true,
+            mesh: true,
+            ..ShaderStages::none()
+        }
+    }
+
+    /// Creates a `ShaderStages` struct with all the ray tracing stages set to `true`.
+    // TODO: add example
+    #[inline]
+    pub fn all_ray_tracing() -> ShaderStages {
+        ShaderStages {
+            raygen: true,
+            any_hit: true,
+            closest_hit: true,
+            miss: true,
+            intersection: true,
+            callable: true,
+            ..ShaderStages::none()
         }
     }
 
@@ -728,6 +784,14 @@ impl ShaderStages {
             && (self.geometry || !other.geometry)
             && (self.fragment || !other.fragment)
             && (self.compute || !other.compute)
+            && (self.task || !other.task)
+            && (self.mesh || !other.mesh)
+            && (self.raygen || !other.raygen)
+            && (self.any_hit || !other.any_hit)
+            && (self.closest_hit || !other.closest_hit)
+            && (self.miss || !other.miss)
+            && (self.intersection || !other.intersection)
+            && (self.callable || !other.callable)
         {
             Ok(())
         } else {
@@ -745,6 +809,14 @@ impl ShaderStages {
             || (self.geometry && other.geometry)
             || (self.fragment && other.fragment)
             || (self.compute && other.compute)
+            || (self.task && other.task)
+            || (self.mesh && other.mesh)
+            || (self.raygen && other.raygen)
+            || (self.any_hit && other.any_hit)
+            || (self.closest_hit && other.closest_hit)
+            || (self.miss && other.miss)
+            || (self.intersection && other.intersection)
+            || (self.callable && other.callable)
     }
 
     #[inline]
@@ -768,8 +840,52 @@ impl ShaderStages {
         if self.compute {
             result |= vk::SHADER_STAGE_COMPUTE_BIT;
         }
+        if self.task {
+            result |= vk::SHADER_STAGE_TASK_BIT_EXT;
+        }
+        if self.mesh {
+            result |= vk::SHADER_STAGE_MESH_BIT_EXT;
+        }
+        if self.raygen {
+            result |= vk::SHADER_STAGE_RAYGEN_BIT_NV;
+        }
+        if self.any_hit {
+            result |= vk::SHADER_STAGE_ANY_HIT_BIT_NV;
+        }
+        if self.closest_hit {
+            result |= vk::SHADER_STAGE_CLOSEST_HIT_BIT_NV;
+        }
+        if self.miss {
+            result |= vk::SHADER_STAGE_MISS_BIT_NV;
+        }
+        if self.intersection {
+            result |= vk::SHADER_STAGE_INTERSECTION_BIT_NV;
+        }
+        if self.callable {
+            result |= vk::SHADER_STAGE_CALLABLE_BIT_NV;
+        }
         result
     }
+
+    #[inline]
+    pub(crate) fn from_vulkan_bits(bits: vk::ShaderStageFlags) -> ShaderStages {
+        ShaderStages {
+            vertex: (bits & vk::SHADER_STAGE_VERTEX_BIT) != 0,
+            tessellation_control: (bits & vk::SHADER_STAGE_TESSELLATION_CONTROL_BIT) != 0,
+            tessellation_evaluation: (bits & vk::SHADER_STAGE_TESSELLATION_EVALUATION_BIT) != 0,
+            geometry: (bits & vk::SHADER_STAGE_GEOMETRY_BIT) != 0,
+            fragment: (bits & vk::SHADER_STAGE_FRAGMENT_BIT) != 0,
+            compute: (bits & vk::SHADER_STAGE_COMPUTE_BIT) != 0,
+            task: (bits & vk::SHADER_STAGE_TASK_BIT_EXT) != 0,
+            mesh: (bits & vk::SHADER_STAGE_MESH_BIT_EXT) != 0,
+            raygen: (bits & vk::SHADER_STAGE_RAYGEN_BIT_NV) != 0,
+            any_hit: (bits & vk::SHADER_STAGE_ANY_HIT_BIT_NV) != 0,
+            closest_hit: (bits & vk::SHADER_STAGE_CLOSEST_HIT_BIT_NV) != 0,
+            miss: (bits & vk::SHADER_STAGE_MISS_BIT_NV) != 0,
+            intersection: (bits & vk::SHADER_STAGE_INTERSECTION_BIT_NV) != 0,
+            callable: (bits & vk::SHADER_STAGE_CALLABLE_BIT_NV) != 0,
+        }
+    }
 }
 
 impl BitOr for ShaderStages {
@@ -784,6 +900,14 @@ impl BitOr for ShaderStages {
             geometry: self.geometry || other.geometry,
             fragment: self.fragment || other.fragment,
             compute: self.compute || other.compute,
+            task: self.task || other.task,
+            mesh: self.mesh || other.mesh,
+            raygen: self.raygen || other.raygen,
+            any_hit: self.any_hit || other.any_hit,
+            closest_hit: self.closest_hit || other.closest_hit,
+            miss: self.miss || other.miss,
+            intersection: self.intersection || other.intersection,
+            callable: self.callable || other.callable,
         }
     }
 }
@@ -798,6 +922,14 @@ impl From<ShaderStages> for PipelineStages {
             geometry_shader: stages.geometry,
             fragment_shader: stages.fragment,
             compute_shader: stages.compute,
+            task_shader: stages.task,
+            mesh_shader: stages.mesh,
+            ray_tracing_shader: stages.raygen
+                || stages.any_hit
+                || stages.closest_hit
+                || stages.miss
+                || stages.intersection
+                || stages.callable,
             ..PipelineStages::none()
         }
     }
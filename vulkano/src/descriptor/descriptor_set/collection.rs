@@ -26,6 +26,18 @@ pub unsafe trait DescriptorSetsCollection {
     /// Returns `None` if out of range.
     // TODO: remove ; user should just use `into_vec` instead
     fn descriptor(&self, set: usize, binding: usize) -> Option<DescriptorDesc>;
+
+    /// Returns the number of descriptor sets of the collections of this type, or `None` if it
+    /// isn't the same for all of them.
+    ///
+    /// The default implementation returns `None`.
+    #[inline]
+    fn num_sets() -> Option<usize>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 unsafe impl DescriptorSetsCollection for () {
@@ -43,6 +55,11 @@ unsafe impl DescriptorSetsCollection for () {
     fn descriptor(&self, _: usize, _: usize) -> Option<DescriptorDesc> {
         None
     }
+
+    #[inline]
+    fn num_sets() -> Option<usize> {
+        Some(0)
+    }
 }
 
 unsafe impl<T> DescriptorSetsCollection for T
@@ -69,6 +86,11 @@ where
             _ => None,
         }
     }
+
+    #[inline]
+    fn num_sets() -> Option<usize> {
+        Some(1)
+    }
 }
 
 unsafe impl<T> DescriptorSetsCollection for Vec<T>
@@ -155,6 +177,11 @@ macro_rules! impl_collection {
 
                 None
             }
+
+            #[inline]
+            fn num_sets() -> Option<usize> {
+                Some(1 + [$(stringify!($others)),+].len())
+            }
         }
 
         impl_collection!($($others),+);
@@ -660,6 +660,10 @@ impl<R> PersistentDescriptorSetBuilderArray<R> {
                     ));
                 }
 
+                if !image_view.identity_swizzle() {
+                    return Err(PersistentDescriptorSetError::NotIdentitySwizzled);
+                }
+
                 if multisampled && image_view.samples() == 1 {
                     return Err(PersistentDescriptorSetError::ExpectedMultisampled);
                 } else if !multisampled && image_view.samples() != 1 {
@@ -880,6 +884,10 @@ where
         ));
     }
 
+    if !desc.sampled && !image_view.identity_swizzle() {
+        return Err(PersistentDescriptorSetError::NotIdentitySwizzled);
+    }
+
     let image_view_ty = DescriptorImageDescDimensions::from_dimensions(image_view.dimensions());
     if image_view_ty != desc.dimensions {
         return Err(PersistentDescriptorSetError::ImageViewTypeMismatch {
@@ -1189,6 +1197,9 @@ pub enum PersistentDescriptorSetError {
         /// Type of the image view that was passed.
         obtained: DescriptorImageDescDimensions,
     },
+
+    /// Storage images and input attachments must have identity swizzling.
+    NotIdentitySwizzled,
 }
 
 impl error::Error for PersistentDescriptorSetError {}
@@ -1236,6 +1247,9 @@ impl fmt::Display for PersistentDescriptorSetError {
                 PersistentDescriptorSetError::ImageViewTypeMismatch { .. } => {
                     "the type of an image view doesn't match what was expected"
                 }
+                PersistentDescriptorSetError::NotIdentitySwizzled => {
+                    "storage images and input attachments must have identity swizzling"
+                }
             }
         )
     }
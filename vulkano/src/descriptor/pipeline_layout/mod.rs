@@ -47,10 +47,14 @@
 //! between multiple pipelines that each use different descriptors. To do so, you have to create a
 //! pipeline layout object in advance and pass it when you create the pipelines.
 //!
-//! TODO: write this section
+//! Pipelines whose layouts are compatible for a given set keep the descriptor sets up to that
+//! set bound when switching from one to the other. A `PipelineLayoutRegistry` can be used to
+//! obtain the same layout object for identical descriptions, so that the descriptor set layouts
+//! are shared as well.
 
 pub use self::empty::EmptyPipelineDesc;
 pub use self::limits_check::PipelineLayoutLimitsError;
+pub use self::registry::PipelineLayoutRegistry;
 pub use self::runtime_desc::RuntimePipelineDesc;
 pub use self::runtime_desc::RuntimePipelineDescError;
 pub use self::sys::PipelineLayout;
@@ -63,12 +67,14 @@ pub use self::traits::PipelineLayoutNotSupersetError;
 pub use self::traits::PipelineLayoutPushConstantsCompatible;
 pub use self::traits::PipelineLayoutSetsCompatible;
 pub use self::traits::PipelineLayoutSuperset;
+pub use self::traits::TypedPipelineLayout;
 pub use self::union::PipelineLayoutDescUnion;
 
 pub(crate) use self::tweaks::PipelineLayoutDescTweaks;
 
 mod empty;
 mod limits_check;
+mod registry;
 mod runtime_desc;
 mod sys;
 mod traits;
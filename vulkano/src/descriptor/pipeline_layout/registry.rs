@@ -0,0 +1,128 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::Weak;
+
+use descriptor::pipeline_layout::PipelineLayout;
+use descriptor::pipeline_layout::PipelineLayoutCreationError;
+use descriptor::pipeline_layout::PipelineLayoutDesc;
+use descriptor::pipeline_layout::RuntimePipelineDesc;
+use device::Device;
+use device::DeviceOwned;
+
+/// Registry of pipeline layouts that hands out the same layout object for identical
+/// descriptions.
+///
+/// Creating a separate pipeline layout for each pipeline prevents descriptor sets from staying
+/// bound when switching between pipelines. When layouts are obtained through a registry, pipelines
+/// that use identical descriptions share the same layout and the same descriptor set layouts, and
+/// the command buffer builders can skip rebinding the descriptor sets that remain valid.
+///
+/// The registry only holds weak references. A layout is destroyed when the last pipeline that
+/// uses it is destroyed, and a new one is created the next time it is requested.
+pub struct PipelineLayoutRegistry {
+    device: Arc<Device>,
+    layouts: Mutex<Vec<Weak<PipelineLayout<RuntimePipelineDesc>>>>,
+}
+
+impl PipelineLayoutRegistry {
+    /// Builds a new empty registry.
+    #[inline]
+    pub fn new(device: Arc<Device>) -> PipelineLayoutRegistry {
+        PipelineLayoutRegistry {
+            device: device,
+            layouts: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns a pipeline layout whose description is identical to `desc`.
+    ///
+    /// If a living layout of the registry matches `desc`, it is returned. Otherwise a new layout
+    /// is created and added to the registry.
+    pub fn get<D>(
+        &self,
+        desc: &D,
+    ) -> Result<Arc<PipelineLayout<RuntimePipelineDesc>>, PipelineLayoutCreationError>
+    where
+        D: ?Sized + PipelineLayoutDesc,
+    {
+        let mut layouts = self.layouts.lock().unwrap();
+        layouts.retain(|layout| layout.upgrade().is_some());
+
+        for layout in layouts.iter() {
+            if let Some(layout) = layout.upgrade() {
+                if layout.desc().is_identical_to(desc) {
+                    return Ok(layout);
+                }
+            }
+        }
+
+        let layout = Arc::new(PipelineLayout::new(
+            self.device.clone(),
+            RuntimePipelineDesc::from_desc(desc),
+        )?);
+        layouts.push(Arc::downgrade(&layout));
+        Ok(layout)
+    }
+
+    /// Returns the number of layouts of the registry that are still alive.
+    #[inline]
+    pub fn len(&self) -> usize {
+        let layouts = self.layouts.lock().unwrap();
+        layouts
+            .iter()
+            .filter(|layout| layout.upgrade().is_some())
+            .count()
+    }
+}
+
+unsafe impl DeviceOwned for PipelineLayoutRegistry {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use descriptor::descriptor::ShaderStages;
+    use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+    use descriptor::pipeline_layout::PipelineLayoutRegistry;
+    use descriptor::pipeline_layout::RuntimePipelineDesc;
+    use std::iter;
+    use std::sync::Arc;
+
+    #[test]
+    fn dedupes_identical_layouts() {
+        let (device, _) = gfx_dev_and_queue!();
+        let registry = PipelineLayoutRegistry::new(device);
+
+        let range = |size| PipelineLayoutDescPcRange {
+            offset: 0,
+            size: size,
+            stages: ShaderStages::all(),
+        };
+        let desc = |size| {
+            RuntimePipelineDesc::new(iter::empty::<iter::Empty<_>>(), iter::once(range(size)))
+                .unwrap()
+        };
+
+        let a = registry.get(&desc(16)).unwrap();
+        let b = registry.get(&desc(16)).unwrap();
+        let c = registry.get(&desc(32)).unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert_eq!(registry.len(), 2);
+
+        drop(c);
+        assert_eq!(registry.len(), 1);
+    }
+}
@@ -63,6 +63,28 @@ impl RuntimePipelineDesc {
             push_constants,
         })
     }
+
+    /// Builds a `RuntimePipelineDesc` that is a copy of another description.
+    pub(crate) fn from_desc<D>(desc: &D) -> RuntimePipelineDesc
+    where
+        D: ?Sized + PipelineLayoutDesc,
+    {
+        let descriptors = (0..desc.num_sets())
+            .map(|set| {
+                (0..desc.num_bindings_in_set(set).unwrap_or(0))
+                    .map(|binding| desc.descriptor(set, binding))
+                    .collect()
+            })
+            .collect();
+        let push_constants = (0..desc.num_push_constants_ranges())
+            .filter_map(|num| desc.push_constants_range(num))
+            .collect();
+
+        RuntimePipelineDesc {
+            descriptors,
+            push_constants,
+        }
+    }
 }
 
 unsafe impl PipelineLayoutDesc for RuntimePipelineDesc {
@@ -103,6 +103,56 @@ pub unsafe trait PipelineLayoutDesc {
         PipelineLayoutDescUnion::new(self, other)
     }
 
+    /// Returns true if `self` and `other` are compatible for set number `set`.
+    ///
+    /// Two layouts are compatible for set N if they define identical descriptors in the sets 0
+    /// to N included, and identical push constant ranges. When switching between pipelines whose
+    /// layouts are compatible for set N, the descriptor sets 0 to N that are bound remain valid
+    /// and don't need to be bound again.
+    fn is_compatible_for_set<T>(&self, other: &T, set: usize) -> bool
+    where
+        Self: Sized,
+        T: ?Sized + PipelineLayoutDesc,
+    {
+        if set >= self.num_sets() || set >= other.num_sets() {
+            return false;
+        }
+
+        for s in 0..=set {
+            let num_bindings = self.num_bindings_in_set(s);
+            if num_bindings != other.num_bindings_in_set(s) {
+                return false;
+            }
+
+            for b in 0..num_bindings.unwrap_or(0) {
+                if self.descriptor(s, b) != other.descriptor(s, b) {
+                    return false;
+                }
+            }
+        }
+
+        push_constants_identical(self, other)
+    }
+
+    /// Returns true if `self` and `other` describe exactly the same sets, descriptors and push
+    /// constant ranges.
+    #[inline]
+    fn is_identical_to<T>(&self, other: &T) -> bool
+    where
+        Self: Sized,
+        T: ?Sized + PipelineLayoutDesc,
+    {
+        if self.num_sets() != other.num_sets() {
+            return false;
+        }
+
+        if self.num_sets() == 0 {
+            push_constants_identical(self, other)
+        } else {
+            self.is_compatible_for_set(other, self.num_sets() - 1)
+        }
+    }
+
     /// Checks whether this description fulfills the device limits requirements.
     #[inline]
     fn check_against_limits(
@@ -124,9 +174,20 @@ pub unsafe trait PipelineLayoutDesc {
     }
 }
 
+// Returns true if both layouts have the same push constant ranges, in the same order.
+fn push_constants_identical<A, B>(a: &A, b: &B) -> bool
+where
+    A: ?Sized + PipelineLayoutDesc,
+    B: ?Sized + PipelineLayoutDesc,
+{
+    a.num_push_constants_ranges() == b.num_push_constants_ranges()
+        && (0..a.num_push_constants_ranges())
+            .all(|n| a.push_constants_range(n) == b.push_constants_range(n))
+}
+
 /// Description of a range of the push constants of a pipeline layout.
 // TODO: should contain the layout as well
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct PipelineLayoutDescPcRange {
     /// Offset in bytes from the start of the push constants to this range.
     pub offset: usize,
@@ -341,3 +402,23 @@ where
         true
     }
 }
+
+/// Pipeline layout whose descriptor sets and push constants have types known at compile time.
+///
+/// Typically implemented on the description of the layout of a pipeline, so that a
+/// `TypedGraphicsPipeline` can derive the types of the descriptor sets and push constants that it
+/// accepts from its layout.
+pub trait TypedPipelineLayout {
+    /// Type of the collection of descriptor sets to bind with the layout.
+    type Sets: DescriptorSetsCollection;
+    /// Type of the push constants of the layout.
+    type PushConstants;
+}
+
+impl<D> TypedPipelineLayout for PipelineLayout<D>
+where
+    D: TypedPipelineLayout,
+{
+    type Sets = D::Sets;
+    type PushConstants = D::PushConstants;
+}
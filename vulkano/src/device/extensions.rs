@@ -110,6 +110,8 @@ device_extensions! {
     khr_display_swapchain => b"VK_KHR_display_swapchain",
     khr_sampler_mirror_clamp_to_edge => b"VK_KHR_sampler_mirror_clamp_to_edge",
     khr_maintenance1 => b"VK_KHR_maintenance1",
+    khr_maintenance2 => b"VK_KHR_maintenance2",
+    khr_image_format_list => b"VK_KHR_image_format_list",
     khr_get_memory_requirements2 => b"VK_KHR_get_memory_requirements2",
     khr_dedicated_allocation => b"VK_KHR_dedicated_allocation",
     khr_incremental_present => b"VK_KHR_incremental_present",
@@ -118,6 +120,26 @@ device_extensions! {
     ext_debug_utils => b"VK_EXT_debug_utils",
     khr_multiview => b"VK_KHR_multiview",
     ext_full_screen_exclusive => b"VK_EXT_full_screen_exclusive",
+    ext_buffer_device_address => b"VK_EXT_buffer_device_address",
+    khr_external_memory => b"VK_KHR_external_memory",
+    khr_external_memory_fd => b"VK_KHR_external_memory_fd",
+    khr_external_memory_win32 => b"VK_KHR_external_memory_win32",
+    ext_external_memory_dma_buf => b"VK_EXT_external_memory_dma_buf",
+    khr_external_semaphore => b"VK_KHR_external_semaphore",
+    khr_external_semaphore_fd => b"VK_KHR_external_semaphore_fd",
+    khr_external_semaphore_win32 => b"VK_KHR_external_semaphore_win32",
+    khr_external_fence => b"VK_KHR_external_fence",
+    khr_external_fence_fd => b"VK_KHR_external_fence_fd",
+    khr_external_fence_win32 => b"VK_KHR_external_fence_win32",
+    khr_synchronization2 => b"VK_KHR_synchronization2",
+    ext_vertex_attribute_divisor => b"VK_EXT_vertex_attribute_divisor",
+    ext_conservative_rasterization => b"VK_EXT_conservative_rasterization",
+    ext_line_rasterization => b"VK_EXT_line_rasterization",
+    khr_pipeline_executable_properties => b"VK_KHR_pipeline_executable_properties",
+    nv_ray_tracing => b"VK_NV_ray_tracing",
+    ext_mesh_shader => b"VK_EXT_mesh_shader",
+    khr_draw_indirect_count => b"VK_KHR_draw_indirect_count",
+    khr_device_group => b"VK_KHR_device_group",
 }
 
 /// This helper type can only be instantiated inside this module.
@@ -91,10 +91,12 @@
 
 use fnv::FnvHasher;
 use smallvec::SmallVec;
+use std::cmp;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::error;
 use std::ffi::CStr;
+use std::ffi::CString;
 use std::fmt;
 use std::hash::BuildHasherDefault;
 use std::hash::Hash;
@@ -106,13 +108,22 @@ use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::MutexGuard;
 use std::sync::Weak;
+use std::time::Duration;
 
+use command_buffer::pool::standard::StandardCommandPoolBuilder;
 use command_buffer::pool::StandardCommandPool;
+use command_buffer::AutoCommandBufferBuilder;
+use command_buffer::BuildError;
+use command_buffer::CommandBuffer;
+use command_buffer::CommandBufferExecError;
 use descriptor::descriptor_set::StdDescriptorPool;
 use instance::Instance;
 use instance::PhysicalDevice;
 use instance::QueueFamily;
 use memory::pool::StdMemoryPool;
+use sync::FlushError;
+use sync::GpuFuture;
+use version::Version;
 
 use check_errors;
 use vk;
@@ -128,11 +139,14 @@ pub use features::Features;
 mod extensions;
 
 use format::Format;
+use format::FormatFeatures;
+use format::FormatProperties;
 use image::ImageCreateFlags;
 use image::ImageFormatProperties;
 use image::ImageTiling;
 use image::ImageType;
 use image::ImageUsage;
+use image::SparseImageFormatProperties;
 
 /// Represents a Vulkan context.
 pub struct Device {
@@ -151,6 +165,9 @@ pub struct Device {
     fence_pool: Mutex<Vec<vk::Fence>>,
     semaphore_pool: Mutex<Vec<vk::Semaphore>>,
     event_pool: Mutex<Vec<vk::Event>>,
+    wait_watchdog: Mutex<Option<Duration>>,
+    // Names assigned to command buffers with `set_object_name`, indexed by type and handle.
+    object_names: Mutex<HashMap<(vk::ObjectType, u64), CString, BuildHasherDefault<FnvHasher>>>,
 }
 
 // The `StandardCommandPool` type doesn't implement Send/Sync, so we have to manually reimplement
@@ -346,6 +363,8 @@ impl Device {
             fence_pool: Mutex::new(Vec::new()),
             semaphore_pool: Mutex::new(Vec::new()),
             event_pool: Mutex::new(Vec::new()),
+            wait_watchdog: Mutex::new(None),
+            object_names: Mutex::new(HashMap::default()),
         });
 
         // Iterator for the produced queues.
@@ -392,6 +411,16 @@ impl Device {
         PhysicalDevice::from_index(&self.instance, self.physical_device).unwrap()
     }
 
+    /// Returns the version of Vulkan that can be used with this device, which is the lowest of
+    /// the versions of the instance and of the physical device.
+    #[inline]
+    pub fn api_version(&self) -> Version {
+        cmp::min(
+            self.instance.api_version(),
+            self.physical_device().api_version(),
+        )
+    }
+
     /// Returns an iterator to the list of queues families that this device uses.
     ///
     /// > **Note**: Will return `-> impl ExactSizeIterator<Item = QueueFamily>` in the future.
@@ -420,6 +449,33 @@ impl Device {
         &self.extensions
     }
 
+    /// Enables or disables the wait watchdog.
+    ///
+    /// When enabled, waiting on a `FenceSignalFuture` for longer than `timeout` prints the
+    /// operations that the future depends on to the standard error output, then keeps waiting.
+    /// These are the submissions of command buffers, with the names given to them with
+    /// `set_object_name`, the acquisitions of swapchain images, and the events waited on by these
+    /// command buffers that are not set. This helps finding out why a frame is frozen. The
+    /// watchdog is disabled by default.
+    #[inline]
+    pub fn set_wait_watchdog(&self, timeout: Option<Duration>) {
+        *self.wait_watchdog.lock().unwrap() = timeout;
+    }
+
+    /// Returns the timeout of the wait watchdog, or `None` if it is disabled.
+    #[inline]
+    pub fn wait_watchdog(&self) -> Option<Duration> {
+        *self.wait_watchdog.lock().unwrap()
+    }
+
+    /// Returns true if the pipeline barriers and the queue submissions use the commands of the
+    /// `khr_synchronization2` extension, which is the case if the `synchronization2` feature is
+    /// enabled.
+    #[inline]
+    pub(crate) fn uses_synchronization2(&self) -> bool {
+        self.extensions.khr_synchronization2 && self.features.synchronization2
+    }
+
     /// Returns the standard memory pool used by default if you don't provide any other pool.
     pub fn standard_pool(me: &Arc<Self>) -> Arc<StdMemoryPool> {
         let mut pool = me.standard_pool.lock().unwrap();
@@ -476,6 +532,63 @@ impl Device {
         }
     }
 
+    /// Records commands into a transient command buffer, submits it to `queue` and blocks until
+    /// the GPU has finished executing it.
+    ///
+    /// The closure receives a builder for a one-time-submit primary command buffer allocated from
+    /// the standard command pool of the queue's family. Once the closure returns, the command
+    /// buffer is built, submitted, and waited upon with a fence. Its resources are released
+    /// before this function returns, and the value returned by the closure is passed through.
+    ///
+    /// This is meant for things like uploading textures or running a quick compute job in tools
+    /// and tests. Since it stalls the calling thread, you shouldn't use it in a frame loop.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `queue` doesn't belong to this device.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vulkano::buffer::BufferUsage;
+    /// use vulkano::buffer::CpuAccessibleBuffer;
+    /// use vulkano::device::Device;
+    /// # let device: std::sync::Arc<vulkano::device::Device> = return;
+    /// # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+    ///
+    /// let buffer =
+    ///     CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0u32).unwrap();
+    ///
+    /// Device::run_commands(&device, queue.clone(), |builder| {
+    ///     builder.fill_buffer(buffer.clone(), 42).unwrap();
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(*buffer.read().unwrap(), 42);
+    /// ```
+    pub fn run_commands<F, R>(
+        me: &Arc<Self>,
+        queue: Arc<Queue>,
+        f: F,
+    ) -> Result<R, RunCommandsError>
+    where
+        F: FnOnce(&mut AutoCommandBufferBuilder<StandardCommandPoolBuilder>) -> R,
+    {
+        assert_eq!(**me, **queue.device());
+
+        let mut builder =
+            AutoCommandBufferBuilder::primary_one_time_submit(me.clone(), queue.family())?;
+        let output = f(&mut builder);
+        let command_buffer = builder.build()?;
+
+        command_buffer
+            .execute(queue)?
+            .then_signal_fence_and_flush()?
+            .wait(None)?;
+
+        Ok(output)
+    }
+
     /// Used to track the number of allocations on this device.
     ///
     /// To ensure valid usage of the Vulkan API, we cannot call `vkAllocateMemory` when
@@ -530,9 +643,89 @@ impl Device {
             pObjectName: name.as_ptr(),
         };
         check_errors(self.vk.SetDebugUtilsObjectNameEXT(self.device, &info))?;
+        // Only the names of command buffers are used by the wait watchdog.
+        if ty == vk::OBJECT_TYPE_COMMAND_BUFFER {
+            self.object_names
+                .lock()
+                .unwrap()
+                .insert((ty, object), name.to_owned());
+        }
         Ok(())
     }
 
+    /// Returns the name that was last assigned to the command buffer `object` with
+    /// `set_object_name`, if any.
+    ///
+    /// This is used by the wait watchdog to name the command buffers in its reports. The names of
+    /// other types of objects are not recorded, and the name of a command buffer is forgotten
+    /// when it is freed or returned to its pool, since its handle can then be reused.
+    ///
+    /// # Panics
+    /// * If `object` is not owned by this device.
+    pub fn object_name<T: VulkanObject + DeviceOwned>(&self, object: &T) -> Option<CString> {
+        assert!(object.device().internal_object() == self.internal_object());
+        self.object_names
+            .lock()
+            .unwrap()
+            .get(&(T::TYPE, object.internal_object().value()))
+            .cloned()
+    }
+
+    // Forgets the name assigned to `object` with `set_object_name`, before its handle is freed
+    // or reused.
+    pub(crate) fn forget_object_name<T: VulkanObject>(&self, object: &T) {
+        self.object_names
+            .lock()
+            .unwrap()
+            .remove(&(T::TYPE, object.internal_object().value()));
+    }
+
+    /// Retrieves the properties of a format when used by the physical device of this device.
+    ///
+    /// This is a shortcut for `format.properties(device.physical_device())`.
+    #[inline]
+    pub fn format_properties(&self, format: Format) -> FormatProperties {
+        format.properties(self.physical_device())
+    }
+
+    /// Returns the first format of `candidates` that supports all the features of
+    /// `required_features` with the given tiling, or `None` if none of them does.
+    ///
+    /// The candidates should be ordered from the most to the least preferred.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vulkano::format::Format;
+    /// use vulkano::format::FormatFeatures;
+    /// use vulkano::image::ImageTiling;
+    /// # let device: std::sync::Arc<vulkano::device::Device> = return;
+    ///
+    /// let storage_format = device.find_supported_format(
+    ///     vec![Format::R16G16B16A16Sfloat, Format::R32G32B32A32Sfloat],
+    ///     ImageTiling::Optimal,
+    ///     &FormatFeatures {
+    ///         storage_image: true,
+    ///         ..FormatFeatures::none()
+    ///     },
+    /// );
+    /// ```
+    pub fn find_supported_format<I>(
+        &self,
+        candidates: I,
+        tiling: ImageTiling,
+        required_features: &FormatFeatures,
+    ) -> Option<Format>
+    where
+        I: IntoIterator<Item = Format>,
+    {
+        candidates.into_iter().find(|&format| {
+            self.format_properties(format)
+                .tiling_features(tiling)
+                .superset_of(required_features)
+        })
+    }
+
     /// Checks the given combination of image attributes/configuration for compatibility with the physical device.
     ///
     /// Returns a struct with additional capabilities available for this image configuration.
@@ -569,6 +762,60 @@ impl Device {
             }
         }
     }
+
+    /// Returns the properties of sparse images with the given attributes, one for each aspect or
+    /// group of aspects of the format.
+    ///
+    /// Returns an empty list if sparse images with these attributes aren't supported. The
+    /// `image_granularity` of the returned properties is the size in texels of the blocks that
+    /// can be bound individually to a partially resident image.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `samples` is not a power of two between 1 and 64.
+    ///
+    pub fn sparse_image_format_properties(
+        &self,
+        format: Format,
+        ty: ImageType,
+        samples: u32,
+        usage: ImageUsage,
+        tiling: ImageTiling,
+    ) -> Vec<SparseImageFormatProperties> {
+        assert!(samples.is_power_of_two() && samples <= 64);
+
+        let vk_i = self.instance().pointers();
+        let physical_device = self.physical_device().internal_object();
+
+        unsafe {
+            let mut num = 0;
+            vk_i.GetPhysicalDeviceSparseImageFormatProperties(
+                physical_device,
+                format as u32,
+                ty.into(),
+                samples,
+                usage.to_usage_bits(),
+                tiling.into(),
+                &mut num,
+                ptr::null_mut(),
+            );
+
+            let mut properties = Vec::with_capacity(num as usize);
+            vk_i.GetPhysicalDeviceSparseImageFormatProperties(
+                physical_device,
+                format as u32,
+                ty.into(),
+                samples,
+                usage.to_usage_bits(),
+                tiling.into(),
+                &mut num,
+                properties.as_mut_ptr(),
+            );
+            properties.set_len(num as usize);
+
+            properties.into_iter().map(Into::into).collect()
+        }
+    }
 }
 
 impl fmt::Debug for Device {
@@ -771,6 +1018,77 @@ impl From<Error> for DeviceCreationError {
     }
 }
 
+/// Error that can be returned by `Device::run_commands`.
+#[derive(Clone, Debug)]
+pub enum RunCommandsError {
+    /// Not enough memory to allocate the command buffer.
+    OomError(OomError),
+    /// Error while building the command buffer.
+    BuildError(BuildError),
+    /// Error while submitting the command buffer.
+    ExecError(CommandBufferExecError),
+    /// Error while flushing the submission or waiting for it to finish.
+    FlushError(FlushError),
+}
+
+impl error::Error for RunCommandsError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            RunCommandsError::OomError(ref err) => Some(err),
+            RunCommandsError::BuildError(ref err) => Some(err),
+            RunCommandsError::ExecError(ref err) => Some(err),
+            RunCommandsError::FlushError(ref err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for RunCommandsError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                RunCommandsError::OomError(_) => "not enough memory to allocate the command buffer",
+                RunCommandsError::BuildError(_) => "error while building the command buffer",
+                RunCommandsError::ExecError(_) => "error while submitting the command buffer",
+                RunCommandsError::FlushError(_) => {
+                    "error while flushing the submission or waiting for it to finish"
+                }
+            }
+        )
+    }
+}
+
+impl From<OomError> for RunCommandsError {
+    #[inline]
+    fn from(err: OomError) -> RunCommandsError {
+        RunCommandsError::OomError(err)
+    }
+}
+
+impl From<BuildError> for RunCommandsError {
+    #[inline]
+    fn from(err: BuildError) -> RunCommandsError {
+        RunCommandsError::BuildError(err)
+    }
+}
+
+impl From<CommandBufferExecError> for RunCommandsError {
+    #[inline]
+    fn from(err: CommandBufferExecError) -> RunCommandsError {
+        RunCommandsError::ExecError(err)
+    }
+}
+
+impl From<FlushError> for RunCommandsError {
+    #[inline]
+    fn from(err: FlushError) -> RunCommandsError {
+        RunCommandsError::FlushError(err)
+    }
+}
+
 /// Represents a queue where commands can be submitted.
 // TODO: should use internal synchronization?
 #[derive(Debug)]
@@ -850,13 +1168,38 @@ unsafe impl SynchronizedVulkanObject for Queue {
 
 #[cfg(test)]
 mod tests {
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
     use device::Device;
     use device::DeviceCreationError;
     use device::DeviceExtensions;
     use features::Features;
+    use format::Format;
+    use format::FormatFeatures;
+    use image::ImageTiling;
     use instance;
     use std::sync::Arc;
 
+    #[test]
+    fn find_depth_format() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        // `D16Unorm` must always be supported as a depth attachment.
+        let format = device.find_supported_format(
+            vec![Format::D16Unorm],
+            ImageTiling::Optimal,
+            &FormatFeatures {
+                depth_stencil_attachment: true,
+                ..FormatFeatures::none()
+            },
+        );
+        assert_eq!(format, Some(Format::D16Unorm));
+
+        let format =
+            device.find_supported_format(vec![], ImageTiling::Optimal, &FormatFeatures::none());
+        assert_eq!(format, None);
+    }
+
     #[test]
     fn one_ref() {
         let (mut device, _) = gfx_dev_and_queue!();
@@ -942,4 +1285,58 @@ mod tests {
             _ => panic!(),
         };
     }
+
+    #[test]
+    fn run_commands() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer =
+            CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), false, 0u32)
+                .unwrap();
+
+        let output = Device::run_commands(&device, queue, |builder| {
+            builder.fill_buffer(buffer.clone(), 0x12345678).unwrap();
+            5
+        })
+        .unwrap();
+
+        assert_eq!(output, 5);
+        assert_eq!(*buffer.read().unwrap(), 0x12345678);
+    }
+
+    #[test]
+    fn wait_watchdog() {
+        use command_buffer::AutoCommandBufferBuilder;
+        use command_buffer::CommandBuffer;
+        use std::time::Duration;
+        use sync::now;
+        use sync::GpuFuture;
+
+        let (device, queue) = gfx_dev_and_queue!();
+
+        assert_eq!(device.wait_watchdog(), None);
+        device.set_wait_watchdog(Some(Duration::from_millis(100)));
+        assert_eq!(device.wait_watchdog(), Some(Duration::from_millis(100)));
+
+        let command_buffer = AutoCommandBufferBuilder::new(device.clone(), queue.family())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(device.object_name(command_buffer.inner()), None);
+
+        let future = now(device.clone())
+            .then_signal_semaphore()
+            .then_execute(queue.clone(), command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+        let mut dependencies = Vec::new();
+        future.describe_dependencies(&mut dependencies);
+        assert_eq!(dependencies.len(), 3);
+        assert!(dependencies[0].starts_with("fence signal"));
+        assert!(dependencies[1].starts_with("execution of a command buffer"));
+        assert!(dependencies[2].starts_with("semaphore signal"));
+
+        future.wait(None).unwrap();
+    }
 }
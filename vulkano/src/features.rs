@@ -280,6 +280,23 @@ pub struct Features {
 
     pub shader_float16: bool,
     pub shader_int8: bool,
+
+    pub vertex_attribute_instance_rate_divisor: bool,
+    pub vertex_attribute_instance_rate_zero_divisor: bool,
+
+    pub rectangular_lines: bool,
+    pub bresenham_lines: bool,
+    pub smooth_lines: bool,
+    pub stippled_rectangular_lines: bool,
+    pub stippled_bresenham_lines: bool,
+    pub stippled_smooth_lines: bool,
+
+    pub pipeline_executable_info: bool,
+
+    pub task_shader: bool,
+    pub mesh_shader: bool,
+
+    pub synchronization2: bool,
 }
 
 pub(crate) struct FeaturesFfi {
@@ -291,6 +308,11 @@ pub(crate) struct FeaturesFfi {
     i8_storage: vk::PhysicalDevice8BitStorageFeatures,
     i16_storage: vk::PhysicalDevice16BitStorageFeatures,
     f16_i8: vk::PhysicalDeviceShaderFloat16Int8Features,
+    vertex_attribute_divisor: vk::PhysicalDeviceVertexAttributeDivisorFeaturesEXT,
+    line_rasterization: vk::PhysicalDeviceLineRasterizationFeaturesEXT,
+    pipeline_executable_properties: vk::PhysicalDevicePipelineExecutablePropertiesFeaturesKHR,
+    mesh_shader: vk::PhysicalDeviceMeshShaderFeaturesEXT,
+    synchronization2: vk::PhysicalDeviceSynchronization2FeaturesKHR,
 }
 
 macro_rules! features {
@@ -554,4 +576,51 @@ features! {
         shader_int8 => shaderInt8,
       ],
     },
+    extension {
+      ty: vk::PhysicalDeviceVertexAttributeDivisorFeaturesEXT,
+      ffi_name: vertex_attribute_divisor,
+      sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_FEATURES_EXT,
+      fields: [
+        vertex_attribute_instance_rate_divisor => vertexAttributeInstanceRateDivisor,
+        vertex_attribute_instance_rate_zero_divisor => vertexAttributeInstanceRateZeroDivisor,
+      ],
+    },
+    extension {
+      ty: vk::PhysicalDeviceLineRasterizationFeaturesEXT,
+      ffi_name: line_rasterization,
+      sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_LINE_RASTERIZATION_FEATURES_EXT,
+      fields: [
+        rectangular_lines => rectangularLines,
+        bresenham_lines => bresenhamLines,
+        smooth_lines => smoothLines,
+        stippled_rectangular_lines => stippledRectangularLines,
+        stippled_bresenham_lines => stippledBresenhamLines,
+        stippled_smooth_lines => stippledSmoothLines,
+      ],
+    },
+    extension {
+      ty: vk::PhysicalDevicePipelineExecutablePropertiesFeaturesKHR,
+      ffi_name: pipeline_executable_properties,
+      sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_PIPELINE_EXECUTABLE_PROPERTIES_FEATURES_KHR,
+      fields: [
+        pipeline_executable_info => pipelineExecutableInfo,
+      ],
+    },
+    extension {
+      ty: vk::PhysicalDeviceMeshShaderFeaturesEXT,
+      ffi_name: mesh_shader,
+      sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_MESH_SHADER_FEATURES_EXT,
+      fields: [
+        task_shader => taskShader,
+        mesh_shader => meshShader,
+      ],
+    },
+    extension {
+      ty: vk::PhysicalDeviceSynchronization2FeaturesKHR,
+      ffi_name: synchronization2,
+      sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_SYNCHRONIZATION_2_FEATURES_KHR,
+      fields: [
+        synchronization2 => synchronization2,
+      ],
+    },
 }
@@ -101,12 +101,18 @@
 //!
 //! // TODO: storage formats
 //!
+//! For other formats, use `Device::find_supported_format` to pick the first format of a list of
+//! candidates that supports the features you need.
+//!
 
 use std::mem::MaybeUninit;
 use std::vec::IntoIter as VecIntoIter;
 use std::{error, fmt, mem};
 
+use features::Features;
 use half::f16;
+use image::ImageTiling;
+use image::ImageType;
 use instance::PhysicalDevice;
 
 use vk;
@@ -217,6 +223,21 @@ macro_rules! formats {
                 }
             }
 
+            /// Returns true if the device features required to create an image with this format
+            /// are enabled in `features`.
+            ///
+            /// Block-compressed formats require the `texture_compression_bc`,
+            /// `texture_compression_etc2` or `texture_compression_astc_ldr` feature depending on
+            /// their family. The other formats don't require any feature.
+            #[inline]
+            pub fn required_features_enabled(&self, features: &Features) -> bool {
+                match *self {
+                    $(
+                        Format::$name => formats!(__inner_feature__ features $($f_ty)*),
+                    )+
+                }
+            }
+
             /// Returns the `Format` corresponding to a Vulkan constant.
             pub(crate) fn from_vulkan_num(val: u32) -> Option<Format> {
                 match val {
@@ -440,6 +461,9 @@ macro_rules! formats {
     (__inner_ty__ $name:ident depthstencil) => { FormatTy::DepthStencil };
     (__inner_ty__ $name:ident compressed=$f:tt) => { FormatTy::Compressed };
 
+    (__inner_feature__ $features:ident compressed=$f:ident) => { $features.$f };
+    (__inner_feature__ $features:ident $($f_ty:tt)*) => { true };
+
 
     (__inner_strongstorage__ $name:ident [$ty:ty; $dim:expr]) => {
         formats!(__inner_strongstorage_common__ $name [$ty; $dim]);
@@ -650,6 +674,575 @@ formats! {
     ASTC_12x12SrgbBlock => FORMAT_ASTC_12x12_SRGB_BLOCK [(12, 12)] [Some(16)] [compressed=texture_compression_astc_ldr] {u8},
 }
 
+impl Format {
+    /// Returns the first format of `candidates` that can be used as a depth-stencil attachment
+    /// with optimal tiling on `physical_device`, or `None` if none of them is supported.
+    pub fn first_supported_depth_stencil_format<I>(
+        physical_device: PhysicalDevice,
+        candidates: I,
+    ) -> Option<Format>
+    where
+        I: IntoIterator<Item = Format>,
+    {
+        candidates.into_iter().find(|format| {
+            format
+                .properties(physical_device)
+                .optimal_tiling_features
+                .depth_stencil_attachment
+        })
+    }
+
+    /// Returns the most precise depth format that is supported by `physical_device` as a
+    /// depth-stencil attachment.
+    ///
+    /// If `with_stencil` is true, only formats that also have a stencil component are
+    /// considered. The implementation is required to support either `D24Unorm_S8Uint` or
+    /// `D32Sfloat_S8Uint`, and `D16Unorm` is always supported, so this should never return
+    /// `None` in practice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vulkano::format::Format;
+    /// # let physical_device: vulkano::instance::PhysicalDevice = return;
+    ///
+    /// // Prefers `D32Sfloat` and falls back to `D24Unorm_S8Uint` or `D16Unorm`.
+    /// let depth_format = Format::supported_depth_format(physical_device, false).unwrap();
+    /// ```
+    pub fn supported_depth_format(
+        physical_device: PhysicalDevice,
+        with_stencil: bool,
+    ) -> Option<Format> {
+        if with_stencil {
+            Format::first_supported_depth_stencil_format(
+                physical_device,
+                [
+                    Format::D32Sfloat_S8Uint,
+                    Format::D24Unorm_S8Uint,
+                    Format::D16Unorm_S8Uint,
+                ]
+                .iter()
+                .cloned(),
+            )
+        } else {
+            Format::first_supported_depth_stencil_format(
+                physical_device,
+                [
+                    Format::D32Sfloat,
+                    Format::D32Sfloat_S8Uint,
+                    Format::D24Unorm_S8Uint,
+                    Format::X8_D24UnormPack32,
+                    Format::D16Unorm,
+                ]
+                .iter()
+                .cloned(),
+            )
+        }
+    }
+
+    /// Returns the numeric type of the components of the format.
+    ///
+    /// For depth-stencil formats, this is the numeric type of the depth component.
+    pub fn numeric_type(&self) -> NumericType {
+        match *self {
+            Format::R4G4UnormPack8
+            | Format::R4G4B4A4UnormPack16
+            | Format::B4G4R4A4UnormPack16
+            | Format::R5G6B5UnormPack16
+            | Format::B5G6R5UnormPack16
+            | Format::R5G5B5A1UnormPack16
+            | Format::B5G5R5A1UnormPack16
+            | Format::A1R5G5B5UnormPack16
+            | Format::R8Unorm
+            | Format::R8G8Unorm
+            | Format::R8G8B8Unorm
+            | Format::B8G8R8Unorm
+            | Format::R8G8B8A8Unorm
+            | Format::B8G8R8A8Unorm
+            | Format::A8B8G8R8UnormPack32
+            | Format::A2R10G10B10UnormPack32
+            | Format::A2B10G10R10UnormPack32
+            | Format::R16Unorm
+            | Format::R16G16Unorm
+            | Format::R16G16B16Unorm
+            | Format::R16G16B16A16Unorm
+            | Format::D16Unorm
+            | Format::X8_D24UnormPack32
+            | Format::D16Unorm_S8Uint
+            | Format::D24Unorm_S8Uint
+            | Format::BC1_RGBUnormBlock
+            | Format::BC1_RGBAUnormBlock
+            | Format::BC2UnormBlock
+            | Format::BC3UnormBlock
+            | Format::BC4UnormBlock
+            | Format::BC5UnormBlock
+            | Format::BC7UnormBlock
+            | Format::ETC2_R8G8B8UnormBlock
+            | Format::ETC2_R8G8B8A1UnormBlock
+            | Format::ETC2_R8G8B8A8UnormBlock
+            | Format::EAC_R11UnormBlock
+            | Format::EAC_R11G11UnormBlock
+            | Format::ASTC_4x4UnormBlock
+            | Format::ASTC_5x4UnormBlock
+            | Format::ASTC_5x5UnormBlock
+            | Format::ASTC_6x5UnormBlock
+            | Format::ASTC_6x6UnormBlock
+            | Format::ASTC_8x5UnormBlock
+            | Format::ASTC_8x6UnormBlock
+            | Format::ASTC_8x8UnormBlock
+            | Format::ASTC_10x5UnormBlock
+            | Format::ASTC_10x6UnormBlock
+            | Format::ASTC_10x8UnormBlock
+            | Format::ASTC_10x10UnormBlock
+            | Format::ASTC_12x10UnormBlock
+            | Format::ASTC_12x12UnormBlock => NumericType::Unorm,
+            Format::R8Snorm
+            | Format::R8G8Snorm
+            | Format::R8G8B8Snorm
+            | Format::B8G8R8Snorm
+            | Format::R8G8B8A8Snorm
+            | Format::B8G8R8A8Snorm
+            | Format::A8B8G8R8SnormPack32
+            | Format::A2R10G10B10SnormPack32
+            | Format::A2B10G10R10SnormPack32
+            | Format::R16Snorm
+            | Format::R16G16Snorm
+            | Format::R16G16B16Snorm
+            | Format::R16G16B16A16Snorm
+            | Format::BC4SnormBlock
+            | Format::BC5SnormBlock
+            | Format::EAC_R11SnormBlock
+            | Format::EAC_R11G11SnormBlock => NumericType::Snorm,
+            Format::R8Uscaled
+            | Format::R8G8Uscaled
+            | Format::R8G8B8Uscaled
+            | Format::B8G8R8Uscaled
+            | Format::R8G8B8A8Uscaled
+            | Format::B8G8R8A8Uscaled
+            | Format::A8B8G8R8UscaledPack32
+            | Format::A2R10G10B10UscaledPack32
+            | Format::A2B10G10R10UscaledPack32
+            | Format::R16Uscaled
+            | Format::R16G16Uscaled
+            | Format::R16G16B16Uscaled
+            | Format::R16G16B16A16Uscaled => NumericType::Uscaled,
+            Format::R8Sscaled
+            | Format::R8G8Sscaled
+            | Format::R8G8B8Sscaled
+            | Format::B8G8R8Sscaled
+            | Format::R8G8B8A8Sscaled
+            | Format::B8G8R8A8Sscaled
+            | Format::A8B8G8R8SscaledPack32
+            | Format::A2R10G10B10SscaledPack32
+            | Format::A2B10G10R10SscaledPack32
+            | Format::R16Sscaled
+            | Format::R16G16Sscaled
+            | Format::R16G16B16Sscaled
+            | Format::R16G16B16A16Sscaled => NumericType::Sscaled,
+            Format::R8Uint
+            | Format::R8G8Uint
+            | Format::R8G8B8Uint
+            | Format::B8G8R8Uint
+            | Format::R8G8B8A8Uint
+            | Format::B8G8R8A8Uint
+            | Format::A8B8G8R8UintPack32
+            | Format::A2R10G10B10UintPack32
+            | Format::A2B10G10R10UintPack32
+            | Format::R16Uint
+            | Format::R16G16Uint
+            | Format::R16G16B16Uint
+            | Format::R16G16B16A16Uint
+            | Format::R32Uint
+            | Format::R32G32Uint
+            | Format::R32G32B32Uint
+            | Format::R32G32B32A32Uint
+            | Format::R64Uint
+            | Format::R64G64Uint
+            | Format::R64G64B64Uint
+            | Format::R64G64B64A64Uint
+            | Format::S8Uint => NumericType::Uint,
+            Format::R8Sint
+            | Format::R8G8Sint
+            | Format::R8G8B8Sint
+            | Format::B8G8R8Sint
+            | Format::R8G8B8A8Sint
+            | Format::B8G8R8A8Sint
+            | Format::A8B8G8R8SintPack32
+            | Format::A2R10G10B10SintPack32
+            | Format::A2B10G10R10SintPack32
+            | Format::R16Sint
+            | Format::R16G16Sint
+            | Format::R16G16B16Sint
+            | Format::R16G16B16A16Sint
+            | Format::R32Sint
+            | Format::R32G32Sint
+            | Format::R32G32B32Sint
+            | Format::R32G32B32A32Sint
+            | Format::R64Sint
+            | Format::R64G64Sint
+            | Format::R64G64B64Sint
+            | Format::R64G64B64A64Sint => NumericType::Sint,
+            Format::R8Srgb
+            | Format::R8G8Srgb
+            | Format::R8G8B8Srgb
+            | Format::B8G8R8Srgb
+            | Format::R8G8B8A8Srgb
+            | Format::B8G8R8A8Srgb
+            | Format::A8B8G8R8SrgbPack32
+            | Format::BC1_RGBSrgbBlock
+            | Format::BC1_RGBASrgbBlock
+            | Format::BC2SrgbBlock
+            | Format::BC3SrgbBlock
+            | Format::BC7SrgbBlock
+            | Format::ETC2_R8G8B8SrgbBlock
+            | Format::ETC2_R8G8B8A1SrgbBlock
+            | Format::ETC2_R8G8B8A8SrgbBlock
+            | Format::ASTC_4x4SrgbBlock
+            | Format::ASTC_5x4SrgbBlock
+            | Format::ASTC_5x5SrgbBlock
+            | Format::ASTC_6x5SrgbBlock
+            | Format::ASTC_6x6SrgbBlock
+            | Format::ASTC_8x5SrgbBlock
+            | Format::ASTC_8x6SrgbBlock
+            | Format::ASTC_8x8SrgbBlock
+            | Format::ASTC_10x5SrgbBlock
+            | Format::ASTC_10x6SrgbBlock
+            | Format::ASTC_10x8SrgbBlock
+            | Format::ASTC_10x10SrgbBlock
+            | Format::ASTC_12x10SrgbBlock
+            | Format::ASTC_12x12SrgbBlock => NumericType::Srgb,
+            Format::R16Sfloat
+            | Format::R16G16Sfloat
+            | Format::R16G16B16Sfloat
+            | Format::R16G16B16A16Sfloat
+            | Format::R32Sfloat
+            | Format::R32G32Sfloat
+            | Format::R32G32B32Sfloat
+            | Format::R32G32B32A32Sfloat
+            | Format::R64Sfloat
+            | Format::R64G64Sfloat
+            | Format::R64G64B64Sfloat
+            | Format::R64G64B64A64Sfloat
+            | Format::D32Sfloat
+            | Format::D32Sfloat_S8Uint
+            | Format::BC6HSfloatBlock => NumericType::Sfloat,
+            Format::B10G11R11UfloatPack32
+            | Format::E5B9G9R9UfloatPack32
+            | Format::BC6HUfloatBlock => NumericType::Ufloat,
+        }
+    }
+
+    /// Returns true if the color components of the format are encoded in the sRGB color space.
+    ///
+    /// Shaders read and write linear values from and to sRGB images. The conversion is done by
+    /// the implementation when sampling, blending or storing to a color attachment.
+    #[inline]
+    pub fn is_srgb(&self) -> bool {
+        self.numeric_type() == NumericType::Srgb
+    }
+
+    /// Returns the number of bits of the red, green, blue and alpha components, in this order, or
+    /// 0 for components that the format doesn't have.
+    ///
+    /// For depth and/or stencil formats, returns the number of bits of the depth and stencil
+    /// components followed by two zeros. Returns `None` for compressed formats.
+    pub fn components(&self) -> Option<[u8; 4]> {
+        match *self {
+            Format::R4G4UnormPack8 => Some([4, 4, 0, 0]),
+            Format::R4G4B4A4UnormPack16 | Format::B4G4R4A4UnormPack16 => Some([4, 4, 4, 4]),
+            Format::R5G6B5UnormPack16 | Format::B5G6R5UnormPack16 => Some([5, 6, 5, 0]),
+            Format::R5G5B5A1UnormPack16
+            | Format::B5G5R5A1UnormPack16
+            | Format::A1R5G5B5UnormPack16 => Some([5, 5, 5, 1]),
+            Format::R8Unorm
+            | Format::R8Snorm
+            | Format::R8Uscaled
+            | Format::R8Sscaled
+            | Format::R8Uint
+            | Format::R8Sint
+            | Format::R8Srgb => Some([8, 0, 0, 0]),
+            Format::R8G8Unorm
+            | Format::R8G8Snorm
+            | Format::R8G8Uscaled
+            | Format::R8G8Sscaled
+            | Format::R8G8Uint
+            | Format::R8G8Sint
+            | Format::R8G8Srgb => Some([8, 8, 0, 0]),
+            Format::R8G8B8Unorm
+            | Format::R8G8B8Snorm
+            | Format::R8G8B8Uscaled
+            | Format::R8G8B8Sscaled
+            | Format::R8G8B8Uint
+            | Format::R8G8B8Sint
+            | Format::R8G8B8Srgb
+            | Format::B8G8R8Unorm
+            | Format::B8G8R8Snorm
+            | Format::B8G8R8Uscaled
+            | Format::B8G8R8Sscaled
+            | Format::B8G8R8Uint
+            | Format::B8G8R8Sint
+            | Format::B8G8R8Srgb => Some([8, 8, 8, 0]),
+            Format::R8G8B8A8Unorm
+            | Format::R8G8B8A8Snorm
+            | Format::R8G8B8A8Uscaled
+            | Format::R8G8B8A8Sscaled
+            | Format::R8G8B8A8Uint
+            | Format::R8G8B8A8Sint
+            | Format::R8G8B8A8Srgb
+            | Format::B8G8R8A8Unorm
+            | Format::B8G8R8A8Snorm
+            | Format::B8G8R8A8Uscaled
+            | Format::B8G8R8A8Sscaled
+            | Format::B8G8R8A8Uint
+            | Format::B8G8R8A8Sint
+            | Format::B8G8R8A8Srgb
+            | Format::A8B8G8R8UnormPack32
+            | Format::A8B8G8R8SnormPack32
+            | Format::A8B8G8R8UscaledPack32
+            | Format::A8B8G8R8SscaledPack32
+            | Format::A8B8G8R8UintPack32
+            | Format::A8B8G8R8SintPack32
+            | Format::A8B8G8R8SrgbPack32 => Some([8, 8, 8, 8]),
+            Format::A2R10G10B10UnormPack32
+            | Format::A2R10G10B10SnormPack32
+            | Format::A2R10G10B10UscaledPack32
+            | Format::A2R10G10B10SscaledPack32
+            | Format::A2R10G10B10UintPack32
+            | Format::A2R10G10B10SintPack32
+            | Format::A2B10G10R10UnormPack32
+            | Format::A2B10G10R10SnormPack32
+            | Format::A2B10G10R10UscaledPack32
+            | Format::A2B10G10R10SscaledPack32
+            | Format::A2B10G10R10UintPack32
+            | Format::A2B10G10R10SintPack32 => Some([10, 10, 10, 2]),
+            Format::R16Unorm
+            | Format::R16Snorm
+            | Format::R16Uscaled
+            | Format::R16Sscaled
+            | Format::R16Uint
+            | Format::R16Sint
+            | Format::R16Sfloat
+            | Format::D16Unorm => Some([16, 0, 0, 0]),
+            Format::R16G16Unorm
+            | Format::R16G16Snorm
+            | Format::R16G16Uscaled
+            | Format::R16G16Sscaled
+            | Format::R16G16Uint
+            | Format::R16G16Sint
+            | Format::R16G16Sfloat => Some([16, 16, 0, 0]),
+            Format::R16G16B16Unorm
+            | Format::R16G16B16Snorm
+            | Format::R16G16B16Uscaled
+            | Format::R16G16B16Sscaled
+            | Format::R16G16B16Uint
+            | Format::R16G16B16Sint
+            | Format::R16G16B16Sfloat => Some([16, 16, 16, 0]),
+            Format::R16G16B16A16Unorm
+            | Format::R16G16B16A16Snorm
+            | Format::R16G16B16A16Uscaled
+            | Format::R16G16B16A16Sscaled
+            | Format::R16G16B16A16Uint
+            | Format::R16G16B16A16Sint
+            | Format::R16G16B16A16Sfloat => Some([16, 16, 16, 16]),
+            Format::R32Uint | Format::R32Sint | Format::R32Sfloat | Format::D32Sfloat => {
+                Some([32, 0, 0, 0])
+            }
+            Format::R32G32Uint | Format::R32G32Sint | Format::R32G32Sfloat => Some([32, 32, 0, 0]),
+            Format::R32G32B32Uint | Format::R32G32B32Sint | Format::R32G32B32Sfloat => {
+                Some([32, 32, 32, 0])
+            }
+            Format::R32G32B32A32Uint | Format::R32G32B32A32Sint | Format::R32G32B32A32Sfloat => {
+                Some([32, 32, 32, 32])
+            }
+            Format::R64Uint | Format::R64Sint | Format::R64Sfloat => Some([64, 0, 0, 0]),
+            Format::R64G64Uint | Format::R64G64Sint | Format::R64G64Sfloat => Some([64, 64, 0, 0]),
+            Format::R64G64B64Uint | Format::R64G64B64Sint | Format::R64G64B64Sfloat => {
+                Some([64, 64, 64, 0])
+            }
+            Format::R64G64B64A64Uint | Format::R64G64B64A64Sint | Format::R64G64B64A64Sfloat => {
+                Some([64, 64, 64, 64])
+            }
+            Format::B10G11R11UfloatPack32 => Some([11, 11, 10, 0]),
+            Format::E5B9G9R9UfloatPack32 => Some([9, 9, 9, 0]),
+            Format::X8_D24UnormPack32 => Some([24, 0, 0, 0]),
+            Format::S8Uint => Some([0, 8, 0, 0]),
+            Format::D16Unorm_S8Uint => Some([16, 8, 0, 0]),
+            Format::D24Unorm_S8Uint => Some([24, 8, 0, 0]),
+            Format::D32Sfloat_S8Uint => Some([32, 8, 0, 0]),
+            _ => None,
+        }
+    }
+
+    /// Returns the format with the same components, but in the sRGB color space instead of
+    /// `Unorm` or in `Unorm` instead of the sRGB color space. Returns `None` if there is no such
+    /// format.
+    ///
+    /// An image created with the mutable format flag can be viewed in the counterpart format of
+    /// its own format, for example to write linear values to an sRGB image from a compute shader.
+    pub fn srgb_counterpart(&self) -> Option<Format> {
+        match *self {
+            Format::R8Unorm => Some(Format::R8Srgb),
+            Format::R8Srgb => Some(Format::R8Unorm),
+            Format::R8G8Unorm => Some(Format::R8G8Srgb),
+            Format::R8G8Srgb => Some(Format::R8G8Unorm),
+            Format::R8G8B8Unorm => Some(Format::R8G8B8Srgb),
+            Format::R8G8B8Srgb => Some(Format::R8G8B8Unorm),
+            Format::B8G8R8Unorm => Some(Format::B8G8R8Srgb),
+            Format::B8G8R8Srgb => Some(Format::B8G8R8Unorm),
+            Format::R8G8B8A8Unorm => Some(Format::R8G8B8A8Srgb),
+            Format::R8G8B8A8Srgb => Some(Format::R8G8B8A8Unorm),
+            Format::B8G8R8A8Unorm => Some(Format::B8G8R8A8Srgb),
+            Format::B8G8R8A8Srgb => Some(Format::B8G8R8A8Unorm),
+            Format::A8B8G8R8UnormPack32 => Some(Format::A8B8G8R8SrgbPack32),
+            Format::A8B8G8R8SrgbPack32 => Some(Format::A8B8G8R8UnormPack32),
+            Format::BC1_RGBUnormBlock => Some(Format::BC1_RGBSrgbBlock),
+            Format::BC1_RGBSrgbBlock => Some(Format::BC1_RGBUnormBlock),
+            Format::BC1_RGBAUnormBlock => Some(Format::BC1_RGBASrgbBlock),
+            Format::BC1_RGBASrgbBlock => Some(Format::BC1_RGBAUnormBlock),
+            Format::BC2UnormBlock => Some(Format::BC2SrgbBlock),
+            Format::BC2SrgbBlock => Some(Format::BC2UnormBlock),
+            Format::BC3UnormBlock => Some(Format::BC3SrgbBlock),
+            Format::BC3SrgbBlock => Some(Format::BC3UnormBlock),
+            Format::BC7UnormBlock => Some(Format::BC7SrgbBlock),
+            Format::BC7SrgbBlock => Some(Format::BC7UnormBlock),
+            Format::ETC2_R8G8B8UnormBlock => Some(Format::ETC2_R8G8B8SrgbBlock),
+            Format::ETC2_R8G8B8SrgbBlock => Some(Format::ETC2_R8G8B8UnormBlock),
+            Format::ETC2_R8G8B8A1UnormBlock => Some(Format::ETC2_R8G8B8A1SrgbBlock),
+            Format::ETC2_R8G8B8A1SrgbBlock => Some(Format::ETC2_R8G8B8A1UnormBlock),
+            Format::ETC2_R8G8B8A8UnormBlock => Some(Format::ETC2_R8G8B8A8SrgbBlock),
+            Format::ETC2_R8G8B8A8SrgbBlock => Some(Format::ETC2_R8G8B8A8UnormBlock),
+            Format::ASTC_4x4UnormBlock => Some(Format::ASTC_4x4SrgbBlock),
+            Format::ASTC_4x4SrgbBlock => Some(Format::ASTC_4x4UnormBlock),
+            Format::ASTC_5x4UnormBlock => Some(Format::ASTC_5x4SrgbBlock),
+            Format::ASTC_5x4SrgbBlock => Some(Format::ASTC_5x4UnormBlock),
+            Format::ASTC_5x5UnormBlock => Some(Format::ASTC_5x5SrgbBlock),
+            Format::ASTC_5x5SrgbBlock => Some(Format::ASTC_5x5UnormBlock),
+            Format::ASTC_6x5UnormBlock => Some(Format::ASTC_6x5SrgbBlock),
+            Format::ASTC_6x5SrgbBlock => Some(Format::ASTC_6x5UnormBlock),
+            Format::ASTC_6x6UnormBlock => Some(Format::ASTC_6x6SrgbBlock),
+            Format::ASTC_6x6SrgbBlock => Some(Format::ASTC_6x6UnormBlock),
+            Format::ASTC_8x5UnormBlock => Some(Format::ASTC_8x5SrgbBlock),
+            Format::ASTC_8x5SrgbBlock => Some(Format::ASTC_8x5UnormBlock),
+            Format::ASTC_8x6UnormBlock => Some(Format::ASTC_8x6SrgbBlock),
+            Format::ASTC_8x6SrgbBlock => Some(Format::ASTC_8x6UnormBlock),
+            Format::ASTC_8x8UnormBlock => Some(Format::ASTC_8x8SrgbBlock),
+            Format::ASTC_8x8SrgbBlock => Some(Format::ASTC_8x8UnormBlock),
+            Format::ASTC_10x5UnormBlock => Some(Format::ASTC_10x5SrgbBlock),
+            Format::ASTC_10x5SrgbBlock => Some(Format::ASTC_10x5UnormBlock),
+            Format::ASTC_10x6UnormBlock => Some(Format::ASTC_10x6SrgbBlock),
+            Format::ASTC_10x6SrgbBlock => Some(Format::ASTC_10x6UnormBlock),
+            Format::ASTC_10x8UnormBlock => Some(Format::ASTC_10x8SrgbBlock),
+            Format::ASTC_10x8SrgbBlock => Some(Format::ASTC_10x8UnormBlock),
+            Format::ASTC_10x10UnormBlock => Some(Format::ASTC_10x10SrgbBlock),
+            Format::ASTC_10x10SrgbBlock => Some(Format::ASTC_10x10UnormBlock),
+            Format::ASTC_12x10UnormBlock => Some(Format::ASTC_12x10SrgbBlock),
+            Format::ASTC_12x10SrgbBlock => Some(Format::ASTC_12x10UnormBlock),
+            Format::ASTC_12x12UnormBlock => Some(Format::ASTC_12x12SrgbBlock),
+            Format::ASTC_12x12SrgbBlock => Some(Format::ASTC_12x12UnormBlock),
+            _ => None,
+        }
+    }
+
+    /// Returns true if an image created with the mutable format flag and the format `self` can
+    /// be viewed with the format `other`.
+    ///
+    /// Uncompressed color formats are compatible if their texels have the same size. Compressed
+    /// formats are only compatible with their sRGB counterpart.
+    pub fn is_compatible_with(&self, other: Format) -> bool {
+        if *self == other {
+            return true;
+        }
+
+        match (self.ty(), other.ty()) {
+            (FormatTy::Float, FormatTy::Float)
+            | (FormatTy::Float, FormatTy::Uint)
+            | (FormatTy::Float, FormatTy::Sint)
+            | (FormatTy::Uint, FormatTy::Float)
+            | (FormatTy::Uint, FormatTy::Uint)
+            | (FormatTy::Uint, FormatTy::Sint)
+            | (FormatTy::Sint, FormatTy::Float)
+            | (FormatTy::Sint, FormatTy::Uint)
+            | (FormatTy::Sint, FormatTy::Sint) => self.size() == other.size(),
+            (FormatTy::Compressed, FormatTy::Compressed) => self.srgb_counterpart() == Some(other),
+            _ => false,
+        }
+    }
+
+    /// Returns the standard width, height and depth in texels of the blocks of a sparse image
+    /// with this format, the given type and the given number of samples, or `None` if there is no
+    /// standard block shape for them.
+    ///
+    /// Standard block shapes are 64 KiB large and only defined for color formats whose texel
+    /// blocks have a power-of-two size. The implementation reports whether it uses them with
+    /// `SparseImageFormatProperties::nonstandard_block_size`.
+    pub fn sparse_block_shape(&self, ty: ImageType, samples: u32) -> Option<[u32; 3]> {
+        match self.ty() {
+            FormatTy::Float | FormatTy::Uint | FormatTy::Sint | FormatTy::Compressed => (),
+            _ => return None,
+        }
+
+        // Index in the tables below, from the size of a texel block.
+        let index = match self.size() {
+            Some(1) => 0,
+            Some(2) => 1,
+            Some(4) => 2,
+            Some(8) => 3,
+            Some(16) => 4,
+            _ => return None,
+        };
+
+        let shape = match (ty, samples) {
+            (ImageType::Dim2d, 1) => [
+                [256, 256, 1],
+                [256, 128, 1],
+                [128, 128, 1],
+                [128, 64, 1],
+                [64, 64, 1],
+            ],
+            (ImageType::Dim2d, 2) => [
+                [128, 256, 1],
+                [128, 128, 1],
+                [64, 128, 1],
+                [64, 64, 1],
+                [32, 64, 1],
+            ],
+            (ImageType::Dim2d, 4) => [
+                [128, 128, 1],
+                [128, 64, 1],
+                [64, 64, 1],
+                [64, 32, 1],
+                [32, 32, 1],
+            ],
+            (ImageType::Dim2d, 8) => [
+                [64, 128, 1],
+                [64, 64, 1],
+                [32, 64, 1],
+                [32, 32, 1],
+                [16, 32, 1],
+            ],
+            (ImageType::Dim2d, 16) => [
+                [64, 64, 1],
+                [64, 32, 1],
+                [32, 32, 1],
+                [32, 16, 1],
+                [16, 16, 1],
+            ],
+            (ImageType::Dim3d, 1) => [
+                [64, 32, 32],
+                [32, 32, 32],
+                [32, 32, 16],
+                [32, 16, 16],
+                [16, 16, 16],
+            ],
+            _ => return None,
+        }[index];
+
+        // The tables are in texel blocks, which are larger than one texel for compressed formats.
+        let (block_width, block_height) = self.block_dimensions();
+        Some([shape[0] * block_width, shape[1] * block_height, shape[2]])
+    }
+}
+
 pub unsafe trait FormatDesc {
     type ClearValue;
 
@@ -833,6 +1426,29 @@ impl FormatTy {
     }
 }
 
+/// Numeric type of the components of a format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NumericType {
+    /// Unsigned integer interpreted as a float between 0.0 and 1.0.
+    Unorm,
+    /// Signed integer interpreted as a float between -1.0 and 1.0.
+    Snorm,
+    /// Unsigned integer converted to a float of the same value.
+    Uscaled,
+    /// Signed integer converted to a float of the same value.
+    Sscaled,
+    /// Unsigned integer.
+    Uint,
+    /// Signed integer.
+    Sint,
+    /// Unsigned floating-point number.
+    Ufloat,
+    /// Signed floating-point number.
+    Sfloat,
+    /// Same as `Unorm`, but the color components are in the sRGB color space.
+    Srgb,
+}
+
 /// Describes a uniform value that will be used to fill an image.
 // TODO: should have the same layout as `vk::ClearValue` for performance
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -1013,6 +1629,17 @@ pub struct FormatProperties {
     pub buffer_features: FormatFeatures,
 }
 
+impl FormatProperties {
+    /// Returns the features available for images with the given tiling.
+    #[inline]
+    pub fn tiling_features(&self, tiling: ImageTiling) -> &FormatFeatures {
+        match tiling {
+            ImageTiling::Optimal => &self.optimal_tiling_features,
+            ImageTiling::Linear => &self.linear_tiling_features,
+        }
+    }
+}
+
 /// The features supported by images with a particular format.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 #[allow(missing_docs)]
@@ -1046,6 +1673,48 @@ pub struct FormatFeatures {
 }
 
 impl FormatFeatures {
+    /// Builds a `FormatFeatures` with all fields set to false.
+    #[inline]
+    pub fn none() -> FormatFeatures {
+        FormatFeatures::default()
+    }
+
+    /// Returns true if all the features of `other` are also in `self`.
+    #[inline]
+    pub fn superset_of(&self, other: &FormatFeatures) -> bool {
+        (self.sampled_image || !other.sampled_image)
+            && (self.storage_image || !other.storage_image)
+            && (self.storage_image_atomic || !other.storage_image_atomic)
+            && (self.uniform_texel_buffer || !other.uniform_texel_buffer)
+            && (self.storage_texel_buffer || !other.storage_texel_buffer)
+            && (self.storage_texel_buffer_atomic || !other.storage_texel_buffer_atomic)
+            && (self.vertex_buffer || !other.vertex_buffer)
+            && (self.color_attachment || !other.color_attachment)
+            && (self.color_attachment_blend || !other.color_attachment_blend)
+            && (self.depth_stencil_attachment || !other.depth_stencil_attachment)
+            && (self.blit_src || !other.blit_src)
+            && (self.blit_dst || !other.blit_dst)
+            && (self.sampled_image_filter_linear || !other.sampled_image_filter_linear)
+            && (self.transfer_src || !other.transfer_src)
+            && (self.transfer_dst || !other.transfer_dst)
+            && (self.midpoint_chroma_samples || !other.midpoint_chroma_samples)
+            && (self.sampled_image_ycbcr_conversion_linear_filter
+                || !other.sampled_image_ycbcr_conversion_linear_filter)
+            && (self.sampled_image_ycbcr_conversion_separate_reconstruction_filter
+                || !other.sampled_image_ycbcr_conversion_separate_reconstruction_filter)
+            && (self.sampled_image_ycbcr_conversion_chroma_reconstruction_explicit
+                || !other.sampled_image_ycbcr_conversion_chroma_reconstruction_explicit)
+            && (self.sampled_image_ycbcr_conversion_chroma_reconstruction_explicit_forceable
+                || !other.sampled_image_ycbcr_conversion_chroma_reconstruction_explicit_forceable)
+            && (self.disjoint || !other.disjoint)
+            && (self.cosited_chroma_samples || !other.cosited_chroma_samples)
+            && (self.sampled_image_filter_minmax || !other.sampled_image_filter_minmax)
+            && (self.img_sampled_image_filter_cubic || !other.img_sampled_image_filter_cubic)
+            && (self.khr_acceleration_structure_vertex_buffer
+                || !other.khr_acceleration_structure_vertex_buffer)
+            && (self.ext_fragment_density_map || !other.ext_fragment_density_map)
+    }
+
     #[inline]
     #[rustfmt::skip]
     pub(crate) fn from_bits(val: u32) -> FormatFeatures {
@@ -1079,3 +1748,67 @@ impl FormatFeatures {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use format::Format;
+    use format::NumericType;
+    use image::ImageType;
+
+    #[test]
+    fn srgb_metadata() {
+        assert!(Format::R8G8B8A8Srgb.is_srgb());
+        assert!(!Format::R8G8B8A8Unorm.is_srgb());
+        assert_eq!(Format::B8G8R8A8Srgb.numeric_type(), NumericType::Srgb);
+        assert_eq!(Format::R8G8B8A8Srgb.components(), Some([8, 8, 8, 8]));
+        assert_eq!(Format::D24Unorm_S8Uint.components(), Some([24, 8, 0, 0]));
+        assert_eq!(Format::BC1_RGBSrgbBlock.components(), None);
+
+        assert_eq!(
+            Format::R8G8B8A8Srgb.srgb_counterpart(),
+            Some(Format::R8G8B8A8Unorm)
+        );
+        assert_eq!(
+            Format::BC1_RGBUnormBlock.srgb_counterpart(),
+            Some(Format::BC1_RGBSrgbBlock)
+        );
+        assert_eq!(Format::R16G16Sfloat.srgb_counterpart(), None);
+    }
+
+    #[test]
+    fn sparse_block_shapes() {
+        assert_eq!(
+            Format::R8G8B8A8Unorm.sparse_block_shape(ImageType::Dim2d, 1),
+            Some([128, 128, 1])
+        );
+        assert_eq!(
+            Format::R16G16B16A16Sfloat.sparse_block_shape(ImageType::Dim3d, 1),
+            Some([32, 16, 16])
+        );
+        assert_eq!(
+            Format::BC1_RGBUnormBlock.sparse_block_shape(ImageType::Dim2d, 1),
+            Some([512, 256, 1])
+        );
+        assert_eq!(
+            Format::R8G8B8Unorm.sparse_block_shape(ImageType::Dim2d, 1),
+            None
+        );
+        assert_eq!(
+            Format::D32Sfloat.sparse_block_shape(ImageType::Dim2d, 1),
+            None
+        );
+        assert_eq!(
+            Format::R8Unorm.sparse_block_shape(ImageType::Dim1d, 1),
+            None
+        );
+    }
+
+    #[test]
+    fn compatible_formats() {
+        assert!(Format::R8G8B8A8Srgb.is_compatible_with(Format::R8G8B8A8Unorm));
+        assert!(Format::R8G8B8A8Unorm.is_compatible_with(Format::R32Uint));
+        assert!(!Format::R8G8B8A8Unorm.is_compatible_with(Format::R16G16B16A16Sfloat));
+        assert!(!Format::R32Sfloat.is_compatible_with(Format::D32Sfloat));
+        assert!(!Format::BC1_RGBUnormBlock.is_compatible_with(Format::BC1_RGBAUnormBlock));
+    }
+}
@@ -223,7 +223,7 @@ pub unsafe trait RenderPassDesc: RenderPassDescClearValues<Vec<ClearValue>> {
     }
 
     /// Returns true if a subpass has a depth attachment or a depth-stencil attachment whose
-    /// layout is not `DepthStencilReadOnlyOptimal`.
+    /// layout is not `DepthStencilReadOnlyOptimal` or `DepthReadOnlyStencilAttachmentOptimal`.
     #[inline]
     fn has_writable_depth(&self, subpass: u32) -> Option<bool> {
         (&self)
@@ -233,7 +233,9 @@ pub unsafe trait RenderPassDesc: RenderPassDescClearValues<Vec<ClearValue>> {
             .map(|p| {
                 let atch_num = match p.depth_stencil {
                     Some((d, l)) => {
-                        if l == ImageLayout::DepthStencilReadOnlyOptimal {
+                        if l == ImageLayout::DepthStencilReadOnlyOptimal
+                            || l == ImageLayout::DepthReadOnlyStencilAttachmentOptimal
+                        {
                             return false;
                         }
                         d
@@ -287,7 +289,7 @@ pub unsafe trait RenderPassDesc: RenderPassDescClearValues<Vec<ClearValue>> {
     }
 
     /// Returns true if a subpass has a stencil attachment or a depth-stencil attachment whose
-    /// layout is not `DepthStencilReadOnlyOptimal`.
+    /// layout is not `DepthStencilReadOnlyOptimal` or `DepthAttachmentStencilReadOnlyOptimal`.
     #[inline]
     fn has_writable_stencil(&self, subpass: u32) -> Option<bool> {
         (&self)
@@ -297,7 +299,9 @@ pub unsafe trait RenderPassDesc: RenderPassDescClearValues<Vec<ClearValue>> {
             .map(|p| {
                 let atch_num = match p.depth_stencil {
                     Some((d, l)) => {
-                        if l == ImageLayout::DepthStencilReadOnlyOptimal {
+                        if l == ImageLayout::DepthStencilReadOnlyOptimal
+                            || l == ImageLayout::DepthAttachmentStencilReadOnlyOptimal
+                        {
                             return false;
                         }
                         d
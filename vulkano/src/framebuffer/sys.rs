@@ -27,6 +27,7 @@ use framebuffer::PassDescription;
 use framebuffer::RenderPassAbstract;
 use framebuffer::RenderPassDesc;
 use framebuffer::RenderPassDescClearValues;
+use image::ImageLayout;
 
 use check_errors;
 use vk;
@@ -106,6 +107,55 @@ where
                 true
             }));
 
+        // The layouts that separate the depth and stencil aspects require `khr_maintenance2`.
+        if !device.loaded_extensions().khr_maintenance2 {
+            let is_separate = |layout: ImageLayout| {
+                layout == ImageLayout::DepthReadOnlyStencilAttachmentOptimal
+                    || layout == ImageLayout::DepthAttachmentStencilReadOnlyOptimal
+            };
+
+            let in_attachments = description
+                .attachment_descs()
+                .any(|a| is_separate(a.initial_layout) || is_separate(a.final_layout));
+            let in_subpasses = description.subpass_descs().any(|p| {
+                p.depth_stencil.map_or(false, |(_, l)| is_separate(l))
+                    || p.input_attachments.iter().any(|&(_, l)| is_separate(l))
+            });
+
+            if in_attachments || in_subpasses {
+                return Err(RenderPassCreationError::Maintenance2ExtensionNotEnabled);
+            }
+        }
+
+        // Each color attachment must be multisampled and resolved into a single-sampled
+        // attachment of the same format.
+        for pass in description.subpass_descs() {
+            if pass.resolve_attachments.is_empty() {
+                continue;
+            }
+
+            if pass.resolve_attachments.len() != pass.color_attachments.len() {
+                return Err(RenderPassCreationError::InvalidResolveAttachments);
+            }
+
+            for (&(resolve, _), &(color, _)) in pass
+                .resolve_attachments
+                .iter()
+                .zip(pass.color_attachments.iter())
+            {
+                let resolve = description.attachment_desc(resolve);
+                let color = description.attachment_desc(color);
+                let valid = match (resolve, color) {
+                    (Some(r), Some(c)) => r.samples == 1 && c.samples > 1 && r.format == c.format,
+                    _ => false,
+                };
+
+                if !valid {
+                    return Err(RenderPassCreationError::InvalidResolveAttachments);
+                }
+            }
+        }
+
         let attachments = description
             .attachment_descs()
             .map(|attachment| {
@@ -135,29 +185,6 @@ where
             .subpass_descs()
             .flat_map(|pass| {
                 // Performing some validation with debug asserts.
-                debug_assert!(
-                    pass.resolve_attachments.is_empty()
-                        || pass.resolve_attachments.len() == pass.color_attachments.len()
-                );
-                debug_assert!(pass
-                    .resolve_attachments
-                    .iter()
-                    .all(|a| attachments[a.0].samples == 1));
-                debug_assert!(
-                    pass.resolve_attachments.is_empty()
-                        || pass
-                            .color_attachments
-                            .iter()
-                            .all(|a| attachments[a.0].samples > 1)
-                );
-                debug_assert!(
-                    pass.resolve_attachments.is_empty()
-                        || pass
-                            .resolve_attachments
-                            .iter()
-                            .zip(pass.color_attachments.iter())
-                            .all(|(r, c)| { attachments[r.0].format == attachments[c.0].format })
-                );
                 debug_assert!(pass
                     .color_attachments
                     .iter()
@@ -542,6 +569,12 @@ pub enum RenderPassCreationError {
     OomError(OomError),
     /// The maximum number of color attachments has been exceeded.
     ColorAttachmentsLimitExceeded,
+    /// The `khr_maintenance2` extension must be enabled to use the
+    /// `DepthReadOnlyStencilAttachmentOptimal` or `DepthAttachmentStencilReadOnlyOptimal` layouts.
+    Maintenance2ExtensionNotEnabled,
+    /// The resolve attachments of a subpass don't match its color attachments. There must be one
+    /// single-sampled resolve attachment per multisampled color attachment, with the same format.
+    InvalidResolveAttachments,
 }
 
 impl error::Error for RenderPassCreationError {
@@ -565,6 +598,13 @@ impl fmt::Display for RenderPassCreationError {
                 RenderPassCreationError::ColorAttachmentsLimitExceeded => {
                     "the maximum number of color attachments has been exceeded"
                 }
+                RenderPassCreationError::Maintenance2ExtensionNotEnabled => {
+                    "the `khr_maintenance2` extension must be enabled to use layouts that separate \
+                     the depth and stencil aspects"
+                }
+                RenderPassCreationError::InvalidResolveAttachments => {
+                    "the resolve attachments of a subpass don't match its color attachments"
+                }
             }
         )
     }
@@ -656,4 +696,27 @@ mod tests {
         assert_ne!(granularity[0], 0);
         assert_ne!(granularity[1], 0);
     }
+
+    #[test]
+    fn resolve_format_mismatch() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let rp = single_pass_renderpass! {
+            device.clone(),
+            attachments: {
+                a: { load: Clear, store: DontCare, format: Format::R8G8B8A8Unorm, samples: 4, },
+                b: { load: DontCare, store: Store, format: Format::B8G8R8A8Unorm, samples: 1, }
+            },
+            pass: {
+                color: [a],
+                depth_stencil: {},
+                resolve: [b],
+            }
+        };
+
+        match rp {
+            Err(RenderPassCreationError::InvalidResolveAttachments) => (),
+            _ => panic!(),
+        }
+    }
 }
@@ -73,6 +73,30 @@ use sync::Sharing;
 /// or a depth buffer that is only used once) then use a transient image as it may improve
 /// performance.
 ///
+/// If the physical device has a memory type that is lazily allocated, which is typically the
+/// case of tile-based GPUs, transient images are backed by this kind of memory. Their content
+/// can then stay in the tile memory during the whole render pass and never be written to main
+/// memory. Otherwise they fall back to regular device-local memory. Use `is_lazily_allocated`
+/// to check which kind of memory was chosen.
+///
+/// # Example
+///
+/// Creating a depth buffer with the same dimensions as the images of a swapchain:
+///
+/// ```
+/// use vulkano::format::Format;
+/// use vulkano::image::AttachmentImage;
+///
+/// # let device: std::sync::Arc<vulkano::device::Device> = return;
+/// # let swapchain: std::sync::Arc<vulkano::swapchain::Swapchain<()>> = return;
+/// let depth_buffer =
+///     AttachmentImage::transient(device.clone(), swapchain.dimensions(), Format::D16Unorm)
+///         .unwrap();
+/// ```
+///
+/// When the swapchain is recreated, the attachment images must be recreated as well with the
+/// new dimensions.
+///
 // TODO: forbid reading transient images outside render passes?
 #[derive(Debug)]
 pub struct AttachmentImage<F = Format, A = PotentialDedicatedAllocation<StdMemoryPoolAlloc>> {
@@ -403,13 +427,27 @@ impl<F> AttachmentImage<F> {
     where
         F: FormatDesc,
     {
-        // TODO: check dimensions against the max_framebuffer_width/height/layers limits
+        // An attachment image that doesn't fit in a framebuffer would be useless.
+        let limits = device.physical_device().limits();
+        if dimensions[0] > limits.max_framebuffer_width()
+            || dimensions[1] > limits.max_framebuffer_height()
+        {
+            return Err(ImageCreationError::UnsupportedDimensions {
+                dimensions: ImageDimensions::Dim2d {
+                    width: dimensions[0],
+                    height: dimensions[1],
+                    array_layers: 1,
+                    cubemap_compatible: false,
+                },
+            });
+        }
 
         let is_depth = match format.format().ty() {
             FormatTy::Depth => true,
             FormatTy::DepthStencil => true,
             FormatTy::Stencil => true,
-            FormatTy::Compressed => panic!(),
+            // Compressed formats can never be used as attachments.
+            FormatTy::Compressed => return Err(ImageCreationError::UnsupportedUsage),
             _ => false,
         };
 
@@ -440,6 +478,14 @@ impl<F> AttachmentImage<F> {
             )?
         };
 
+        // Lazily allocated memory can only back transient images. If the implementation allows
+        // it for this image, we prefer it over any other kind of memory.
+        let lazily_allocated = usage.transient_attachment
+            && device
+                .physical_device()
+                .memory_types()
+                .any(|t| t.is_lazily_allocated() && mem_reqs.memory_type_bits & (1 << t.id()) != 0);
+
         let mem = MemoryPool::alloc_from_requirements(
             &Device::standard_pool(&device),
             &mem_reqs,
@@ -447,7 +493,13 @@ impl<F> AttachmentImage<F> {
             MappingRequirement::DoNotMap,
             DedicatedAlloc::Image(&image),
             |t| {
-                if t.is_device_local() {
+                if t.is_lazily_allocated() {
+                    if lazily_allocated {
+                        AllocFromRequirementsFilter::Preferred
+                    } else {
+                        AllocFromRequirementsFilter::Forbidden
+                    }
+                } else if t.is_device_local() && !lazily_allocated {
                     AllocFromRequirementsFilter::Preferred
                 } else {
                     AllocFromRequirementsFilter::Allowed
@@ -486,6 +538,19 @@ impl<F, A> AttachmentImage<F, A> {
     }
 }
 
+impl<F, A> AttachmentImage<F, A>
+where
+    A: MemoryPoolAlloc,
+{
+    /// Returns true if the image is backed by lazily allocated memory.
+    ///
+    /// This can only be the case for transient images.
+    #[inline]
+    pub fn is_lazily_allocated(&self) -> bool {
+        self.memory.memory().memory_type().is_lazily_allocated()
+    }
+}
+
 unsafe impl<F, A> ImageAccess for AttachmentImage<F, A>
 where
     F: 'static + Send + Sync,
@@ -680,6 +745,7 @@ where
 mod tests {
     use super::AttachmentImage;
     use format::Format;
+    use image::sys::ImageCreationError;
 
     #[test]
     fn create_regular() {
@@ -693,6 +759,32 @@ mod tests {
         let _img = AttachmentImage::transient(device, [32, 32], Format::R8G8B8A8Unorm).unwrap();
     }
 
+    #[test]
+    fn regular_not_lazily_allocated() {
+        let (device, _) = gfx_dev_and_queue!();
+        let img = AttachmentImage::new(device, [32, 32], Format::R8G8B8A8Unorm).unwrap();
+        assert!(!img.is_lazily_allocated());
+    }
+
+    #[test]
+    fn compressed_format() {
+        let (device, _) = gfx_dev_and_queue!();
+        match AttachmentImage::new(device, [32, 32], Format::BC1_RGBUnormBlock) {
+            Err(ImageCreationError::UnsupportedUsage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn too_large_for_framebuffer() {
+        let (device, _) = gfx_dev_and_queue!();
+        let max_width = device.physical_device().limits().max_framebuffer_width();
+        match AttachmentImage::new(device, [max_width + 1, 32], Format::R8G8B8A8Unorm) {
+            Err(ImageCreationError::UnsupportedDimensions { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
     #[test]
     fn d16_unorm_always_supported() {
         let (device, _) = gfx_dev_and_queue!();
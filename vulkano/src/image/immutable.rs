@@ -8,20 +8,30 @@
 // according to those terms.
 
 use smallvec::SmallVec;
+use std::error;
+use std::fmt;
 use std::hash::Hash;
 use std::hash::Hasher;
+use std::ops::Range;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use buffer::BufferAccess;
+use buffer::BufferContents;
 use buffer::BufferUsage;
 use buffer::CpuAccessibleBuffer;
 use buffer::TypedBufferAccess;
+use command_buffer::validity::check_generate_mipmaps_format;
 use command_buffer::AutoCommandBuffer;
 use command_buffer::AutoCommandBufferBuilder;
+use command_buffer::BuildError;
 use command_buffer::CommandBuffer;
+use command_buffer::CommandBufferExecError;
 use command_buffer::CommandBufferExecFuture;
+use command_buffer::CopyBufferImageError;
+use command_buffer::GenerateMipmapsError;
 use device::Device;
 use device::Queue;
 use format::AcceptsPixels;
@@ -34,6 +44,7 @@ use image::traits::ImageAccess;
 use image::traits::ImageContent;
 use image::traits::ImageViewAccess;
 use image::Dimensions;
+use image::ImageDimensions;
 use image::ImageInner;
 use image::ImageLayout;
 use image::ImageUsage;
@@ -47,9 +58,11 @@ use memory::pool::MemoryPoolAlloc;
 use memory::pool::PotentialDedicatedAllocation;
 use memory::pool::StdMemoryPoolAlloc;
 use memory::DedicatedAlloc;
+use memory::DeviceMemoryAllocError;
 use sync::AccessError;
 use sync::NowFuture;
 use sync::Sharing;
+use OomError;
 
 /// Image whose purpose is to be used for read-only purposes. You can write to the image once,
 /// but then you must only ever read from it.
@@ -61,14 +74,28 @@ pub struct ImmutableImage<F, A = PotentialDedicatedAllocation<StdMemoryPoolAlloc
     dimensions: Dimensions,
     memory: A,
     format: F,
-    initialized: AtomicBool,
+    // Bit N is set once the mipmap level N has been initialized. The image can only be used once
+    // all its levels have been initialized.
+    initialized_levels: AtomicU64,
+    // Bit N is set when an initialization access that writes the mipmap level N is submitted,
+    // so that each level is only written once.
+    written_levels: AtomicU64,
     layout: ImageLayout,
 }
 
+// Returns the bits of the mipmap levels in `levels`.
+#[inline]
+fn levels_mask(levels: Range<u32>) -> u64 {
+    // There are at most 32 mipmap levels, as dimensions are 32 bits integers.
+    ((1u64 << (levels.end - levels.start)) - 1) << levels.start
+}
+
 // Must not implement Clone, as that would lead to multiple `used` values.
 pub struct ImmutableImageInitialization<F, A = PotentialDedicatedAllocation<StdMemoryPoolAlloc>> {
     image: Arc<ImmutableImage<F, A>>,
     used: AtomicBool,
+    // Mipmap levels of the image that this initialization gives access to.
+    mip_levels_access: Range<u32>,
 }
 
 impl<F> ImmutableImage<F> {
@@ -124,7 +151,12 @@ impl<F> ImmutableImage<F> {
             ImageLayout::ShaderReadOnlyOptimal,
             queue_families,
         )?;
-        image.initialized.store(true, Ordering::Relaxed); // Allow uninitialized access for backwards compatibility
+        // Allow uninitialized access for backwards compatibility
+        let all_levels = levels_mask(0..image.mipmap_levels());
+        image
+            .initialized_levels
+            .store(all_levels, Ordering::Relaxed);
+        image.written_levels.store(all_levels, Ordering::Relaxed);
         Ok(image)
     }
 
@@ -204,13 +236,15 @@ impl<F> ImmutableImage<F> {
             memory: mem,
             dimensions: dimensions,
             format: format,
-            initialized: AtomicBool::new(false),
+            initialized_levels: AtomicU64::new(0),
+            written_levels: AtomicU64::new(0),
             layout: layout,
         });
 
         let init = ImmutableImageInitialization {
             image: image.clone(),
             used: AtomicBool::new(false),
+            mip_levels_access: 0..image.mipmap_levels(),
         };
 
         Ok((image, init))
@@ -218,11 +252,12 @@ impl<F> ImmutableImage<F> {
 
     /// Construct an ImmutableImage from the contents of `iter`.
     ///
-    /// TODO: Support mipmaps
+    /// See `from_buffer` for more information about `mipmaps` and the returned future.
     #[inline]
-    pub fn from_iter<P, I>(
+    pub fn from_iter<P, I, M>(
         iter: I,
         dimensions: Dimensions,
+        mipmaps: M,
         format: F,
         queue: Arc<Queue>,
     ) -> Result<
@@ -230,12 +265,13 @@ impl<F> ImmutableImage<F> {
             Arc<Self>,
             CommandBufferExecFuture<NowFuture, AutoCommandBuffer>,
         ),
-        ImageCreationError,
+        ImmutableImageCreationError,
     >
     where
-        P: Send + Sync + Clone + 'static,
+        P: BufferContents + Send + Sync + Clone + 'static,
         F: FormatDesc + AcceptsPixels<P> + 'static + Send + Sync,
         I: ExactSizeIterator<Item = P>,
+        M: Into<MipmapsCount>,
         Format: AcceptsPixels<P>,
     {
         let source = CpuAccessibleBuffer::from_iter(
@@ -244,15 +280,23 @@ impl<F> ImmutableImage<F> {
             false,
             iter,
         )?;
-        ImmutableImage::from_buffer(source, dimensions, format, queue)
+        ImmutableImage::from_buffer(source, dimensions, mipmaps, format, queue)
     }
 
     /// Construct an ImmutableImage containing a copy of the data in `source`.
     ///
-    /// TODO: Support mipmaps
-    pub fn from_buffer<B, P>(
+    /// `source` contains the data of the first mipmap level. If `mipmaps` is not
+    /// `MipmapsCount::One`, the other levels are generated with
+    /// `AutoCommandBufferBuilder::generate_mipmaps`. This requires the format to support blits
+    /// and linear filtering. This is checked before the image is created, and an error is
+    /// returned if it isn't the case.
+    ///
+    /// The image is in the `ShaderReadOnlyOptimal` layout once the returned future has been
+    /// executed. The image can't be used by the GPU before that.
+    pub fn from_buffer<B, P, M>(
         source: B,
         dimensions: Dimensions,
+        mipmaps: M,
         format: F,
         queue: Arc<Queue>,
     ) -> Result<
@@ -260,15 +304,28 @@ impl<F> ImmutableImage<F> {
             Arc<Self>,
             CommandBufferExecFuture<NowFuture, AutoCommandBuffer>,
         ),
-        ImageCreationError,
+        ImmutableImageCreationError,
     >
     where
         B: BufferAccess + TypedBufferAccess<Content = [P]> + 'static + Clone + Send + Sync,
         P: Send + Sync + Clone + 'static,
         F: FormatDesc + AcceptsPixels<P> + 'static + Send + Sync,
+        M: Into<MipmapsCount>,
         Format: AcceptsPixels<P>,
     {
+        let mipmaps = mipmaps.into();
+        let generate_mipmaps = match mipmaps {
+            MipmapsCount::One => false,
+            _ => true,
+        };
+
+        if generate_mipmaps {
+            check_generate_mipmaps_format(source.device(), format.format())
+                .map_err(GenerateMipmapsError::from)?;
+        }
+
         let usage = ImageUsage {
+            transfer_source: generate_mipmaps,
             transfer_destination: true,
             sampled: true,
             ..ImageUsage::none()
@@ -279,7 +336,7 @@ impl<F> ImmutableImage<F> {
             source.device().clone(),
             dimensions,
             format,
-            MipmapsCount::One,
+            mipmaps,
             usage,
             layout,
             source.device().active_queue_families(),
@@ -294,15 +351,17 @@ impl<F> ImmutableImage<F> {
             0,
             dimensions.array_layers_with_cube(),
             0,
-        )
-        .unwrap();
-        let cb = cbb.build().unwrap();
+        )?;
 
-        let future = match cb.execute(queue) {
-            Ok(f) => f,
-            Err(_) => unreachable!(),
-        };
+        if buffer.mipmap_levels() > 1 {
+            cbb.generate_mipmaps(ImmutableImageInitialization {
+                image: buffer.clone(),
+                used: AtomicBool::new(false),
+                mip_levels_access: 0..buffer.mipmap_levels(),
+            })?;
+        }
 
+        let future = cbb.build()?.execute(queue)?;
         Ok((buffer, future))
     }
 }
@@ -378,7 +437,8 @@ where
             return Err(AccessError::ExclusiveDenied);
         }
 
-        if !self.initialized.load(Ordering::Relaxed) {
+        let all_levels = levels_mask(0..self.mipmap_levels());
+        if self.initialized_levels.load(Ordering::Relaxed) != all_levels {
             return Err(AccessError::BufferNotInitialized);
         }
 
@@ -471,13 +531,56 @@ where
     }
 }
 
+impl<F, A> ImmutableImageInitialization<F, A> {
+    /// Returns an access to a single mipmap level of the image, in order to upload the content of
+    /// each level separately.
+    ///
+    /// Accesses to different mipmap levels don't conflict with each other. Each level can only be
+    /// written once: submitting a command buffer that writes a level that has already been
+    /// written fails with `AccessError::AlreadyInUse`. The image can only be used once all the
+    /// levels have been written.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `level` is not one of the mipmap levels that this access gives access to.
+    ///
+    pub fn mip_level(&self, level: u32) -> ImmutableImageInitialization<F, A> {
+        assert!(self.mip_levels_access.start <= level && level < self.mip_levels_access.end);
+        ImmutableImageInitialization {
+            image: self.image.clone(),
+            used: AtomicBool::new(false),
+            mip_levels_access: level..level + 1,
+        }
+    }
+}
+
 unsafe impl<F, A> ImageAccess for ImmutableImageInitialization<F, A>
 where
     F: 'static + Send + Sync,
 {
     #[inline]
     fn inner(&self) -> ImageInner {
-        ImageAccess::inner(&self.image)
+        ImageInner {
+            image: &self.image.image,
+            first_layer: 0,
+            num_layers: self.image.image.dimensions().array_layers() as usize,
+            first_mipmap_level: self.mip_levels_access.start as usize,
+            num_mipmap_levels: (self.mip_levels_access.end - self.mip_levels_access.start) as usize,
+        }
+    }
+
+    #[inline]
+    fn mipmap_levels(&self) -> u32 {
+        self.mip_levels_access.end - self.mip_levels_access.start
+    }
+
+    #[inline]
+    fn dimensions(&self) -> ImageDimensions {
+        self.image
+            .image
+            .dimensions()
+            .mipmap_dimensions(self.mip_levels_access.start)
+            .unwrap()
     }
 
     #[inline]
@@ -497,7 +600,15 @@ where
 
     #[inline]
     fn conflicts_image(&self, other: &dyn ImageAccess) -> bool {
-        self.conflict_key() == other.conflict_key() // TODO:
+        if self.conflict_key() != other.conflict_key() {
+            return false;
+        }
+
+        // Different mipmap levels of the same image don't conflict.
+        let other = other.inner();
+        let other_start = other.first_mipmap_level as u32;
+        let other_end = other_start + other.num_mipmap_levels as u32;
+        self.mip_levels_access.start < other_end && other_start < self.mip_levels_access.end
     }
 
     #[inline]
@@ -514,16 +625,25 @@ where
             });
         }
 
-        if self.image.initialized.load(Ordering::Relaxed) {
+        if self.used.compare_and_swap(false, true, Ordering::Relaxed) {
             return Err(AccessError::AlreadyInUse);
         }
 
-        // FIXME: Mipmapped textures require multiple writes to initialize
-        if !self.used.compare_and_swap(false, true, Ordering::Relaxed) {
-            Ok(())
-        } else {
-            Err(AccessError::AlreadyInUse)
+        // Each mipmap level can only be written by one initialization access.
+        let levels = levels_mask(self.mip_levels_access.clone());
+        let prev = self
+            .image
+            .written_levels
+            .fetch_or(levels, Ordering::Relaxed);
+        if prev & levels != 0 {
+            self.image
+                .written_levels
+                .fetch_and(!(levels & !prev), Ordering::Relaxed);
+            self.used.store(false, Ordering::Relaxed);
+            return Err(AccessError::AlreadyInUse);
         }
+
+        Ok(())
     }
 
     #[inline]
@@ -533,8 +653,21 @@ where
 
     #[inline]
     unsafe fn unlock(&self, new_layout: Option<ImageLayout>) {
+        let levels = levels_mask(self.mip_levels_access.clone());
+
+        // `None` means that the submission failed, in which case the levels can be written again.
+        if new_layout.is_none() {
+            self.image
+                .written_levels
+                .fetch_and(!levels, Ordering::Relaxed);
+            self.used.store(false, Ordering::Relaxed);
+            return;
+        }
+
         assert_eq!(new_layout, Some(self.image.layout));
-        self.image.initialized.store(true, Ordering::Relaxed);
+        self.image
+            .initialized_levels
+            .fetch_or(levels, Ordering::Relaxed);
     }
 }
 
@@ -559,3 +692,263 @@ where
         ImageAccess::inner(self).hash(state);
     }
 }
+
+/// Error that can happen when creating an `ImmutableImage` from host data.
+#[derive(Debug, Clone)]
+pub enum ImmutableImageCreationError {
+    /// Error while creating the image.
+    ImageCreationError(ImageCreationError),
+    /// Not enough memory to allocate the staging buffer.
+    AllocError(DeviceMemoryAllocError),
+    /// Not enough memory to create the command buffer.
+    OomError(OomError),
+    /// The copy command is invalid.
+    CopyBufferImageError(CopyBufferImageError),
+    /// The mipmaps of the image can't be generated.
+    GenerateMipmapsError(GenerateMipmapsError),
+    /// Error while building the command buffer.
+    BuildError(BuildError),
+    /// Error while submitting the command buffer.
+    CommandBufferExecError(CommandBufferExecError),
+}
+
+impl error::Error for ImmutableImageCreationError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            ImmutableImageCreationError::ImageCreationError(ref err) => Some(err),
+            ImmutableImageCreationError::AllocError(ref err) => Some(err),
+            ImmutableImageCreationError::OomError(ref err) => Some(err),
+            ImmutableImageCreationError::CopyBufferImageError(ref err) => Some(err),
+            ImmutableImageCreationError::GenerateMipmapsError(ref err) => Some(err),
+            ImmutableImageCreationError::BuildError(ref err) => Some(err),
+            ImmutableImageCreationError::CommandBufferExecError(ref err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for ImmutableImageCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                ImmutableImageCreationError::ImageCreationError(_) => {
+                    "error while creating the image"
+                }
+                ImmutableImageCreationError::AllocError(_) => {
+                    "failed to allocate the staging buffer"
+                }
+                ImmutableImageCreationError::OomError(_) => "not enough memory available",
+                ImmutableImageCreationError::CopyBufferImageError(_) => {
+                    "the copy command is invalid"
+                }
+                ImmutableImageCreationError::GenerateMipmapsError(_) => {
+                    "the mipmaps of the image can't be generated"
+                }
+                ImmutableImageCreationError::BuildError(_) => {
+                    "error while building the command buffer"
+                }
+                ImmutableImageCreationError::CommandBufferExecError(_) => {
+                    "error while submitting the command buffer"
+                }
+            }
+        )
+    }
+}
+
+impl From<ImageCreationError> for ImmutableImageCreationError {
+    #[inline]
+    fn from(err: ImageCreationError) -> ImmutableImageCreationError {
+        ImmutableImageCreationError::ImageCreationError(err)
+    }
+}
+
+impl From<DeviceMemoryAllocError> for ImmutableImageCreationError {
+    #[inline]
+    fn from(err: DeviceMemoryAllocError) -> ImmutableImageCreationError {
+        ImmutableImageCreationError::AllocError(err)
+    }
+}
+
+impl From<OomError> for ImmutableImageCreationError {
+    #[inline]
+    fn from(err: OomError) -> ImmutableImageCreationError {
+        ImmutableImageCreationError::OomError(err)
+    }
+}
+
+impl From<CopyBufferImageError> for ImmutableImageCreationError {
+    #[inline]
+    fn from(err: CopyBufferImageError) -> ImmutableImageCreationError {
+        ImmutableImageCreationError::CopyBufferImageError(err)
+    }
+}
+
+impl From<GenerateMipmapsError> for ImmutableImageCreationError {
+    #[inline]
+    fn from(err: GenerateMipmapsError) -> ImmutableImageCreationError {
+        ImmutableImageCreationError::GenerateMipmapsError(err)
+    }
+}
+
+impl From<BuildError> for ImmutableImageCreationError {
+    #[inline]
+    fn from(err: BuildError) -> ImmutableImageCreationError {
+        ImmutableImageCreationError::BuildError(err)
+    }
+}
+
+impl From<CommandBufferExecError> for ImmutableImageCreationError {
+    #[inline]
+    fn from(err: CommandBufferExecError) -> ImmutableImageCreationError {
+        ImmutableImageCreationError::CommandBufferExecError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use command_buffer::validity::CheckGenerateMipmapsError;
+    use command_buffer::GenerateMipmapsError;
+    use format::Format;
+    use image::Dimensions;
+    use image::ImageAccess;
+    use image::ImageLayout;
+    use image::ImageUsage;
+    use image::ImmutableImage;
+    use image::ImmutableImageCreationError;
+    use image::MipmapsCount;
+    use sync::AccessError;
+    use sync::GpuFuture;
+
+    #[test]
+    fn from_iter_with_mipmaps() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pixels = (0..64 * 64).map(|_| [255u8, 0, 0, 255]);
+        // Blits are always supported for this format.
+        let (image, future) = ImmutableImage::from_iter(
+            pixels,
+            Dimensions::Dim2d {
+                width: 64,
+                height: 64,
+            },
+            MipmapsCount::Log2,
+            Format::R8G8B8A8Unorm,
+            queue.clone(),
+        )
+        .unwrap();
+        assert_eq!(image.mipmap_levels(), 7);
+
+        future
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+    }
+
+    #[test]
+    fn from_iter_mipmaps_depth_format() {
+        let (_, queue) = gfx_dev_and_queue!();
+
+        let pixels = (0..64 * 64).map(|_| 0u16);
+        let result = ImmutableImage::from_iter(
+            pixels,
+            Dimensions::Dim2d {
+                width: 64,
+                height: 64,
+            },
+            MipmapsCount::Log2,
+            Format::D16Unorm,
+            queue.clone(),
+        );
+
+        match result {
+            Err(ImmutableImageCreationError::GenerateMipmapsError(
+                GenerateMipmapsError::CheckGenerateMipmapsError(
+                    CheckGenerateMipmapsError::FormatNotSupported,
+                ),
+            )) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn mip_levels_dont_conflict() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let usage = ImageUsage {
+            transfer_destination: true,
+            sampled: true,
+            ..ImageUsage::none()
+        };
+        let (_, init) = ImmutableImage::uninitialized(
+            device.clone(),
+            Dimensions::Dim2d {
+                width: 64,
+                height: 64,
+            },
+            Format::R8G8B8A8Unorm,
+            MipmapsCount::Log2,
+            usage,
+            ImageLayout::ShaderReadOnlyOptimal,
+            Some(queue.family()),
+        )
+        .unwrap();
+
+        let level0 = init.mip_level(0);
+        let level1 = init.mip_level(1);
+        assert!(!level0.conflicts_image(&level1));
+        assert!(level0.conflicts_image(&init));
+        assert!(init.conflicts_image(&level1));
+        assert_eq!(level1.mipmap_levels(), 1);
+        assert_eq!(level1.dimensions().width_height(), [32, 32]);
+    }
+
+    #[test]
+    fn initialized_per_mip_level() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let usage = ImageUsage {
+            transfer_destination: true,
+            sampled: true,
+            ..ImageUsage::none()
+        };
+        let layout = ImageLayout::ShaderReadOnlyOptimal;
+        let (image, init) = ImmutableImage::uninitialized(
+            device.clone(),
+            Dimensions::Dim2d {
+                width: 2,
+                height: 2,
+            },
+            Format::R8G8B8A8Unorm,
+            MipmapsCount::Log2,
+            usage,
+            layout,
+            Some(queue.family()),
+        )
+        .unwrap();
+        assert_eq!(image.mipmap_levels(), 2);
+
+        unsafe {
+            let level0 = init.mip_level(0);
+            level0.try_gpu_lock(true, ImageLayout::Undefined).unwrap();
+            match init.mip_level(0).try_gpu_lock(true, ImageLayout::Undefined) {
+                Err(AccessError::AlreadyInUse) => (),
+                _ => panic!(),
+            }
+            level0.unlock(Some(layout));
+
+            match image.try_gpu_lock(false, layout) {
+                Err(AccessError::BufferNotInitialized) => (),
+                _ => panic!(),
+            }
+
+            let level1 = init.mip_level(1);
+            level1.try_gpu_lock(true, ImageLayout::Undefined).unwrap();
+            level1.unlock(Some(layout));
+            image.try_gpu_lock(false, layout).unwrap();
+        }
+    }
+}
@@ -39,4 +39,12 @@ pub enum ImageLayout {
     TransferDstOptimal = vk::IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL,
     Preinitialized = vk::IMAGE_LAYOUT_PREINITIALIZED,
     PresentSrc = vk::IMAGE_LAYOUT_PRESENT_SRC_KHR,
+    /// The depth aspect is read-only, for example to be sampled, while the stencil aspect is used
+    /// as an attachment. Requires the `khr_maintenance2` extension.
+    DepthReadOnlyStencilAttachmentOptimal =
+        vk::IMAGE_LAYOUT_DEPTH_READ_ONLY_STENCIL_ATTACHMENT_OPTIMAL_KHR,
+    /// The depth aspect is used as an attachment while the stencil aspect is read-only.
+    /// Requires the `khr_maintenance2` extension.
+    DepthAttachmentStencilReadOnlyOptimal =
+        vk::IMAGE_LAYOUT_DEPTH_ATTACHMENT_STENCIL_READ_ONLY_OPTIMAL_KHR,
 }
@@ -0,0 +1,640 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Image with linear tiling whose memory is accessible from the CPU.
+//!
+//! The texels of a `LinearImage` are laid out in memory row by row, which makes it possible to
+//! read or write them directly from the CPU without going through a buffer. This is useful for
+//! streaming textures that change every frame, or for reading back a screenshot in the exact
+//! format of the image.
+//!
+//! Each row of the image starts `row_pitch` bytes after the previous one, and this pitch can be
+//! larger than the size of a row of texels. Use `LinearImage::layout` to know where the texels
+//! are.
+//!
+//! Implementations are only required to support linear tiling for two-dimensional color images
+//! with one mipmap level, one array layer and the transfer usages. Other combinations may or may
+//! not be supported, in which case an `ImageCreationError` is returned.
+
+use std::error;
+use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::iter::Empty;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::sync::RwLockReadGuard;
+use std::sync::RwLockWriteGuard;
+
+use buffer::cpu_access::host_memory_filter;
+use buffer::BufferAccess;
+use device::Device;
+use device::DeviceOwned;
+use format::FormatDesc;
+use format::FormatTy;
+use image::sys::ImageCreationError;
+use image::sys::LinearLayout;
+use image::sys::UnsafeImage;
+use image::traits::ImageAccess;
+use image::ImageDimensions;
+use image::ImageInner;
+use image::ImageLayout;
+use image::ImageUsage;
+use image::MipmapsCount;
+use instance::MemoryType;
+use memory::pool::AllocLayout;
+use memory::pool::MappingRequirement;
+use memory::pool::MemoryPool;
+use memory::pool::MemoryPoolAlloc;
+use memory::pool::PotentialDedicatedAllocation;
+use memory::pool::StdMemoryPoolAlloc;
+use memory::CpuAccess as MemCpuAccess;
+use memory::DedicatedAlloc;
+use sync::AccessError;
+use sync::Sharing;
+
+/// Two-dimensional image with linear tiling, in host-visible memory.
+///
+/// The image stays in the `General` layout. Its content can be read with `read()` and written
+/// with `write()` from the CPU, as long as it is not in use by the GPU.
+#[derive(Debug)]
+pub struct LinearImage<F, A = PotentialDedicatedAllocation<StdMemoryPoolAlloc>> {
+    // Inner implementation.
+    image: UnsafeImage,
+
+    // Memory used to back the image.
+    memory: A,
+
+    // Layout of the texels in memory.
+    layout: LinearLayout,
+
+    // Format.
+    format: F,
+
+    // Same as in `CpuAccessibleBuffer`. Checked every time the image is accessed by the CPU or
+    // locked by the GPU.
+    access: RwLock<CurrentGpuAccess>,
+
+    // If true, then the image has been transitioned to the `General` layout. If false, then it
+    // is still `Preinitialized`.
+    initialized: AtomicBool,
+}
+
+#[derive(Debug)]
+enum CurrentGpuAccess {
+    NonExclusive {
+        // Number of non-exclusive GPU accesses. Can be 0.
+        num: AtomicUsize,
+    },
+    Exclusive {
+        // Number of exclusive locks. Cannot be 0. If 0 is reached, we must jump to `NonExclusive`.
+        num: usize,
+    },
+}
+
+impl<F> LinearImage<F> {
+    /// Creates a new image with the transfer source and transfer destination usages.
+    ///
+    /// If `host_cached` is true, memory that is cached on the host is preferred, which makes
+    /// reading the image from the CPU faster. Otherwise uncached memory is preferred, which is
+    /// better for images that are only written by the CPU.
+    #[inline]
+    pub fn new(
+        device: Arc<Device>,
+        dimensions: [u32; 2],
+        format: F,
+        host_cached: bool,
+    ) -> Result<Arc<LinearImage<F>>, ImageCreationError>
+    where
+        F: FormatDesc,
+    {
+        let usage = ImageUsage {
+            transfer_source: true,
+            transfer_destination: true,
+            ..ImageUsage::none()
+        };
+
+        LinearImage::with_usage(device, dimensions, format, usage, host_cached)
+    }
+
+    /// Same as `new`, but allows specifying the usage.
+    ///
+    /// Returns `ImageCreationError::UnsupportedUsage` if the format doesn't support the usage
+    /// with linear tiling.
+    pub fn with_usage(
+        device: Arc<Device>,
+        dimensions: [u32; 2],
+        format: F,
+        usage: ImageUsage,
+        host_cached: bool,
+    ) -> Result<Arc<LinearImage<F>>, ImageCreationError>
+    where
+        F: FormatDesc,
+    {
+        let (image, mem_reqs) = unsafe {
+            let dims = ImageDimensions::Dim2d {
+                width: dimensions[0],
+                height: dimensions[1],
+                array_layers: 1,
+                cubemap_compatible: false,
+            };
+
+            UnsafeImage::new(
+                device.clone(),
+                usage,
+                format.format(),
+                dims,
+                1,
+                MipmapsCount::One,
+                Sharing::Exclusive::<Empty<u32>>,
+                true,
+                true,
+            )?
+        };
+
+        let mem = MemoryPool::alloc_from_requirements(
+            &Device::standard_pool(&device),
+            &mem_reqs,
+            AllocLayout::Linear,
+            MappingRequirement::Map,
+            DedicatedAlloc::Image(&image),
+            |t| host_memory_filter(t, host_cached),
+        )?;
+        debug_assert!((mem.offset() % mem_reqs.alignment) == 0);
+        unsafe {
+            image.bind_memory(mem.memory(), mem.offset())?;
+        }
+
+        // Depth and stencil aspects have separate layouts. Only the depth one is exposed.
+        let layout = unsafe {
+            match image.format().ty() {
+                FormatTy::Depth | FormatTy::DepthStencil => image.depth_linear_layout(0),
+                FormatTy::Stencil => image.stencil_linear_layout(0),
+                _ => image.color_linear_layout(0),
+            }
+        };
+
+        Ok(Arc::new(LinearImage {
+            image: image,
+            memory: mem,
+            layout: layout,
+            format: format,
+            access: RwLock::new(CurrentGpuAccess::NonExclusive {
+                num: AtomicUsize::new(0),
+            }),
+            initialized: AtomicBool::new(false),
+        }))
+    }
+}
+
+impl<F, A> LinearImage<F, A> {
+    /// Returns the dimensions of the image.
+    #[inline]
+    pub fn dimensions(&self) -> [u32; 2] {
+        let dims = self.image.dimensions();
+        [dims.width(), dims.height()]
+    }
+
+    /// Returns the layout of the texels in memory, as returned by `vkGetImageSubresourceLayout`.
+    ///
+    /// The `offset` of the layout is relative to the start of the memory of the image, so it is
+    /// already applied to the slices returned by `read()` and `write()`.
+    #[inline]
+    pub fn layout(&self) -> LinearLayout {
+        self.layout
+    }
+
+    /// Returns the number of bytes between the start of two consecutive rows of texels.
+    #[inline]
+    pub fn row_pitch(&self) -> usize {
+        self.layout.row_pitch
+    }
+}
+
+impl<F, A> LinearImage<F, A>
+where
+    A: MemoryPoolAlloc,
+{
+    /// Returns the memory type of the memory that backs the image.
+    #[inline]
+    pub fn memory_type(&self) -> MemoryType {
+        self.memory.memory().memory_type()
+    }
+
+    /// Locks the image in order to read its content from the CPU.
+    ///
+    /// If the image is currently used in exclusive mode by the GPU, this function will return
+    /// an error. Similarly if you called `write()` on the image and haven't dropped the lock,
+    /// this function will return an error as well.
+    #[inline]
+    pub fn read(&self) -> Result<ReadLock, ReadLockError> {
+        let lock = match self.access.try_read() {
+            Ok(l) => l,
+            Err(_) => return Err(ReadLockError::CpuWriteLocked),
+        };
+
+        if let CurrentGpuAccess::Exclusive { .. } = *lock {
+            return Err(ReadLockError::GpuWriteLocked);
+        }
+
+        let offset = self.memory.offset() + self.layout.offset;
+        let range = offset..offset + self.layout.size;
+
+        Ok(ReadLock {
+            inner: unsafe { self.memory.mapped_memory().unwrap().read_write(range) },
+            row_pitch: self.layout.row_pitch,
+            lock: lock,
+        })
+    }
+
+    /// Locks the image in order to write its content from the CPU.
+    ///
+    /// If the image is currently in use by the GPU, this function will return an error.
+    /// Similarly if you called `read()` on the image and haven't dropped the lock, this function
+    /// will return an error as well.
+    #[inline]
+    pub fn write(&self) -> Result<WriteLock, WriteLockError> {
+        let lock = match self.access.try_write() {
+            Ok(l) => l,
+            Err(_) => return Err(WriteLockError::CpuLocked),
+        };
+
+        match *lock {
+            CurrentGpuAccess::NonExclusive { ref num } if num.load(Ordering::SeqCst) == 0 => (),
+            _ => return Err(WriteLockError::GpuLocked),
+        }
+
+        let offset = self.memory.offset() + self.layout.offset;
+        let range = offset..offset + self.layout.size;
+
+        Ok(WriteLock {
+            inner: unsafe { self.memory.mapped_memory().unwrap().read_write(range) },
+            row_pitch: self.layout.row_pitch,
+            lock: lock,
+        })
+    }
+}
+
+unsafe impl<F, A> ImageAccess for LinearImage<F, A>
+where
+    F: 'static + Send + Sync,
+{
+    #[inline]
+    fn inner(&self) -> ImageInner {
+        ImageInner {
+            image: &self.image,
+            first_layer: 0,
+            num_layers: 1,
+            first_mipmap_level: 0,
+            num_mipmap_levels: 1,
+        }
+    }
+
+    #[inline]
+    fn initial_layout_requirement(&self) -> ImageLayout {
+        ImageLayout::General
+    }
+
+    #[inline]
+    fn final_layout_requirement(&self) -> ImageLayout {
+        ImageLayout::General
+    }
+
+    #[inline]
+    fn conflicts_buffer(&self, other: &dyn BufferAccess) -> bool {
+        false
+    }
+
+    #[inline]
+    fn conflicts_image(&self, other: &dyn ImageAccess) -> bool {
+        self.conflict_key() == other.conflict_key() // TODO:
+    }
+
+    #[inline]
+    fn conflict_key(&self) -> u64 {
+        self.image.key()
+    }
+
+    #[inline]
+    fn try_gpu_lock(
+        &self,
+        exclusive_access: bool,
+        expected_layout: ImageLayout,
+    ) -> Result<(), AccessError> {
+        if expected_layout != ImageLayout::General && expected_layout != ImageLayout::Undefined {
+            return Err(AccessError::UnexpectedImageLayout {
+                requested: expected_layout,
+                allowed: ImageLayout::General,
+            });
+        }
+
+        if expected_layout == ImageLayout::General && !self.initialized.load(Ordering::SeqCst) {
+            return Err(AccessError::ImageNotInitialized {
+                requested: expected_layout,
+            });
+        }
+
+        if exclusive_access {
+            let mut lock = match self.access.try_write() {
+                Ok(lock) => lock,
+                Err(_) => return Err(AccessError::AlreadyInUse),
+            };
+
+            match *lock {
+                CurrentGpuAccess::NonExclusive { ref num } if num.load(Ordering::SeqCst) == 0 => (),
+                _ => return Err(AccessError::AlreadyInUse),
+            };
+
+            *lock = CurrentGpuAccess::Exclusive { num: 1 };
+            Ok(())
+        } else {
+            let lock = match self.access.try_read() {
+                Ok(lock) => lock,
+                Err(_) => return Err(AccessError::AlreadyInUse),
+            };
+
+            match *lock {
+                CurrentGpuAccess::Exclusive { .. } => return Err(AccessError::AlreadyInUse),
+                CurrentGpuAccess::NonExclusive { ref num } => num.fetch_add(1, Ordering::SeqCst),
+            };
+
+            Ok(())
+        }
+    }
+
+    #[inline]
+    unsafe fn increase_gpu_lock(&self) {
+        {
+            let read_lock = self.access.read().unwrap();
+            if let CurrentGpuAccess::NonExclusive { ref num } = *read_lock {
+                let prev = num.fetch_add(1, Ordering::SeqCst);
+                debug_assert!(prev >= 1);
+                return;
+            }
+        }
+
+        let mut write_lock = self.access.write().unwrap();
+        if let CurrentGpuAccess::Exclusive { ref mut num } = *write_lock {
+            *num += 1;
+        } else {
+            unreachable!()
+        }
+    }
+
+    #[inline]
+    unsafe fn unlock(&self, new_layout: Option<ImageLayout>) {
+        assert!(new_layout.is_none() || new_layout == Some(ImageLayout::General));
+        if new_layout.is_some() {
+            self.initialized.store(true, Ordering::SeqCst);
+        }
+
+        {
+            let read_lock = self.access.read().unwrap();
+            if let CurrentGpuAccess::NonExclusive { ref num } = *read_lock {
+                let prev = num.fetch_sub(1, Ordering::SeqCst);
+                debug_assert!(prev >= 1);
+                return;
+            }
+        }
+
+        let mut write_lock = self.access.write().unwrap();
+        if let CurrentGpuAccess::Exclusive { ref mut num } = *write_lock {
+            if *num != 1 {
+                *num -= 1;
+                return;
+            }
+        } else {
+            panic!()
+        }
+
+        *write_lock = CurrentGpuAccess::NonExclusive {
+            num: AtomicUsize::new(0),
+        };
+    }
+
+    #[inline]
+    unsafe fn layout_initialized(&self) {
+        self.initialized.store(true, Ordering::SeqCst);
+    }
+
+    #[inline]
+    fn is_layout_initialized(&self) -> bool {
+        self.initialized.load(Ordering::SeqCst)
+    }
+}
+
+unsafe impl<F, A> DeviceOwned for LinearImage<F, A> {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.image.device()
+    }
+}
+
+impl<F, A> PartialEq for LinearImage<F, A>
+where
+    F: 'static + Send + Sync,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        ImageAccess::inner(self) == ImageAccess::inner(other)
+    }
+}
+
+impl<F, A> Eq for LinearImage<F, A> where F: 'static + Send + Sync {}
+
+impl<F, A> Hash for LinearImage<F, A>
+where
+    F: 'static + Send + Sync,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ImageAccess::inner(self).hash(state);
+    }
+}
+
+/// Object that can be used to read the content of a `LinearImage`.
+///
+/// Derefs to the bytes of the image, starting with the first texel of the first row. Note that
+/// this object holds a rwlock read guard on the image. While it is alive, any attempt to write
+/// the image's content or to submit a GPU command that writes this image will fail.
+pub struct ReadLock<'a> {
+    inner: MemCpuAccess<'a, [u8]>,
+    row_pitch: usize,
+    lock: RwLockReadGuard<'a, CurrentGpuAccess>,
+}
+
+impl<'a> ReadLock<'a> {
+    /// Returns the number of bytes between the start of two consecutive rows.
+    #[inline]
+    pub fn row_pitch(&self) -> usize {
+        self.row_pitch
+    }
+
+    /// Returns the bytes of the row `y`, including the padding at the end of the row if any.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `y` is out of range.
+    ///
+    #[inline]
+    pub fn row(&self, y: u32) -> &[u8] {
+        let start = y as usize * self.row_pitch;
+        let end = (start + self.row_pitch).min(self.inner.len());
+        &self.inner[start..end]
+    }
+}
+
+impl<'a> Deref for ReadLock<'a> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.inner.deref()
+    }
+}
+
+/// Error when attempting to CPU-read an image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReadLockError {
+    /// The image is already locked for write mode by the CPU.
+    CpuWriteLocked,
+    /// The image is already locked for write mode by the GPU.
+    GpuWriteLocked,
+}
+
+impl error::Error for ReadLockError {}
+
+impl fmt::Display for ReadLockError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                ReadLockError::CpuWriteLocked => {
+                    "the image is already locked for write mode by the CPU"
+                }
+                ReadLockError::GpuWriteLocked => {
+                    "the image is already locked for write mode by the GPU"
+                }
+            }
+        )
+    }
+}
+
+/// Object that can be used to read or write the content of a `LinearImage`.
+///
+/// Derefs to the bytes of the image, starting with the first texel of the first row. Note that
+/// this object holds a rwlock write guard on the image. While it is alive, any attempt to access
+/// the image's content or to submit a GPU command that uses this image will fail.
+///
+/// If the memory is not host-coherent, the written range is flushed when the lock is dropped.
+pub struct WriteLock<'a> {
+    inner: MemCpuAccess<'a, [u8]>,
+    row_pitch: usize,
+    lock: RwLockWriteGuard<'a, CurrentGpuAccess>,
+}
+
+impl<'a> WriteLock<'a> {
+    /// Returns the number of bytes between the start of two consecutive rows.
+    #[inline]
+    pub fn row_pitch(&self) -> usize {
+        self.row_pitch
+    }
+
+    /// Returns the bytes of the row `y`, including the padding at the end of the row if any.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `y` is out of range.
+    ///
+    #[inline]
+    pub fn row_mut(&mut self, y: u32) -> &mut [u8] {
+        let start = y as usize * self.row_pitch;
+        let end = (start + self.row_pitch).min(self.inner.len());
+        &mut self.inner[start..end]
+    }
+}
+
+impl<'a> Deref for WriteLock<'a> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.inner.deref()
+    }
+}
+
+impl<'a> DerefMut for WriteLock<'a> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.inner.deref_mut()
+    }
+}
+
+/// Error when attempting to CPU-write an image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WriteLockError {
+    /// The image is already locked by the CPU.
+    CpuLocked,
+    /// The image is already locked by the GPU.
+    GpuLocked,
+}
+
+impl error::Error for WriteLockError {}
+
+impl fmt::Display for WriteLockError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                WriteLockError::CpuLocked => "the image is already locked by the CPU",
+                WriteLockError::GpuLocked => "the image is already locked by the GPU",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use format::Format;
+    use image::linear::ReadLockError;
+    use image::LinearImage;
+
+    #[test]
+    fn write_then_read() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let image = LinearImage::new(device, [16, 16], Format::R8G8B8A8Unorm, true).unwrap();
+        assert!(image.memory_type().is_host_visible());
+        assert!(image.row_pitch() >= 16 * 4);
+
+        {
+            let mut lock = image.write().unwrap();
+            for byte in lock.row_mut(3).iter_mut() {
+                *byte = 0xff;
+            }
+
+            match image.read() {
+                Err(ReadLockError::CpuWriteLocked) => (),
+                _ => panic!(),
+            }
+        }
+
+        let lock = image.read().unwrap();
+        assert!(lock.row(3)[..16 * 4].iter().all(|&b| b == 0xff));
+    }
+}
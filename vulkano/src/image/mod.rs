@@ -30,8 +30,8 @@
 //!
 //! # High-level wrappers
 //!
-//! In the vulkano library, an image is any object that implements the `Image` trait and an image
-//! view is any object that implements the `ImageView` trait.
+//! In the vulkano library, an image is any object that implements the `ImageAccess` trait and an
+//! image view is any object that implements the `ImageViewAccess` trait.
 //!
 //! Since these traits are low-level, you are encouraged to not implement them yourself but instead
 //! use one of the provided implementations that are specialized depending on the way you are going
@@ -40,6 +40,12 @@
 //! - An `AttachmentImage` can be used when you want to draw to an image.
 //! - An `ImmutableImage` stores data which never need be changed after the initial upload,
 //!   like a texture.
+//! - A `LinearImage` has its texels laid out row by row in host-visible memory, and can be read
+//!   or written directly from the CPU.
+//!
+//! All these images are also views of themselves, with a view type that matches the `Dimensions`
+//! they were created with. Use an `ImageView` to look at the same image with a different view
+//! type, for example to write to the faces of a cubemap as a 2D array.
 //!
 //! # Low-level information
 //!
@@ -49,9 +55,14 @@
 use std::cmp;
 use std::convert::TryFrom;
 
+use format::Format;
+use format::FormatTy;
+
 pub use self::attachment::AttachmentImage;
 pub use self::immutable::ImmutableImage;
+pub use self::immutable::ImmutableImageCreationError;
 pub use self::layout::ImageLayout;
+pub use self::linear::LinearImage;
 pub use self::storage::StorageImage;
 pub use self::swapchain::SwapchainImage;
 pub use self::sys::ImageCreationError;
@@ -59,15 +70,19 @@ pub use self::traits::ImageAccess;
 pub use self::traits::ImageInner;
 pub use self::traits::ImageViewAccess;
 pub use self::usage::ImageUsage;
+pub use self::view::ImageView;
+pub use self::view::ImageViewCreationError;
 
 pub mod attachment; // TODO: make private
 pub mod immutable; // TODO: make private
 mod layout;
+pub mod linear;
 mod storage;
 pub mod swapchain; // TODO: make private
 pub mod sys;
 pub mod traits;
 mod usage;
+mod view;
 
 /// Specifies how many mipmaps must be allocated.
 ///
@@ -164,6 +179,83 @@ impl From<vk::ImageFormatProperties> for ImageFormatProperties {
     }
 }
 
+/// Properties of a sparse image format, returned by `Device::sparse_image_format_properties`.
+///
+/// Memory is bound to the image in blocks whose dimensions in texels are `image_granularity`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SparseImageFormatProperties {
+    /// True if these properties apply to the color aspect of the image.
+    pub color: bool,
+    /// True if these properties apply to the depth aspect of the image.
+    pub depth: bool,
+    /// True if these properties apply to the stencil aspect of the image.
+    pub stencil: bool,
+    /// True if these properties apply to the metadata of the image.
+    pub metadata: bool,
+    /// Width, height and depth of a sparse block, in texels.
+    pub image_granularity: [u32; 3],
+    /// True if the image uses a single mip tail region for all its array layers.
+    pub single_miptail: bool,
+    /// True if the first mip level whose dimensions aren't a multiple of the block dimensions
+    /// starts the mip tail region.
+    pub aligned_mip_size: bool,
+    /// True if the block dimensions don't match the standard block shape of the format. See
+    /// `Format::sparse_block_shape`.
+    pub nonstandard_block_size: bool,
+}
+
+impl From<vk::SparseImageFormatProperties> for SparseImageFormatProperties {
+    fn from(props: vk::SparseImageFormatProperties) -> Self {
+        Self {
+            color: (props.aspectMask & vk::IMAGE_ASPECT_COLOR_BIT) != 0,
+            depth: (props.aspectMask & vk::IMAGE_ASPECT_DEPTH_BIT) != 0,
+            stencil: (props.aspectMask & vk::IMAGE_ASPECT_STENCIL_BIT) != 0,
+            metadata: (props.aspectMask & vk::IMAGE_ASPECT_METADATA_BIT) != 0,
+            image_granularity: [
+                props.imageGranularity.width,
+                props.imageGranularity.height,
+                props.imageGranularity.depth,
+            ],
+            single_miptail: (props.flags & vk::SPARSE_IMAGE_FORMAT_SINGLE_MIPTAIL_BIT) != 0,
+            aligned_mip_size: (props.flags & vk::SPARSE_IMAGE_FORMAT_ALIGNED_MIP_SIZE_BIT) != 0,
+            nonstandard_block_size: (props.flags
+                & vk::SPARSE_IMAGE_FORMAT_NONSTANDARD_BLOCK_SIZE_BIT)
+                != 0,
+        }
+    }
+}
+
+/// Sparse memory requirements of an image, returned by `UnsafeImage::sparse_memory_requirements`.
+///
+/// The mip levels starting at `image_mip_tail_first_lod` are too small to be bound block by
+/// block, and are instead bound together as an opaque region called the mip tail.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SparseImageMemoryRequirements {
+    /// Properties of the format of the image for the aspects these requirements apply to.
+    pub format_properties: SparseImageFormatProperties,
+    /// First mip level that is part of the mip tail.
+    pub image_mip_tail_first_lod: u32,
+    /// Size in bytes of the mip tail of one array layer, or of the whole image if
+    /// `single_miptail` is true.
+    pub image_mip_tail_size: usize,
+    /// Offset of the mip tail in the opaque memory range of the image.
+    pub image_mip_tail_offset: usize,
+    /// Offset between the mip tails of two consecutive array layers.
+    pub image_mip_tail_stride: usize,
+}
+
+impl From<vk::SparseImageMemoryRequirements> for SparseImageMemoryRequirements {
+    fn from(reqs: vk::SparseImageMemoryRequirements) -> Self {
+        Self {
+            format_properties: reqs.formatProperties.into(),
+            image_mip_tail_first_lod: reqs.imageMipTailFirstLod,
+            image_mip_tail_size: reqs.imageMipTailSize as usize,
+            image_mip_tail_offset: reqs.imageMipTailOffset as usize,
+            image_mip_tail_stride: reqs.imageMipTailStride as usize,
+        }
+    }
+}
+
 /// Specifies how the components of an image must be swizzled.
 ///
 /// When creating an image view, it is possible to ask the implementation to modify the value
@@ -185,6 +277,29 @@ pub struct Swizzle {
     pub a: ComponentSwizzle,
 }
 
+impl Swizzle {
+    /// Returns true if all the components return the value that they should normally have.
+    #[inline]
+    pub fn is_identity(&self) -> bool {
+        (self.r == ComponentSwizzle::Identity || self.r == ComponentSwizzle::Red)
+            && (self.g == ComponentSwizzle::Identity || self.g == ComponentSwizzle::Green)
+            && (self.b == ComponentSwizzle::Identity || self.b == ComponentSwizzle::Blue)
+            && (self.a == ComponentSwizzle::Identity || self.a == ComponentSwizzle::Alpha)
+    }
+}
+
+impl From<Swizzle> for vk::ComponentMapping {
+    #[inline]
+    fn from(swizzle: Swizzle) -> Self {
+        vk::ComponentMapping {
+            r: swizzle.r.into(),
+            g: swizzle.g.into(),
+            b: swizzle.b.into(),
+            a: swizzle.a.into(),
+        }
+    }
+}
+
 /// Describes the value that an individual component must return when being accessed.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ComponentSwizzle {
@@ -211,6 +326,21 @@ impl Default for ComponentSwizzle {
     }
 }
 
+impl From<ComponentSwizzle> for vk::ComponentSwizzle {
+    #[inline]
+    fn from(swizzle: ComponentSwizzle) -> Self {
+        match swizzle {
+            ComponentSwizzle::Identity => vk::COMPONENT_SWIZZLE_IDENTITY,
+            ComponentSwizzle::Zero => vk::COMPONENT_SWIZZLE_ZERO,
+            ComponentSwizzle::One => vk::COMPONENT_SWIZZLE_ONE,
+            ComponentSwizzle::Red => vk::COMPONENT_SWIZZLE_R,
+            ComponentSwizzle::Green => vk::COMPONENT_SWIZZLE_G,
+            ComponentSwizzle::Blue => vk::COMPONENT_SWIZZLE_B,
+            ComponentSwizzle::Alpha => vk::COMPONENT_SWIZZLE_A,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Dimensions {
     Dim1d {
@@ -478,6 +608,46 @@ impl From<ImageTiling> for vk::ImageTiling {
     }
 }
 
+/// One of the aspects of an image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ImageAspect {
+    /// The color of an image whose format is not a depth or stencil format.
+    Color,
+    /// The depth component of a depth or depth-stencil image.
+    Depth,
+    /// The stencil component of a stencil or depth-stencil image.
+    Stencil,
+}
+
+impl ImageAspect {
+    /// Returns true if images of the given format have this aspect.
+    #[inline]
+    pub fn is_in_format(&self, format: Format) -> bool {
+        match (*self, format.ty()) {
+            (ImageAspect::Color, FormatTy::Float)
+            | (ImageAspect::Color, FormatTy::Uint)
+            | (ImageAspect::Color, FormatTy::Sint)
+            | (ImageAspect::Color, FormatTy::Compressed) => true,
+            (ImageAspect::Depth, FormatTy::Depth)
+            | (ImageAspect::Depth, FormatTy::DepthStencil) => true,
+            (ImageAspect::Stencil, FormatTy::Stencil)
+            | (ImageAspect::Stencil, FormatTy::DepthStencil) => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<ImageAspect> for vk::ImageAspectFlags {
+    #[inline]
+    fn from(aspect: ImageAspect) -> Self {
+        match aspect {
+            ImageAspect::Color => vk::IMAGE_ASPECT_COLOR_BIT,
+            ImageAspect::Depth => vk::IMAGE_ASPECT_DEPTH_BIT,
+            ImageAspect::Stencil => vk::IMAGE_ASPECT_STENCIL_BIT,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ViewType {
     Dim1d,
@@ -684,7 +854,27 @@ impl ImageDimensions {
 
 #[cfg(test)]
 mod tests {
+    use image::ComponentSwizzle;
     use image::ImageDimensions;
+    use image::Swizzle;
+
+    #[test]
+    fn swizzle_identity() {
+        assert!(Swizzle::default().is_identity());
+        assert!(Swizzle {
+            r: ComponentSwizzle::Red,
+            g: ComponentSwizzle::Identity,
+            b: ComponentSwizzle::Blue,
+            a: ComponentSwizzle::Alpha,
+        }
+        .is_identity());
+        assert!(!Swizzle {
+            r: ComponentSwizzle::Blue,
+            b: ComponentSwizzle::Red,
+            ..Swizzle::default()
+        }
+        .is_identity());
+    }
 
     #[test]
     fn max_mipmaps() {
@@ -8,8 +8,13 @@
 // according to those terms.
 
 use smallvec::SmallVec;
+#[cfg(unix)]
+use std::fs::File;
 use std::hash::Hash;
 use std::hash::Hasher;
+#[cfg(windows)]
+use std::os::raw::c_void;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -17,6 +22,7 @@ use std::sync::Arc;
 use buffer::BufferAccess;
 use device::Device;
 use format::ClearValue;
+use format::Format;
 use format::FormatDesc;
 use format::FormatTy;
 use image::sys::ImageCreationError;
@@ -27,9 +33,11 @@ use image::traits::ImageClearValue;
 use image::traits::ImageContent;
 use image::traits::ImageViewAccess;
 use image::Dimensions;
+use image::ImageCreateFlags;
 use image::ImageInner;
 use image::ImageLayout;
 use image::ImageUsage;
+use instance::MemoryType;
 use instance::QueueFamily;
 use memory::pool::AllocFromRequirementsFilter;
 use memory::pool::AllocLayout;
@@ -39,11 +47,20 @@ use memory::pool::MemoryPoolAlloc;
 use memory::pool::PotentialDedicatedAllocation;
 use memory::pool::StdMemoryPool;
 use memory::DedicatedAlloc;
+use memory::DeviceMemory;
+use memory::DeviceMemoryAllocError;
+use memory::ExternalMemoryHandleType;
+use memory::MemoryRequirements;
 use sync::AccessError;
 use sync::Sharing;
 
 /// General-purpose image in device memory. Can be used for any usage, but will be slower than a
 /// specialized image.
+///
+/// The image always has the `storage` usage and stays in the `General` layout, which means that
+/// it can be bound as a storage image in a descriptor set and read or written by shaders
+/// without any layout transition. The content of the image is preserved between command
+/// buffers.
 #[derive(Debug)]
 pub struct StorageImage<F, A = Arc<StdMemoryPool>>
 where
@@ -69,10 +86,18 @@ where
 
     // Number of times this image is locked on the GPU side.
     gpu_lock: AtomicUsize,
+
+    // If true, then the image has been transitioned to the `General` layout. If false, then it
+    // is still `Undefined`.
+    initialized: AtomicBool,
 }
 
 impl<F> StorageImage<F> {
     /// Creates a new image with the given dimensions and format.
+    ///
+    /// The image gets the `storage` usage, plus all the other usages that the format supports
+    /// with optimal tiling. Returns `ImageCreationError::UnsupportedUsage` if the format can't be
+    /// used for storage images.
     #[inline]
     pub fn new<'a, I>(
         device: Arc<Device>,
@@ -84,22 +109,33 @@ impl<F> StorageImage<F> {
         F: FormatDesc,
         I: IntoIterator<Item = QueueFamily<'a>>,
     {
+        let features = format
+            .format()
+            .properties(device.physical_device())
+            .optimal_tiling_features;
+        if !features.storage_image {
+            return Err(ImageCreationError::UnsupportedUsage);
+        }
+
         let is_depth = match format.format().ty() {
             FormatTy::Depth => true,
             FormatTy::DepthStencil => true,
             FormatTy::Stencil => true,
-            FormatTy::Compressed => panic!(),
             _ => false,
         };
 
+        // The transfer features are only reported if `khr_maintenance1` is enabled. Otherwise they
+        // are always supported.
+        let maintenance1 = device.loaded_extensions().khr_maintenance1;
+
         let usage = ImageUsage {
-            transfer_source: true,
-            transfer_destination: true,
-            sampled: true,
+            transfer_source: !maintenance1 || features.transfer_src,
+            transfer_destination: !maintenance1 || features.transfer_dst,
+            sampled: features.sampled_image,
             storage: true,
-            color_attachment: !is_depth,
-            depth_stencil_attachment: is_depth,
-            input_attachment: true,
+            color_attachment: !is_depth && features.color_attachment,
+            depth_stencil_attachment: is_depth && features.depth_stencil_attachment,
+            input_attachment: features.color_attachment || features.depth_stencil_attachment,
             transient_attachment: false,
         };
 
@@ -107,6 +143,7 @@ impl<F> StorageImage<F> {
     }
 
     /// Same as `new`, but allows specifying the usage.
+    #[inline]
     pub fn with_usage<'a, I>(
         device: Arc<Device>,
         dimensions: Dimensions,
@@ -114,6 +151,42 @@ impl<F> StorageImage<F> {
         usage: ImageUsage,
         queue_families: I,
     ) -> Result<Arc<StorageImage<F>>, ImageCreationError>
+    where
+        F: FormatDesc,
+        I: IntoIterator<Item = QueueFamily<'a>>,
+    {
+        StorageImage::with_flags(
+            device,
+            dimensions,
+            format,
+            usage,
+            ImageCreateFlags::none(),
+            &[],
+            queue_families,
+        )
+    }
+
+    /// Same as `with_usage`, but allows passing additional creation flags.
+    ///
+    /// Set `flags.mutable_format` in order to create views of the image with other compatible
+    /// formats with `ImageView::with_format`. If `view_formats` is not empty, only these formats
+    /// can be used for the views, which requires the `khr_image_format_list` extension. The list
+    /// must then include `format`.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if a flag other than `mutable_format` is set.
+    /// - Panics if `view_formats` is not empty and `flags.mutable_format` isn't set.
+    ///
+    pub fn with_flags<'a, I>(
+        device: Arc<Device>,
+        dimensions: Dimensions,
+        format: F,
+        usage: ImageUsage,
+        flags: ImageCreateFlags,
+        view_formats: &[Format],
+        queue_families: I,
+    ) -> Result<Arc<StorageImage<F>>, ImageCreationError>
     where
         F: FormatDesc,
         I: IntoIterator<Item = QueueFamily<'a>>,
@@ -130,10 +203,12 @@ impl<F> StorageImage<F> {
                 Sharing::Exclusive
             };
 
-            UnsafeImage::new(
+            UnsafeImage::with_flags(
                 device.clone(),
                 usage,
                 format.format(),
+                flags,
+                view_formats,
                 dimensions.to_image_dimensions(),
                 1,
                 1,
@@ -179,8 +254,297 @@ impl<F> StorageImage<F> {
             format: format,
             queue_families: queue_families,
             gpu_lock: AtomicUsize::new(0),
+            initialized: AtomicBool::new(false),
+        }))
+    }
+
+    /// Same as `with_usage`, but the memory of the image is a dedicated allocation that can be
+    /// exported with `export_fd` or `export_win32_handle` as one of `handle_types`.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the extensions required by `handle_types` are not enabled on the device.
+    ///
+    pub fn with_exportable_memory<'a, I>(
+        device: Arc<Device>,
+        dimensions: Dimensions,
+        format: F,
+        usage: ImageUsage,
+        queue_families: I,
+        handle_types: ExternalMemoryHandleType,
+    ) -> Result<Arc<StorageImage<F>>, ImageCreationError>
+    where
+        F: FormatDesc,
+        I: IntoIterator<Item = QueueFamily<'a>>,
+    {
+        let (image, mem_reqs, queue_families) = StorageImage::<F>::external_image(
+            &device,
+            dimensions,
+            &format,
+            usage,
+            queue_families,
+            handle_types,
+        )?;
+
+        let mem_ty = external_memory_type(&device, mem_reqs.memory_type_bits)?;
+        let mem = DeviceMemory::dedicated_alloc_exportable(
+            device.clone(),
+            mem_ty,
+            mem_reqs.size,
+            DedicatedAlloc::Image(&image),
+            handle_types,
+        )?;
+
+        StorageImage::external_finish(image, mem, dimensions, format, queue_families, false)
+    }
+
+    /// Builds an image whose memory is imported from a POSIX file descriptor, for example a
+    /// dma-buf exported by a camera capture or a video decoder.
+    ///
+    /// The content of the imported memory is considered initialized and in the `General`
+    /// layout. The memory type is picked among the ones returned by
+    /// `DeviceMemory::fd_memory_type_bits` that are compatible with the image, preferring
+    /// device-local memory. Returns `NoCompatibleMemoryType` if there is none.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one file descriptor handle type.
+    /// - Panics if the extensions required by `handle_type` are not enabled on the device.
+    ///
+    /// # Safety
+    ///
+    /// - `fd` must be a valid handle to memory of type `handle_type` that is large enough for
+    ///   the image. See `DeviceMemory::import_fd`.
+    /// - The memory must have been written by an image with the same dimensions, format, usage
+    ///   and optimal tiling, since the layout of the memory can't be described otherwise.
+    /// - For the `opaque_fd` handle type, the memory must have been exported with the memory
+    ///   type that is picked, which is the case if it was exported by `with_exportable_memory`
+    ///   with the same parameters on the same physical device.
+    ///
+    #[cfg(unix)]
+    pub unsafe fn from_fd<'a, I>(
+        device: Arc<Device>,
+        dimensions: Dimensions,
+        format: F,
+        usage: ImageUsage,
+        queue_families: I,
+        handle_type: ExternalMemoryHandleType,
+        fd: File,
+    ) -> Result<Arc<StorageImage<F>>, ImageCreationError>
+    where
+        F: FormatDesc,
+        I: IntoIterator<Item = QueueFamily<'a>>,
+    {
+        let (image, mem_reqs, queue_families) = StorageImage::<F>::external_image(
+            &device,
+            dimensions,
+            &format,
+            usage,
+            queue_families,
+            handle_type,
+        )?;
+
+        let fd_memory_type_bits = DeviceMemory::fd_memory_type_bits(&device, handle_type, &fd)?;
+        let mem_ty =
+            external_memory_type(&device, mem_reqs.memory_type_bits & fd_memory_type_bits)?;
+        let mem = DeviceMemory::import_fd(
+            device.clone(),
+            mem_ty,
+            mem_reqs.size,
+            DedicatedAlloc::Image(&image),
+            handle_type,
+            fd,
+        )?;
+
+        StorageImage::external_finish(image, mem, dimensions, format, queue_families, true)
+    }
+
+    /// Builds an image whose memory is imported from a Windows handle, for example a shared
+    /// D3D11 texture.
+    ///
+    /// The content of the imported memory is considered initialized and in the `General`
+    /// layout.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one Windows handle type.
+    /// - Panics if the extensions required by `handle_type` are not enabled on the device.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid handle to memory of type `handle_type` that is large enough
+    ///   for the image. See `DeviceMemory::import_win32_handle`.
+    /// - The memory must have been written by an image with the same dimensions, format, usage
+    ///   and optimal tiling.
+    ///
+    #[cfg(windows)]
+    pub unsafe fn from_win32_handle<'a, I>(
+        device: Arc<Device>,
+        dimensions: Dimensions,
+        format: F,
+        usage: ImageUsage,
+        queue_families: I,
+        handle_type: ExternalMemoryHandleType,
+        handle: *mut c_void,
+    ) -> Result<Arc<StorageImage<F>>, ImageCreationError>
+    where
+        F: FormatDesc,
+        I: IntoIterator<Item = QueueFamily<'a>>,
+    {
+        let (image, mem_reqs, queue_families) = StorageImage::<F>::external_image(
+            &device,
+            dimensions,
+            &format,
+            usage,
+            queue_families,
+            handle_type,
+        )?;
+
+        let mem_ty = external_memory_type(&device, mem_reqs.memory_type_bits)?;
+        let mem = DeviceMemory::import_win32_handle(
+            device.clone(),
+            mem_ty,
+            mem_reqs.size,
+            DedicatedAlloc::Image(&image),
+            handle_type,
+            handle,
+        )?;
+
+        StorageImage::external_finish(image, mem, dimensions, format, queue_families, true)
+    }
+
+    // Creates the image of `with_exportable_memory`, `from_fd` and `from_win32_handle`.
+    fn external_image<'a, I>(
+        device: &Arc<Device>,
+        dimensions: Dimensions,
+        format: &F,
+        usage: ImageUsage,
+        queue_families: I,
+        handle_types: ExternalMemoryHandleType,
+    ) -> Result<(UnsafeImage, MemoryRequirements, SmallVec<[u32; 4]>), ImageCreationError>
+    where
+        F: FormatDesc,
+        I: IntoIterator<Item = QueueFamily<'a>>,
+    {
+        let queue_families = queue_families
+            .into_iter()
+            .map(|f| f.id())
+            .collect::<SmallVec<[u32; 4]>>();
+
+        let (image, mem_reqs) = unsafe {
+            let sharing = if queue_families.len() >= 2 {
+                Sharing::Concurrent(queue_families.iter().cloned())
+            } else {
+                Sharing::Exclusive
+            };
+
+            UnsafeImage::new_with_external_memory(
+                device.clone(),
+                usage,
+                format.format(),
+                dimensions.to_image_dimensions(),
+                1,
+                1,
+                sharing,
+                false,
+                false,
+                handle_types,
+            )?
+        };
+
+        Ok((image, mem_reqs, queue_families))
+    }
+
+    // Binds the dedicated memory of an external image and builds the `StorageImage`.
+    fn external_finish(
+        image: UnsafeImage,
+        mem: DeviceMemory,
+        dimensions: Dimensions,
+        format: F,
+        queue_families: SmallVec<[u32; 4]>,
+        initialized: bool,
+    ) -> Result<Arc<StorageImage<F>>, ImageCreationError> {
+        unsafe {
+            image.bind_memory(&mem, 0)?;
+        }
+
+        let view = unsafe {
+            UnsafeImageView::raw(
+                &image,
+                dimensions.to_view_type(),
+                0..image.mipmap_levels(),
+                0..image.dimensions().array_layers(),
+            )?
+        };
+
+        Ok(Arc::new(StorageImage {
+            image: image,
+            view: view,
+            memory: PotentialDedicatedAllocation::Dedicated(mem),
+            dimensions: dimensions,
+            format: format,
+            queue_families: queue_families,
+            gpu_lock: AtomicUsize::new(0),
+            initialized: AtomicBool::new(initialized),
         }))
     }
+
+    /// Exports the memory of the image as a POSIX file descriptor.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the image wasn't created with `with_exportable_memory` with `handle_type`.
+    /// - Panics if `handle_type` doesn't contain exactly one file descriptor handle type.
+    ///
+    #[cfg(unix)]
+    pub fn export_fd(
+        &self,
+        handle_type: ExternalMemoryHandleType,
+    ) -> Result<File, DeviceMemoryAllocError> {
+        match self.memory {
+            PotentialDedicatedAllocation::Dedicated(ref mem) => mem.export_fd(handle_type),
+            _ => panic!("the memory of the image is not exportable"),
+        }
+    }
+
+    /// Exports the memory of the image as a Windows handle.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the image wasn't created with `with_exportable_memory` with `handle_type`.
+    /// - Panics if `handle_type` doesn't contain exactly one Windows handle type.
+    ///
+    #[cfg(windows)]
+    pub fn export_win32_handle(
+        &self,
+        handle_type: ExternalMemoryHandleType,
+    ) -> Result<*mut c_void, DeviceMemoryAllocError> {
+        match self.memory {
+            PotentialDedicatedAllocation::Dedicated(ref mem) => {
+                mem.export_win32_handle(handle_type)
+            }
+            _ => panic!("the memory of the image is not exportable"),
+        }
+    }
+}
+
+// Picks the memory type of an external allocation among `memory_type_bits`, preferring
+// device-local memory.
+fn external_memory_type(
+    device: &Arc<Device>,
+    memory_type_bits: u32,
+) -> Result<MemoryType, ImageCreationError> {
+    let mut types = device
+        .physical_device()
+        .memory_types()
+        .filter(|t| (memory_type_bits & (1 << t.id())) != 0);
+    let first = types
+        .next()
+        .ok_or(ImageCreationError::NoCompatibleMemoryType)?;
+    if first.is_device_local() {
+        return Ok(first);
+    }
+    Ok(types.find(|t| t.is_device_local()).unwrap_or(first))
 }
 
 impl<F, A> StorageImage<F, A>
@@ -204,7 +568,7 @@ where
         ImageInner {
             image: &self.image,
             first_layer: 0,
-            num_layers: self.dimensions.array_layers() as usize,
+            num_layers: self.dimensions.array_layers_with_cube() as usize,
             first_mipmap_level: 0,
             num_mipmap_levels: 1,
         }
@@ -237,7 +601,6 @@ where
 
     #[inline]
     fn try_gpu_lock(&self, _: bool, expected_layout: ImageLayout) -> Result<(), AccessError> {
-        // TODO: handle initial layout transition
         if expected_layout != ImageLayout::General && expected_layout != ImageLayout::Undefined {
             return Err(AccessError::UnexpectedImageLayout {
                 requested: expected_layout,
@@ -245,6 +608,12 @@ where
             });
         }
 
+        if expected_layout == ImageLayout::General && !self.initialized.load(Ordering::SeqCst) {
+            return Err(AccessError::ImageNotInitialized {
+                requested: expected_layout,
+            });
+        }
+
         let val = self.gpu_lock.compare_and_swap(0, 1, Ordering::SeqCst);
         if val == 0 {
             Ok(())
@@ -262,8 +631,21 @@ where
     #[inline]
     unsafe fn unlock(&self, new_layout: Option<ImageLayout>) {
         assert!(new_layout.is_none() || new_layout == Some(ImageLayout::General));
+        if new_layout.is_some() {
+            self.initialized.store(true, Ordering::SeqCst);
+        }
         self.gpu_lock.fetch_sub(1, Ordering::SeqCst);
     }
+
+    #[inline]
+    unsafe fn layout_initialized(&self) {
+        self.initialized.store(true, Ordering::SeqCst);
+    }
+
+    #[inline]
+    fn is_layout_initialized(&self) -> bool {
+        self.initialized.load(Ordering::SeqCst)
+    }
 }
 
 unsafe impl<F, A> ImageClearValue<F::ClearValue> for StorageImage<F, A>
@@ -367,7 +749,10 @@ where
 mod tests {
     use super::StorageImage;
     use format::Format;
+    use image::sys::ImageCreationError;
     use image::Dimensions;
+    use image::ImageUsage;
+    use memory::ExternalMemoryHandleType;
 
     #[test]
     fn create() {
@@ -383,4 +768,46 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[test]
+    fn compressed_format_not_storage() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let res = StorageImage::new(
+            device,
+            Dimensions::Dim2d {
+                width: 32,
+                height: 32,
+            },
+            Format::BC1_RGBUnormBlock,
+            Some(queue.family()),
+        );
+        match res {
+            Err(ImageCreationError::UnsupportedUsage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn exportable_requires_extension() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let usage = ImageUsage {
+            storage: true,
+            ..ImageUsage::none()
+        };
+
+        assert_should_panic!("the khr_external_memory extension must be enabled", {
+            let _ = StorageImage::with_exportable_memory(
+                device,
+                Dimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                },
+                Format::R8G8B8A8Unorm,
+                usage,
+                Some(queue.family()),
+                ExternalMemoryHandleType::posix(),
+            );
+        });
+    }
 }
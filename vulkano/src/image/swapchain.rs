@@ -7,6 +7,7 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+use std::fmt;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::sync::Arc;
@@ -42,7 +43,13 @@ use OomError;
 /// method on the swapchain), which will have the effect of showing the content of the image to
 /// the screen. Once an image has been presented, it can no longer be used unless it is acquired
 /// again.
-// TODO: #[derive(Debug)]
+///
+/// The image must be in the `PresentSrc` layout when it is presented. Command buffers that use
+/// it, for example as a color attachment or as the destination of a transfer, automatically
+/// transition it from `PresentSrc` to the layout they need and back to `PresentSrc` at the end.
+/// The first use of each image transitions it from the `Undefined` layout instead. For this
+/// reason, swapchain images can only be used as color attachments and transfer destinations, and
+/// creating a swapchain with any other usage returns an error.
 pub struct SwapchainImage<W> {
     swapchain: Arc<Swapchain<W>>,
     image_offset: usize,
@@ -76,6 +83,15 @@ impl<W> SwapchainImage<W> {
         [dims.width(), dims.height()]
     }
 
+    /// Returns the index of the image in the swapchain.
+    ///
+    /// This is the index returned by `acquire_next_image` when this image is acquired, and the
+    /// index to pass when presenting it.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.image_offset
+    }
+
     /// Returns the swapchain this image belongs to.
     #[inline]
     pub fn swapchain(&self) -> &Arc<Swapchain<W>> {
@@ -219,6 +235,17 @@ unsafe impl<W> ImageViewAccess for SwapchainImage<W> {
     }
 }
 
+impl<W> fmt::Debug for SwapchainImage<W> {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "<Vulkan swapchain image #{} of {:?}>",
+            self.image_offset, self.swapchain
+        )
+    }
+}
+
 impl<W> PartialEq for SwapchainImage<W> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
@@ -21,18 +21,26 @@ use std::hash::Hasher;
 use std::mem;
 use std::mem::MaybeUninit;
 use std::ops::Range;
+use std::os::raw::c_void;
 use std::ptr;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use device::Device;
 use format::Format;
 use format::FormatTy;
+use image::ImageAspect;
+use image::ImageCreateFlags;
 use image::ImageDimensions;
+use image::ImageLayout;
 use image::ImageUsage;
 use image::MipmapsCount;
+use image::SparseImageMemoryRequirements;
+use image::Swizzle;
 use image::ViewType;
 use memory::DeviceMemory;
 use memory::DeviceMemoryAllocError;
+use memory::ExternalMemoryHandleType;
 use memory::MemoryRequirements;
 use sync::Sharing;
 
@@ -71,6 +79,26 @@ pub struct UnsafeImage {
     // `vkDestroyImage` is called only if `needs_destruction` is true.
     needs_destruction: bool,
     preinitialized_layout: bool,
+    // Flags the image was created with, in addition to the cubemap compatibility.
+    flags: ImageCreateFlags,
+    // Formats that views of the image are allowed to have. Empty if there is no restriction.
+    view_formats: SmallVec<[Format; 4]>,
+    exclusive_sharing: bool,
+    // Queue family ownership transfers submitted so far.
+    queue_family_transfer: Mutex<QueueFamilyTransfer>,
+    // Memory bound to the blocks of a sparse image with a safe bind command, kept alive until
+    // the image is destroyed.
+    sparse_memory: Mutex<Vec<Arc<DeviceMemory>>>,
+}
+
+// State of the queue family ownership transfers of an image.
+#[derive(Debug, Copy, Clone, Default)]
+struct QueueFamilyTransfer {
+    // Queue family the image was last released to, if any.
+    owner: Option<u32>,
+    // Queue family the image was last released from and old and new layouts of the release, if
+    // it hasn't been acquired yet.
+    pending_acquire: Option<(u32, ImageLayout, ImageLayout)>,
 }
 
 impl UnsafeImage {
@@ -98,6 +126,126 @@ impl UnsafeImage {
         Mi: Into<MipmapsCount>,
         I: Iterator<Item = u32>,
     {
+        UnsafeImage::with_flags(
+            device,
+            usage,
+            format,
+            ImageCreateFlags::none(),
+            &[],
+            dimensions,
+            num_samples,
+            mipmaps,
+            sharing,
+            linear_tiling,
+            preinitialized_layout,
+        )
+    }
+
+    /// Same as `new`, but the memory bound to the image can be exported to or imported from one
+    /// of `handle_types`.
+    ///
+    /// The memory must then be allocated with `DeviceMemory::dedicated_alloc_exportable` or
+    /// imported with `DeviceMemory::import_fd` or `DeviceMemory::import_win32_handle`. Without
+    /// an extension describing the memory layout, such as the DRM format modifiers, imported
+    /// memory must come from an image created with the same parameters on a compatible device.
+    ///
+    /// # Panic
+    ///
+    /// - Same panic conditions as `new`.
+    /// - Panics if the extensions required by `handle_types` are not enabled on the device.
+    ///
+    pub unsafe fn new_with_external_memory<'a, Mi, I>(
+        device: Arc<Device>,
+        usage: ImageUsage,
+        format: Format,
+        dimensions: ImageDimensions,
+        num_samples: u32,
+        mipmaps: Mi,
+        sharing: Sharing<I>,
+        linear_tiling: bool,
+        preinitialized_layout: bool,
+        handle_types: ExternalMemoryHandleType,
+    ) -> Result<(UnsafeImage, MemoryRequirements), ImageCreationError>
+    where
+        Mi: Into<MipmapsCount>,
+        I: Iterator<Item = u32>,
+    {
+        handle_types.check_extensions(device.loaded_extensions());
+
+        let sharing = match sharing {
+            Sharing::Exclusive => (vk::SHARING_MODE_EXCLUSIVE, SmallVec::<[u32; 8]>::new()),
+            Sharing::Concurrent(ids) => (vk::SHARING_MODE_CONCURRENT, ids.collect()),
+        };
+
+        UnsafeImage::new_impl(
+            device,
+            usage,
+            format,
+            dimensions,
+            num_samples,
+            mipmaps.into(),
+            sharing,
+            linear_tiling,
+            preinitialized_layout,
+            ImageCreateFlags::none(),
+            SmallVec::new(),
+            Some(handle_types),
+        )
+    }
+
+    /// Same as `new`, but allows passing additional creation flags.
+    ///
+    /// If `flags.mutable_format` is true, views of the image can be created with a format other
+    /// than the format of the image, as long as it is compatible with it. See
+    /// `Format::is_compatible_with`.
+    ///
+    /// If `view_formats` is not empty, it is the list of formats that views of the image are
+    /// allowed to have. Declaring this list at creation lets the implementation keep
+    /// optimizations that it would otherwise have to disable for mutable-format images. This
+    /// requires the `khr_image_format_list` extension. The list must include the format of the
+    /// image itself, and each format must be compatible with it.
+    ///
+    /// If `flags.sparse_binding` is true, the image is sparse. Memory must then be bound to it
+    /// with a `SubmitBindSparseBuilder` instead of `bind_memory`. With `flags.sparse_residency`,
+    /// parts of the image can be left unbound, and the blocks returned by
+    /// `sparse_memory_requirements` can be bound individually. The `sparse_binding`,
+    /// `sparse_residency_image2d`, `sparse_residency_image3d`, `sparse_residency*_samples` and
+    /// `sparse_residency_aliased` features must be enabled accordingly.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the dimensions is 0.
+    /// - Panics if the number of mipmaps is 0.
+    /// - Panics if the number of samples is 0.
+    /// - Panics if `flags.sparse_residency` or `flags.sparse_aliased` is set without
+    ///   `flags.sparse_binding`.
+    /// - Panics if `flags.sparse_residency` is set and `linear_tiling` is true.
+    /// - Panics if `flags.cube_compatible` or `flags.array_2d_compatible` is set. Cubemap
+    ///   compatibility is set through `dimensions` instead.
+    /// - Panics if `view_formats` is not empty and `flags.mutable_format` isn't set.
+    ///
+    pub unsafe fn with_flags<'a, Mi, I>(
+        device: Arc<Device>,
+        usage: ImageUsage,
+        format: Format,
+        flags: ImageCreateFlags,
+        view_formats: &[Format],
+        dimensions: ImageDimensions,
+        num_samples: u32,
+        mipmaps: Mi,
+        sharing: Sharing<I>,
+        linear_tiling: bool,
+        preinitialized_layout: bool,
+    ) -> Result<(UnsafeImage, MemoryRequirements), ImageCreationError>
+    where
+        Mi: Into<MipmapsCount>,
+        I: Iterator<Item = u32>,
+    {
+        assert!(!flags.cube_compatible && !flags.array_2d_compatible);
+        assert!(flags.sparse_binding || (!flags.sparse_residency && !flags.sparse_aliased));
+        assert!(!flags.sparse_residency || !linear_tiling);
+        assert!(view_formats.is_empty() || flags.mutable_format);
+
         let sharing = match sharing {
             Sharing::Exclusive => (vk::SHARING_MODE_EXCLUSIVE, SmallVec::<[u32; 8]>::new()),
             Sharing::Concurrent(ids) => (vk::SHARING_MODE_CONCURRENT, ids.collect()),
@@ -113,6 +261,9 @@ impl UnsafeImage {
             sharing,
             linear_tiling,
             preinitialized_layout,
+            flags,
+            view_formats.iter().cloned().collect(),
+            None,
         )
     }
 
@@ -127,12 +278,21 @@ impl UnsafeImage {
         (sh_mode, sh_indices): (vk::SharingMode, SmallVec<[u32; 8]>),
         linear_tiling: bool,
         preinitialized_layout: bool,
+        create_flags: ImageCreateFlags,
+        view_formats: SmallVec<[Format; 4]>,
+        external_memory: Option<ExternalMemoryHandleType>,
     ) -> Result<(UnsafeImage, MemoryRequirements), ImageCreationError> {
         // TODO: doesn't check that the proper features are enabled
 
         let vk = device.pointers();
         let vk_i = device.instance().pointers();
 
+        // Block-compressed formats are only supported if the corresponding
+        // `textureCompression*` feature is enabled.
+        if !format.required_features_enabled(device.enabled_features()) {
+            return Err(ImageCreationError::TextureCompressionFeatureNotEnabled);
+        }
+
         // Checking if image usage conforms to what is supported.
         let format_features = {
             let physical_device = device.physical_device().internal_object();
@@ -350,7 +510,7 @@ impl UnsafeImage {
         }
 
         // Decoding the dimensions.
-        let (ty, extent, array_layers, flags) = match dimensions {
+        let (ty, extent, array_layers, mut flags) = match dimensions {
             ImageDimensions::Dim1d {
                 width,
                 array_layers,
@@ -414,6 +574,49 @@ impl UnsafeImage {
             }
         };
 
+        // Sparse images require the features corresponding to their type and number of samples.
+        if create_flags.sparse_binding && !device.enabled_features().sparse_binding {
+            return Err(ImageCreationError::SparseBindingFeatureNotEnabled);
+        }
+        if create_flags.sparse_residency {
+            let features = device.enabled_features();
+            let supported = match ty {
+                vk::IMAGE_TYPE_2D => features.sparse_residency_image2d,
+                vk::IMAGE_TYPE_3D => features.sparse_residency_image3d,
+                _ => false,
+            } && match num_samples {
+                1 => true,
+                2 => features.sparse_residency2_samples,
+                4 => features.sparse_residency4_samples,
+                8 => features.sparse_residency8_samples,
+                16 => features.sparse_residency16_samples,
+                _ => false,
+            };
+            if !supported {
+                return Err(ImageCreationError::SparseResidencyFeatureNotEnabled);
+            }
+        }
+        if create_flags.sparse_aliased && !device.enabled_features().sparse_residency_aliased {
+            return Err(ImageCreationError::SparseResidencyAliasedFeatureNotEnabled);
+        }
+
+        {
+            let create_flags: vk::ImageCreateFlags = create_flags.into();
+            flags |= create_flags;
+        }
+
+        if !view_formats.is_empty() {
+            if !view_formats.contains(&format) {
+                return Err(ImageCreationError::ViewFormatsMissingImageFormat);
+            }
+            if !view_formats.iter().all(|&f| format.is_compatible_with(f)) {
+                return Err(ImageCreationError::IncompatibleViewFormat);
+            }
+            if !device.loaded_extensions().khr_image_format_list {
+                return Err(ImageCreationError::ImageFormatListExtensionNotEnabled);
+            }
+        }
+
         // Checking the dimensions against the limits.
         if array_layers > device.physical_device().limits().max_image_array_layers() {
             let err = ImageCreationError::UnsupportedDimensions {
@@ -481,7 +684,7 @@ impl UnsafeImage {
                 ty,
                 tiling,
                 usage,
-                0, /* TODO */
+                flags,
                 output.as_mut_ptr(),
             );
 
@@ -508,9 +711,37 @@ impl UnsafeImage {
 
         // Everything now ok. Creating the image.
         let image = {
+            // The structs below are chained in this order through their `pNext` field:
+            // external memory, format list.
+            let mut next: *const c_void = ptr::null();
+
+            let external_memory_info =
+                external_memory.map(|handle_types| vk::ExternalMemoryImageCreateInfoKHR {
+                    sType: vk::STRUCTURE_TYPE_EXTERNAL_MEMORY_IMAGE_CREATE_INFO_KHR,
+                    pNext: next,
+                    handleTypes: handle_types.to_vulkan_bits(),
+                });
+            if let Some(ref info) = external_memory_info {
+                next = info as *const vk::ExternalMemoryImageCreateInfoKHR as *const _;
+            }
+
+            let format_list = if !view_formats.is_empty() {
+                Some(vk::ImageFormatListCreateInfoKHR {
+                    sType: vk::STRUCTURE_TYPE_IMAGE_FORMAT_LIST_CREATE_INFO_KHR,
+                    pNext: next,
+                    viewFormatCount: view_formats.len() as u32,
+                    pViewFormats: view_formats.as_ptr() as *const vk::Format,
+                })
+            } else {
+                None
+            };
+            if let Some(ref info) = format_list {
+                next = info as *const vk::ImageFormatListCreateInfoKHR as *const _;
+            }
+
             let infos = vk::ImageCreateInfo {
                 sType: vk::STRUCTURE_TYPE_IMAGE_CREATE_INFO,
-                pNext: ptr::null(),
+                pNext: next,
                 flags: flags,
                 imageType: ty,
                 format: format as u32,
@@ -599,6 +830,11 @@ impl UnsafeImage {
             format_features: format_features,
             needs_destruction: true,
             preinitialized_layout,
+            flags: create_flags,
+            view_formats: view_formats,
+            exclusive_sharing: sh_mode == vk::SHARING_MODE_EXCLUSIVE,
+            queue_family_transfer: Mutex::new(QueueFamilyTransfer::default()),
+            sparse_memory: Mutex::new(Vec::new()),
         };
 
         Ok((image, mem_reqs))
@@ -635,12 +871,20 @@ impl UnsafeImage {
             format_features: output.assume_init().optimalTilingFeatures,
             needs_destruction: false,     // TODO: pass as parameter
             preinitialized_layout: false, // TODO: Maybe this should be passed in?
+            flags: ImageCreateFlags::none(),
+            view_formats: SmallVec::new(),
+            exclusive_sharing: true, // TODO: pass as parameter
+            queue_family_transfer: Mutex::new(QueueFamilyTransfer::default()),
+            sparse_memory: Mutex::new(Vec::new()),
         }
     }
 
     pub unsafe fn bind_memory(&self, memory: &DeviceMemory, offset: usize) -> Result<(), OomError> {
         let vk = self.device.pointers();
 
+        // Memory is bound to sparse images with `vkQueueBindSparse`.
+        debug_assert!(!self.flags.sparse_binding);
+
         // We check for correctness in debug mode.
         debug_assert!({
             let mut mem_reqs = MaybeUninit::uninit();
@@ -680,6 +924,147 @@ impl UnsafeImage {
         self.mipmaps
     }
 
+    /// Returns true if the image was created with the mutable format flag, in which case views
+    /// can be created with a format compatible with the format of the image.
+    #[inline]
+    pub fn mutable_format(&self) -> bool {
+        self.flags.mutable_format
+    }
+
+    /// Returns the formats that views of the image are allowed to have, as declared when the
+    /// image was created. An empty list means that any compatible format is allowed if the
+    /// image is mutable-format.
+    #[inline]
+    pub fn view_formats(&self) -> &[Format] {
+        &self.view_formats
+    }
+
+    /// Returns true if the image was created with `Sharing::Exclusive`.
+    ///
+    /// An exclusive image can only be accessed by one queue family at a time, and its ownership
+    /// must be explicitly transferred in order to use it from another queue family.
+    #[inline]
+    pub fn is_sharing_exclusive(&self) -> bool {
+        self.exclusive_sharing
+    }
+
+    /// Returns the id of the queue family that the image has been released to by the last
+    /// queue family ownership transfer submitted to a queue, or `None` if no transfer has been
+    /// submitted so far.
+    #[inline]
+    pub fn queue_family_owner(&self) -> Option<u32> {
+        self.queue_family_transfer.lock().unwrap().owner
+    }
+
+    /// Returns the old and new layouts of the last queue family ownership release submitted for
+    /// the image, if the matching acquire hasn't been submitted yet.
+    ///
+    /// The image must not be used by any command between the release and the acquire.
+    #[inline]
+    pub fn pending_acquire_layouts(&self) -> Option<(ImageLayout, ImageLayout)> {
+        self.queue_family_transfer
+            .lock()
+            .unwrap()
+            .pending_acquire
+            .map(|(_, old_layout, new_layout)| (old_layout, new_layout))
+    }
+
+    /// Returns the id of the queue family that the image has been released from by the last
+    /// queue family ownership release submitted to a queue, if the matching acquire hasn't been
+    /// submitted yet.
+    #[inline]
+    pub fn pending_acquire_source(&self) -> Option<u32> {
+        self.queue_family_transfer
+            .lock()
+            .unwrap()
+            .pending_acquire
+            .map(|(source, _, _)| source)
+    }
+
+    // Records that a command buffer releasing the image from the queue family `source` to the
+    // queue family `destination` has been submitted.
+    #[inline]
+    pub(crate) fn set_queue_family_released(
+        &self,
+        source: u32,
+        destination: u32,
+        old_layout: ImageLayout,
+        new_layout: ImageLayout,
+    ) {
+        *self.queue_family_transfer.lock().unwrap() = QueueFamilyTransfer {
+            owner: Some(destination),
+            pending_acquire: Some((source, old_layout, new_layout)),
+        };
+    }
+
+    // Records that a command buffer acquiring the image has been submitted.
+    #[inline]
+    pub(crate) fn set_queue_family_acquired(&self) {
+        self.queue_family_transfer.lock().unwrap().pending_acquire = None;
+    }
+
+    /// Returns the flags the image was created with.
+    ///
+    /// Cubemap compatibility isn't part of the flags. It is returned by `dimensions` instead.
+    #[inline]
+    pub fn flags(&self) -> ImageCreateFlags {
+        self.flags
+    }
+
+    /// Returns the sparse memory requirements of the image, one for each aspect or group of
+    /// aspects of its format.
+    ///
+    /// Returns an empty list if the image wasn't created with the sparse residency flag.
+    pub fn sparse_memory_requirements(&self) -> Vec<SparseImageMemoryRequirements> {
+        unsafe {
+            let vk = self.device.pointers();
+
+            let mut num = 0;
+            vk.GetImageSparseMemoryRequirements(
+                self.device.internal_object(),
+                self.image,
+                &mut num,
+                ptr::null_mut(),
+            );
+
+            let mut requirements = Vec::with_capacity(num as usize);
+            vk.GetImageSparseMemoryRequirements(
+                self.device.internal_object(),
+                self.image,
+                &mut num,
+                requirements.as_mut_ptr(),
+            );
+            requirements.set_len(num as usize);
+
+            requirements.into_iter().map(Into::into).collect()
+        }
+    }
+
+    /// Returns the memory requirements of the image.
+    ///
+    /// For a sparse image, `alignment` is the size in bytes of one sparse block.
+    pub fn memory_requirements(&self) -> MemoryRequirements {
+        unsafe {
+            let vk = self.device.pointers();
+
+            let mut output = MaybeUninit::uninit();
+            vk.GetImageMemoryRequirements(
+                self.device.internal_object(),
+                self.image,
+                output.as_mut_ptr(),
+            );
+            MemoryRequirements::from_vulkan_reqs(output.assume_init())
+        }
+    }
+
+    // Keeps `memory` alive until the image is destroyed.
+    pub(crate) fn keep_sparse_memory_alive(&self, memory: Arc<DeviceMemory>) {
+        let mut sparse_memory = self.sparse_memory.lock().unwrap();
+        if !sparse_memory.iter().any(|m| Arc::ptr_eq(m, &memory)) {
+            sparse_memory.push(memory);
+        }
+    }
+
     #[inline]
     pub fn dimensions(&self) -> ImageDimensions {
         self.dimensions
@@ -918,6 +1303,25 @@ pub enum ImageCreationError {
     UnsupportedUsage,
     /// The `shader_storage_image_multisample` feature must be enabled to create such an image.
     ShaderStorageImageMultisampleFeatureNotEnabled,
+    /// The `texture_compression_bc`, `texture_compression_etc2` or `texture_compression_astc_ldr`
+    /// feature corresponding to the compressed format must be enabled.
+    TextureCompressionFeatureNotEnabled,
+    /// The `sparse_binding` feature must be enabled to create a sparse image.
+    SparseBindingFeatureNotEnabled,
+    /// The `sparse_residency_image2d` or `sparse_residency_image3d` feature corresponding to the
+    /// type of the image, and the `sparse_residency*_samples` feature corresponding to its
+    /// number of samples, must be enabled to create a partially resident image.
+    SparseResidencyFeatureNotEnabled,
+    /// The `sparse_residency_aliased` feature must be enabled to create an aliased sparse image.
+    SparseResidencyAliasedFeatureNotEnabled,
+    /// The `khr_image_format_list` extension must be enabled to pass a list of view formats.
+    ImageFormatListExtensionNotEnabled,
+    /// One of the view formats isn't compatible with the format of the image.
+    IncompatibleViewFormat,
+    /// The list of view formats doesn't include the format of the image.
+    ViewFormatsMissingImageFormat,
+    /// None of the memory types of the device can be used for the memory of the image.
+    NoCompatibleMemoryType,
 }
 
 impl error::Error for ImageCreationError {
@@ -958,6 +1362,34 @@ impl fmt::Display for ImageCreationError {
                     "the `shader_storage_image_multisample` feature must be enabled to create such \
                  an image"
                 }
+                ImageCreationError::TextureCompressionFeatureNotEnabled => {
+                    "the texture compression feature corresponding to the compressed format must \
+                 be enabled"
+                }
+                ImageCreationError::SparseBindingFeatureNotEnabled => {
+                    "the `sparse_binding` feature must be enabled to create a sparse image"
+                }
+                ImageCreationError::SparseResidencyFeatureNotEnabled => {
+                    "the sparse residency features corresponding to the type and number of samples \
+                 of the image must be enabled"
+                }
+                ImageCreationError::SparseResidencyAliasedFeatureNotEnabled => {
+                    "the `sparse_residency_aliased` feature must be enabled to create an aliased \
+                 sparse image"
+                }
+                ImageCreationError::ImageFormatListExtensionNotEnabled => {
+                    "the `khr_image_format_list` extension must be enabled to restrict the formats \
+                 of the views of an image"
+                }
+                ImageCreationError::IncompatibleViewFormat => {
+                    "one of the view formats isn't compatible with the format of the image"
+                }
+                ImageCreationError::ViewFormatsMissingImageFormat => {
+                    "the list of view formats doesn't include the format of the image"
+                }
+                ImageCreationError::NoCompatibleMemoryType => {
+                    "none of the memory types of the device can be used for the memory of the image"
+                }
             }
         )
     }
@@ -1016,25 +1448,33 @@ pub struct UnsafeImageView {
     view: vk::ImageView,
     device: Arc<Device>,
     usage: vk::ImageUsageFlagBits,
-    identity_swizzle: bool,
+    swizzle: Swizzle,
     format: Format,
 }
 
 impl UnsafeImageView {
     /// See the docs of new().
+    #[inline]
     pub unsafe fn raw(
         image: &UnsafeImage,
         ty: ViewType,
         mipmap_levels: Range<u32>,
         array_layers: Range<u32>,
     ) -> Result<UnsafeImageView, OomError> {
-        let vk = image.device.pointers();
-
-        assert!(mipmap_levels.end > mipmap_levels.start);
-        assert!(mipmap_levels.end <= image.mipmaps);
-        assert!(array_layers.end > array_layers.start);
-        assert!(array_layers.end <= image.dimensions.array_layers());
+        UnsafeImageView::with_swizzle(image, ty, mipmap_levels, array_layers, Swizzle::default())
+    }
 
+    /// Same as `raw`, but the components of the view are swizzled according to `swizzle`.
+    ///
+    /// Note that views that don't have identity swizzling can't be used as framebuffer
+    /// attachments, storage images or input attachments.
+    pub unsafe fn with_swizzle(
+        image: &UnsafeImage,
+        ty: ViewType,
+        mipmap_levels: Range<u32>,
+        array_layers: Range<u32>,
+        swizzle: Swizzle,
+    ) -> Result<UnsafeImageView, OomError> {
         let aspect_mask = match image.format.ty() {
             FormatTy::Float | FormatTy::Uint | FormatTy::Sint | FormatTy::Compressed => {
                 vk::IMAGE_ASPECT_COLOR_BIT
@@ -1044,6 +1484,138 @@ impl UnsafeImageView {
             FormatTy::DepthStencil => vk::IMAGE_ASPECT_DEPTH_BIT | vk::IMAGE_ASPECT_STENCIL_BIT,
         };
 
+        UnsafeImageView::new_impl(
+            image,
+            ty,
+            mipmap_levels,
+            array_layers,
+            swizzle,
+            aspect_mask,
+            image.format,
+        )
+    }
+
+    /// Same as `with_swizzle`, but the view has a different format from the image. The
+    /// components of the image are reinterpreted according to `format`, for example to read an
+    /// sRGB image as `Unorm` without conversion.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `format` is different from the format of the image and the image was not
+    ///   created with the mutable format flag.
+    /// - Panics if `format` isn't compatible with the format of the image.
+    /// - Panics if the image was created with a list of view formats that doesn't contain
+    ///   `format`.
+    ///
+    pub unsafe fn with_format(
+        image: &UnsafeImage,
+        ty: ViewType,
+        mipmap_levels: Range<u32>,
+        array_layers: Range<u32>,
+        swizzle: Swizzle,
+        format: Format,
+    ) -> Result<UnsafeImageView, OomError> {
+        assert!(format == image.format || image.flags.mutable_format);
+        assert!(image.format.is_compatible_with(format));
+        assert!(image.view_formats.is_empty() || image.view_formats.contains(&format));
+
+        let aspect_mask = match format.ty() {
+            FormatTy::Float | FormatTy::Uint | FormatTy::Sint | FormatTy::Compressed => {
+                vk::IMAGE_ASPECT_COLOR_BIT
+            }
+            FormatTy::Depth => vk::IMAGE_ASPECT_DEPTH_BIT,
+            FormatTy::Stencil => vk::IMAGE_ASPECT_STENCIL_BIT,
+            FormatTy::DepthStencil => vk::IMAGE_ASPECT_DEPTH_BIT | vk::IMAGE_ASPECT_STENCIL_BIT,
+        };
+
+        UnsafeImageView::new_impl(
+            image,
+            ty,
+            mipmap_levels,
+            array_layers,
+            swizzle,
+            aspect_mask,
+            format,
+        )
+    }
+
+    /// Same as `with_swizzle`, but the view only gives access to one aspect of the image.
+    ///
+    /// This is typically used to sample the depth of a depth-stencil image, as a view that is
+    /// sampled must only have one aspect.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the format of the image doesn't have `aspect`.
+    ///
+    pub unsafe fn with_aspect(
+        image: &UnsafeImage,
+        ty: ViewType,
+        mipmap_levels: Range<u32>,
+        array_layers: Range<u32>,
+        swizzle: Swizzle,
+        aspect: ImageAspect,
+    ) -> Result<UnsafeImageView, OomError> {
+        assert!(aspect.is_in_format(image.format));
+        UnsafeImageView::new_impl(
+            image,
+            ty,
+            mipmap_levels,
+            array_layers,
+            swizzle,
+            aspect.into(),
+            image.format,
+        )
+    }
+
+    /// Same as `with_format`, but the view only gives access to one aspect of the image.
+    ///
+    /// # Panic
+    ///
+    /// - Panics for the same reasons as `with_format`.
+    /// - Panics if `format` doesn't have `aspect`.
+    ///
+    pub unsafe fn with_format_and_aspect(
+        image: &UnsafeImage,
+        ty: ViewType,
+        mipmap_levels: Range<u32>,
+        array_layers: Range<u32>,
+        swizzle: Swizzle,
+        format: Format,
+        aspect: ImageAspect,
+    ) -> Result<UnsafeImageView, OomError> {
+        assert!(format == image.format || image.flags.mutable_format);
+        assert!(image.format.is_compatible_with(format));
+        assert!(image.view_formats.is_empty() || image.view_formats.contains(&format));
+        assert!(aspect.is_in_format(format));
+
+        UnsafeImageView::new_impl(
+            image,
+            ty,
+            mipmap_levels,
+            array_layers,
+            swizzle,
+            aspect.into(),
+            format,
+        )
+    }
+
+    unsafe fn new_impl(
+        image: &UnsafeImage,
+        ty: ViewType,
+        mipmap_levels: Range<u32>,
+        array_layers: Range<u32>,
+        swizzle: Swizzle,
+        aspect_mask: vk::ImageAspectFlags,
+        format: Format,
+    ) -> Result<UnsafeImageView, OomError> {
+        let vk = image.device.pointers();
+
+        assert!(mipmap_levels.end > mipmap_levels.start);
+        assert!(mipmap_levels.end <= image.mipmaps);
+        assert!(array_layers.end > array_layers.start);
+        assert!(array_layers.end <= image.dimensions.array_layers());
+
         let view_type = match (
             image.dimensions(),
             ty,
@@ -1088,13 +1660,8 @@ impl UnsafeImageView {
                 flags: 0, // reserved
                 image: image.internal_object(),
                 viewType: view_type,
-                format: image.format as u32,
-                components: vk::ComponentMapping {
-                    r: 0,
-                    g: 0,
-                    b: 0,
-                    a: 0,
-                }, // FIXME:
+                format: format as u32,
+                components: swizzle.into(),
                 subresourceRange: vk::ImageSubresourceRange {
                     aspectMask: aspect_mask,
                     baseMipLevel: mipmap_levels.start,
@@ -1118,8 +1685,8 @@ impl UnsafeImageView {
             view: view,
             device: image.device.clone(),
             usage: image.usage,
-            identity_swizzle: true, // FIXME:
-            format: image.format,
+            swizzle: swizzle,
+            format: format,
         })
     }
 
@@ -1153,6 +1720,18 @@ impl UnsafeImageView {
         self.format
     }
 
+    /// Returns the swizzling of the components of the view.
+    #[inline]
+    pub fn swizzle(&self) -> Swizzle {
+        self.swizzle
+    }
+
+    /// Returns true if the view doesn't use components swizzling.
+    #[inline]
+    pub fn identity_swizzle(&self) -> bool {
+        self.swizzle.is_identity()
+    }
+
     #[inline]
     pub fn usage_transfer_source(&self) -> bool {
         (self.usage & vk::IMAGE_USAGE_TRANSFER_SRC_BIT) != 0
@@ -1249,6 +1828,7 @@ mod tests {
     use super::UnsafeImage;
 
     use format::Format;
+    use image::ImageCreateFlags;
     use image::ImageDimensions;
     use sync::Sharing;
 
@@ -1456,6 +2036,46 @@ mod tests {
         };
     }
 
+    #[test]
+    fn sparse_binding_feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let usage = ImageUsage {
+            sampled: true,
+            ..ImageUsage::none()
+        };
+        let flags = ImageCreateFlags {
+            sparse_binding: true,
+            ..ImageCreateFlags::none()
+        };
+
+        let res = unsafe {
+            UnsafeImage::with_flags(
+                device,
+                usage,
+                Format::R8G8B8A8Unorm,
+                flags,
+                &[],
+                ImageDimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                    array_layers: 1,
+                    cubemap_compatible: false,
+                },
+                1,
+                1,
+                Sharing::Exclusive::<Empty<_>>,
+                false,
+                false,
+            )
+        };
+
+        match res {
+            Err(ImageCreationError::SparseBindingFeatureNotEnabled) => (),
+            _ => panic!(),
+        };
+    }
+
     #[test]
     fn shader_storage_image_multisample() {
         let (device, _) = gfx_dev_and_queue!();
@@ -1526,6 +2146,41 @@ mod tests {
         };
     }
 
+    #[test]
+    fn compressed_feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+        assert!(!device.enabled_features().texture_compression_bc);
+
+        let usage = ImageUsage {
+            sampled: true,
+            ..ImageUsage::none()
+        };
+
+        let res = unsafe {
+            UnsafeImage::new(
+                device,
+                usage,
+                Format::BC1_RGBUnormBlock,
+                ImageDimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                    array_layers: 1,
+                    cubemap_compatible: false,
+                },
+                1,
+                1,
+                Sharing::Exclusive::<Empty<_>>,
+                false,
+                false,
+            )
+        };
+
+        match res {
+            Err(ImageCreationError::TextureCompressionFeatureNotEnabled) => (),
+            _ => panic!(),
+        };
+    }
+
     #[test]
     fn transient_forbidden_with_some_usages() {
         let (device, _) = gfx_dev_and_queue!();
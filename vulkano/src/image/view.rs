@@ -0,0 +1,860 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+
+use device::Device;
+use device::DeviceOwned;
+use format::Format;
+use image::sys::UnsafeImageView;
+use image::traits::ImageAccess;
+use image::traits::ImageViewAccess;
+use image::Dimensions;
+use image::ImageAspect;
+use image::ImageDimensions;
+use image::ImageLayout;
+use image::Swizzle;
+use image::ViewType;
+use sampler::Sampler;
+use OomError;
+
+/// View of an image that interprets it with a different view type, for example a 2D image with
+/// six array layers viewed as a cubemap.
+///
+/// The high-level images of vulkano already implement `ImageViewAccess` with a view that matches
+/// the `Dimensions` they were created with. An `ImageView` is useful when you need another view
+/// of the same image, such as a `Dim2dArray` view of a cubemap in order to write to its faces.
+///
+/// The layouts used in descriptor sets are the same as the ones of the view of the image itself.
+///
+/// # Example
+///
+/// ```
+/// # use vulkano::format::Format;
+/// # use vulkano::image::Dimensions;
+/// # use vulkano::image::ImageView;
+/// # use vulkano::image::StorageImage;
+/// # let device: std::sync::Arc<vulkano::device::Device> = return;
+/// # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+/// let image = StorageImage::new(
+///     device.clone(),
+///     Dimensions::Cubemap { size: 512 },
+///     Format::R8G8B8A8Unorm,
+///     Some(queue.family()),
+/// )
+/// .unwrap();
+///
+/// // The cubemap can be sampled as a cube with `image` itself, and written face by face with
+/// // `faces`.
+/// let faces = ImageView::dim2d_array(image.clone()).unwrap();
+/// ```
+pub struct ImageView<I> {
+    image: I,
+    view: UnsafeImageView,
+    dimensions: Dimensions,
+}
+
+impl<I> ImageView<I>
+where
+    I: ImageAccess + ImageViewAccess,
+{
+    /// Builds a `Dim2dArray` view of all the array layers of `image`.
+    #[inline]
+    pub fn dim2d_array(image: I) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        ImageView::new(image, ViewType::Dim2dArray)
+    }
+
+    /// Builds a `Cubemap` view of `image`.
+    ///
+    /// The image must have been created as cubemap-compatible, and must have exactly six array
+    /// layers.
+    #[inline]
+    pub fn cube(image: I) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        ImageView::new(image, ViewType::Cubemap)
+    }
+
+    /// Builds a `CubemapArray` view of `image`.
+    ///
+    /// The image must have been created as cubemap-compatible, and its number of array layers
+    /// must be a multiple of six. The `image_cube_array` feature must be enabled on the device.
+    #[inline]
+    pub fn cube_array(image: I) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        ImageView::new(image, ViewType::CubemapArray)
+    }
+
+    /// Builds a view of `image` with the given type. The view covers all the mipmap levels and
+    /// array layers of the image.
+    #[inline]
+    pub fn new(image: I, ty: ViewType) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        let (num_mipmap_levels, num_layers) = {
+            let inner = ImageAccess::inner(&image);
+            (inner.num_mipmap_levels as u32, inner.num_layers as u32)
+        };
+
+        ImageView::with_range(image, ty, 0..num_mipmap_levels, 0..num_layers)
+    }
+
+    /// Builds a view of `image` that only covers some of its mipmap levels and array layers.
+    ///
+    /// `mipmap_levels` and `array_layers` are relative to the subresources that `image` gives
+    /// access to. For example, you can create one view per mipmap level in order to generate the
+    /// mipmaps with a compute shader, or a `Dim2d` view of a single face of a cubemap.
+    ///
+    /// The dimensions of the view are the ones of the first mipmap level of the range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vulkano::image::ImageView;
+    /// # use vulkano::image::ViewType;
+    /// # let image: std::sync::Arc<vulkano::image::StorageImage<vulkano::format::Format>> = return;
+    /// // Views the face of the cubemap that points towards negative X.
+    /// let face = ImageView::with_range(image.clone(), ViewType::Dim2d, 0..1, 1..2).unwrap();
+    /// ```
+    #[inline]
+    pub fn with_range(
+        image: I,
+        ty: ViewType,
+        mipmap_levels: Range<u32>,
+        array_layers: Range<u32>,
+    ) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        ImageView::with_swizzle(image, ty, mipmap_levels, array_layers, Swizzle::default())
+    }
+
+    /// Same as `with_range`, but the components of the view are swizzled according to
+    /// `swizzle`.
+    ///
+    /// Views that don't have identity swizzling can only be sampled. Using them as a storage
+    /// image, an input attachment or a framebuffer attachment returns an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vulkano::image::ComponentSwizzle;
+    /// # use vulkano::image::ImageView;
+    /// # use vulkano::image::Swizzle;
+    /// # use vulkano::image::ViewType;
+    /// # let image: std::sync::Arc<vulkano::image::ImmutableImage<vulkano::format::Format>> = return;
+    /// // Samples a single-channel texture as a grayscale image.
+    /// let swizzle = Swizzle {
+    ///     r: ComponentSwizzle::Red,
+    ///     g: ComponentSwizzle::Red,
+    ///     b: ComponentSwizzle::Red,
+    ///     a: ComponentSwizzle::One,
+    /// };
+    /// let view = ImageView::with_swizzle(image.clone(), ViewType::Dim2d, 0..1, 0..1, swizzle)
+    ///     .unwrap();
+    /// ```
+    #[inline]
+    pub fn with_swizzle(
+        image: I,
+        ty: ViewType,
+        mipmap_levels: Range<u32>,
+        array_layers: Range<u32>,
+        swizzle: Swizzle,
+    ) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        ImageView::new_impl(image, ty, mipmap_levels, array_layers, swizzle, None, None)
+    }
+
+    /// Builds a view of `image` that only gives access to one of its aspects. The view covers
+    /// all the mipmap levels and array layers of the image.
+    ///
+    /// Shaders can only sample one aspect of a depth-stencil image at a time. For example, use
+    /// `ImageAspect::Depth` to sample a shadow map whose format also has a stencil component.
+    ///
+    /// Note that while a subpass uses the stencil of the image as an attachment, the image must
+    /// be in the `DepthReadOnlyStencilAttachmentOptimal` layout in order to sample its depth.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vulkano::image::ImageAspect;
+    /// # use vulkano::image::ImageView;
+    /// # use vulkano::image::ViewType;
+    /// # let shadow_map: std::sync::Arc<vulkano::image::AttachmentImage> = return;
+    /// let depth = ImageView::with_aspect(shadow_map.clone(), ViewType::Dim2d, ImageAspect::Depth)
+    ///     .unwrap();
+    /// ```
+    pub fn with_aspect(
+        image: I,
+        ty: ViewType,
+        aspect: ImageAspect,
+    ) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        let (num_mipmap_levels, num_layers) = {
+            let inner = ImageAccess::inner(&image);
+            (inner.num_mipmap_levels as u32, inner.num_layers as u32)
+        };
+
+        ImageView::new_impl(
+            image,
+            ty,
+            0..num_mipmap_levels,
+            0..num_layers,
+            Swizzle::default(),
+            Some(aspect),
+            None,
+        )
+    }
+
+    /// Builds a view of `image` that reinterprets its content with another format. The view
+    /// covers all the mipmap levels and array layers of the image.
+    ///
+    /// The image must have been created with the mutable format flag, and `format` must be
+    /// compatible with the format of the image. See `Format::is_compatible_with`. This is
+    /// typically used to access an sRGB image as `Unorm`, or the other way around, in order to
+    /// choose whether the implementation converts between the sRGB and linear color spaces.
+    ///
+    /// If the image was created with a list of view formats, `format` must be one of them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vulkano::format::Format;
+    /// # use vulkano::image::ImageView;
+    /// # use vulkano::image::ViewType;
+    /// # let image: std::sync::Arc<vulkano::image::StorageImage<Format>> = return;
+    /// let linear = ImageView::with_format(image.clone(), ViewType::Dim2d, Format::R8G8B8A8Unorm)
+    ///     .unwrap();
+    /// ```
+    pub fn with_format(
+        image: I,
+        ty: ViewType,
+        format: Format,
+    ) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        let (num_mipmap_levels, num_layers) = {
+            let inner = ImageAccess::inner(&image);
+            (inner.num_mipmap_levels as u32, inner.num_layers as u32)
+        };
+
+        ImageView::new_impl(
+            image,
+            ty,
+            0..num_mipmap_levels,
+            0..num_layers,
+            Swizzle::default(),
+            None,
+            Some(format),
+        )
+    }
+
+    /// Builds a view of `image` that reinterprets its content with another format and only gives
+    /// access to one of its aspects. The view covers all the mipmap levels and array layers of
+    /// the image.
+    ///
+    /// The same restrictions as `with_format` apply, and `aspect` must be in `format`.
+    pub fn with_format_and_aspect(
+        image: I,
+        ty: ViewType,
+        format: Format,
+        aspect: ImageAspect,
+    ) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        let (num_mipmap_levels, num_layers) = {
+            let inner = ImageAccess::inner(&image);
+            (inner.num_mipmap_levels as u32, inner.num_layers as u32)
+        };
+
+        ImageView::new_impl(
+            image,
+            ty,
+            0..num_mipmap_levels,
+            0..num_layers,
+            Swizzle::default(),
+            Some(aspect),
+            Some(format),
+        )
+    }
+
+    fn new_impl(
+        image: I,
+        ty: ViewType,
+        mipmap_levels: Range<u32>,
+        array_layers: Range<u32>,
+        swizzle: Swizzle,
+        aspect: Option<ImageAspect>,
+        format: Option<Format>,
+    ) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        let (view, dimensions) = {
+            let inner = ImageAccess::inner(&image);
+
+            if let Some(format) = format {
+                if format != inner.image.format() && !inner.image.mutable_format() {
+                    return Err(ImageViewCreationError::MutableFormatNotEnabled);
+                }
+
+                if !inner.image.format().is_compatible_with(format) {
+                    return Err(ImageViewCreationError::IncompatibleFormat);
+                }
+
+                let view_formats = inner.image.view_formats();
+                if !view_formats.is_empty() && !view_formats.contains(&format) {
+                    return Err(ImageViewCreationError::ViewFormatNotAllowed);
+                }
+            }
+
+            if let Some(aspect) = aspect {
+                if !aspect.is_in_format(format.unwrap_or_else(|| inner.image.format())) {
+                    return Err(ImageViewCreationError::AspectNotInFormat);
+                }
+            }
+
+            if mipmap_levels.start >= mipmap_levels.end
+                || mipmap_levels.end > inner.num_mipmap_levels as u32
+            {
+                return Err(ImageViewCreationError::MipmapLevelsOutOfRange {
+                    range: mipmap_levels,
+                    num_mipmap_levels: inner.num_mipmap_levels as u32,
+                });
+            }
+
+            if array_layers.start >= array_layers.end || array_layers.end > inner.num_layers as u32
+            {
+                return Err(ImageViewCreationError::ArrayLayersOutOfRange {
+                    range: array_layers,
+                    num_layers: inner.num_layers as u32,
+                });
+            }
+
+            let num_layers = array_layers.end - array_layers.start;
+            let first_mipmap_level = inner.first_mipmap_level as u32 + mipmap_levels.start;
+            let first_layer = inner.first_layer as u32 + array_layers.start;
+            let image_dimensions = inner
+                .image
+                .dimensions()
+                .mipmap_dimensions(first_mipmap_level)
+                .unwrap();
+
+            let dimensions = match (image_dimensions, ty) {
+                (ImageDimensions::Dim1d { width, .. }, ViewType::Dim1d) if num_layers == 1 => {
+                    Dimensions::Dim1d { width }
+                }
+                (ImageDimensions::Dim1d { width, .. }, ViewType::Dim1dArray) => {
+                    Dimensions::Dim1dArray {
+                        width,
+                        array_layers: num_layers,
+                    }
+                }
+                (ImageDimensions::Dim2d { width, height, .. }, ViewType::Dim2d)
+                    if num_layers == 1 =>
+                {
+                    Dimensions::Dim2d { width, height }
+                }
+                (ImageDimensions::Dim2d { width, height, .. }, ViewType::Dim2dArray) => {
+                    Dimensions::Dim2dArray {
+                        width,
+                        height,
+                        array_layers: num_layers,
+                    }
+                }
+                (
+                    ImageDimensions::Dim2d {
+                        width,
+                        cubemap_compatible: true,
+                        ..
+                    },
+                    ViewType::Cubemap,
+                ) if num_layers == 6 => Dimensions::Cubemap { size: width },
+                (
+                    ImageDimensions::Dim2d {
+                        width,
+                        cubemap_compatible: true,
+                        ..
+                    },
+                    ViewType::CubemapArray,
+                ) if num_layers % 6 == 0 => {
+                    if !inner.image.device().enabled_features().image_cube_array {
+                        return Err(ImageViewCreationError::ImageCubeArrayFeatureNotEnabled);
+                    }
+
+                    Dimensions::CubemapArray {
+                        size: width,
+                        array_layers: num_layers / 6,
+                    }
+                }
+                (
+                    ImageDimensions::Dim3d {
+                        width,
+                        height,
+                        depth,
+                    },
+                    ViewType::Dim3d,
+                ) => Dimensions::Dim3d {
+                    width,
+                    height,
+                    depth,
+                },
+                _ => return Err(ImageViewCreationError::IncompatibleViewType),
+            };
+
+            let mipmap_levels =
+                first_mipmap_level..first_mipmap_level + (mipmap_levels.end - mipmap_levels.start);
+            let array_layers = first_layer..first_layer + num_layers;
+
+            let view = unsafe {
+                match (aspect, format) {
+                    (Some(aspect), Some(format)) => UnsafeImageView::with_format_and_aspect(
+                        inner.image,
+                        ty,
+                        mipmap_levels,
+                        array_layers,
+                        swizzle,
+                        format,
+                        aspect,
+                    )?,
+                    (Some(aspect), None) => UnsafeImageView::with_aspect(
+                        inner.image,
+                        ty,
+                        mipmap_levels,
+                        array_layers,
+                        swizzle,
+                        aspect,
+                    )?,
+                    (None, Some(format)) => UnsafeImageView::with_format(
+                        inner.image,
+                        ty,
+                        mipmap_levels,
+                        array_layers,
+                        swizzle,
+                        format,
+                    )?,
+                    (None, None) => UnsafeImageView::with_swizzle(
+                        inner.image,
+                        ty,
+                        mipmap_levels,
+                        array_layers,
+                        swizzle,
+                    )?,
+                }
+            };
+
+            (view, dimensions)
+        };
+
+        Ok(Arc::new(ImageView {
+            image: image,
+            view: view,
+            dimensions: dimensions,
+        }))
+    }
+
+    /// Returns the image this view was created from.
+    #[inline]
+    pub fn image(&self) -> &I {
+        &self.image
+    }
+}
+
+unsafe impl<I> ImageViewAccess for ImageView<I>
+where
+    I: ImageAccess + ImageViewAccess,
+{
+    #[inline]
+    fn parent(&self) -> &dyn ImageAccess {
+        &self.image
+    }
+
+    #[inline]
+    fn dimensions(&self) -> Dimensions {
+        self.dimensions
+    }
+
+    #[inline]
+    fn inner(&self) -> &UnsafeImageView {
+        &self.view
+    }
+
+    #[inline]
+    fn format(&self) -> Format {
+        self.view.format()
+    }
+
+    #[inline]
+    fn descriptor_set_storage_image_layout(&self) -> ImageLayout {
+        self.image.descriptor_set_storage_image_layout()
+    }
+
+    #[inline]
+    fn descriptor_set_combined_image_sampler_layout(&self) -> ImageLayout {
+        self.image.descriptor_set_combined_image_sampler_layout()
+    }
+
+    #[inline]
+    fn descriptor_set_sampled_image_layout(&self) -> ImageLayout {
+        self.image.descriptor_set_sampled_image_layout()
+    }
+
+    #[inline]
+    fn descriptor_set_input_attachment_layout(&self) -> ImageLayout {
+        self.image.descriptor_set_input_attachment_layout()
+    }
+
+    #[inline]
+    fn identity_swizzle(&self) -> bool {
+        self.view.identity_swizzle()
+    }
+
+    #[inline]
+    fn can_be_sampled(&self, sampler: &Sampler) -> bool {
+        self.image.can_be_sampled(sampler)
+    }
+}
+
+unsafe impl<I> DeviceOwned for ImageView<I>
+where
+    I: ImageAccess,
+{
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        ImageAccess::inner(&self.image).image.device()
+    }
+}
+
+impl<I> fmt::Debug for ImageView<I>
+where
+    I: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        fmt.debug_struct("ImageView")
+            .field("image", &self.image)
+            .field("view", &self.view)
+            .field("dimensions", &self.dimensions)
+            .finish()
+    }
+}
+
+/// Error that can happen when creating an `ImageView`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImageViewCreationError {
+    /// Out of memory.
+    OomError(OomError),
+    /// The view type is not compatible with the dimensions of the image. For example the image
+    /// is not cubemap-compatible, or doesn't have a number of array layers that matches the view
+    /// type.
+    IncompatibleViewType,
+    /// The `image_cube_array` feature must be enabled to create a cubemap array view.
+    ImageCubeArrayFeatureNotEnabled,
+    /// The range of mipmap levels is empty or goes beyond the mipmap levels of the image.
+    MipmapLevelsOutOfRange {
+        range: Range<u32>,
+        num_mipmap_levels: u32,
+    },
+    /// The range of array layers is empty or goes beyond the array layers of the image.
+    ArrayLayersOutOfRange { range: Range<u32>, num_layers: u32 },
+    /// The format of the image doesn't have the requested aspect.
+    AspectNotInFormat,
+    /// The view has a different format from the image, but the image wasn't created with the
+    /// mutable format flag.
+    MutableFormatNotEnabled,
+    /// The format of the view isn't compatible with the format of the image.
+    IncompatibleFormat,
+    /// The image was created with a list of view formats that doesn't contain the format of the
+    /// view.
+    ViewFormatNotAllowed,
+}
+
+impl error::Error for ImageViewCreationError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            ImageViewCreationError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ImageViewCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                ImageViewCreationError::OomError(_) => "out of memory",
+                ImageViewCreationError::IncompatibleViewType => {
+                    "the view type is not compatible with the dimensions of the image"
+                }
+                ImageViewCreationError::ImageCubeArrayFeatureNotEnabled => {
+                    "the `image_cube_array` feature must be enabled to create a cubemap array view"
+                }
+                ImageViewCreationError::MipmapLevelsOutOfRange { .. } => {
+                    "the range of mipmap levels is empty or goes beyond the mipmap levels of the \
+                     image"
+                }
+                ImageViewCreationError::AspectNotInFormat => {
+                    "the format of the image doesn't have the requested aspect"
+                }
+                ImageViewCreationError::MutableFormatNotEnabled => {
+                    "the view has a different format from the image, but the image wasn't \
+                     created with the mutable format flag"
+                }
+                ImageViewCreationError::IncompatibleFormat => {
+                    "the format of the view isn't compatible with the format of the image"
+                }
+                ImageViewCreationError::ViewFormatNotAllowed => {
+                    "the image was created with a list of view formats that doesn't contain the \
+                     format of the view"
+                }
+                ImageViewCreationError::ArrayLayersOutOfRange { .. } => {
+                    "the range of array layers is empty or goes beyond the array layers of the \
+                     image"
+                }
+            }
+        )
+    }
+}
+
+impl From<OomError> for ImageViewCreationError {
+    #[inline]
+    fn from(err: OomError) -> ImageViewCreationError {
+        ImageViewCreationError::OomError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use format::Format;
+    use image::AttachmentImage;
+    use image::Dimensions;
+    use image::ImageAspect;
+    use image::ImageCreateFlags;
+    use image::ImageCreationError;
+    use image::ImageUsage;
+    use image::ImageView;
+    use image::ImageViewAccess;
+    use image::ImageViewCreationError;
+    use image::StorageImage;
+    use image::ViewType;
+
+    #[test]
+    fn cube_faces_as_array() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let image = StorageImage::new(
+            device,
+            Dimensions::Cubemap { size: 32 },
+            Format::R8G8B8A8Unorm,
+            Some(queue.family()),
+        )
+        .unwrap();
+
+        let faces = ImageView::dim2d_array(image.clone()).unwrap();
+        assert_eq!(
+            faces.dimensions(),
+            Dimensions::Dim2dArray {
+                width: 32,
+                height: 32,
+                array_layers: 6,
+            }
+        );
+
+        let cube = ImageView::cube(image).unwrap();
+        assert_eq!(cube.dimensions(), Dimensions::Cubemap { size: 32 });
+    }
+
+    #[test]
+    fn single_face() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let image = StorageImage::new(
+            device,
+            Dimensions::Cubemap { size: 32 },
+            Format::R8G8B8A8Unorm,
+            Some(queue.family()),
+        )
+        .unwrap();
+
+        let face = ImageView::with_range(image.clone(), ViewType::Dim2d, 0..1, 5..6).unwrap();
+        assert_eq!(
+            face.dimensions(),
+            Dimensions::Dim2d {
+                width: 32,
+                height: 32,
+            }
+        );
+
+        match ImageView::with_range(image, ViewType::Dim2d, 0..1, 6..7) {
+            Err(ImageViewCreationError::ArrayLayersOutOfRange { num_layers: 6, .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn depth_aspect() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let format = Format::supported_depth_format(device.physical_device(), false).unwrap();
+        let image = AttachmentImage::sampled(device, [32, 32], format).unwrap();
+
+        let depth = ImageView::with_aspect(image.clone(), ViewType::Dim2d, ImageAspect::Depth);
+        assert!(depth.is_ok());
+
+        match ImageView::with_aspect(image, ViewType::Dim2d, ImageAspect::Color) {
+            Err(ImageViewCreationError::AspectNotInFormat) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn cube_from_non_cube_compatible() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let image = StorageImage::new(
+            device,
+            Dimensions::Dim2dArray {
+                width: 32,
+                height: 32,
+                array_layers: 6,
+            },
+            Format::R8G8B8A8Unorm,
+            Some(queue.family()),
+        )
+        .unwrap();
+
+        match ImageView::cube(image) {
+            Err(ImageViewCreationError::IncompatibleViewType) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn unorm_view_of_srgb_image() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let usage = ImageUsage {
+            sampled: true,
+            ..ImageUsage::none()
+        };
+        let dimensions = Dimensions::Dim2d {
+            width: 32,
+            height: 32,
+        };
+
+        let image = StorageImage::with_flags(
+            device.clone(),
+            dimensions,
+            Format::R8G8B8A8Srgb,
+            usage,
+            ImageCreateFlags {
+                mutable_format: true,
+                ..ImageCreateFlags::none()
+            },
+            &[],
+            Some(queue.family()),
+        )
+        .unwrap();
+
+        let view = ImageView::with_format(image.clone(), ViewType::Dim2d, Format::R8G8B8A8Unorm);
+        assert_eq!(view.unwrap().format(), Format::R8G8B8A8Unorm);
+
+        match ImageView::with_format(image.clone(), ViewType::Dim2d, Format::R16G16B16A16Sfloat) {
+            Err(ImageViewCreationError::IncompatibleFormat) => (),
+            _ => panic!(),
+        }
+
+        let view = ImageView::with_format_and_aspect(
+            image.clone(),
+            ViewType::Dim2d,
+            Format::R8G8B8A8Unorm,
+            ImageAspect::Color,
+        );
+        assert_eq!(view.unwrap().format(), Format::R8G8B8A8Unorm);
+
+        match ImageView::with_format_and_aspect(
+            image,
+            ViewType::Dim2d,
+            Format::R8G8B8A8Unorm,
+            ImageAspect::Depth,
+        ) {
+            Err(ImageViewCreationError::AspectNotInFormat) => (),
+            _ => panic!(),
+        }
+
+        let image = StorageImage::with_usage(
+            device,
+            dimensions,
+            Format::R8G8B8A8Srgb,
+            usage,
+            Some(queue.family()),
+        )
+        .unwrap();
+
+        match ImageView::with_format(image, ViewType::Dim2d, Format::R8G8B8A8Unorm) {
+            Err(ImageViewCreationError::MutableFormatNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn view_formats_require_extension() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let dimensions = Dimensions::Dim2d {
+            width: 32,
+            height: 32,
+        };
+        let usage = ImageUsage {
+            sampled: true,
+            storage: true,
+            ..ImageUsage::none()
+        };
+
+        let res = StorageImage::with_flags(
+            device,
+            dimensions,
+            Format::R8G8B8A8Unorm,
+            usage,
+            ImageCreateFlags {
+                mutable_format: true,
+                ..ImageCreateFlags::none()
+            },
+            &[Format::R8G8B8A8Unorm, Format::R32Uint],
+            Some(queue.family()),
+        );
+
+        match res {
+            Err(ImageCreationError::ImageFormatListExtensionNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn view_formats_include_image_format() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let dimensions = Dimensions::Dim2d {
+            width: 32,
+            height: 32,
+        };
+        let usage = ImageUsage {
+            sampled: true,
+            storage: true,
+            ..ImageUsage::none()
+        };
+
+        let res = StorageImage::with_flags(
+            device,
+            dimensions,
+            Format::R8G8B8A8Unorm,
+            usage,
+            ImageCreateFlags {
+                mutable_format: true,
+                ..ImageCreateFlags::none()
+            },
+            &[Format::R32Uint],
+            Some(queue.family()),
+        );
+
+        match res {
+            Err(ImageCreationError::ViewFormatsMissingImageFormat) => (),
+            _ => panic!(),
+        }
+    }
+}
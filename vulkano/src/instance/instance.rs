@@ -95,6 +95,7 @@ use version::Version;
 pub struct Instance {
     instance: vk::Instance,
     //alloc: Option<Box<Alloc + Send + Sync>>,
+    api_version: Version,
     physical_devices: Vec<PhysicalDeviceInfos>,
     vk: vk::InstancePointers,
     extensions: RawInstanceExtensions,
@@ -209,6 +210,22 @@ impl Instance {
             None
         };
 
+        // The version of Vulkan that the application uses. Without an application info, it is
+        // Vulkan 1.0.
+        let api_version = if app_infos.is_some() {
+            Version {
+                major: 1,
+                minor: 1,
+                patch: 0,
+            }
+        } else {
+            Version {
+                major: 1,
+                minor: 0,
+                patch: 0,
+            }
+        };
+
         // Building the `vk::ApplicationInfo` if required.
         let app_infos = if let Some(app_infos) = app_infos {
             Some(vk::ApplicationInfo {
@@ -236,12 +253,7 @@ impl Instance {
                     .engine_version
                     .map(|v| v.into_vulkan_version())
                     .unwrap_or(0),
-                apiVersion: Version {
-                    major: 1,
-                    minor: 1,
-                    patch: 0,
-                }
-                .into_vulkan_version(), // TODO:
+                apiVersion: api_version.into_vulkan_version(), // TODO:
             })
         } else {
             None
@@ -316,6 +328,7 @@ impl Instance {
         Ok(Arc::new(Instance {
             instance: instance,
             //alloc: None,
+            api_version: api_version,
             physical_devices: physical_devices,
             vk: vk,
             extensions: extensions,
@@ -496,6 +509,16 @@ impl Instance {
         InstanceExtensions::from(&self.extensions)
     }
 
+    /// Returns the version of Vulkan that the instance was created for. This is Vulkan 1.1 if
+    /// application infos were passed to `Instance::new`, and Vulkan 1.0 otherwise.
+    ///
+    /// The functionalities of the physical devices that were added in more recent versions
+    /// can't be used.
+    #[inline]
+    pub fn api_version(&self) -> Version {
+        self.api_version
+    }
+
     #[inline]
     pub fn raw_loaded_extensions(&self) -> &RawInstanceExtensions {
         &self.extensions
@@ -7,13 +7,18 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+use std::cmp;
 use std::error;
 use std::fmt;
+#[cfg(unix)]
+use std::fs::File;
 use std::mem::MaybeUninit;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::ops::Range;
 use std::os::raw::c_void;
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use std::ptr;
 use std::sync::Arc;
 
@@ -23,6 +28,7 @@ use device::DeviceOwned;
 use instance::MemoryType;
 use memory::Content;
 use memory::DedicatedAlloc;
+use memory::ExternalMemoryHandleType;
 use vk;
 use Error;
 use OomError;
@@ -48,6 +54,7 @@ pub struct DeviceMemory {
     device: Arc<Device>,
     size: usize,
     memory_type_index: u32,
+    export_handle_types: ExternalMemoryHandleType,
 }
 
 impl DeviceMemory {
@@ -83,6 +90,205 @@ impl DeviceMemory {
         memory_type: MemoryType,
         size: usize,
         resource: DedicatedAlloc,
+    ) -> Result<DeviceMemory, DeviceMemoryAllocError> {
+        unsafe { DeviceMemory::alloc_impl(device, memory_type, size, resource, None, None) }
+    }
+
+    /// Same as `alloc`, but the memory can be exported with `export_fd` or
+    /// `export_win32_handle` as one of `handle_types`.
+    ///
+    /// # Panic
+    ///
+    /// - Same panic conditions as `alloc`.
+    /// - Panics if the extensions required by `handle_types` are not enabled on the device.
+    ///
+    #[inline]
+    pub fn alloc_exportable(
+        device: Arc<Device>,
+        memory_type: MemoryType,
+        size: usize,
+        handle_types: ExternalMemoryHandleType,
+    ) -> Result<DeviceMemory, DeviceMemoryAllocError> {
+        DeviceMemory::dedicated_alloc_exportable(
+            device,
+            memory_type,
+            size,
+            DedicatedAlloc::None,
+            handle_types,
+        )
+    }
+
+    /// Equivalent of `dedicated_alloc` for `alloc_exportable`.
+    ///
+    /// If `resource` is a buffer or an image, it must have been created with
+    /// `UnsafeBuffer::new_with_external_memory` or `UnsafeImage::new_with_external_memory` with
+    /// the same handle types.
+    #[inline]
+    pub fn dedicated_alloc_exportable(
+        device: Arc<Device>,
+        memory_type: MemoryType,
+        size: usize,
+        resource: DedicatedAlloc,
+        handle_types: ExternalMemoryHandleType,
+    ) -> Result<DeviceMemory, DeviceMemoryAllocError> {
+        handle_types.check_extensions(device.loaded_extensions());
+        unsafe {
+            DeviceMemory::alloc_impl(
+                device,
+                memory_type,
+                size,
+                resource,
+                Some(handle_types),
+                None,
+            )
+        }
+    }
+
+    /// Imports memory that was exported by another API or process as a POSIX file descriptor.
+    ///
+    /// On success, the ownership of `fd` is transferred to the Vulkan implementation and the file
+    /// descriptor must not be used anymore. On failure, `fd` is closed.
+    ///
+    /// # Panic
+    ///
+    /// - Same panic conditions as `alloc`.
+    /// - Panics if `handle_type` doesn't contain exactly one file descriptor handle type.
+    /// - Panics if the extensions required by `handle_type` are not enabled on the device.
+    ///
+    /// # Safety
+    ///
+    /// - `fd` must be a valid handle to memory of type `handle_type`, of at least `size` bytes.
+    /// - `memory_type` must be compatible with the imported memory.
+    /// - If `resource` is a buffer or an image, it must have been created with
+    ///   `UnsafeBuffer::new_with_external_memory` or `UnsafeImage::new_with_external_memory`
+    ///   with `handle_type`.
+    ///
+    #[cfg(unix)]
+    pub unsafe fn import_fd(
+        device: Arc<Device>,
+        memory_type: MemoryType,
+        size: usize,
+        resource: DedicatedAlloc,
+        handle_type: ExternalMemoryHandleType,
+        fd: File,
+    ) -> Result<DeviceMemory, DeviceMemoryAllocError> {
+        assert!(handle_type.count() == 1 && handle_type.is_fd());
+        handle_type.check_extensions(device.loaded_extensions());
+
+        let fd = fd.into_raw_fd();
+        let result = DeviceMemory::alloc_impl(
+            device,
+            memory_type,
+            size,
+            resource,
+            None,
+            Some(ImportHandle::Fd(handle_type, fd)),
+        );
+
+        // The Vulkan implementation only takes ownership of the file descriptor on success.
+        if result.is_err() {
+            drop(File::from_raw_fd(fd));
+        }
+
+        result
+    }
+
+    /// Returns the memory types that memory imported from `fd` can use, as a bitmask with one
+    /// bit for each memory type index of the physical device.
+    ///
+    /// The memory type passed to `import_fd` must be one of them, and must also be compatible
+    /// with the resource that the memory is bound to. Vulkan doesn't report the memory types of
+    /// the `opaque_fd` handle type, for which the memory type must be the one that the memory was
+    /// allocated with when exporting it. In that case, all the memory types are returned.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one file descriptor handle type.
+    /// - Panics if the extensions required by `handle_type` are not enabled on the device.
+    ///
+    #[cfg(unix)]
+    pub fn fd_memory_type_bits(
+        device: &Device,
+        handle_type: ExternalMemoryHandleType,
+        fd: &File,
+    ) -> Result<u32, DeviceMemoryAllocError> {
+        assert!(handle_type.count() == 1 && handle_type.is_fd());
+        handle_type.check_extensions(device.loaded_extensions());
+
+        if handle_type.opaque_fd {
+            return Ok(!0);
+        }
+
+        let vk = device.pointers();
+
+        unsafe {
+            let mut properties = vk::MemoryFdPropertiesKHR {
+                sType: vk::STRUCTURE_TYPE_MEMORY_FD_PROPERTIES_KHR,
+                pNext: ptr::null_mut(),
+                memoryTypeBits: 0,
+            };
+
+            check_errors(vk.GetMemoryFdPropertiesKHR(
+                device.internal_object(),
+                handle_type.to_vulkan_bits(),
+                fd.as_raw_fd(),
+                &mut properties,
+            ))?;
+
+            Ok(properties.memoryTypeBits)
+        }
+    }
+
+    /// Imports memory that was exported by another API or process as a Windows handle.
+    ///
+    /// Contrary to file descriptors, the ownership of `handle` is not transferred. It can be
+    /// closed once the memory has been imported.
+    ///
+    /// # Panic
+    ///
+    /// - Same panic conditions as `alloc`.
+    /// - Panics if `handle_type` doesn't contain exactly one Windows handle type.
+    /// - Panics if the extensions required by `handle_type` are not enabled on the device.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid handle to memory of type `handle_type`, of at least `size`
+    ///   bytes.
+    /// - `memory_type` must be compatible with the imported memory.
+    /// - If `resource` is a buffer or an image, it must have been created with
+    ///   `UnsafeBuffer::new_with_external_memory` or `UnsafeImage::new_with_external_memory`
+    ///   with `handle_type`.
+    ///
+    #[cfg(windows)]
+    pub unsafe fn import_win32_handle(
+        device: Arc<Device>,
+        memory_type: MemoryType,
+        size: usize,
+        resource: DedicatedAlloc,
+        handle_type: ExternalMemoryHandleType,
+        handle: *mut c_void,
+    ) -> Result<DeviceMemory, DeviceMemoryAllocError> {
+        assert!(handle_type.count() == 1 && !handle_type.is_fd());
+        handle_type.check_extensions(device.loaded_extensions());
+
+        DeviceMemory::alloc_impl(
+            device,
+            memory_type,
+            size,
+            resource,
+            None,
+            Some(ImportHandle::Win32(handle_type, handle)),
+        )
+    }
+
+    // Shared implementation of the allocation functions.
+    unsafe fn alloc_impl(
+        device: Arc<Device>,
+        memory_type: MemoryType,
+        size: usize,
+        resource: DedicatedAlloc,
+        export: Option<ExternalMemoryHandleType>,
+        import: Option<ImportHandle>,
     ) -> Result<DeviceMemory, DeviceMemoryAllocError> {
         assert!(size >= 1);
         assert_eq!(
@@ -99,7 +305,7 @@ impl DeviceMemory {
             ));
         }
 
-        let memory = unsafe {
+        let memory = {
             let physical_device = device.physical_device();
             let mut allocation_count = device.allocation_count().lock().expect("Poisoned mutex");
             if *allocation_count >= physical_device.limits().max_memory_allocation_count() {
@@ -107,18 +313,22 @@ impl DeviceMemory {
             }
             let vk = device.pointers();
 
+            // The structs below are chained in this order through their `pNext` field:
+            // import, export, dedicated allocation.
+            let mut next: *const c_void = ptr::null();
+
             // Decide whether we are going to pass a `vkMemoryDedicatedAllocateInfoKHR`.
             let dedicated_alloc_info = if device.loaded_extensions().khr_dedicated_allocation {
                 match resource {
                     DedicatedAlloc::Buffer(buffer) => Some(vk::MemoryDedicatedAllocateInfoKHR {
                         sType: vk::STRUCTURE_TYPE_MEMORY_DEDICATED_ALLOCATE_INFO_KHR,
-                        pNext: ptr::null(),
+                        pNext: next,
                         image: 0,
                         buffer: buffer.internal_object(),
                     }),
                     DedicatedAlloc::Image(image) => Some(vk::MemoryDedicatedAllocateInfoKHR {
                         sType: vk::STRUCTURE_TYPE_MEMORY_DEDICATED_ALLOCATE_INFO_KHR,
-                        pNext: ptr::null(),
+                        pNext: next,
                         image: image.internal_object(),
                         buffer: 0,
                     }),
@@ -127,13 +337,59 @@ impl DeviceMemory {
             } else {
                 None
             };
+            if let Some(ref info) = dedicated_alloc_info {
+                next = info as *const vk::MemoryDedicatedAllocateInfoKHR as *const _;
+            }
+
+            let export_info = export.map(|handle_types| vk::ExportMemoryAllocateInfoKHR {
+                sType: vk::STRUCTURE_TYPE_EXPORT_MEMORY_ALLOCATE_INFO_KHR,
+                pNext: next,
+                handleTypes: handle_types.to_vulkan_bits(),
+            });
+            if let Some(ref info) = export_info {
+                next = info as *const vk::ExportMemoryAllocateInfoKHR as *const _;
+            }
+
+            #[cfg(unix)]
+            let import_fd_info =
+                import.map(
+                    |ImportHandle::Fd(handle_type, fd)| vk::ImportMemoryFdInfoKHR {
+                        sType: vk::STRUCTURE_TYPE_IMPORT_MEMORY_FD_INFO_KHR,
+                        pNext: next,
+                        handleType: handle_type.to_vulkan_bits(),
+                        fd: fd,
+                    },
+                );
+            #[cfg(unix)]
+            {
+                if let Some(ref info) = import_fd_info {
+                    next = info as *const vk::ImportMemoryFdInfoKHR as *const _;
+                }
+            }
+
+            #[cfg(windows)]
+            let import_win32_info = import.map(|ImportHandle::Win32(handle_type, handle)| {
+                vk::ImportMemoryWin32HandleInfoKHR {
+                    sType: vk::STRUCTURE_TYPE_IMPORT_MEMORY_WIN32_HANDLE_INFO_KHR,
+                    pNext: next,
+                    handleType: handle_type.to_vulkan_bits(),
+                    handle: handle,
+                    name: ptr::null(),
+                }
+            });
+            #[cfg(windows)]
+            {
+                if let Some(ref info) = import_win32_info {
+                    next = info as *const vk::ImportMemoryWin32HandleInfoKHR as *const _;
+                }
+            }
+
+            #[cfg(not(any(unix, windows)))]
+            let _ = import;
 
             let infos = vk::MemoryAllocateInfo {
                 sType: vk::STRUCTURE_TYPE_MEMORY_ALLOCATE_INFO,
-                pNext: dedicated_alloc_info
-                    .as_ref()
-                    .map(|i| i as *const vk::MemoryDedicatedAllocateInfoKHR)
-                    .unwrap_or(ptr::null()) as *const _,
+                pNext: next,
                 allocationSize: size as u64,
                 memoryTypeIndex: memory_type.id(),
             };
@@ -154,6 +410,7 @@ impl DeviceMemory {
             device: device,
             size: size,
             memory_type_index: memory_type.id(),
+            export_handle_types: export.unwrap_or(ExternalMemoryHandleType::none()),
         })
     }
 
@@ -221,6 +478,107 @@ impl DeviceMemory {
     pub fn size(&self) -> usize {
         self.size
     }
+
+    /// Returns the handle types the memory can be exported as.
+    ///
+    /// This is only non-empty if the memory was allocated with `alloc_exportable` or
+    /// `dedicated_alloc_exportable`.
+    #[inline]
+    pub fn export_handle_types(&self) -> ExternalMemoryHandleType {
+        self.export_handle_types
+    }
+
+    /// Exports the memory as a POSIX file descriptor.
+    ///
+    /// Each call returns a new file descriptor that refers to the same memory. The memory stays
+    /// alive as long as this `DeviceMemory` or any of the file descriptors exist.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one file descriptor handle type.
+    /// - Panics if the memory wasn't allocated as exportable with `handle_type`.
+    ///
+    #[cfg(unix)]
+    pub fn export_fd(
+        &self,
+        handle_type: ExternalMemoryHandleType,
+    ) -> Result<File, DeviceMemoryAllocError> {
+        assert!(handle_type.count() == 1 && handle_type.is_fd());
+        assert!(
+            self.export_handle_types.to_vulkan_bits() & handle_type.to_vulkan_bits() != 0,
+            "the memory was not allocated as exportable with this handle type"
+        );
+
+        let vk = self.device.pointers();
+
+        let fd = unsafe {
+            let info = vk::MemoryGetFdInfoKHR {
+                sType: vk::STRUCTURE_TYPE_MEMORY_GET_FD_INFO_KHR,
+                pNext: ptr::null(),
+                memory: self.memory,
+                handleType: handle_type.to_vulkan_bits(),
+            };
+
+            let mut output = MaybeUninit::uninit();
+            check_errors(vk.GetMemoryFdKHR(
+                self.device.internal_object(),
+                &info,
+                output.as_mut_ptr(),
+            ))?;
+            output.assume_init()
+        };
+
+        Ok(unsafe { File::from_raw_fd(fd) })
+    }
+
+    /// Exports the memory as a Windows handle.
+    ///
+    /// For the NT handle types, the returned handle must be closed with `CloseHandle` once it is
+    /// no longer needed.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one Windows handle type.
+    /// - Panics if the memory wasn't allocated as exportable with `handle_type`.
+    ///
+    #[cfg(windows)]
+    pub fn export_win32_handle(
+        &self,
+        handle_type: ExternalMemoryHandleType,
+    ) -> Result<*mut c_void, DeviceMemoryAllocError> {
+        assert!(handle_type.count() == 1 && !handle_type.is_fd());
+        assert!(
+            self.export_handle_types.to_vulkan_bits() & handle_type.to_vulkan_bits() != 0,
+            "the memory was not allocated as exportable with this handle type"
+        );
+
+        let vk = self.device.pointers();
+
+        unsafe {
+            let info = vk::MemoryGetWin32HandleInfoKHR {
+                sType: vk::STRUCTURE_TYPE_MEMORY_GET_WIN32_HANDLE_INFO_KHR,
+                pNext: ptr::null(),
+                memory: self.memory,
+                handleType: handle_type.to_vulkan_bits(),
+            };
+
+            let mut output = MaybeUninit::uninit();
+            check_errors(vk.GetMemoryWin32HandleKHR(
+                self.device.internal_object(),
+                &info,
+                output.as_mut_ptr(),
+            ))?;
+            Ok(output.assume_init())
+        }
+    }
+}
+
+// Handle passed to `alloc_impl` when importing memory.
+enum ImportHandle {
+    #[cfg(unix)]
+    Fd(ExternalMemoryHandleType, RawFd),
+    #[cfg(windows)]
+    Win32(ExternalMemoryHandleType, *mut c_void),
 }
 
 unsafe impl DeviceOwned for DeviceMemory {
@@ -348,12 +706,13 @@ impl MappedDeviceMemory {
         .unwrap(); // TODO: error
 
         if !self.coherent {
+            let aligned = self.atom_aligned_range(range.clone());
             let range = vk::MappedMemoryRange {
                 sType: vk::STRUCTURE_TYPE_MAPPED_MEMORY_RANGE,
                 pNext: ptr::null(),
                 memory: self.memory.internal_object(),
-                offset: range.start as u64,
-                size: (range.end - range.start) as u64,
+                offset: aligned.start as u64,
+                size: (aligned.end - aligned.start) as u64,
             };
 
             // TODO: check result?
@@ -367,6 +726,29 @@ impl MappedDeviceMemory {
             range: range,
         }
     }
+
+    /// Expands `range` so that it can be passed to `vkFlushMappedMemoryRanges` and
+    /// `vkInvalidateMappedMemoryRanges`, which require offsets and sizes to be multiples of the
+    /// `nonCoherentAtomSize` limit of the physical device.
+    #[inline]
+    fn atom_aligned_range(&self, range: Range<usize>) -> Range<usize> {
+        let atom_size = self
+            .memory
+            .device()
+            .physical_device()
+            .limits()
+            .non_coherent_atom_size() as usize;
+        align_range_to_atom(range, atom_size, self.memory.size())
+    }
+}
+
+// Rounds `range` outwards to multiples of `atom_size`, without going past `memory_size`.
+#[inline]
+fn align_range_to_atom(range: Range<usize>, atom_size: usize, memory_size: usize) -> Range<usize> {
+    debug_assert!(atom_size.is_power_of_two());
+    let start = range.start & !(atom_size - 1);
+    let end = (range.end + atom_size - 1) & !(atom_size - 1);
+    start..cmp::min(end, memory_size)
 }
 
 impl AsRef<DeviceMemory> for MappedDeviceMemory {
@@ -457,13 +839,14 @@ impl<'a, T: ?Sized + 'a> Drop for CpuAccess<'a, T> {
         // If the memory doesn't have the `coherent` flag, we need to flush the data.
         if !self.coherent {
             let vk = self.mem.as_ref().device().pointers();
+            let aligned = self.mem.atom_aligned_range(self.range.clone());
 
             let range = vk::MappedMemoryRange {
                 sType: vk::STRUCTURE_TYPE_MAPPED_MEMORY_RANGE,
                 pNext: ptr::null(),
                 memory: self.mem.as_ref().internal_object(),
-                offset: self.range.start as u64,
-                size: (self.range.end - self.range.start) as u64,
+                offset: aligned.start as u64,
+                size: (aligned.end - aligned.start) as u64,
             };
 
             // TODO: check result?
@@ -483,6 +866,8 @@ pub enum DeviceMemoryAllocError {
     TooManyObjects,
     /// Memory map failed.
     MemoryMapFailed,
+    /// The external handle passed when importing memory is invalid.
+    InvalidExternalHandle,
 }
 
 impl error::Error for DeviceMemoryAllocError {
@@ -507,6 +892,9 @@ impl fmt::Display for DeviceMemoryAllocError {
                     "the maximum number of allocations has been exceeded"
                 }
                 DeviceMemoryAllocError::MemoryMapFailed => "memory map failed",
+                DeviceMemoryAllocError::InvalidExternalHandle => {
+                    "the external handle passed when importing memory is invalid"
+                }
             }
         )
     }
@@ -521,6 +909,7 @@ impl From<Error> for DeviceMemoryAllocError {
             }
             Error::TooManyObjects => DeviceMemoryAllocError::TooManyObjects,
             Error::MemoryMapFailed => DeviceMemoryAllocError::MemoryMapFailed,
+            Error::InvalidExternalHandle => DeviceMemoryAllocError::InvalidExternalHandle,
             _ => panic!("unexpected error: {:?}", err),
         }
     }
@@ -535,8 +924,10 @@ impl From<OomError> for DeviceMemoryAllocError {
 
 #[cfg(test)]
 mod tests {
+    use super::align_range_to_atom;
     use memory::DeviceMemory;
     use memory::DeviceMemoryAllocError;
+    use memory::ExternalMemoryHandleType;
     use OomError;
 
     #[test]
@@ -546,6 +937,14 @@ mod tests {
         let _ = DeviceMemory::alloc(device.clone(), mem_ty, 256).unwrap();
     }
 
+    #[test]
+    fn atom_alignment() {
+        assert_eq!(align_range_to_atom(0..256, 64, 1024), 0..256);
+        assert_eq!(align_range_to_atom(10..70, 64, 1024), 0..128);
+        assert_eq!(align_range_to_atom(1000..1010, 64, 1010), 960..1010);
+        assert_eq!(align_range_to_atom(5..6, 1, 1024), 5..6);
+    }
+
     #[test]
     fn zero_size() {
         let (device, _) = gfx_dev_and_queue!();
@@ -597,6 +996,17 @@ mod tests {
         panic!()
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn export_requires_exportable() {
+        let (device, _) = gfx_dev_and_queue!();
+        let mem_ty = device.physical_device().memory_types().next().unwrap();
+        let mem = DeviceMemory::alloc(device.clone(), mem_ty, 256).unwrap();
+        assert_should_panic!({
+            let _ = mem.export_fd(ExternalMemoryHandleType::posix());
+        });
+    }
+
     #[test]
     fn allocation_count() {
         let (device, _) = gfx_dev_and_queue!();
@@ -0,0 +1,197 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use device::DeviceExtensions;
+use std::ops::BitOr;
+use vk;
+
+/// Describes the handle types that can be used to share memory with other APIs or processes.
+///
+/// When exporting or importing memory, exactly one of the fields must be set. When creating an
+/// exportable allocation or buffer, several fields can be set at once.
+///
+/// - `opaque_fd` requires the `khr_external_memory_fd` extension.
+/// - `dma_buf` requires the `khr_external_memory_fd` and `ext_external_memory_dma_buf`
+///   extensions.
+/// - `opaque_win32`, `opaque_win32_kmt`, `d3d11_texture`, `d3d11_texture_kmt`, `d3d12_heap` and
+///   `d3d12_resource` require the `khr_external_memory_win32` extension.
+///
+/// All of them require the `khr_external_memory` extension.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ExternalMemoryHandleType {
+    pub opaque_fd: bool,
+    pub opaque_win32: bool,
+    pub opaque_win32_kmt: bool,
+    pub d3d11_texture: bool,
+    pub d3d11_texture_kmt: bool,
+    pub d3d12_heap: bool,
+    pub d3d12_resource: bool,
+    pub dma_buf: bool,
+}
+
+impl ExternalMemoryHandleType {
+    /// Builds an `ExternalMemoryHandleType` with all fields set to false.
+    #[inline]
+    pub fn none() -> ExternalMemoryHandleType {
+        ExternalMemoryHandleType {
+            opaque_fd: false,
+            opaque_win32: false,
+            opaque_win32_kmt: false,
+            d3d11_texture: false,
+            d3d11_texture_kmt: false,
+            d3d12_heap: false,
+            d3d12_resource: false,
+            dma_buf: false,
+        }
+    }
+
+    /// Builds an `ExternalMemoryHandleType` for a POSIX file descriptor.
+    #[inline]
+    pub fn posix() -> ExternalMemoryHandleType {
+        ExternalMemoryHandleType {
+            opaque_fd: true,
+            ..ExternalMemoryHandleType::none()
+        }
+    }
+
+    /// Builds an `ExternalMemoryHandleType` for a Linux dma-buf file descriptor.
+    #[inline]
+    pub fn dma_buf() -> ExternalMemoryHandleType {
+        ExternalMemoryHandleType {
+            dma_buf: true,
+            ..ExternalMemoryHandleType::none()
+        }
+    }
+
+    /// Builds an `ExternalMemoryHandleType` for a Windows NT handle.
+    #[inline]
+    pub fn win32() -> ExternalMemoryHandleType {
+        ExternalMemoryHandleType {
+            opaque_win32: true,
+            ..ExternalMemoryHandleType::none()
+        }
+    }
+
+    /// Returns true if the handle types are file descriptors.
+    #[inline]
+    pub(crate) fn is_fd(&self) -> bool {
+        self.opaque_fd || self.dma_buf
+    }
+
+    /// Returns the number of fields that are set.
+    #[inline]
+    pub(crate) fn count(&self) -> u32 {
+        self.to_vulkan_bits().count_ones()
+    }
+
+    /// Turns this `ExternalMemoryHandleType` into raw Vulkan bits.
+    pub(crate) fn to_vulkan_bits(&self) -> vk::ExternalMemoryHandleTypeFlagsKHR {
+        let mut result = 0;
+        if self.opaque_fd {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_FD_BIT_KHR;
+        }
+        if self.opaque_win32 {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_WIN32_BIT_KHR;
+        }
+        if self.opaque_win32_kmt {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT_KHR;
+        }
+        if self.d3d11_texture {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D11_TEXTURE_BIT_KHR;
+        }
+        if self.d3d11_texture_kmt {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D11_TEXTURE_KMT_BIT_KHR;
+        }
+        if self.d3d12_heap {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_HEAP_BIT_KHR;
+        }
+        if self.d3d12_resource {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_RESOURCE_BIT_KHR;
+        }
+        if self.dma_buf {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_DMA_BUF_BIT_EXT;
+        }
+        result
+    }
+
+    /// Checks that the extensions required by the handle types are enabled.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if an extension required by one of the handle types is not enabled.
+    ///
+    pub(crate) fn check_extensions(&self, extensions: &DeviceExtensions) {
+        assert!(
+            extensions.khr_external_memory,
+            "the khr_external_memory extension must be enabled"
+        );
+        if self.is_fd() {
+            assert!(
+                extensions.khr_external_memory_fd,
+                "the khr_external_memory_fd extension must be enabled"
+            );
+        }
+        if self.dma_buf {
+            assert!(
+                extensions.ext_external_memory_dma_buf,
+                "the ext_external_memory_dma_buf extension must be enabled"
+            );
+        }
+        if self.opaque_win32
+            || self.opaque_win32_kmt
+            || self.d3d11_texture
+            || self.d3d11_texture_kmt
+            || self.d3d12_heap
+            || self.d3d12_resource
+        {
+            assert!(
+                extensions.khr_external_memory_win32,
+                "the khr_external_memory_win32 extension must be enabled"
+            );
+        }
+    }
+}
+
+impl BitOr for ExternalMemoryHandleType {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        ExternalMemoryHandleType {
+            opaque_fd: self.opaque_fd || rhs.opaque_fd,
+            opaque_win32: self.opaque_win32 || rhs.opaque_win32,
+            opaque_win32_kmt: self.opaque_win32_kmt || rhs.opaque_win32_kmt,
+            d3d11_texture: self.d3d11_texture || rhs.d3d11_texture,
+            d3d11_texture_kmt: self.d3d11_texture_kmt || rhs.d3d11_texture_kmt,
+            d3d12_heap: self.d3d12_heap || rhs.d3d12_heap,
+            d3d12_resource: self.d3d12_resource || rhs.d3d12_resource,
+            dma_buf: self.dma_buf || rhs.dma_buf,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use memory::ExternalMemoryHandleType;
+    use vk;
+
+    #[test]
+    fn vulkan_bits() {
+        assert_eq!(ExternalMemoryHandleType::none().to_vulkan_bits(), 0);
+        assert_eq!(
+            ExternalMemoryHandleType::posix().to_vulkan_bits(),
+            vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_FD_BIT_KHR
+        );
+
+        let both = ExternalMemoryHandleType::posix() | ExternalMemoryHandleType::dma_buf();
+        assert_eq!(both.count(), 2);
+        assert!(both.is_fd());
+        assert!(!ExternalMemoryHandleType::win32().is_fd());
+    }
+}
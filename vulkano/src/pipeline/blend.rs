@@ -150,6 +150,21 @@ impl AttachmentBlend {
         }
     }
 
+    /// Returns true if one of the blend factors refers to the second source color, which
+    /// requires the `dual_src_blend` feature.
+    #[inline]
+    pub(crate) fn uses_dual_source(&self) -> bool {
+        self.enabled
+            && [
+                self.color_source,
+                self.color_destination,
+                self.alpha_source,
+                self.alpha_destination,
+            ]
+            .iter()
+            .any(|f| f.is_dual_source())
+    }
+
     #[inline]
     pub(crate) fn into_vulkan_state(self) -> vk::PipelineColorBlendAttachmentState {
         vk::PipelineColorBlendAttachmentState {
@@ -264,3 +279,40 @@ pub enum BlendFactor {
     Src1Alpha = vk::BLEND_FACTOR_SRC1_ALPHA,
     OneMinusSrc1Alpha = vk::BLEND_FACTOR_ONE_MINUS_SRC1_ALPHA,
 }
+
+impl BlendFactor {
+    /// Returns true if the factor refers to the second color output of the fragment shader.
+    #[inline]
+    pub(crate) fn is_dual_source(&self) -> bool {
+        match *self {
+            BlendFactor::Src1Color
+            | BlendFactor::OneMinusSrc1Color
+            | BlendFactor::Src1Alpha
+            | BlendFactor::OneMinusSrc1Alpha => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pipeline::blend::AttachmentBlend;
+    use pipeline::blend::BlendFactor;
+
+    #[test]
+    fn dual_source() {
+        assert!(!AttachmentBlend::alpha_blending().uses_dual_source());
+
+        let blend = AttachmentBlend {
+            color_destination: BlendFactor::OneMinusSrc1Color,
+            ..AttachmentBlend::alpha_blending()
+        };
+        assert!(blend.uses_dual_source());
+
+        let disabled = AttachmentBlend {
+            enabled: false,
+            ..blend
+        };
+        assert!(!disabled.uses_dual_source());
+    }
+}
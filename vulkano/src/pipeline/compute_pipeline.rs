@@ -10,11 +10,11 @@
 use std::error;
 use std::fmt;
 use std::marker::PhantomData;
-use std::mem;
 use std::mem::MaybeUninit;
 use std::ptr;
 use std::sync::Arc;
 
+use command_buffer::validity::dispatch_base_supported;
 use descriptor::descriptor::DescriptorDesc;
 use descriptor::descriptor_set::UnsafeDescriptorSetLayout;
 use descriptor::pipeline_layout::PipelineLayout;
@@ -25,8 +25,13 @@ use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
 use descriptor::pipeline_layout::PipelineLayoutNotSupersetError;
 use descriptor::pipeline_layout::PipelineLayoutSuperset;
 use descriptor::pipeline_layout::PipelineLayoutSys;
+use pipeline::executable;
+use pipeline::executable::PipelineExecutableError;
+use pipeline::executable::PipelineExecutableInternalRepresentation;
+use pipeline::executable::PipelineExecutableProperties;
+use pipeline::executable::PipelineExecutableStatistic;
+use pipeline::shader::specialization_info;
 use pipeline::shader::EntryPointAbstract;
-use pipeline::shader::SpecializationConstants;
 
 use check_errors;
 use device::Device;
@@ -47,6 +52,9 @@ use VulkanObject;
 pub struct ComputePipeline<Pl> {
     inner: Inner,
     pipeline_layout: Pl,
+    dispatch_base: bool,
+    capture_statistics: bool,
+    capture_internal_representations: bool,
 }
 
 struct Inner {
@@ -56,6 +64,18 @@ struct Inner {
 
 impl ComputePipeline<()> {
     /// Builds a new `ComputePipeline`.
+    ///
+    /// The pipeline layout is built from the layout of `shader`. With the `vulkano-shaders`
+    /// macros, this is the generated `Layout` type, and `shader` is typically obtained with
+    /// `shader.main_entry_point()`. Pass `&()` as `specialization` if the shader has no
+    /// specialization constants, and `&Default::default()` to use the default values declared in
+    /// the shader.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the `SpecializationConstants` implementation declares the same constant twice
+    ///   or declares a constant out of the range of the struct.
+    ///
     pub fn new<Cs>(
         device: Arc<Device>,
         shader: &Cs,
@@ -75,6 +95,71 @@ impl ComputePipeline<()> {
             )
         }
     }
+
+    /// Same as `new`, but the pipeline can also be used with `dispatch_base`.
+    ///
+    /// Returns an error if the device doesn't support Vulkan 1.1 and the `khr_device_group`
+    /// extension isn't enabled.
+    pub fn with_dispatch_base<Cs>(
+        device: Arc<Device>,
+        shader: &Cs,
+        specialization: &Cs::SpecializationConstants,
+    ) -> Result<ComputePipeline<PipelineLayout<Cs::PipelineLayout>>, ComputePipelineCreationError>
+    where
+        Cs::PipelineLayout: Clone,
+        Cs: EntryPointAbstract,
+    {
+        if !dispatch_base_supported(&device) {
+            return Err(ComputePipelineCreationError::DispatchBaseNotSupported);
+        }
+
+        unsafe {
+            let pipeline_layout = shader.layout().clone().build(device.clone())?;
+            ComputePipeline::create(
+                device,
+                shader,
+                specialization,
+                pipeline_layout,
+                true,
+                false,
+                false,
+            )
+        }
+    }
+
+    /// Same as `new`, but captures the statistics and the internal representations of the
+    /// executables of the pipeline, so that they can be queried with `executable_statistics` and
+    /// `executable_internal_representations`.
+    ///
+    /// Returns an error if the `pipeline_executable_info` feature isn't enabled.
+    pub fn with_executable_info<Cs>(
+        device: Arc<Device>,
+        shader: &Cs,
+        specialization: &Cs::SpecializationConstants,
+        capture_statistics: bool,
+        capture_internal_representations: bool,
+    ) -> Result<ComputePipeline<PipelineLayout<Cs::PipelineLayout>>, ComputePipelineCreationError>
+    where
+        Cs::PipelineLayout: Clone,
+        Cs: EntryPointAbstract,
+    {
+        if !device.enabled_features().pipeline_executable_info {
+            return Err(ComputePipelineCreationError::PipelineExecutableInfoFeatureNotEnabled);
+        }
+
+        unsafe {
+            let pipeline_layout = shader.layout().clone().build(device.clone())?;
+            ComputePipeline::create(
+                device,
+                shader,
+                specialization,
+                pipeline_layout,
+                false,
+                capture_statistics,
+                capture_internal_representations,
+            )
+        }
+    }
 }
 
 impl<Pl> ComputePipeline<Pl> {
@@ -116,17 +201,35 @@ impl<Pl> ComputePipeline<Pl> {
         Cs::PipelineLayout: Clone,
         Cs: EntryPointAbstract,
         Pl: PipelineLayoutAbstract,
+    {
+        ComputePipeline::create(
+            device,
+            shader,
+            specialization,
+            pipeline_layout,
+            false,
+            false,
+            false,
+        )
+    }
+
+    unsafe fn create<Cs>(
+        device: Arc<Device>,
+        shader: &Cs,
+        specialization: &Cs::SpecializationConstants,
+        pipeline_layout: Pl,
+        dispatch_base: bool,
+        capture_statistics: bool,
+        capture_internal_representations: bool,
+    ) -> Result<ComputePipeline<Pl>, ComputePipelineCreationError>
+    where
+        Cs: EntryPointAbstract,
+        Pl: PipelineLayoutAbstract,
     {
         let vk = device.pointers();
 
         let pipeline = {
-            let spec_descriptors = Cs::SpecializationConstants::descriptors();
-            let specialization = vk::SpecializationInfo {
-                mapEntryCount: spec_descriptors.len() as u32,
-                pMapEntries: spec_descriptors.as_ptr() as *const _,
-                dataSize: mem::size_of_val(specialization),
-                pData: specialization as *const Cs::SpecializationConstants as *const _,
-            };
+            let specialization = specialization_info(specialization);
 
             let stage = vk::PipelineShaderStageCreateInfo {
                 sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
@@ -142,10 +245,21 @@ impl<Pl> ComputePipeline<Pl> {
                 },
             };
 
+            let mut flags = 0;
+            if dispatch_base {
+                flags |= vk::PIPELINE_CREATE_DISPATCH_BASE;
+            }
+            if capture_statistics {
+                flags |= vk::PIPELINE_CREATE_CAPTURE_STATISTICS_BIT_KHR;
+            }
+            if capture_internal_representations {
+                flags |= vk::PIPELINE_CREATE_CAPTURE_INTERNAL_REPRESENTATIONS_BIT_KHR;
+            }
+
             let infos = vk::ComputePipelineCreateInfo {
                 sType: vk::STRUCTURE_TYPE_COMPUTE_PIPELINE_CREATE_INFO,
                 pNext: ptr::null(),
-                flags: 0,
+                flags: flags,
                 stage: stage,
                 layout: PipelineLayoutAbstract::sys(&pipeline_layout).internal_object(),
                 basePipelineHandle: 0,
@@ -170,6 +284,9 @@ impl<Pl> ComputePipeline<Pl> {
                 pipeline: pipeline,
             },
             pipeline_layout: pipeline_layout,
+            dispatch_base: dispatch_base,
+            capture_statistics: capture_statistics,
+            capture_internal_representations: capture_internal_representations,
         })
     }
 }
@@ -193,12 +310,66 @@ impl<Pl> ComputePipeline<Pl> {
     pub fn layout(&self) -> &Pl {
         &self.pipeline_layout
     }
+
+    /// Returns the properties of the executables that the implementation compiled for this
+    /// pipeline.
+    ///
+    /// Requires the `pipeline_executable_info` feature.
+    #[inline]
+    pub fn executable_properties(
+        &self,
+    ) -> Result<Vec<PipelineExecutableProperties>, PipelineExecutableError> {
+        executable::executable_properties(&self.inner.device, self.inner.pipeline)
+    }
+
+    /// Returns the statistics of the executable at index `executable_index` in the list returned
+    /// by `executable_properties`.
+    ///
+    /// The pipeline must have been built with `with_executable_info` and `capture_statistics`.
+    #[inline]
+    pub fn executable_statistics(
+        &self,
+        executable_index: u32,
+    ) -> Result<Vec<PipelineExecutableStatistic>, PipelineExecutableError> {
+        if !self.capture_statistics {
+            return Err(PipelineExecutableError::StatisticsNotCaptured);
+        }
+
+        executable::executable_statistics(&self.inner.device, self.inner.pipeline, executable_index)
+    }
+
+    /// Returns the internal representations of the executable at index `executable_index` in the
+    /// list returned by `executable_properties`.
+    ///
+    /// The pipeline must have been built with `with_executable_info` and
+    /// `capture_internal_representations`.
+    #[inline]
+    pub fn executable_internal_representations(
+        &self,
+        executable_index: u32,
+    ) -> Result<Vec<PipelineExecutableInternalRepresentation>, PipelineExecutableError> {
+        if !self.capture_internal_representations {
+            return Err(PipelineExecutableError::InternalRepresentationsNotCaptured);
+        }
+
+        executable::executable_internal_representations(
+            &self.inner.device,
+            self.inner.pipeline,
+            executable_index,
+        )
+    }
 }
 
 /// Trait implemented on all compute pipelines.
 pub unsafe trait ComputePipelineAbstract: PipelineLayoutAbstract {
     /// Returns an opaque object that represents the inside of the compute pipeline.
     fn inner(&self) -> ComputePipelineSys;
+
+    /// Returns true if the pipeline can be used with `dispatch_base`.
+    #[inline]
+    fn dispatch_base(&self) -> bool {
+        false
+    }
 }
 
 unsafe impl<Pl> ComputePipelineAbstract for ComputePipeline<Pl>
@@ -209,6 +380,11 @@ where
     fn inner(&self) -> ComputePipelineSys {
         ComputePipelineSys(self.inner.pipeline, PhantomData)
     }
+
+    #[inline]
+    fn dispatch_base(&self) -> bool {
+        self.dispatch_base
+    }
 }
 
 unsafe impl<T> ComputePipelineAbstract for T
@@ -220,6 +396,11 @@ where
     fn inner(&self) -> ComputePipelineSys {
         (**self).inner()
     }
+
+    #[inline]
+    fn dispatch_base(&self) -> bool {
+        (**self).dispatch_base()
+    }
 }
 
 /// Opaque object that represents the inside of the compute pipeline. Can be made into a trait
@@ -321,6 +502,12 @@ pub enum ComputePipelineCreationError {
     PipelineLayoutCreationError(PipelineLayoutCreationError),
     /// The pipeline layout is not compatible with what the shader expects.
     IncompatiblePipelineLayout(PipelineLayoutNotSupersetError),
+    /// Dispatching with a base workgroup requires Vulkan 1.1 or the `khr_device_group`
+    /// extension.
+    DispatchBaseNotSupported,
+    /// The `pipeline_executable_info` feature must be enabled in order to capture statistics or
+    /// internal representations.
+    PipelineExecutableInfoFeatureNotEnabled,
 }
 
 impl error::Error for ComputePipelineCreationError {
@@ -330,6 +517,8 @@ impl error::Error for ComputePipelineCreationError {
             ComputePipelineCreationError::OomError(ref err) => Some(err),
             ComputePipelineCreationError::PipelineLayoutCreationError(ref err) => Some(err),
             ComputePipelineCreationError::IncompatiblePipelineLayout(ref err) => Some(err),
+            ComputePipelineCreationError::DispatchBaseNotSupported => None,
+            ComputePipelineCreationError::PipelineExecutableInfoFeatureNotEnabled => None,
         }
     }
 }
@@ -348,6 +537,14 @@ impl fmt::Display for ComputePipelineCreationError {
                 ComputePipelineCreationError::IncompatiblePipelineLayout(_) => {
                     "the pipeline layout is not compatible with what the shader expects"
                 }
+                ComputePipelineCreationError::DispatchBaseNotSupported => {
+                    "dispatching with a base workgroup requires Vulkan 1.1 or the \
+                     `khr_device_group` extension"
+                }
+                ComputePipelineCreationError::PipelineExecutableInfoFeatureNotEnabled => {
+                    "the `pipeline_executable_info` feature must be enabled in order to capture \
+                     statistics or internal representations"
+                }
             }
         )
     }
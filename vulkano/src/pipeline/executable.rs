@@ -0,0 +1,396 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Introspection of the executables compiled by the implementation for a pipeline.
+//!
+//! When building a pipeline, the Vulkan implementation compiles its shaders into one or more
+//! executables. With the `pipeline_executable_info` feature of the
+//! `khr_pipeline_executable_properties` extension, you can query the list of these
+//! executables, statistics about each of them (for example the number of registers used or
+//! spilled), and their internal representations (for example the generated assembly). This is
+//! mostly useful for profiling and debugging.
+//!
+//! This is supported by graphics pipelines and compute pipelines. Statistics and internal
+//! representations are only available if the pipeline was built with `capture_statistics()` and
+//! `capture_internal_representations()` respectively, or with
+//! `ComputePipeline::with_executable_info` for a compute pipeline.
+
+use std::error;
+use std::ffi::CStr;
+use std::fmt;
+use std::os::raw::c_char;
+use std::ptr;
+
+use descriptor::descriptor::ShaderStages;
+use device::Device;
+
+use check_errors;
+use vk;
+use Error;
+use OomError;
+use VulkanObject;
+
+/// Properties of one of the executables of a pipeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelineExecutableProperties {
+    /// The shader stages that were compiled into this executable.
+    pub stages: ShaderStages,
+    /// Short name of the executable, chosen by the implementation.
+    pub name: String,
+    /// Description of the executable, chosen by the implementation.
+    pub description: String,
+    /// Subgroup size with which the executable is dispatched, or 0 if not applicable.
+    pub subgroup_size: u32,
+}
+
+/// A statistic about an executable of a pipeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineExecutableStatistic {
+    /// Short name of the statistic.
+    pub name: String,
+    /// Description of the statistic.
+    pub description: String,
+    /// Value of the statistic.
+    pub value: PipelineExecutableStatisticValue,
+}
+
+/// Value of a `PipelineExecutableStatistic`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PipelineExecutableStatisticValue {
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+}
+
+/// An internal representation of an executable of a pipeline, such as its intermediate code or
+/// its generated assembly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelineExecutableInternalRepresentation {
+    /// Short name of the representation.
+    pub name: String,
+    /// Description of the representation.
+    pub description: String,
+    /// True if `data` is text, in which case it ends with a null character.
+    pub is_text: bool,
+    /// The content of the representation.
+    pub data: Vec<u8>,
+}
+
+// Converts a null-terminated string returned by the implementation.
+fn to_string(chars: &[c_char]) -> String {
+    unsafe { CStr::from_ptr(chars.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn check_feature(device: &Device) -> Result<(), PipelineExecutableError> {
+    if !device.enabled_features().pipeline_executable_info {
+        return Err(PipelineExecutableError::FeatureNotEnabled);
+    }
+
+    Ok(())
+}
+
+// Returns the properties of the executables of `pipeline`.
+pub(crate) fn executable_properties(
+    device: &Device,
+    pipeline: vk::Pipeline,
+) -> Result<Vec<PipelineExecutableProperties>, PipelineExecutableError> {
+    check_feature(device)?;
+
+    unsafe {
+        let vk = device.pointers();
+
+        let info = vk::PipelineInfoKHR {
+            sType: vk::STRUCTURE_TYPE_PIPELINE_INFO_KHR,
+            pNext: ptr::null(),
+            pipeline,
+        };
+
+        let mut num = 0;
+        check_errors(vk.GetPipelineExecutablePropertiesKHR(
+            device.internal_object(),
+            &info,
+            &mut num,
+            ptr::null_mut(),
+        ))?;
+
+        let mut properties = (0..num)
+            .map(|_| vk::PipelineExecutablePropertiesKHR {
+                sType: vk::STRUCTURE_TYPE_PIPELINE_EXECUTABLE_PROPERTIES_KHR,
+                pNext: ptr::null_mut(),
+                stages: 0,
+                name: [0; vk::MAX_DESCRIPTION_SIZE as usize],
+                description: [0; vk::MAX_DESCRIPTION_SIZE as usize],
+                subgroupSize: 0,
+            })
+            .collect::<Vec<_>>();
+        check_errors(vk.GetPipelineExecutablePropertiesKHR(
+            device.internal_object(),
+            &info,
+            &mut num,
+            properties.as_mut_ptr(),
+        ))?;
+        properties.truncate(num as usize);
+
+        Ok(properties
+            .into_iter()
+            .map(|p| PipelineExecutableProperties {
+                stages: ShaderStages::from_vulkan_bits(p.stages),
+                name: to_string(&p.name),
+                description: to_string(&p.description),
+                subgroup_size: p.subgroupSize,
+            })
+            .collect())
+    }
+}
+
+// Checks that `executable_index` is the index of one of the executables of `pipeline`.
+fn check_executable_index(
+    device: &Device,
+    pipeline: vk::Pipeline,
+    executable_index: u32,
+) -> Result<(), PipelineExecutableError> {
+    let num = executable_properties(device, pipeline)?.len();
+
+    if executable_index as usize >= num {
+        return Err(PipelineExecutableError::ExecutableIndexOutOfRange {
+            executable_index,
+            num_executables: num as u32,
+        });
+    }
+
+    Ok(())
+}
+
+// Returns the statistics of an executable of `pipeline`.
+pub(crate) fn executable_statistics(
+    device: &Device,
+    pipeline: vk::Pipeline,
+    executable_index: u32,
+) -> Result<Vec<PipelineExecutableStatistic>, PipelineExecutableError> {
+    check_executable_index(device, pipeline, executable_index)?;
+
+    unsafe {
+        let vk = device.pointers();
+
+        let info = vk::PipelineExecutableInfoKHR {
+            sType: vk::STRUCTURE_TYPE_PIPELINE_EXECUTABLE_INFO_KHR,
+            pNext: ptr::null(),
+            pipeline,
+            executableIndex: executable_index,
+        };
+
+        let mut num = 0;
+        check_errors(vk.GetPipelineExecutableStatisticsKHR(
+            device.internal_object(),
+            &info,
+            &mut num,
+            ptr::null_mut(),
+        ))?;
+
+        let mut statistics = (0..num)
+            .map(|_| vk::PipelineExecutableStatisticKHR {
+                sType: vk::STRUCTURE_TYPE_PIPELINE_EXECUTABLE_STATISTIC_KHR,
+                pNext: ptr::null_mut(),
+                name: [0; vk::MAX_DESCRIPTION_SIZE as usize],
+                description: [0; vk::MAX_DESCRIPTION_SIZE as usize],
+                format: 0,
+                value: vk::PipelineExecutableStatisticValueKHR { u64: 0 },
+            })
+            .collect::<Vec<_>>();
+        check_errors(vk.GetPipelineExecutableStatisticsKHR(
+            device.internal_object(),
+            &info,
+            &mut num,
+            statistics.as_mut_ptr(),
+        ))?;
+        statistics.truncate(num as usize);
+
+        // Statistics with a format that vulkano doesn't know about are skipped.
+        Ok(statistics
+            .into_iter()
+            .filter_map(|s| {
+                let value = match s.format {
+                    vk::PIPELINE_EXECUTABLE_STATISTIC_FORMAT_BOOL32_KHR => {
+                        PipelineExecutableStatisticValue::Bool(s.value.b32 != vk::FALSE)
+                    }
+                    vk::PIPELINE_EXECUTABLE_STATISTIC_FORMAT_INT64_KHR => {
+                        PipelineExecutableStatisticValue::I64(s.value.i64)
+                    }
+                    vk::PIPELINE_EXECUTABLE_STATISTIC_FORMAT_UINT64_KHR => {
+                        PipelineExecutableStatisticValue::U64(s.value.u64)
+                    }
+                    vk::PIPELINE_EXECUTABLE_STATISTIC_FORMAT_FLOAT64_KHR => {
+                        PipelineExecutableStatisticValue::F64(s.value.f64)
+                    }
+                    _ => return None,
+                };
+
+                Some(PipelineExecutableStatistic {
+                    name: to_string(&s.name),
+                    description: to_string(&s.description),
+                    value: value,
+                })
+            })
+            .collect())
+    }
+}
+
+// Returns the internal representations of an executable of `pipeline`.
+pub(crate) fn executable_internal_representations(
+    device: &Device,
+    pipeline: vk::Pipeline,
+    executable_index: u32,
+) -> Result<Vec<PipelineExecutableInternalRepresentation>, PipelineExecutableError> {
+    check_executable_index(device, pipeline, executable_index)?;
+
+    unsafe {
+        let vk = device.pointers();
+
+        let info = vk::PipelineExecutableInfoKHR {
+            sType: vk::STRUCTURE_TYPE_PIPELINE_EXECUTABLE_INFO_KHR,
+            pNext: ptr::null(),
+            pipeline,
+            executableIndex: executable_index,
+        };
+
+        let mut num = 0;
+        check_errors(vk.GetPipelineExecutableInternalRepresentationsKHR(
+            device.internal_object(),
+            &info,
+            &mut num,
+            ptr::null_mut(),
+        ))?;
+
+        // The first call with null data pointers returns the size of each representation.
+        let mut representations = (0..num)
+            .map(|_| vk::PipelineExecutableInternalRepresentationKHR {
+                sType: vk::STRUCTURE_TYPE_PIPELINE_EXECUTABLE_INTERNAL_REPRESENTATION_KHR,
+                pNext: ptr::null_mut(),
+                name: [0; vk::MAX_DESCRIPTION_SIZE as usize],
+                description: [0; vk::MAX_DESCRIPTION_SIZE as usize],
+                isText: vk::FALSE,
+                dataSize: 0,
+                pData: ptr::null_mut(),
+            })
+            .collect::<Vec<_>>();
+        check_errors(vk.GetPipelineExecutableInternalRepresentationsKHR(
+            device.internal_object(),
+            &info,
+            &mut num,
+            representations.as_mut_ptr(),
+        ))?;
+        representations.truncate(num as usize);
+
+        let mut data = representations
+            .iter()
+            .map(|r| vec![0u8; r.dataSize])
+            .collect::<Vec<_>>();
+        for (r, d) in representations.iter_mut().zip(data.iter_mut()) {
+            r.pData = d.as_mut_ptr() as *mut _;
+        }
+        check_errors(vk.GetPipelineExecutableInternalRepresentationsKHR(
+            device.internal_object(),
+            &info,
+            &mut num,
+            representations.as_mut_ptr(),
+        ))?;
+
+        Ok(representations
+            .into_iter()
+            .zip(data)
+            .map(|(r, mut d)| {
+                d.truncate(r.dataSize);
+                PipelineExecutableInternalRepresentation {
+                    name: to_string(&r.name),
+                    description: to_string(&r.description),
+                    is_text: r.isText != vk::FALSE,
+                    data: d,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Error that can happen when querying the executables of a pipeline.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PipelineExecutableError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// The `pipeline_executable_info` feature must be enabled.
+    FeatureNotEnabled,
+    /// The pipeline was not built with `capture_statistics()`.
+    StatisticsNotCaptured,
+    /// The pipeline was not built with `capture_internal_representations()`.
+    InternalRepresentationsNotCaptured,
+    /// The executable index is out of range.
+    ExecutableIndexOutOfRange {
+        /// The index that was requested.
+        executable_index: u32,
+        /// The number of executables of the pipeline.
+        num_executables: u32,
+    },
+}
+
+impl error::Error for PipelineExecutableError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            PipelineExecutableError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PipelineExecutableError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                PipelineExecutableError::OomError(_) => "not enough memory available",
+                PipelineExecutableError::FeatureNotEnabled => {
+                    "the `pipeline_executable_info` feature must be enabled"
+                }
+                PipelineExecutableError::StatisticsNotCaptured => {
+                    "the pipeline was not built with `capture_statistics()`"
+                }
+                PipelineExecutableError::InternalRepresentationsNotCaptured => {
+                    "the pipeline was not built with `capture_internal_representations()`"
+                }
+                PipelineExecutableError::ExecutableIndexOutOfRange { .. } => {
+                    "the executable index is out of range"
+                }
+            }
+        )
+    }
+}
+
+impl From<OomError> for PipelineExecutableError {
+    #[inline]
+    fn from(err: OomError) -> PipelineExecutableError {
+        PipelineExecutableError::OomError(err)
+    }
+}
+
+impl From<Error> for PipelineExecutableError {
+    #[inline]
+    fn from(err: Error) -> PipelineExecutableError {
+        match err {
+            err @ Error::OutOfHostMemory => PipelineExecutableError::OomError(OomError::from(err)),
+            err @ Error::OutOfDeviceMemory => {
+                PipelineExecutableError::OomError(OomError::from(err))
+            }
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
@@ -0,0 +1,324 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use crossbeam;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::ptr;
+use std::sync::mpsc;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+
+use device::Device;
+use device::DeviceOwned;
+use pipeline::cache::PipelineCache;
+use pipeline::graphics_pipeline::GraphicsPipelineAbstract;
+use pipeline::graphics_pipeline::GraphicsPipelineCreationError;
+
+use check_errors;
+use vk;
+use VulkanObject;
+
+/// Result of building one of the pipelines of a batch.
+pub type GraphicsPipelineBatchResult =
+    Result<Arc<dyn GraphicsPipelineAbstract + Send + Sync>, GraphicsPipelineCreationError>;
+
+// Function that is called with the create info of a pipeline of the batch.
+type Create<'a> = &'a mut dyn FnMut(
+    &Device,
+    vk::PipelineCache,
+    &vk::GraphicsPipelineCreateInfo,
+) -> Result<vk::Pipeline, GraphicsPipelineCreationError>;
+
+// Checks and prepares the create info of a pipeline of the batch, passes it to the function, then
+// builds the pipeline from the handle that the function returns.
+type Entry = Box<dyn FnOnce(&Arc<Device>, Create) -> GraphicsPipelineBatchResult + Send>;
+
+/// Builds many graphics pipelines at once.
+///
+/// Compiling pipelines is expensive, and Vulkan implementations can compile multiple pipelines in
+/// parallel. The pipelines are added to the batch with `GraphicsPipelineBuilder::add_to_batch`,
+/// then they are all created with a single call to `vkCreateGraphicsPipelines`, which gives the
+/// implementation the opportunity to compile them together. Use `build_async` to create them
+/// without blocking the current thread.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use vulkano::descriptor::pipeline_layout::EmptyPipelineDesc;
+/// # use vulkano::device::Device;
+/// # use vulkano::framebuffer::RenderPassAbstract;
+/// # use vulkano::framebuffer::Subpass;
+/// # use vulkano::pipeline::cache::PipelineCache;
+/// # use vulkano::pipeline::shader::EmptyShaderInterfaceDef;
+/// # use vulkano::pipeline::shader::GraphicsEntryPoint;
+/// # use vulkano::pipeline::GraphicsPipeline;
+/// # use vulkano::pipeline::GraphicsPipelineBatch;
+/// # type EntryPoint = GraphicsEntryPoint<'static, (), EmptyShaderInterfaceDef,
+/// #     EmptyShaderInterfaceDef, EmptyPipelineDesc>;
+/// # let device: Arc<Device> = return;
+/// # let vs: EntryPoint = return;
+/// # let opaque_fs: EntryPoint = return;
+/// # let transparent_fs: EntryPoint = return;
+/// # let subpass: Subpass<Arc<dyn RenderPassAbstract + Send + Sync>> = return;
+/// let cache = PipelineCache::empty(device.clone()).unwrap();
+/// let mut batch = GraphicsPipelineBatch::new(device.clone()).cache(cache);
+///
+/// let opaque = GraphicsPipeline::start()
+///     .vertex_shader(vs, ())
+///     .fragment_shader(opaque_fs, ())
+///     .viewports_dynamic_scissors_irrelevant(1)
+///     .render_pass(subpass.clone())
+///     .add_to_batch(&mut batch);
+/// let transparent = GraphicsPipeline::start()
+///     .vertex_shader(vs, ())
+///     .fragment_shader(transparent_fs, ())
+///     .viewports_dynamic_scissors_irrelevant(1)
+///     .blend_alpha_blending()
+///     .render_pass(subpass)
+///     .add_to_batch(&mut batch);
+///
+/// let mut pipelines = batch.build();
+/// let transparent_pipeline = pipelines.remove(transparent).unwrap();
+/// let opaque_pipeline = pipelines.remove(opaque).unwrap();
+/// ```
+pub struct GraphicsPipelineBatch {
+    device: Arc<Device>,
+    cache: Option<Arc<PipelineCache>>,
+    entries: Vec<Entry>,
+}
+
+impl GraphicsPipelineBatch {
+    /// Builds a new empty batch.
+    #[inline]
+    pub fn new(device: Arc<Device>) -> GraphicsPipelineBatch {
+        GraphicsPipelineBatch {
+            device: device,
+            cache: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Uses `cache` when creating all the pipelines of the batch. The caches passed to the
+    /// builders of the pipelines are ignored.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `cache` doesn't belong to the same device.
+    ///
+    #[inline]
+    pub fn cache(mut self, cache: Arc<PipelineCache>) -> Self {
+        assert_eq!(
+            cache.device().internal_object(),
+            self.device.internal_object()
+        );
+        self.cache = Some(cache);
+        self
+    }
+
+    // Adds a pipeline to the batch. Returns the index of its result in the list returned when
+    // building.
+    pub(super) fn add_entry(&mut self, entry: Entry) -> usize {
+        self.entries.push(entry);
+        self.entries.len() - 1
+    }
+
+    /// Returns the number of pipelines in the batch.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the batch contains no pipeline.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Creates all the pipelines of the batch.
+    ///
+    /// The results are in the same order as the calls to `add_to_batch`. A pipeline whose
+    /// parameters are invalid gets an error without preventing the other pipelines from being
+    /// created. If `vkCreateGraphicsPipelines` itself fails, all the valid pipelines get its
+    /// error.
+    ///
+    /// The parameters of the pipelines are checked and prepared in parallel, one thread per
+    /// pipeline, then the pipelines are all created at once from the calling thread.
+    pub fn build(self) -> Vec<GraphicsPipelineBatchResult> {
+        let GraphicsPipelineBatch {
+            device,
+            cache,
+            entries,
+        } = self;
+        let device = &device;
+        let cache = cache.as_ref().map(|c| c.internal_object()).unwrap_or(0);
+        let num_entries = entries.len();
+
+        let results = crossbeam::thread::scope(|scope| {
+            let (prepared_sender, prepared_receiver) = mpsc::channel();
+
+            let threads = entries
+                .into_iter()
+                .enumerate()
+                .map(|(index, entry)| {
+                    let prepared_sender = prepared_sender.clone();
+                    scope.spawn(move |_| prepare_entry(device, index, entry, prepared_sender))
+                })
+                .collect::<Vec<_>>();
+
+            // Each thread sends exactly one message, either the create info of its pipeline or
+            // nothing if the parameters of the pipeline are invalid.
+            let mut prepared = (0..num_entries)
+                .filter_map(|_| prepared_receiver.recv().unwrap())
+                .collect::<Vec<Prepared>>();
+            prepared.sort_by_key(|p| p.index);
+
+            let infos = prepared
+                .iter()
+                .map(|p| unsafe { ptr::read(&p.info.0) })
+                .collect::<Vec<_>>();
+            let pipelines = unsafe { create_pipelines(device, cache, &infos) };
+            for (info_index, p) in prepared.into_iter().enumerate() {
+                let pipeline = match pipelines {
+                    Ok(ref pipelines) => Ok(pipelines[info_index]),
+                    Err(ref err) => Err(err.clone()),
+                };
+                let _ = p.pipeline_sender.send(pipeline);
+            }
+
+            threads
+                .into_iter()
+                .map(|thread| match thread.join().unwrap() {
+                    Ok(result) => result,
+                    Err(panic) => panic::resume_unwind(panic),
+                })
+                .collect::<Vec<_>>()
+        });
+
+        match results {
+            Ok(results) => results,
+            Err(panic) => panic::resume_unwind(panic),
+        }
+    }
+
+    /// Same as `build`, but creates the pipelines from a new thread instead of blocking the
+    /// calling thread.
+    ///
+    /// The results can be obtained by joining the returned thread.
+    #[inline]
+    pub fn build_async(self) -> JoinHandle<Vec<GraphicsPipelineBatchResult>> {
+        thread::spawn(move || self.build())
+    }
+}
+
+// Create info of a pipeline of the batch, sent by the thread of the pipeline.
+struct Prepared {
+    index: usize,
+    info: PreparedInfo,
+    pipeline_sender: Sender<Result<vk::Pipeline, GraphicsPipelineCreationError>>,
+}
+
+// The create info points to data on the stack of the thread that prepared it. This thread waits
+// for the handle of its pipeline, so the data stays alive until the pipelines are created.
+struct PreparedInfo(vk::GraphicsPipelineCreateInfo);
+
+unsafe impl Send for PreparedInfo {}
+
+// Prepares the create info of an entry and sends it to the thread that creates the pipelines, then
+// finishes building the pipeline once its handle has been sent back.
+//
+// Sends `None` instead if the entry doesn't produce a create info, so that the thread that creates
+// the pipelines doesn't wait for it.
+fn prepare_entry(
+    device: &Arc<Device>,
+    index: usize,
+    entry: Entry,
+    prepared_sender: Sender<Option<Prepared>>,
+) -> thread::Result<GraphicsPipelineBatchResult> {
+    let mut sent = false;
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        entry(device, &mut |_, _, info| {
+            let (pipeline_sender, pipeline_receiver) = mpsc::channel();
+            sent = true;
+            prepared_sender
+                .send(Some(Prepared {
+                    index: index,
+                    // The create info only contains plain data and pointers.
+                    info: PreparedInfo(unsafe { ptr::read(info) }),
+                    pipeline_sender: pipeline_sender,
+                }))
+                .unwrap();
+            pipeline_receiver.recv().unwrap()
+        })
+    }));
+
+    if !sent {
+        let _ = prepared_sender.send(None);
+    }
+
+    result
+}
+
+// Creates the pipelines described by `infos` with a single call to `vkCreateGraphicsPipelines`.
+unsafe fn create_pipelines(
+    device: &Device,
+    cache: vk::PipelineCache,
+    infos: &[vk::GraphicsPipelineCreateInfo],
+) -> Result<Vec<vk::Pipeline>, GraphicsPipelineCreationError> {
+    if infos.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let vk = device.pointers();
+    let mut pipelines = vec![0; infos.len()];
+    let result = check_errors(vk.CreateGraphicsPipelines(
+        device.internal_object(),
+        cache,
+        infos.len() as u32,
+        infos.as_ptr(),
+        ptr::null(),
+        pipelines.as_mut_ptr(),
+    ));
+
+    if let Err(err) = result {
+        // The pipelines that could be created must be destroyed.
+        for &pipeline in pipelines.iter().filter(|&&p| p != vk::NULL_HANDLE) {
+            vk.DestroyPipeline(device.internal_object(), pipeline, ptr::null());
+        }
+        return Err(err.into());
+    }
+
+    Ok(pipelines)
+}
+
+#[cfg(test)]
+mod tests {
+    use pipeline::GraphicsPipelineBatch;
+
+    #[test]
+    fn empty() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let batch = GraphicsPipelineBatch::new(device);
+        assert!(batch.is_empty());
+        assert!(batch.build().is_empty());
+    }
+
+    #[test]
+    fn empty_async() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let batch = GraphicsPipelineBatch::new(device);
+        assert!(batch.build_async().join().unwrap().is_empty());
+    }
+}
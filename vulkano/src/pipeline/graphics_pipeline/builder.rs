@@ -14,36 +14,50 @@
 use smallvec::SmallVec;
 use std::mem;
 use std::mem::MaybeUninit;
+use std::ops::Range;
+use std::os::raw::c_void;
 use std::ptr;
 use std::sync::Arc;
 use std::u32;
 
 use descriptor::pipeline_layout::PipelineLayoutAbstract;
 use device::Device;
+use device::DeviceOwned;
+use device::Features;
 use framebuffer::RenderPassAbstract;
 use framebuffer::Subpass;
 use pipeline::blend::AttachmentBlend;
 use pipeline::blend::AttachmentsBlend;
 use pipeline::blend::Blend;
 use pipeline::blend::LogicOp;
+use pipeline::cache::PipelineCache;
 use pipeline::depth_stencil::Compare;
 use pipeline::depth_stencil::DepthBounds;
 use pipeline::depth_stencil::DepthStencil;
+use pipeline::depth_stencil::Stencil;
 use pipeline::graphics_pipeline::GraphicsPipeline;
+use pipeline::graphics_pipeline::GraphicsPipelineAbstract;
+use pipeline::graphics_pipeline::GraphicsPipelineBatch;
 use pipeline::graphics_pipeline::GraphicsPipelineCreationError;
 use pipeline::graphics_pipeline::Inner as GraphicsPipelineInner;
 use pipeline::input_assembly::PrimitiveTopology;
+use pipeline::raster::ConservativeRasterization;
 use pipeline::raster::CullMode;
+use pipeline::raster::DepthBias;
 use pipeline::raster::DepthBiasControl;
 use pipeline::raster::FrontFace;
+use pipeline::raster::LineRasterizationMode;
+use pipeline::raster::LineStipple;
 use pipeline::raster::PolygonMode;
 use pipeline::raster::Rasterization;
+use pipeline::shader::specialization_info;
 use pipeline::shader::EmptyEntryPointDummy;
 use pipeline::shader::GraphicsEntryPointAbstract;
 use pipeline::shader::GraphicsShaderType;
 use pipeline::shader::ShaderInterfaceDefMatch;
 use pipeline::shader::SpecializationConstants;
 use pipeline::vertex::BufferlessDefinition;
+use pipeline::vertex::InputRate;
 use pipeline::vertex::SingleBufferDefinition;
 use pipeline::vertex::VertexDefinition;
 use pipeline::viewport::Scissor;
@@ -60,7 +74,22 @@ use VulkanObject;
 
 /// Prototype for a `GraphicsPipeline`.
 // TODO: we can optimize this by filling directly the raw vk structs
-pub struct GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp> {
+pub struct GraphicsPipelineBuilder<
+    Vdef,
+    Vs,
+    Vss,
+    Tcs,
+    Tcss,
+    Tes,
+    Tess,
+    Gs,
+    Gss,
+    Ts,
+    Tss,
+    Fs,
+    Fss,
+    Rp,
+> {
     vertex_input: Vdef,
     vertex_shader: Option<(Vs, Vss)>,
     input_assembly: vk::PipelineInputAssemblyStateCreateInfo,
@@ -69,15 +98,51 @@ pub struct GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss,
     input_assembly_topology: PrimitiveTopology,
     tessellation: Option<TessInfo<Tcs, Tcss, Tes, Tess>>,
     geometry_shader: Option<(Gs, Gss)>,
+    task_shader: Option<(Ts, Tss)>,
     viewport: Option<ViewportsState>,
     raster: Rasterization,
     multisample: vk::PipelineMultisampleStateCreateInfo,
+    // Mask of the samples that are written to, one bit per sample. The `pSampleMask` member of
+    // `multisample` is only set to point to this value when building the pipeline.
+    sample_mask: [u32; 2],
+    // True if other pipelines can be created as derivatives of this one.
+    allow_derivatives: bool,
+    // The pipeline this one is a derivative of, if any.
+    base_pipeline: Option<Arc<dyn GraphicsPipelineAbstract + Send + Sync>>,
+    // The pipeline cache to use when building the pipeline, if any.
+    cache: Option<Arc<PipelineCache>>,
+    // True if the statistics of the executables of the pipeline must be captured.
+    capture_statistics: bool,
+    // True if the internal representations of the executables of the pipeline must be captured.
+    capture_internal_representations: bool,
     fragment_shader: Option<(Fs, Fss)>,
     depth_stencil: DepthStencil,
     blend: Blend,
     render_pass: Option<Subpass<Rp>>,
 }
 
+// The raw Vulkan structs of the builder don't point to anything, except for the `pSampleMask`
+// member of `multisample` while the pipeline is being built.
+unsafe impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs, Fss, Rp> Send
+    for GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs, Fss, Rp>
+where
+    Vdef: Send,
+    Vs: Send,
+    Vss: Send,
+    Tcs: Send,
+    Tcss: Send,
+    Tes: Send,
+    Tess: Send,
+    Gs: Send,
+    Gss: Send,
+    Ts: Send,
+    Tss: Send,
+    Fs: Send,
+    Fss: Send,
+    Rp: Send,
+{
+}
+
 // Additional parameters if tessellation is used.
 #[derive(Copy, Clone)]
 struct TessInfo<Tcs, Tcss, Tes, Tess> {
@@ -98,6 +163,8 @@ impl
         (),
         EmptyEntryPointDummy,
         (),
+        EmptyEntryPointDummy,
+        (),
         (),
     >
 {
@@ -115,12 +182,19 @@ impl
                 input_assembly_topology: PrimitiveTopology::TriangleList,
                 tessellation: None,
                 geometry_shader: None,
+                task_shader: None,
                 viewport: None,
                 raster: Default::default(),
                 multisample: vk::PipelineMultisampleStateCreateInfo {
                     sType: vk::STRUCTURE_TYPE_PIPELINE_MULTISAMPLE_STATE_CREATE_INFO,
                     ..mem::zeroed()
                 },
+                sample_mask: [0xffffffff; 2],
+                allow_derivatives: false,
+                base_pipeline: None,
+                cache: None,
+                capture_statistics: false,
+                capture_internal_representations: false,
                 fragment_shader: None,
                 depth_stencil: DepthStencil::disabled(),
                 blend: Blend::pass_through(),
@@ -130,25 +204,28 @@ impl
     }
 }
 
-impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
-    GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
+impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs, Fss, Rp>
+    GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs, Fss, Rp>
 where
     Vdef: VertexDefinition<Vs::InputDefinition>,
     Vs: GraphicsEntryPointAbstract,
     Fs: GraphicsEntryPointAbstract,
     Gs: GraphicsEntryPointAbstract,
+    Ts: GraphicsEntryPointAbstract,
     Tcs: GraphicsEntryPointAbstract,
     Tes: GraphicsEntryPointAbstract,
     Vss: SpecializationConstants,
     Tcss: SpecializationConstants,
     Tess: SpecializationConstants,
     Gss: SpecializationConstants,
+    Tss: SpecializationConstants,
     Fss: SpecializationConstants,
     Vs::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
     Fs::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
     Tcs::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
     Tes::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
     Gs::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
+    Ts::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
     Tcs::InputDefinition: ShaderInterfaceDefMatch<Vs::OutputDefinition>,
     Tes::InputDefinition: ShaderInterfaceDefMatch<Tcs::OutputDefinition>,
     Gs::InputDefinition: ShaderInterfaceDefMatch<Tes::OutputDefinition>
@@ -174,6 +251,7 @@ where
     ///
     /// Configures the inferred layout for each descriptor `(set, binding)` in `dynamic_buffers` to accept dynamic
     /// buffers.
+    #[inline]
     pub fn with_auto_layout(
         self,
         device: Arc<Device>,
@@ -182,6 +260,66 @@ where
         GraphicsPipeline<Vdef, Box<dyn PipelineLayoutAbstract + Send + Sync>, Rp>,
         GraphicsPipelineCreationError,
     > {
+        self.with_auto_layout_impl(device, dynamic_buffers, create_graphics_pipeline)
+    }
+
+    /// Adds the graphics pipeline to `batch`, using an inferred pipeline layout, so that it is
+    /// created together with the other pipelines of the batch.
+    ///
+    /// Returns the index of the result of the pipeline in the list returned by
+    /// `GraphicsPipelineBatch::build`.
+    pub fn add_to_batch(self, batch: &mut GraphicsPipelineBatch) -> usize
+    where
+        Self: Send + 'static,
+        GraphicsPipeline<Vdef, Box<dyn PipelineLayoutAbstract + Send + Sync>, Rp>:
+            GraphicsPipelineAbstract + Send + Sync + 'static,
+    {
+        batch.add_entry(Box::new(move |device, create| {
+            let pipeline = self.with_auto_layout_impl(device.clone(), &[], create)?;
+            Ok(Arc::new(pipeline) as Arc<_>)
+        }))
+    }
+
+    /// Adds the graphics pipeline to `batch`, with the given pipeline layout, so that it is
+    /// created together with the other pipelines of the batch.
+    ///
+    /// Returns the index of the result of the pipeline in the list returned by
+    /// `GraphicsPipelineBatch::build`.
+    pub fn add_to_batch_with_pipeline_layout<Pl>(
+        self,
+        batch: &mut GraphicsPipelineBatch,
+        pipeline_layout: Pl,
+    ) -> usize
+    where
+        Self: Send + 'static,
+        Pl: PipelineLayoutAbstract + Send + 'static,
+        GraphicsPipeline<Vdef, Pl, Rp>: GraphicsPipelineAbstract + Send + Sync + 'static,
+    {
+        batch.add_entry(Box::new(move |device, create| {
+            let pipeline =
+                self.with_pipeline_layout_impl(device.clone(), pipeline_layout, create)?;
+            Ok(Arc::new(pipeline) as Arc<_>)
+        }))
+    }
+
+    // Implementation of `with_auto_layout`. `create` is called to create the pipeline from its
+    // create info and the handle of the pipeline cache of the builder.
+    fn with_auto_layout_impl<C>(
+        self,
+        device: Arc<Device>,
+        dynamic_buffers: &[(usize, usize)],
+        create: C,
+    ) -> Result<
+        GraphicsPipeline<Vdef, Box<dyn PipelineLayoutAbstract + Send + Sync>, Rp>,
+        GraphicsPipelineCreationError,
+    >
+    where
+        C: FnOnce(
+            &Device,
+            vk::PipelineCache,
+            &vk::GraphicsPipelineCreateInfo,
+        ) -> Result<vk::Pipeline, GraphicsPipelineCreationError>,
+    {
         let pipeline_layout;
 
         if let Some(ref tess) = self.tessellation {
@@ -372,24 +510,26 @@ where
                     ));
                 }
 
-                pipeline_layout = Box::new(
-                    PipelineLayoutDescTweaks::new(
-                        self.vertex_shader
-                            .as_ref()
-                            .unwrap()
-                            .0
-                            .layout()
-                            .clone()
-                            .union(self.fragment_shader.as_ref().unwrap().0.layout().clone()),
-                        dynamic_buffers.into_iter().cloned(),
-                    )
-                    .build(device.clone())
-                    .unwrap(),
-                ) as Box<_>; // TODO: error
+                let desc = self
+                    .vertex_shader
+                    .as_ref()
+                    .unwrap()
+                    .0
+                    .layout()
+                    .clone()
+                    .union(self.fragment_shader.as_ref().unwrap().0.layout().clone());
+                pipeline_layout = match self.task_shader {
+                    Some(ref task_shader) => build_pipeline_layout(
+                        device.clone(),
+                        desc.union(task_shader.0.layout().clone()),
+                        dynamic_buffers,
+                    )?,
+                    None => build_pipeline_layout(device.clone(), desc, dynamic_buffers)?,
+                };
             }
         }
 
-        self.with_pipeline_layout(device, pipeline_layout)
+        self.with_pipeline_layout_impl(device, pipeline_layout, create)
     }
 
     /// Builds the graphics pipeline.
@@ -397,13 +537,33 @@ where
     /// Does the same as `build`, except that `build` automatically builds the pipeline layout
     /// object corresponding to the union of your shaders while this function allows you to specify
     /// the pipeline layout.
+    #[inline]
     pub fn with_pipeline_layout<Pl>(
+        self,
+        device: Arc<Device>,
+        pipeline_layout: Pl,
+    ) -> Result<GraphicsPipeline<Vdef, Pl, Rp>, GraphicsPipelineCreationError>
+    where
+        Pl: PipelineLayoutAbstract,
+    {
+        self.with_pipeline_layout_impl(device, pipeline_layout, create_graphics_pipeline)
+    }
+
+    // Implementation of `with_pipeline_layout`. `create` is called to create the pipeline from its
+    // create info and the handle of the pipeline cache of the builder.
+    fn with_pipeline_layout_impl<Pl, C>(
         mut self,
         device: Arc<Device>,
         pipeline_layout: Pl,
+        create: C,
     ) -> Result<GraphicsPipeline<Vdef, Pl, Rp>, GraphicsPipelineCreationError>
     where
         Pl: PipelineLayoutAbstract,
+        C: FnOnce(
+            &Device,
+            vk::PipelineCache,
+            &vk::GraphicsPipelineCreateInfo,
+        ) -> Result<vk::Pipeline, GraphicsPipelineCreationError>,
     {
         // TODO: return errors instead of panicking if missing param
 
@@ -435,6 +595,9 @@ where
                 tess.tessellation_evaluation_shader.0.layout(),
             )?;
         }
+        if let Some(ref task_shader) = self.task_shader {
+            PipelineLayoutSuperset::ensure_superset_of(&pipeline_layout, task_shader.0.layout())?;
+        }
 
         // Check that the subpass can accept the output of the fragment shader.
         if !RenderPassSubpassInterface::is_compatible_with(
@@ -449,70 +612,51 @@ where
         let mut dynamic_states: SmallVec<[vk::DynamicState; 8]> = SmallVec::new();
 
         // Creating the specialization constants of the various stages.
-        let vertex_shader_specialization = {
-            let spec_descriptors = Vss::descriptors();
-            let constants = &self.vertex_shader.as_ref().unwrap().1;
-            vk::SpecializationInfo {
-                mapEntryCount: spec_descriptors.len() as u32,
-                pMapEntries: spec_descriptors.as_ptr() as *const _,
-                dataSize: mem::size_of_val(constants),
-                pData: constants as *const Vss as *const _,
-            }
-        };
-        let tess_shader_specialization = if let Some(ref tess) = self.tessellation {
-            let tcs_spec = {
-                let spec_descriptors = Tcss::descriptors();
-                let constants = &tess.tessellation_control_shader.1;
-                vk::SpecializationInfo {
-                    mapEntryCount: spec_descriptors.len() as u32,
-                    pMapEntries: spec_descriptors.as_ptr() as *const _,
-                    dataSize: mem::size_of_val(constants),
-                    pData: constants as *const Tcss as *const _,
-                }
-            };
-            let tes_spec = {
-                let spec_descriptors = Tess::descriptors();
-                let constants = &tess.tessellation_evaluation_shader.1;
-                vk::SpecializationInfo {
-                    mapEntryCount: spec_descriptors.len() as u32,
-                    pMapEntries: spec_descriptors.as_ptr() as *const _,
-                    dataSize: mem::size_of_val(constants),
-                    pData: constants as *const Tess as *const _,
-                }
-            };
-            Some((tcs_spec, tes_spec))
-        } else {
-            None
-        };
-        let geometry_shader_specialization = if let Some(ref gs) = self.geometry_shader {
-            let spec_descriptors = Gss::descriptors();
-            let constants = &gs.1;
-            Some(vk::SpecializationInfo {
-                mapEntryCount: spec_descriptors.len() as u32,
-                pMapEntries: spec_descriptors.as_ptr() as *const _,
-                dataSize: mem::size_of_val(constants),
-                pData: constants as *const Gss as *const _,
-            })
-        } else {
-            None
-        };
-        let fragment_shader_specialization = {
-            let spec_descriptors = Fss::descriptors();
-            let constants = &self.fragment_shader.as_ref().unwrap().1;
-            vk::SpecializationInfo {
-                mapEntryCount: spec_descriptors.len() as u32,
-                pMapEntries: spec_descriptors.as_ptr() as *const _,
-                dataSize: mem::size_of_val(constants),
-                pData: constants as *const Fss as *const _,
-            }
+        let vertex_shader_specialization =
+            specialization_info(&self.vertex_shader.as_ref().unwrap().1);
+        let tess_shader_specialization = self.tessellation.as_ref().map(|tess| {
+            (
+                specialization_info(&tess.tessellation_control_shader.1),
+                specialization_info(&tess.tessellation_evaluation_shader.1),
+            )
+        });
+        let geometry_shader_specialization = self
+            .geometry_shader
+            .as_ref()
+            .map(|gs| specialization_info(&gs.1));
+        let task_shader_specialization = self
+            .task_shader
+            .as_ref()
+            .map(|ts| specialization_info(&ts.1));
+        let fragment_shader_specialization =
+            specialization_info(&self.fragment_shader.as_ref().unwrap().1);
+
+        // True if the primitives are generated by a mesh shader instead of the vertex input and
+        // input assembly stages.
+        let mesh_pipeline = match self.vertex_shader.as_ref().unwrap().0.ty() {
+            GraphicsShaderType::Mesh => true,
+            _ => false,
         };
 
         // List of shader stages.
         let stages = {
             let mut stages = SmallVec::<[_; 5]>::new();
 
-            match self.vertex_shader.as_ref().unwrap().0.ty() {
-                GraphicsShaderType::Vertex => {}
+            let first_stage = match self.vertex_shader.as_ref().unwrap().0.ty() {
+                GraphicsShaderType::Vertex => vk::SHADER_STAGE_VERTEX_BIT,
+                GraphicsShaderType::Mesh => {
+                    if !device.enabled_features().mesh_shader {
+                        return Err(GraphicsPipelineCreationError::MeshShaderFeatureNotEnabled);
+                    }
+
+                    if self.tessellation.is_some() || self.geometry_shader.is_some() {
+                        return Err(
+                            GraphicsPipelineCreationError::MeshShaderWithVertexPipelineStages,
+                        );
+                    }
+
+                    vk::SHADER_STAGE_MESH_BIT_EXT
+                }
                 _ => return Err(GraphicsPipelineCreationError::WrongShaderType),
             };
 
@@ -520,7 +664,7 @@ where
                 sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
                 pNext: ptr::null(),
                 flags: 0, // reserved
-                stage: vk::SHADER_STAGE_VERTEX_BIT,
+                stage: first_stage,
                 module: self
                     .vertex_shader
                     .as_ref()
@@ -553,6 +697,31 @@ where
                 pSpecializationInfo: &fragment_shader_specialization as *const _,
             });
 
+            if let Some(ref ts) = self.task_shader {
+                if first_stage != vk::SHADER_STAGE_MESH_BIT_EXT {
+                    return Err(GraphicsPipelineCreationError::TaskShaderWithoutMeshShader);
+                }
+
+                if !device.enabled_features().task_shader {
+                    return Err(GraphicsPipelineCreationError::TaskShaderFeatureNotEnabled);
+                }
+
+                match ts.0.ty() {
+                    GraphicsShaderType::Task => {}
+                    _ => return Err(GraphicsPipelineCreationError::WrongShaderType),
+                };
+
+                stages.push(vk::PipelineShaderStageCreateInfo {
+                    sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
+                    pNext: ptr::null(),
+                    flags: 0, // reserved
+                    stage: vk::SHADER_STAGE_TASK_BIT_EXT,
+                    module: ts.0.module().internal_object(),
+                    pName: ts.0.name().as_ptr(),
+                    pSpecializationInfo: task_shader_specialization.as_ref().unwrap() as *const _,
+                });
+            }
+
             if let Some(ref gs) = self.geometry_shader {
                 if !device.enabled_features().geometry_shader {
                     return Err(GraphicsPipelineCreationError::GeometryShaderFeatureNotEnabled);
@@ -571,8 +740,6 @@ where
             }
 
             if let Some(ref tess) = self.tessellation {
-                // FIXME: must check that the control shader and evaluation shader are compatible
-
                 if !device.enabled_features().tessellation_shader {
                     return Err(GraphicsPipelineCreationError::TessellationShaderFeatureNotEnabled);
                 }
@@ -622,12 +789,14 @@ where
         };
 
         // Vertex bindings.
-        let (binding_descriptions, attribute_descriptions) = {
+        let (vertex_input_bindings, binding_descriptions, binding_divisors, attribute_descriptions) = {
             let (buffers_iter, attribs_iter) = self
                 .vertex_input
                 .definition(self.vertex_shader.as_ref().unwrap().0.input())?;
 
+            let mut vertex_input_bindings = Vec::with_capacity(buffers_iter.len());
             let mut binding_descriptions = SmallVec::<[_; 8]>::new();
+            let mut binding_divisors = SmallVec::<[_; 8]>::new();
             for (num, stride, rate) in buffers_iter {
                 if stride
                     > device
@@ -648,10 +817,28 @@ where
                     );
                 }
 
+                match rate {
+                    InputRate::Instance { divisor } if divisor != 1 => {
+                        check_instance_rate_divisor(
+                            num as usize,
+                            divisor,
+                            device.enabled_features(),
+                            max_vertex_attrib_divisor(&device),
+                        )?;
+
+                        binding_divisors.push(vk::VertexInputBindingDivisorDescriptionEXT {
+                            binding: num as u32,
+                            divisor,
+                        });
+                    }
+                    _ => (),
+                }
+
+                vertex_input_bindings.push((num, stride, rate));
                 binding_descriptions.push(vk::VertexInputBindingDescription {
                     binding: num as u32,
                     stride: stride as u32,
-                    inputRate: rate as u32,
+                    inputRate: rate.into(),
                 });
             }
 
@@ -690,7 +877,12 @@ where
                 });
             }
 
-            (binding_descriptions, attribute_descriptions)
+            (
+                vertex_input_bindings,
+                binding_descriptions,
+                binding_divisors,
+                attribute_descriptions,
+            )
         };
 
         if binding_descriptions.len()
@@ -727,9 +919,23 @@ where
             );
         }
 
+        let divisor_state = if !binding_divisors.is_empty() {
+            Some(vk::PipelineVertexInputDivisorStateCreateInfoEXT {
+                sType: vk::STRUCTURE_TYPE_PIPELINE_VERTEX_INPUT_DIVISOR_STATE_CREATE_INFO_EXT,
+                pNext: ptr::null(),
+                vertexBindingDivisorCount: binding_divisors.len() as u32,
+                pVertexBindingDivisors: binding_divisors.as_ptr(),
+            })
+        } else {
+            None
+        };
+
         let vertex_input_state = vk::PipelineVertexInputStateCreateInfo {
             sType: vk::STRUCTURE_TYPE_PIPELINE_VERTEX_INPUT_STATE_CREATE_INFO,
-            pNext: ptr::null(),
+            pNext: divisor_state
+                .as_ref()
+                .map(|s| s as *const _ as *const _)
+                .unwrap_or(ptr::null()),
             flags: 0, // reserved
             vertexBindingDescriptionCount: binding_descriptions.len() as u32,
             pVertexBindingDescriptions: binding_descriptions.as_ptr(),
@@ -737,7 +943,8 @@ where
             pVertexAttributeDescriptions: attribute_descriptions.as_ptr(),
         };
 
-        if self.input_assembly.primitiveRestartEnable != vk::FALSE
+        if !mesh_pipeline
+            && self.input_assembly.primitiveRestartEnable != vk::FALSE
             && !self.input_assembly_topology.supports_primitive_restart()
         {
             return Err(
@@ -747,11 +954,19 @@ where
             );
         }
 
-        // TODO: should check from the tess eval shader instead of the input assembly
         if let Some(ref gs) = self.geometry_shader {
             match gs.0.ty() {
                 GraphicsShaderType::Geometry(primitives) => {
-                    if !primitives.matches(self.input_assembly_topology) {
+                    // With tessellation, the geometry shader receives the primitives generated
+                    // by the tessellation evaluation shader, which never have adjacency.
+                    // TODO: should check the exact primitive from the tess eval shader
+                    let matches = if self.tessellation.is_some() {
+                        !primitives.has_adjacency()
+                    } else {
+                        primitives.matches(self.input_assembly_topology)
+                    };
+
+                    if !matches {
                         return Err(
                             GraphicsPipelineCreationError::TopologyNotMatchingGeometryShader,
                         );
@@ -766,6 +981,9 @@ where
                 if self.tessellation.is_none() {
                     return Err(GraphicsPipelineCreationError::InvalidPrimitiveTopology);
                 }
+                if vertices_per_patch == 0 {
+                    return Err(GraphicsPipelineCreationError::InvalidPatchControlPoints);
+                }
                 if vertices_per_patch
                     > device
                         .physical_device()
@@ -826,6 +1044,10 @@ where
             }
         };
 
+        if vp_num == 0 {
+            return Err(GraphicsPipelineCreationError::NoViewports);
+        }
+
         if vp_num > 1 && !device.enabled_features().multi_viewport {
             return Err(GraphicsPipelineCreationError::MultiViewportFeatureNotEnabled);
         }
@@ -838,8 +1060,17 @@ where
         }
 
         for vp in vp_vp.iter() {
+            // A negative height flips the viewport vertically, which is allowed by
+            // `VK_KHR_maintenance1`.
+            if vp.height < 0.0 && !device.loaded_extensions().khr_maintenance1 {
+                return Err(
+                    GraphicsPipelineCreationError::NegativeViewportHeightWithoutMaintenance1,
+                );
+            }
+
             if vp.width > device.physical_device().limits().max_viewport_dimensions()[0] as f32
-                || vp.height > device.physical_device().limits().max_viewport_dimensions()[1] as f32
+                || vp.height.abs()
+                    > device.physical_device().limits().max_viewport_dimensions()[1] as f32
             {
                 return Err(GraphicsPipelineCreationError::MaxViewportDimensionsExceeded);
             }
@@ -847,6 +1078,8 @@ where
             if vp.x < device.physical_device().limits().viewport_bounds_range()[0]
                 || vp.x + vp.width > device.physical_device().limits().viewport_bounds_range()[1]
                 || vp.y < device.physical_device().limits().viewport_bounds_range()[0]
+                || vp.y > device.physical_device().limits().viewport_bounds_range()[1]
+                || vp.y + vp.height < device.physical_device().limits().viewport_bounds_range()[0]
                 || vp.y + vp.height > device.physical_device().limits().viewport_bounds_range()[1]
             {
                 return Err(GraphicsPipelineCreationError::ViewportBoundsExceeded);
@@ -909,9 +1142,94 @@ where
             return Err(GraphicsPipelineCreationError::FillModeNonSolidFeatureNotEnabled);
         }
 
+        let mut rasterization_next: *const c_void = ptr::null();
+
+        let conservative_state = if self.raster.conservative != ConservativeRasterization::Disabled
+        {
+            if !device.loaded_extensions().ext_conservative_rasterization {
+                return Err(
+                    GraphicsPipelineCreationError::ConservativeRasterizationExtensionNotEnabled,
+                );
+            }
+
+            let (max_extra_size, underestimation) = conservative_rasterization_properties(&device);
+            check_conservative_rasterization(
+                self.raster.conservative,
+                max_extra_size,
+                underestimation,
+            )?;
+
+            let (mode, extra_size) = self.raster.conservative.into_vulkan();
+            Some(vk::PipelineRasterizationConservativeStateCreateInfoEXT {
+                sType: vk::STRUCTURE_TYPE_PIPELINE_RASTERIZATION_CONSERVATIVE_STATE_CREATE_INFO_EXT,
+                pNext: rasterization_next,
+                flags: 0, // reserved
+                conservativeRasterizationMode: mode,
+                extraPrimitiveOverestimationSize: extra_size,
+            })
+        } else {
+            None
+        };
+        if let Some(ref state) = conservative_state {
+            rasterization_next = state as *const _ as *const _;
+        }
+
+        let line_state = if self.raster.line_rasterization_mode != LineRasterizationMode::Default
+            || self.raster.line_stipple.is_some()
+        {
+            if !device.loaded_extensions().ext_line_rasterization {
+                return Err(GraphicsPipelineCreationError::LineRasterizationExtensionNotEnabled);
+            }
+
+            let features = device.enabled_features();
+            let (mode_supported, stipple_supported) = match self.raster.line_rasterization_mode {
+                LineRasterizationMode::Default => (true, features.stippled_rectangular_lines),
+                LineRasterizationMode::Rectangular => (
+                    features.rectangular_lines,
+                    features.stippled_rectangular_lines,
+                ),
+                LineRasterizationMode::Bresenham => {
+                    (features.bresenham_lines, features.stippled_bresenham_lines)
+                }
+                LineRasterizationMode::RectangularSmooth => {
+                    (features.smooth_lines, features.stippled_smooth_lines)
+                }
+            };
+
+            if !mode_supported {
+                return Err(GraphicsPipelineCreationError::LineRasterizationModeFeatureNotEnabled);
+            }
+
+            if self.raster.line_stipple.is_some() && !stipple_supported {
+                return Err(GraphicsPipelineCreationError::StippledLineFeatureNotEnabled);
+            }
+
+            let stipple = self.raster.line_stipple.unwrap_or(LineStipple {
+                factor: 1,
+                pattern: 0xffff,
+            });
+            Some(vk::PipelineRasterizationLineStateCreateInfoEXT {
+                sType: vk::STRUCTURE_TYPE_PIPELINE_RASTERIZATION_LINE_STATE_CREATE_INFO_EXT,
+                pNext: rasterization_next,
+                lineRasterizationMode: self.raster.line_rasterization_mode as u32,
+                stippledLineEnable: if self.raster.line_stipple.is_some() {
+                    vk::TRUE
+                } else {
+                    vk::FALSE
+                },
+                lineStippleFactor: stipple.factor,
+                lineStipplePattern: stipple.pattern,
+            })
+        } else {
+            None
+        };
+        if let Some(ref state) = line_state {
+            rasterization_next = state as *const _ as *const _;
+        }
+
         let rasterization = vk::PipelineRasterizationStateCreateInfo {
             sType: vk::STRUCTURE_TYPE_PIPELINE_RASTERIZATION_STATE_CREATE_INFO,
-            pNext: ptr::null(),
+            pNext: rasterization_next,
             flags: 0, // reserved
             depthClampEnable: if self.raster.depth_clamp {
                 vk::TRUE
@@ -953,6 +1271,11 @@ where
                 return Err(GraphicsPipelineCreationError::AlphaToOneFeatureNotEnabled);
             }
         }
+        self.multisample.pSampleMask = if self.sample_mask != [0xffffffff; 2] {
+            self.sample_mask.as_ptr()
+        } else {
+            ptr::null()
+        };
 
         let depth_stencil = {
             let db = match self.depth_stencil.depth_bounds_test {
@@ -962,6 +1285,11 @@ where
                         return Err(GraphicsPipelineCreationError::DepthBoundsFeatureNotEnabled);
                     }
 
+                    if range.start < 0.0 || range.start > 1.0 || range.end < 0.0 || range.end > 1.0
+                    {
+                        return Err(GraphicsPipelineCreationError::DepthBoundsOutOfRange);
+                    }
+
                     (vk::TRUE, range.start, range.end)
                 }
                 DepthBounds::Dynamic => {
@@ -1093,6 +1421,14 @@ where
             }
         };
 
+        let dual_source = match self.blend.attachments {
+            AttachmentsBlend::Collective(ref blend) => blend.uses_dual_source(),
+            AttachmentsBlend::Individual(ref blend) => blend.iter().any(|b| b.uses_dual_source()),
+        };
+        if dual_source && !device.enabled_features().dual_src_blend {
+            return Err(GraphicsPipelineCreationError::DualSrcBlendFeatureNotEnabled);
+        }
+
         let blend_atch: SmallVec<[vk::PipelineColorBlendAttachmentState; 8]> = {
             let num_atch = self.render_pass.as_ref().unwrap().num_color_attachments();
 
@@ -1107,7 +1443,10 @@ where
                         );
                     }
 
-                    if !device.enabled_features().independent_blend {
+                    // Identical blend states for all the attachments don't need the feature.
+                    if blend.windows(2).any(|w| w[0] != w[1])
+                        && !device.enabled_features().independent_blend
+                    {
                         return Err(
                             GraphicsPipelineCreationError::IndependentBlendFeatureNotEnabled,
                         );
@@ -1156,15 +1495,54 @@ where
             None
         };
 
-        let pipeline = unsafe {
+        // TODO: the other flags are not critical
+        let mut flags = 0;
+        if self.allow_derivatives {
+            flags |= vk::PIPELINE_CREATE_ALLOW_DERIVATIVES_BIT;
+        }
+        if let Some(ref base) = self.base_pipeline {
+            assert_eq!(base.device().internal_object(), device.internal_object());
+            flags |= vk::PIPELINE_CREATE_DERIVATIVE_BIT;
+        }
+
+        if self.capture_statistics || self.capture_internal_representations {
+            if !device.enabled_features().pipeline_executable_info {
+                return Err(GraphicsPipelineCreationError::PipelineExecutableInfoFeatureNotEnabled);
+            }
+        }
+        if self.capture_statistics {
+            flags |= vk::PIPELINE_CREATE_CAPTURE_STATISTICS_BIT_KHR;
+        }
+        if self.capture_internal_representations {
+            flags |= vk::PIPELINE_CREATE_CAPTURE_INTERNAL_REPRESENTATIONS_BIT_KHR;
+        }
+
+        let cache_handle = match self.cache {
+            Some(ref cache) => {
+                assert_eq!(cache.device().internal_object(), device.internal_object());
+                cache.internal_object()
+            }
+            None => 0,
+        };
+
+        let pipeline = {
             let infos = vk::GraphicsPipelineCreateInfo {
                 sType: vk::STRUCTURE_TYPE_GRAPHICS_PIPELINE_CREATE_INFO,
                 pNext: ptr::null(),
-                flags: 0, // TODO: some flags are available but none are critical
+                flags,
                 stageCount: stages.len() as u32,
                 pStages: stages.as_ptr(),
-                pVertexInputState: &vertex_input_state,
-                pInputAssemblyState: &self.input_assembly,
+                // Mesh pipelines have no vertex input and input assembly stages.
+                pVertexInputState: if mesh_pipeline {
+                    ptr::null()
+                } else {
+                    &vertex_input_state
+                },
+                pInputAssemblyState: if mesh_pipeline {
+                    ptr::null()
+                } else {
+                    &self.input_assembly
+                },
                 pTessellationState: tessellation
                     .as_ref()
                     .map(|t| t as *const _)
@@ -1187,20 +1565,15 @@ where
                     .inner()
                     .internal_object(),
                 subpass: self.render_pass.as_ref().unwrap().index(),
-                basePipelineHandle: 0, // TODO:
-                basePipelineIndex: -1, // TODO:
+                basePipelineHandle: self
+                    .base_pipeline
+                    .as_ref()
+                    .map(|p| GraphicsPipelineAbstract::inner(&**p).internal_object())
+                    .unwrap_or(0),
+                basePipelineIndex: -1,
             };
 
-            let mut output = MaybeUninit::uninit();
-            check_errors(vk.CreateGraphicsPipelines(
-                device.internal_object(),
-                0,
-                1,
-                &infos,
-                ptr::null(),
-                output.as_mut_ptr(),
-            ))?;
-            output.assume_init()
+            create(&device, cache_handle, &infos)?
         };
 
         // Some drivers return `VK_SUCCESS` but provide a null handle if they
@@ -1220,6 +1593,7 @@ where
             layout: pipeline_layout,
 
             vertex_definition: self.vertex_input,
+            vertex_input_bindings: vertex_input_bindings,
 
             render_pass: render_pass,
             render_pass_subpass: render_pass_subpass,
@@ -1233,16 +1607,177 @@ where
             dynamic_stencil_write_mask: self.depth_stencil.stencil_back.write_mask.is_none(),
             dynamic_stencil_reference: self.depth_stencil.stencil_back.reference.is_none(),
             dynamic_blend_constants: self.blend.blend_constants.is_none(),
+            allow_derivatives: self.allow_derivatives,
+            capture_statistics: self.capture_statistics,
+            capture_internal_representations: self.capture_internal_representations,
+            mesh_shader: mesh_pipeline,
+            task_shader: self.task_shader.is_some(),
 
             num_viewports: self.viewport.as_ref().unwrap().num_viewports(),
         })
     }
+}
+
+// Checks whether the instance rate divisor of a binding, different from 1, is supported.
+fn check_instance_rate_divisor(
+    binding: usize,
+    divisor: u32,
+    features: &Features,
+    max_divisor: u32,
+) -> Result<(), GraphicsPipelineCreationError> {
+    if !features.vertex_attribute_instance_rate_divisor {
+        return Err(GraphicsPipelineCreationError::InstanceRateDivisorFeatureNotEnabled);
+    }
+
+    if divisor == 0 && !features.vertex_attribute_instance_rate_zero_divisor {
+        return Err(GraphicsPipelineCreationError::InstanceRateZeroDivisorFeatureNotEnabled);
+    }
+
+    if divisor > max_divisor {
+        return Err(
+            GraphicsPipelineCreationError::MaxVertexAttribDivisorExceeded {
+                binding: binding,
+                max: max_divisor,
+                obtained: divisor,
+            },
+        );
+    }
+
+    Ok(())
+}
 
-    // TODO: add build_with_cache method
+// Checks whether the conservative rasterization mode is supported, given the
+// `maxExtraPrimitiveOverestimationSize` and `primitiveUnderestimation` properties of the device.
+fn check_conservative_rasterization(
+    mode: ConservativeRasterization,
+    max_extra_size: f32,
+    underestimation: bool,
+) -> Result<(), GraphicsPipelineCreationError> {
+    match mode {
+        ConservativeRasterization::Disabled => Ok(()),
+        ConservativeRasterization::Overestimate { extra_size } => {
+            if !(extra_size >= 0.0 && extra_size <= max_extra_size) {
+                return Err(
+                    GraphicsPipelineCreationError::MaxExtraPrimitiveOverestimationSizeExceeded,
+                );
+            }
+            Ok(())
+        }
+        ConservativeRasterization::Underestimate => {
+            if !underestimation {
+                return Err(GraphicsPipelineCreationError::PrimitiveUnderestimationNotSupported);
+            }
+            Ok(())
+        }
+    }
 }
 
-impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
-    GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
+// Queries the `maxExtraPrimitiveOverestimationSize` and `primitiveUnderestimation` properties of
+// the physical device of `device`.
+fn conservative_rasterization_properties(device: &Device) -> (f32, bool) {
+    unsafe {
+        let vk_i = device.instance().pointers();
+
+        let mut conservative_properties =
+            vk::PhysicalDeviceConservativeRasterizationPropertiesEXT {
+                sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_CONSERVATIVE_RASTERIZATION_PROPERTIES_EXT,
+                pNext: ptr::null_mut(),
+                primitiveOverestimationSize: 0.0,
+                maxExtraPrimitiveOverestimationSize: 0.0,
+                extraPrimitiveOverestimationSizeGranularity: 0.0,
+                primitiveUnderestimation: vk::FALSE,
+                conservativePointAndLineRasterization: vk::FALSE,
+                degenerateTrianglesRasterized: vk::FALSE,
+                degenerateLinesRasterized: vk::FALSE,
+                fullyCoveredFragmentShaderInputVariable: vk::FALSE,
+                conservativeRasterizationPostDepthCoverage: vk::FALSE,
+            };
+
+        let mut output = vk::PhysicalDeviceProperties2KHR {
+            sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_PROPERTIES_2_KHR,
+            pNext: &mut conservative_properties as *mut _ as *mut _,
+            properties: mem::zeroed(),
+        };
+
+        vk_i.GetPhysicalDeviceProperties2KHR(
+            device.physical_device().internal_object(),
+            &mut output,
+        );
+
+        (
+            conservative_properties.maxExtraPrimitiveOverestimationSize,
+            conservative_properties.primitiveUnderestimation != 0,
+        )
+    }
+}
+
+// Queries the `maxVertexAttribDivisor` limit of the physical device of `device`.
+fn max_vertex_attrib_divisor(device: &Device) -> u32 {
+    if !device.loaded_extensions().ext_vertex_attribute_divisor {
+        return 0;
+    }
+
+    unsafe {
+        let vk_i = device.instance().pointers();
+
+        let mut divisor_properties = vk::PhysicalDeviceVertexAttributeDivisorPropertiesEXT {
+            sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_PROPERTIES_EXT,
+            pNext: ptr::null_mut(),
+            maxVertexAttribDivisor: 0,
+        };
+
+        let mut output = vk::PhysicalDeviceProperties2KHR {
+            sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_PROPERTIES_2_KHR,
+            pNext: &mut divisor_properties as *mut _ as *mut _,
+            properties: mem::zeroed(),
+        };
+
+        vk_i.GetPhysicalDeviceProperties2KHR(
+            device.physical_device().internal_object(),
+            &mut output,
+        );
+
+        divisor_properties.maxVertexAttribDivisor
+    }
+}
+
+// Builds the pipeline layout described by `desc`, with the given dynamic buffers.
+fn build_pipeline_layout<L>(
+    device: Arc<Device>,
+    desc: L,
+    dynamic_buffers: &[(usize, usize)],
+) -> Result<Box<dyn PipelineLayoutAbstract + Send + Sync>, GraphicsPipelineCreationError>
+where
+    L: PipelineLayoutDesc + Send + Sync + 'static,
+{
+    let layout =
+        PipelineLayoutDescTweaks::new(desc, dynamic_buffers.iter().cloned()).build(device)?;
+    Ok(Box::new(layout))
+}
+
+// Creates a single graphics pipeline.
+fn create_graphics_pipeline(
+    device: &Device,
+    cache: vk::PipelineCache,
+    infos: &vk::GraphicsPipelineCreateInfo,
+) -> Result<vk::Pipeline, GraphicsPipelineCreationError> {
+    unsafe {
+        let vk = device.pointers();
+        let mut output = MaybeUninit::uninit();
+        check_errors(vk.CreateGraphicsPipelines(
+            device.internal_object(),
+            cache,
+            1,
+            infos,
+            ptr::null(),
+            output.as_mut_ptr(),
+        ))?;
+        Ok(output.assume_init())
+    }
+}
+
+impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs, Fss, Rp>
+    GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs, Fss, Rp>
 {
     // TODO: add pipeline derivate system
 
@@ -1251,7 +1786,8 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     pub fn vertex_input<T>(
         self,
         vertex_input: T,
-    ) -> GraphicsPipelineBuilder<T, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp> {
+    ) -> GraphicsPipelineBuilder<T, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs, Fss, Rp>
+    {
         GraphicsPipelineBuilder {
             vertex_input: vertex_input,
             vertex_shader: self.vertex_shader,
@@ -1259,9 +1795,16 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             input_assembly_topology: self.input_assembly_topology,
             tessellation: self.tessellation,
             geometry_shader: self.geometry_shader,
+            task_shader: self.task_shader,
             viewport: self.viewport,
             raster: self.raster,
             multisample: self.multisample,
+            sample_mask: self.sample_mask,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
+            cache: self.cache,
+            capture_statistics: self.capture_statistics,
+            capture_internal_representations: self.capture_internal_representations,
             fragment_shader: self.fragment_shader,
             depth_stencil: self.depth_stencil,
             blend: self.blend,
@@ -1286,6 +1829,8 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         Tess,
         Gs,
         Gss,
+        Ts,
+        Tss,
         Fs,
         Fss,
         Rp,
@@ -1294,13 +1839,18 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     }
 
     /// Sets the vertex shader to use.
-    // TODO: correct specialization constants
+    ///
+    /// The `specialization_constants` are applied to the shader when the pipeline is built. For
+    /// shaders generated with `vulkano_shaders::shader!`, passing `Default::default()` uses the
+    /// default values declared in the shader. Building the pipeline panics if the
+    /// `SpecializationConstants` implementation declares the same constant twice or declares a
+    /// constant out of the range of the struct.
     #[inline]
     pub fn vertex_shader<Vs2, Vss2>(
         self,
         shader: Vs2,
         specialization_constants: Vss2,
-    ) -> GraphicsPipelineBuilder<Vdef, Vs2, Vss2, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
+    ) -> GraphicsPipelineBuilder<Vdef, Vs2, Vss2, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs, Fss, Rp>
     where
         Vs2: GraphicsEntryPointAbstract<SpecializationConstants = Vss2>,
         Vss2: SpecializationConstants,
@@ -1312,9 +1862,74 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             input_assembly_topology: self.input_assembly_topology,
             tessellation: self.tessellation,
             geometry_shader: self.geometry_shader,
+            task_shader: self.task_shader,
             viewport: self.viewport,
             raster: self.raster,
             multisample: self.multisample,
+            sample_mask: self.sample_mask,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
+            cache: self.cache,
+            capture_statistics: self.capture_statistics,
+            capture_internal_representations: self.capture_internal_representations,
+            fragment_shader: self.fragment_shader,
+            depth_stencil: self.depth_stencil,
+            blend: self.blend,
+            render_pass: self.render_pass,
+        }
+    }
+
+    /// Sets the mesh shader to use, instead of a vertex shader.
+    ///
+    /// A pipeline with a mesh shader has no vertex input and input assembly stages: the vertex
+    /// input and the primitive topology of the builder are ignored, and the pipeline can't have
+    /// tessellation or geometry shaders. Such a pipeline is drawn with the `draw_mesh_tasks`
+    /// commands.
+    ///
+    /// Requires the `mesh_shader` feature.
+    #[inline]
+    pub fn mesh_shader<Ms2, Mss2>(
+        self,
+        shader: Ms2,
+        specialization_constants: Mss2,
+    ) -> GraphicsPipelineBuilder<Vdef, Ms2, Mss2, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs, Fss, Rp>
+    where
+        Ms2: GraphicsEntryPointAbstract<SpecializationConstants = Mss2>,
+        Mss2: SpecializationConstants,
+    {
+        self.vertex_shader(shader, specialization_constants)
+    }
+
+    /// Sets the task shader to use. Can only be used together with a mesh shader.
+    ///
+    /// Requires the `task_shader` feature.
+    #[inline]
+    pub fn task_shader<Ts2, Tss2>(
+        self,
+        shader: Ts2,
+        specialization_constants: Tss2,
+    ) -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts2, Tss2, Fs, Fss, Rp>
+    where
+        Ts2: GraphicsEntryPointAbstract<SpecializationConstants = Tss2>,
+        Tss2: SpecializationConstants,
+    {
+        GraphicsPipelineBuilder {
+            vertex_input: self.vertex_input,
+            vertex_shader: self.vertex_shader,
+            input_assembly: self.input_assembly,
+            input_assembly_topology: self.input_assembly_topology,
+            tessellation: self.tessellation,
+            geometry_shader: self.geometry_shader,
+            task_shader: Some((shader, specialization_constants)),
+            viewport: self.viewport,
+            raster: self.raster,
+            multisample: self.multisample,
+            sample_mask: self.sample_mask,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
+            cache: self.cache,
+            capture_statistics: self.capture_statistics,
+            capture_internal_representations: self.capture_internal_representations,
             fragment_shader: self.fragment_shader,
             depth_stencil: self.depth_stencil,
             blend: self.blend,
@@ -1431,6 +2046,9 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     /// Sets the topology of the primitives to a list of patches. Can only be used and must be used
     /// with a tessellation shader.
     ///
+    /// `vertices_per_patch` must be at least 1, and at most the `max_tessellation_patch_size`
+    /// limit of the device.
+    ///
     /// > **Note**: This is equivalent to
     /// > `self.primitive_topology(PrimitiveTopology::PatchList { vertices_per_patch })`.
     #[inline]
@@ -1439,7 +2057,8 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     }
 
     /// Sets the tessellation shaders to use.
-    // TODO: correct specialization constants
+    ///
+    /// Each shader is given its own specialization constants, just like in `vertex_shader`.
     #[inline]
     pub fn tessellation_shaders<Tcs2, Tcss2, Tes2, Tess2>(
         self,
@@ -1447,7 +2066,22 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         tessellation_control_shader_spec_constants: Tcss2,
         tessellation_evaluation_shader: Tes2,
         tessellation_evaluation_shader_spec_constants: Tess2,
-    ) -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs2, Tcss2, Tes2, Tess2, Gs, Gss, Fs, Fss, Rp>
+    ) -> GraphicsPipelineBuilder<
+        Vdef,
+        Vs,
+        Vss,
+        Tcs2,
+        Tcss2,
+        Tes2,
+        Tess2,
+        Gs,
+        Gss,
+        Ts,
+        Tss,
+        Fs,
+        Fss,
+        Rp,
+    >
     where
         Tcs2: GraphicsEntryPointAbstract<SpecializationConstants = Tcss2>,
         Tes2: GraphicsEntryPointAbstract<SpecializationConstants = Tess2>,
@@ -1470,9 +2104,16 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                 ),
             }),
             geometry_shader: self.geometry_shader,
+            task_shader: self.task_shader,
             viewport: self.viewport,
             raster: self.raster,
             multisample: self.multisample,
+            sample_mask: self.sample_mask,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
+            cache: self.cache,
+            capture_statistics: self.capture_statistics,
+            capture_internal_representations: self.capture_internal_representations,
             fragment_shader: self.fragment_shader,
             depth_stencil: self.depth_stencil,
             blend: self.blend,
@@ -1488,13 +2129,14 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     }
 
     /// Sets the geometry shader to use.
-    // TODO: correct specialization constants
+    ///
+    /// See `vertex_shader` for the `specialization_constants` parameter.
     #[inline]
     pub fn geometry_shader<Gs2, Gss2>(
         self,
         shader: Gs2,
         specialization_constants: Gss2,
-    ) -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs2, Gss2, Fs, Fss, Rp>
+    ) -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs2, Gss2, Ts, Tss, Fs, Fss, Rp>
     where
         Gs2: GraphicsEntryPointAbstract<SpecializationConstants = Gss2>,
         Gss2: SpecializationConstants,
@@ -1506,9 +2148,16 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             input_assembly_topology: self.input_assembly_topology,
             tessellation: self.tessellation,
             geometry_shader: Some((shader, specialization_constants)),
+            task_shader: self.task_shader,
             viewport: self.viewport,
             raster: self.raster,
             multisample: self.multisample,
+            sample_mask: self.sample_mask,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
+            cache: self.cache,
+            capture_statistics: self.capture_statistics,
+            capture_internal_representations: self.capture_internal_representations,
             fragment_shader: self.fragment_shader,
             depth_stencil: self.depth_stencil,
             blend: self.blend,
@@ -1534,6 +2183,10 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     }
 
     /// Sets the characteristics of viewports and scissor boxes in advance.
+    ///
+    /// Using more than one viewport requires the `multi_viewport` feature, and the number of
+    /// viewports can't exceed the `max_viewports` limit. This also applies to the other
+    /// `viewports_*` methods.
     #[inline]
     pub fn viewports_scissors<I>(mut self, viewports: I) -> Self
     where
@@ -1592,6 +2245,8 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     /// If true, then the depth value of the vertices will be clamped to the range `[0.0 ; 1.0]`.
     /// If false, fragments whose depth is outside of this range will be discarded before the
     /// fragment shader even runs.
+    ///
+    /// Enabling depth clamping requires the `depth_clamp` feature.
     #[inline]
     pub fn depth_clamp(mut self, clamp: bool) -> Self {
         self.raster.depth_clamp = clamp;
@@ -1695,7 +2350,79 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self
     }
 
-    // TODO: missing DepthBiasControl
+    /// Enables a static depth bias. The depth of each fragment is offset by
+    /// `constant_factor`, plus `slope_factor` times the maximum depth slope of the polygon. If
+    /// `clamp` is not 0.0, the bias is then clamped to it.
+    ///
+    /// Using a `clamp` different from 0.0 requires the `depth_bias_clamp` feature to be enabled
+    /// on the device.
+    ///
+    /// Depth bias is disabled by default.
+    #[inline]
+    pub fn depth_bias(mut self, constant_factor: f32, clamp: f32, slope_factor: f32) -> Self {
+        self.raster.depth_bias = DepthBiasControl::Static(DepthBias {
+            constant_factor,
+            clamp,
+            slope_factor,
+        });
+        self
+    }
+
+    /// Enables depth bias, with the bias values set dynamically when drawing.
+    #[inline]
+    pub fn depth_bias_dynamic(mut self) -> Self {
+        self.raster.depth_bias = DepthBiasControl::Dynamic;
+        self
+    }
+
+    /// Disables depth bias.
+    #[inline]
+    pub fn depth_bias_disabled(mut self) -> Self {
+        self.raster.depth_bias = DepthBiasControl::Disabled;
+        self
+    }
+
+    /// Sets the conservative rasterization mode. Anything other than `Disabled` requires the
+    /// `ext_conservative_rasterization` extension.
+    ///
+    /// Conservative rasterization is disabled by default.
+    #[inline]
+    pub fn conservative_rasterization(mut self, mode: ConservativeRasterization) -> Self {
+        self.raster.conservative = mode;
+        self
+    }
+
+    /// Sets the algorithm used to rasterize lines. Anything other than `Default` requires the
+    /// `ext_line_rasterization` extension and the feature corresponding to the mode.
+    #[inline]
+    pub fn line_rasterization_mode(mut self, mode: LineRasterizationMode) -> Self {
+        self.raster.line_rasterization_mode = mode;
+        self
+    }
+
+    /// Enables line stippling. Each bit of `pattern` tells whether a pixel of the line is drawn,
+    /// and is used for `factor` consecutive pixels.
+    ///
+    /// Requires the `ext_line_rasterization` extension and the stippled lines feature
+    /// corresponding to the line rasterization mode.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `factor` is not between 1 and 256.
+    ///
+    #[inline]
+    pub fn line_stipple(mut self, factor: u32, pattern: u16) -> Self {
+        assert!(factor >= 1 && factor <= 256);
+        self.raster.line_stipple = Some(LineStipple { factor, pattern });
+        self
+    }
+
+    /// Disables line stippling. This is the default.
+    #[inline]
+    pub fn line_stipple_disabled(mut self) -> Self {
+        self.raster.line_stipple = None;
+        self
+    }
 
     /// Disables sample shading. The fragment shader will only be run once per fragment (ie. per
     /// pixel) and not once by sample. The output will then be copied in all of the covered
@@ -1765,18 +2492,30 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self
     }
 
-    // TODO: rasterizationSamples and pSampleMask
+    /// Sets the mask of the samples that can be written to, one bit per sample. The samples
+    /// whose bit is 0 are left untouched. Bits above the number of samples of the subpass are
+    /// ignored.
+    ///
+    /// The number of samples itself is determined by the attachments of the subpass.
+    ///
+    /// All the samples are written by default.
+    #[inline]
+    pub fn sample_mask(mut self, mask: u64) -> Self {
+        self.sample_mask = [mask as u32, (mask >> 32) as u32];
+        self
+    }
 
     /// Sets the fragment shader to use.
     ///
     /// The fragment shader is run once for each pixel that is covered by each primitive.
-    // TODO: correct specialization constants
+    ///
+    /// See `vertex_shader` for the `specialization_constants` parameter.
     #[inline]
     pub fn fragment_shader<Fs2, Fss2>(
         self,
         shader: Fs2,
         specialization_constants: Fss2,
-    ) -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs2, Fss2, Rp>
+    ) -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs2, Fss2, Rp>
     where
         Fs2: GraphicsEntryPointAbstract<SpecializationConstants = Fss2>,
         Fss2: SpecializationConstants,
@@ -1788,9 +2527,16 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             input_assembly_topology: self.input_assembly_topology,
             tessellation: self.tessellation,
             geometry_shader: self.geometry_shader,
+            task_shader: self.task_shader,
             viewport: self.viewport,
             raster: self.raster,
             multisample: self.multisample,
+            sample_mask: self.sample_mask,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
+            cache: self.cache,
+            capture_statistics: self.capture_statistics,
+            capture_internal_representations: self.capture_internal_representations,
             fragment_shader: Some((shader, specialization_constants)),
             depth_stencil: self.depth_stencil,
             blend: self.blend,
@@ -1832,14 +2578,86 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self
     }
 
-    // TODO: missing tons of depth-stencil stuff
+    /// Sets the comparison used by the depth test.
+    ///
+    /// The depth test is disabled if `compare` is `Always` and depth writes are disabled.
+    #[inline]
+    pub fn depth_compare(mut self, compare: Compare) -> Self {
+        self.depth_stencil.depth_compare = compare;
+        self
+    }
+
+    /// Enables the depth bounds test. Fragments whose depth value in the depth attachment is
+    /// outside of `bounds` are discarded. The bounds must be between 0.0 and 1.0.
+    ///
+    /// Requires the `depth_bounds` feature.
+    #[inline]
+    pub fn depth_bounds(mut self, bounds: Range<f32>) -> Self {
+        self.depth_stencil.depth_bounds_test = DepthBounds::Fixed(bounds);
+        self
+    }
+
+    /// Enables the depth bounds test, with bounds that will need to be set when drawing.
+    ///
+    /// Requires the `depth_bounds` feature.
+    #[inline]
+    pub fn depth_bounds_dynamic(mut self) -> Self {
+        self.depth_stencil.depth_bounds_test = DepthBounds::Dynamic;
+        self
+    }
+
+    /// Disables the depth bounds test. This is the default.
+    #[inline]
+    pub fn depth_bounds_disabled(mut self) -> Self {
+        self.depth_stencil.depth_bounds_test = DepthBounds::Disabled;
+        self
+    }
+
+    /// Sets the stencil test performed for front-facing primitives.
+    ///
+    /// The compare mask, write mask and reference must be dynamic for both faces or for none.
+    #[inline]
+    pub fn stencil_front(mut self, stencil: Stencil) -> Self {
+        self.depth_stencil.stencil_front = stencil;
+        self
+    }
+
+    /// Sets the stencil test performed for back-facing primitives.
+    ///
+    /// The compare mask, write mask and reference must be dynamic for both faces or for none.
+    #[inline]
+    pub fn stencil_back(mut self, stencil: Stencil) -> Self {
+        self.depth_stencil.stencil_back = stencil;
+        self
+    }
+
+    /// Sets the same stencil test for front-facing and back-facing primitives.
+    ///
+    /// > **Note**: This is a shortcut for calling both `stencil_front` and `stencil_back`.
+    #[inline]
+    pub fn stencil(mut self, stencil: Stencil) -> Self {
+        self.depth_stencil.stencil_front = stencil;
+        self.depth_stencil.stencil_back = stencil;
+        self
+    }
+
+    /// Disables the stencil test. This is the default.
+    #[inline]
+    pub fn stencil_disabled(self) -> Self {
+        self.stencil(Stencil::default())
+    }
 
+    /// Uses the same blend state for all the color attachments of the subpass.
     #[inline]
     pub fn blend_collective(mut self, blend: AttachmentBlend) -> Self {
         self.blend.attachments = AttachmentsBlend::Collective(blend);
         self
     }
 
+    /// Uses a different blend state for each color attachment of the subpass. There must be
+    /// exactly one blend state per color attachment.
+    ///
+    /// Using blend states that are not all identical requires the `independent_blend` feature.
     #[inline]
     pub fn blend_individual<I>(mut self, blend: I) -> Self
     where
@@ -1856,11 +2674,15 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self.blend_collective(AttachmentBlend::pass_through())
     }
 
+    /// Blends the fragment shader outputs with the framebuffer attachments, using the alpha
+    /// value of the outputs.
     #[inline]
     pub fn blend_alpha_blending(self) -> Self {
         self.blend_collective(AttachmentBlend::alpha_blending())
     }
 
+    /// Applies a logical operation between the fragment shader outputs and the framebuffer
+    /// attachments, instead of blending. Requires the `logic_op` feature.
     #[inline]
     pub fn blend_logic_op(mut self, logic_op: LogicOp) -> Self {
         self.blend.logic_op = Some(logic_op);
@@ -1892,12 +2714,84 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self
     }
 
+    /// Allows other pipelines to be created as derivatives of this one with `derive_from`.
+    ///
+    /// Derivatives are a hint for the driver, which can make creating many similar pipelines
+    /// faster. Not allowed by default.
+    #[inline]
+    pub fn allow_derivatives(mut self) -> Self {
+        self.allow_derivatives = true;
+        self
+    }
+
+    /// Creates the pipeline as a derivative of `parent`, which is expected to be similar.
+    ///
+    /// The parent is kept alive until the pipeline is built.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `parent` was not created with `allow_derivatives`.
+    /// - Panics when building if `parent` doesn't belong to the same device.
+    ///
+    #[inline]
+    pub fn derive_from<P>(mut self, parent: P) -> Self
+    where
+        P: GraphicsPipelineAbstract + Send + Sync + 'static,
+    {
+        assert!(
+            parent.allows_derivatives(),
+            "the parent pipeline must have been created with allow_derivatives"
+        );
+        self.base_pipeline = Some(Arc::new(parent));
+        self
+    }
+
+    /// Uses `cache` when building the pipeline.
+    ///
+    /// The Vulkan implementation looks for an existing entry in the cache, or adds one if there
+    /// is none. A pipeline cache can be shared by pipelines built from multiple threads.
+    ///
+    /// The cache is ignored when the pipeline is added to a `GraphicsPipelineBatch`, which uses
+    /// its own cache for all its pipelines.
+    ///
+    /// # Panic
+    ///
+    /// - Panics when building if `cache` doesn't belong to the same device.
+    ///
+    #[inline]
+    pub fn cache(mut self, cache: Arc<PipelineCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Captures statistics about the executables of the pipeline when building it, so that they
+    /// can be queried with `executable_statistics`.
+    ///
+    /// Requires the `pipeline_executable_info` feature.
+    #[inline]
+    pub fn capture_statistics(mut self) -> Self {
+        self.capture_statistics = true;
+        self
+    }
+
+    /// Captures the internal representations of the executables of the pipeline when building
+    /// it, so that they can be queried with `executable_internal_representations`. This can make
+    /// building the pipeline slower.
+    ///
+    /// Requires the `pipeline_executable_info` feature.
+    #[inline]
+    pub fn capture_internal_representations(mut self) -> Self {
+        self.capture_internal_representations = true;
+        self
+    }
+
     /// Sets the render pass subpass to use.
     #[inline]
     pub fn render_pass<Rp2>(
         self,
         subpass: Subpass<Rp2>,
-    ) -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp2> {
+    ) -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs, Fss, Rp2>
+    {
         GraphicsPipelineBuilder {
             vertex_input: self.vertex_input,
             vertex_shader: self.vertex_shader,
@@ -1905,9 +2799,16 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             input_assembly_topology: self.input_assembly_topology,
             tessellation: self.tessellation,
             geometry_shader: self.geometry_shader,
+            task_shader: self.task_shader,
             viewport: self.viewport,
             raster: self.raster,
             multisample: self.multisample,
+            sample_mask: self.sample_mask,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
+            cache: self.cache,
+            capture_statistics: self.capture_statistics,
+            capture_internal_representations: self.capture_internal_representations,
             fragment_shader: self.fragment_shader,
             depth_stencil: self.depth_stencil,
             blend: self.blend,
@@ -1916,8 +2817,8 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     }
 }
 
-impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp> Clone
-    for GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
+impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs, Fss, Rp> Clone
+    for GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Fs, Fss, Rp>
 where
     Vdef: Clone,
     Vs: Clone,
@@ -1928,6 +2829,8 @@ where
     Tess: Clone,
     Gs: Clone,
     Gss: Clone,
+    Ts: Clone,
+    Tss: Clone,
     Fs: Clone,
     Fss: Clone,
     Rp: Clone,
@@ -1940,6 +2843,7 @@ where
             input_assembly_topology: self.input_assembly_topology,
             tessellation: self.tessellation.clone(),
             geometry_shader: self.geometry_shader.clone(),
+            task_shader: self.task_shader.clone(),
             viewport: self.viewport.clone(),
             raster: self.raster.clone(),
             multisample: vk::PipelineMultisampleStateCreateInfo {
@@ -1953,6 +2857,12 @@ where
                 alphaToCoverageEnable: self.multisample.alphaToCoverageEnable,
                 alphaToOneEnable: self.multisample.alphaToOneEnable,
             },
+            sample_mask: self.sample_mask,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline.clone(),
+            cache: self.cache.clone(),
+            capture_statistics: self.capture_statistics,
+            capture_internal_representations: self.capture_internal_representations,
             fragment_shader: self.fragment_shader.clone(),
             depth_stencil: self.depth_stencil.clone(),
             blend: self.blend.clone(),
@@ -1960,3 +2870,86 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::check_conservative_rasterization;
+    use super::check_instance_rate_divisor;
+    use device::Features;
+    use pipeline::graphics_pipeline::GraphicsPipelineCreationError;
+    use pipeline::raster::ConservativeRasterization;
+
+    #[test]
+    fn instance_rate_divisor_checked() {
+        let features = Features::none();
+        assert_eq!(
+            check_instance_rate_divisor(0, 2, &features, 16),
+            Err(GraphicsPipelineCreationError::InstanceRateDivisorFeatureNotEnabled)
+        );
+
+        let features = Features {
+            vertex_attribute_instance_rate_divisor: true,
+            ..Features::none()
+        };
+        assert_eq!(check_instance_rate_divisor(0, 2, &features, 16), Ok(()));
+        assert_eq!(check_instance_rate_divisor(0, 16, &features, 16), Ok(()));
+        assert_eq!(
+            check_instance_rate_divisor(3, 17, &features, 16),
+            Err(
+                GraphicsPipelineCreationError::MaxVertexAttribDivisorExceeded {
+                    binding: 3,
+                    max: 16,
+                    obtained: 17,
+                }
+            )
+        );
+        assert_eq!(
+            check_instance_rate_divisor(0, 0, &features, 16),
+            Err(GraphicsPipelineCreationError::InstanceRateZeroDivisorFeatureNotEnabled)
+        );
+
+        let features = Features {
+            vertex_attribute_instance_rate_divisor: true,
+            vertex_attribute_instance_rate_zero_divisor: true,
+            ..Features::none()
+        };
+        assert_eq!(check_instance_rate_divisor(0, 0, &features, 16), Ok(()));
+    }
+
+    #[test]
+    fn conservative_rasterization_checked() {
+        assert_eq!(
+            check_conservative_rasterization(ConservativeRasterization::Disabled, 0.0, false),
+            Ok(())
+        );
+
+        let overestimate = |extra_size| ConservativeRasterization::Overestimate {
+            extra_size: extra_size,
+        };
+        assert_eq!(
+            check_conservative_rasterization(overestimate(0.0), 0.75, false),
+            Ok(())
+        );
+        assert_eq!(
+            check_conservative_rasterization(overestimate(0.75), 0.75, false),
+            Ok(())
+        );
+        assert_eq!(
+            check_conservative_rasterization(overestimate(1.0), 0.75, false),
+            Err(GraphicsPipelineCreationError::MaxExtraPrimitiveOverestimationSizeExceeded)
+        );
+        assert_eq!(
+            check_conservative_rasterization(overestimate(-0.5), 0.75, false),
+            Err(GraphicsPipelineCreationError::MaxExtraPrimitiveOverestimationSizeExceeded)
+        );
+
+        assert_eq!(
+            check_conservative_rasterization(ConservativeRasterization::Underestimate, 0.0, false),
+            Err(GraphicsPipelineCreationError::PrimitiveUnderestimationNotSupported)
+        );
+        assert_eq!(
+            check_conservative_rasterization(ConservativeRasterization::Underestimate, 0.0, true),
+            Ok(())
+        );
+    }
+}
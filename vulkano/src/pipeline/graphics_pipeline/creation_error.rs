@@ -11,6 +11,7 @@ use std::error;
 use std::fmt;
 use std::u32;
 
+use descriptor::pipeline_layout::PipelineLayoutCreationError;
 use descriptor::pipeline_layout::PipelineLayoutNotSupersetError;
 use pipeline::input_assembly::PrimitiveTopology;
 use pipeline::shader::ShaderInterfaceMismatchError;
@@ -27,6 +28,9 @@ pub enum GraphicsPipelineCreationError {
     /// The pipeline layout is not compatible with what the shaders expect.
     IncompatiblePipelineLayout(PipelineLayoutNotSupersetError),
 
+    /// Error while creating the pipeline layout object.
+    PipelineLayoutCreationError(PipelineLayoutCreationError),
+
     /// The interface between the vertex shader and the geometry shader mismatches.
     VertexGeometryStagesMismatch(ShaderInterfaceMismatchError),
 
@@ -100,6 +104,9 @@ pub enum GraphicsPipelineCreationError {
         primitive: PrimitiveTopology,
     },
 
+    /// The pipeline must use at least one viewport.
+    NoViewports,
+
     /// The `multi_viewport` feature must be enabled in order to use multiple viewports at once.
     MultiViewportFeatureNotEnabled,
 
@@ -117,12 +124,34 @@ pub enum GraphicsPipelineCreationError {
     /// The minimum or maximum bounds of viewports have been exceeded.
     ViewportBoundsExceeded,
 
+    /// The `khr_maintenance1` extension must be enabled in order to use viewports with a negative
+    /// height.
+    NegativeViewportHeightWithoutMaintenance1,
+
     /// The `wide_lines` feature must be enabled in order to use a line width greater than 1.0.
     WideLinesFeatureNotEnabled,
 
     /// The `depth_clamp` feature must be enabled in order to use depth clamping.
     DepthClampFeatureNotEnabled,
 
+    /// The `vertex_attribute_instance_rate_divisor` feature must be enabled in order to use an
+    /// instance rate divisor different from 1.
+    InstanceRateDivisorFeatureNotEnabled,
+
+    /// The `vertex_attribute_instance_rate_zero_divisor` feature must be enabled in order to use
+    /// an instance rate divisor of 0.
+    InstanceRateZeroDivisorFeatureNotEnabled,
+
+    /// The instance rate divisor of a binding is greater than the `maxVertexAttribDivisor` limit.
+    MaxVertexAttribDivisorExceeded {
+        /// Index of the faulty binding.
+        binding: usize,
+        /// Maximum allowed value.
+        max: u32,
+        /// Value that was passed.
+        obtained: u32,
+    },
+
     /// The `depth_bias_clamp` feature must be enabled in order to use a depth bias clamp different
     /// from 0.0.
     DepthBiasClampFeatureNotEnabled,
@@ -131,9 +160,38 @@ pub enum GraphicsPipelineCreationError {
     /// from `Fill`.
     FillModeNonSolidFeatureNotEnabled,
 
+    /// The `ext_conservative_rasterization` extension must be enabled in order to use
+    /// conservative rasterization.
+    ConservativeRasterizationExtensionNotEnabled,
+
+    /// The extra size of conservative overestimation is negative or greater than the
+    /// `max_extra_primitive_overestimation_size` property of the device.
+    MaxExtraPrimitiveOverestimationSizeExceeded,
+
+    /// The device doesn't support the `Underestimate` conservative rasterization mode.
+    PrimitiveUnderestimationNotSupported,
+
+    /// The `ext_line_rasterization` extension must be enabled in order to use a line
+    /// rasterization mode other than `Default` or line stippling.
+    LineRasterizationExtensionNotEnabled,
+
+    /// The feature corresponding to the line rasterization mode must be enabled.
+    LineRasterizationModeFeatureNotEnabled,
+
+    /// The feature corresponding to stippled lines in the line rasterization mode must be
+    /// enabled.
+    StippledLineFeatureNotEnabled,
+
     /// The `depth_bounds` feature must be enabled in order to use depth bounds testing.
     DepthBoundsFeatureNotEnabled,
 
+    /// The depth bounds must be between 0.0 and 1.0.
+    DepthBoundsOutOfRange,
+
+    /// The `pipeline_executable_info` feature must be enabled in order to capture statistics or
+    /// internal representations.
+    PipelineExecutableInfoFeatureNotEnabled,
+
     /// The requested stencil test is invalid.
     WrongStencilState,
 
@@ -146,6 +204,18 @@ pub enum GraphicsPipelineCreationError {
     /// The `tessellation_shader` feature must be enabled in order to use tessellation shaders.
     TessellationShaderFeatureNotEnabled,
 
+    /// The `mesh_shader` feature must be enabled in order to use mesh shaders.
+    MeshShaderFeatureNotEnabled,
+
+    /// The `task_shader` feature must be enabled in order to use task shaders.
+    TaskShaderFeatureNotEnabled,
+
+    /// A task shader can only be used in a pipeline that has a mesh shader.
+    TaskShaderWithoutMeshShader,
+
+    /// A pipeline with a mesh shader can't have tessellation or geometry shaders.
+    MeshShaderWithVertexPipelineStages,
+
     /// The number of attachments specified in the blending does not match the number of
     /// attachments in the subpass.
     MismatchBlendingAttachmentsCount,
@@ -157,6 +227,10 @@ pub enum GraphicsPipelineCreationError {
     /// The `logic_op` feature must be enabled in order to use logic operations.
     LogicOpFeatureNotEnabled,
 
+    /// The `dual_src_blend` feature must be enabled in order to use blend factors that refer to
+    /// the second source color.
+    DualSrcBlendFeatureNotEnabled,
+
     /// The depth test requires a depth attachment but render pass has no depth attachment, or
     /// depth writing is enabled and the depth attachment is read-only.
     NoDepthAttachment,
@@ -169,6 +243,9 @@ pub enum GraphicsPipelineCreationError {
     /// tessellation shader.
     InvalidPrimitiveTopology,
 
+    /// The number of vertices per patch of a patch list is 0.
+    InvalidPatchControlPoints,
+
     /// The `maxTessellationPatchSize` limit was exceeded.
     MaxTessellationPatchSizeExceeded,
 
@@ -190,6 +267,7 @@ impl error::Error for GraphicsPipelineCreationError {
         match *self {
             GraphicsPipelineCreationError::OomError(ref err) => Some(err),
             GraphicsPipelineCreationError::IncompatiblePipelineLayout(ref err) => Some(err),
+            GraphicsPipelineCreationError::PipelineLayoutCreationError(ref err) => Some(err),
             GraphicsPipelineCreationError::VertexGeometryStagesMismatch(ref err) => Some(err),
             GraphicsPipelineCreationError::VertexTessControlStagesMismatch(ref err) => Some(err),
             GraphicsPipelineCreationError::VertexFragmentStagesMismatch(ref err) => Some(err),
@@ -240,6 +318,9 @@ impl fmt::Display for GraphicsPipelineCreationError {
                 GraphicsPipelineCreationError::IncompatiblePipelineLayout(_) => {
                     "the pipeline layout is not compatible with what the shaders expect"
                 }
+                GraphicsPipelineCreationError::PipelineLayoutCreationError(_) => {
+                    "error while creating the pipeline layout object"
+                }
                 GraphicsPipelineCreationError::FragmentShaderRenderPassIncompatible => {
                     "the output of the fragment shader is not compatible with what the render pass \
                  subpass expects"
@@ -266,6 +347,9 @@ impl fmt::Display for GraphicsPipelineCreationError {
                     "the user requested to use primitive restart, but the primitive topology \
                  doesn't support it"
                 }
+                GraphicsPipelineCreationError::NoViewports => {
+                    "the pipeline must use at least one viewport"
+                }
                 GraphicsPipelineCreationError::MultiViewportFeatureNotEnabled => {
                     "the `multi_viewport` feature must be enabled in order to use multiple viewports \
                  at once"
@@ -279,6 +363,10 @@ impl fmt::Display for GraphicsPipelineCreationError {
                 GraphicsPipelineCreationError::ViewportBoundsExceeded => {
                     "the minimum or maximum bounds of viewports have been exceeded"
                 }
+                GraphicsPipelineCreationError::NegativeViewportHeightWithoutMaintenance1 => {
+                    "the `khr_maintenance1` extension must be enabled in order to use viewports \
+                 with a negative height"
+                }
                 GraphicsPipelineCreationError::WideLinesFeatureNotEnabled => {
                     "the `wide_lines` feature must be enabled in order to use a line width \
                  greater than 1.0"
@@ -286,6 +374,18 @@ impl fmt::Display for GraphicsPipelineCreationError {
                 GraphicsPipelineCreationError::DepthClampFeatureNotEnabled => {
                     "the `depth_clamp` feature must be enabled in order to use depth clamping"
                 }
+                GraphicsPipelineCreationError::InstanceRateDivisorFeatureNotEnabled => {
+                    "the `vertex_attribute_instance_rate_divisor` feature must be enabled in order \
+                     to use an instance rate divisor different from 1"
+                }
+                GraphicsPipelineCreationError::InstanceRateZeroDivisorFeatureNotEnabled => {
+                    "the `vertex_attribute_instance_rate_zero_divisor` feature must be enabled in \
+                     order to use an instance rate divisor of 0"
+                }
+                GraphicsPipelineCreationError::MaxVertexAttribDivisorExceeded { .. } => {
+                    "the instance rate divisor of a binding is greater than the \
+                     `maxVertexAttribDivisor` limit"
+                }
                 GraphicsPipelineCreationError::DepthBiasClampFeatureNotEnabled => {
                     "the `depth_bias_clamp` feature must be enabled in order to use a depth bias \
                  clamp different from 0.0."
@@ -294,9 +394,38 @@ impl fmt::Display for GraphicsPipelineCreationError {
                     "the `fill_mode_non_solid` feature must be enabled in order to use a polygon mode \
                  different from `Fill`"
                 }
+                GraphicsPipelineCreationError::ConservativeRasterizationExtensionNotEnabled => {
+                    "the `ext_conservative_rasterization` extension must be enabled in order to use \
+                     conservative rasterization"
+                }
+                GraphicsPipelineCreationError::MaxExtraPrimitiveOverestimationSizeExceeded => {
+                    "the extra size of conservative overestimation is negative or greater than the \
+                     `max_extra_primitive_overestimation_size` property of the device"
+                }
+                GraphicsPipelineCreationError::PrimitiveUnderestimationNotSupported => {
+                    "the device doesn't support the `Underestimate` conservative rasterization mode"
+                }
+                GraphicsPipelineCreationError::LineRasterizationExtensionNotEnabled => {
+                    "the `ext_line_rasterization` extension must be enabled in order to use a line \
+                     rasterization mode other than `Default` or line stippling"
+                }
+                GraphicsPipelineCreationError::LineRasterizationModeFeatureNotEnabled => {
+                    "the feature corresponding to the line rasterization mode must be enabled"
+                }
+                GraphicsPipelineCreationError::StippledLineFeatureNotEnabled => {
+                    "the feature corresponding to stippled lines in the line rasterization mode must \
+                     be enabled"
+                }
                 GraphicsPipelineCreationError::DepthBoundsFeatureNotEnabled => {
                     "the `depth_bounds` feature must be enabled in order to use depth bounds testing"
                 }
+                GraphicsPipelineCreationError::DepthBoundsOutOfRange => {
+                    "the depth bounds must be between 0.0 and 1.0"
+                }
+                GraphicsPipelineCreationError::PipelineExecutableInfoFeatureNotEnabled => {
+                    "the `pipeline_executable_info` feature must be enabled in order to capture \
+                     statistics or internal representations"
+                }
                 GraphicsPipelineCreationError::WrongStencilState => {
                     "the requested stencil test is invalid"
                 }
@@ -310,6 +439,18 @@ impl fmt::Display for GraphicsPipelineCreationError {
                     "the `tessellation_shader` feature must be enabled in order to use tessellation \
                  shaders"
                 }
+                GraphicsPipelineCreationError::MeshShaderFeatureNotEnabled => {
+                    "the `mesh_shader` feature must be enabled in order to use mesh shaders"
+                }
+                GraphicsPipelineCreationError::TaskShaderFeatureNotEnabled => {
+                    "the `task_shader` feature must be enabled in order to use task shaders"
+                }
+                GraphicsPipelineCreationError::TaskShaderWithoutMeshShader => {
+                    "a task shader can only be used in a pipeline that has a mesh shader"
+                }
+                GraphicsPipelineCreationError::MeshShaderWithVertexPipelineStages => {
+                    "a pipeline with a mesh shader can't have tessellation or geometry shaders"
+                }
                 GraphicsPipelineCreationError::MismatchBlendingAttachmentsCount => {
                     "the number of attachments specified in the blending does not match the number of \
                  attachments in the subpass"
@@ -321,6 +462,10 @@ impl fmt::Display for GraphicsPipelineCreationError {
                 GraphicsPipelineCreationError::LogicOpFeatureNotEnabled => {
                     "the `logic_op` feature must be enabled in order to use logic operations"
                 }
+                GraphicsPipelineCreationError::DualSrcBlendFeatureNotEnabled => {
+                    "the `dual_src_blend` feature must be enabled in order to use blend factors \
+                 that refer to the second source color"
+                }
                 GraphicsPipelineCreationError::NoDepthAttachment => {
                     "the depth attachment of the render pass does not match the depth test"
                 }
@@ -331,6 +476,9 @@ impl fmt::Display for GraphicsPipelineCreationError {
                     "trying to use a patch list without a tessellation shader, or a non-patch-list \
                  with a tessellation shader"
                 }
+                GraphicsPipelineCreationError::InvalidPatchControlPoints => {
+                    "the number of vertices per patch of a patch list is 0"
+                }
                 GraphicsPipelineCreationError::MaxTessellationPatchSizeExceeded => {
                     "the maximum tessellation patch size was exceeded"
                 }
@@ -362,6 +510,13 @@ impl From<PipelineLayoutNotSupersetError> for GraphicsPipelineCreationError {
     }
 }
 
+impl From<PipelineLayoutCreationError> for GraphicsPipelineCreationError {
+    #[inline]
+    fn from(err: PipelineLayoutCreationError) -> GraphicsPipelineCreationError {
+        GraphicsPipelineCreationError::PipelineLayoutCreationError(err)
+    }
+}
+
 impl From<IncompatibleVertexDefinitionError> for GraphicsPipelineCreationError {
     #[inline]
     fn from(err: IncompatibleVertexDefinitionError) -> GraphicsPipelineCreationError {
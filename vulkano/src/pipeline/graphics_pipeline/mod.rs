@@ -33,20 +33,32 @@ use framebuffer::RenderPassDesc;
 use framebuffer::RenderPassDescClearValues;
 use framebuffer::RenderPassSys;
 use framebuffer::Subpass;
+use pipeline::executable;
+use pipeline::executable::PipelineExecutableError;
+use pipeline::executable::PipelineExecutableInternalRepresentation;
+use pipeline::executable::PipelineExecutableProperties;
+use pipeline::executable::PipelineExecutableStatistic;
 use pipeline::shader::EmptyEntryPointDummy;
 use pipeline::vertex::BufferlessDefinition;
 use pipeline::vertex::IncompatibleVertexDefinitionError;
+use pipeline::vertex::InputRate;
 use pipeline::vertex::VertexDefinition;
 use pipeline::vertex::VertexSource;
 use vk;
 use SafeDeref;
 use VulkanObject;
 
+pub use self::batch::GraphicsPipelineBatch;
+pub use self::batch::GraphicsPipelineBatchResult;
 pub use self::builder::GraphicsPipelineBuilder;
 pub use self::creation_error::GraphicsPipelineCreationError;
+pub use self::typed::TypedGraphicsPipeline;
+pub use self::typed::TypedGraphicsPipelineError;
 
+mod batch;
 mod builder;
 mod creation_error;
+mod typed;
 // FIXME: restore
 //mod tests;
 
@@ -62,6 +74,9 @@ pub struct GraphicsPipeline<VertexDefinition, Layout, RenderP> {
     render_pass_subpass: u32,
 
     vertex_definition: VertexDefinition,
+    // Binding number, stride and input rate of each vertex buffer, as returned by the vertex
+    // definition when the pipeline was built.
+    vertex_input_bindings: Vec<(u32, usize, InputRate)>,
 
     dynamic_line_width: bool,
     dynamic_viewport: bool,
@@ -73,6 +88,12 @@ pub struct GraphicsPipeline<VertexDefinition, Layout, RenderP> {
     dynamic_stencil_reference: bool,
     dynamic_blend_constants: bool,
 
+    allow_derivatives: bool,
+    capture_statistics: bool,
+    capture_internal_representations: bool,
+    mesh_shader: bool,
+    task_shader: bool,
+
     num_viewports: u32,
 }
 
@@ -97,6 +118,8 @@ impl GraphicsPipeline<(), (), ()> {
         (),
         EmptyEntryPointDummy,
         (),
+        EmptyEntryPointDummy,
+        (),
         (),
     > {
         GraphicsPipelineBuilder::new()
@@ -170,6 +193,12 @@ impl<Mv, L, Rp> GraphicsPipeline<Mv, L, Rp> {
         self.dynamic_scissor
     }
 
+    /// Returns true if the depth bias used by this pipeline is dynamic.
+    #[inline]
+    pub fn has_dynamic_depth_bias(&self) -> bool {
+        self.dynamic_depth_bias
+    }
+
     /// Returns true if the depth bounds used by this pipeline are dynamic.
     #[inline]
     pub fn has_dynamic_depth_bounds(&self) -> bool {
@@ -193,6 +222,77 @@ impl<Mv, L, Rp> GraphicsPipeline<Mv, L, Rp> {
     pub fn has_dynamic_stencil_reference(&self) -> bool {
         self.dynamic_stencil_reference
     }
+
+    /// Returns true if the blend constants used by this pipeline are dynamic.
+    #[inline]
+    pub fn has_dynamic_blend_constants(&self) -> bool {
+        self.dynamic_blend_constants
+    }
+
+    /// Returns true if other pipelines can be created as derivatives of this one.
+    #[inline]
+    pub fn allows_derivatives(&self) -> bool {
+        self.allow_derivatives
+    }
+
+    /// Returns true if the primitives of this pipeline are generated by a mesh shader.
+    #[inline]
+    pub fn has_mesh_shader(&self) -> bool {
+        self.mesh_shader
+    }
+
+    /// Returns true if this pipeline has a task shader.
+    #[inline]
+    pub fn has_task_shader(&self) -> bool {
+        self.task_shader
+    }
+
+    /// Returns the properties of the executables that the implementation compiled for this
+    /// pipeline.
+    ///
+    /// Requires the `pipeline_executable_info` feature.
+    #[inline]
+    pub fn executable_properties(
+        &self,
+    ) -> Result<Vec<PipelineExecutableProperties>, PipelineExecutableError> {
+        executable::executable_properties(&self.inner.device, self.inner.pipeline)
+    }
+
+    /// Returns the statistics of the executable at index `executable_index` in the list returned
+    /// by `executable_properties`.
+    ///
+    /// The pipeline must have been built with `capture_statistics()`.
+    #[inline]
+    pub fn executable_statistics(
+        &self,
+        executable_index: u32,
+    ) -> Result<Vec<PipelineExecutableStatistic>, PipelineExecutableError> {
+        if !self.capture_statistics {
+            return Err(PipelineExecutableError::StatisticsNotCaptured);
+        }
+
+        executable::executable_statistics(&self.inner.device, self.inner.pipeline, executable_index)
+    }
+
+    /// Returns the internal representations of the executable at index `executable_index` in the
+    /// list returned by `executable_properties`.
+    ///
+    /// The pipeline must have been built with `capture_internal_representations()`.
+    #[inline]
+    pub fn executable_internal_representations(
+        &self,
+        executable_index: u32,
+    ) -> Result<Vec<PipelineExecutableInternalRepresentation>, PipelineExecutableError> {
+        if !self.capture_internal_representations {
+            return Err(PipelineExecutableError::InternalRepresentationsNotCaptured);
+        }
+
+        executable::executable_internal_representations(
+            &self.inner.device,
+            self.inner.pipeline,
+            executable_index,
+        )
+    }
 }
 
 unsafe impl<Mv, L, Rp> PipelineLayoutAbstract for GraphicsPipeline<Mv, L, Rp>
@@ -369,6 +469,14 @@ pub unsafe trait GraphicsPipelineAbstract:
     /// Returns true if the scissors used by this pipeline are dynamic.
     fn has_dynamic_scissors(&self) -> bool;
 
+    /// Returns true if the depth bias used by this pipeline is dynamic.
+    ///
+    /// The default implementation returns `false`.
+    #[inline]
+    fn has_dynamic_depth_bias(&self) -> bool {
+        false
+    }
+
     /// Returns true if the depth bounds used by this pipeline are dynamic.
     fn has_dynamic_depth_bounds(&self) -> bool;
 
@@ -380,6 +488,44 @@ pub unsafe trait GraphicsPipelineAbstract:
 
     /// Returns true if the stencil references used by this pipeline are dynamic.
     fn has_dynamic_stencil_reference(&self) -> bool;
+
+    /// Returns true if the blend constants used by this pipeline are dynamic.
+    ///
+    /// The default implementation returns `false`.
+    #[inline]
+    fn has_dynamic_blend_constants(&self) -> bool {
+        false
+    }
+
+    /// Returns true if other pipelines can be created as derivatives of this one.
+    ///
+    /// The default implementation returns `false`.
+    #[inline]
+    fn allows_derivatives(&self) -> bool {
+        false
+    }
+
+    /// Returns true if the primitives of this pipeline are generated by a mesh shader.
+    ///
+    /// Such a pipeline must be drawn with the `draw_mesh_tasks` commands.
+    ///
+    /// The default implementation returns `false`.
+    #[inline]
+    fn has_mesh_shader(&self) -> bool {
+        false
+    }
+
+    /// Returns true if this pipeline has a task shader.
+    ///
+    /// The default implementation returns `false`.
+    #[inline]
+    fn has_task_shader(&self) -> bool {
+        false
+    }
+
+    /// Returns the binding number, the stride (in bytes) and the input rate of each vertex
+    /// buffer expected by this pipeline.
+    fn vertex_input_bindings(&self) -> &[(u32, usize, InputRate)];
 }
 
 unsafe impl<Mv, L, Rp> GraphicsPipelineAbstract for GraphicsPipeline<Mv, L, Rp>
@@ -418,6 +564,11 @@ where
         self.dynamic_scissor
     }
 
+    #[inline]
+    fn has_dynamic_depth_bias(&self) -> bool {
+        self.dynamic_depth_bias
+    }
+
     #[inline]
     fn has_dynamic_depth_bounds(&self) -> bool {
         self.dynamic_depth_bounds
@@ -437,6 +588,31 @@ where
     fn has_dynamic_stencil_reference(&self) -> bool {
         self.dynamic_stencil_reference
     }
+
+    #[inline]
+    fn has_dynamic_blend_constants(&self) -> bool {
+        self.dynamic_blend_constants
+    }
+
+    #[inline]
+    fn allows_derivatives(&self) -> bool {
+        self.allow_derivatives
+    }
+
+    #[inline]
+    fn has_mesh_shader(&self) -> bool {
+        self.mesh_shader
+    }
+
+    #[inline]
+    fn has_task_shader(&self) -> bool {
+        self.task_shader
+    }
+
+    #[inline]
+    fn vertex_input_bindings(&self) -> &[(u32, usize, InputRate)] {
+        &self.vertex_input_bindings
+    }
 }
 
 unsafe impl<T> GraphicsPipelineAbstract for T
@@ -474,6 +650,11 @@ where
         (**self).has_dynamic_scissors()
     }
 
+    #[inline]
+    fn has_dynamic_depth_bias(&self) -> bool {
+        (**self).has_dynamic_depth_bias()
+    }
+
     #[inline]
     fn has_dynamic_depth_bounds(&self) -> bool {
         (**self).has_dynamic_depth_bounds()
@@ -493,6 +674,31 @@ where
     fn has_dynamic_stencil_reference(&self) -> bool {
         (**self).has_dynamic_stencil_reference()
     }
+
+    #[inline]
+    fn has_dynamic_blend_constants(&self) -> bool {
+        (**self).has_dynamic_blend_constants()
+    }
+
+    #[inline]
+    fn allows_derivatives(&self) -> bool {
+        (**self).allows_derivatives()
+    }
+
+    #[inline]
+    fn has_mesh_shader(&self) -> bool {
+        (**self).has_mesh_shader()
+    }
+
+    #[inline]
+    fn has_task_shader(&self) -> bool {
+        (**self).has_task_shader()
+    }
+
+    #[inline]
+    fn vertex_input_bindings(&self) -> &[(u32, usize, InputRate)] {
+        (**self).vertex_input_bindings()
+    }
 }
 
 impl<Mv, L, Rp> PartialEq for GraphicsPipeline<Mv, L, Rp>
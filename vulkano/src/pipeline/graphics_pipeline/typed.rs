@@ -0,0 +1,251 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+
+use descriptor::descriptor_set::DescriptorSetsCollection;
+use descriptor::pipeline_layout::PipelineLayoutDesc;
+use descriptor::pipeline_layout::PipelineLayoutPushConstantsCompatible;
+use descriptor::pipeline_layout::PipelineLayoutSetsCompatible;
+use descriptor::pipeline_layout::TypedPipelineLayout;
+use pipeline::graphics_pipeline::GraphicsPipelineAbstract;
+use pipeline::vertex::VertexSource;
+
+/// Graphics pipeline whose vertex buffers, descriptor sets and push constants have types that are
+/// known at compile time.
+///
+/// `V` is the type of the vertex buffers that must be passed to
+/// `AutoCommandBufferBuilder::draw_typed`, and `L` is the layout of the pipeline, whose
+/// `TypedPipelineLayout` implementation gives the types of the descriptor sets and of the push
+/// constants. Passing a different tuple of descriptor sets or a different kind of vertex buffer
+/// fails to compile, instead of returning an error when the command is added.
+///
+/// `V` must be accepted by the vertex definition of the pipeline. When wrapping the pipeline, the
+/// number of descriptor sets of `L::Sets` and the size of `L::PushConstants` are checked against
+/// the layout of the pipeline. The content of the descriptor sets is still checked against the
+/// layout when drawing.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate vulkano;
+/// # fn main() {
+/// # use std::sync::Arc;
+/// # use vulkano::buffer::CpuAccessibleBuffer;
+/// # use vulkano::command_buffer::AutoCommandBufferBuilder;
+/// # use vulkano::command_buffer::DynamicState;
+/// # use vulkano::descriptor::DescriptorSet;
+/// # use vulkano::descriptor::pipeline_layout::PipelineLayout;
+/// # use vulkano::descriptor::pipeline_layout::TypedPipelineLayout;
+/// # use vulkano::framebuffer::RenderPassAbstract;
+/// # use vulkano::pipeline::vertex::SingleBufferDefinition;
+/// # use vulkano::pipeline::GraphicsPipeline;
+/// # use vulkano::pipeline::TypedGraphicsPipeline;
+/// # #[derive(Default, Copy, Clone)]
+/// # struct Vertex { position: [f32; 2] }
+/// # impl_vertex!(Vertex, position);
+/// # use vulkano::descriptor::descriptor::DescriptorDesc;
+/// # use vulkano::descriptor::pipeline_layout::PipelineLayoutDesc;
+/// # use vulkano::descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+/// # mod vs { pub struct Layout; }
+/// # unsafe impl PipelineLayoutDesc for vs::Layout {
+/// #     fn num_sets(&self) -> usize { 1 }
+/// #     fn num_bindings_in_set(&self, _: usize) -> Option<usize> { None }
+/// #     fn descriptor(&self, _: usize, _: usize) -> Option<DescriptorDesc> { None }
+/// #     fn num_push_constants_ranges(&self) -> usize { 0 }
+/// #     fn push_constants_range(&self, _: usize) -> Option<PipelineLayoutDescPcRange> { None }
+/// # }
+/// # let pipeline: Arc<GraphicsPipeline<SingleBufferDefinition<Vertex>,
+/// #     PipelineLayout<vs::Layout>,
+/// #     Arc<dyn RenderPassAbstract + Send + Sync>>> = return;
+/// # let mut builder: AutoCommandBufferBuilder = return;
+/// # let vertex_buffer: Arc<CpuAccessibleBuffer<[Vertex]>> = return;
+/// # let set: Arc<dyn DescriptorSet + Send + Sync> = return;
+/// impl TypedPipelineLayout for vs::Layout {
+///     type Sets = Arc<dyn DescriptorSet + Send + Sync>;
+///     type PushConstants = ();
+/// }
+///
+/// type VertexBuffer = Arc<CpuAccessibleBuffer<[Vertex]>>;
+///
+/// let pipeline = TypedGraphicsPipeline::<_, VertexBuffer, vs::Layout>::new(pipeline).unwrap();
+///
+/// builder
+///     .draw_typed(&pipeline, &DynamicState::none(), vertex_buffer, set, ())
+///     .unwrap();
+/// # }
+/// ```
+///
+/// Passing descriptor sets of another type doesn't compile:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate vulkano;
+/// # fn main() {
+/// # use std::sync::Arc;
+/// # use vulkano::buffer::CpuAccessibleBuffer;
+/// # use vulkano::command_buffer::AutoCommandBufferBuilder;
+/// # use vulkano::command_buffer::DynamicState;
+/// # use vulkano::descriptor::DescriptorSet;
+/// # use vulkano::descriptor::pipeline_layout::PipelineLayout;
+/// # use vulkano::descriptor::pipeline_layout::TypedPipelineLayout;
+/// # use vulkano::framebuffer::RenderPassAbstract;
+/// # use vulkano::pipeline::vertex::SingleBufferDefinition;
+/// # use vulkano::pipeline::GraphicsPipeline;
+/// # use vulkano::pipeline::TypedGraphicsPipeline;
+/// # #[derive(Default, Copy, Clone)]
+/// # struct Vertex { position: [f32; 2] }
+/// # impl_vertex!(Vertex, position);
+/// # use vulkano::descriptor::descriptor::DescriptorDesc;
+/// # use vulkano::descriptor::pipeline_layout::PipelineLayoutDesc;
+/// # use vulkano::descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+/// # mod vs { pub struct Layout; }
+/// # unsafe impl PipelineLayoutDesc for vs::Layout {
+/// #     fn num_sets(&self) -> usize { 1 }
+/// #     fn num_bindings_in_set(&self, _: usize) -> Option<usize> { None }
+/// #     fn descriptor(&self, _: usize, _: usize) -> Option<DescriptorDesc> { None }
+/// #     fn num_push_constants_ranges(&self) -> usize { 0 }
+/// #     fn push_constants_range(&self, _: usize) -> Option<PipelineLayoutDescPcRange> { None }
+/// # }
+/// # let pipeline: Arc<GraphicsPipeline<SingleBufferDefinition<Vertex>,
+/// #     PipelineLayout<vs::Layout>,
+/// #     Arc<dyn RenderPassAbstract + Send + Sync>>> = return;
+/// # let mut builder: AutoCommandBufferBuilder = return;
+/// # let vertex_buffer: Arc<CpuAccessibleBuffer<[Vertex]>> = return;
+/// # let set: Arc<dyn DescriptorSet + Send + Sync> = return;
+/// impl TypedPipelineLayout for vs::Layout {
+///     type Sets = Arc<dyn DescriptorSet + Send + Sync>;
+///     type PushConstants = ();
+/// }
+///
+/// type VertexBuffer = Arc<CpuAccessibleBuffer<[Vertex]>>;
+///
+/// let pipeline = TypedGraphicsPipeline::<_, VertexBuffer, vs::Layout>::new(pipeline).unwrap();
+///
+/// builder
+///     .draw_typed(&pipeline, &DynamicState::none(), vertex_buffer, (), ())
+///     .unwrap();
+/// # }
+/// ```
+pub struct TypedGraphicsPipeline<Gp, V, L> {
+    pipeline: Gp,
+    marker: PhantomData<fn(V, L)>,
+}
+
+impl<Gp, V, L> TypedGraphicsPipeline<Gp, V, L>
+where
+    Gp: GraphicsPipelineAbstract
+        + VertexSource<V>
+        + PipelineLayoutSetsCompatible<L::Sets>
+        + PipelineLayoutPushConstantsCompatible<L::PushConstants>,
+    L: TypedPipelineLayout,
+{
+    /// Wraps around a graphics pipeline.
+    ///
+    /// Returns an error if the number of descriptor sets of `L::Sets` or the size of
+    /// `L::PushConstants` don't match the layout of the pipeline.
+    #[inline]
+    pub fn new(
+        pipeline: Gp,
+    ) -> Result<TypedGraphicsPipeline<Gp, V, L>, TypedGraphicsPipelineError> {
+        if let Some(obtained) = L::Sets::num_sets() {
+            let expected = PipelineLayoutDesc::num_sets(&pipeline);
+            if obtained != expected {
+                return Err(TypedGraphicsPipelineError::DescriptorSetsCountMismatch {
+                    expected,
+                    obtained,
+                });
+            }
+        }
+
+        let required = (0..pipeline.num_push_constants_ranges())
+            .filter_map(|num| pipeline.push_constants_range(num))
+            .map(|range| range.offset + range.size)
+            .max()
+            .unwrap_or(0);
+        let obtained = mem::size_of::<L::PushConstants>();
+        if obtained < required {
+            return Err(TypedGraphicsPipelineError::NotEnoughPushConstants { required, obtained });
+        }
+
+        Ok(TypedGraphicsPipeline {
+            pipeline: pipeline,
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<Gp, V, L> TypedGraphicsPipeline<Gp, V, L> {
+    /// Returns the wrapped pipeline.
+    #[inline]
+    pub fn pipeline(&self) -> &Gp {
+        &self.pipeline
+    }
+
+    /// Destroys the wrapper and returns the pipeline.
+    #[inline]
+    pub fn into_inner(self) -> Gp {
+        self.pipeline
+    }
+}
+
+impl<Gp, V, L> Clone for TypedGraphicsPipeline<Gp, V, L>
+where
+    Gp: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        TypedGraphicsPipeline {
+            pipeline: self.pipeline.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Error that can happen when wrapping a graphics pipeline in a `TypedGraphicsPipeline`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TypedGraphicsPipelineError {
+    /// The descriptor sets collection doesn't contain as many sets as the layout of the pipeline.
+    DescriptorSetsCountMismatch {
+        /// Number of descriptor sets of the layout of the pipeline.
+        expected: usize,
+        /// Number of descriptor sets of the collection.
+        obtained: usize,
+    },
+    /// The push constants are smaller than the end of the last push constants range of the
+    /// layout of the pipeline.
+    NotEnoughPushConstants {
+        /// End in bytes of the last push constants range.
+        required: usize,
+        /// Size in bytes of the push constants.
+        obtained: usize,
+    },
+}
+
+impl error::Error for TypedGraphicsPipelineError {}
+
+impl fmt::Display for TypedGraphicsPipelineError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                TypedGraphicsPipelineError::DescriptorSetsCountMismatch { .. } => {
+                    "the number of descriptor sets doesn't match the layout of the pipeline"
+                }
+                TypedGraphicsPipelineError::NotEnoughPushConstants { .. } => {
+                    "the push constants are smaller than the push constants ranges of the layout"
+                }
+            }
+        )
+    }
+}
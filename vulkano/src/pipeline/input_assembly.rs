@@ -44,18 +44,37 @@ impl InputAssembly {
 /// Describes how vertices must be grouped together to form primitives.
 ///
 /// Note that some topologies don't support primitive restart.
+///
+/// The topologies with adjacency provide additional vertices that can only be accessed by a
+/// geometry shader whose input primitive also has adjacency. Without a geometry shader, the
+/// adjacent vertices are ignored.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum PrimitiveTopology {
+    /// Each vertex is a point.
     PointList,
+    /// Each pair of vertices is a line.
     LineList,
+    /// Each vertex forms a line with the previous one. Supports primitive restart.
     LineStrip,
+    /// Each group of three vertices is a triangle.
     TriangleList,
+    /// Each vertex forms a triangle with the two previous ones. Supports primitive restart.
     TriangleStrip,
+    /// Each vertex forms a triangle with the previous one and the first one. Supports primitive
+    /// restart.
     TriangleFan,
+    /// Each group of four vertices is a line, with the first and last vertices adjacent to it.
     LineListWithAdjacency,
+    /// Line strip where the first and last vertices are only adjacent to the strip. Supports
+    /// primitive restart.
     LineStripWithAdjacency,
+    /// Each group of six vertices is a triangle, with every other vertex adjacent to it.
     TriangleListWithAdjacency,
+    /// Triangle strip where every other vertex is adjacent to the strip. Supports primitive
+    /// restart.
     TriangleStripWithAdjacency,
+    /// Each group of `vertices_per_patch` vertices is a patch that is processed by the
+    /// tessellation shaders. Can only be used with tessellation, and must be used with it.
     PatchList { vertices_per_patch: u32 },
 }
 
@@ -99,6 +118,18 @@ impl PrimitiveTopology {
             _ => false,
         }
     }
+
+    /// Returns true if this primitive topology provides adjacency information.
+    #[inline]
+    pub fn has_adjacency(&self) -> bool {
+        match *self {
+            PrimitiveTopology::LineListWithAdjacency => true,
+            PrimitiveTopology::LineStripWithAdjacency => true,
+            PrimitiveTopology::TriangleListWithAdjacency => true,
+            PrimitiveTopology::TriangleStripWithAdjacency => true,
+            _ => false,
+        }
+    }
 }
 
 /// Trait for types that can be used as indices by the GPU.
@@ -17,12 +17,14 @@
 //! the CPU). Consequently it is a CPU-intensive operation that should be performed at
 //! initialization or during a loading screen.
 //!
-//! There are two kinds of pipelines:
+//! There are three kinds of pipelines:
 //!
 //! - `ComputePipeline`s, for compute operations (general-purpose operations that read/write data
 //!   in buffers or raw pixels in images).
 //! - `GraphicsPipeline`s, for graphical operations (operations that take vertices as input and
 //!   write pixels to a framebuffer).
+//! - `RayTracingPipeline`s, for ray tracing operations. They require the `nv_ray_tracing`
+//!   extension.
 //!
 //! # Creating a compute pipeline.
 //!
@@ -81,19 +83,48 @@ pub use self::compute_pipeline::ComputePipelineCreationError;
 pub use self::compute_pipeline::ComputePipelineSys;
 pub use self::graphics_pipeline::GraphicsPipeline;
 pub use self::graphics_pipeline::GraphicsPipelineAbstract;
+pub use self::graphics_pipeline::GraphicsPipelineBatch;
+pub use self::graphics_pipeline::GraphicsPipelineBatchResult;
 pub use self::graphics_pipeline::GraphicsPipelineBuilder;
 pub use self::graphics_pipeline::GraphicsPipelineCreationError;
 pub use self::graphics_pipeline::GraphicsPipelineSys;
+pub use self::graphics_pipeline::TypedGraphicsPipeline;
+pub use self::graphics_pipeline::TypedGraphicsPipelineError;
+pub use self::ray_tracing_pipeline::RayTracingPipeline;
+pub use self::ray_tracing_pipeline::RayTracingPipelineAbstract;
+pub use self::ray_tracing_pipeline::RayTracingPipelineBuilder;
+pub use self::ray_tracing_pipeline::RayTracingPipelineCreationError;
+pub use self::ray_tracing_pipeline::RayTracingPipelineSys;
+pub use self::ray_tracing_pipeline::RayTracingShaderGroup;
+pub use self::ray_tracing_pipeline::RayTracingShaderStage;
+pub use self::ray_tracing_pipeline::ShaderBindingTable;
+pub use self::ray_tracing_pipeline::ShaderBindingTableError;
+pub use self::ray_tracing_pipeline::ShaderBindingTableRegion;
+pub use self::ray_tracing_pipeline::ShaderBindingTableRegions;
+
+use vk;
 
 mod compute_pipeline;
 mod graphics_pipeline;
+mod ray_tracing_pipeline;
 
 pub mod blend;
 pub mod cache;
 pub mod depth_stencil;
+pub mod executable;
 pub mod input_assembly;
 pub mod multisample;
 pub mod raster;
 pub mod shader;
 pub mod vertex;
 pub mod viewport;
+
+/// The kind of pipeline that a command binds or uses.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum PipelineBindPoint {
+    Graphics = vk::PIPELINE_BIND_POINT_GRAPHICS,
+    Compute = vk::PIPELINE_BIND_POINT_COMPUTE,
+    /// Requires the `nv_ray_tracing` extension.
+    RayTracing = vk::PIPELINE_BIND_POINT_RAY_TRACING_NV,
+}
@@ -43,6 +43,18 @@ pub struct Rasterization {
     pub line_width: Option<f32>,
 
     pub depth_bias: DepthBiasControl,
+
+    /// Whether conservative rasterization is used. Anything other than `Disabled` requires the
+    /// `ext_conservative_rasterization` extension.
+    pub conservative: ConservativeRasterization,
+
+    /// Algorithm used to rasterize lines. Anything other than `Default` requires the
+    /// `ext_line_rasterization` extension.
+    pub line_rasterization_mode: LineRasterizationMode,
+
+    /// If `Some`, lines are stippled with the given pattern. Requires the
+    /// `ext_line_rasterization` extension.
+    pub line_stipple: Option<LineStipple>,
 }
 
 impl Default for Rasterization {
@@ -56,6 +68,9 @@ impl Default for Rasterization {
             front_face: Default::default(),
             line_width: Some(1.0),
             depth_bias: DepthBiasControl::Disabled,
+            conservative: ConservativeRasterization::Disabled,
+            line_rasterization_mode: LineRasterizationMode::Default,
+            line_stipple: None,
         }
     }
 }
@@ -77,7 +92,7 @@ impl DepthBiasControl {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct DepthBias {
     pub constant_factor: f32,
     /// Requires the `depth_bias_clamp` feature to be enabled.
@@ -145,3 +160,78 @@ impl Default for PolygonMode {
         PolygonMode::Fill
     }
 }
+
+/// Conservative rasterization mode.
+///
+/// With conservative rasterization, the coverage of a pixel by a primitive is computed from the
+/// whole area of the pixel instead of from the sample locations.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ConservativeRasterization {
+    /// Conservative rasterization is disabled. This is the default.
+    Disabled,
+    /// A pixel is covered if any part of it is covered by the primitive. The primitive is
+    /// enlarged by `extra_size` pixels in addition to the size the implementation already uses.
+    Overestimate {
+        /// Must be between 0.0 and the `max_extra_primitive_overestimation_size` property of
+        /// the device.
+        extra_size: f32,
+    },
+    /// A pixel is covered only if it is entirely covered by the primitive.
+    Underestimate,
+}
+
+impl ConservativeRasterization {
+    /// Returns the mode and the extra overestimation size to pass to Vulkan.
+    #[inline]
+    pub(crate) fn into_vulkan(self) -> (vk::ConservativeRasterizationModeEXT, f32) {
+        match self {
+            ConservativeRasterization::Disabled => {
+                (vk::CONSERVATIVE_RASTERIZATION_MODE_DISABLED_EXT, 0.0)
+            }
+            ConservativeRasterization::Overestimate { extra_size } => (
+                vk::CONSERVATIVE_RASTERIZATION_MODE_OVERESTIMATE_EXT,
+                extra_size,
+            ),
+            ConservativeRasterization::Underestimate => {
+                (vk::CONSERVATIVE_RASTERIZATION_MODE_UNDERESTIMATE_EXT, 0.0)
+            }
+        }
+    }
+}
+
+/// Algorithm used to rasterize lines.
+///
+/// Every mode other than `Default` requires the corresponding feature of the
+/// `ext_line_rasterization` extension to be enabled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LineRasterizationMode {
+    /// Lets the implementation choose between `Rectangular` and a parallelogram algorithm.
+    Default = vk::LINE_RASTERIZATION_MODE_DEFAULT_EXT,
+    /// Lines are rasterized as rectangles. Requires the `rectangular_lines` feature.
+    Rectangular = vk::LINE_RASTERIZATION_MODE_RECTANGULAR_EXT,
+    /// Lines are rasterized with the Bresenham algorithm, one pixel wide. Requires the
+    /// `bresenham_lines` feature.
+    Bresenham = vk::LINE_RASTERIZATION_MODE_BRESENHAM_EXT,
+    /// Lines are rasterized as anti-aliased rectangles. Requires the `smooth_lines` feature.
+    RectangularSmooth = vk::LINE_RASTERIZATION_MODE_RECTANGULAR_SMOOTH_EXT,
+}
+
+impl Default for LineRasterizationMode {
+    #[inline]
+    fn default() -> LineRasterizationMode {
+        LineRasterizationMode::Default
+    }
+}
+
+/// Stipple pattern of lines.
+///
+/// Each bit of `pattern` tells whether a pixel of the line is drawn, starting with the least
+/// significant bit. Each bit is used for `factor` consecutive pixels.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LineStipple {
+    /// Number of pixels that use each bit of the pattern. Must be between 1 and 256.
+    pub factor: u32,
+    /// Bit pattern of the line.
+    pub pattern: u16,
+}
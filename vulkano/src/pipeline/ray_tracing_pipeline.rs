@@ -0,0 +1,996 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::ffi::CStr;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::slice;
+use std::sync::Arc;
+
+use descriptor::descriptor::DescriptorDesc;
+use descriptor::descriptor_set::UnsafeDescriptorSetLayout;
+use descriptor::pipeline_layout::PipelineLayoutAbstract;
+use descriptor::pipeline_layout::PipelineLayoutDesc;
+use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+use descriptor::pipeline_layout::PipelineLayoutNotSupersetError;
+use descriptor::pipeline_layout::PipelineLayoutSuperset;
+use descriptor::pipeline_layout::PipelineLayoutSys;
+use pipeline::cache::PipelineCache;
+use pipeline::shader::EntryPointAbstract;
+use pipeline::shader::ShaderModule;
+use pipeline::shader::SpecializationConstants;
+use pipeline::shader::SpecializationMapEntry;
+
+use check_errors;
+use device::Device;
+use device::DeviceOwned;
+use vk;
+use Error;
+use OomError;
+use SafeDeref;
+use VulkanObject;
+
+/// A pipeline object that describes to the Vulkan implementation how it should trace rays.
+///
+/// A ray tracing pipeline is made of a list of shader stages, and of a list of *shader groups*
+/// that reference these stages by their index. The groups are what the shader binding table
+/// refers to when a ray is traced: one group for the ray generation shader, and any number of
+/// groups for the miss, hit and callable shaders.
+///
+/// Ray tracing pipelines require the `nv_ray_tracing` extension. Acceleration structures are not
+/// handled by vulkano yet, and must be provided to the shaders through other means.
+pub struct RayTracingPipeline<Pl> {
+    inner: Inner,
+    pipeline_layout: Pl,
+    stages: Vec<RayTracingShaderStage>,
+    groups: Vec<RayTracingShaderGroup>,
+    group_handles: Vec<u8>,
+    shader_group_handle_size: usize,
+    shader_group_base_alignment: usize,
+}
+
+struct Inner {
+    pipeline: vk::Pipeline,
+    device: Arc<Device>,
+}
+
+impl RayTracingPipeline<()> {
+    /// Starts the building process of a ray tracing pipeline. Returns a builder object that you
+    /// can fill with the various parameters.
+    #[inline]
+    pub fn start<'a>() -> RayTracingPipelineBuilder<'a> {
+        RayTracingPipelineBuilder {
+            stages: Vec::new(),
+            groups: Vec::new(),
+            max_recursion_depth: 1,
+            cache: None,
+        }
+    }
+}
+
+impl<Pl> fmt::Debug for RayTracingPipeline<Pl> {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "<Vulkan ray tracing pipeline {:?}>",
+            self.inner.pipeline
+        )
+    }
+}
+
+impl<Pl> RayTracingPipeline<Pl> {
+    /// Returns the `Device` this ray tracing pipeline was created with.
+    #[inline]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.inner.device
+    }
+
+    /// Returns the pipeline layout used in this ray tracing pipeline.
+    #[inline]
+    pub fn layout(&self) -> &Pl {
+        &self.pipeline_layout
+    }
+
+    /// Returns the shader groups of the pipeline, in the order they were added to the builder.
+    #[inline]
+    pub fn groups(&self) -> &[RayTracingShaderGroup] {
+        &self.groups
+    }
+
+    /// Returns the size in bytes of a shader group handle.
+    #[inline]
+    pub fn shader_group_handle_size(&self) -> usize {
+        self.shader_group_handle_size
+    }
+
+    /// Returns the opaque handle of a shader group, or `None` if `group` is out of range.
+    ///
+    /// The handles are what the records of a shader binding table are made of.
+    #[inline]
+    pub fn shader_group_handle(&self, group: u32) -> Option<&[u8]> {
+        let size = self.shader_group_handle_size;
+        let start = group as usize * size;
+        self.group_handles.get(start..start + size)
+    }
+
+    /// Builds the content of a shader binding table for this pipeline.
+    ///
+    /// Each parameter is a list of indices in the shader groups of the pipeline. `raygen` must
+    /// be a general group containing a ray generation shader, `miss` and `callable` must be
+    /// general groups containing respectively miss and callable shaders, and `hit` must be hit
+    /// groups.
+    ///
+    /// The returned table must be uploaded to a buffer created with the `ray_tracing` usage so
+    /// that it can be passed to `trace_rays` along with this pipeline.
+    pub fn shader_binding_table(
+        &self,
+        raygen: u32,
+        miss: &[u32],
+        hit: &[u32],
+        callable: &[u32],
+    ) -> Result<ShaderBindingTable, ShaderBindingTableError> {
+        build_shader_binding_table(
+            self.inner.pipeline,
+            &self.stages,
+            &self.groups,
+            &self.group_handles,
+            self.shader_group_handle_size,
+            self.shader_group_base_alignment,
+            raygen,
+            miss,
+            hit,
+            callable,
+        )
+    }
+}
+
+/// Trait implemented on all ray tracing pipelines.
+pub unsafe trait RayTracingPipelineAbstract: PipelineLayoutAbstract {
+    /// Returns an opaque object that represents the inside of the ray tracing pipeline.
+    fn inner(&self) -> RayTracingPipelineSys;
+}
+
+unsafe impl<Pl> RayTracingPipelineAbstract for RayTracingPipeline<Pl>
+where
+    Pl: PipelineLayoutAbstract,
+{
+    #[inline]
+    fn inner(&self) -> RayTracingPipelineSys {
+        RayTracingPipelineSys(self.inner.pipeline, PhantomData)
+    }
+}
+
+unsafe impl<T> RayTracingPipelineAbstract for T
+where
+    T: SafeDeref,
+    T::Target: RayTracingPipelineAbstract,
+{
+    #[inline]
+    fn inner(&self) -> RayTracingPipelineSys {
+        (**self).inner()
+    }
+}
+
+/// Opaque object that represents the inside of the ray tracing pipeline. Can be made into a
+/// trait object.
+#[derive(Debug, Copy, Clone)]
+pub struct RayTracingPipelineSys<'a>(vk::Pipeline, PhantomData<&'a ()>);
+
+unsafe impl<'a> VulkanObject for RayTracingPipelineSys<'a> {
+    type Object = vk::Pipeline;
+
+    const TYPE: vk::ObjectType = vk::OBJECT_TYPE_PIPELINE;
+
+    #[inline]
+    fn internal_object(&self) -> vk::Pipeline {
+        self.0
+    }
+}
+
+unsafe impl<Pl> PipelineLayoutAbstract for RayTracingPipeline<Pl>
+where
+    Pl: PipelineLayoutAbstract,
+{
+    #[inline]
+    fn sys(&self) -> PipelineLayoutSys {
+        self.layout().sys()
+    }
+
+    #[inline]
+    fn descriptor_set_layout(&self, index: usize) -> Option<&Arc<UnsafeDescriptorSetLayout>> {
+        self.layout().descriptor_set_layout(index)
+    }
+}
+
+unsafe impl<Pl> PipelineLayoutDesc for RayTracingPipeline<Pl>
+where
+    Pl: PipelineLayoutDesc,
+{
+    #[inline]
+    fn num_sets(&self) -> usize {
+        self.pipeline_layout.num_sets()
+    }
+
+    #[inline]
+    fn num_bindings_in_set(&self, set: usize) -> Option<usize> {
+        self.pipeline_layout.num_bindings_in_set(set)
+    }
+
+    #[inline]
+    fn descriptor(&self, set: usize, binding: usize) -> Option<DescriptorDesc> {
+        self.pipeline_layout.descriptor(set, binding)
+    }
+
+    #[inline]
+    fn num_push_constants_ranges(&self) -> usize {
+        self.pipeline_layout.num_push_constants_ranges()
+    }
+
+    #[inline]
+    fn push_constants_range(&self, num: usize) -> Option<PipelineLayoutDescPcRange> {
+        self.pipeline_layout.push_constants_range(num)
+    }
+}
+
+unsafe impl<Pl> DeviceOwned for RayTracingPipeline<Pl> {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.device()
+    }
+}
+
+unsafe impl<Pl> VulkanObject for RayTracingPipeline<Pl> {
+    type Object = vk::Pipeline;
+
+    const TYPE: vk::ObjectType = vk::OBJECT_TYPE_PIPELINE;
+
+    #[inline]
+    fn internal_object(&self) -> vk::Pipeline {
+        self.inner.pipeline
+    }
+}
+
+impl Drop for Inner {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let vk = self.device.pointers();
+            vk.DestroyPipeline(self.device.internal_object(), self.pipeline, ptr::null());
+        }
+    }
+}
+
+/// Prototype for a `RayTracingPipeline`.
+pub struct RayTracingPipelineBuilder<'a> {
+    stages: Vec<StageDesc<'a>>,
+    groups: Vec<RayTracingShaderGroup>,
+    max_recursion_depth: u32,
+    cache: Option<Arc<PipelineCache>>,
+}
+
+struct StageDesc<'a> {
+    stage: RayTracingShaderStage,
+    module: &'a ShaderModule,
+    name: &'a CStr,
+    layout: &'a dyn PipelineLayoutDesc,
+    spec_descriptors: &'static [SpecializationMapEntry],
+    spec_data: &'a [u8],
+}
+
+impl<'a> RayTracingPipelineBuilder<'a> {
+    /// Adds a shader stage to the pipeline. The stages are numbered in the order they are added,
+    /// starting from 0, and are referenced by their number in the shader groups.
+    ///
+    /// The `vulkano-shaders` crate can't generate the entry points of ray tracing shaders. They
+    /// can be obtained with `ShaderModule::compute_entry_point`, with a layout whose stages are
+    /// the ray tracing stages that use it.
+    pub fn stage<E>(
+        mut self,
+        stage: RayTracingShaderStage,
+        entry_point: &'a E,
+        specialization: &'a E::SpecializationConstants,
+    ) -> Self
+    where
+        E: EntryPointAbstract,
+        E::PipelineLayout: 'a,
+    {
+        let spec_data = unsafe {
+            slice::from_raw_parts(
+                specialization as *const E::SpecializationConstants as *const u8,
+                mem::size_of_val(specialization),
+            )
+        };
+
+        self.stages.push(StageDesc {
+            stage: stage,
+            module: entry_point.module(),
+            name: entry_point.name(),
+            layout: entry_point.layout(),
+            spec_descriptors: E::SpecializationConstants::descriptors(),
+            spec_data: spec_data,
+        });
+        self
+    }
+
+    /// Adds a shader group to the pipeline. The groups are numbered in the order they are added,
+    /// starting from 0.
+    #[inline]
+    pub fn group(mut self, group: RayTracingShaderGroup) -> Self {
+        self.groups.push(group);
+        self
+    }
+
+    /// Sets the maximum number of nested `traceNV` calls that the shaders can perform. The
+    /// default value is 1.
+    #[inline]
+    pub fn max_recursion_depth(mut self, depth: u32) -> Self {
+        self.max_recursion_depth = depth;
+        self
+    }
+
+    /// Sets the pipeline cache to use when creating the pipeline.
+    #[inline]
+    pub fn cache(mut self, cache: Arc<PipelineCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Builds the ray tracing pipeline, using `pipeline_layout` as the layout.
+    ///
+    /// An error will be returned if the pipeline layout isn't a superset of what the shaders
+    /// use.
+    pub fn build<Pl>(
+        self,
+        device: Arc<Device>,
+        pipeline_layout: Pl,
+    ) -> Result<RayTracingPipeline<Pl>, RayTracingPipelineCreationError>
+    where
+        Pl: PipelineLayoutAbstract,
+    {
+        if !device.loaded_extensions().nv_ray_tracing {
+            return Err(RayTracingPipelineCreationError::ExtensionNotEnabled);
+        }
+
+        let properties = ray_tracing_properties(&device);
+
+        if self.max_recursion_depth > properties.maxRecursionDepth {
+            return Err(RayTracingPipelineCreationError::MaxRecursionDepthExceeded {
+                max: properties.maxRecursionDepth,
+                obtained: self.max_recursion_depth,
+            });
+        }
+
+        if !self
+            .stages
+            .iter()
+            .any(|s| s.stage == RayTracingShaderStage::Raygen)
+        {
+            return Err(RayTracingPipelineCreationError::NoRaygenShader);
+        }
+
+        if self.groups.is_empty() {
+            return Err(RayTracingPipelineCreationError::NoShaderGroups);
+        }
+
+        let stages: Vec<_> = self.stages.iter().map(|s| s.stage).collect();
+        for (num, group) in self.groups.iter().enumerate() {
+            if !group.is_valid(&stages) {
+                return Err(RayTracingPipelineCreationError::InvalidShaderGroup {
+                    group: num as u32,
+                });
+            }
+        }
+
+        for stage in self.stages.iter() {
+            PipelineLayoutSuperset::ensure_superset_of(&pipeline_layout, stage.layout)?;
+        }
+
+        let vk = device.pointers();
+
+        let pipeline = unsafe {
+            let specializations: Vec<_> = self
+                .stages
+                .iter()
+                .map(|stage| vk::SpecializationInfo {
+                    mapEntryCount: stage.spec_descriptors.len() as u32,
+                    pMapEntries: stage.spec_descriptors.as_ptr() as *const _,
+                    dataSize: stage.spec_data.len(),
+                    pData: stage.spec_data.as_ptr() as *const _,
+                })
+                .collect();
+
+            let vk_stages: Vec<_> = self
+                .stages
+                .iter()
+                .zip(specializations.iter())
+                .map(
+                    |(stage, specialization)| vk::PipelineShaderStageCreateInfo {
+                        sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
+                        pNext: ptr::null(),
+                        flags: 0,
+                        stage: stage.stage as u32,
+                        module: stage.module.internal_object(),
+                        pName: stage.name.as_ptr(),
+                        pSpecializationInfo: if specialization.dataSize == 0 {
+                            ptr::null()
+                        } else {
+                            specialization
+                        },
+                    },
+                )
+                .collect();
+
+            let vk_groups: Vec<_> = self.groups.iter().map(|g| g.to_vulkan()).collect();
+
+            let infos = vk::RayTracingPipelineCreateInfoNV {
+                sType: vk::STRUCTURE_TYPE_RAY_TRACING_PIPELINE_CREATE_INFO_NV,
+                pNext: ptr::null(),
+                flags: 0,
+                stageCount: vk_stages.len() as u32,
+                pStages: vk_stages.as_ptr(),
+                groupCount: vk_groups.len() as u32,
+                pGroups: vk_groups.as_ptr(),
+                maxRecursionDepth: self.max_recursion_depth,
+                layout: PipelineLayoutAbstract::sys(&pipeline_layout).internal_object(),
+                basePipelineHandle: 0,
+                basePipelineIndex: -1,
+            };
+
+            let cache_handle = match self.cache {
+                Some(ref cache) => {
+                    assert_eq!(cache.device().internal_object(), device.internal_object());
+                    cache.internal_object()
+                }
+                None => 0,
+            };
+
+            let mut output = MaybeUninit::uninit();
+            check_errors(vk.CreateRayTracingPipelinesNV(
+                device.internal_object(),
+                cache_handle,
+                1,
+                &infos,
+                ptr::null(),
+                output.as_mut_ptr(),
+            ))?;
+            output.assume_init()
+        };
+
+        let inner = Inner {
+            device: device.clone(),
+            pipeline: pipeline,
+        };
+
+        let shader_group_handle_size = properties.shaderGroupHandleSize as usize;
+        let mut group_handles = vec![0; shader_group_handle_size * self.groups.len()];
+        unsafe {
+            check_errors(vk.GetRayTracingShaderGroupHandlesNV(
+                device.internal_object(),
+                pipeline,
+                0,
+                self.groups.len() as u32,
+                group_handles.len(),
+                group_handles.as_mut_ptr() as *mut _,
+            ))?;
+        }
+
+        Ok(RayTracingPipeline {
+            inner: inner,
+            pipeline_layout: pipeline_layout,
+            stages: stages,
+            groups: self.groups,
+            group_handles: group_handles,
+            shader_group_handle_size: shader_group_handle_size,
+            shader_group_base_alignment: properties.shaderGroupBaseAlignment as usize,
+        })
+    }
+}
+
+/// Queries the ray tracing properties of the physical device of `device`.
+fn ray_tracing_properties(device: &Device) -> vk::PhysicalDeviceRayTracingPropertiesNV {
+    unsafe {
+        let vk_i = device.instance().pointers();
+
+        let mut ray_tracing_properties = vk::PhysicalDeviceRayTracingPropertiesNV {
+            sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_RAY_TRACING_PROPERTIES_NV,
+            pNext: ptr::null_mut(),
+            shaderGroupHandleSize: 0,
+            maxRecursionDepth: 0,
+            maxShaderGroupStride: 0,
+            shaderGroupBaseAlignment: 0,
+            maxGeometryCount: 0,
+            maxInstanceCount: 0,
+            maxTriangleCount: 0,
+            maxDescriptorSetAccelerationStructures: 0,
+        };
+
+        let mut output = vk::PhysicalDeviceProperties2KHR {
+            sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_PROPERTIES_2_KHR,
+            pNext: &mut ray_tracing_properties as *mut _ as *mut _,
+            properties: mem::zeroed(),
+        };
+
+        vk_i.GetPhysicalDeviceProperties2KHR(
+            device.physical_device().internal_object(),
+            &mut output,
+        );
+
+        ray_tracing_properties
+    }
+}
+
+/// A shader stage of a ray tracing pipeline.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum RayTracingShaderStage {
+    /// Generates the rays. Executed once for each element of the `trace_rays` dimensions.
+    Raygen = vk::SHADER_STAGE_RAYGEN_BIT_NV,
+    /// Executed for each potential intersection, and can ignore it.
+    AnyHit = vk::SHADER_STAGE_ANY_HIT_BIT_NV,
+    /// Executed for the closest intersection of a ray.
+    ClosestHit = vk::SHADER_STAGE_CLOSEST_HIT_BIT_NV,
+    /// Executed when a ray doesn't intersect anything.
+    Miss = vk::SHADER_STAGE_MISS_BIT_NV,
+    /// Computes the intersections of a ray with procedural geometry.
+    Intersection = vk::SHADER_STAGE_INTERSECTION_BIT_NV,
+    /// Executed when another shader calls `executeCallableNV`.
+    Callable = vk::SHADER_STAGE_CALLABLE_BIT_NV,
+}
+
+/// A shader group of a ray tracing pipeline. The shaders are referenced by their index in the
+/// stages of the pipeline.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RayTracingShaderGroup {
+    /// A group made of a single ray generation, miss or callable shader.
+    General { shader: u32 },
+    /// A hit group for triangle geometry.
+    TrianglesHit {
+        closest_hit: Option<u32>,
+        any_hit: Option<u32>,
+    },
+    /// A hit group for procedural geometry, whose intersections are computed by an
+    /// intersection shader.
+    ProceduralHit {
+        intersection: u32,
+        closest_hit: Option<u32>,
+        any_hit: Option<u32>,
+    },
+}
+
+impl RayTracingShaderGroup {
+    // Returns the stage of the shader of a general group.
+    #[inline]
+    fn general_stage(&self, stages: &[RayTracingShaderStage]) -> Option<RayTracingShaderStage> {
+        match *self {
+            RayTracingShaderGroup::General { shader } => stages.get(shader as usize).cloned(),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn is_hit_group(&self) -> bool {
+        match *self {
+            RayTracingShaderGroup::General { .. } => false,
+            _ => true,
+        }
+    }
+
+    // Checks that the group references shaders of the right stages.
+    fn is_valid(&self, stages: &[RayTracingShaderStage]) -> bool {
+        let check = |shader: Option<u32>, expected: RayTracingShaderStage| match shader {
+            Some(shader) => stages.get(shader as usize) == Some(&expected),
+            None => true,
+        };
+
+        match *self {
+            RayTracingShaderGroup::General { .. } => match self.general_stage(stages) {
+                Some(RayTracingShaderStage::Raygen)
+                | Some(RayTracingShaderStage::Miss)
+                | Some(RayTracingShaderStage::Callable) => true,
+                _ => false,
+            },
+            RayTracingShaderGroup::TrianglesHit {
+                closest_hit,
+                any_hit,
+            } => {
+                check(closest_hit, RayTracingShaderStage::ClosestHit)
+                    && check(any_hit, RayTracingShaderStage::AnyHit)
+            }
+            RayTracingShaderGroup::ProceduralHit {
+                intersection,
+                closest_hit,
+                any_hit,
+            } => {
+                check(Some(intersection), RayTracingShaderStage::Intersection)
+                    && check(closest_hit, RayTracingShaderStage::ClosestHit)
+                    && check(any_hit, RayTracingShaderStage::AnyHit)
+            }
+        }
+    }
+
+    fn to_vulkan(&self) -> vk::RayTracingShaderGroupCreateInfoNV {
+        let (ty, general, closest_hit, any_hit, intersection) = match *self {
+            RayTracingShaderGroup::General { shader } => (
+                vk::RAY_TRACING_SHADER_GROUP_TYPE_GENERAL_NV,
+                Some(shader),
+                None,
+                None,
+                None,
+            ),
+            RayTracingShaderGroup::TrianglesHit {
+                closest_hit,
+                any_hit,
+            } => (
+                vk::RAY_TRACING_SHADER_GROUP_TYPE_TRIANGLES_HIT_GROUP_NV,
+                None,
+                closest_hit,
+                any_hit,
+                None,
+            ),
+            RayTracingShaderGroup::ProceduralHit {
+                intersection,
+                closest_hit,
+                any_hit,
+            } => (
+                vk::RAY_TRACING_SHADER_GROUP_TYPE_PROCEDURAL_HIT_GROUP_NV,
+                None,
+                closest_hit,
+                any_hit,
+                Some(intersection),
+            ),
+        };
+
+        vk::RayTracingShaderGroupCreateInfoNV {
+            sType: vk::STRUCTURE_TYPE_RAY_TRACING_SHADER_GROUP_CREATE_INFO_NV,
+            pNext: ptr::null(),
+            type_: ty,
+            generalShader: general.unwrap_or(vk::SHADER_UNUSED_NV),
+            closestHitShader: closest_hit.unwrap_or(vk::SHADER_UNUSED_NV),
+            anyHitShader: any_hit.unwrap_or(vk::SHADER_UNUSED_NV),
+            intersectionShader: intersection.unwrap_or(vk::SHADER_UNUSED_NV),
+        }
+    }
+}
+
+/// Content of a shader binding table, built with `RayTracingPipeline::shader_binding_table`.
+///
+/// The table is made of four regions, one for each kind of shader, that contain the handles of
+/// shader groups. Each region starts at a multiple of the `shaderGroupBaseAlignment` limit of the
+/// device, as required by Vulkan.
+#[derive(Debug, Clone)]
+pub struct ShaderBindingTable {
+    pipeline: vk::Pipeline,
+    data: Vec<u8>,
+    regions: ShaderBindingTableRegions,
+    base_alignment: usize,
+}
+
+impl ShaderBindingTable {
+    /// Returns the bytes of the table, to be copied at the start of a buffer.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the location of the regions of the table within the data.
+    #[inline]
+    pub fn regions(&self) -> ShaderBindingTableRegions {
+        self.regions
+    }
+
+    /// Returns the alignment that the start of the buffer containing the table must respect.
+    #[inline]
+    pub fn base_alignment(&self) -> usize {
+        self.base_alignment
+    }
+
+    /// Returns true if the table was built by `pipeline`.
+    #[inline]
+    pub fn is_for_pipeline<P>(&self, pipeline: &P) -> bool
+    where
+        P: ?Sized + RayTracingPipelineAbstract,
+    {
+        self.pipeline == pipeline.inner().internal_object()
+    }
+}
+
+/// Location of the four regions of a shader binding table.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ShaderBindingTableRegions {
+    pub raygen: ShaderBindingTableRegion,
+    pub miss: ShaderBindingTableRegion,
+    pub hit: ShaderBindingTableRegion,
+    pub callable: ShaderBindingTableRegion,
+}
+
+/// Location of a region of a shader binding table.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ShaderBindingTableRegion {
+    /// Offset in bytes of the region from the start of the table.
+    pub offset: usize,
+    /// Distance in bytes between two records of the region.
+    pub stride: usize,
+    /// Number of records in the region.
+    pub len: usize,
+}
+
+fn build_shader_binding_table(
+    pipeline: vk::Pipeline,
+    stages: &[RayTracingShaderStage],
+    groups: &[RayTracingShaderGroup],
+    group_handles: &[u8],
+    handle_size: usize,
+    base_alignment: usize,
+    raygen: u32,
+    miss: &[u32],
+    hit: &[u32],
+    callable: &[u32],
+) -> Result<ShaderBindingTable, ShaderBindingTableError> {
+    let check = |indices: &[u32], valid: &dyn Fn(&RayTracingShaderGroup) -> bool| {
+        for &group in indices {
+            match groups.get(group as usize) {
+                Some(g) if valid(g) => (),
+                Some(_) => return Err(ShaderBindingTableError::WrongGroupKind { group: group }),
+                None => return Err(ShaderBindingTableError::GroupOutOfRange { group: group }),
+            }
+        }
+        Ok(())
+    };
+    let general =
+        |expected| move |g: &RayTracingShaderGroup| g.general_stage(stages) == Some(expected);
+
+    check(&[raygen], &general(RayTracingShaderStage::Raygen))?;
+    check(miss, &general(RayTracingShaderStage::Miss))?;
+    check(hit, &|g| g.is_hit_group())?;
+    check(callable, &general(RayTracingShaderStage::Callable))?;
+
+    let align = |offset: usize| (offset + base_alignment - 1) / base_alignment * base_alignment;
+
+    let mut data = Vec::new();
+    let mut region = |indices: &[u32]| {
+        let offset = align(data.len());
+        data.resize(offset, 0);
+        for &group in indices {
+            let start = group as usize * handle_size;
+            data.extend_from_slice(&group_handles[start..start + handle_size]);
+        }
+        ShaderBindingTableRegion {
+            offset: offset,
+            stride: handle_size,
+            len: indices.len(),
+        }
+    };
+
+    let regions = ShaderBindingTableRegions {
+        raygen: region(&[raygen]),
+        miss: region(miss),
+        hit: region(hit),
+        callable: region(callable),
+    };
+
+    Ok(ShaderBindingTable {
+        pipeline: pipeline,
+        data: data,
+        regions: regions,
+        base_alignment: base_alignment,
+    })
+}
+
+/// Error that can happen when creating a ray tracing pipeline.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RayTracingPipelineCreationError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// The `nv_ray_tracing` extension must be enabled in order to create ray tracing pipelines.
+    ExtensionNotEnabled,
+    /// The requested maximum recursion depth is higher than what the device supports.
+    MaxRecursionDepthExceeded {
+        /// Maximum allowed value.
+        max: u32,
+        /// Value that was passed.
+        obtained: u32,
+    },
+    /// The pipeline doesn't contain any ray generation shader.
+    NoRaygenShader,
+    /// The pipeline doesn't contain any shader group.
+    NoShaderGroups,
+    /// A shader group references a shader that doesn't exist or that is of the wrong stage.
+    InvalidShaderGroup {
+        /// Index of the faulty group.
+        group: u32,
+    },
+    /// The pipeline layout is not compatible with what the shaders expect.
+    IncompatiblePipelineLayout(PipelineLayoutNotSupersetError),
+}
+
+impl error::Error for RayTracingPipelineCreationError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            RayTracingPipelineCreationError::OomError(ref err) => Some(err),
+            RayTracingPipelineCreationError::IncompatiblePipelineLayout(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RayTracingPipelineCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                RayTracingPipelineCreationError::OomError(_) => "not enough memory available",
+                RayTracingPipelineCreationError::ExtensionNotEnabled => {
+                    "the `nv_ray_tracing` extension is not enabled"
+                }
+                RayTracingPipelineCreationError::MaxRecursionDepthExceeded { .. } => {
+                    "the maximum recursion depth is higher than what the device supports"
+                }
+                RayTracingPipelineCreationError::NoRaygenShader => {
+                    "the pipeline doesn't contain any ray generation shader"
+                }
+                RayTracingPipelineCreationError::NoShaderGroups => {
+                    "the pipeline doesn't contain any shader group"
+                }
+                RayTracingPipelineCreationError::InvalidShaderGroup { .. } => {
+                    "a shader group references a shader that doesn't exist or that is of the \
+                     wrong stage"
+                }
+                RayTracingPipelineCreationError::IncompatiblePipelineLayout(_) => {
+                    "the pipeline layout is not compatible with what the shaders expect"
+                }
+            }
+        )
+    }
+}
+
+impl From<OomError> for RayTracingPipelineCreationError {
+    #[inline]
+    fn from(err: OomError) -> RayTracingPipelineCreationError {
+        RayTracingPipelineCreationError::OomError(err)
+    }
+}
+
+impl From<PipelineLayoutNotSupersetError> for RayTracingPipelineCreationError {
+    #[inline]
+    fn from(err: PipelineLayoutNotSupersetError) -> RayTracingPipelineCreationError {
+        RayTracingPipelineCreationError::IncompatiblePipelineLayout(err)
+    }
+}
+
+impl From<Error> for RayTracingPipelineCreationError {
+    #[inline]
+    fn from(err: Error) -> RayTracingPipelineCreationError {
+        match err {
+            err @ Error::OutOfHostMemory => {
+                RayTracingPipelineCreationError::OomError(OomError::from(err))
+            }
+            err @ Error::OutOfDeviceMemory => {
+                RayTracingPipelineCreationError::OomError(OomError::from(err))
+            }
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+/// Error that can happen when building a shader binding table.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ShaderBindingTableError {
+    /// A group index is out of range of the groups of the pipeline.
+    GroupOutOfRange {
+        /// Index of the faulty group.
+        group: u32,
+    },
+    /// A group is not of the kind expected by its region of the table.
+    WrongGroupKind {
+        /// Index of the faulty group.
+        group: u32,
+    },
+}
+
+impl error::Error for ShaderBindingTableError {}
+
+impl fmt::Display for ShaderBindingTableError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                ShaderBindingTableError::GroupOutOfRange { .. } => {
+                    "a group index is out of range of the groups of the pipeline"
+                }
+                ShaderBindingTableError::WrongGroupKind { .. } => {
+                    "a group is not of the kind expected by its region of the table"
+                }
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_shader_binding_table;
+    use descriptor::pipeline_layout::EmptyPipelineDesc;
+    use descriptor::pipeline_layout::PipelineLayoutDesc;
+    use pipeline::RayTracingPipeline;
+    use pipeline::RayTracingPipelineCreationError;
+    use pipeline::RayTracingShaderGroup;
+    use pipeline::RayTracingShaderStage;
+    use pipeline::ShaderBindingTableError;
+
+    #[test]
+    fn extension_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+        let layout = EmptyPipelineDesc.build(device.clone()).unwrap();
+
+        match RayTracingPipeline::start().build(device, layout) {
+            Err(RayTracingPipelineCreationError::ExtensionNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn binding_table_alignment() {
+        let stages = [
+            RayTracingShaderStage::Raygen,
+            RayTracingShaderStage::Miss,
+            RayTracingShaderStage::ClosestHit,
+        ];
+        let groups = [
+            RayTracingShaderGroup::General { shader: 0 },
+            RayTracingShaderGroup::General { shader: 1 },
+            RayTracingShaderGroup::TrianglesHit {
+                closest_hit: Some(2),
+                any_hit: None,
+            },
+        ];
+        let handles: Vec<u8> = (0..48).collect();
+
+        let table = build_shader_binding_table(
+            0,
+            &stages,
+            &groups,
+            &handles,
+            16,
+            64,
+            0,
+            &[1, 1],
+            &[2],
+            &[],
+        )
+        .unwrap();
+        let regions = table.regions();
+        assert_eq!(regions.raygen.offset, 0);
+        assert_eq!(regions.miss.offset, 64);
+        assert_eq!(regions.miss.len, 2);
+        assert_eq!(regions.hit.offset, 128);
+        assert_eq!(regions.hit.stride, 16);
+        assert_eq!(&table.data()[64..80], &handles[16..32]);
+        assert_eq!(&table.data()[128..144], &handles[32..48]);
+
+        assert_eq!(
+            build_shader_binding_table(0, &stages, &groups, &handles, 16, 64, 1, &[], &[], &[])
+                .unwrap_err(),
+            ShaderBindingTableError::WrongGroupKind { group: 1 }
+        );
+        assert_eq!(
+            build_shader_binding_table(0, &stages, &groups, &handles, 16, 64, 0, &[], &[3], &[])
+                .unwrap_err(),
+            ShaderBindingTableError::GroupOutOfRange { group: 3 }
+        );
+    }
+}
@@ -286,6 +286,8 @@ pub enum GraphicsShaderType {
     TessellationEvaluation,
     Geometry(GeometryShaderExecutionMode),
     Fragment,
+    Task,
+    Mesh,
 }
 
 /// Declares which type of primitives are expected by the geometry shader.
@@ -299,6 +301,16 @@ pub enum GeometryShaderExecutionMode {
 }
 
 impl GeometryShaderExecutionMode {
+    /// Returns true if the geometry shader expects primitives with adjacency information.
+    #[inline]
+    pub fn has_adjacency(&self) -> bool {
+        match *self {
+            GeometryShaderExecutionMode::LinesWithAdjacency => true,
+            GeometryShaderExecutionMode::TrianglesWithAdjacency => true,
+            _ => false,
+        }
+    }
+
     /// Returns true if the given primitive topology can be used with this execution mode.
     #[inline]
     pub fn matches(&self, input: PrimitiveTopology) -> bool {
@@ -641,6 +653,9 @@ impl fmt::Display for ShaderInterfaceMismatchError {
 /// - The size of each `SpecializationMapEntry` must match the size of the corresponding constant
 ///   (`4` for booleans).
 ///
+/// When a pipeline is built, vulkano panics if two entries have the same `constant_id` or if an
+/// entry is out of the range of the struct.
+///
 pub unsafe trait SpecializationConstants {
     /// Returns descriptors of the struct's layout.
     fn descriptors() -> &'static [SpecializationMapEntry];
@@ -671,3 +686,99 @@ pub struct SpecializationMapEntry {
     /// Size of the data in bytes. Must match the size of the constant (`4` for booleans).
     pub size: usize,
 }
+
+// Builds the `VkSpecializationInfo` that applies `constants` to a shader stage of a pipeline.
+//
+// Panics if two entries have the same constant ID, or if an entry is out of the range of
+// `constants`.
+pub(crate) fn specialization_info<S>(constants: &S) -> vk::SpecializationInfo
+where
+    S: SpecializationConstants,
+{
+    let descriptors = S::descriptors();
+    let data_size = mem::size_of_val(constants);
+
+    for (index, entry) in descriptors.iter().enumerate() {
+        assert!(
+            entry.size <= data_size && entry.offset as usize <= data_size - entry.size,
+            "specialization constant {} is out of the range of the struct",
+            entry.constant_id
+        );
+        assert!(
+            descriptors[..index]
+                .iter()
+                .all(|other| other.constant_id != entry.constant_id),
+            "specialization constant {} is declared twice",
+            entry.constant_id
+        );
+    }
+
+    vk::SpecializationInfo {
+        mapEntryCount: descriptors.len() as u32,
+        pMapEntries: descriptors.as_ptr() as *const _,
+        dataSize: data_size,
+        pData: constants as *const S as *const _,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pipeline::shader::specialization_info;
+    use pipeline::shader::SpecializationConstants;
+    use pipeline::shader::SpecializationMapEntry;
+
+    #[repr(C)]
+    struct Constants {
+        a: i32,
+        b: f32,
+    }
+
+    #[test]
+    fn duplicate_constant_id() {
+        struct Duplicate(Constants);
+
+        unsafe impl SpecializationConstants for Duplicate {
+            fn descriptors() -> &'static [SpecializationMapEntry] {
+                static DESCRIPTORS: [SpecializationMapEntry; 2] = [
+                    SpecializationMapEntry {
+                        constant_id: 0,
+                        offset: 0,
+                        size: 4,
+                    },
+                    SpecializationMapEntry {
+                        constant_id: 0,
+                        offset: 4,
+                        size: 4,
+                    },
+                ];
+
+                &DESCRIPTORS
+            }
+        }
+
+        assert_should_panic!("declared twice", {
+            specialization_info(&Duplicate(Constants { a: 0, b: 0.0 }));
+        });
+    }
+
+    #[test]
+    fn constant_out_of_range() {
+        struct OutOfRange(Constants);
+
+        unsafe impl SpecializationConstants for OutOfRange {
+            fn descriptors() -> &'static [SpecializationMapEntry] {
+                static DESCRIPTORS: [SpecializationMapEntry; 1] = [SpecializationMapEntry {
+                    constant_id: 0,
+                    offset: 6,
+                    size: 4,
+                }];
+
+                &DESCRIPTORS
+            }
+        }
+
+        assert_should_panic!("out of the range", {
+            specialization_info(&OutOfRange(Constants { a: 0, b: 0.0 }));
+        });
+    }
+}
@@ -52,13 +52,27 @@ where
 }
 
 /// How the vertex source should be unrolled.
-#[derive(Copy, Clone, Debug)]
-#[repr(u32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum InputRate {
     /// Each element of the source corresponds to a vertex.
-    Vertex = vk::VERTEX_INPUT_RATE_VERTEX,
-    /// Each element of the source corresponds to an instance.
-    Instance = vk::VERTEX_INPUT_RATE_INSTANCE,
+    Vertex,
+    /// Each element of the source corresponds to `divisor` consecutive instances.
+    ///
+    /// A divisor different from 1 requires the `ext_vertex_attribute_divisor` extension and the
+    /// `vertex_attribute_instance_rate_divisor` feature. A divisor of 0 means that all the
+    /// instances use the same element, and requires the
+    /// `vertex_attribute_instance_rate_zero_divisor` feature.
+    Instance { divisor: u32 },
+}
+
+impl From<InputRate> for vk::VertexInputRate {
+    #[inline]
+    fn from(val: InputRate) -> Self {
+        match val {
+            InputRate::Vertex => vk::VERTEX_INPUT_RATE_VERTEX,
+            InputRate::Instance { .. } => vk::VERTEX_INPUT_RATE_INSTANCE,
+        }
+    }
 }
 
 /// Information about a single attribute within a vertex.
@@ -96,17 +110,23 @@ impl error::Error for IncompatibleVertexDefinitionError {}
 impl fmt::Display for IncompatibleVertexDefinitionError {
     #[inline]
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        write!(
-            fmt,
-            "{}",
-            match *self {
-                IncompatibleVertexDefinitionError::MissingAttribute { .. } =>
-                    "an attribute is missing",
-                IncompatibleVertexDefinitionError::FormatMismatch { .. } => {
-                    "the format of an attribute does not match"
-                }
-            }
-        )
+        match *self {
+            IncompatibleVertexDefinitionError::MissingAttribute { ref attribute } => write!(
+                fmt,
+                "the attribute `{}` of the vertex shader is missing in the vertex definition",
+                attribute
+            ),
+            IncompatibleVertexDefinitionError::FormatMismatch {
+                ref attribute,
+                shader,
+                definition,
+            } => write!(
+                fmt,
+                "the format of the attribute `{}` does not match: the vertex shader expects \
+                 {:?} over {} location(s), but the vertex definition has {:?} x {}",
+                attribute, shader.0, shader.1, definition.0, definition.1
+            ),
+        }
     }
 }
 
@@ -24,12 +24,26 @@ use pipeline::vertex::VertexDefinition;
 use pipeline::vertex::VertexSource;
 
 /// Same as `SingleBufferDefinition` but advances by instance.
-pub struct SingleInstanceBufferDefinition<T>(pub PhantomData<T>);
+pub struct SingleInstanceBufferDefinition<T> {
+    marker: PhantomData<T>,
+    // Number of consecutive instances that use the same element of the buffer.
+    divisor: u32,
+}
 
 impl<T> SingleInstanceBufferDefinition<T> {
     #[inline]
     pub fn new() -> SingleInstanceBufferDefinition<T> {
-        SingleInstanceBufferDefinition(PhantomData)
+        SingleInstanceBufferDefinition::with_divisor(1)
+    }
+
+    /// Same as `new`, but each element of the buffer is used by `divisor` consecutive instances.
+    /// See `InputRate::Instance`.
+    #[inline]
+    pub fn with_divisor(divisor: u32) -> SingleInstanceBufferDefinition<T> {
+        SingleInstanceBufferDefinition {
+            marker: PhantomData,
+            divisor: divisor,
+        }
     }
 }
 
@@ -88,7 +102,14 @@ where
         }
         .into_iter(); // TODO: meh
 
-        let buffers = Some((0, mem::size_of::<T>(), InputRate::Instance)).into_iter();
+        let buffers = Some((
+            0,
+            mem::size_of::<T>(),
+            InputRate::Instance {
+                divisor: self.divisor,
+            },
+        ))
+        .into_iter();
         Ok((buffers, attrib))
     }
 }
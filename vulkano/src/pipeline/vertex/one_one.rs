@@ -24,12 +24,26 @@ use pipeline::vertex::VertexSource;
 
 /// Unstable.
 // TODO: bad way to do things
-pub struct OneVertexOneInstanceDefinition<T, U>(pub PhantomData<(T, U)>);
+pub struct OneVertexOneInstanceDefinition<T, U> {
+    marker: PhantomData<(T, U)>,
+    // Number of consecutive instances that use the same element of the instance buffer.
+    divisor: u32,
+}
 
 impl<T, U> OneVertexOneInstanceDefinition<T, U> {
     #[inline]
     pub fn new() -> OneVertexOneInstanceDefinition<T, U> {
-        OneVertexOneInstanceDefinition(PhantomData)
+        OneVertexOneInstanceDefinition::with_divisor(1)
+    }
+
+    /// Same as `new`, but each element of the instance buffer is used by `divisor` consecutive
+    /// instances. See `InputRate::Instance`.
+    #[inline]
+    pub fn with_divisor(divisor: u32) -> OneVertexOneInstanceDefinition<T, U> {
+        OneVertexOneInstanceDefinition {
+            marker: PhantomData,
+            divisor: divisor,
+        }
     }
 }
 
@@ -92,7 +106,13 @@ where
 
         let buffers = vec![
             (0, mem::size_of::<T>(), InputRate::Vertex),
-            (1, mem::size_of::<U>(), InputRate::Instance),
+            (
+                1,
+                mem::size_of::<U>(),
+                InputRate::Instance {
+                    divisor: self.divisor,
+                },
+            ),
         ]
         .into_iter();
 
@@ -143,3 +163,28 @@ where
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pipeline::shader::EmptyShaderInterfaceDef;
+    use pipeline::vertex::InputRate;
+    use pipeline::vertex::OneVertexOneInstanceDefinition;
+    use pipeline::vertex::VertexDefinition;
+
+    #[test]
+    fn instance_divisor() {
+        let definition = OneVertexOneInstanceDefinition::<(), ()>::new();
+        let (buffers, _) = definition.definition(&EmptyShaderInterfaceDef).unwrap();
+        assert_eq!(
+            buffers.map(|(_, _, rate)| rate).collect::<Vec<_>>(),
+            vec![InputRate::Vertex, InputRate::Instance { divisor: 1 }]
+        );
+
+        let definition = OneVertexOneInstanceDefinition::<(), ()>::with_divisor(4);
+        let (buffers, _) = definition.definition(&EmptyShaderInterfaceDef).unwrap();
+        assert_eq!(
+            buffers.map(|(_, _, rate)| rate).collect::<Vec<_>>(),
+            vec![InputRate::Vertex, InputRate::Instance { divisor: 4 }]
+        );
+    }
+}
@@ -48,6 +48,7 @@
 //! In all cases the number of viewports and scissor boxes must be the same.
 //!
 
+use instance::PhysicalDevice;
 use std::ops::Range;
 use vk;
 
@@ -142,6 +143,72 @@ pub struct Viewport {
 }
 
 impl Viewport {
+    /// Builds a viewport that covers an image of the given dimensions, with a depth range of
+    /// `0.0` to `1.0`.
+    ///
+    /// Passing the dimensions of a swapchain, as returned by `Swapchain::dimensions`, gives a
+    /// viewport that covers the whole window.
+    #[inline]
+    pub fn from_dimensions(dimensions: [u32; 2]) -> Viewport {
+        Viewport {
+            origin: [0.0, 0.0],
+            dimensions: [dimensions[0] as f32, dimensions[1] as f32],
+            depth_range: 0.0..1.0,
+        }
+    }
+
+    /// Builds a viewport that covers an image of the given dimensions, but with the Y axis
+    /// pointing upwards as in OpenGL.
+    ///
+    /// This is equivalent to `Viewport::from_dimensions(dimensions).flipped_y()`, and requires
+    /// the `khr_maintenance1` extension.
+    #[inline]
+    pub fn from_dimensions_flipped_y(dimensions: [u32; 2]) -> Viewport {
+        Viewport::from_dimensions(dimensions).flipped_y()
+    }
+
+    /// Returns the same viewport with the Y axis reversed.
+    ///
+    /// The origin is moved to the bottom of the viewport and its height is made negative, so that
+    /// a Y coordinate of `-1.0` is at the bottom of the viewport instead of the top.
+    ///
+    /// Viewports with a negative height require the `khr_maintenance1` extension.
+    #[inline]
+    pub fn flipped_y(self) -> Viewport {
+        Viewport {
+            origin: [self.origin[0], self.origin[1] + self.dimensions[1]],
+            dimensions: [self.dimensions[0], -self.dimensions[1]],
+            depth_range: self.depth_range,
+        }
+    }
+
+    /// Returns true if the height of the viewport is negative, which means that its Y axis is
+    /// reversed.
+    #[inline]
+    pub fn is_flipped_y(&self) -> bool {
+        self.dimensions[1] < 0.0
+    }
+
+    /// Returns true if the origin or the dimensions of the viewport are not whole numbers of
+    /// pixels.
+    #[inline]
+    pub fn is_fractional(&self) -> bool {
+        self.origin
+            .iter()
+            .chain(self.dimensions.iter())
+            .any(|v| v.fract() != 0.0)
+    }
+
+    /// Returns true if the physical device takes the fractional part of the origin and dimensions
+    /// of viewports into account.
+    ///
+    /// The precision is given by the `viewport_sub_pixel_bits` limit. If it is 0, the device
+    /// rounds fractional viewports to whole pixels.
+    #[inline]
+    pub fn supports_fractional(physical_device: PhysicalDevice) -> bool {
+        physical_device.limits().viewport_sub_pixel_bits() > 0
+    }
+
     #[inline]
     pub(crate) fn into_vulkan_viewport(self) -> vk::Viewport {
         vk::Viewport {
@@ -169,6 +236,16 @@ pub struct Scissor {
 }
 
 impl Scissor {
+    /// Defines a scissor box that covers an image of the given dimensions, such as the
+    /// dimensions of a swapchain.
+    #[inline]
+    pub fn from_dimensions(dimensions: [u32; 2]) -> Scissor {
+        Scissor {
+            origin: [0, 0],
+            dimensions: dimensions,
+        }
+    }
+
     /// Defines a scissor box that it outside of the image.
     #[inline]
     pub fn irrelevant() -> Scissor {
@@ -199,3 +276,42 @@ impl Default for Scissor {
         Scissor::irrelevant()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pipeline::viewport::Scissor;
+    use pipeline::viewport::Viewport;
+
+    #[test]
+    fn from_dimensions() {
+        let viewport = Viewport::from_dimensions([800, 600]);
+        assert_eq!(viewport.origin, [0.0, 0.0]);
+        assert_eq!(viewport.dimensions, [800.0, 600.0]);
+        assert_eq!(viewport.depth_range, 0.0..1.0);
+        assert!(!viewport.is_flipped_y());
+        assert!(!viewport.is_fractional());
+
+        let scissor = Scissor::from_dimensions([800, 600]);
+        assert_eq!(scissor.origin, [0, 0]);
+        assert_eq!(scissor.dimensions, [800, 600]);
+    }
+
+    #[test]
+    fn flipped_y() {
+        let viewport = Viewport::from_dimensions_flipped_y([800, 600]);
+        assert_eq!(viewport.origin, [0.0, 600.0]);
+        assert_eq!(viewport.dimensions, [800.0, -600.0]);
+        assert!(viewport.is_flipped_y());
+        assert_eq!(viewport.flipped_y(), Viewport::from_dimensions([800, 600]));
+    }
+
+    #[test]
+    fn fractional() {
+        let viewport = Viewport {
+            origin: [0.5, 0.0],
+            dimensions: [100.0, 100.0],
+            depth_range: 0.0..1.0,
+        };
+        assert!(viewport.is_fractional());
+    }
+}
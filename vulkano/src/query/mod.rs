@@ -12,10 +12,19 @@
 //! In Vulkan, queries are not created individually. Instead you manipulate **query pools**, which
 //! represent a collection of queries. Whenever you use a query, you have to specify both the query
 //! pool and the slot id within that query pool.
+//!
+//! The `QueryPool` type is used with the `begin_query`, `end_query`, `write_timestamp` and
+//! `reset_query_pool` commands of `AutoCommandBufferBuilder`. Once the command buffer has been
+//! executed, the results can be read back with `QueryPool::get_results`.
+//!
+//! On top of this, `GpuProfiler` measures the time spent by the GPU in named zones of command
+//! buffers with timestamp queries.
 
 use std::error;
 use std::fmt;
+use std::mem;
 use std::mem::MaybeUninit;
+use std::ops::Range;
 use std::ptr;
 use std::sync::Arc;
 
@@ -26,8 +35,16 @@ use check_errors;
 use vk;
 use Error;
 use OomError;
+use Success;
 use VulkanObject;
 
+pub use self::profiler::GpuProfiler;
+pub use self::profiler::ProfilerScope;
+pub use self::profiler::ProfilerScopeError;
+pub use self::profiler::ProfilerZone;
+
+mod profiler;
+
 pub struct UnsafeQueryPool {
     pool: vk::QueryPool,
     device: Arc<Device>,
@@ -104,7 +121,7 @@ impl UnsafeQueryPool {
     pub fn queries_range(&self, first_index: u32, count: u32) -> Option<UnsafeQueriesRange> {
         assert!(count >= 1);
 
-        if first_index + count < self.num_slots() {
+        if first_index + count <= self.num_slots() {
             Some(UnsafeQueriesRange {
                 pool: self,
                 first: first_index,
@@ -181,6 +198,31 @@ pub enum QueryType {
     Timestamp,
 }
 
+impl QueryType {
+    /// Returns the number of values that a query of this type writes as its result.
+    ///
+    /// This is 1 for occlusion and timestamp queries, and one value per enabled flag for pipeline
+    /// statistics queries.
+    #[inline]
+    pub fn result_size(&self) -> usize {
+        match *self {
+            QueryType::Occlusion | QueryType::Timestamp => 1,
+            QueryType::PipelineStatistics(flags) => flags.count(),
+        }
+    }
+}
+
+impl Into<vk::QueryType> for QueryType {
+    #[inline]
+    fn into(self) -> vk::QueryType {
+        match self {
+            QueryType::Occlusion => vk::QUERY_TYPE_OCCLUSION,
+            QueryType::PipelineStatistics(_) => vk::QUERY_TYPE_PIPELINE_STATISTICS,
+            QueryType::Timestamp => vk::QUERY_TYPE_TIMESTAMP,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct QueryPipelineStatisticFlags {
     pub input_assembly_vertices: bool,
@@ -213,6 +255,13 @@ impl QueryPipelineStatisticFlags {
             compute_shader_invocations: false,
         }
     }
+
+    /// Returns the number of flags that are enabled.
+    #[inline]
+    pub fn count(&self) -> usize {
+        let bits: vk::QueryPipelineStatisticFlags = (*self).into();
+        bits.count_ones() as usize
+    }
 }
 
 impl Into<vk::QueryPipelineStatisticFlags> for QueryPipelineStatisticFlags {
@@ -319,6 +368,247 @@ impl From<Error> for QueryPoolCreationError {
     }
 }
 
+/// A pool of queries of the same type, that can be used by the command buffer builder.
+///
+/// Contrary to `UnsafeQueryPool`, the pool remembers the type of its queries, which allows
+/// checking the commands that use it and reading back its results safely.
+pub struct QueryPool {
+    inner: UnsafeQueryPool,
+    ty: QueryType,
+}
+
+impl QueryPool {
+    /// Builds a new query pool with `num_queries` queries of type `ty`.
+    #[inline]
+    pub fn new(
+        device: Arc<Device>,
+        ty: QueryType,
+        num_queries: u32,
+    ) -> Result<QueryPool, QueryPoolCreationError> {
+        Ok(QueryPool {
+            inner: UnsafeQueryPool::new(device, ty, num_queries)?,
+            ty: ty,
+        })
+    }
+
+    /// Returns the type of the queries of the pool.
+    #[inline]
+    pub fn ty(&self) -> QueryType {
+        self.ty
+    }
+
+    /// Returns the number of queries of the pool.
+    #[inline]
+    pub fn num_queries(&self) -> u32 {
+        self.inner.num_slots()
+    }
+
+    /// Returns the underlying query pool.
+    #[inline]
+    pub fn inner(&self) -> &UnsafeQueryPool {
+        &self.inner
+    }
+
+    /// Copies the results of the queries in `queries` to `destination`.
+    ///
+    /// Each query writes `ty().result_size()` values, followed by an additional value if
+    /// `with_availability` is set in `flags`. Results are written as `u32` or `u64` depending on
+    /// the type of the elements of `destination`.
+    ///
+    /// Returns `Ok(false)` if `wait` is not set in `flags` and some of the results weren't
+    /// available yet. In that case, only the results that were available have been written,
+    /// unless `partial` is set.
+    pub fn get_results<T>(
+        &self,
+        queries: Range<u32>,
+        destination: &mut [T],
+        flags: QueryResultFlags,
+    ) -> Result<bool, GetResultsError>
+    where
+        T: QueryResultElement,
+    {
+        if queries.start >= queries.end || queries.end > self.num_queries() {
+            return Err(GetResultsError::OutOfRange);
+        }
+
+        if let QueryType::Timestamp = self.ty {
+            if flags.partial {
+                return Err(GetResultsError::InvalidFlags);
+            }
+        }
+
+        let per_query = self.ty.result_size() + if flags.with_availability { 1 } else { 0 };
+        let required = (queries.end - queries.start) as usize * per_query;
+        if destination.len() < required {
+            return Err(GetResultsError::BufferTooSmall {
+                required: required,
+                actual: destination.len(),
+            });
+        }
+
+        let mut vk_flags: vk::QueryResultFlags = flags.into();
+        if T::IS_64_BIT {
+            vk_flags |= vk::QUERY_RESULT_64_BIT;
+        }
+
+        unsafe {
+            let vk = self.device().pointers();
+            let result = check_errors(vk.GetQueryPoolResults(
+                self.device().internal_object(),
+                self.inner.internal_object(),
+                queries.start,
+                queries.end - queries.start,
+                mem::size_of_val(destination),
+                destination.as_mut_ptr() as *mut _,
+                (per_query * mem::size_of::<T>()) as vk::DeviceSize,
+                vk_flags,
+            ))?;
+
+            match result {
+                Success::Success => Ok(true),
+                Success::NotReady => Ok(false),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// Converts a number of timestamp ticks, usually the difference between two timestamps
+    /// written with `write_timestamp`, to nanoseconds.
+    #[inline]
+    pub fn timestamp_to_nanoseconds(&self, ticks: u64) -> f64 {
+        let period = self.device().physical_device().limits().timestamp_period();
+        ticks as f64 * period as f64
+    }
+}
+
+unsafe impl DeviceOwned for QueryPool {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.inner.device()
+    }
+}
+
+/// Type of the values that query results can be written as.
+pub unsafe trait QueryResultElement: Copy {
+    /// True if the results are written as 64-bits values.
+    const IS_64_BIT: bool;
+}
+
+unsafe impl QueryResultElement for u32 {
+    const IS_64_BIT: bool = false;
+}
+
+unsafe impl QueryResultElement for u64 {
+    const IS_64_BIT: bool = true;
+}
+
+/// Flags that control how the results of queries are read.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct QueryResultFlags {
+    /// Wait until the results of all the queries are available.
+    pub wait: bool,
+    /// Write an additional value after the results of each query, which is non-zero if they are
+    /// available.
+    pub with_availability: bool,
+    /// Allow writing partial results of queries that aren't available yet.
+    pub partial: bool,
+}
+
+impl QueryResultFlags {
+    /// Builds a `QueryResultFlags` with all the flags set to false.
+    #[inline]
+    pub fn none() -> QueryResultFlags {
+        QueryResultFlags {
+            wait: false,
+            with_availability: false,
+            partial: false,
+        }
+    }
+}
+
+impl Into<vk::QueryResultFlags> for QueryResultFlags {
+    #[inline]
+    fn into(self) -> vk::QueryResultFlags {
+        let mut result = 0;
+        if self.wait {
+            result |= vk::QUERY_RESULT_WAIT_BIT;
+        }
+        if self.with_availability {
+            result |= vk::QUERY_RESULT_WITH_AVAILABILITY_BIT;
+        }
+        if self.partial {
+            result |= vk::QUERY_RESULT_PARTIAL_BIT;
+        }
+        result
+    }
+}
+
+/// Error that can happen when reading the results of queries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GetResultsError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// The connection to the device has been lost.
+    DeviceLost,
+    /// The range of queries is empty or is out of the range of the pool.
+    OutOfRange,
+    /// The destination doesn't have enough elements to hold the results.
+    BufferTooSmall {
+        /// Required number of elements.
+        required: usize,
+        /// Number of elements of the destination.
+        actual: usize,
+    },
+    /// The `partial` flag was used with timestamp queries.
+    InvalidFlags,
+}
+
+impl error::Error for GetResultsError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            GetResultsError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GetResultsError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                GetResultsError::OomError(_) => "not enough memory available",
+                GetResultsError::DeviceLost => "the connection to the device has been lost",
+                GetResultsError::OutOfRange => {
+                    "the range of queries is empty or is out of the range of the pool"
+                }
+                GetResultsError::BufferTooSmall { .. } => {
+                    "the destination doesn't have enough elements to hold the results"
+                }
+                GetResultsError::InvalidFlags => {
+                    "the `partial` flag was used with timestamp queries"
+                }
+            }
+        )
+    }
+}
+
+impl From<Error> for GetResultsError {
+    #[inline]
+    fn from(err: Error) -> GetResultsError {
+        match err {
+            e @ Error::OutOfHostMemory | e @ Error::OutOfDeviceMemory => {
+                GetResultsError::OomError(From::from(e))
+            }
+            Error::DeviceLost => GetResultsError::DeviceLost,
+            _ => panic!("Unexpected error value: {}", err as i32),
+        }
+    }
+}
+
 pub struct OcclusionQueriesPool {
     inner: UnsafeQueryPool,
 }
@@ -364,9 +654,12 @@ unsafe impl DeviceOwned for OcclusionQueriesPool {
 
 #[cfg(test)]
 mod tests {
+    use query::GetResultsError;
     use query::OcclusionQueriesPool;
     use query::QueryPipelineStatisticFlags;
+    use query::QueryPool;
     use query::QueryPoolCreationError;
+    use query::QueryResultFlags;
     use query::QueryType;
     use query::UnsafeQueryPool;
 
@@ -386,4 +679,41 @@ mod tests {
             _ => panic!(),
         };
     }
+
+    #[test]
+    fn get_results_checks() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = QueryPool::new(device, QueryType::Timestamp, 4).unwrap();
+        let mut results = [0u64; 4];
+
+        assert_eq!(
+            pool.get_results(2..6, &mut results, QueryResultFlags::none()),
+            Err(GetResultsError::OutOfRange)
+        );
+        assert_eq!(
+            pool.get_results(
+                0..4,
+                &mut results[..2],
+                QueryResultFlags {
+                    with_availability: true,
+                    ..QueryResultFlags::none()
+                }
+            ),
+            Err(GetResultsError::BufferTooSmall {
+                required: 8,
+                actual: 2,
+            })
+        );
+        assert_eq!(
+            pool.get_results(
+                0..4,
+                &mut results,
+                QueryResultFlags {
+                    partial: true,
+                    ..QueryResultFlags::none()
+                }
+            ),
+            Err(GetResultsError::InvalidFlags)
+        );
+    }
 }
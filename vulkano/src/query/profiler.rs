@@ -0,0 +1,479 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::sync::Arc;
+
+use command_buffer::AutoCommandBufferBuilder;
+use command_buffer::ResetQueryPoolError;
+use command_buffer::WriteTimestampError;
+use device::Device;
+use instance::QueueFamily;
+use query::GetResultsError;
+use query::QueryPool;
+use query::QueryPoolCreationError;
+use query::QueryResultFlags;
+use query::QueryType;
+use sync::PipelineStages;
+
+/// Measures the time that the GPU spends executing named zones of command buffers.
+///
+/// The profiler owns one timestamp query pool per frame in flight. At the start of each frame,
+/// call `begin_frame` to switch to the next pool, then surround the commands to measure with
+/// `scope`. A timestamp is written when the scope begins, and another one when it ends.
+///
+/// Since the GPU runs behind the CPU, the results of a frame are only read back once the frame
+/// comes around again. Call `report` right before `begin_frame` to get the duration of each zone
+/// of the oldest frame in flight.
+///
+/// # Example
+///
+/// ```
+/// use vulkano::command_buffer::AutoCommandBufferBuilder;
+/// use vulkano::command_buffer::CommandBuffer;
+/// use vulkano::query::GpuProfiler;
+/// use vulkano::sync::GpuFuture;
+/// # use std::sync::Arc;
+/// # use vulkano::pipeline::ComputePipelineAbstract;
+/// # let device: Arc<vulkano::device::Device> = return;
+/// # let queue: Arc<vulkano::device::Queue> = return;
+/// # let pipeline: Arc<ComputePipelineAbstract + Send + Sync> = return;
+///
+/// let mut profiler = GpuProfiler::new(device.clone(), queue.family(), 2, 16).unwrap();
+///
+/// loop {
+///     if let Some(zones) = profiler.report().unwrap() {
+///         for zone in zones {
+///             println!("{}: {} ms", zone.name, zone.milliseconds);
+///         }
+///     }
+///
+///     let mut builder = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+///     unsafe {
+///         profiler.begin_frame(&mut builder).unwrap();
+///     }
+///
+///     let mut scope = profiler.scope(&mut builder, "culling").unwrap();
+///     scope.dispatch([64, 1, 1], pipeline.clone(), (), ()).unwrap();
+///     scope.end().unwrap();
+///
+///     // The command buffer of the frame must have finished executing when the frame is reused
+///     // by `begin_frame`.
+///     builder
+///         .build()
+///         .unwrap()
+///         .execute(queue.clone())
+///         .unwrap()
+///         .then_signal_fence_and_flush()
+///         .unwrap()
+///         .wait(None)
+///         .unwrap();
+/// }
+/// ```
+pub struct GpuProfiler {
+    frames: Vec<Frame>,
+    // Index within `frames` of the frame being recorded.
+    current: usize,
+    // True if `begin_frame` has been called at least once.
+    frame_started: bool,
+    max_zones: u32,
+    // Number of meaningful bits in the timestamps of the queue family.
+    timestamp_valid_bits: Option<u32>,
+}
+
+struct Frame {
+    pool: Arc<QueryPool>,
+    // Names of the zones of the frame, in the order they were begun. Zone `n` uses the queries
+    // `2 * n` and `2 * n + 1`.
+    zones: Vec<String>,
+}
+
+impl GpuProfiler {
+    /// Builds a new profiler for `frames_in_flight` frames, each of them having at most
+    /// `max_zones` zones. The zones must be recorded in command buffers that are executed on
+    /// queues of `queue_family`.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `frames_in_flight` or `max_zones` is 0.
+    ///
+    pub fn new(
+        device: Arc<Device>,
+        queue_family: QueueFamily,
+        frames_in_flight: usize,
+        max_zones: u32,
+    ) -> Result<GpuProfiler, QueryPoolCreationError> {
+        assert!(frames_in_flight >= 1);
+        assert!(max_zones >= 1);
+
+        let frames = (0..frames_in_flight)
+            .map(|_| {
+                Ok(Frame {
+                    pool: Arc::new(QueryPool::new(
+                        device.clone(),
+                        QueryType::Timestamp,
+                        max_zones * 2,
+                    )?),
+                    zones: Vec::new(),
+                })
+            })
+            .collect::<Result<_, QueryPoolCreationError>>()?;
+
+        Ok(GpuProfiler {
+            frames: frames,
+            current: 0,
+            frame_started: false,
+            max_zones: max_zones,
+            timestamp_valid_bits: queue_family.timestamp_valid_bits(),
+        })
+    }
+
+    /// Returns the number of frames in flight that the profiler was created with.
+    #[inline]
+    pub fn frames_in_flight(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns the maximum number of zones per frame.
+    #[inline]
+    pub fn max_zones(&self) -> u32 {
+        self.max_zones
+    }
+
+    /// Switches to the next frame, and adds a command to `builder` that resets its queries.
+    ///
+    /// This must be called outside of a render pass, before any call to `scope` for this frame.
+    /// The zones that were recorded for the frame the last time it was in flight are discarded,
+    /// so call `report` first if you need them.
+    ///
+    /// # Safety
+    ///
+    /// - The command buffer in which this frame was recorded the last time, `frames_in_flight`
+    ///   frames ago, must have finished executing.
+    /// - The command buffer of `builder` must be executed before the ones in which the zones of
+    ///   this frame are recorded, or be the same command buffer.
+    ///
+    pub unsafe fn begin_frame<P>(
+        &mut self,
+        builder: &mut AutoCommandBufferBuilder<P>,
+    ) -> Result<(), ResetQueryPoolError> {
+        let next = if self.frame_started {
+            (self.current + 1) % self.frames.len()
+        } else {
+            0
+        };
+
+        let frame = &mut self.frames[next];
+        builder.reset_query_pool(frame.pool.clone(), 0..self.max_zones * 2)?;
+        frame.zones.clear();
+
+        self.current = next;
+        self.frame_started = true;
+        Ok(())
+    }
+
+    /// Begins a zone named `name` in the current frame.
+    ///
+    /// A timestamp is written to `builder` right away, and another one when the returned
+    /// `ProfilerScope` ends. The commands to measure can be added through the scope, which
+    /// dereferences to the builder.
+    pub fn scope<'a, P, S>(
+        &'a mut self,
+        builder: &'a mut AutoCommandBufferBuilder<P>,
+        name: S,
+    ) -> Result<ProfilerScope<'a, P>, ProfilerScopeError>
+    where
+        S: Into<String>,
+    {
+        let end_query = self.begin_zone(builder, name.into())?;
+
+        Ok(ProfilerScope {
+            profiler: self,
+            builder: builder,
+            end_query: end_query,
+            ended: false,
+        })
+    }
+
+    // Writes the starting timestamp of a new zone, and returns the query to use for its ending
+    // timestamp.
+    fn begin_zone<P>(
+        &mut self,
+        builder: &mut AutoCommandBufferBuilder<P>,
+        name: String,
+    ) -> Result<u32, ProfilerScopeError> {
+        if !self.frame_started {
+            return Err(ProfilerScopeError::FrameNotStarted);
+        }
+
+        let frame = &mut self.frames[self.current];
+        if frame.zones.len() as u32 >= self.max_zones {
+            return Err(ProfilerScopeError::TooManyZones);
+        }
+
+        let start_query = frame.zones.len() as u32 * 2;
+        unsafe {
+            // Safe because the queries of the frame were reset by `begin_frame`, and each query is
+            // only written once per frame.
+            builder.write_timestamp(
+                frame.pool.clone(),
+                start_query,
+                PipelineStages {
+                    top_of_pipe: true,
+                    ..PipelineStages::none()
+                },
+            )?;
+        }
+
+        frame.zones.push(name);
+        Ok(start_query + 1)
+    }
+
+    /// Returns the duration of each zone of the oldest frame in flight, which is the frame that
+    /// will be reused by the next call to `begin_frame`.
+    ///
+    /// Returns `Ok(None)` if no zone was recorded for this frame, or if the GPU hasn't finished
+    /// executing it yet. Zones are returned in the order in which they were begun.
+    pub fn report(&self) -> Result<Option<Vec<ProfilerZone>>, GetResultsError> {
+        let oldest = if self.frame_started {
+            (self.current + 1) % self.frames.len()
+        } else {
+            0
+        };
+        let frame = &self.frames[oldest];
+
+        if frame.zones.is_empty() {
+            return Ok(None);
+        }
+
+        // Each query is followed by its availability.
+        let num_queries = frame.zones.len() as u32 * 2;
+        let mut results = vec![0u64; num_queries as usize * 2];
+        frame.pool.get_results(
+            0..num_queries,
+            &mut results,
+            QueryResultFlags {
+                with_availability: true,
+                ..QueryResultFlags::none()
+            },
+        )?;
+
+        if results.chunks(2).any(|query| query[1] == 0) {
+            return Ok(None);
+        }
+
+        let zones = frame
+            .zones
+            .iter()
+            .zip(results.chunks(4))
+            .map(|(name, queries)| {
+                let ticks = ticks_between(queries[0], queries[2], self.timestamp_valid_bits);
+                ProfilerZone {
+                    name: name.clone(),
+                    milliseconds: frame.pool.timestamp_to_nanoseconds(ticks) / 1_000_000.0,
+                }
+            })
+            .collect();
+
+        Ok(Some(zones))
+    }
+}
+
+// Returns the number of ticks between two timestamps, of which only the `valid_bits` lowest bits
+// are meaningful.
+fn ticks_between(start: u64, end: u64, valid_bits: Option<u32>) -> u64 {
+    let mask = match valid_bits {
+        Some(bits) if bits < 64 => (1 << bits) - 1,
+        _ => !0,
+    };
+
+    (end & mask).wrapping_sub(start & mask) & mask
+}
+
+/// A zone of a `GpuProfiler` being recorded.
+///
+/// Dereferences to the command buffer builder that the zone is recorded in. The zone ends when
+/// `end` is called. If this object is dropped instead, the zone ends too but errors are ignored.
+pub struct ProfilerScope<'a, P: 'a> {
+    profiler: &'a mut GpuProfiler,
+    builder: &'a mut AutoCommandBufferBuilder<P>,
+    end_query: u32,
+    // True if the ending timestamp has been written.
+    ended: bool,
+}
+
+impl<'a, P> ProfilerScope<'a, P> {
+    /// Ends the zone by writing its ending timestamp.
+    #[inline]
+    pub fn end(mut self) -> Result<(), WriteTimestampError> {
+        self.write_end_timestamp()
+    }
+
+    fn write_end_timestamp(&mut self) -> Result<(), WriteTimestampError> {
+        self.ended = true;
+
+        let pool = self.profiler.frames[self.profiler.current].pool.clone();
+        unsafe {
+            // Safe for the same reasons as the starting timestamp.
+            self.builder.write_timestamp(
+                pool,
+                self.end_query,
+                PipelineStages {
+                    bottom_of_pipe: true,
+                    ..PipelineStages::none()
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Begins a zone nested in this one.
+    pub fn scope<'b, S>(&'b mut self, name: S) -> Result<ProfilerScope<'b, P>, ProfilerScopeError>
+    where
+        S: Into<String>,
+    {
+        self.profiler.scope(self.builder, name)
+    }
+}
+
+impl<'a, P> Deref for ProfilerScope<'a, P> {
+    type Target = AutoCommandBufferBuilder<P>;
+
+    #[inline]
+    fn deref(&self) -> &AutoCommandBufferBuilder<P> {
+        self.builder
+    }
+}
+
+impl<'a, P> DerefMut for ProfilerScope<'a, P> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut AutoCommandBufferBuilder<P> {
+        self.builder
+    }
+}
+
+impl<'a, P> Drop for ProfilerScope<'a, P> {
+    #[inline]
+    fn drop(&mut self) {
+        if !self.ended {
+            let _ = self.write_end_timestamp();
+        }
+    }
+}
+
+/// Duration of a zone measured by a `GpuProfiler`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfilerZone {
+    /// Name that was passed to `scope`.
+    pub name: String,
+    /// Time that the GPU took to execute the zone, in milliseconds.
+    pub milliseconds: f64,
+}
+
+/// Error that can happen when beginning a zone of a `GpuProfiler`.
+#[derive(Debug, Clone)]
+pub enum ProfilerScopeError {
+    /// `begin_frame` was never called.
+    FrameNotStarted,
+    /// All the zones of the current frame are already used.
+    TooManyZones,
+    /// Error while writing the timestamp.
+    WriteTimestampError(WriteTimestampError),
+}
+
+impl error::Error for ProfilerScopeError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            ProfilerScopeError::WriteTimestampError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ProfilerScopeError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                ProfilerScopeError::FrameNotStarted => "`begin_frame` was never called",
+                ProfilerScopeError::TooManyZones => {
+                    "all the zones of the current frame are already used"
+                }
+                ProfilerScopeError::WriteTimestampError(_) => "error while writing the timestamp",
+            }
+        )
+    }
+}
+
+impl From<WriteTimestampError> for ProfilerScopeError {
+    #[inline]
+    fn from(err: WriteTimestampError) -> ProfilerScopeError {
+        ProfilerScopeError::WriteTimestampError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use command_buffer::AutoCommandBufferBuilder;
+    use query::profiler::ticks_between;
+    use query::GpuProfiler;
+    use query::ProfilerScopeError;
+
+    #[test]
+    fn zones_checked() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let mut profiler = GpuProfiler::new(device.clone(), queue.family(), 2, 1).unwrap();
+        assert_eq!(profiler.report().unwrap(), None);
+
+        let mut builder = AutoCommandBufferBuilder::new(device, queue.family()).unwrap();
+        match profiler.scope(&mut builder, "zone") {
+            Err(ProfilerScopeError::FrameNotStarted) => (),
+            _ => panic!(),
+        }
+
+        if queue.family().timestamp_valid_bits().is_none() {
+            return;
+        }
+
+        unsafe {
+            profiler.begin_frame(&mut builder).unwrap();
+        }
+        profiler.scope(&mut builder, "zone").unwrap().end().unwrap();
+        match profiler.scope(&mut builder, "zone") {
+            Err(ProfilerScopeError::TooManyZones) => (),
+            _ => panic!(),
+        };
+    }
+
+    #[test]
+    fn ticks_masked() {
+        assert_eq!(ticks_between(10, 25, Some(36)), 15);
+        assert_eq!(ticks_between(10, 25, None), 15);
+
+        // The end timestamp wrapped around the valid bits.
+        let max = (1 << 36) - 1;
+        assert_eq!(ticks_between(max - 4, 5, Some(36)), 10);
+
+        // The invalid bits are ignored.
+        assert_eq!(
+            ticks_between(0xff00_0000_0000_0010, 0x0000_0000_0000_0020, Some(36)),
+            16
+        );
+
+        assert_eq!(ticks_between(u64::max_value(), 1, Some(64)), 2);
+    }
+}
@@ -57,6 +57,73 @@ pub struct Capabilities {
     pub present_modes: SupportedPresentModes,
 }
 
+impl Capabilities {
+    /// Returns the first format and color space of `preferred` that is supported, or `None` if
+    /// none of them is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vulkano::format::Format;
+    /// use vulkano::swapchain::ColorSpace;
+    /// # let caps: vulkano::swapchain::Capabilities = return;
+    ///
+    /// let (format, color_space) = caps
+    ///     .pick_format(&[
+    ///         (Format::B8G8R8A8Srgb, ColorSpace::SrgbNonLinear),
+    ///         (Format::R8G8B8A8Srgb, ColorSpace::SrgbNonLinear),
+    ///     ])
+    ///     .unwrap_or(caps.supported_formats[0]);
+    /// ```
+    pub fn pick_format(&self, preferred: &[(Format, ColorSpace)]) -> Option<(Format, ColorSpace)> {
+        preferred
+            .iter()
+            .find(|f| self.supported_formats.contains(f))
+            .cloned()
+    }
+
+    /// Returns the first present mode of `preferred` that is supported, or `Fifo` if none of them
+    /// is.
+    #[inline]
+    pub fn pick_present_mode(&self, preferred: &[PresentMode]) -> PresentMode {
+        preferred
+            .iter()
+            .cloned()
+            .find(|&m| self.present_modes.supports(m))
+            .unwrap_or(PresentMode::Fifo)
+    }
+
+    /// Returns `count` clamped to the number of images that a swapchain can have.
+    #[inline]
+    pub fn clamp_image_count(&self, count: u32) -> u32 {
+        let count = count.max(self.min_image_count);
+        match self.max_image_count {
+            Some(max) => count.min(max),
+            None => count,
+        }
+    }
+
+    /// Returns the dimensions that a swapchain for a window of dimensions `window_dimensions`
+    /// should have.
+    ///
+    /// This is the current extent of the surface if it has one, or `window_dimensions` clamped
+    /// to the supported extents otherwise.
+    #[inline]
+    pub fn image_extent(&self, window_dimensions: [u32; 2]) -> [u32; 2] {
+        match self.current_extent {
+            Some(extent) => extent,
+            None => [
+                window_dimensions[0]
+                    .max(self.min_image_extent[0])
+                    .min(self.max_image_extent[0]),
+                window_dimensions[1]
+                    .max(self.min_image_extent[1])
+                    .min(self.max_image_extent[1]),
+            ],
+        }
+    }
+}
+
 /// The way presenting a swapchain is accomplished.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u32)]
@@ -572,6 +639,26 @@ impl Default for SurfaceTransform {
 /// Additionally you can try detect whether the implementation supports any additional color space
 /// and perform a manual conversion to that color space from inside your shader.
 ///
+/// # HDR output
+///
+/// The color spaces other than `SrgbNonLinear` are only reported by `Surface::capabilities` if
+/// the `VK_EXT_swapchain_colorspace` extension is enabled on the instance. HDR output is done by
+/// picking a format and a color space for which `is_hdr` returns true, most commonly
+/// `A2B10G10R10UnormPack32` with `Hdr10St2084`, or `R16G16B16A16Sfloat` with
+/// `ExtendedSrgbLinear`. Your shaders must then write values encoded for that color space.
+///
+/// ```
+/// use vulkano::format::Format;
+/// use vulkano::swapchain::ColorSpace;
+/// # let caps: vulkano::swapchain::Capabilities = return;
+///
+/// let hdr = caps.pick_format(&[
+///     (Format::A2B10G10R10UnormPack32, ColorSpace::Hdr10St2084),
+///     (Format::R16G16B16A16Sfloat, ColorSpace::ExtendedSrgbLinear),
+/// ]);
+/// let (format, color_space) = hdr.unwrap_or(caps.supported_formats[0]);
+/// ```
+///
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u32)]
 pub enum ColorSpace {
@@ -589,11 +676,28 @@ pub enum ColorSpace {
     AdobeRgbLinear = vk::COLOR_SPACE_ADOBERGB_LINEAR_EXT,
     AdobeRgbNonLinear = vk::COLOR_SPACE_ADOBERGB_NONLINEAR_EXT,
     PassThrough = vk::COLOR_SPACE_PASS_THROUGH_EXT,
+    ExtendedSrgbNonLinear = vk::COLOR_SPACE_EXTENDED_SRGB_NONLINEAR_EXT,
+}
+
+impl ColorSpace {
+    /// Returns true if the color space can represent a high dynamic range, meaning brightnesses
+    /// above the one of the white of the sRGB color space.
+    #[inline]
+    pub fn is_hdr(&self) -> bool {
+        match *self {
+            ColorSpace::ExtendedSrgbLinear
+            | ColorSpace::ExtendedSrgbNonLinear
+            | ColorSpace::Hdr10St2084
+            | ColorSpace::Hdr10Hlg
+            | ColorSpace::DolbyVision => true,
+            _ => false,
+        }
+    }
 }
 
 #[inline]
-pub fn color_space_from_num(val: u32) -> ColorSpace {
-    match val {
+pub fn color_space_from_num(val: u32) -> Option<ColorSpace> {
+    Some(match val {
         vk::COLOR_SPACE_SRGB_NONLINEAR_KHR => ColorSpace::SrgbNonLinear,
         vk::COLOR_SPACE_DISPLAY_P3_NONLINEAR_EXT => ColorSpace::DisplayP3NonLinear,
         vk::COLOR_SPACE_EXTENDED_SRGB_LINEAR_EXT => ColorSpace::ExtendedSrgbLinear,
@@ -608,6 +712,111 @@ pub fn color_space_from_num(val: u32) -> ColorSpace {
         vk::COLOR_SPACE_ADOBERGB_LINEAR_EXT => ColorSpace::AdobeRgbLinear,
         vk::COLOR_SPACE_ADOBERGB_NONLINEAR_EXT => ColorSpace::AdobeRgbNonLinear,
         vk::COLOR_SPACE_PASS_THROUGH_EXT => ColorSpace::PassThrough,
-        _ => panic!("Wrong value for color space enum"),
+        vk::COLOR_SPACE_EXTENDED_SRGB_NONLINEAR_EXT => ColorSpace::ExtendedSrgbNonLinear,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use format::Format;
+    use image::ImageUsage;
+    use swapchain::capabilities::color_space_from_num;
+    use swapchain::Capabilities;
+    use swapchain::ColorSpace;
+    use swapchain::PresentMode;
+    use swapchain::SupportedCompositeAlpha;
+    use swapchain::SupportedPresentModes;
+    use swapchain::SupportedSurfaceTransforms;
+    use swapchain::SurfaceTransform;
+    use vk;
+
+    fn capabilities() -> Capabilities {
+        Capabilities {
+            min_image_count: 2,
+            max_image_count: Some(4),
+            current_extent: None,
+            min_image_extent: [16, 16],
+            max_image_extent: [1024, 1024],
+            max_image_array_layers: 1,
+            supported_transforms: SupportedSurfaceTransforms {
+                identity: true,
+                ..SupportedSurfaceTransforms::none()
+            },
+            current_transform: SurfaceTransform::Identity,
+            supported_composite_alpha: SupportedCompositeAlpha {
+                opaque: true,
+                ..SupportedCompositeAlpha::none()
+            },
+            supported_usage_flags: ImageUsage::color_attachment(),
+            supported_formats: vec![
+                (Format::R8G8B8A8Unorm, ColorSpace::SrgbNonLinear),
+                (Format::B8G8R8A8Srgb, ColorSpace::SrgbNonLinear),
+            ],
+            present_modes: SupportedPresentModes {
+                fifo: true,
+                immediate: true,
+                ..SupportedPresentModes::none()
+            },
+        }
+    }
+
+    #[test]
+    fn pick() {
+        let caps = capabilities();
+
+        assert_eq!(
+            caps.pick_format(&[
+                (Format::B8G8R8A8Srgb, ColorSpace::ExtendedSrgbLinear),
+                (Format::B8G8R8A8Srgb, ColorSpace::SrgbNonLinear),
+                (Format::R8G8B8A8Unorm, ColorSpace::SrgbNonLinear),
+            ]),
+            Some((Format::B8G8R8A8Srgb, ColorSpace::SrgbNonLinear))
+        );
+        assert_eq!(
+            caps.pick_format(&[(Format::R8G8B8A8Srgb, ColorSpace::SrgbNonLinear)]),
+            None
+        );
+
+        assert_eq!(
+            caps.pick_present_mode(&[PresentMode::Mailbox, PresentMode::Immediate]),
+            PresentMode::Immediate
+        );
+        assert_eq!(
+            caps.pick_present_mode(&[PresentMode::Mailbox]),
+            PresentMode::Fifo
+        );
+    }
+
+    #[test]
+    fn clamp() {
+        let caps = capabilities();
+
+        assert_eq!(caps.clamp_image_count(1), 2);
+        assert_eq!(caps.clamp_image_count(3), 3);
+        assert_eq!(caps.clamp_image_count(8), 4);
+
+        assert_eq!(caps.image_extent([8, 2000]), [16, 1024]);
+        assert_eq!(caps.image_extent([640, 480]), [640, 480]);
+
+        let caps = Capabilities {
+            current_extent: Some([800, 600]),
+            ..caps
+        };
+        assert_eq!(caps.image_extent([640, 480]), [800, 600]);
+    }
+
+    #[test]
+    fn color_spaces() {
+        assert_eq!(
+            color_space_from_num(vk::COLOR_SPACE_HDR10_ST2084_EXT),
+            Some(ColorSpace::Hdr10St2084)
+        );
+        assert_eq!(color_space_from_num(0x7fffffff), None);
+
+        assert!(ColorSpace::Hdr10St2084.is_hdr());
+        assert!(ColorSpace::ExtendedSrgbLinear.is_hdr());
+        assert!(!ColorSpace::SrgbNonLinear.is_hdr());
+        assert!(!ColorSpace::DisplayP3NonLinear.is_hdr());
     }
 }
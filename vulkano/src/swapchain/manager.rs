@@ -0,0 +1,290 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use smallvec::SmallVec;
+use std::sync::Arc;
+
+use device::Device;
+use device::DeviceOwned;
+use image::swapchain::SwapchainImage;
+use swapchain::PresentMode;
+use swapchain::Surface;
+use swapchain::Swapchain;
+use swapchain::SwapchainCreationError;
+use swapchain::SwapchainParameters;
+
+/// Swapchain that is recreated automatically when its parameters change.
+///
+/// Changing the present mode or the dimensions of the images, or the swapchain becoming
+/// suboptimal, only marks the swapchain as needing to be recreated. The recreation happens the
+/// next time `recreate_if_needed` is called, which should be done before acquiring an image.
+///
+/// # Example
+///
+/// ```
+/// use vulkano::swapchain;
+/// use vulkano::swapchain::AcquireError;
+/// use vulkano::swapchain::SwapchainCreationError;
+/// # let mut manager: vulkano::swapchain::SwapchainManager<()> = return;
+/// # let vsync_toggled: bool = return;
+/// # let window_dimensions: [u32; 2] = return;
+///
+/// loop {
+///     if vsync_toggled {
+///         manager.set_vsync(!manager.vsync());
+///     }
+///     manager.set_dimensions(window_dimensions);
+///
+///     match manager.recreate_if_needed() {
+///         Ok(true) => {
+///             // Recreate the framebuffers from `manager.images()`.
+///         }
+///         Ok(false) => (),
+///         // The window is minimized, try again later.
+///         Err(SwapchainCreationError::UnsupportedDimensions) => continue,
+///         Err(err) => panic!("{:?}", err),
+///     }
+///
+///     let (index, _, acquire_future) =
+///         match swapchain::acquire_next_image(manager.swapchain().clone(), None) {
+///             Ok(r) => r,
+///             Err(AcquireError::OutOfDate) => {
+///                 manager.invalidate();
+///                 continue;
+///             }
+///             Err(err) => panic!("{:?}", err),
+///         };
+///
+///     // Draw on the image and present it.
+/// }
+/// ```
+pub struct SwapchainManager<W> {
+    swapchain: Arc<Swapchain<W>>,
+    images: Vec<Arc<SwapchainImage<W>>>,
+    parameters: SwapchainParameters,
+    requested: Requested,
+}
+
+// Parameters to use the next time the swapchain is recreated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Requested {
+    dimensions: Option<[u32; 2]>,
+    // Present modes to use, in order of preference. `Fifo` is used if none of them is supported.
+    present_modes: SmallVec<[PresentMode; 2]>,
+    // True if the parameters have changed or if the swapchain has been invalidated since the last
+    // recreation.
+    changed: bool,
+}
+
+impl Requested {
+    fn new(dimensions: Option<[u32; 2]>, present_mode: PresentMode) -> Requested {
+        Requested {
+            dimensions: dimensions,
+            present_modes: SmallVec::from_elem(present_mode, 1),
+            changed: false,
+        }
+    }
+
+    fn vsync(&self) -> bool {
+        self.present_modes[0] != PresentMode::Immediate
+    }
+
+    fn set_dimensions(&mut self, dimensions: [u32; 2]) {
+        if self.dimensions != Some(dimensions) {
+            self.dimensions = Some(dimensions);
+            self.changed = true;
+        }
+    }
+
+    fn set_present_modes(&mut self, modes: &[PresentMode]) {
+        if &self.present_modes[..] != modes {
+            self.present_modes = modes.iter().cloned().collect();
+            self.changed = true;
+        }
+    }
+}
+
+impl<W> SwapchainManager<W> {
+    /// Creates the swapchain with the given parameters.
+    ///
+    /// See `Swapchain::with_parameters`.
+    #[inline]
+    pub fn new(
+        device: Arc<Device>,
+        surface: Arc<Surface<W>>,
+        mut parameters: SwapchainParameters,
+    ) -> Result<SwapchainManager<W>, SwapchainCreationError> {
+        let (swapchain, images) = Swapchain::with_parameters(device, surface, parameters.clone())?;
+        parameters.dimensions = Some(swapchain.dimensions());
+
+        Ok(SwapchainManager {
+            swapchain,
+            images,
+            requested: Requested::new(parameters.dimensions, parameters.present_mode),
+            parameters,
+        })
+    }
+
+    /// Returns the current swapchain.
+    #[inline]
+    pub fn swapchain(&self) -> &Arc<Swapchain<W>> {
+        &self.swapchain
+    }
+
+    /// Returns the images of the current swapchain.
+    #[inline]
+    pub fn images(&self) -> &[Arc<SwapchainImage<W>>] {
+        &self.images
+    }
+
+    /// Returns the present mode of the current swapchain.
+    #[inline]
+    pub fn present_mode(&self) -> PresentMode {
+        self.swapchain.present_mode()
+    }
+
+    /// Uses `mode` as the present mode once the swapchain is recreated. If `mode` isn't supported
+    /// by the surface, `Fifo` is used instead.
+    #[inline]
+    pub fn set_present_mode(&mut self, mode: PresentMode) {
+        self.set_present_modes(&[mode]);
+    }
+
+    /// Returns true if vertical synchronization is requested, which is the case if the preferred
+    /// present mode is anything but `Immediate`.
+    ///
+    /// This reflects the last call to `set_vsync` or `set_present_mode`, even if the swapchain
+    /// hasn't been recreated yet or if the surface doesn't support the requested present mode.
+    /// Use `present_mode` to get the present mode of the current swapchain.
+    #[inline]
+    pub fn vsync(&self) -> bool {
+        self.requested.vsync()
+    }
+
+    /// Enables or disables vertical synchronization once the swapchain is recreated.
+    ///
+    /// Enabling it uses the `Fifo` present mode. Disabling it uses `Immediate`, or `Mailbox` if
+    /// `Immediate` isn't supported, which never blocks but doesn't tear either. If neither is
+    /// supported, `Fifo` is kept.
+    #[inline]
+    pub fn set_vsync(&mut self, vsync: bool) {
+        if vsync {
+            self.set_present_modes(&[PresentMode::Fifo]);
+        } else {
+            self.set_present_modes(&[PresentMode::Immediate, PresentMode::Mailbox]);
+        }
+    }
+
+    /// Sets the dimensions of the images once the swapchain is recreated. This should be called
+    /// when the window is resized.
+    ///
+    /// The dimensions are ignored if the surface has a current extent.
+    #[inline]
+    pub fn set_dimensions(&mut self, dimensions: [u32; 2]) {
+        self.requested.set_dimensions(dimensions);
+    }
+
+    /// Marks the swapchain as needing to be recreated, for example after acquiring an image or
+    /// presenting returned an `OutOfDate` error.
+    #[inline]
+    pub fn invalidate(&mut self) {
+        self.requested.changed = true;
+    }
+
+    /// Returns true if the next call to `recreate_if_needed` is going to recreate the swapchain.
+    #[inline]
+    pub fn needs_recreation(&self) -> bool {
+        self.requested.changed || self.swapchain.is_suboptimal()
+    }
+
+    /// Recreates the swapchain if its parameters have changed, if it has been invalidated or if
+    /// it is suboptimal.
+    ///
+    /// Returns true if the swapchain has been recreated, in which case the images have changed
+    /// as well. Returns `SwapchainCreationError::UnsupportedDimensions` while the window is
+    /// minimized, in which case the swapchain is recreated by a later call.
+    pub fn recreate_if_needed(&mut self) -> Result<bool, SwapchainCreationError> {
+        if !self.needs_recreation() {
+            return Ok(false);
+        }
+
+        let capabilities = self
+            .swapchain
+            .surface()
+            .capabilities(self.swapchain.device().physical_device())?;
+        let parameters = SwapchainParameters {
+            num_images: capabilities.clamp_image_count(self.parameters.num_images),
+            dimensions: self
+                .requested
+                .dimensions
+                .map(|d| capabilities.image_extent(d)),
+            present_mode: capabilities.pick_present_mode(&self.requested.present_modes),
+            ..self.parameters.clone()
+        };
+
+        let (swapchain, images) = self.swapchain.recreate_with_parameters(parameters)?;
+        self.swapchain = swapchain;
+        self.images = images;
+        self.requested.changed = false;
+        Ok(true)
+    }
+
+    #[inline]
+    fn set_present_modes(&mut self, modes: &[PresentMode]) {
+        self.requested.set_present_modes(modes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Requested;
+    use swapchain::PresentMode;
+
+    #[test]
+    fn vsync_requested() {
+        let mut requested = Requested::new(Some([800, 600]), PresentMode::Fifo);
+        assert!(requested.vsync());
+
+        requested.set_present_modes(&[PresentMode::Immediate, PresentMode::Mailbox]);
+        assert!(!requested.vsync());
+
+        requested.set_present_modes(&[PresentMode::Mailbox]);
+        assert!(requested.vsync());
+
+        let requested = Requested::new(None, PresentMode::Immediate);
+        assert!(!requested.vsync());
+    }
+
+    #[test]
+    fn changes_tracked() {
+        let mut requested = Requested::new(Some([800, 600]), PresentMode::Fifo);
+        assert!(!requested.changed);
+
+        requested.set_dimensions([800, 600]);
+        requested.set_present_modes(&[PresentMode::Fifo]);
+        assert!(!requested.changed);
+
+        requested.set_dimensions([1024, 768]);
+        assert!(requested.changed);
+        assert_eq!(requested.dimensions, Some([1024, 768]));
+
+        requested.changed = false;
+        requested.set_present_modes(&[PresentMode::Mailbox]);
+        assert!(requested.changed);
+        assert_eq!(&requested.present_modes[..], &[PresentMode::Mailbox]);
+    }
+
+    #[test]
+    fn dimensions_set_after_surface_extent() {
+        let mut requested = Requested::new(None, PresentMode::Fifo);
+        requested.set_dimensions([640, 480]);
+        assert!(requested.changed);
+        assert_eq!(requested.dimensions, Some([640, 480]));
+    }
+}
@@ -216,6 +216,28 @@
 //! # }
 //! ```
 //!
+//! Alternatively, `Swapchain::with_parameters` takes all these values in a `SwapchainParameters`.
+//! `SwapchainParameters::from_capabilities` fills them with values that the surface supports, and
+//! uses the current extent of the surface as the dimensions.
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use vulkano::device::Device;
+//! # use vulkano::image::ImageUsage;
+//! # use vulkano::swapchain::{PresentMode, Surface, Swapchain, SwapchainParameters};
+//! # fn create_swapchain(
+//! #     device: Arc<Device>, surface: Arc<Surface<()>>,
+//! # ) -> Result<(), Box<dyn std::error::Error>> {
+//! let caps = surface.capabilities(device.physical_device())?;
+//! let params = SwapchainParameters {
+//!     present_mode: PresentMode::Mailbox,
+//!     ..SwapchainParameters::from_capabilities(&caps, ImageUsage::color_attachment())
+//! };
+//! let (swapchain, buffers) = Swapchain::with_parameters(device, surface, params)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! Creating a swapchain not only returns the swapchain object, but also all the images that belong
 //! to it.
 //!
@@ -297,12 +319,16 @@
 //!         .then_swapchain_present(queue.clone(), swapchain.clone(), index)
 //!         .then_signal_fence_and_flush().unwrap(); // TODO: PresentError?
 //!
-//!     if suboptimal {
+//!     if suboptimal || swapchain.is_suboptimal() {
 //!         recreate_swapchain = true;
 //!     }
 //! }
 //! ```
 //!
+//! `suboptimal` only tells whether the acquisition was suboptimal, and is also available through
+//! `SwapchainAcquireFuture::is_suboptimal`. `Swapchain::is_suboptimal` additionally takes into
+//! account the presents that have been submitted.
+//!
 
 use std::sync::atomic::AtomicBool;
 
@@ -317,6 +343,7 @@ pub use self::capabilities::SupportedPresentModesIter;
 pub use self::capabilities::SupportedSurfaceTransforms;
 pub use self::capabilities::SupportedSurfaceTransformsIter;
 pub use self::capabilities::SurfaceTransform;
+pub use self::manager::SwapchainManager;
 pub use self::present_region::PresentRegion;
 pub use self::present_region::RectangleLayer;
 pub use self::surface::CapabilitiesError;
@@ -334,9 +361,12 @@ pub use self::swapchain::PresentFuture;
 pub use self::swapchain::Swapchain;
 pub use self::swapchain::SwapchainAcquireFuture;
 pub use self::swapchain::SwapchainCreationError;
+pub use self::swapchain::SwapchainParameters;
+pub use self::swapchain::Win32Monitor;
 
 mod capabilities;
 pub mod display;
+mod manager;
 mod present_region;
 mod surface;
 mod swapchain;
@@ -43,6 +43,9 @@ pub struct Surface<W> {
     // If true, a swapchain has been associated to this surface, and that any new swapchain
     // creation should be forbidden.
     has_swapchain: AtomicBool,
+
+    // True if the surface was created from a Win32 window.
+    win32: bool,
 }
 
 impl<W> Surface<W> {
@@ -60,6 +63,7 @@ impl<W> Surface<W> {
             instance: instance,
             surface: surface,
             has_swapchain: AtomicBool::new(false),
+            win32: false,
         }
     }
 
@@ -128,6 +132,7 @@ impl<W> Surface<W> {
             instance: instance.clone(),
             surface: surface,
             has_swapchain: AtomicBool::new(false),
+            win32: false,
         }))
     }
 
@@ -178,6 +183,7 @@ impl<W> Surface<W> {
             instance: instance.clone(),
             surface: surface,
             has_swapchain: AtomicBool::new(false),
+            win32: true,
         }))
     }
 
@@ -227,6 +233,7 @@ impl<W> Surface<W> {
             instance: instance.clone(),
             surface: surface,
             has_swapchain: AtomicBool::new(false),
+            win32: false,
         }))
     }
 
@@ -276,6 +283,7 @@ impl<W> Surface<W> {
             instance: instance.clone(),
             surface: surface,
             has_swapchain: AtomicBool::new(false),
+            win32: false,
         }))
     }
 
@@ -325,6 +333,7 @@ impl<W> Surface<W> {
             instance: instance.clone(),
             surface: surface,
             has_swapchain: AtomicBool::new(false),
+            win32: false,
         }))
     }
 
@@ -370,6 +379,7 @@ impl<W> Surface<W> {
             instance: instance.clone(),
             surface: surface,
             has_swapchain: AtomicBool::new(false),
+            win32: false,
         }))
     }
 
@@ -416,6 +426,7 @@ impl<W> Surface<W> {
             instance: instance.clone(),
             surface: surface,
             has_swapchain: AtomicBool::new(false),
+            win32: false,
         }))
     }
 
@@ -462,6 +473,7 @@ impl<W> Surface<W> {
             instance: instance.clone(),
             surface: surface,
             has_swapchain: AtomicBool::new(false),
+            win32: false,
         }))
     }
 
@@ -507,6 +519,7 @@ impl<W> Surface<W> {
             instance: instance.clone(),
             surface: surface,
             has_swapchain: AtomicBool::new(false),
+            win32: false,
         }))
     }
 
@@ -632,13 +645,15 @@ impl<W> Surface<W> {
                     debug_assert!(usage.color_attachment); // specs say that this must be true
                     usage
                 },
+                // Formats and color spaces that vulkano doesn't know about can't be passed when
+                // creating a swapchain, so they are skipped.
                 supported_formats: formats
                     .into_iter()
-                    .map(|f| {
-                        (
-                            Format::from_vulkan_num(f.format).unwrap(),
-                            capabilities::color_space_from_num(f.colorSpace),
-                        )
+                    .filter_map(|f| {
+                        Some((
+                            Format::from_vulkan_num(f.format)?,
+                            capabilities::color_space_from_num(f.colorSpace)?,
+                        ))
                     })
                     .collect(),
                 present_modes: modes,
@@ -656,6 +671,12 @@ impl<W> Surface<W> {
     pub fn instance(&self) -> &Arc<Instance> {
         &self.instance
     }
+
+    /// Returns true if the surface was created from a Win32 window.
+    #[inline]
+    pub(crate) fn is_win32(&self) -> bool {
+        self.win32
+    }
 }
 
 unsafe impl<W> SurfaceSwapchainLock for Surface<W> {
@@ -704,6 +725,9 @@ pub enum SurfaceCreationError {
         /// Name of the missing extension.
         name: &'static str,
     },
+
+    /// The kind of window that was passed can't be used to create a surface on this platform.
+    UnsupportedWindow,
 }
 
 impl error::Error for SurfaceCreationError {
@@ -727,6 +751,9 @@ impl fmt::Display for SurfaceCreationError {
                 SurfaceCreationError::MissingExtension { .. } => {
                     "the extension required for this function was not enabled"
                 }
+                SurfaceCreationError::UnsupportedWindow => {
+                    "the kind of window can't be used to create a surface on this platform"
+                }
             }
         )
     }
@@ -11,6 +11,7 @@ use std::error;
 use std::fmt;
 use std::mem;
 use std::mem::MaybeUninit;
+use std::os::raw::c_void;
 use std::ptr;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
@@ -38,6 +39,7 @@ use image::ImageLayout;
 use image::ImageTiling;
 use image::ImageType;
 use image::ImageUsage;
+use swapchain::Capabilities;
 use swapchain::CapabilitiesError;
 use swapchain::ColorSpace;
 use swapchain::CompositeAlpha;
@@ -49,6 +51,7 @@ use swapchain::SurfaceTransform;
 use sync::AccessCheckError;
 use sync::AccessError;
 use sync::AccessFlagBits;
+use sync::Event;
 use sync::Fence;
 use sync::FlushError;
 use sync::GpuFuture;
@@ -94,6 +97,105 @@ impl FullscreenExclusive {
     }
 }
 
+/// A Win32 monitor handle, used to tell the implementation on which monitor a swapchain is
+/// fullscreen.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Win32Monitor(*const c_void);
+
+impl Win32Monitor {
+    /// Wraps a Win32 `HMONITOR` handle.
+    ///
+    /// # Safety
+    ///
+    /// - `hmonitor` must be a valid `HMONITOR` handle.
+    #[inline]
+    pub unsafe fn new<T>(hmonitor: *const T) -> Win32Monitor {
+        Win32Monitor(hmonitor as *const c_void)
+    }
+}
+
+// `HMONITOR` handles can be used from any thread.
+unsafe impl Send for Win32Monitor {}
+unsafe impl Sync for Win32Monitor {}
+
+/// Parameters of a swapchain, passed to `Swapchain::with_parameters`.
+///
+/// All the values must be supported by the surface, as returned by `Surface::capabilities`.
+/// `SwapchainParameters::from_capabilities` builds parameters that are always supported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapchainParameters {
+    /// Number of images of the swapchain.
+    pub num_images: u32,
+    /// Format of the images.
+    pub format: Format,
+    /// Color space of the images. Must be supported by the surface together with `format`.
+    pub color_space: ColorSpace,
+    /// Dimensions of the images, or `None` to use the current extent of the surface.
+    pub dimensions: Option<[u32; 2]>,
+    /// Number of array layers of the images.
+    pub layers: u32,
+    /// How the images are going to be used. Must not be empty, and can only contain
+    /// `color_attachment` and `transfer_destination`.
+    pub usage: ImageUsage,
+    /// Queue families that can access the images.
+    pub sharing: SharingMode,
+    /// Transform applied to the images when they are presented.
+    pub transform: SurfaceTransform,
+    /// How the alpha value of the images is composited with the other windows.
+    pub composite_alpha: CompositeAlpha,
+    /// How the images are queued for presentation.
+    pub present_mode: PresentMode,
+    /// How fullscreen exclusivity is handled.
+    pub fullscreen_exclusive: FullscreenExclusive,
+    /// Monitor on which the swapchain is fullscreen, for Win32 surfaces. Required on Windows
+    /// when `fullscreen_exclusive` is `AppControlled`, and ignored otherwise.
+    pub win32_monitor: Option<Win32Monitor>,
+    /// Whether the implementation is allowed to discard the rendering operations that affect
+    /// regions of the surface which aren't visible.
+    pub clipped: bool,
+}
+
+impl SwapchainParameters {
+    /// Builds parameters that are supported by a surface with the given capabilities.
+    ///
+    /// This uses the minimum number of images, the first supported format, the current extent
+    /// and transform of the surface, the first supported composite alpha and the `Fifo` present
+    /// mode, which is always supported. The images are only accessible from one queue family.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `usage` is empty.
+    ///
+    pub fn from_capabilities(
+        capabilities: &Capabilities,
+        usage: ImageUsage,
+    ) -> SwapchainParameters {
+        assert_ne!(usage, ImageUsage::none());
+
+        let (format, color_space) = capabilities.supported_formats[0];
+
+        SwapchainParameters {
+            num_images: capabilities.min_image_count,
+            format,
+            color_space,
+            dimensions: None,
+            layers: 1,
+            usage,
+            sharing: SharingMode::Exclusive,
+            transform: capabilities.current_transform,
+            composite_alpha: capabilities
+                .supported_composite_alpha
+                .iter()
+                .next()
+                .unwrap(),
+            present_mode: PresentMode::Fifo,
+            fullscreen_exclusive: FullscreenExclusive::Default,
+            win32_monitor: None,
+            clipped: true,
+        }
+    }
+}
+
 /// Tries to take ownership of an image in order to draw on it.
 ///
 /// The function returns the index of the image in the array of images that was returned
@@ -134,6 +236,10 @@ pub fn acquire_next_image<W>(
         acquire_result?
     };
 
+    if suboptimal {
+        swapchain.suboptimal.store(true, Ordering::SeqCst);
+    }
+
     Ok((
         id,
         suboptimal,
@@ -142,6 +248,7 @@ pub fn acquire_next_image<W>(
             semaphore: Some(semaphore),
             fence: Some(fence),
             image_id: id,
+            suboptimal,
             finished: AtomicBool::new(false),
         },
     ))
@@ -251,7 +358,12 @@ pub struct Swapchain<W> {
     mode: PresentMode,
     fullscreen_exclusive: FullscreenExclusive,
     fullscreen_exclusive_held: AtomicBool,
+    win32_monitor: Option<Win32Monitor>,
     clipped: bool,
+
+    // True if acquiring or presenting an image has reported that the swapchain no longer
+    // matches the surface exactly.
+    suboptimal: AtomicBool,
 }
 
 struct ImageEntry {
@@ -273,6 +385,9 @@ impl<W> Swapchain<W> {
     /// important to take into account if your fragment shader has side-effects or if you want to
     /// read back the content of the image afterwards.
     ///
+    /// The `usage` can only contain `color_attachment` and `transfer_destination`, otherwise
+    /// `UsageNotAllowedForSwapchainImages` is returned.
+    ///
     /// This function returns the swapchain plus a list of the images that belong to the
     /// swapchain. The order in which the images are returned is important for the
     /// `acquire_next_image` and `present` functions.
@@ -318,6 +433,7 @@ impl<W> Swapchain<W> {
             alpha,
             mode,
             fullscreen_exclusive,
+            None,
             clipped,
             None,
         )
@@ -351,7 +467,7 @@ impl<W> Swapchain<W> {
             surface,
             num_images,
             format.format(),
-            ColorSpace::SrgbNonLinear,
+            color_space,
             Some(dimensions),
             layers,
             usage,
@@ -360,12 +476,106 @@ impl<W> Swapchain<W> {
             alpha,
             mode,
             fullscreen_exclusive,
+            None,
             clipped,
             Some(&*old_swapchain),
         )
     }
 
+    /// Builds a new swapchain from a set of parameters.
+    ///
+    /// This is the same as `Swapchain::new`, except that the dimensions can be left to the
+    /// current extent of the surface.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the device and the surface don't belong to the same instance.
+    /// - Panics if `params.usage` is empty.
+    ///
+    #[inline]
+    pub fn with_parameters(
+        device: Arc<Device>,
+        surface: Arc<Surface<W>>,
+        params: SwapchainParameters,
+    ) -> Result<(Arc<Swapchain<W>>, Vec<Arc<SwapchainImage<W>>>), SwapchainCreationError> {
+        Swapchain::new_inner(
+            device,
+            surface,
+            params.num_images,
+            params.format,
+            params.color_space,
+            params.dimensions,
+            params.layers,
+            params.usage,
+            params.sharing,
+            params.transform,
+            params.composite_alpha,
+            params.present_mode,
+            params.fullscreen_exclusive,
+            params.win32_monitor,
+            params.clipped,
+            None,
+        )
+    }
+
+    /// Returns the parameters that were used to create this swapchain.
+    ///
+    /// The dimensions are always `Some`, even if the swapchain was created with the current
+    /// extent of the surface.
+    #[inline]
+    pub fn parameters(&self) -> SwapchainParameters {
+        SwapchainParameters {
+            num_images: self.num_images,
+            format: self.format,
+            color_space: self.color_space,
+            dimensions: Some(self.dimensions),
+            layers: self.layers,
+            usage: self.usage,
+            sharing: self.sharing.clone(),
+            transform: self.transform,
+            composite_alpha: self.alpha,
+            present_mode: self.mode,
+            fullscreen_exclusive: self.fullscreen_exclusive,
+            win32_monitor: self.win32_monitor,
+            clipped: self.clipped,
+        }
+    }
+
+    /// Recreates the swapchain with new parameters, reusing the resources of this one.
+    ///
+    /// This swapchain can no longer acquire images afterwards. The images that were already
+    /// acquired can still be presented.
+    #[inline]
+    pub fn recreate_with_parameters(
+        &self,
+        params: SwapchainParameters,
+    ) -> Result<(Arc<Swapchain<W>>, Vec<Arc<SwapchainImage<W>>>), SwapchainCreationError> {
+        Swapchain::new_inner(
+            self.device.clone(),
+            self.surface.clone(),
+            params.num_images,
+            params.format,
+            params.color_space,
+            params.dimensions,
+            params.layers,
+            params.usage,
+            params.sharing,
+            params.transform,
+            params.composite_alpha,
+            params.present_mode,
+            params.fullscreen_exclusive,
+            params.win32_monitor,
+            params.clipped,
+            Some(self),
+        )
+    }
+
     /// Recreates the swapchain with current dimensions of corresponding surface.
+    ///
+    /// This should be called when `acquire_next_image` or presenting returns an `OutOfDate`
+    /// error, or when the acquisition was suboptimal. Returns
+    /// `SwapchainCreationError::MissingDimensions` if the surface has no current extent, in
+    /// which case `recreate_with_dimensions` must be used instead.
     pub fn recreate(
         &self,
     ) -> Result<(Arc<Swapchain<W>>, Vec<Arc<SwapchainImage<W>>>), SwapchainCreationError> {
@@ -383,6 +593,7 @@ impl<W> Swapchain<W> {
             self.alpha,
             self.mode,
             self.fullscreen_exclusive,
+            self.win32_monitor,
             self.clipped,
             Some(self),
         )
@@ -407,6 +618,7 @@ impl<W> Swapchain<W> {
             self.alpha,
             self.mode,
             self.fullscreen_exclusive,
+            self.win32_monitor,
             self.clipped,
             Some(self),
         )
@@ -426,6 +638,7 @@ impl<W> Swapchain<W> {
         alpha: CompositeAlpha,
         mode: PresentMode,
         fullscreen_exclusive: FullscreenExclusive,
+        win32_monitor: Option<Win32Monitor>,
         clipped: bool,
         old_swapchain: Option<&Swapchain<W>>,
     ) -> Result<(Arc<Swapchain<W>>, Vec<Arc<SwapchainImage<W>>>), SwapchainCreationError> {
@@ -466,11 +679,20 @@ impl<W> Swapchain<W> {
             }
             dimensions
         } else {
-            capabilities.current_extent.unwrap()
+            match capabilities.current_extent {
+                Some(extent) => extent,
+                None => return Err(SwapchainCreationError::MissingDimensions),
+            }
         };
+        // The surface's extent is zero while the window is minimized, but swapchains can't be
+        // empty.
+        if dimensions[0] == 0 || dimensions[1] == 0 {
+            return Err(SwapchainCreationError::UnsupportedDimensions);
+        }
         if layers < 1 || layers > capabilities.max_image_array_layers {
             return Err(SwapchainCreationError::UnsupportedArrayLayers);
         }
+        check_swapchain_image_usage(usage)?;
         if (usage.to_usage_bits() & capabilities.supported_usage_flags.to_usage_bits())
             != usage.to_usage_bits()
         {
@@ -539,6 +761,33 @@ impl<W> Swapchain<W> {
             });
         }
 
+        // Without the extension, the swapchain behaves as with `FullscreenExclusive::Default`.
+        let fullscreen_exclusive = if surface_full_screen_exclusive_info.is_some() {
+            fullscreen_exclusive
+        } else {
+            FullscreenExclusive::Default
+        };
+
+        check_win32_monitor(fullscreen_exclusive, surface.is_win32(), &win32_monitor)?;
+
+        let surface_full_screen_exclusive_win32_info = match win32_monitor {
+            Some(monitor) if surface_full_screen_exclusive_info.is_some() => {
+                Some(vk::SurfaceFullScreenExclusiveWin32InfoEXT {
+                    sType: vk::STRUCTURE_TYPE_SURFACE_FULL_SCREEN_EXCLUSIVE_WIN32_INFO_EXT,
+                    pNext: ptr::null(),
+                    hmonitor: monitor.0,
+                })
+            }
+            _ => None,
+        };
+
+        if let (Some(info), Some(win32_info)) = (
+            surface_full_screen_exclusive_info.as_mut(),
+            surface_full_screen_exclusive_win32_info.as_ref(),
+        ) {
+            info.pNext = win32_info as *const _ as *const _;
+        }
+
         let p_next = match surface_full_screen_exclusive_info.as_ref() {
             Some(some) => unsafe { mem::transmute(some as *const _) },
             None => ptr::null(),
@@ -691,7 +940,9 @@ impl<W> Swapchain<W> {
             mode: mode,
             fullscreen_exclusive,
             fullscreen_exclusive_held: AtomicBool::new(fullscreen_exclusive_held),
+            win32_monitor,
             clipped: clipped,
+            suboptimal: AtomicBool::new(false),
         });
 
         let swapchain_images = unsafe {
@@ -786,7 +1037,12 @@ impl<W> Swapchain<W> {
         self.clipped
     }
 
-    /// Returns the value of 'fullscreen_exclusive` that was passed when creating the swapchain.
+    /// Returns the value of `fullscreen_exclusive` that was passed when creating the swapchain.
+    ///
+    /// This is `FullscreenExclusive::Default` if the `VK_EXT_full_screen_exclusive` device
+    /// extension, or the `VK_KHR_get_physical_device_properties2` and
+    /// `VK_KHR_get_surface_capabilities2` instance extensions, weren't enabled. In that case the
+    /// requested value is ignored, and `acquire_fullscreen_exclusive` returns `NotAppControlled`.
     ///
     /// See the documentation of `FullscreenExclusive`
     #[inline]
@@ -807,13 +1063,18 @@ impl<W> Swapchain<W> {
             return Err(FullscreenExclusiveError::DoubleAcquire);
         }
 
-        unsafe {
-            check_errors(
-                self.device.pointers().AcquireFullScreenExclusiveModeEXT(
+        let result =
+            unsafe {
+                check_errors(self.device.pointers().AcquireFullScreenExclusiveModeEXT(
                     self.device.internal_object(),
                     self.swapchain,
-                ),
-            )?;
+                ))
+            };
+
+        if let Err(err) = result {
+            self.fullscreen_exclusive_held
+                .store(false, Ordering::SeqCst);
+            return Err(err.into());
         }
 
         Ok(())
@@ -854,6 +1115,23 @@ impl<W> Swapchain<W> {
         }
     }
 
+    /// Returns true if acquiring or presenting an image of this swapchain has reported that the
+    /// swapchain is suboptimal.
+    ///
+    /// The swapchain can still be used, but no longer matches the properties of the surface
+    /// exactly and should be recreated. A present that is submitted as part of another
+    /// submission, for example with `then_signal_fence_and_flush`, is also taken into account.
+    #[inline]
+    pub fn is_suboptimal(&self) -> bool {
+        self.suboptimal.load(Ordering::SeqCst)
+    }
+
+    // Flag that is set by `SubmitPresentBuilder` when presenting returns `VK_SUBOPTIMAL_KHR`.
+    #[inline]
+    pub(crate) fn suboptimal_flag(&self) -> &AtomicBool {
+        &self.suboptimal
+    }
+
     // This method is necessary to allow `SwapchainImage`s to signal when they have been
     // transitioned out of their initial `undefined` image layout.
     //
@@ -910,6 +1188,40 @@ impl<W> Drop for Swapchain<W> {
     }
 }
 
+// Checks that a monitor is provided for an application-controlled fullscreen exclusive swapchain
+// on a Win32 surface.
+fn check_win32_monitor(
+    fullscreen_exclusive: FullscreenExclusive,
+    win32_surface: bool,
+    win32_monitor: &Option<Win32Monitor>,
+) -> Result<(), SwapchainCreationError> {
+    if fullscreen_exclusive == FullscreenExclusive::AppControlled
+        && win32_surface
+        && win32_monitor.is_none()
+    {
+        return Err(SwapchainCreationError::MissingWin32Monitor);
+    }
+
+    Ok(())
+}
+
+// Checks that the swapchain images are only going to be used as color attachments and transfer
+// destinations, which are the only uses for which `SwapchainImage` handles the transitions from
+// and to the `PresentSrc` layout.
+fn check_swapchain_image_usage(usage: ImageUsage) -> Result<(), SwapchainCreationError> {
+    let allowed = ImageUsage {
+        color_attachment: usage.color_attachment,
+        transfer_destination: usage.transfer_destination,
+        ..ImageUsage::none()
+    };
+
+    if usage != allowed {
+        return Err(SwapchainCreationError::UsageNotAllowedForSwapchainImages);
+    }
+
+    Ok(())
+}
+
 /// Error that can happen when creation a swapchain.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum SwapchainCreationError {
@@ -939,10 +1251,15 @@ pub enum SwapchainCreationError {
     UnsupportedFormat,
     /// The requested dimensions are not supported by the surface.
     UnsupportedDimensions,
+    /// No dimensions were requested, and the surface doesn't have a current extent.
+    MissingDimensions,
     /// The requested array layers count is not supported by the surface.
     UnsupportedArrayLayers,
     /// The requested image usage is not supported by the surface.
     UnsupportedUsageFlags,
+    /// The requested image usage contains something other than `color_attachment` and
+    /// `transfer_destination`, which are the only ways swapchain images can be used.
+    UsageNotAllowedForSwapchainImages,
     /// The requested surface transform is not supported by the surface.
     UnsupportedSurfaceTransform,
     /// The requested composite alpha is not supported by the surface.
@@ -951,6 +1268,9 @@ pub enum SwapchainCreationError {
     UnsupportedPresentMode,
     /// The image configuration is not supported by the physical device.
     UnsupportedImageConfiguration,
+    /// The `fullscreen_exclusive` mode is `AppControlled` on a Win32 surface, but no
+    /// `win32_monitor` was provided.
+    MissingWin32Monitor,
 }
 
 impl error::Error for SwapchainCreationError {
@@ -1003,12 +1323,19 @@ impl fmt::Display for SwapchainCreationError {
                 SwapchainCreationError::UnsupportedDimensions => {
                     "the requested dimensions are not supported by the surface"
                 }
+                SwapchainCreationError::MissingDimensions => {
+                    "no dimensions were requested and the surface doesn't have a current extent"
+                }
                 SwapchainCreationError::UnsupportedArrayLayers => {
                     "the requested array layers count is not supported by the surface"
                 }
                 SwapchainCreationError::UnsupportedUsageFlags => {
                     "the requested image usage is not supported by the surface"
                 }
+                SwapchainCreationError::UsageNotAllowedForSwapchainImages => {
+                    "swapchain images can only be used as color attachments and transfer \
+                     destinations"
+                }
                 SwapchainCreationError::UnsupportedSurfaceTransform => {
                     "the requested surface transform is not supported by the surface"
                 }
@@ -1021,6 +1348,10 @@ impl fmt::Display for SwapchainCreationError {
                 SwapchainCreationError::UnsupportedImageConfiguration => {
                     "the requested image configuration is not supported by the physical device"
                 }
+                SwapchainCreationError::MissingWin32Monitor => {
+                    "an application-controlled fullscreen exclusive swapchain on a Win32 surface \
+                     requires a monitor"
+                }
             }
         )
     }
@@ -1068,6 +1399,8 @@ pub struct SwapchainAcquireFuture<W> {
     // Fence that is signalled when the acquire is complete. Empty if the acquire has already
     // happened.
     fence: Option<Fence>,
+    // True if the acquisition returned `VK_SUBOPTIMAL_KHR`.
+    suboptimal: bool,
     finished: AtomicBool,
 }
 
@@ -1078,6 +1411,13 @@ impl<W> SwapchainAcquireFuture<W> {
         self.image_id
     }
 
+    /// Returns true if the image was acquired but the swapchain is suboptimal. This is the same
+    /// value as the one returned by `acquire_next_image`.
+    #[inline]
+    pub fn is_suboptimal(&self) -> bool {
+        self.suboptimal
+    }
+
     /// Returns the corresponding swapchain.
     #[inline]
     pub fn swapchain(&self) -> &Arc<Swapchain<W>> {
@@ -1164,6 +1504,19 @@ unsafe impl<W> GpuFuture for SwapchainAcquireFuture<W> {
 
         Ok(None)
     }
+
+    #[inline]
+    fn describe_dependencies(&self, out: &mut Vec<String>) {
+        out.push(format!(
+            "acquisition of swapchain image {}{}",
+            self.image_id,
+            if self.finished.load(Ordering::SeqCst) {
+                " (finished)"
+            } else {
+                ""
+            }
+        ));
+    }
 }
 
 unsafe impl<W> DeviceOwned for SwapchainAcquireFuture<W> {
@@ -1539,6 +1892,27 @@ where
                 .check_image_access(image, layout, exclusive, queue)
         }
     }
+
+    #[inline]
+    fn describe_dependencies(&self, out: &mut Vec<String>) {
+        out.push(format!(
+            "presentation of swapchain image {}{}",
+            self.image_id,
+            if self.finished.load(Ordering::SeqCst) {
+                " (finished)"
+            } else if !self.flushed.load(Ordering::SeqCst) {
+                " (not flushed)"
+            } else {
+                ""
+            }
+        ));
+        self.previous.describe_dependencies(out);
+    }
+
+    #[inline]
+    fn waited_events(&self, out: &mut Vec<Arc<Event>>) {
+        self.previous.waited_events(out);
+    }
 }
 
 unsafe impl<P, W> DeviceOwned for PresentFuture<P, W>
@@ -1624,3 +1998,124 @@ pub unsafe fn acquire_next_image_raw<W>(
 
     Ok(AcquiredImage { id, suboptimal })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::check_swapchain_image_usage;
+    use super::check_win32_monitor;
+    use format::Format;
+    use image::ImageUsage;
+    use swapchain::Capabilities;
+    use swapchain::ColorSpace;
+    use swapchain::CompositeAlpha;
+    use swapchain::FullscreenExclusive;
+    use swapchain::PresentMode;
+    use swapchain::SupportedCompositeAlpha;
+    use swapchain::SupportedPresentModes;
+    use swapchain::SupportedSurfaceTransforms;
+    use swapchain::SurfaceTransform;
+    use swapchain::SwapchainCreationError;
+    use swapchain::SwapchainParameters;
+    use swapchain::Win32Monitor;
+    use sync::SharingMode;
+
+    #[test]
+    fn parameters_from_capabilities() {
+        let capabilities = Capabilities {
+            min_image_count: 2,
+            max_image_count: Some(3),
+            current_extent: Some([640, 480]),
+            min_image_extent: [1, 1],
+            max_image_extent: [4096, 4096],
+            max_image_array_layers: 1,
+            supported_transforms: SupportedSurfaceTransforms {
+                rotate90: true,
+                ..SupportedSurfaceTransforms::none()
+            },
+            current_transform: SurfaceTransform::Rotate90,
+            supported_composite_alpha: SupportedCompositeAlpha {
+                inherit: true,
+                ..SupportedCompositeAlpha::none()
+            },
+            supported_usage_flags: ImageUsage::color_attachment(),
+            supported_formats: vec![
+                (Format::B8G8R8A8Srgb, ColorSpace::SrgbNonLinear),
+                (Format::R8G8B8A8Unorm, ColorSpace::SrgbNonLinear),
+            ],
+            present_modes: SupportedPresentModes {
+                fifo: true,
+                ..SupportedPresentModes::none()
+            },
+        };
+
+        let params =
+            SwapchainParameters::from_capabilities(&capabilities, ImageUsage::color_attachment());
+        assert_eq!(
+            params,
+            SwapchainParameters {
+                num_images: 2,
+                format: Format::B8G8R8A8Srgb,
+                color_space: ColorSpace::SrgbNonLinear,
+                dimensions: None,
+                layers: 1,
+                usage: ImageUsage::color_attachment(),
+                sharing: SharingMode::Exclusive,
+                transform: SurfaceTransform::Rotate90,
+                composite_alpha: CompositeAlpha::Inherit,
+                present_mode: PresentMode::Fifo,
+                fullscreen_exclusive: FullscreenExclusive::Default,
+                win32_monitor: None,
+                clipped: true,
+            }
+        );
+    }
+
+    #[test]
+    fn usage_restricted_to_color_attachment_and_transfer_destination() {
+        assert_eq!(
+            check_swapchain_image_usage(ImageUsage {
+                color_attachment: true,
+                transfer_destination: true,
+                ..ImageUsage::none()
+            }),
+            Ok(())
+        );
+        assert_eq!(
+            check_swapchain_image_usage(ImageUsage {
+                color_attachment: true,
+                storage: true,
+                ..ImageUsage::none()
+            }),
+            Err(SwapchainCreationError::UsageNotAllowedForSwapchainImages)
+        );
+        assert_eq!(
+            check_swapchain_image_usage(ImageUsage {
+                sampled: true,
+                ..ImageUsage::none()
+            }),
+            Err(SwapchainCreationError::UsageNotAllowedForSwapchainImages)
+        );
+    }
+
+    #[test]
+    fn win32_monitor_required_when_app_controlled() {
+        assert_eq!(
+            check_win32_monitor(FullscreenExclusive::AppControlled, true, &None),
+            Err(SwapchainCreationError::MissingWin32Monitor)
+        );
+        assert_eq!(
+            check_win32_monitor(FullscreenExclusive::AppControlled, false, &None),
+            Ok(())
+        );
+        assert_eq!(
+            check_win32_monitor(FullscreenExclusive::Allowed, true, &None),
+            Ok(())
+        );
+
+        let monitor = unsafe { Win32Monitor::new(1usize as *const ()) };
+        assert_eq!(
+            check_win32_monitor(FullscreenExclusive::AppControlled, true, &Some(monitor)),
+            Ok(())
+        );
+    }
+}
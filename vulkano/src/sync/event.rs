@@ -10,6 +10,7 @@
 use std::mem::MaybeUninit;
 use std::ptr;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use check_errors;
 use device::Device;
@@ -25,6 +26,13 @@ use VulkanObject;
 /// event to be signaled, in order to avoid interfering with progress of other clients of the GPU.
 /// If the event isn't signaled within these limits, results are undefined and may include
 /// device loss.
+///
+/// The host operations of an event take `&self`, so that an event can be set or reset from the
+/// CPU while it is shared with the commands of a command buffer.
+///
+/// In debug builds, waiting on a `FenceSignalFuture` for more than a few seconds reports the
+/// events that its command buffers wait on and that are not set. The wait watchdog of the device
+/// reports them as well, see `Device::set_wait_watchdog`.
 #[derive(Debug)]
 pub struct Event {
     // The event.
@@ -32,6 +40,8 @@ pub struct Event {
     // The device.
     device: Arc<Device>,
     must_put_in_pool: bool,
+    // Vulkan requires the host accesses to an event to be externally synchronized.
+    host_access: Mutex<()>,
 }
 
 impl Event {
@@ -54,6 +64,7 @@ impl Event {
                     event: raw_event,
                     device: device,
                     must_put_in_pool: true,
+                    host_access: Mutex::new(()),
                 })
             }
             None => {
@@ -93,6 +104,7 @@ impl Event {
             device: device,
             event: event,
             must_put_in_pool: must_put_in_pool,
+            host_access: Mutex::new(()),
         })
     }
 
@@ -113,7 +125,8 @@ impl Event {
 
     /// See the docs of set().
     #[inline]
-    pub fn set_raw(&mut self) -> Result<(), OomError> {
+    pub fn set_raw(&self) -> Result<(), OomError> {
+        let _lock = self.host_access.lock().unwrap();
         unsafe {
             let vk = self.device.pointers();
             check_errors(vk.SetEvent(self.device.internal_object(), self.event))?;
@@ -130,13 +143,14 @@ impl Event {
     /// - Panics if the device or host ran out of memory.
     ///
     #[inline]
-    pub fn set(&mut self) {
+    pub fn set(&self) {
         self.set_raw().unwrap();
     }
 
     /// See the docs of reset().
     #[inline]
-    pub fn reset_raw(&mut self) -> Result<(), OomError> {
+    pub fn reset_raw(&self) -> Result<(), OomError> {
+        let _lock = self.host_access.lock().unwrap();
         unsafe {
             let vk = self.device.pointers();
             check_errors(vk.ResetEvent(self.device.internal_object(), self.event))?;
@@ -151,7 +165,7 @@ impl Event {
     /// - Panics if the device or host ran out of memory.
     ///
     #[inline]
-    pub fn reset(&mut self) {
+    pub fn reset(&self) {
         self.reset_raw().unwrap();
     }
 }
@@ -191,7 +205,13 @@ impl Drop for Event {
 
 #[cfg(test)]
 mod tests {
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::CommandBuffer;
+    use std::sync::Arc;
+    use sync::AccessFlagBits;
     use sync::Event;
+    use sync::GpuFuture;
+    use sync::PipelineStages;
     use VulkanObject;
 
     #[test]
@@ -204,7 +224,7 @@ mod tests {
     #[test]
     fn event_set() {
         let (device, _) = gfx_dev_and_queue!();
-        let mut event = Event::alloc(device).unwrap();
+        let event = Event::alloc(device).unwrap();
         assert!(!event.signaled().unwrap());
 
         event.set();
@@ -215,7 +235,7 @@ mod tests {
     fn event_reset() {
         let (device, _) = gfx_dev_and_queue!();
 
-        let mut event = Event::alloc(device).unwrap();
+        let event = Event::alloc(device).unwrap();
         event.set();
         assert!(event.signaled().unwrap());
 
@@ -239,4 +259,50 @@ mod tests {
         assert_eq!(device.event_pool().lock().unwrap().len(), 0);
         assert_eq!(event2.internal_object(), event1_internal_obj);
     }
+
+    #[test]
+    fn waited_events() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let event = Arc::new(Event::alloc(device.clone()).unwrap());
+        let mut cbb = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+        unsafe {
+            cbb.wait_events(
+                Some(event.clone()),
+                PipelineStages {
+                    host: true,
+                    ..PipelineStages::none()
+                },
+                AccessFlagBits {
+                    host_write: true,
+                    ..AccessFlagBits::none()
+                },
+                PipelineStages {
+                    transfer: true,
+                    ..PipelineStages::none()
+                },
+                AccessFlagBits {
+                    transfer_read: true,
+                    ..AccessFlagBits::none()
+                },
+            )
+            .unwrap();
+        }
+        let command_buffer = cbb.build().unwrap();
+        assert_eq!(command_buffer.waited_events().len(), 1);
+
+        let future = command_buffer
+            .execute(queue.clone())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+        let mut events = Vec::new();
+        future.waited_events(&mut events);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].internal_object(), event.internal_object());
+
+        // The event can be set while it is shared with the command buffer.
+        event.set();
+        future.wait(None).unwrap();
+    }
 }
@@ -0,0 +1,140 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use device::DeviceExtensions;
+use std::ops::BitOr;
+use vk;
+
+/// Describes the handle types that can be used to share a fence with other APIs or
+/// processes.
+///
+/// When exporting or importing a fence, exactly one of the fields must be set. When creating
+/// an exportable fence, several fields can be set at once.
+///
+/// - `opaque_fd` and `sync_fd` require the `khr_external_fence_fd` extension.
+/// - `opaque_win32` and `opaque_win32_kmt` require the `khr_external_fence_win32` extension.
+///
+/// All of them require the `khr_external_fence` extension.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ExternalFenceHandleType {
+    pub opaque_fd: bool,
+    pub opaque_win32: bool,
+    pub opaque_win32_kmt: bool,
+    pub sync_fd: bool,
+}
+
+impl ExternalFenceHandleType {
+    /// Builds an `ExternalFenceHandleType` with all fields set to false.
+    #[inline]
+    pub fn none() -> ExternalFenceHandleType {
+        ExternalFenceHandleType {
+            opaque_fd: false,
+            opaque_win32: false,
+            opaque_win32_kmt: false,
+            sync_fd: false,
+        }
+    }
+
+    /// Builds an `ExternalFenceHandleType` for a POSIX file descriptor.
+    #[inline]
+    pub fn posix() -> ExternalFenceHandleType {
+        ExternalFenceHandleType {
+            opaque_fd: true,
+            ..ExternalFenceHandleType::none()
+        }
+    }
+
+    /// Builds an `ExternalFenceHandleType` for a Linux sync file, as used by the DRM and
+    /// V4L2 subsystems.
+    #[inline]
+    pub fn sync_fd() -> ExternalFenceHandleType {
+        ExternalFenceHandleType {
+            sync_fd: true,
+            ..ExternalFenceHandleType::none()
+        }
+    }
+
+    /// Builds an `ExternalFenceHandleType` for a Windows NT handle.
+    #[inline]
+    pub fn win32() -> ExternalFenceHandleType {
+        ExternalFenceHandleType {
+            opaque_win32: true,
+            ..ExternalFenceHandleType::none()
+        }
+    }
+
+    /// Returns true if the handle types are file descriptors.
+    #[inline]
+    pub(crate) fn is_fd(&self) -> bool {
+        self.opaque_fd || self.sync_fd
+    }
+
+    /// Returns the number of fields that are set.
+    #[inline]
+    pub(crate) fn count(&self) -> u32 {
+        self.to_vulkan_bits().count_ones()
+    }
+
+    /// Turns this `ExternalFenceHandleType` into raw Vulkan bits.
+    pub(crate) fn to_vulkan_bits(&self) -> vk::ExternalFenceHandleTypeFlagsKHR {
+        let mut result = 0;
+        if self.opaque_fd {
+            result |= vk::EXTERNAL_FENCE_HANDLE_TYPE_OPAQUE_FD_BIT_KHR;
+        }
+        if self.opaque_win32 {
+            result |= vk::EXTERNAL_FENCE_HANDLE_TYPE_OPAQUE_WIN32_BIT_KHR;
+        }
+        if self.opaque_win32_kmt {
+            result |= vk::EXTERNAL_FENCE_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT_KHR;
+        }
+        if self.sync_fd {
+            result |= vk::EXTERNAL_FENCE_HANDLE_TYPE_SYNC_FD_BIT_KHR;
+        }
+        result
+    }
+
+    /// Checks that the extensions required by the handle types are enabled.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if an extension required by one of the handle types is not enabled.
+    ///
+    pub(crate) fn check_extensions(&self, extensions: &DeviceExtensions) {
+        assert!(
+            extensions.khr_external_fence,
+            "the khr_external_fence extension must be enabled"
+        );
+        if self.is_fd() {
+            assert!(
+                extensions.khr_external_fence_fd,
+                "the khr_external_fence_fd extension must be enabled"
+            );
+        }
+        if self.opaque_win32 || self.opaque_win32_kmt {
+            assert!(
+                extensions.khr_external_fence_win32,
+                "the khr_external_fence_win32 extension must be enabled"
+            );
+        }
+    }
+}
+
+impl BitOr for ExternalFenceHandleType {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        ExternalFenceHandleType {
+            opaque_fd: self.opaque_fd || rhs.opaque_fd,
+            opaque_win32: self.opaque_win32 || rhs.opaque_win32,
+            opaque_win32_kmt: self.opaque_win32_kmt || rhs.opaque_win32_kmt,
+            sync_fd: self.sync_fd || rhs.sync_fd,
+        }
+    }
+}
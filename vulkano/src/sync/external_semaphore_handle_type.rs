@@ -0,0 +1,147 @@
+// Copyright (c) 2020 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use device::DeviceExtensions;
+use std::ops::BitOr;
+use vk;
+
+/// Describes the handle types that can be used to share a semaphore with other APIs or
+/// processes.
+///
+/// When exporting or importing a semaphore, exactly one of the fields must be set. When creating
+/// an exportable semaphore, several fields can be set at once.
+///
+/// - `opaque_fd` and `sync_fd` require the `khr_external_semaphore_fd` extension.
+/// - `opaque_win32`, `opaque_win32_kmt` and `d3d12_fence` require the
+///   `khr_external_semaphore_win32` extension.
+///
+/// All of them require the `khr_external_semaphore` extension.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ExternalSemaphoreHandleType {
+    pub opaque_fd: bool,
+    pub opaque_win32: bool,
+    pub opaque_win32_kmt: bool,
+    pub d3d12_fence: bool,
+    pub sync_fd: bool,
+}
+
+impl ExternalSemaphoreHandleType {
+    /// Builds an `ExternalSemaphoreHandleType` with all fields set to false.
+    #[inline]
+    pub fn none() -> ExternalSemaphoreHandleType {
+        ExternalSemaphoreHandleType {
+            opaque_fd: false,
+            opaque_win32: false,
+            opaque_win32_kmt: false,
+            d3d12_fence: false,
+            sync_fd: false,
+        }
+    }
+
+    /// Builds an `ExternalSemaphoreHandleType` for a POSIX file descriptor.
+    #[inline]
+    pub fn posix() -> ExternalSemaphoreHandleType {
+        ExternalSemaphoreHandleType {
+            opaque_fd: true,
+            ..ExternalSemaphoreHandleType::none()
+        }
+    }
+
+    /// Builds an `ExternalSemaphoreHandleType` for a Linux sync file, as used by the DRM and
+    /// V4L2 subsystems.
+    #[inline]
+    pub fn sync_fd() -> ExternalSemaphoreHandleType {
+        ExternalSemaphoreHandleType {
+            sync_fd: true,
+            ..ExternalSemaphoreHandleType::none()
+        }
+    }
+
+    /// Builds an `ExternalSemaphoreHandleType` for a Windows NT handle.
+    #[inline]
+    pub fn win32() -> ExternalSemaphoreHandleType {
+        ExternalSemaphoreHandleType {
+            opaque_win32: true,
+            ..ExternalSemaphoreHandleType::none()
+        }
+    }
+
+    /// Returns true if the handle types are file descriptors.
+    #[inline]
+    pub(crate) fn is_fd(&self) -> bool {
+        self.opaque_fd || self.sync_fd
+    }
+
+    /// Returns the number of fields that are set.
+    #[inline]
+    pub(crate) fn count(&self) -> u32 {
+        self.to_vulkan_bits().count_ones()
+    }
+
+    /// Turns this `ExternalSemaphoreHandleType` into raw Vulkan bits.
+    pub(crate) fn to_vulkan_bits(&self) -> vk::ExternalSemaphoreHandleTypeFlagsKHR {
+        let mut result = 0;
+        if self.opaque_fd {
+            result |= vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_FD_BIT_KHR;
+        }
+        if self.opaque_win32 {
+            result |= vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32_BIT_KHR;
+        }
+        if self.opaque_win32_kmt {
+            result |= vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT_KHR;
+        }
+        if self.d3d12_fence {
+            result |= vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_D3D12_FENCE_BIT_KHR;
+        }
+        if self.sync_fd {
+            result |= vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_SYNC_FD_BIT_KHR;
+        }
+        result
+    }
+
+    /// Checks that the extensions required by the handle types are enabled.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if an extension required by one of the handle types is not enabled.
+    ///
+    pub(crate) fn check_extensions(&self, extensions: &DeviceExtensions) {
+        assert!(
+            extensions.khr_external_semaphore,
+            "the khr_external_semaphore extension must be enabled"
+        );
+        if self.is_fd() {
+            assert!(
+                extensions.khr_external_semaphore_fd,
+                "the khr_external_semaphore_fd extension must be enabled"
+            );
+        }
+        if self.opaque_win32 || self.opaque_win32_kmt || self.d3d12_fence {
+            assert!(
+                extensions.khr_external_semaphore_win32,
+                "the khr_external_semaphore_win32 extension must be enabled"
+            );
+        }
+    }
+}
+
+impl BitOr for ExternalSemaphoreHandleType {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        ExternalSemaphoreHandleType {
+            opaque_fd: self.opaque_fd || rhs.opaque_fd,
+            opaque_win32: self.opaque_win32 || rhs.opaque_win32,
+            opaque_win32_kmt: self.opaque_win32_kmt || rhs.opaque_win32_kmt,
+            d3d12_fence: self.d3d12_fence || rhs.d3d12_fence,
+            sync_fd: self.sync_fd || rhs.sync_fd,
+        }
+    }
+}
@@ -10,7 +10,15 @@
 use smallvec::SmallVec;
 use std::error;
 use std::fmt;
+#[cfg(unix)]
+use std::fs::File;
 use std::mem::MaybeUninit;
+#[cfg(windows)]
+use std::os::raw::c_void;
+#[cfg(unix)]
+use std::os::unix::io::FromRawFd;
+#[cfg(unix)]
+use std::os::unix::io::IntoRawFd;
 use std::ptr;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
@@ -20,6 +28,7 @@ use std::time::Duration;
 use check_errors;
 use device::Device;
 use device::DeviceOwned;
+use sync::ExternalFenceHandleType;
 use vk;
 use Error;
 use OomError;
@@ -49,6 +58,9 @@ where
     // Indicates whether this fence was taken from the fence pool.
     // If true, will be put back into fence pool on drop.
     must_put_in_pool: bool,
+
+    // Handle types the fence can be exported as.
+    export_handle_types: ExternalFenceHandleType,
 }
 
 impl<D> Fence<D>
@@ -75,11 +87,12 @@ where
                     device: device,
                     signaled: AtomicBool::new(false),
                     must_put_in_pool: true,
+                    export_handle_types: ExternalFenceHandleType::none(),
                 })
             }
             None => {
                 // Pool is empty, alloc new fence
-                Fence::alloc_impl(device, false, true)
+                Fence::alloc_impl(device, false, true, None)
             }
         }
     }
@@ -87,20 +100,53 @@ where
     /// Builds a new fence.
     #[inline]
     pub fn alloc(device: D) -> Result<Fence<D>, OomError> {
-        Fence::alloc_impl(device, false, false)
+        Fence::alloc_impl(device, false, false, None)
     }
 
     /// Builds a new fence in signaled state.
     #[inline]
     pub fn alloc_signaled(device: D) -> Result<Fence<D>, OomError> {
-        Fence::alloc_impl(device, true, false)
+        Fence::alloc_impl(device, true, false, None)
     }
 
-    fn alloc_impl(device: D, signaled: bool, must_put_in_pool: bool) -> Result<Fence<D>, OomError> {
+    /// Builds a new fence that can be exported with `export_fd` or `export_win32_handle` as one
+    /// of `handle_types`.
+    ///
+    /// This is used to synchronize with other APIs or processes, for example to let a compositor
+    /// know when the GPU has finished a submission.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the extensions required by `handle_types` are not enabled on the device.
+    ///
+    #[inline]
+    pub fn alloc_exportable(
+        device: D,
+        handle_types: ExternalFenceHandleType,
+    ) -> Result<Fence<D>, OomError> {
+        handle_types.check_extensions(device.loaded_extensions());
+        Fence::alloc_impl(device, false, false, Some(handle_types))
+    }
+
+    fn alloc_impl(
+        device: D,
+        signaled: bool,
+        must_put_in_pool: bool,
+        export: Option<ExternalFenceHandleType>,
+    ) -> Result<Fence<D>, OomError> {
         let fence = unsafe {
+            let export_info = export.map(|handle_types| vk::ExportFenceCreateInfoKHR {
+                sType: vk::STRUCTURE_TYPE_EXPORT_FENCE_CREATE_INFO_KHR,
+                pNext: ptr::null(),
+                handleTypes: handle_types.to_vulkan_bits(),
+            });
+
             let infos = vk::FenceCreateInfo {
                 sType: vk::STRUCTURE_TYPE_FENCE_CREATE_INFO,
-                pNext: ptr::null(),
+                pNext: export_info
+                    .as_ref()
+                    .map(|i| i as *const vk::ExportFenceCreateInfoKHR as *const _)
+                    .unwrap_or(ptr::null()),
                 flags: if signaled {
                     vk::FENCE_CREATE_SIGNALED_BIT
                 } else {
@@ -124,9 +170,218 @@ where
             device: device,
             signaled: AtomicBool::new(signaled),
             must_put_in_pool: must_put_in_pool,
+            export_handle_types: export.unwrap_or(ExternalFenceHandleType::none()),
         })
     }
 
+    /// Returns the handle types the fence can be exported as.
+    ///
+    /// This is only non-empty if the fence was created with `alloc_exportable`.
+    #[inline]
+    pub fn export_handle_types(&self) -> ExternalFenceHandleType {
+        self.export_handle_types
+    }
+
+    /// Exports the fence as a POSIX file descriptor.
+    ///
+    /// With `opaque_fd`, the file descriptor refers to the fence itself. With `sync_fd`, it is a
+    /// sync file that is signaled when the pending signal operation of the fence completes, and
+    /// exporting it resets the fence.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one file descriptor handle type.
+    /// - Panics if the fence wasn't created as exportable with `handle_type`.
+    ///
+    /// # Safety
+    ///
+    /// - With `sync_fd`, the fence must be signaled or a signal operation of the fence must have
+    ///   been submitted.
+    ///
+    #[cfg(unix)]
+    pub unsafe fn export_fd(
+        &self,
+        handle_type: ExternalFenceHandleType,
+    ) -> Result<File, ExternalFenceError> {
+        assert!(handle_type.count() == 1 && handle_type.is_fd());
+        assert!(
+            self.export_handle_types.to_vulkan_bits() & handle_type.to_vulkan_bits() != 0,
+            "the fence was not created as exportable with this handle type"
+        );
+
+        let vk = self.device.pointers();
+
+        let info = vk::FenceGetFdInfoKHR {
+            sType: vk::STRUCTURE_TYPE_FENCE_GET_FD_INFO_KHR,
+            pNext: ptr::null(),
+            fence: self.fence,
+            handleType: handle_type.to_vulkan_bits(),
+        };
+
+        let mut output = MaybeUninit::uninit();
+        check_errors(vk.GetFenceFdKHR(self.device.internal_object(), &info, output.as_mut_ptr()))?;
+
+        if handle_type.sync_fd {
+            self.signaled.store(false, Ordering::Relaxed);
+        }
+
+        Ok(File::from_raw_fd(output.assume_init()))
+    }
+
+    /// Imports the payload of a fence that was exported by another API or process as a POSIX
+    /// file descriptor.
+    ///
+    /// If `temporary` is true, the imported payload is only used until the next reset of the
+    /// fence, after which the fence goes back to its own payload. Sync files can only be
+    /// imported temporarily.
+    ///
+    /// On success, the ownership of `fd` is transferred to the Vulkan implementation and the file
+    /// descriptor must not be used anymore. On failure, `fd` is closed.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one file descriptor handle type.
+    /// - Panics if the extensions required by `handle_type` are not enabled on the device.
+    /// - Panics if `handle_type` is `sync_fd` and `temporary` is false.
+    /// - Panics if `temporary` is false and the fence was taken from the fence pool.
+    ///
+    /// # Safety
+    ///
+    /// - `fd` must be a valid fence handle of type `handle_type`.
+    /// - The fence must not be in use by any pending queue operation.
+    ///
+    #[cfg(unix)]
+    pub unsafe fn import_fd(
+        &self,
+        handle_type: ExternalFenceHandleType,
+        fd: File,
+        temporary: bool,
+    ) -> Result<(), ExternalFenceError> {
+        assert!(handle_type.count() == 1 && handle_type.is_fd());
+        handle_type.check_extensions(self.device.loaded_extensions());
+        assert!(temporary || !handle_type.sync_fd);
+        assert!(temporary || !self.must_put_in_pool);
+
+        let vk = self.device.pointers();
+        let fd = fd.into_raw_fd();
+
+        let info = vk::ImportFenceFdInfoKHR {
+            sType: vk::STRUCTURE_TYPE_IMPORT_FENCE_FD_INFO_KHR,
+            pNext: ptr::null(),
+            fence: self.fence,
+            flags: if temporary {
+                vk::FENCE_IMPORT_TEMPORARY_BIT_KHR
+            } else {
+                0
+            },
+            handleType: handle_type.to_vulkan_bits(),
+            fd: fd,
+        };
+
+        // The Vulkan implementation only takes ownership of the file descriptor on success.
+        match check_errors(vk.ImportFenceFdKHR(self.device.internal_object(), &info)) {
+            Ok(_) => {
+                self.signaled.store(false, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(err) => {
+                drop(File::from_raw_fd(fd));
+                Err(err.into())
+            }
+        }
+    }
+
+    /// Exports the fence as a Windows handle.
+    ///
+    /// For the NT handle types, the returned handle must be closed with `CloseHandle` once it is
+    /// no longer needed.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one Windows handle type.
+    /// - Panics if the fence wasn't created as exportable with `handle_type`.
+    ///
+    #[cfg(windows)]
+    pub fn export_win32_handle(
+        &self,
+        handle_type: ExternalFenceHandleType,
+    ) -> Result<*mut c_void, ExternalFenceError> {
+        assert!(handle_type.count() == 1 && !handle_type.is_fd());
+        assert!(
+            self.export_handle_types.to_vulkan_bits() & handle_type.to_vulkan_bits() != 0,
+            "the fence was not created as exportable with this handle type"
+        );
+
+        let vk = self.device.pointers();
+
+        unsafe {
+            let info = vk::FenceGetWin32HandleInfoKHR {
+                sType: vk::STRUCTURE_TYPE_FENCE_GET_WIN32_HANDLE_INFO_KHR,
+                pNext: ptr::null(),
+                fence: self.fence,
+                handleType: handle_type.to_vulkan_bits(),
+            };
+
+            let mut output = MaybeUninit::uninit();
+            check_errors(vk.GetFenceWin32HandleKHR(
+                self.device.internal_object(),
+                &info,
+                output.as_mut_ptr(),
+            ))?;
+            Ok(output.assume_init())
+        }
+    }
+
+    /// Imports the payload of a fence that was exported by another API or process as a Windows
+    /// handle.
+    ///
+    /// If `temporary` is true, the imported payload is only used until the next reset of the
+    /// fence. Contrary to file descriptors, the ownership of `handle` is not transferred. It can
+    /// be closed once the fence has been imported.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one Windows handle type.
+    /// - Panics if the extensions required by `handle_type` are not enabled on the device.
+    /// - Panics if `temporary` is false and the fence was taken from the fence pool.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid fence handle of type `handle_type`.
+    /// - The fence must not be in use by any pending queue operation.
+    ///
+    #[cfg(windows)]
+    pub unsafe fn import_win32_handle(
+        &self,
+        handle_type: ExternalFenceHandleType,
+        handle: *mut c_void,
+        temporary: bool,
+    ) -> Result<(), ExternalFenceError> {
+        assert!(handle_type.count() == 1 && !handle_type.is_fd());
+        handle_type.check_extensions(self.device.loaded_extensions());
+        assert!(temporary || !self.must_put_in_pool);
+
+        let vk = self.device.pointers();
+
+        let info = vk::ImportFenceWin32HandleInfoKHR {
+            sType: vk::STRUCTURE_TYPE_IMPORT_FENCE_WIN32_HANDLE_INFO_KHR,
+            pNext: ptr::null(),
+            fence: self.fence,
+            flags: if temporary {
+                vk::FENCE_IMPORT_TEMPORARY_BIT_KHR
+            } else {
+                0
+            },
+            handleType: handle_type.to_vulkan_bits(),
+            handle: handle,
+            name: ptr::null(),
+        };
+
+        check_errors(vk.ImportFenceWin32HandleKHR(self.device.internal_object(), &info))?;
+        self.signaled.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+
     /// Returns true if the fence is signaled.
     #[inline]
     pub fn ready(&self) -> Result<bool, OomError> {
@@ -189,21 +444,52 @@ where
         }
     }
 
-    /// Waits for multiple fences at once.
+    /// Waits for multiple fences at once, until all of them are signaled or the timeout duration
+    /// has elapsed.
     ///
     /// # Panic
     ///
     /// Panics if not all fences belong to the same device.
     pub fn multi_wait<'a, I>(iter: I, timeout: Option<Duration>) -> Result<(), FenceWaitError>
+    where
+        I: IntoIterator<Item = &'a Fence<D>>,
+        D: 'a,
+    {
+        Fence::multi_wait_impl(iter, timeout, true)
+    }
+
+    /// Waits for multiple fences at once, until at least one of them is signaled or the timeout
+    /// duration has elapsed.
+    ///
+    /// Returns `Ok` immediately if one of the fences is already known to be signaled, or if
+    /// `iter` is empty.
+    ///
+    /// # Panic
+    ///
+    /// Panics if not all fences belong to the same device.
+    pub fn multi_wait_any<'a, I>(iter: I, timeout: Option<Duration>) -> Result<(), FenceWaitError>
+    where
+        I: IntoIterator<Item = &'a Fence<D>>,
+        D: 'a,
+    {
+        Fence::multi_wait_impl(iter, timeout, false)
+    }
+
+    fn multi_wait_impl<'a, I>(
+        iter: I,
+        timeout: Option<Duration>,
+        wait_all: bool,
+    ) -> Result<(), FenceWaitError>
     where
         I: IntoIterator<Item = &'a Fence<D>>,
         D: 'a,
     {
         let mut device: Option<&Device> = None;
+        let mut any_signaled = false;
 
-        let fences: SmallVec<[vk::Fence; 8]> = iter
+        let fences: SmallVec<[&Fence<D>; 8]> = iter
             .into_iter()
-            .filter_map(|fence| {
+            .filter(|fence| {
                 match &mut device {
                     dev @ &mut None => *dev = Some(&*fence.device),
                     &mut Some(ref dev)
@@ -215,13 +501,20 @@ where
                 };
 
                 if fence.signaled.load(Ordering::Relaxed) {
-                    None
+                    any_signaled = true;
+                    false
                 } else {
-                    Some(fence.fence)
+                    true
                 }
             })
             .collect();
 
+        if fences.is_empty() || (any_signaled && !wait_all) {
+            return Ok(());
+        }
+
+        let raw_fences: SmallVec<[vk::Fence; 8]> = fences.iter().map(|fence| fence.fence).collect();
+
         let timeout_ns = if let Some(timeout) = timeout {
             timeout
                 .as_secs()
@@ -231,23 +524,27 @@ where
             u64::max_value()
         };
 
-        let r = if let Some(device) = device {
-            unsafe {
-                let vk = device.pointers();
-                check_errors(vk.WaitForFences(
-                    device.internal_object(),
-                    fences.len() as u32,
-                    fences.as_ptr(),
-                    vk::TRUE,
-                    timeout_ns,
-                ))?
-            }
-        } else {
-            return Ok(());
+        let r = unsafe {
+            let device = device.unwrap();
+            let vk = device.pointers();
+            check_errors(vk.WaitForFences(
+                device.internal_object(),
+                raw_fences.len() as u32,
+                raw_fences.as_ptr(),
+                if wait_all { vk::TRUE } else { vk::FALSE },
+                timeout_ns,
+            ))?
         };
 
         match r {
-            Success::Success => Ok(()),
+            Success::Success => {
+                if wait_all {
+                    for fence in fences {
+                        fence.signaled.store(true, Ordering::Relaxed);
+                    }
+                }
+                Ok(())
+            }
             Success::Timeout => Err(FenceWaitError::Timeout),
             _ => unreachable!(),
         }
@@ -400,9 +697,64 @@ impl From<Error> for FenceWaitError {
     }
 }
 
+/// Error that can happen when exporting or importing a fence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExternalFenceError {
+    /// Not enough memory available.
+    OomError(OomError),
+    /// The maximum number of handles has been exceeded.
+    TooManyObjects,
+    /// The handle to import is invalid or isn't of the requested handle type.
+    InvalidExternalHandle,
+}
+
+impl error::Error for ExternalFenceError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            ExternalFenceError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ExternalFenceError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                ExternalFenceError::OomError(_) => "not enough memory available",
+                ExternalFenceError::TooManyObjects => {
+                    "the maximum number of handles has been exceeded"
+                }
+                ExternalFenceError::InvalidExternalHandle => {
+                    "the handle to import is invalid or isn't of the requested handle type"
+                }
+            }
+        )
+    }
+}
+
+impl From<Error> for ExternalFenceError {
+    #[inline]
+    fn from(err: Error) -> ExternalFenceError {
+        match err {
+            e @ Error::OutOfHostMemory | e @ Error::OutOfDeviceMemory => {
+                ExternalFenceError::OomError(e.into())
+            }
+            Error::TooManyObjects => ExternalFenceError::TooManyObjects,
+            Error::InvalidExternalHandle => ExternalFenceError::InvalidExternalHandle,
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
+    use sync::ExternalFenceHandleType;
     use sync::Fence;
     use VulkanObject;
 
@@ -430,6 +782,25 @@ mod tests {
         fence.wait(Some(Duration::new(0, 10))).unwrap();
     }
 
+    #[test]
+    fn multi_wait_any_signaled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let signaled = Fence::alloc_signaled(device.clone()).unwrap();
+        let unsignaled = Fence::alloc(device.clone()).unwrap();
+
+        Fence::multi_wait_any(
+            [&unsignaled, &signaled].iter().cloned(),
+            Some(Duration::new(0, 10)),
+        )
+        .unwrap();
+        assert!(Fence::multi_wait(
+            [&unsignaled, &signaled].iter().cloned(),
+            Some(Duration::new(0, 10)),
+        )
+        .is_err());
+    }
+
     #[test]
     fn fence_reset() {
         let (device, _) = gfx_dev_and_queue!();
@@ -494,4 +865,13 @@ mod tests {
         assert_eq!(device.fence_pool().lock().unwrap().len(), 0);
         assert_eq!(fence2.internal_object(), fence1_internal_obj);
     }
+
+    #[test]
+    fn exportable_requires_extension() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        assert_should_panic!("the khr_external_fence extension must be enabled", {
+            let _ = Fence::alloc_exportable(device, ExternalFenceHandleType::posix());
+        });
+    }
 }
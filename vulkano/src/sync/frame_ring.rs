@@ -0,0 +1,267 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Multiple copies of a resource used in turn by successive frames.
+//!
+//! When rendering, the CPU usually prepares frame N+1 while the GPU is still processing frame N.
+//! Resources that are modified every frame, such as a uniform buffer or a descriptor set that
+//! points to it, therefore need to exist in several copies: one that the GPU is reading and one
+//! that the CPU is writing.
+//!
+//! A `FrameRing` holds these copies and hands them out in turn. Each time you submit the work of
+//! a frame, you give the corresponding `FenceSignalFuture` to the ring. When the ring comes back
+//! to the same copy a few frames later, it waits for that fence before letting you access the
+//! copy again.
+//!
+//! ```
+//! # use vulkano::sync::FrameRing;
+//! # use vulkano::sync::GpuFuture;
+//! # let device: std::sync::Arc<vulkano::device::Device> = return;
+//! # fn create_uniform_buffer() -> u32 { 0 }
+//! let mut ring = FrameRing::from_fn(2, |_| create_uniform_buffer());
+//!
+//! loop {
+//!     // Blocks if the GPU is still using this copy.
+//!     let uniforms = ring.next_frame(None).unwrap();
+//!     // ... write to `uniforms` and record the command buffer of the frame ...
+//!
+//!     let future = vulkano::sync::now(device.clone())
+//!         .then_signal_fence_and_flush()
+//!         .unwrap();
+//!     ring.submit(future);
+//! }
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+
+use sync::FenceSignalFuture;
+use sync::FlushError;
+use sync::GpuFuture;
+
+/// Ring of `N` copies of a resource, with one copy per frame in flight.
+///
+/// See the module-level documentation for more information.
+pub struct FrameRing<T> {
+    frames: Vec<Frame<T>>,
+    // Index of the copy being written by the CPU.
+    current: usize,
+}
+
+struct Frame<T> {
+    resource: T,
+    // Future of the last submission that uses this copy, if it hasn't been waited upon yet.
+    future: Option<Box<dyn FrameFuture>>,
+}
+
+// Type-erased `FenceSignalFuture`.
+trait FrameFuture {
+    fn wait(&self, timeout: Option<Duration>) -> Result<(), FlushError>;
+}
+
+impl<F> FrameFuture for FenceSignalFuture<F>
+where
+    F: GpuFuture,
+{
+    #[inline]
+    fn wait(&self, timeout: Option<Duration>) -> Result<(), FlushError> {
+        FenceSignalFuture::wait(self, timeout)
+    }
+}
+
+impl<T> FrameRing<T> {
+    /// Builds a ring from a list of copies of the resource.
+    ///
+    /// The first frame uses the first element of the list.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `resources` is empty.
+    ///
+    pub fn new<I>(resources: I) -> FrameRing<T>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let frames: Vec<_> = resources
+            .into_iter()
+            .map(|resource| Frame {
+                resource: resource,
+                future: None,
+            })
+            .collect();
+        assert!(!frames.is_empty());
+
+        FrameRing {
+            frames: frames,
+            current: 0,
+        }
+    }
+
+    /// Builds a ring of `count` copies, by calling `f` with the index of each copy.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `count` is 0.
+    ///
+    #[inline]
+    pub fn from_fn<F>(count: usize, f: F) -> FrameRing<T>
+    where
+        F: FnMut(usize) -> T,
+    {
+        FrameRing::new((0..count).map(f))
+    }
+
+    /// Returns the number of copies in the ring.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns the index of the copy used by the current frame.
+    #[inline]
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Returns the copy used by the current frame.
+    #[inline]
+    pub fn current(&self) -> &T {
+        &self.frames[self.current].resource
+    }
+
+    /// Returns the copy used by the current frame, for writing.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the current frame has already been submitted with `submit`, as the GPU may
+    ///   still be using the copy. Call `next_frame` first.
+    ///
+    #[inline]
+    pub fn current_mut(&mut self) -> &mut T {
+        let frame = &mut self.frames[self.current];
+        assert!(
+            frame.future.is_none(),
+            "the current frame has already been submitted"
+        );
+        &mut frame.resource
+    }
+
+    /// Moves to the next copy of the ring and returns it.
+    ///
+    /// If the copy has been used by a submission that may still be executing, blocks until the
+    /// fence of this submission is signaled. If `timeout` is `None`, the wait is infinite.
+    /// Otherwise a `FlushError::Timeout` is returned if the fence is not signaled in time.
+    ///
+    /// Waiting for the fence also releases the resources that were locked by the submission.
+    pub fn next_frame(&mut self, timeout: Option<Duration>) -> Result<&mut T, FlushError> {
+        self.current = (self.current + 1) % self.frames.len();
+
+        let frame = &mut self.frames[self.current];
+        if let Some(future) = frame.future.take() {
+            future.wait(timeout)?;
+        }
+
+        Ok(&mut frame.resource)
+    }
+
+    /// Registers the future of the submission that uses the copy of the current frame.
+    ///
+    /// The next time the ring comes back to this copy, `next_frame` waits for the fence of
+    /// `future` to be signaled.
+    ///
+    /// If the current frame has already been submitted, the previous future is waited upon and
+    /// dropped.
+    pub fn submit<F>(&mut self, future: FenceSignalFuture<F>)
+    where
+        F: GpuFuture + 'static,
+    {
+        // Dropping the previous future blocks until it is signaled.
+        self.frames[self.current].future = Some(Box::new(future));
+    }
+
+    /// Blocks until all the submissions registered with `submit` are finished.
+    ///
+    /// This is typically used before destroying the resources, for example when recreating the
+    /// swapchain.
+    pub fn wait_all(&mut self, timeout: Option<Duration>) -> Result<(), FlushError> {
+        for frame in self.frames.iter_mut() {
+            if let Some(future) = frame.future.take() {
+                future.wait(timeout)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns all the copies of the ring, in order.
+    ///
+    /// The copies that are not used by the current frame may still be in use by the GPU, which
+    /// is why this method only gives read access.
+    #[inline]
+    pub fn resources(&self) -> impl ExactSizeIterator<Item = &T> {
+        self.frames.iter().map(|frame| &frame.resource)
+    }
+}
+
+impl<T> fmt::Debug for FrameRing<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        fmt.debug_struct("FrameRing")
+            .field("resources", &self.resources().collect::<Vec<_>>())
+            .field("current", &self.current)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+    use sync::now;
+    use sync::FrameRing;
+    use sync::GpuFuture;
+
+    #[test]
+    fn rotates() {
+        let mut ring = FrameRing::new(vec![10, 20, 30]);
+        assert_eq!(ring.len(), 3);
+        assert_eq!(*ring.current(), 10);
+
+        assert_eq!(*ring.next_frame(None).unwrap(), 20);
+        assert_eq!(*ring.next_frame(None).unwrap(), 30);
+        assert_eq!(*ring.next_frame(None).unwrap(), 10);
+        assert_eq!(ring.current_index(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty() {
+        let _ = FrameRing::<u32>::new(vec![]);
+    }
+
+    #[test]
+    fn write_after_submit() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let mut ring = FrameRing::from_fn(2, |n| n);
+        let future = now(device.clone()).then_signal_fence_and_flush().unwrap();
+        ring.submit(future);
+
+        // The ring can't be unwind-safe since it holds the future.
+        let res = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            *ring.current_mut() = 5;
+        }));
+        assert!(res.is_err());
+
+        ring.next_frame(None).unwrap();
+        *ring.next_frame(None).unwrap() = 5;
+        assert_eq!(*ring.current(), 5);
+    }
+}
@@ -23,10 +23,13 @@ use image::ImageAccess;
 use image::ImageLayout;
 use sync::AccessCheckError;
 use sync::AccessFlagBits;
+use sync::Event;
 use sync::Fence;
+use sync::FenceWaitError;
 use sync::FlushError;
 use sync::GpuFuture;
 use sync::PipelineStages;
+use VulkanObject;
 
 /// Builds a new fence signal future.
 #[inline]
@@ -144,7 +147,7 @@ where
 
         match mem::replace(&mut *state, FenceSignalFutureState::Cleaned) {
             FenceSignalFutureState::Flushed(previous, fence) => {
-                fence.wait(timeout)?;
+                wait_with_watchdog(&previous, &fence, timeout)?;
                 unsafe {
                     previous.signal_finished();
                 }
@@ -156,6 +159,75 @@ where
     }
 }
 
+// In debug builds, time after which a wait whose command buffers wait on events reports the
+// events that are not set, if the wait watchdog of the device is disabled.
+#[cfg(debug_assertions)]
+const UNSET_EVENTS_TIMEOUT: Option<Duration> = Some(Duration::from_secs(5));
+#[cfg(not(debug_assertions))]
+const UNSET_EVENTS_TIMEOUT: Option<Duration> = None;
+
+// Waits for `fence`. If the wait takes longer than the timeout of the wait watchdog of the device,
+// prints the operations that `previous` depends on and the events waited on by its command
+// buffers that are not set, before waiting for the rest of `timeout`.
+fn wait_with_watchdog<F>(
+    previous: &F,
+    fence: &Fence,
+    timeout: Option<Duration>,
+) -> Result<(), FenceWaitError>
+where
+    F: GpuFuture,
+{
+    let mut events = Vec::new();
+    previous.waited_events(&mut events);
+
+    let watchdog = fence.device().wait_watchdog();
+    let report_after = match (watchdog, UNSET_EVENTS_TIMEOUT) {
+        (Some(watchdog), _) => watchdog,
+        (None, Some(unset_events_timeout)) if !events.is_empty() => unset_events_timeout,
+        _ => return fence.wait(timeout),
+    };
+
+    if timeout.map(|t| t <= report_after).unwrap_or(false) {
+        return fence.wait(timeout);
+    }
+
+    match fence.wait(Some(report_after)) {
+        Err(FenceWaitError::Timeout) => (),
+        result => return result,
+    }
+
+    if watchdog.is_some() {
+        let mut dependencies = Vec::new();
+        previous.describe_dependencies(&mut dependencies);
+        eprintln!(
+            "vulkano: waiting on a fence for more than {:?}, it depends on:",
+            report_after
+        );
+        for dependency in dependencies {
+            eprintln!("    - {}", dependency);
+        }
+    }
+
+    // An event that is set or reset by the command buffers themselves may be reported as well,
+    // but an event that is never set is always reported.
+    let unset_events = events
+        .iter()
+        .filter(|event| !event.signaled().unwrap_or(true))
+        .collect::<Vec<_>>();
+    if !unset_events.is_empty() {
+        eprintln!(
+            "vulkano: waiting on a fence for more than {:?}, its command buffers wait on events \
+             that are not set:",
+            report_after
+        );
+        for event in unset_events {
+            eprintln!("    - event {:#x}", event.internal_object());
+        }
+    }
+
+    fence.wait(timeout.map(|t| t - report_after))
+}
+
 impl<F> FenceSignalFuture<F>
 where
     F: GpuFuture,
@@ -411,6 +483,43 @@ where
             Err(AccessCheckError::Unknown)
         }
     }
+
+    #[inline]
+    fn describe_dependencies(&self, out: &mut Vec<String>) {
+        // Another thread may be waiting on this future while holding the lock.
+        match self.state.try_lock() {
+            Ok(state) => match *state {
+                FenceSignalFutureState::Pending(ref prev, _)
+                | FenceSignalFutureState::PartiallyFlushed(ref prev, _) => {
+                    out.push("fence signal (not flushed)".to_owned());
+                    prev.describe_dependencies(out);
+                }
+                FenceSignalFutureState::Flushed(ref prev, _) => {
+                    out.push("fence signal".to_owned());
+                    prev.describe_dependencies(out);
+                }
+                FenceSignalFutureState::Cleaned => {
+                    out.push("fence signal (finished)".to_owned());
+                }
+                FenceSignalFutureState::Poisoned => {
+                    out.push("fence signal (poisoned)".to_owned());
+                }
+            },
+            Err(_) => out.push("fence signal (being waited on by another thread)".to_owned()),
+        }
+    }
+
+    #[inline]
+    fn waited_events(&self, out: &mut Vec<Arc<Event>>) {
+        if let Ok(state) = self.state.try_lock() {
+            match *state {
+                FenceSignalFutureState::Pending(ref prev, _)
+                | FenceSignalFutureState::PartiallyFlushed(ref prev, _)
+                | FenceSignalFutureState::Flushed(ref prev, _) => prev.waited_events(out),
+                FenceSignalFutureState::Cleaned | FenceSignalFutureState::Poisoned => (),
+            }
+        }
+    }
 }
 
 unsafe impl<F> DeviceOwned for FenceSignalFuture<F>
@@ -437,7 +546,7 @@ where
             FenceSignalFutureState::Flushed(previous, fence) => {
                 // This is a normal situation. Submitting worked.
                 // TODO: handle errors?
-                fence.wait(None).unwrap();
+                wait_with_watchdog(&previous, &fence, None).unwrap();
                 unsafe {
                     previous.signal_finished();
                 }
@@ -513,4 +622,14 @@ where
     ) -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
         (**self).check_image_access(image, layout, exclusive, queue)
     }
+
+    #[inline]
+    fn describe_dependencies(&self, out: &mut Vec<String>) {
+        (**self).describe_dependencies(out)
+    }
+
+    #[inline]
+    fn waited_events(&self, out: &mut Vec<Arc<Event>>) {
+        (**self).waited_events(out)
+    }
 }
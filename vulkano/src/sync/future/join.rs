@@ -18,6 +18,7 @@ use image::ImageAccess;
 use image::ImageLayout;
 use sync::AccessCheckError;
 use sync::AccessFlagBits;
+use sync::Event;
 use sync::FlushError;
 use sync::GpuFuture;
 use sync::PipelineStages;
@@ -265,4 +266,16 @@ where
             (Ok(Some((a1, a2))), Ok(Some((b1, b2)))) => Ok(Some((a1 | b1, a2 | b2))),
         }
     }
+
+    #[inline]
+    fn describe_dependencies(&self, out: &mut Vec<String>) {
+        self.first.describe_dependencies(out);
+        self.second.describe_dependencies(out);
+    }
+
+    #[inline]
+    fn waited_events(&self, out: &mut Vec<Arc<Event>>) {
+        self.first.waited_events(out);
+        self.second.waited_events(out);
+    }
 }
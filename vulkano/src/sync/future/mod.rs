@@ -28,6 +28,7 @@ use swapchain::PresentFuture;
 use swapchain::PresentRegion;
 use swapchain::Swapchain;
 use sync::AccessFlagBits;
+use sync::Event;
 use sync::FenceWaitError;
 use sync::PipelineStages;
 use OomError;
@@ -45,6 +46,42 @@ mod semaphore_signal;
 /// Represents an event that will happen on the GPU in the future.
 ///
 /// See the documentation of the `sync` module for explanations about futures.
+///
+/// # Example
+///
+/// A typical frame acquires an image, executes a command buffer that draws to it, presents it,
+/// and signals a fence. The semaphores between these operations are inserted automatically, and
+/// the resulting future keeps the command buffer and its resources alive until the fence is
+/// signaled.
+///
+/// ```
+/// use vulkano::swapchain;
+/// use vulkano::sync;
+/// use vulkano::sync::GpuFuture;
+/// # use std::sync::Arc;
+/// # let device: Arc<vulkano::device::Device> = return;
+/// # let queue: Arc<vulkano::device::Queue> = return;
+/// # let swapchain: Arc<vulkano::swapchain::Swapchain<()>> = return;
+/// # let command_buffer: vulkano::command_buffer::AutoCommandBuffer = return;
+///
+/// let mut previous_frame_end: Box<dyn GpuFuture> = Box::new(sync::now(device.clone()));
+///
+/// // At each frame, release the resources of the frames that have finished.
+/// previous_frame_end.cleanup_finished();
+///
+/// let (image_num, _suboptimal, acquire_future) =
+///     swapchain::acquire_next_image(swapchain.clone(), None).unwrap();
+///
+/// let future = previous_frame_end
+///     .join(acquire_future)
+///     .then_execute(queue.clone(), command_buffer)
+///     .unwrap()
+///     .then_swapchain_present(queue.clone(), swapchain.clone(), image_num)
+///     .then_signal_fence_and_flush()
+///     .unwrap();
+///
+/// previous_frame_end = future.boxed();
+/// ```
 // TODO: consider switching all methods to take `&mut self` for optimization purposes
 pub unsafe trait GpuFuture: DeviceOwned {
     /// If possible, checks whether the submission has finished. If so, gives up ownership of the
@@ -144,6 +181,22 @@ pub unsafe trait GpuFuture: DeviceOwned {
         queue: &Queue,
     ) -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError>;
 
+    /// Appends to `out` a description of the operations that this future represents and depends
+    /// on, from the most recent to the oldest.
+    ///
+    /// This is used by the wait watchdog of the device to report what a frozen wait is blocked
+    /// on. The default implementation doesn't describe anything.
+    #[inline]
+    fn describe_dependencies(&self, _out: &mut Vec<String>) {}
+
+    /// Appends to `out` the events that the command buffers submitted by this future and the
+    /// futures it depends on wait on.
+    ///
+    /// This is used to report the events that are not set when a wait is blocked. The default
+    /// implementation doesn't append anything.
+    #[inline]
+    fn waited_events(&self, _out: &mut Vec<Arc<Event>>) {}
+
     /// Joins this future with another one, representing the moment when both events have happened.
     // TODO: handle errors
     fn join<F>(self, other: F) -> JoinFuture<Self, F>
@@ -349,6 +402,16 @@ where
     ) -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
         (**self).check_image_access(image, layout, exclusive, queue)
     }
+
+    #[inline]
+    fn describe_dependencies(&self, out: &mut Vec<String>) {
+        (**self).describe_dependencies(out)
+    }
+
+    #[inline]
+    fn waited_events(&self, out: &mut Vec<Arc<Event>>) {
+        (**self).waited_events(out)
+    }
 }
 
 /// Access to a resource was denied.
@@ -23,6 +23,7 @@ use image::ImageAccess;
 use image::ImageLayout;
 use sync::AccessCheckError;
 use sync::AccessFlagBits;
+use sync::Event;
 use sync::FlushError;
 use sync::GpuFuture;
 use sync::PipelineStages;
@@ -168,6 +169,24 @@ where
             .check_image_access(image, layout, exclusive, queue)
             .map(|_| None)
     }
+
+    #[inline]
+    fn describe_dependencies(&self, out: &mut Vec<String>) {
+        out.push(format!(
+            "semaphore signal{}",
+            if self.finished.load(Ordering::SeqCst) {
+                " (finished)"
+            } else {
+                ""
+            }
+        ));
+        self.previous.describe_dependencies(out);
+    }
+
+    #[inline]
+    fn waited_events(&self, out: &mut Vec<Arc<Event>>) {
+        self.previous.waited_events(out);
+    }
 }
 
 unsafe impl<F> DeviceOwned for SemaphoreSignalFuture<F>
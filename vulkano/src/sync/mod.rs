@@ -107,8 +107,12 @@ use device::Queue;
 use std::sync::Arc;
 
 pub use self::event::Event;
+pub use self::external_fence_handle_type::ExternalFenceHandleType;
+pub use self::external_semaphore_handle_type::ExternalSemaphoreHandleType;
+pub use self::fence::ExternalFenceError;
 pub use self::fence::Fence;
 pub use self::fence::FenceWaitError;
+pub use self::frame_ring::FrameRing;
 pub use self::future::now;
 pub use self::future::AccessCheckError;
 pub use self::future::AccessError;
@@ -120,10 +124,14 @@ pub use self::future::NowFuture;
 pub use self::future::SemaphoreSignalFuture;
 pub use self::pipeline::AccessFlagBits;
 pub use self::pipeline::PipelineStages;
+pub use self::semaphore::ExternalSemaphoreError;
 pub use self::semaphore::Semaphore;
 
 mod event;
+mod external_fence_handle_type;
+mod external_semaphore_handle_type;
 mod fence;
+mod frame_ring;
 mod future;
 mod pipeline;
 mod semaphore;
@@ -77,6 +77,9 @@ pipeline_stages! {
     late_fragment_tests => vk::PIPELINE_STAGE_LATE_FRAGMENT_TESTS_BIT,
     color_attachment_output => vk::PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT,
     compute_shader => vk::PIPELINE_STAGE_COMPUTE_SHADER_BIT,
+    task_shader => vk::PIPELINE_STAGE_TASK_SHADER_BIT_EXT,
+    mesh_shader => vk::PIPELINE_STAGE_MESH_SHADER_BIT_EXT,
+    ray_tracing_shader => vk::PIPELINE_STAGE_RAY_TRACING_SHADER_BIT_NV,
     transfer => vk::PIPELINE_STAGE_TRANSFER_BIT,
     bottom_of_pipe => vk::PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT,
     host => vk::PIPELINE_STAGE_HOST_BIT,
@@ -195,6 +198,9 @@ impl AccessFlagBits {
             && !stages.geometry_shader
             && !stages.fragment_shader
             && !stages.compute_shader
+            && !stages.task_shader
+            && !stages.mesh_shader
+            && !stages.ray_tracing_shader
             && !stages.all_graphics
         {
             return false;
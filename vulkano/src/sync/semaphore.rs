@@ -7,14 +7,26 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+use std::error;
+use std::fmt;
+#[cfg(unix)]
+use std::fs::File;
 use std::mem::MaybeUninit;
+#[cfg(windows)]
+use std::os::raw::c_void;
+#[cfg(unix)]
+use std::os::unix::io::FromRawFd;
+#[cfg(unix)]
+use std::os::unix::io::IntoRawFd;
 use std::ptr;
 use std::sync::Arc;
 
 use check_errors;
 use device::Device;
 use device::DeviceOwned;
+use sync::ExternalSemaphoreHandleType;
 use vk;
+use Error;
 use OomError;
 use SafeDeref;
 use VulkanObject;
@@ -31,6 +43,7 @@ where
     semaphore: vk::Semaphore,
     device: D,
     must_put_in_pool: bool,
+    export_handle_types: ExternalSemaphoreHandleType,
 }
 
 impl<D> Semaphore<D>
@@ -50,10 +63,11 @@ where
                 device: device,
                 semaphore: raw_sem,
                 must_put_in_pool: true,
+                export_handle_types: ExternalSemaphoreHandleType::none(),
             }),
             None => {
                 // Pool is empty, alloc new semaphore
-                Semaphore::alloc_impl(device, true)
+                Semaphore::alloc_impl(device, true, None)
             }
         }
     }
@@ -61,23 +75,54 @@ where
     /// Builds a new semaphore.
     #[inline]
     pub fn alloc(device: D) -> Result<Semaphore<D>, OomError> {
-        Semaphore::alloc_impl(device, false)
+        Semaphore::alloc_impl(device, false, None)
     }
 
-    fn alloc_impl(device: D, must_put_in_pool: bool) -> Result<Semaphore<D>, OomError> {
+    /// Builds a new semaphore that can be exported with `export_fd` or `export_win32_handle` as
+    /// one of `handle_types`.
+    ///
+    /// This is used to synchronize with other APIs or processes, for example to let a media
+    /// pipeline know when the GPU has finished writing to an exported image.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the extensions required by `handle_types` are not enabled on the device.
+    ///
+    #[inline]
+    pub fn alloc_exportable(
+        device: D,
+        handle_types: ExternalSemaphoreHandleType,
+    ) -> Result<Semaphore<D>, OomError> {
+        handle_types.check_extensions(device.loaded_extensions());
+        Semaphore::alloc_impl(device, false, Some(handle_types))
+    }
+
+    fn alloc_impl(
+        device: D,
+        must_put_in_pool: bool,
+        export: Option<ExternalSemaphoreHandleType>,
+    ) -> Result<Semaphore<D>, OomError> {
         let semaphore = unsafe {
-            // since the creation is constant, we use a `static` instead of a struct on the stack
-            static mut INFOS: vk::SemaphoreCreateInfo = vk::SemaphoreCreateInfo {
+            let export_info = export.map(|handle_types| vk::ExportSemaphoreCreateInfoKHR {
+                sType: vk::STRUCTURE_TYPE_EXPORT_SEMAPHORE_CREATE_INFO_KHR,
+                pNext: ptr::null(),
+                handleTypes: handle_types.to_vulkan_bits(),
+            });
+
+            let infos = vk::SemaphoreCreateInfo {
                 sType: vk::STRUCTURE_TYPE_SEMAPHORE_CREATE_INFO,
-                pNext: 0 as *const _, // ptr::null()
-                flags: 0,             // reserved
+                pNext: export_info
+                    .as_ref()
+                    .map(|i| i as *const vk::ExportSemaphoreCreateInfoKHR as *const _)
+                    .unwrap_or(ptr::null()),
+                flags: 0, // reserved
             };
 
             let vk = device.pointers();
             let mut output = MaybeUninit::uninit();
             check_errors(vk.CreateSemaphore(
                 device.internal_object(),
-                &INFOS,
+                &infos,
                 ptr::null(),
                 output.as_mut_ptr(),
             ))?;
@@ -88,8 +133,212 @@ where
             device: device,
             semaphore: semaphore,
             must_put_in_pool: must_put_in_pool,
+            export_handle_types: export.unwrap_or(ExternalSemaphoreHandleType::none()),
         })
     }
+
+    /// Returns the handle types the semaphore can be exported as.
+    ///
+    /// This is only non-empty if the semaphore was created with `alloc_exportable`.
+    #[inline]
+    pub fn export_handle_types(&self) -> ExternalSemaphoreHandleType {
+        self.export_handle_types
+    }
+
+    /// Exports the semaphore as a POSIX file descriptor.
+    ///
+    /// With `opaque_fd`, the file descriptor refers to the semaphore itself. With `sync_fd`, it
+    /// is a sync file that is signaled when the pending signal operation of the semaphore
+    /// completes, and exporting it unsignals the semaphore as if it had been waited upon.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one file descriptor handle type.
+    /// - Panics if the semaphore wasn't created as exportable with `handle_type`.
+    ///
+    /// # Safety
+    ///
+    /// - With `sync_fd`, a signal operation of the semaphore must have been submitted.
+    ///
+    #[cfg(unix)]
+    pub unsafe fn export_fd(
+        &self,
+        handle_type: ExternalSemaphoreHandleType,
+    ) -> Result<File, ExternalSemaphoreError> {
+        assert!(handle_type.count() == 1 && handle_type.is_fd());
+        assert!(
+            self.export_handle_types.to_vulkan_bits() & handle_type.to_vulkan_bits() != 0,
+            "the semaphore was not created as exportable with this handle type"
+        );
+
+        let vk = self.device.pointers();
+
+        let info = vk::SemaphoreGetFdInfoKHR {
+            sType: vk::STRUCTURE_TYPE_SEMAPHORE_GET_FD_INFO_KHR,
+            pNext: ptr::null(),
+            semaphore: self.semaphore,
+            handleType: handle_type.to_vulkan_bits(),
+        };
+
+        let mut output = MaybeUninit::uninit();
+        check_errors(vk.GetSemaphoreFdKHR(
+            self.device.internal_object(),
+            &info,
+            output.as_mut_ptr(),
+        ))?;
+
+        Ok(File::from_raw_fd(output.assume_init()))
+    }
+
+    /// Imports the payload of a semaphore that was exported by another API or process as a
+    /// POSIX file descriptor.
+    ///
+    /// If `temporary` is true, the imported payload is only used until the next wait operation
+    /// on the semaphore, after which the semaphore goes back to its own payload. Sync files can
+    /// only be imported temporarily.
+    ///
+    /// On success, the ownership of `fd` is transferred to the Vulkan implementation and the file
+    /// descriptor must not be used anymore. On failure, `fd` is closed.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one file descriptor handle type.
+    /// - Panics if the extensions required by `handle_type` are not enabled on the device.
+    /// - Panics if `handle_type` is `sync_fd` and `temporary` is false.
+    /// - Panics if `temporary` is false and the semaphore was taken from the semaphore pool.
+    ///
+    /// # Safety
+    ///
+    /// - `fd` must be a valid semaphore handle of type `handle_type`.
+    /// - The semaphore must not be in use by any pending queue operation.
+    ///
+    #[cfg(unix)]
+    pub unsafe fn import_fd(
+        &self,
+        handle_type: ExternalSemaphoreHandleType,
+        fd: File,
+        temporary: bool,
+    ) -> Result<(), ExternalSemaphoreError> {
+        assert!(handle_type.count() == 1 && handle_type.is_fd());
+        handle_type.check_extensions(self.device.loaded_extensions());
+        assert!(temporary || !handle_type.sync_fd);
+        assert!(temporary || !self.must_put_in_pool);
+
+        let vk = self.device.pointers();
+        let fd = fd.into_raw_fd();
+
+        let info = vk::ImportSemaphoreFdInfoKHR {
+            sType: vk::STRUCTURE_TYPE_IMPORT_SEMAPHORE_FD_INFO_KHR,
+            pNext: ptr::null(),
+            semaphore: self.semaphore,
+            flags: if temporary {
+                vk::SEMAPHORE_IMPORT_TEMPORARY_BIT_KHR
+            } else {
+                0
+            },
+            handleType: handle_type.to_vulkan_bits(),
+            fd: fd,
+        };
+
+        // The Vulkan implementation only takes ownership of the file descriptor on success.
+        match check_errors(vk.ImportSemaphoreFdKHR(self.device.internal_object(), &info)) {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                drop(File::from_raw_fd(fd));
+                Err(err.into())
+            }
+        }
+    }
+
+    /// Exports the semaphore as a Windows handle.
+    ///
+    /// For the NT handle types, the returned handle must be closed with `CloseHandle` once it is
+    /// no longer needed.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one Windows handle type.
+    /// - Panics if the semaphore wasn't created as exportable with `handle_type`.
+    ///
+    #[cfg(windows)]
+    pub fn export_win32_handle(
+        &self,
+        handle_type: ExternalSemaphoreHandleType,
+    ) -> Result<*mut c_void, ExternalSemaphoreError> {
+        assert!(handle_type.count() == 1 && !handle_type.is_fd());
+        assert!(
+            self.export_handle_types.to_vulkan_bits() & handle_type.to_vulkan_bits() != 0,
+            "the semaphore was not created as exportable with this handle type"
+        );
+
+        let vk = self.device.pointers();
+
+        unsafe {
+            let info = vk::SemaphoreGetWin32HandleInfoKHR {
+                sType: vk::STRUCTURE_TYPE_SEMAPHORE_GET_WIN32_HANDLE_INFO_KHR,
+                pNext: ptr::null(),
+                semaphore: self.semaphore,
+                handleType: handle_type.to_vulkan_bits(),
+            };
+
+            let mut output = MaybeUninit::uninit();
+            check_errors(vk.GetSemaphoreWin32HandleKHR(
+                self.device.internal_object(),
+                &info,
+                output.as_mut_ptr(),
+            ))?;
+            Ok(output.assume_init())
+        }
+    }
+
+    /// Imports the payload of a semaphore that was exported by another API or process as a
+    /// Windows handle.
+    ///
+    /// If `temporary` is true, the imported payload is only used until the next wait operation
+    /// on the semaphore. Contrary to file descriptors, the ownership of `handle` is not
+    /// transferred. It can be closed once the semaphore has been imported.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `handle_type` doesn't contain exactly one Windows handle type.
+    /// - Panics if the extensions required by `handle_type` are not enabled on the device.
+    /// - Panics if `temporary` is false and the semaphore was taken from the semaphore pool.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid semaphore handle of type `handle_type`.
+    /// - The semaphore must not be in use by any pending queue operation.
+    ///
+    #[cfg(windows)]
+    pub unsafe fn import_win32_handle(
+        &self,
+        handle_type: ExternalSemaphoreHandleType,
+        handle: *mut c_void,
+        temporary: bool,
+    ) -> Result<(), ExternalSemaphoreError> {
+        assert!(handle_type.count() == 1 && !handle_type.is_fd());
+        handle_type.check_extensions(self.device.loaded_extensions());
+        assert!(temporary || !self.must_put_in_pool);
+
+        let vk = self.device.pointers();
+
+        let info = vk::ImportSemaphoreWin32HandleInfoKHR {
+            sType: vk::STRUCTURE_TYPE_IMPORT_SEMAPHORE_WIN32_HANDLE_INFO_KHR,
+            pNext: ptr::null(),
+            semaphore: self.semaphore,
+            flags: if temporary {
+                vk::SEMAPHORE_IMPORT_TEMPORARY_BIT_KHR
+            } else {
+                0
+            },
+            handleType: handle_type.to_vulkan_bits(),
+            handle: handle,
+            name: ptr::null(),
+        };
+
+        check_errors(vk.ImportSemaphoreWin32HandleKHR(self.device.internal_object(), &info))?;
+        Ok(())
+    }
 }
 
 unsafe impl DeviceOwned for Semaphore {
@@ -131,8 +380,63 @@ where
     }
 }
 
+/// Error that can happen when exporting or importing a semaphore.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExternalSemaphoreError {
+    /// Not enough memory available.
+    OomError(OomError),
+    /// The maximum number of handles has been exceeded.
+    TooManyObjects,
+    /// The handle to import is invalid or isn't of the requested handle type.
+    InvalidExternalHandle,
+}
+
+impl error::Error for ExternalSemaphoreError {
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            ExternalSemaphoreError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ExternalSemaphoreError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            fmt,
+            "{}",
+            match *self {
+                ExternalSemaphoreError::OomError(_) => "not enough memory available",
+                ExternalSemaphoreError::TooManyObjects => {
+                    "the maximum number of handles has been exceeded"
+                }
+                ExternalSemaphoreError::InvalidExternalHandle => {
+                    "the handle to import is invalid or isn't of the requested handle type"
+                }
+            }
+        )
+    }
+}
+
+impl From<Error> for ExternalSemaphoreError {
+    #[inline]
+    fn from(err: Error) -> ExternalSemaphoreError {
+        match err {
+            e @ Error::OutOfHostMemory | e @ Error::OutOfDeviceMemory => {
+                ExternalSemaphoreError::OomError(e.into())
+            }
+            Error::TooManyObjects => ExternalSemaphoreError::TooManyObjects,
+            Error::InvalidExternalHandle => ExternalSemaphoreError::InvalidExternalHandle,
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use sync::ExternalSemaphoreHandleType;
     use sync::Semaphore;
     use VulkanObject;
 
@@ -158,4 +462,21 @@ mod tests {
         assert_eq!(device.semaphore_pool().lock().unwrap().len(), 0);
         assert_eq!(sem2.internal_object(), sem1_internal_obj);
     }
+
+    #[test]
+    fn handle_type_bits() {
+        let both = ExternalSemaphoreHandleType::posix() | ExternalSemaphoreHandleType::sync_fd();
+        assert_eq!(both.count(), 2);
+        assert!(both.is_fd());
+        assert!(!ExternalSemaphoreHandleType::win32().is_fd());
+    }
+
+    #[test]
+    fn exportable_requires_extension() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        assert_should_panic!("the khr_external_semaphore extension must be enabled", {
+            let _ = Semaphore::alloc_exportable(device, ExternalSemaphoreHandleType::posix());
+        });
+    }
 }